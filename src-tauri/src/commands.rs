@@ -1,12 +1,36 @@
+use std::collections::BTreeMap;
+
 use crate::models::{
-    BackupInfo, BackupResult, ConfigureResult, EnvCheckResult, HealthResult, InstallEnvResult,
-    InstallLockInfo, InstallResult, InstallerStatus, LogSummary, ModelCatalogItem,
-    OpenClawConfigInput, OpenClawFileConfig, ProcessControlResult, RollbackResult, SecurityResult,
-    SkillCatalogItem, UninstallResult, UpgradeResult,
+    AppLockStatus, AppSettingsTransferResult, AvailabilityReport, BackupCategory, BackupInfo,
+    BackupResult, BackupStorageConfig, BackupStorageResult, BackupVerifyResult, ChannelStats,
+    ConfigWatchAlert, ConfigWatchConfig, ConfigureResult, ConsentStatus, CustomProviderInput,
+    CustomProviderRecord, CustomProviderResult, EnvCheckResult, EnvDiffReport,
+    ExistingConfigPrefill, FeishuSkillsInput, FeishuSkillsResult, FileAuditConfig,
+    FileAuditFinding, FirewallRuleStatus, GatewayPortChangeResult, HealthResult, InstallEnvResult,
+    InstallLockInfo, InstallMoveResult, InstallResult, InstallerStatus, IsolationReport,
+    KeepAliveConfig, KeepAliveStatus, LeftoverCleanResult, LeftoverItem, LogEntry, LogPage,
+    LogPurgeResult, LogQuery, LogRotationConfig, LogSummary, MigrationRecord, MigrationResult,
+    ModelCatalogItem, ModelCatalogPage, ModelChainTestResult, ModelPresetImportResult,
+    OfflineBundleResult, OllamaStatus, OpenClawConfigInput, OpenClawFileConfig, PortInspection,
+    ProcessControlResult, ProcessTree, ProfileInfo, ProviderKeyCheck, ProviderSignupInfo,
+    QuietHoursConfig, ReadOnlyStatus, ResultWarning, RollbackResult, SbomResult, ScriptScanConfig,
+    ScriptScanReport, SecretKind, SecretReveal, SecurityAction, SecurityResult,
+    SecurityScheduleConfig, SecurityScheduleStatus, SetupPreset, ShortcutConfig, ShortcutResult,
+    SkillCatalogItem, StartupReport, StatusApiConfig, SupportShareConfig, SupportShareResult,
+    TelegramBotInfo, TelegramChannelStatus, TelegramPairingRequest, TelegramWebhookInput,
+    TelegramWebhookResult, TempSweepResult, TestMessageResult, TlsStatus, ToolPermissions,
+    ToolPermissionsResult, TunnelConfigInput, TunnelStatus, UninstallPreview, UninstallResult,
+    UpgradeCheck, UpgradeResult, WorkspaceVersion, WorkspaceVersioningConfig,
 };
 use crate::modules::{
-    backup, browser, config, donate, env, health, installer, logger, model_catalog, paths, port,
-    process, security, skills, state_store, upgrade,
+    app_lock, app_settings, backup, browser, channel_message, channel_stats, channels::telegram,
+    config, config_watch, consent, custom_provider, donate, env, env_diff, feishu, file_audit,
+    firewall, gateway_port, health, install_move, installer, isolation, keep_alive, leftovers,
+    logger, migration, model_catalog, model_presets, offline_bundle, paths, port, process,
+    process_tree, profiles, provider_check, provider_signup, quiet_hours, read_only, reconfigure,
+    sbom, script_scan, secret_reveal, secrets, security, setup_presets, shortcuts, skills, startup,
+    state_store, status_api, support_bundle, support_share, temp_files, tls, tool_policy, tunnel,
+    upgrade, uptime, warm_up, workspace_git,
 };
 
 // Convert internal anyhow errors into UI-friendly strings while keeping a server-side log.
@@ -17,6 +41,113 @@ fn map_err<T>(result: anyhow::Result<T>) -> Result<T, String> {
     })
 }
 
+fn unlocked(action: &str) -> Result<(), String> {
+    map_err(app_lock::ensure_unlocked(action))
+}
+
+/// Status queries the lock screen needs, plus unlocking itself. These run even while the
+/// app is PIN-locked, so nothing here may reveal a secret, open a path or change state.
+const SAFE_WHEN_LOCKED_DOWN: &[&str] = &[
+    "check_env",
+    "get_install_lock_info",
+    "health_check",
+    "get_status",
+    "get_startup_report",
+    "get_read_only_status",
+    "get_app_lock_status",
+    "unlock_app",
+    "lock_app",
+];
+
+/// Commands that read, export or run the gateway without changing its setup. They need the
+/// app unlocked but stay available in read-only mode; every other command is refused in
+/// either state, so a newly added command is guarded unless it is listed.
+const SAFE_WHEN_READ_ONLY: &[&str] = &[
+    "inspect_port",
+    "get_provider_signup_info",
+    "open_provider_signup",
+    "list_setup_presets",
+    "get_current_config",
+    "list_backups",
+    "verify_backup",
+    "get_backup_storage",
+    "check_upgrade",
+    "get_channel_stats",
+    "get_migration_record",
+    "security_check",
+    "list_logs",
+    "read_log",
+    "read_log_page",
+    "query_logs",
+    "begin_log_run",
+    "end_log_run",
+    "export_log",
+    "open_management_url",
+    "open_path",
+    "export_support_bundle",
+    "get_log_rotation",
+    "logs_dir_path",
+    "donate_wechat_qr",
+    "list_skill_catalog",
+    "list_model_catalog",
+    "get_model_catalog_page",
+    "get_tunnel_status",
+    "get_availability_report",
+    "get_workspace_versioning",
+    "list_workspace_versions",
+    "get_config_watch",
+    "list_config_watch_alerts",
+    "get_file_audit_config",
+    "list_file_audit_findings",
+    "get_tool_permissions",
+    "get_quiet_hours",
+    "export_app_settings",
+    "export_model_preset",
+    "get_process_tree",
+    "get_status_api",
+    "get_shortcuts",
+    "list_profiles",
+    "get_script_scan_config",
+    "start_script_scan",
+    "get_script_scan_report",
+    "get_firewall_rule_status",
+    "export_offline_bundle",
+    "validate_telegram_token",
+    "list_telegram_pairing_requests",
+    "get_telegram_status",
+    "get_keep_alive_status",
+    "verify_isolation",
+    "diff_gateway_env",
+    "validate_provider_key",
+    "test_model_chain",
+    "get_support_share",
+    "get_consents",
+    "list_custom_providers",
+    "preview_uninstall",
+    "scan_leftovers",
+    "detect_ollama",
+    "validate_bedrock_credentials",
+    "generate_sbom",
+    "get_tls_status",
+    "disable_read_only",
+    "get_security_schedule",
+    "reveal_secret",
+];
+
+/// Runs before every invoke (see `main.rs`): anything not in `SAFE_WHEN_LOCKED_DOWN`
+/// needs the app unlocked, and anything not in `SAFE_WHEN_READ_ONLY` read-only mode off.
+pub fn guard_invoke(command: &str) -> Result<(), String> {
+    if SAFE_WHEN_LOCKED_DOWN.contains(&command) {
+        return Ok(());
+    }
+    let action = format!("\"{}\"", command.replace('_', " "));
+    unlocked(&action)?;
+    if SAFE_WHEN_READ_ONLY.contains(&command) {
+        return Ok(());
+    }
+    map_err(read_only::ensure_writable(&action))
+}
+
 #[tauri::command]
 pub async fn check_env(port: u16) -> Result<EnvCheckResult, String> {
     map_err(env::check_env(port).await)
@@ -28,8 +159,13 @@ pub fn install_env(port: u16) -> Result<InstallEnvResult, String> {
 }
 
 #[tauri::command]
-pub fn release_port(port: u16) -> Result<String, String> {
-    map_err(port::release_port(port))
+pub fn inspect_port(port: u16) -> Result<PortInspection, String> {
+    map_err(port::inspect_port(port))
+}
+
+#[tauri::command]
+pub fn release_port(port: u16, expected_pid: u32) -> Result<String, String> {
+    map_err(port::release_port(port, expected_pid))
 }
 
 #[tauri::command]
@@ -69,9 +205,85 @@ pub fn configure(payload: OpenClawConfigInput) -> Result<ConfigureResult, String
     map_err(config::configure(&payload))
 }
 
+#[tauri::command]
+pub fn onboard_core(payload: OpenClawConfigInput) -> Result<ConfigureResult, String> {
+    map_err(config::configure_phase(
+        &payload,
+        config::ConfigurePhase::OnboardCore,
+    ))
+}
+
+#[tauri::command]
+pub fn apply_keys(payload: OpenClawConfigInput) -> Result<ConfigureResult, String> {
+    map_err(config::configure_phase(
+        &payload,
+        config::ConfigurePhase::ApplyKeys,
+    ))
+}
+
+#[tauri::command]
+pub fn apply_models(payload: OpenClawConfigInput) -> Result<ConfigureResult, String> {
+    map_err(config::configure_phase(
+        &payload,
+        config::ConfigurePhase::ApplyModels,
+    ))
+}
+
+#[tauri::command]
+pub fn apply_channels(payload: OpenClawConfigInput) -> Result<ConfigureResult, String> {
+    map_err(config::configure_phase(
+        &payload,
+        config::ConfigurePhase::ApplyChannels,
+    ))
+}
+
+#[tauri::command]
+pub fn apply_skills(payload: OpenClawConfigInput) -> Result<ConfigureResult, String> {
+    map_err(config::configure_phase(
+        &payload,
+        config::ConfigurePhase::ApplySkills,
+    ))
+}
+
+#[tauri::command]
+pub fn get_provider_signup_info(provider: String) -> Result<ProviderSignupInfo, String> {
+    map_err(provider_signup::get_provider_signup_info(&provider))
+}
+
+#[tauri::command]
+pub fn open_provider_signup(provider: String, region: Option<String>) -> Result<String, String> {
+    map_err(provider_signup::open_provider_signup(
+        &provider,
+        region.as_deref(),
+    ))
+}
+
+#[tauri::command]
+pub fn list_setup_presets() -> Result<Vec<SetupPreset>, String> {
+    Ok(setup_presets::list_setup_presets())
+}
+
+#[tauri::command]
+pub fn apply_setup_preset(
+    name: String,
+    base: Option<OpenClawConfigInput>,
+) -> Result<OpenClawConfigInput, String> {
+    map_err(setup_presets::apply_setup_preset(&name, base))
+}
+
+#[tauri::command]
+pub fn reconfigure_from_existing(
+    install_dir: Option<String>,
+) -> Result<ExistingConfigPrefill, String> {
+    map_err(reconfigure::reconfigure_from_existing(
+        install_dir.as_deref(),
+    ))
+}
+
 #[tauri::command]
 pub fn get_current_config() -> Result<OpenClawFileConfig, String> {
-    map_err(config::read_current_config())
+    unlocked("Viewing the configuration")?;
+    map_err(secret_reveal::masked_current_config())
 }
 
 #[tauri::command]
@@ -80,8 +292,13 @@ pub fn update_provider_api_key(provider: String, api_key: String) -> Result<Stri
 }
 
 #[tauri::command]
-pub fn start() -> Result<ProcessControlResult, String> {
-    map_err(process::start())
+pub async fn start() -> Result<ProcessControlResult, String> {
+    map_err(warm_up::start_with_warm_up().await)
+}
+
+#[tauri::command]
+pub fn set_warm_up_after_start(enabled: bool) -> Result<(), String> {
+    map_err(state_store::set_warm_up_after_start(enabled))
 }
 
 #[tauri::command]
@@ -95,8 +312,22 @@ pub fn end_openclaw() -> Result<ProcessControlResult, String> {
 }
 
 #[tauri::command]
-pub fn restart() -> Result<ProcessControlResult, String> {
-    map_err(process::restart())
+pub async fn restart() -> Result<ProcessControlResult, String> {
+    // Sync commands run on the main thread; waiting for readiness would freeze the UI.
+    let result = tokio::task::spawn_blocking(process::restart)
+        .await
+        .map_err(|err| anyhow::anyhow!("Gateway restart worker failed: {err}"));
+    map_err(result.and_then(|result| result))
+}
+
+#[tauri::command]
+pub fn install_service() -> Result<String, String> {
+    map_err(process::install_service())
+}
+
+#[tauri::command]
+pub fn uninstall_service() -> Result<String, String> {
+    map_err(process::uninstall_service())
 }
 
 #[tauri::command]
@@ -110,8 +341,13 @@ pub async fn get_status() -> Result<InstallerStatus, String> {
 }
 
 #[tauri::command]
-pub fn backup() -> Result<BackupResult, String> {
-    map_err(backup::backup())
+pub fn backup(include_excluded: Option<bool>) -> Result<BackupResult, String> {
+    map_err(backup::backup(include_excluded.unwrap_or(false)))
+}
+
+#[tauri::command]
+pub fn backup_category(category: BackupCategory) -> Result<BackupResult, String> {
+    map_err(backup::backup_category(category))
 }
 
 #[tauri::command]
@@ -124,6 +360,41 @@ pub fn rollback(backup_id: String) -> Result<RollbackResult, String> {
     map_err(backup::rollback(&backup_id))
 }
 
+#[tauri::command]
+pub fn verify_backup(backup_id: String) -> Result<BackupVerifyResult, String> {
+    map_err(backup::verify_backup(&backup_id))
+}
+
+#[tauri::command]
+pub fn restore_backup_category(
+    backup_id: String,
+    category: BackupCategory,
+) -> Result<RollbackResult, String> {
+    map_err(backup::restore_category(&backup_id, category))
+}
+
+#[tauri::command]
+pub fn get_backup_storage() -> Result<BackupStorageConfig, String> {
+    map_err(state_store::load_backup_storage())
+}
+
+#[tauri::command]
+pub fn configure_backup_storage(
+    payload: BackupStorageConfig,
+) -> Result<BackupStorageResult, String> {
+    map_err(backup::configure_backup_storage(&payload))
+}
+
+#[tauri::command]
+pub fn sweep_temp_files() -> Result<TempSweepResult, String> {
+    Ok(temp_files::sweep_stale())
+}
+
+#[tauri::command]
+pub async fn check_upgrade() -> Result<UpgradeCheck, String> {
+    map_err(upgrade::check_upgrade().await)
+}
+
 #[tauri::command]
 pub async fn upgrade() -> Result<UpgradeResult, String> {
     map_err(upgrade::upgrade().await)
@@ -134,6 +405,55 @@ pub fn switch_model(primary: String, fallbacks: Vec<String>) -> Result<Configure
     map_err(config::switch_model(&primary, &fallbacks))
 }
 
+#[tauri::command]
+pub fn send_test_message(
+    channel: String,
+    text: String,
+    target: Option<String>,
+) -> Result<TestMessageResult, String> {
+    map_err(channel_message::send_test_message(
+        &channel,
+        &text,
+        target.as_deref(),
+    ))
+}
+
+#[tauri::command]
+pub fn get_channel_stats() -> Result<Vec<ChannelStats>, String> {
+    map_err(channel_stats::get_channel_stats())
+}
+
+#[tauri::command]
+pub async fn change_gateway_port(new_port: u16) -> Result<GatewayPortChangeResult, String> {
+    map_err(gateway_port::change_gateway_port(new_port).await)
+}
+
+#[tauri::command]
+pub fn move_install_dir(new_path: String) -> Result<InstallMoveResult, String> {
+    map_err(install_move::move_install_dir(&new_path))
+}
+
+#[tauri::command]
+pub fn get_migration_record() -> Result<Option<MigrationRecord>, String> {
+    map_err(state_store::load_migration_record())
+}
+
+#[tauri::command]
+pub fn migrate_existing_install(
+    source_dir: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<MigrationResult, String> {
+    map_err(migration::migrate_existing_install(
+        source_dir.as_deref(),
+        overwrite.unwrap_or(false),
+    ))
+}
+
+#[tauri::command]
+pub fn seal_env_secrets() -> Result<Vec<String>, String> {
+    map_err(secrets::seal_existing_env())
+}
+
 #[tauri::command]
 pub fn security_check() -> Result<SecurityResult, String> {
     map_err(security::run_security_check())
@@ -149,6 +469,34 @@ pub fn read_log(name: String, max_lines: Option<usize>) -> Result<String, String
     map_err(logger::read_log(&name, max_lines.unwrap_or(400)))
 }
 
+#[tauri::command]
+pub fn read_log_page(
+    name: String,
+    before: Option<u64>,
+    max_lines: Option<usize>,
+) -> Result<LogPage, String> {
+    map_err(logger::read_log_page(
+        &name,
+        before,
+        max_lines.unwrap_or(400),
+    ))
+}
+
+#[tauri::command]
+pub fn query_logs(query: LogQuery) -> Result<Vec<LogEntry>, String> {
+    map_err(logger::query_logs(&query))
+}
+
+#[tauri::command]
+pub fn begin_log_run() -> String {
+    logger::begin_run()
+}
+
+#[tauri::command]
+pub fn end_log_run() {
+    logger::end_run()
+}
+
 #[tauri::command]
 pub fn export_log(name: String, output_path: String) -> Result<String, String> {
     map_err((|| {
@@ -175,6 +523,7 @@ pub fn reload_config() -> Result<String, String> {
 
 #[tauri::command]
 pub fn open_management_url(url: String) -> Result<String, String> {
+    unlocked("Opening the dashboard")?;
     map_err(browser::open_management_url(&url))
 }
 
@@ -183,6 +532,38 @@ pub fn open_path(path: String) -> Result<String, String> {
     map_err(browser::open_path(&path))
 }
 
+#[tauri::command]
+pub fn export_support_bundle(
+    output_path: String,
+    correlation_id: Option<String>,
+) -> Result<String, String> {
+    unlocked("Exporting a support bundle")?;
+    map_err((|| {
+        let out = paths::normalize_path(&output_path)?;
+        support_bundle::export_support_bundle(&out, correlation_id.as_deref())
+    })())
+}
+
+#[tauri::command]
+pub fn get_log_rotation() -> LogRotationConfig {
+    logger::get_log_rotation()
+}
+
+#[tauri::command]
+pub fn set_log_rotation(payload: LogRotationConfig) -> Result<LogRotationConfig, String> {
+    map_err(logger::set_log_rotation(&payload))
+}
+
+#[tauri::command]
+pub fn purge_logs(older_than_days: u32) -> Result<LogPurgeResult, String> {
+    map_err(logger::purge_logs(older_than_days))
+}
+
+#[tauri::command]
+pub fn get_startup_report() -> StartupReport {
+    startup::report()
+}
+
 #[tauri::command]
 pub fn logs_dir_path() -> Result<String, String> {
     map_err(logger::logs_dir_path())
@@ -194,8 +575,8 @@ pub fn donate_wechat_qr() -> Result<String, String> {
 }
 
 #[tauri::command]
-pub fn list_skill_catalog() -> Result<Vec<SkillCatalogItem>, String> {
-    map_err(skills::list_skill_catalog())
+pub async fn list_skill_catalog() -> Result<Vec<SkillCatalogItem>, String> {
+    map_err(skills::list_skill_catalog().await)
 }
 
 #[tauri::command]
@@ -203,7 +584,550 @@ pub fn list_model_catalog() -> Result<Vec<ModelCatalogItem>, String> {
     map_err(model_catalog::list_model_catalog())
 }
 
+#[tauri::command]
+pub fn get_model_catalog_page(
+    offset: usize,
+    limit: usize,
+    filter: Option<String>,
+    sort: Option<String>,
+    modality: Option<String>,
+) -> Result<ModelCatalogPage, String> {
+    map_err(model_catalog::get_model_catalog_page(
+        offset,
+        limit,
+        filter.as_deref(),
+        sort.as_deref(),
+        modality.as_deref(),
+    ))
+}
+
 #[tauri::command]
 pub fn setup_telegram_pair(pair_code: String) -> Result<String, String> {
-    map_err(config::setup_telegram_pair(&pair_code))
+    map_err(telegram::approve_pairing(&pair_code))
+}
+
+#[tauri::command]
+pub async fn configure_feishu_skills(
+    options: FeishuSkillsInput,
+) -> Result<FeishuSkillsResult, String> {
+    map_err(feishu::configure_feishu_skills(&options).await)
+}
+
+#[tauri::command]
+pub async fn configure_telegram_webhook(
+    options: TelegramWebhookInput,
+) -> Result<TelegramWebhookResult, String> {
+    map_err(telegram::configure_telegram_webhook(&options).await)
+}
+
+#[tauri::command]
+pub async fn configure_tunnel(options: TunnelConfigInput) -> Result<TunnelStatus, String> {
+    map_err(tunnel::configure_tunnel(&options).await)
+}
+
+#[tauri::command]
+pub fn start_tunnel() -> Result<TunnelStatus, String> {
+    map_err(tunnel::start_tunnel())
+}
+
+#[tauri::command]
+pub fn stop_tunnel() -> Result<TunnelStatus, String> {
+    map_err(tunnel::stop_tunnel())
+}
+
+#[tauri::command]
+pub fn get_tunnel_status() -> Result<TunnelStatus, String> {
+    Ok(tunnel::tunnel_status())
+}
+
+#[tauri::command]
+pub fn get_availability_report() -> Result<AvailabilityReport, String> {
+    map_err(uptime::get_availability_report())
+}
+
+#[tauri::command]
+pub fn configure_workspace_versioning(
+    payload: WorkspaceVersioningConfig,
+) -> Result<WorkspaceVersioningConfig, String> {
+    map_err(workspace_git::configure_workspace_versioning(&payload))
+}
+
+#[tauri::command]
+pub fn get_workspace_versioning() -> Result<WorkspaceVersioningConfig, String> {
+    map_err(state_store::load_workspace_versioning())
+}
+
+#[tauri::command]
+pub fn list_workspace_versions(
+    path: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<WorkspaceVersion>, String> {
+    map_err(workspace_git::list_workspace_versions(path, limit))
+}
+
+#[tauri::command]
+pub fn restore_workspace_file(path: String, rev: String) -> Result<String, String> {
+    map_err(workspace_git::restore_workspace_file(&path, &rev))
+}
+
+#[tauri::command]
+pub fn configure_config_watch(payload: ConfigWatchConfig) -> Result<ConfigWatchConfig, String> {
+    map_err(config_watch::configure_config_watch(&payload))
+}
+
+#[tauri::command]
+pub fn get_config_watch() -> Result<ConfigWatchConfig, String> {
+    map_err(state_store::load_config_watch())
+}
+
+#[tauri::command]
+pub fn list_config_watch_alerts() -> Result<Vec<ConfigWatchAlert>, String> {
+    Ok(config_watch::load_alerts())
+}
+
+#[tauri::command]
+pub fn acknowledge_config_watch_alerts() -> Result<(), String> {
+    map_err(config_watch::acknowledge_alerts())
+}
+
+#[tauri::command]
+pub fn clear_config_watch_alerts() -> Result<(), String> {
+    map_err(config_watch::clear_alerts())
+}
+
+#[tauri::command]
+pub fn configure_file_audit(payload: FileAuditConfig) -> Result<FileAuditConfig, String> {
+    map_err(file_audit::configure_file_audit(&payload))
+}
+
+#[tauri::command]
+pub fn get_file_audit_config() -> Result<FileAuditConfig, String> {
+    map_err(state_store::load_file_audit_config())
+}
+
+#[tauri::command]
+pub fn list_file_audit_findings() -> Result<Vec<FileAuditFinding>, String> {
+    Ok(file_audit::load_findings())
+}
+
+#[tauri::command]
+pub fn clear_file_audit_findings() -> Result<(), String> {
+    map_err(file_audit::clear_findings())
+}
+
+#[tauri::command]
+pub fn get_tool_permissions() -> Result<ToolPermissions, String> {
+    map_err(tool_policy::get_tool_permissions())
+}
+
+#[tauri::command]
+pub fn set_tool_permissions(payload: ToolPermissions) -> Result<ToolPermissionsResult, String> {
+    map_err(tool_policy::set_tool_permissions(&payload))
+}
+
+#[tauri::command]
+pub fn get_quiet_hours() -> Result<QuietHoursConfig, String> {
+    map_err(state_store::load_quiet_hours())
+}
+
+#[tauri::command]
+pub fn set_quiet_hours(payload: QuietHoursConfig) -> Result<QuietHoursConfig, String> {
+    map_err(quiet_hours::set_quiet_hours(&payload))
+}
+
+#[tauri::command]
+pub fn export_app_settings(path: String) -> Result<AppSettingsTransferResult, String> {
+    unlocked("Exporting settings")?;
+    map_err(app_settings::export_app_settings(&path))
+}
+
+#[tauri::command]
+pub fn import_app_settings(path: String) -> Result<AppSettingsTransferResult, String> {
+    map_err(app_settings::import_app_settings(&path))
+}
+
+#[tauri::command]
+pub fn export_model_preset(
+    path: String,
+    name: String,
+    description: String,
+) -> Result<String, String> {
+    unlocked("Exporting a model preset")?;
+    map_err(model_presets::export_model_preset(
+        &path,
+        &name,
+        &description,
+    ))
+}
+
+#[tauri::command]
+pub fn import_model_preset(path: String, apply: bool) -> Result<ModelPresetImportResult, String> {
+    map_err(model_presets::import_model_preset(&path, apply))
+}
+
+#[tauri::command]
+pub fn get_process_tree() -> Result<ProcessTree, String> {
+    map_err(process_tree::get_process_tree())
+}
+
+#[tauri::command]
+pub fn kill_child_process(pid: u32) -> Result<String, String> {
+    map_err(process_tree::kill_child_process(pid))
+}
+
+#[tauri::command]
+pub fn get_status_api() -> Result<StatusApiConfig, String> {
+    map_err(state_store::load_status_api())
+}
+
+#[tauri::command]
+pub fn set_status_api(
+    payload: StatusApiConfig,
+    rotate_token: Option<bool>,
+) -> Result<StatusApiConfig, String> {
+    map_err(status_api::set_status_api(
+        &payload,
+        rotate_token.unwrap_or(false),
+    ))
+}
+
+#[tauri::command]
+pub fn get_shortcuts() -> Result<ShortcutConfig, String> {
+    map_err(state_store::load_shortcuts())
+}
+
+#[tauri::command]
+pub fn set_shortcuts(bindings: BTreeMap<String, String>) -> Result<ShortcutResult, String> {
+    map_err(shortcuts::set_shortcuts(&bindings))
+}
+
+#[tauri::command]
+pub fn list_profiles() -> Result<Vec<ProfileInfo>, String> {
+    map_err(profiles::list_profiles())
+}
+
+#[tauri::command]
+pub fn create_profile(name: String) -> Result<ProfileInfo, String> {
+    map_err(profiles::create_profile(&name))
+}
+
+#[tauri::command]
+pub fn switch_profile(name: String) -> Result<ProfileInfo, String> {
+    map_err(profiles::switch_profile(&name))
+}
+
+#[tauri::command]
+pub fn delete_profile(name: String) -> Result<(), String> {
+    map_err(profiles::delete_profile(&name))
+}
+
+#[tauri::command]
+pub fn get_script_scan_config() -> Result<ScriptScanConfig, String> {
+    map_err(state_store::load_script_scan())
+}
+
+#[tauri::command]
+pub fn set_script_scan_config(payload: ScriptScanConfig) -> Result<ScriptScanConfig, String> {
+    map_err(script_scan::set_script_scan_config(&payload))
+}
+
+/// Returns false when a scan is already running; progress arrives as events.
+#[tauri::command]
+pub fn start_script_scan() -> bool {
+    script_scan::start()
+}
+
+#[tauri::command]
+pub fn get_script_scan_report() -> Option<ScriptScanReport> {
+    script_scan::last_report()
+}
+
+#[tauri::command]
+pub fn get_firewall_rule_status(port: u16) -> FirewallRuleStatus {
+    firewall::firewall_rule_status(port)
+}
+
+#[tauri::command]
+pub fn add_firewall_rule(port: u16) -> Result<FirewallRuleStatus, String> {
+    map_err(firewall::add_firewall_rule(port))
+}
+
+#[tauri::command]
+pub fn remove_firewall_rule(port: u16) -> Result<FirewallRuleStatus, String> {
+    map_err(firewall::remove_firewall_rule(port))
+}
+
+#[tauri::command]
+pub fn export_offline_bundle(dest_dir: Option<String>) -> Result<OfflineBundleResult, String> {
+    map_err(offline_bundle::export_offline_bundle(dest_dir))
+}
+
+#[tauri::command]
+pub async fn validate_telegram_token(token: Option<String>) -> Result<TelegramBotInfo, String> {
+    map_err(telegram::validate_bot_token(token).await)
+}
+
+#[tauri::command]
+pub fn list_telegram_pairing_requests() -> Result<Vec<TelegramPairingRequest>, String> {
+    map_err(telegram::list_pairing_requests())
+}
+
+#[tauri::command]
+pub fn approve_telegram_pairing(code: String) -> Result<String, String> {
+    map_err(telegram::approve_pairing(&code))
+}
+
+#[tauri::command]
+pub fn reject_telegram_pairing(code: String) -> Result<String, String> {
+    map_err(telegram::reject_pairing(&code))
+}
+
+#[tauri::command]
+pub fn get_telegram_status() -> Result<TelegramChannelStatus, String> {
+    map_err(telegram::telegram_status())
+}
+
+#[tauri::command]
+pub fn repair_discord_channel(bot_token: Option<String>) -> Result<Vec<ResultWarning>, String> {
+    map_err(config::repair_discord_channel(bot_token))
+}
+
+#[tauri::command]
+pub fn get_keep_alive_status() -> Result<KeepAliveStatus, String> {
+    map_err(keep_alive::keep_alive_status())
+}
+
+#[tauri::command]
+pub fn configure_keep_alive(payload: KeepAliveConfig) -> Result<KeepAliveConfig, String> {
+    map_err(keep_alive::configure_keep_alive(&payload))
+}
+
+#[tauri::command]
+pub fn run_keep_alive_check() -> Result<KeepAliveStatus, String> {
+    map_err(keep_alive::run_keep_alive_check())
+}
+
+#[tauri::command]
+pub fn verify_isolation() -> Result<IsolationReport, String> {
+    map_err(isolation::verify_isolation())
+}
+
+#[tauri::command]
+pub fn diff_gateway_env() -> Result<EnvDiffReport, String> {
+    map_err(env_diff::diff_gateway_env())
+}
+
+#[tauri::command]
+pub async fn validate_provider_key(
+    provider: String,
+    api_key: String,
+    region: Option<String>,
+    proxy: Option<String>,
+) -> Result<ProviderKeyCheck, String> {
+    map_err(
+        provider_check::validate_provider_key(&provider, &api_key, region.as_deref(), proxy).await,
+    )
+}
+
+#[tauri::command]
+pub async fn test_model_chain() -> Result<ModelChainTestResult, String> {
+    map_err(provider_check::test_model_chain().await)
+}
+
+#[tauri::command]
+pub fn get_support_share() -> Result<SupportShareConfig, String> {
+    map_err(support_share::get_support_share())
+}
+
+#[tauri::command]
+pub fn configure_support_share(config: SupportShareConfig) -> Result<SupportShareConfig, String> {
+    map_err(support_share::configure_support_share(&config))
+}
+
+#[tauri::command]
+pub async fn share_support_bundle(
+    correlation_id: Option<String>,
+) -> Result<SupportShareResult, String> {
+    unlocked("Sharing a support bundle")?;
+    map_err(support_share::share_support_bundle(correlation_id.as_deref()).await)
+}
+
+#[tauri::command]
+pub fn get_consents() -> Result<ConsentStatus, String> {
+    map_err(consent::get_consents())
+}
+
+#[tauri::command]
+pub fn set_consent(
+    kind: String,
+    accepted: bool,
+    version: Option<String>,
+) -> Result<ConsentStatus, String> {
+    map_err(consent::set_consent(&kind, accepted, version.as_deref()))
+}
+
+#[tauri::command]
+pub fn add_custom_provider(input: CustomProviderInput) -> Result<CustomProviderResult, String> {
+    map_err(custom_provider::add_custom_provider(&input))
+}
+
+#[tauri::command]
+pub fn list_custom_providers() -> Result<Vec<CustomProviderRecord>, String> {
+    map_err(custom_provider::list_custom_providers())
+}
+
+#[tauri::command]
+pub fn preview_uninstall() -> Result<UninstallPreview, String> {
+    map_err(installer::preview_uninstall())
+}
+
+#[tauri::command]
+pub fn scan_leftovers() -> Result<Vec<LeftoverItem>, String> {
+    map_err(leftovers::scan_leftovers())
+}
+
+#[tauri::command]
+pub fn clean_leftover(id: String) -> Result<LeftoverCleanResult, String> {
+    map_err(leftovers::clean_leftover(&id))
+}
+
+#[tauri::command]
+pub async fn detect_ollama() -> Result<OllamaStatus, String> {
+    Ok(env::detect_ollama().await)
+}
+
+#[tauri::command]
+pub fn install_ollama() -> Result<InstallEnvResult, String> {
+    map_err(env::install_ollama())
+}
+
+#[tauri::command]
+pub async fn configure_ollama(
+    models: Vec<String>,
+    make_primary: bool,
+) -> Result<CustomProviderResult, String> {
+    map_err(env::configure_ollama(&models, make_primary).await)
+}
+
+#[tauri::command]
+pub async fn validate_bedrock_credentials(
+    access_key_id: String,
+    secret_access_key: String,
+    region: String,
+    proxy: Option<String>,
+) -> Result<ProviderKeyCheck, String> {
+    map_err(
+        provider_check::validate_bedrock_credentials(
+            &access_key_id,
+            &secret_access_key,
+            &region,
+            proxy,
+        )
+        .await,
+    )
+}
+
+#[tauri::command]
+pub fn generate_sbom() -> Result<SbomResult, String> {
+    map_err(sbom::generate_sbom())
+}
+
+#[tauri::command]
+pub fn get_tls_status() -> TlsStatus {
+    tls::tls_status()
+}
+
+#[tauri::command]
+pub fn generate_gateway_certificate() -> Result<TlsStatus, String> {
+    map_err(tls::generate_self_signed())
+}
+
+#[tauri::command]
+pub fn import_gateway_certificate(pfx_path: String, password: String) -> Result<TlsStatus, String> {
+    map_err(tls::import_pfx(&pfx_path, &password))
+}
+
+#[tauri::command]
+pub fn disable_gateway_tls() -> Result<TlsStatus, String> {
+    map_err(tls::disable_tls())
+}
+
+#[tauri::command]
+pub fn get_read_only_status() -> ReadOnlyStatus {
+    read_only::read_only_status()
+}
+
+#[tauri::command]
+pub fn enable_read_only(pin: String) -> Result<ReadOnlyStatus, String> {
+    unlocked("Enabling read-only mode")?;
+    map_err(read_only::enable_read_only(&pin))
+}
+
+#[tauri::command]
+pub fn disable_read_only(pin: String) -> Result<ReadOnlyStatus, String> {
+    map_err(read_only::disable_read_only(&pin))
+}
+
+#[tauri::command]
+pub fn get_app_lock_status() -> AppLockStatus {
+    app_lock::app_lock_status()
+}
+
+#[tauri::command]
+pub fn configure_app_lock(
+    pin: String,
+    lock_after_minutes: u32,
+    current_pin: Option<String>,
+) -> Result<AppLockStatus, String> {
+    map_err(app_lock::configure_app_lock(
+        &pin,
+        lock_after_minutes,
+        current_pin.as_deref(),
+    ))
+}
+
+#[tauri::command]
+pub fn disable_app_lock(pin: String) -> Result<AppLockStatus, String> {
+    map_err(app_lock::disable_app_lock(&pin))
+}
+
+#[tauri::command]
+pub fn unlock_app(pin: String) -> Result<AppLockStatus, String> {
+    map_err(app_lock::unlock(&pin))
+}
+
+#[tauri::command]
+pub fn lock_app() -> AppLockStatus {
+    app_lock::lock_now()
+}
+
+#[tauri::command]
+pub fn get_security_schedule() -> Result<SecurityScheduleStatus, String> {
+    map_err(security::security_schedule_status())
+}
+
+#[tauri::command]
+pub fn configure_security_schedule(
+    payload: SecurityScheduleConfig,
+) -> Result<SecurityScheduleConfig, String> {
+    map_err(security::configure_security_schedule(&payload))
+}
+
+#[tauri::command]
+pub fn reveal_secret(
+    kind: SecretKind,
+    provider: Option<String>,
+    reveal: bool,
+) -> Result<SecretReveal, String> {
+    unlocked("Revealing a secret")?;
+    map_err(secret_reveal::reveal_secret(
+        kind,
+        provider.as_deref(),
+        reveal,
+    ))
+}
+
+#[tauri::command]
+pub fn apply_security_action(action: SecurityAction) -> Result<SecurityResult, String> {
+    map_err(security::apply_security_action(action))
 }
@@ -9,55 +9,27 @@ mod modules;
 use tauri::{
     menu::{Menu, MenuItem},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Manager, WindowEvent,
+    webview::PageLoadEvent,
+    AppHandle, Emitter, Manager, WindowEvent,
 };
 
-use modules::{logger, paths, process, state_store};
+use modules::{
+    app_lock, browser, config_watch, gateway_service, install_progress, keep_alive, logger, paths,
+    process, profiles, read_only, script_scan, security, shortcuts, startup, status_api,
+    status_stream, telemetry, temp_files,
+};
 
 const MAIN_WINDOW_LABEL: &str = "main";
 const TRAY_MENU_TOGGLE_ID: &str = "tray_toggle";
 const TRAY_MENU_STOP_OPENCLAW_ID: &str = "tray_stop_openclaw";
 const TRAY_MENU_EXIT_ID: &str = "tray_exit";
 
-fn init_openclaw_home_override() {
-    // 1) Respect explicit overrides (e.g. custom dev launch scripts).
-    if let Ok(value) = std::env::var("OPENCLAW_INSTALLER_OPENCLAW_HOME") {
-        if !value.trim().is_empty() {
-            return;
-        }
-    }
-
-    // 2) If this installer has already installed OpenClaw, pin the home to that install_dir
-    //    to keep future runs consistent and isolated from any other OpenClaw on the machine.
-    if let Ok(Some(state)) = state_store::load_install_state() {
-        if !state.install_dir.trim().is_empty() {
-            if let Ok(dir) = paths::normalize_path(&state.install_dir) {
-                if !paths::is_user_profile_default_openclaw_dir(&dir) {
-                    std::env::set_var(
-                        "OPENCLAW_INSTALLER_OPENCLAW_HOME",
-                        dir.to_string_lossy().to_string(),
-                    );
-                    return;
-                }
-                logger::warn(&format!(
-                    "Ignoring legacy install_dir (unsafe): {}",
-                    dir.to_string_lossy()
-                ));
-            }
-        }
-    }
-
-    // 3) Default: an isolated per-user directory under LocalAppData.
-    //    This avoids touching `%USERPROFILE%\\.openclaw` by default.
-    let fallback = paths::default_isolated_openclaw_home();
-    std::env::set_var(
-        "OPENCLAW_INSTALLER_OPENCLAW_HOME",
-        fallback.to_string_lossy().to_string(),
-    );
-}
-
 fn reveal_main_window(app: &AppHandle) {
     if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+        // Lock before the window paints, so a due lock never flashes the last screen.
+        if app_lock::on_reveal() {
+            let _ = app.emit(app_lock::EVENT, app_lock::app_lock_status());
+        }
         let _ = window.show();
         let _ = window.unminimize();
         let _ = window.set_focus();
@@ -69,6 +41,7 @@ fn toggle_main_window(app: &AppHandle) {
         let visible = window.is_visible().unwrap_or(true);
         if visible {
             let _ = window.hide();
+            app_lock::note_hidden();
         } else {
             reveal_main_window(app);
         }
@@ -134,17 +107,256 @@ fn setup_tray(app: &mut tauri::App) -> tauri::Result<()> {
 }
 
 fn main() {
-    init_openclaw_home_override();
+    startup::begin();
+    let args = std::env::args().collect::<Vec<_>>();
+    if args
+        .iter()
+        .any(|arg| arg == gateway_service::SERVICE_HOST_ARG)
+    {
+        if let Err(err) = gateway_service::run_host(&args) {
+            logger::error(&format!("Gateway service host failed: {err}"));
+            std::process::exit(1);
+        }
+        return;
+    }
+    profiles::restore_active();
+    profiles::bind_openclaw_home(true);
     if let Err(err) = paths::ensure_dirs() {
         eprintln!("Failed to initialize directories: {err}");
     }
+    startup::mark("dirs_ready");
     logger::info("OpenClaw Installer started.");
+    telemetry::init();
+    startup::defer("temp_sweeper", temp_files::start_background_sweeper);
+    startup::defer("status_api", status_api::start_if_enabled);
+    startup::defer("keep_alive", keep_alive::start);
+    startup::defer("gateway_log_rotation", || {
+        process::start_gateway_log_rotation(false)
+    });
 
+    let handler: fn(tauri::ipc::Invoke) -> bool = tauri::generate_handler![
+        commands::check_env,
+        commands::install_env,
+        commands::inspect_port,
+        commands::release_port,
+        commands::get_install_lock_info,
+        commands::install_openclaw,
+        commands::uninstall_openclaw,
+        commands::configure,
+        commands::onboard_core,
+        commands::apply_keys,
+        commands::apply_models,
+        commands::apply_channels,
+        commands::apply_skills,
+        commands::reconfigure_from_existing,
+        commands::get_provider_signup_info,
+        commands::open_provider_signup,
+        commands::list_setup_presets,
+        commands::apply_setup_preset,
+        commands::get_current_config,
+        commands::update_provider_api_key,
+        commands::start,
+        commands::set_warm_up_after_start,
+        commands::stop,
+        commands::end_openclaw,
+        commands::restart,
+        commands::install_service,
+        commands::uninstall_service,
+        commands::health_check,
+        commands::get_status,
+        commands::backup,
+        commands::backup_category,
+        commands::list_backups,
+        commands::rollback,
+        commands::restore_backup_category,
+        commands::get_backup_storage,
+        commands::configure_backup_storage,
+        commands::sweep_temp_files,
+        commands::check_upgrade,
+        commands::upgrade,
+        commands::switch_model,
+        commands::change_gateway_port,
+        commands::get_channel_stats,
+        commands::send_test_message,
+        commands::move_install_dir,
+        commands::migrate_existing_install,
+        commands::get_migration_record,
+        commands::security_check,
+        commands::seal_env_secrets,
+        commands::list_logs,
+        commands::read_log,
+        commands::read_log_page,
+        commands::query_logs,
+        commands::begin_log_run,
+        commands::end_log_run,
+        commands::export_log,
+        commands::export_support_bundle,
+        commands::get_startup_report,
+        commands::get_log_rotation,
+        commands::set_log_rotation,
+        commands::purge_logs,
+        commands::clear_cache,
+        commands::clear_sessions,
+        commands::reload_config,
+        commands::open_management_url,
+        commands::open_path,
+        commands::logs_dir_path,
+        commands::donate_wechat_qr,
+        commands::list_skill_catalog,
+        commands::list_model_catalog,
+        commands::get_model_catalog_page,
+        commands::setup_telegram_pair,
+        commands::configure_feishu_skills,
+        commands::configure_telegram_webhook,
+        commands::configure_tunnel,
+        commands::start_tunnel,
+        commands::stop_tunnel,
+        commands::get_tunnel_status,
+        commands::get_availability_report,
+        commands::configure_workspace_versioning,
+        commands::get_workspace_versioning,
+        commands::list_workspace_versions,
+        commands::restore_workspace_file,
+        commands::configure_config_watch,
+        commands::get_config_watch,
+        commands::list_config_watch_alerts,
+        commands::acknowledge_config_watch_alerts,
+        commands::clear_config_watch_alerts,
+        commands::configure_file_audit,
+        commands::get_file_audit_config,
+        commands::list_file_audit_findings,
+        commands::clear_file_audit_findings,
+        commands::get_tool_permissions,
+        commands::set_tool_permissions,
+        commands::get_quiet_hours,
+        commands::set_quiet_hours,
+        commands::export_app_settings,
+        commands::import_app_settings,
+        commands::export_model_preset,
+        commands::import_model_preset,
+        commands::get_process_tree,
+        commands::kill_child_process,
+        commands::get_status_api,
+        commands::set_status_api,
+        commands::get_shortcuts,
+        commands::set_shortcuts,
+        commands::list_profiles,
+        commands::create_profile,
+        commands::switch_profile,
+        commands::delete_profile,
+        commands::get_script_scan_config,
+        commands::set_script_scan_config,
+        commands::start_script_scan,
+        commands::get_script_scan_report,
+        commands::verify_backup,
+        commands::get_firewall_rule_status,
+        commands::add_firewall_rule,
+        commands::remove_firewall_rule,
+        commands::export_offline_bundle,
+        commands::validate_telegram_token,
+        commands::list_telegram_pairing_requests,
+        commands::approve_telegram_pairing,
+        commands::reject_telegram_pairing,
+        commands::get_telegram_status,
+        commands::repair_discord_channel,
+        commands::get_keep_alive_status,
+        commands::configure_keep_alive,
+        commands::run_keep_alive_check,
+        commands::verify_isolation,
+        commands::diff_gateway_env,
+        commands::validate_provider_key,
+        commands::test_model_chain,
+        commands::get_support_share,
+        commands::configure_support_share,
+        commands::share_support_bundle,
+        commands::get_consents,
+        commands::set_consent,
+        commands::add_custom_provider,
+        commands::list_custom_providers,
+        commands::preview_uninstall,
+        commands::scan_leftovers,
+        commands::clean_leftover,
+        commands::detect_ollama,
+        commands::install_ollama,
+        commands::configure_ollama,
+        commands::validate_bedrock_credentials,
+        commands::generate_sbom,
+        commands::get_tls_status,
+        commands::generate_gateway_certificate,
+        commands::import_gateway_certificate,
+        commands::disable_gateway_tls,
+        commands::get_read_only_status,
+        commands::enable_read_only,
+        commands::disable_read_only,
+        commands::get_app_lock_status,
+        commands::configure_app_lock,
+        commands::disable_app_lock,
+        commands::unlock_app,
+        commands::lock_app,
+        commands::get_security_schedule,
+        commands::configure_security_schedule,
+        commands::reveal_secret,
+        commands::apply_security_action
+    ];
     tauri::Builder::default()
+        .plugin(shortcuts::plugin())
         .setup(|app| {
             setup_tray(app)?;
+            startup::mark("tray_ready");
+            let handle = app.handle().clone();
+            install_progress::set_sink(move |progress| {
+                let _ = handle.emit(install_progress::EVENT, progress);
+            });
+            let handle = app.handle().clone();
+            shortcuts::set_handler(handle.clone(), move |action| match action {
+                shortcuts::ACTION_TOGGLE_WINDOW => toggle_main_window(&handle),
+                shortcuts::ACTION_RESTART_GATEWAY => {
+                    let restarted = app_lock::ensure_unlocked("Restarting the gateway")
+                        .and_then(|_| read_only::ensure_writable("Restarting the gateway"))
+                        .and_then(|_| process::restart());
+                    match restarted {
+                        Ok(result) => {
+                            logger::info(&format!("Shortcut restart: {}", result.message))
+                        }
+                        Err(err) => logger::warn(&format!("Shortcut restart failed: {err}")),
+                    }
+                }
+                shortcuts::ACTION_OPEN_DASHBOARD => {
+                    // The dashboard URL carries the gateway token.
+                    let opened = app_lock::ensure_unlocked("Opening the dashboard")
+                        .and_then(|_| browser::open_dashboard());
+                    if let Err(err) = opened {
+                        logger::warn(&format!("Shortcut open dashboard failed: {err}"));
+                    }
+                }
+                _ => {}
+            });
+            startup::defer("shortcuts", shortcuts::start);
+            let handle = app.handle().clone();
+            status_stream::set_sink(move |change| {
+                let _ = handle.emit(status_stream::EVENT, change);
+            });
+            startup::defer("status_stream", status_stream::start);
+            let handle = app.handle().clone();
+            script_scan::set_sink(move |progress| {
+                let _ = handle.emit(script_scan::EVENT, progress);
+            });
+            let handle = app.handle().clone();
+            config_watch::set_sink(move |alerts| {
+                let _ = handle.emit(config_watch::EVENT, alerts);
+            });
+            let handle = app.handle().clone();
+            security::set_alert_sink(move |alert| {
+                let _ = handle.emit(security::ALERT_EVENT, alert);
+            });
+            startup::defer("security_schedule", security::start_scheduler);
             Ok(())
         })
+        .on_page_load(|webview, payload| {
+            if webview.label() == MAIN_WINDOW_LABEL && payload.event() == PageLoadEvent::Finished {
+                startup::window_ready();
+            }
+        })
         .on_window_event(|window, event| {
             if window.label() != MAIN_WINDOW_LABEL {
                 return;
@@ -155,46 +367,18 @@ fn main() {
                 if let Err(err) = window.hide() {
                     logger::error(&format!("Failed to hide window to tray: {err}"));
                 } else {
+                    app_lock::note_hidden();
                     logger::info("Main window hidden to system tray.");
                 }
             }
         })
-        .invoke_handler(tauri::generate_handler![
-            commands::check_env,
-            commands::install_env,
-            commands::release_port,
-            commands::get_install_lock_info,
-            commands::install_openclaw,
-            commands::uninstall_openclaw,
-            commands::configure,
-            commands::get_current_config,
-            commands::update_provider_api_key,
-            commands::start,
-            commands::stop,
-            commands::end_openclaw,
-            commands::restart,
-            commands::health_check,
-            commands::get_status,
-            commands::backup,
-            commands::list_backups,
-            commands::rollback,
-            commands::upgrade,
-            commands::switch_model,
-            commands::security_check,
-            commands::list_logs,
-            commands::read_log,
-            commands::export_log,
-            commands::clear_cache,
-            commands::clear_sessions,
-            commands::reload_config,
-            commands::open_management_url,
-            commands::open_path,
-            commands::logs_dir_path,
-            commands::donate_wechat_qr,
-            commands::list_skill_catalog,
-            commands::list_model_catalog,
-            commands::setup_telegram_pair
-        ])
+        .invoke_handler(move |invoke| {
+            if let Err(err) = commands::guard_invoke(invoke.message.command()) {
+                invoke.resolver.reject(err);
+                return true;
+            }
+            handler(invoke)
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -5,6 +5,9 @@ fn default_kimi_region() -> String {
     "cn".to_string()
 }
 
+/// Latest GA data-plane version of the Azure OpenAI API.
+pub const AZURE_DEFAULT_API_VERSION: &str = "2024-10-21";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DependencyStatus {
     pub name: String,
@@ -31,11 +34,90 @@ pub struct EnvCheckResult {
     pub port_status: PortStatus,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WarningSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Typed warning carried by configure/install/upgrade/env results. `code` is a
+/// stable snake_case id the UI groups and localizes by; `message` is the English fallback.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResultWarning {
+    pub code: String,
+    pub severity: WarningSeverity,
+    pub message: String,
+    pub remediation: Option<String>,
+    pub provider: Option<String>,
+    pub channel: Option<String>,
+}
+
+impl ResultWarning {
+    pub fn new(code: &str, severity: WarningSeverity, message: impl Into<String>) -> Self {
+        Self {
+            code: code.to_string(),
+            severity,
+            message: message.into(),
+            remediation: None,
+            provider: None,
+            channel: None,
+        }
+    }
+
+    pub fn info(code: &str, message: impl Into<String>) -> Self {
+        Self::new(code, WarningSeverity::Info, message)
+    }
+
+    pub fn warning(code: &str, message: impl Into<String>) -> Self {
+        Self::new(code, WarningSeverity::Warning, message)
+    }
+
+    pub fn error(code: &str, message: impl Into<String>) -> Self {
+        Self::new(code, WarningSeverity::Error, message)
+    }
+
+    pub fn with_remediation(mut self, remediation: impl Into<String>) -> Self {
+        self.remediation = Some(remediation.into());
+        self
+    }
+
+    pub fn with_provider(mut self, provider: impl Into<String>) -> Self {
+        self.provider = Some(provider.into());
+        self
+    }
+
+    pub fn with_channel(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstallEnvResult {
     pub installed: Vec<String>,
     pub skipped: Vec<String>,
-    pub warnings: Vec<String>,
+    pub warnings: Vec<ResultWarning>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OllamaModel {
+    /// Tag as Ollama serves it, e.g. `llama3.1:8b`.
+    pub name: String,
+    pub size_bytes: u64,
+    pub parameter_size: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct OllamaStatus {
+    /// `ollama` is on PATH.
+    pub installed: bool,
+    pub path: Option<String>,
+    /// The local API answered.
+    pub running: bool,
+    pub version: Option<String>,
+    pub models: Vec<OllamaModel>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +127,10 @@ pub enum SourceMethod {
     Bun,
     Git,
     Binary,
+    /// Local bundle from `export_offline_bundle` or an `npm pack` tarball, given as
+    /// `source_url`; installs without network access.
+    #[serde(rename = "offline_archive")]
+    OfflineArchive,
 }
 
 impl Default for SourceMethod {
@@ -74,6 +160,8 @@ pub struct OpenClawConfigInput {
     pub bind_address: String,
     pub source_method: SourceMethod,
     pub source_url: Option<String>,
+    /// Expected SHA-256 (hex, optionally `sha256:`-prefixed) of a binary download.
+    pub source_checksum: Option<String>,
     pub launch_args: String,
     pub onboarding_mode: String,
     pub onboarding_flow: String,
@@ -89,13 +177,39 @@ pub struct OpenClawConfigInput {
     pub enable_workspace_memory: bool,
     #[serde(default = "default_kimi_region")]
     pub kimi_region: String,
+    /// Azure OpenAI resource endpoint, e.g. `https://contoso.openai.azure.com`.
+    pub azure_endpoint: String,
+    /// Deployment to call; Azure routes by deployment name, so it is the model id
+    /// (`azure/<deployment>`). Defaults to the `azure/` models in the chain.
+    pub azure_deployment: String,
+    /// `api-version` for clients that still send one; `AZURE_DEFAULT_API_VERSION` when unset.
+    pub azure_api_version: Option<String>,
+    /// AWS access key pair for `amazon-bedrock/*` models; written to `.env` as the
+    /// standard `AWS_*` variables the AWS SDK reads.
+    pub aws_access_key_id: String,
+    pub aws_secret_access_key: String,
+    pub aws_region: String,
     pub enable_feishu_channel: bool,
     pub feishu_app_id: String,
     pub feishu_app_secret: String,
+    pub enable_dingtalk_channel: bool,
+    /// AppKey/AppSecret of the DingTalk enterprise internal app (robot).
+    pub dingtalk_app_key: String,
+    pub dingtalk_app_secret: String,
     pub enable_telegram_channel: bool,
     pub telegram_bot_token: String,
     pub telegram_pair_code: String,
+    pub enable_discord_channel: bool,
+    pub discord_bot_token: String,
+    /// Server (guild) the bot answers in; empty allows every server it is invited to.
+    pub discord_guild_id: String,
+    /// Single channel inside `discord_guild_id` to allow; empty allows all of them.
+    pub discord_channel_id: String,
     pub auto_open_dashboard: bool,
+    /// Free trial: configure a known-free OpenRouter model chain (a free OpenRouter key is
+    /// still required) and remind the user to pick a paid model later. Dropped when a key
+    /// for another provider is given.
+    pub trial_mode: bool,
 }
 
 impl Default for OpenClawConfigInput {
@@ -118,6 +232,7 @@ impl Default for OpenClawConfigInput {
             bind_address: "127.0.0.1".to_string(),
             source_method: SourceMethod::Npm,
             source_url: None,
+            source_checksum: None,
             launch_args: "gateway".to_string(),
             onboarding_mode: "local".to_string(),
             onboarding_flow: "quickstart".to_string(),
@@ -132,17 +247,223 @@ impl Default for OpenClawConfigInput {
             enable_session_memory_hook: true,
             enable_workspace_memory: true,
             kimi_region: default_kimi_region(),
+            azure_endpoint: String::new(),
+            azure_deployment: String::new(),
+            azure_api_version: None,
+            aws_access_key_id: String::new(),
+            aws_secret_access_key: String::new(),
+            aws_region: String::new(),
             enable_feishu_channel: false,
             feishu_app_id: String::new(),
             feishu_app_secret: String::new(),
+            enable_dingtalk_channel: false,
+            dingtalk_app_key: String::new(),
+            dingtalk_app_secret: String::new(),
             enable_telegram_channel: false,
             telegram_bot_token: String::new(),
             telegram_pair_code: String::new(),
+            enable_discord_channel: false,
+            discord_bot_token: String::new(),
+            discord_guild_id: String::new(),
+            discord_channel_id: String::new(),
             auto_open_dashboard: true,
+            trial_mode: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderEndpoint {
+    /// `global` or `cn`.
+    pub region: String,
+    pub api_base_url: String,
+    /// Console page where API keys are created for this region.
+    pub console_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderSignupInfo {
+    pub provider: String,
+    pub display_name: String,
+    pub endpoints: Vec<ProviderEndpoint>,
+    /// Region to preselect, from the saved Kimi region setting; `global` when unset.
+    pub recommended_region: String,
+    pub free_tier_note: Option<String>,
+    /// The global endpoint is usually unreachable from mainland China without a proxy.
+    pub proxy_usually_required_in_cn: bool,
+    pub env_var: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderKeyStatus {
+    Valid,
+    Invalid,
+    /// The provider could not be reached or gave an answer that says nothing about the key.
+    Unverified,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderKeyCheck {
+    pub provider: String,
+    pub region: String,
+    pub env_var: Option<String>,
+    pub status: ProviderKeyStatus,
+    pub http_status: Option<u16>,
+    pub message: String,
+}
+
+/// An OpenAI-compatible endpoint to register as `models.providers.<name>` (Ollama,
+/// LM Studio, vLLM, a company proxy). `api_key` may be empty for local servers.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct CustomProviderInput {
+    pub name: String,
+    pub base_url: String,
+    pub api_key: String,
+    pub model_ids: Vec<String>,
+    pub context_window: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomProviderRecord {
+    pub name: String,
+    pub base_url: String,
+    /// Where the key lives in `.env`; `None` for keyless local servers.
+    pub env_var: Option<String>,
+    pub model_ids: Vec<String>,
+    pub added_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomProviderResult {
+    pub provider: CustomProviderRecord,
+    /// `name/model` keys usable in the model chain.
+    pub model_keys: Vec<String>,
+    pub warnings: Vec<ResultWarning>,
+}
+
+/// One recorded decision: `version` is the text the user saw (risk statement version),
+/// `accepted` what they chose.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConsentRecord {
+    pub version: String,
+    pub accepted: bool,
+    pub decided_at: String,
+    /// Carried over from an install made before consent was recorded, when onboarding
+    /// passed `--accept-risk` unconditionally.
+    #[serde(default)]
+    pub migrated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ConsentStore {
+    pub risk_statement: Option<ConsentRecord>,
+    pub telemetry: Option<ConsentRecord>,
+}
+
+/// App-level read-only mode for kiosk/demo machines. The PIN is only kept as a salted
+/// SHA-256 hash, DPAPI-sealed where available.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ReadOnlyConfig {
+    pub enabled: bool,
+    pub pin_salt: String,
+    pub pin_hash: String,
+    /// `pin_hash` is a DPAPI blob rather than the hex hash.
+    pub sealed: bool,
+    pub enabled_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKind {
+    ApiKey,
+    GatewayToken,
+    TelegramBotToken,
+    DiscordBotToken,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretReveal {
+    pub kind: SecretKind,
+    pub provider: Option<String>,
+    pub masked: String,
+    /// Raw value, only while a reveal is open.
+    pub value: Option<String>,
+    /// When the open reveal ends and the UI should show `masked` again.
+    pub expires_at: Option<String>,
+}
+
+/// Optional PIN asked for when the window comes back from the tray after
+/// `lock_after_minutes`. `pin_hash` is DPAPI-sealed when `sealed`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppLockConfig {
+    pub enabled: bool,
+    pub pin_salt: String,
+    pub pin_hash: String,
+    pub sealed: bool,
+    pub lock_after_minutes: u32,
+}
+
+impl Default for AppLockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pin_salt: String::new(),
+            pin_hash: String::new(),
+            sealed: false,
+            lock_after_minutes: 5,
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub lock_after_minutes: u32,
+    /// Seconds until another PIN attempt is accepted after repeated failures.
+    pub retry_after_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadOnlyStatus {
+    pub enabled: bool,
+    pub enabled_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsentStatus {
+    /// Version of the risk statement this installer shows; an acceptance of an older
+    /// one has to be renewed.
+    pub risk_statement_version: String,
+    pub risk_accepted: bool,
+    pub records: ConsentStore,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetupPreset {
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    /// The preset applied to a default wizard payload, for previewing in the UI.
+    pub config: OpenClawConfigInput,
+}
+
+/// Payload of the `install://progress` event. Phase updates carry `message`; streamed
+/// npm/git/bun output carries `stream` (`stdout`/`stderr`) and `line`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallProgress {
+    pub phase: String,
+    pub percent: u8,
+    pub message: String,
+    pub stream: Option<String>,
+    pub line: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstallResult {
     pub method: String,
@@ -154,7 +475,7 @@ pub struct InstallResult {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigureResult {
     pub config_path: String,
-    pub warnings: Vec<String>,
+    pub warnings: Vec<ResultWarning>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -162,6 +483,56 @@ pub struct ProcessControlResult {
     pub running: bool,
     pub pid: Option<u32>,
     pub message: String,
+    /// Readiness report for a freshly spawned gateway; `None` for stop/daemon paths.
+    #[serde(default)]
+    pub boot: Option<BootReport>,
+    /// Outcome of the optional post-start warm-up request; `None` when it did not run.
+    #[serde(default)]
+    pub warm_up: Option<WarmUpResult>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum StartOutcome {
+    /// Process alive and the gateway port answers (or it logged that it is listening).
+    Started,
+    /// Process alive but not ready within the timeout; it may still come up.
+    Starting,
+    /// Process exited before becoming ready.
+    FailedAtBoot,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BootReport {
+    pub outcome: StartOutcome,
+    pub waited_ms: u64,
+    /// Last lines the gateway wrote to stderr during this start.
+    pub stderr_tail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarmUpResult {
+    pub ok: bool,
+    pub latency_ms: Option<u64>,
+    pub message: String,
+}
+
+/// One model of the chain answering a one-token prompt; `role` is `primary` or
+/// `fallback`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelProbeResult {
+    pub model: String,
+    pub role: String,
+    pub ok: bool,
+    pub latency_ms: Option<u64>,
+    pub http_status: Option<u16>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelChainTestResult {
+    pub results: Vec<ModelProbeResult>,
+    pub all_ok: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -170,6 +541,29 @@ pub struct HealthResult {
     pub status: u16,
     pub url: String,
     pub body: String,
+    /// Authenticated HTTP round-trip measured on status samples; `None` when not probed.
+    #[serde(default)]
+    pub latency_ms: Option<u64>,
+    /// Up, but the round-trip exceeded the sluggish threshold.
+    #[serde(default)]
+    pub sluggish: bool,
+    /// Something answers on the port, but it is not an OpenClaw gateway.
+    #[serde(default)]
+    pub foreign_service: bool,
+    /// Something answers on the port but never identified itself as OpenClaw.
+    #[serde(default)]
+    pub unverified: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupCategory {
+    Config,
+    Secrets,
+    Sessions,
+    Workspace,
+    #[default]
+    Full,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -178,11 +572,26 @@ pub struct BackupInfo {
     pub path: String,
     pub created_at: String,
     pub size: u64,
+    pub category: BackupCategory,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupResult {
     pub backup: BackupInfo,
+    /// Files left out of the archive, e.g. over the per-file size limit.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// Archive entries re-hashed against the SHA-256 list in the backup manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupVerifyResult {
+    pub backup_id: String,
+    /// False for backups made before checksums were recorded; nothing could be checked.
+    pub has_checksums: bool,
+    pub checked: usize,
+    pub mismatched: Vec<String>,
+    pub missing: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -191,6 +600,50 @@ pub struct RollbackResult {
     pub auto_backup: BackupInfo,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupStorageConfig {
+    /// Folder holding backup archives. Empty means the default under %APPDATA%.
+    pub location: String,
+    /// Total size cap for all archives in MB; the oldest are pruned first. 0 disables the cap.
+    pub max_total_mb: u64,
+    /// Files larger than this many MB are left out of backups with a warning. 0 disables it.
+    pub max_file_mb: u64,
+    /// Names (`node_modules`, `*.log`) match any path segment; patterns containing `/`
+    /// match a path relative to the backed-up folder (`workspace/tmp`).
+    pub exclude_patterns: Vec<String>,
+}
+
+impl Default for BackupStorageConfig {
+    fn default() -> Self {
+        Self {
+            location: String::new(),
+            max_total_mb: 0,
+            max_file_mb: 2048,
+            exclude_patterns: ["node_modules", "cache", "npm-cache", "logs"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupStorageResult {
+    pub config: BackupStorageConfig,
+    pub dir: String,
+    pub moved: usize,
+    pub pruned: Vec<String>,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TempSweepResult {
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
+    pub failed: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpgradeResult {
     pub old_version: String,
@@ -198,13 +651,84 @@ pub struct UpgradeResult {
     pub rolled_back: bool,
     pub backup_id: String,
     pub message: String,
+    pub warnings: Vec<ResultWarning>,
+    /// How long the gateway was stopped, when it was running before the upgrade.
+    #[serde(default)]
+    pub downtime_ms: Option<u64>,
+}
+
+/// Result of the compatibility checks run before an upgrade. `blocked` means the
+/// upgrade would fail mid-install and is refused until the errors are fixed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradeCheck {
+    pub current_version: String,
+    pub target_version: Option<String>,
+    pub node_version: Option<String>,
+    pub required_node: Option<String>,
+    pub blocked: bool,
+    pub warnings: Vec<ResultWarning>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UninstallResult {
     pub stopped_process: bool,
     pub removed_paths: Vec<String>,
-    pub warnings: Vec<String>,
+    pub warnings: Vec<ResultWarning>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum UninstallItemKind {
+    Directory,
+    ScheduledTask,
+    Service,
+    FirewallRule,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UninstallPreviewItem {
+    pub kind: UninstallItemKind,
+    /// Folder path, task, service or rule name.
+    pub target: String,
+    pub size_bytes: Option<u64>,
+    /// False for entries uninstall leaves in place; `note` says how to remove them.
+    pub removed: bool,
+    pub note: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UninstallPreview {
+    pub items: Vec<UninstallPreviewItem>,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LeftoverKind {
+    Directory,
+    ScheduledTask,
+    Service,
+    RunKey,
+    FirewallRule,
+    NpmLink,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeftoverItem {
+    /// `<kind>:<target>`; pass it to `clean_leftover`.
+    pub id: String,
+    pub kind: LeftoverKind,
+    /// Path, task, service, Run value or rule name.
+    pub target: String,
+    pub size_bytes: Option<u64>,
+    pub reason: String,
+    pub needs_admin: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeftoverCleanResult {
+    pub cleaned: LeftoverItem,
+    pub remaining: Vec<LeftoverItem>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -221,6 +745,20 @@ pub struct SecurityIssue {
     pub message: String,
     pub path: Option<String>,
     pub suggestion: Option<String>,
+    /// Fix the UI can offer as a button, applied with `apply_security_action`.
+    #[serde(default)]
+    pub action: Option<SecurityAction>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityAction {
+    /// Add the installer's inbound rule for the gateway port.
+    AddFirewallRule,
+    /// Bind the gateway to 127.0.0.1 only.
+    BindLoopback,
+    /// Require a gateway token (generated) instead of no auth.
+    EnableTokenAuth,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -229,6 +767,94 @@ pub struct SecurityResult {
     pub issues: Vec<SecurityIssue>,
 }
 
+/// Opt-in background security check. A toast is shown when the score drops or a new
+/// High severity issue appears.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecurityScheduleConfig {
+    pub enabled: bool,
+    pub interval_hours: u32,
+}
+
+impl Default for SecurityScheduleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityCheckRecord {
+    pub checked_at: String,
+    pub result: SecurityResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityScheduleStatus {
+    pub config: SecurityScheduleConfig,
+    pub last: Option<SecurityCheckRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityAlert {
+    pub title: String,
+    pub message: String,
+    pub score: u8,
+    pub previous_score: Option<u8>,
+}
+
+/// Limits for the suspicious-script scan over the OpenClaw home and install folder.
+/// `exclude_dirs` are folder names skipped at any depth; `max_files` caps how many
+/// changed scripts one run reads and `max_secs` caps its wall time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScriptScanConfig {
+    pub exclude_dirs: Vec<String>,
+    pub max_files: u64,
+    pub max_secs: u64,
+}
+
+impl Default for ScriptScanConfig {
+    fn default() -> Self {
+        Self {
+            exclude_dirs: ["node_modules", ".git", ".cache"]
+                .map(str::to_string)
+                .to_vec(),
+            max_files: 5_000,
+            max_secs: 30,
+        }
+    }
+}
+
+/// Pushed on `security://scan-progress` while a script scan runs, and once with
+/// `done` when it ends.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ScriptScanProgress {
+    pub files_seen: u64,
+    pub files_read: u64,
+    pub files_reused: u64,
+    pub flagged: u64,
+    pub elapsed_ms: u64,
+    pub done: bool,
+    pub truncated: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptScanReport {
+    pub finished_at: String,
+    pub files_seen: u64,
+    pub files_read: u64,
+    /// Unchanged since the previous scan, so its earlier verdict was reused.
+    pub files_reused: u64,
+    pub elapsed_ms: u64,
+    /// Why the scan stopped early, if a budget ran out.
+    pub truncated: Option<String>,
+    pub findings: Vec<SecurityIssue>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstallerStatus {
     pub running: bool,
@@ -238,6 +864,39 @@ pub struct InstallerStatus {
     pub current_model: String,
     pub port: u16,
     pub health: HealthResult,
+    pub tunnel: TunnelStatus,
+    /// `service` when the installer's Windows service owns the gateway, `daemon` when an
+    /// OpenClaw scheduled task does, otherwise `installer`.
+    pub managed_by: String,
+    /// Unacknowledged changes to watched openclaw.json keys.
+    pub config_alerts: usize,
+    /// Running on the free trial model chain; the UI should suggest a paid model.
+    pub trial_mode: bool,
+    pub watchdog: WatchdogState,
+}
+
+/// Autostart crash history, kept in run prefs. Once `crash_loop` is set the installer
+/// stops relaunching the gateway until the user starts or stops it.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct WatchdogState {
+    /// Crashes in a row that each came within the rapid-crash window of a start.
+    pub consecutive_crashes: u32,
+    pub last_started_ms: i64,
+    pub last_uptime_secs: Option<u64>,
+    pub last_crash_at: Option<String>,
+    /// Autostart waits until this time (epoch ms) after a crash; doubles per crash.
+    pub next_attempt_ms: i64,
+    pub crash_loop: bool,
+    pub last_stderr_tail: Option<String>,
+}
+
+/// Pushed on `status://changed`. `reason` is one of `initial`, `started`, `crashed`,
+/// `crash_loop`, `user_stopped`, `health_failed`, `health_recovered` or `updated`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub reason: String,
+    pub status: InstallerStatus,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -246,6 +905,131 @@ pub struct LogSummary {
     pub path: String,
     pub size: u64,
     pub modified_at: String,
+    /// `gateway` for the gateway's stdout/stderr logs and their archives, else `installer`.
+    pub kind: String,
+}
+
+/// One installer log line. Lines written before structured logging have an empty
+/// `module` and no correlation id.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct LogEntry {
+    pub ts: String,
+    pub level: String,
+    pub module: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+    pub message: String,
+}
+
+/// A window of whole lines from a log file. Pass `start_offset` back as `before` to
+/// page towards the start of the file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogPage {
+    pub name: String,
+    pub text: String,
+    pub start_offset: u64,
+    pub end_offset: u64,
+    pub file_size: u64,
+    pub has_more: bool,
+}
+
+/// Filters for `query_logs`. `level` is a minimum (`warn` also returns errors);
+/// `since`/`until` are RFC 3339 timestamps.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct LogQuery {
+    pub level: Option<String>,
+    pub module: Option<String>,
+    pub correlation_id: Option<String>,
+    pub contains: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Size-based rotation for installer and gateway logs. A log over `max_file_mb` is
+/// renamed to `<name>.1.log` (gzipped when `compress`), keeping `max_files` rotations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogRotationConfig {
+    pub max_file_mb: u64,
+    pub max_files: u32,
+    pub compress: bool,
+    /// How gateway stdout/stderr logs are split into archives. The gateway holds them
+    /// open, so they are only cut when it is (re)started.
+    pub gateway_split: GatewayLogSplit,
+    /// Archived gateway logs older than this many days are deleted; 0 keeps them all.
+    pub gateway_retention_days: u32,
+}
+
+impl Default for LogRotationConfig {
+    fn default() -> Self {
+        Self {
+            max_file_mb: 10,
+            max_files: 5,
+            compress: true,
+            gateway_split: GatewayLogSplit::Daily,
+            gateway_retention_days: 14,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GatewayLogSplit {
+    /// A log last written on an earlier day becomes `openclaw-stdout.<date>.log`.
+    #[default]
+    Daily,
+    /// Every start archives the previous run as `openclaw-stdout.<date>_<time>.log`.
+    PerRun,
+    /// Only the `max_file_mb` rotation applies.
+    Size,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SupportShareKind {
+    /// `PUT <url>/<file>`; a body that is a URL is taken as the link (transfer.sh and
+    /// its clones, S3-compatible buckets that accept uploads on a prefix).
+    #[default]
+    Http,
+    /// `PUT <url>/<file>` with basic auth (Nextcloud, ownCloud, any WebDAV share).
+    WebDav,
+}
+
+/// The user's own upload destination for `share_support_bundle`. Nothing is uploaded
+/// until it is enabled with a URL.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SupportShareConfig {
+    pub enabled: bool,
+    pub kind: SupportShareKind,
+    pub url: String,
+    pub username: String,
+    pub password: String,
+    /// Sent as `Authorization` on `http` uploads when set (e.g. `Bearer ...`).
+    pub auth_header: String,
+    /// Where helpers download from, when that differs from `url`. The link is
+    /// `<public_url>/<file>`.
+    pub public_url: String,
+    /// `password` and `auth_header` are DPAPI-sealed on disk when set.
+    pub sealed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupportShareResult {
+    pub share_id: String,
+    pub link: String,
+    pub size_bytes: u64,
+    /// Secret values replaced with `******` before upload.
+    pub redactions: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogPurgeResult {
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -255,6 +1039,19 @@ pub struct SkillCatalogItem {
     pub eligible: bool,
     pub bundled: bool,
     pub source: String,
+    pub required_bins: Vec<String>,
+    pub required_env: Vec<String>,
+    /// Requirements not satisfied on this machine (binaries or env vars).
+    pub missing: Vec<String>,
+    /// Size of the skill folder on disk, when the CLI reports where it lives.
+    pub footprint_bytes: Option<u64>,
+    /// What the skill asks for, e.g. `exec:gh`, `env:GITHUB_TOKEN`, `network`.
+    pub permissions: Vec<String>,
+    /// ClawHub metadata; `None` when the registry is unreachable.
+    pub version: Option<String>,
+    /// ClawHub star count.
+    pub stars: Option<u64>,
+    pub downloads: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -264,16 +1061,73 @@ pub struct ModelCatalogItem {
     pub name: String,
     pub available: Option<bool>,
     pub missing: bool,
+    pub context_window: Option<u64>,
+    /// USD per million tokens.
+    pub input_price_per_mtok: Option<f64>,
+    pub output_price_per_mtok: Option<f64>,
+    /// Accepted input (`text`, `image`, `audio`, `video`); empty when unknown.
+    #[serde(default)]
+    pub modalities: Vec<String>,
 }
 
+/// One page of the (optionally filtered) model catalog.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct InstallState {
+pub struct ModelCatalogPage {
+    pub items: Vec<ModelCatalogItem>,
+    pub offset: usize,
+    pub limit: usize,
+    /// Entries matching the filter, across all pages.
+    pub total: usize,
+    /// SHA-256 over the whole matching list; unchanged hash means no page changed.
+    pub content_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallState {
     pub method: SourceMethod,
     pub install_dir: String,
     pub source_url: Option<String>,
     pub command_path: String,
     pub version: String,
     pub launch_args: String,
+    /// Verified `installer-bin\openclaw.cmd` under the install dir; preferred over
+    /// `command_path` so nothing on a hot path has to fall back to `npx`.
+    #[serde(default)]
+    pub shim_path: Option<String>,
+    /// Registry check of the installed npm package; None when it could not be checked.
+    #[serde(default)]
+    pub verified_package: Option<VerifiedPackage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SbomResult {
+    pub path: String,
+    pub component_count: usize,
+    pub generated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TlsStatus {
+    pub enabled: bool,
+    pub cert_path: Option<String>,
+    pub key_path: Option<String>,
+    pub subject: Option<String>,
+    /// RFC 3339 `notAfter` of the certificate.
+    pub expires_at: Option<String>,
+    pub warnings: Vec<ResultWarning>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VerifiedPackage {
+    pub version: String,
+    /// SSRI hash (`sha512-…`) of the tarball, equal in the lockfile and the registry.
+    pub integrity: String,
+    /// The registry publishes a provenance attestation (built by CI from the source repo).
+    pub provenance: bool,
+    /// `npm audit signatures` accepted the registry signature of every installed package.
+    #[serde(default)]
+    pub signatures_verified: bool,
+    pub verified_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -296,4 +1150,633 @@ pub struct OpenClawFileConfig {
     pub install_dir: String,
     pub launch_args: String,
     pub updated_at: String,
+    /// Filled only for the webview, where `api_key` is masked.
+    #[serde(default)]
+    pub provider_keys: Vec<ProviderKeyState>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderKeyState {
+    pub provider: String,
+    pub has_key: bool,
+    /// Placeholder such as `••••abcd`; the raw key needs `reveal_secret`.
+    pub masked: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct FeishuSkillsInput {
+    /// Empty values fall back to the app credentials saved by the install wizard.
+    pub app_id: String,
+    pub app_secret: String,
+    pub enable_doc: bool,
+    pub enable_wiki: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeishuSkillsResult {
+    pub enabled_tools: Vec<String>,
+    pub granted_scopes: Vec<String>,
+    pub missing_scopes: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TelegramWebhookInput {
+    /// Public HTTPS URL that forwards to the local gateway (frp, tailscale funnel, ...).
+    pub public_url: String,
+    /// Optional secret sent by Telegram in `X-Telegram-Bot-Api-Secret-Token`; generated when empty.
+    pub secret_token: String,
+}
+
+/// Bot behind a token, as reported by Telegram `getMe`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramBotInfo {
+    pub id: i64,
+    pub username: String,
+    pub first_name: String,
+    pub can_join_groups: bool,
+    /// False while privacy mode is on; the bot then only sees commands and mentions in groups.
+    pub can_read_all_group_messages: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramPairingRequest {
+    pub code: String,
+    pub user_id: Option<String>,
+    pub username: Option<String>,
+    pub requested_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramChannelStatus {
+    pub configured: bool,
+    pub connected: bool,
+    /// `webhook` or `polling`.
+    pub mode: String,
+    pub detail: String,
+    /// None when the CLI could not list requests.
+    pub pending_requests: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramWebhookResult {
+    /// Effective update mode after setup: `webhook` or `polling`.
+    pub mode: String,
+    pub webhook_url: Option<String>,
+    pub pending_update_count: u64,
+    pub last_error: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TunnelConfigInput {
+    /// `cloudflared` or `frpc`.
+    pub provider: String,
+    pub enabled: bool,
+    /// Named Cloudflare Tunnel token. Empty means an ephemeral trycloudflare.com quick tunnel.
+    pub cloudflared_token: String,
+    /// Stable public URL routed to the gateway (named tunnel hostname or frp custom domain).
+    pub public_url: String,
+    pub frp_server_addr: String,
+    pub frp_server_port: u16,
+    pub frp_auth_token: String,
+    pub frp_custom_domain: String,
+}
+
+impl Default for TunnelConfigInput {
+    fn default() -> Self {
+        Self {
+            provider: "cloudflared".to_string(),
+            enabled: false,
+            cloudflared_token: String::new(),
+            public_url: String::new(),
+            frp_server_addr: String::new(),
+            frp_server_port: 7000,
+            frp_auth_token: String::new(),
+            frp_custom_domain: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TunnelStatus {
+    pub provider: String,
+    pub enabled: bool,
+    pub running: bool,
+    pub pid: Option<u32>,
+    /// Public dashboard URL; open it via `open_management_url` to attach the gateway token.
+    pub public_url: Option<String>,
+    pub binary_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvailabilityWindow {
+    /// `24h`, `7d` or `30d`.
+    pub label: String,
+    /// None when no health samples were recorded in the window.
+    pub availability_percent: Option<f64>,
+    pub sample_count: usize,
+    pub outage_count: usize,
+    pub longest_outage_secs: u64,
+    /// Mean time to recovery over outages that ended inside the window.
+    pub mttr_secs: Option<u64>,
+    pub restart_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AvailabilityReport {
+    pub generated_at: String,
+    pub windows: Vec<AvailabilityWindow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceVersioningConfig {
+    pub enabled: bool,
+    /// Minimum minutes between automatic snapshot commits of the workspace.
+    pub interval_minutes: u32,
+}
+
+impl Default for WorkspaceVersioningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: 15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceVersion {
+    pub rev: String,
+    pub committed_at: String,
+    pub message: String,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct FileAuditConfig {
+    pub enabled: bool,
+    /// Directories outside the workspace that the agent is not expected to modify.
+    /// Empty means the user's Desktop and Documents folders.
+    pub protected_paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConfigWatchConfig {
+    pub enabled: bool,
+    /// Dotted openclaw.json key paths; `*` matches one segment and a key also covers
+    /// everything nested under it (e.g. `gateway.auth`, `channels.*.token`).
+    pub keys: Vec<String>,
+}
+
+impl Default for ConfigWatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            keys: [
+                "gateway.auth",
+                "gateway.bind",
+                "channels.*.token",
+                "channels.*.botToken",
+                "channels.*.appSecret",
+                "tools",
+            ]
+            .iter()
+            .map(|k| k.to_string())
+            .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigWatchAlert {
+    pub id: String,
+    pub detected_at: String,
+    /// Full key path that changed, e.g. `channels.telegram.token`.
+    pub key: String,
+    /// `added`, `changed` or `removed`. Values are never recorded.
+    pub change: String,
+    pub acknowledged: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileAuditFinding {
+    pub detected_at: String,
+    pub path: String,
+    /// `created`, `modified` or `deleted`.
+    pub change: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ToolPermissions {
+    /// Shell exec policy: `deny`, `allowlist` or `full`.
+    pub exec_security: String,
+    /// When exec asks for approval: `off`, `on-miss` or `always`.
+    pub exec_ask: String,
+    pub elevated_enabled: bool,
+    /// Restrict file read/write tools to the agent workspace.
+    pub fs_workspace_only: bool,
+    pub browser_enabled: bool,
+    /// Sandbox mode for agent sessions: `off`, `non-main` or `all`.
+    pub sandbox_mode: String,
+    pub denied_tools: Vec<String>,
+}
+
+impl Default for ToolPermissions {
+    fn default() -> Self {
+        Self {
+            exec_security: "full".to_string(),
+            exec_ask: "on-miss".to_string(),
+            elevated_enabled: false,
+            fs_workspace_only: false,
+            browser_enabled: true,
+            sandbox_mode: "off".to_string(),
+            denied_tools: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolPermissionsResult {
+    pub permissions: ToolPermissions,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuietHoursRule {
+    /// Channel id as used in `channels.<id>` (e.g. `telegram`, `feishu`).
+    pub channel: String,
+    pub enabled: bool,
+    /// Local time `HH:MM` when the channel is paused.
+    pub start: String,
+    /// Local time `HH:MM` when the channel resumes. May be earlier than `start` to span midnight.
+    pub end: String,
+}
+
+impl Default for QuietHoursRule {
+    fn default() -> Self {
+        Self {
+            channel: "telegram".to_string(),
+            enabled: true,
+            start: "23:00".to_string(),
+            end: "07:00".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct QuietHoursConfig {
+    pub rules: Vec<QuietHoursRule>,
+}
+
+/// Opt-in background check that the gateway's channel connections (Feishu/Telegram
+/// websockets) are still alive; stale ones are reconnected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeepAliveConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+    /// Consecutive failed checks before a channel counts as stale and is reconnected.
+    pub stale_after_checks: u32,
+}
+
+impl Default for KeepAliveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: 300,
+            stale_after_checks: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct KeepAliveStatus {
+    pub config: KeepAliveConfig,
+    pub last_check_at: Option<String>,
+    /// Channel states seen by the last check.
+    pub channels: Vec<ChannelConnection>,
+    /// Most recent reconnects, newest last, e.g. `telegram: channel reloaded`.
+    pub actions: Vec<String>,
+}
+
+/// Opt-in read-only HTTP API on loopback for widgets and scripts. Every request must
+/// carry `token` as a bearer token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusApiConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+impl Default for StatusApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 28790,
+            token: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupPhase {
+    pub name: String,
+    /// Milliseconds since process start.
+    pub at_ms: u64,
+}
+
+/// Where launch time went. `settled` turns true once deferred startup work finished.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupReport {
+    pub budget_ms: u64,
+    pub window_ready_ms: Option<u64>,
+    pub settled: bool,
+    pub phases: Vec<StartupPhase>,
+}
+
+/// Named profiles and which one is active, stored at the app data root. `active: None`
+/// is the default profile.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ProfileRegistry {
+    pub active: Option<String>,
+    pub profiles: Vec<ProfileRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileRecord {
+    pub name: String,
+    pub created_at: String,
+    /// Gateway port assigned at creation; profiles from before ports were assigned have none.
+    #[serde(default)]
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub active: bool,
+    pub created_at: Option<String>,
+    pub installed: bool,
+    pub version: Option<String>,
+    pub gateway_running: bool,
+    pub data_dir: String,
+    pub openclaw_home: String,
+    /// Gateway port this profile uses, so several gateways can run side by side.
+    pub port: u16,
+}
+
+/// Global hotkeys, `action -> accelerator` (e.g. `toggle_window -> Ctrl+Alt+O`).
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ShortcutConfig {
+    pub bindings: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortcutConflict {
+    pub action: String,
+    pub accelerator: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShortcutResult {
+    pub config: ShortcutConfig,
+    /// Actions whose hotkey is active.
+    pub registered: Vec<String>,
+    /// Bindings Windows refused, usually because another app already owns the hotkey.
+    pub conflicts: Vec<ShortcutConflict>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppSettingsTransferResult {
+    pub path: String,
+    /// Settings sections written to (export) or applied from (import) the file.
+    pub sections: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ModelPreset {
+    pub name: String,
+    pub description: String,
+    pub model_chain: ModelChain,
+    /// Model aliases (`alias -> provider/model`) used to route chat commands like `/model fast`.
+    pub aliases: std::collections::BTreeMap<String, String>,
+    pub base_url: Option<String>,
+    pub kimi_region: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelPresetImportResult {
+    pub preset: ModelPreset,
+    /// Referenced models that are not in the current model catalog.
+    pub unknown_models: Vec<String>,
+    /// Providers referenced by the preset that have no API key configured yet.
+    pub missing_keys: Vec<String>,
+    pub applied: bool,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessNode {
+    pub pid: u32,
+    pub parent_pid: u32,
+    pub name: String,
+    pub command_line: String,
+    pub memory_bytes: u64,
+    /// Total CPU time (user + kernel) consumed since the process started.
+    pub cpu_seconds: f64,
+    /// 0 for the gateway itself, 1 for its direct children, and so on.
+    pub depth: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessTree {
+    pub root_pid: Option<u32>,
+    /// Depth-first order, so the UI can indent by `depth`.
+    pub processes: Vec<ProcessNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvVarDiff {
+    pub name: String,
+    pub gateway: Option<String>,
+    pub shell: Option<String>,
+}
+
+/// Gateway environment as spawned by the installer versus a new terminal's. Secret
+/// values are never included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvDiffReport {
+    /// When the compared gateway environment was recorded; `None` when no gateway has
+    /// been started yet and the environment of the next start was used.
+    pub captured_at: Option<String>,
+    /// In the terminal's PATH but not the gateway's, in terminal order.
+    pub missing_path_entries: Vec<String>,
+    pub extra_path_entries: Vec<String>,
+    pub encoding_vars: Vec<EnvVarDiff>,
+    /// Set in the terminal only (names).
+    pub missing_vars: Vec<String>,
+    /// Set in both with different values (names).
+    pub changed_vars: Vec<String>,
+}
+
+/// Where one running gateway process actually keeps its state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayIsolation {
+    pub pid: u32,
+    pub name: String,
+    /// Started by this installer (PID file) or listening on the configured port.
+    pub managed: bool,
+    pub state_dir: Option<String>,
+    pub config_path: Option<String>,
+    /// `environment`, `command_line`, `default` (variable unset, so OpenClaw falls back
+    /// to `%USERPROFILE%\.openclaw`) or `unknown` (environment not readable).
+    pub source: String,
+    /// `None` when the state directory could not be determined.
+    pub isolated: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IsolationReport {
+    pub isolated: bool,
+    pub expected_state_dir: String,
+    pub expected_config_path: String,
+    pub processes: Vec<GatewayIsolation>,
+    pub issues: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExistingConfigPrefill {
+    pub config_path: String,
+    /// OpenClaw home the config was found in. Nothing is bound to it until the
+    /// prefilled payload (whose `install_dir` it is) is configured.
+    pub openclaw_home: String,
+    /// True when openclaw.json exists but this installer has no install record for it.
+    pub install_state_missing: bool,
+    /// Wizard payload built from the existing config. API keys are masked; sending a
+    /// masked value back keeps the key already on disk.
+    pub payload: OpenClawConfigInput,
+    pub masked_keys: Vec<String>,
+    /// Channels already configured in openclaw.json. They are left untouched by the
+    /// prefilled payload.
+    pub configured_channels: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelConnection {
+    pub channel: String,
+    pub connected: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TestMessageResult {
+    pub channel: String,
+    pub target: String,
+    pub delivered: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ChannelStats {
+    pub channel: String,
+    /// Messages seen in the retained gateway stdout log (counts restart with log rotation).
+    pub inbound_messages: u64,
+    pub outbound_messages: u64,
+    pub last_inbound_at: Option<String>,
+    pub last_outbound_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayPortChangeResult {
+    pub old_port: u16,
+    pub new_port: u16,
+    pub health: HealthResult,
+    /// Dashboard URLs for the new port: loopback first, then LAN when bound to all interfaces.
+    pub urls: Vec<String>,
+    pub channels: Vec<ChannelConnection>,
+    pub warnings: Vec<ResultWarning>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineBundleResult {
+    pub path: String,
+    pub version: String,
+    pub files: usize,
+    pub size_bytes: u64,
+}
+
+/// Inbound Windows Firewall rule for the gateway port in LAN mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirewallRuleStatus {
+    pub rule_name: String,
+    pub port: u16,
+    pub exists: bool,
+    /// Whether this call added or removed the rule.
+    pub changed: bool,
+}
+
+/// Where the installer-managed home was adopted from, kept in state for support and undo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    pub source_dir: String,
+    pub target_dir: String,
+    pub migrated_at: String,
+    pub items: Vec<String>,
+    pub env_keys: Vec<String>,
+    pub old_port: Option<u16>,
+    pub new_port: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationResult {
+    pub record: MigrationRecord,
+    /// Pre-migration snapshot of the installer home, when it already had a config.
+    pub backup_id: Option<String>,
+    pub warnings: Vec<ResultWarning>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallMoveResult {
+    pub old_dir: String,
+    pub new_dir: String,
+    pub bytes_moved: u64,
+    pub restarted: bool,
+    pub warnings: Vec<ResultWarning>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortInspection {
+    pub port: u16,
+    pub in_use: bool,
+    pub pid: Option<u32>,
+    pub process_name: Option<String>,
+    pub executable_path: Option<String>,
+    pub command_line: Option<String>,
+    /// Authenticode signer (certificate subject CN/O) when the executable is signed.
+    pub publisher: Option<String>,
+    /// Get-AuthenticodeSignature status, e.g. `Valid`, `NotSigned`, `HashMismatch`.
+    pub signature_status: Option<String>,
+    /// The owner looks like an OpenClaw gateway (ours or another instance).
+    pub looks_like_openclaw: bool,
+    /// The owner is the gateway this installer started.
+    pub is_installer_gateway: bool,
+    /// Safe choices for the UI: `reuse_gateway`, `pick_port`, `terminate`.
+    pub actions: Vec<String>,
+    pub suggested_port: Option<u16>,
 }
@@ -0,0 +1,199 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use crate::models::{AppLockConfig, AppLockStatus};
+
+use super::{logger, read_only, secret_reveal, secrets, state_store};
+
+pub const EVENT: &str = "app-lock";
+const MAX_ATTEMPTS: u32 = 5;
+const LOCKOUT: Duration = Duration::from_secs(60);
+
+struct Session {
+    locked: bool,
+    hidden_at: Option<Instant>,
+    failures: u32,
+    blocked_until: Option<Instant>,
+}
+
+// Starts locked: a configured PIN is asked for on every launch.
+static SESSION: Mutex<Session> = Mutex::new(Session {
+    locked: true,
+    hidden_at: None,
+    failures: 0,
+    blocked_until: None,
+});
+
+pub fn app_lock_status() -> AppLockStatus {
+    let cfg = load_or_locked();
+    let session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    AppLockStatus {
+        enabled: cfg.enabled,
+        locked: cfg.enabled && session.locked,
+        lock_after_minutes: cfg.lock_after_minutes,
+        retry_after_secs: session
+            .blocked_until
+            .map(|until| until.saturating_duration_since(Instant::now()).as_secs())
+            .unwrap_or(0),
+    }
+}
+
+/// Sets (or changes) the PIN. Changing an existing one needs the current PIN.
+pub fn configure_app_lock(
+    pin: &str,
+    lock_after_minutes: u32,
+    current_pin: Option<&str>,
+) -> Result<AppLockStatus> {
+    read_only::validate_pin(pin)?;
+    let cfg = state_store::load_app_lock()?;
+    if cfg.enabled {
+        verify(&cfg, current_pin.unwrap_or_default())?;
+    }
+    let salt = uuid::Uuid::new_v4().simple().to_string();
+    let hash = read_only::hash_pin(&salt, pin.trim());
+    let (pin_hash, sealed) = match secrets::seal_value(&hash) {
+        Some(sealed) => (sealed?, true),
+        None => (hash, false),
+    };
+    state_store::save_app_lock(&AppLockConfig {
+        enabled: true,
+        pin_salt: salt,
+        pin_hash,
+        sealed,
+        lock_after_minutes,
+    })?;
+    set_locked(false);
+    logger::info(&format!(
+        "App lock enabled (after {lock_after_minutes} min in the tray)."
+    ));
+    Ok(app_lock_status())
+}
+
+pub fn disable_app_lock(pin: &str) -> Result<AppLockStatus> {
+    let cfg = state_store::load_app_lock()?;
+    if cfg.enabled {
+        verify(&cfg, pin)?;
+        state_store::save_app_lock(&AppLockConfig::default())?;
+        logger::info("App lock disabled.");
+    }
+    Ok(app_lock_status())
+}
+
+pub fn unlock(pin: &str) -> Result<AppLockStatus> {
+    let cfg = state_store::load_app_lock()?;
+    if cfg.enabled {
+        verify(&cfg, pin)?;
+    }
+    set_locked(false);
+    Ok(app_lock_status())
+}
+
+pub fn lock_now() -> AppLockStatus {
+    set_locked(true);
+    secret_reveal::hide_all();
+    app_lock_status()
+}
+
+/// Called when the main window goes to the tray; starts the lock timeout.
+pub fn note_hidden() {
+    secret_reveal::hide_all();
+    SESSION.lock().unwrap_or_else(|e| e.into_inner()).hidden_at = Some(Instant::now());
+}
+
+/// Called when the main window is revealed. Locks when it sat in the tray for at least
+/// `lock_after_minutes`; returns whether the UI has to ask for the PIN.
+pub fn on_reveal() -> bool {
+    let cfg = load_or_locked();
+    let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(hidden_at) = session.hidden_at.take() {
+        if lock_due(hidden_at.elapsed(), cfg.lock_after_minutes) {
+            session.locked = true;
+        }
+    }
+    cfg.enabled && session.locked
+}
+
+/// Guard for commands that reveal secrets or change anything.
+pub fn ensure_unlocked(action: &str) -> Result<()> {
+    if app_lock_status().locked {
+        return Err(anyhow!(
+            "{action} needs the app PIN; unlock the installer first."
+        ));
+    }
+    Ok(())
+}
+
+/// The lock config, or — when it cannot be read — an enabled lock that locks immediately.
+/// A damaged or unreadable file must not switch the lock off; `unlock` then reports the
+/// load error instead of letting anyone in.
+fn load_or_locked() -> AppLockConfig {
+    match state_store::load_app_lock() {
+        Ok(cfg) => cfg,
+        Err(err) => {
+            logger::warn(&format!("App lock state unreadable, staying locked: {err}"));
+            set_locked(true);
+            AppLockConfig {
+                enabled: true,
+                ..Default::default()
+            }
+        }
+    }
+}
+
+fn set_locked(locked: bool) {
+    SESSION.lock().unwrap_or_else(|e| e.into_inner()).locked = locked;
+}
+
+fn verify(cfg: &AppLockConfig, pin: &str) -> Result<()> {
+    let stored = if cfg.sealed {
+        secrets::open_value(&cfg.pin_hash)?
+    } else {
+        cfg.pin_hash.clone()
+    };
+    check_pin(&cfg.pin_salt, &stored, pin).inspect_err(|_| {
+        logger::warn("App lock: wrong PIN entered.");
+    })
+}
+
+/// PIN check shared with read-only mode: one failure counter and lockout for both
+/// prompts, so switching between them does not buy extra guesses.
+pub fn check_pin(salt: &str, stored_hash: &str, pin: &str) -> Result<()> {
+    let mut session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    if let Some(until) = session.blocked_until.filter(|until| *until > now) {
+        return Err(anyhow!(
+            "Too many wrong PINs; try again in {} s.",
+            until.duration_since(now).as_secs().max(1)
+        ));
+    }
+    if read_only::pin_matches(salt, pin, stored_hash) {
+        session.failures = 0;
+        session.blocked_until = None;
+        return Ok(());
+    }
+    session.failures += 1;
+    if session.failures >= MAX_ATTEMPTS {
+        session.failures = 0;
+        session.blocked_until = Some(now + LOCKOUT);
+    }
+    Err(anyhow!("Wrong PIN."))
+}
+
+fn lock_due(hidden_for: Duration, lock_after_minutes: u32) -> bool {
+    hidden_for >= Duration::from_secs(u64::from(lock_after_minutes) * 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lock_due;
+    use std::time::Duration;
+
+    #[test]
+    fn lock_is_due_after_the_configured_time_in_the_tray() {
+        assert!(lock_due(Duration::ZERO, 0));
+        assert!(!lock_due(Duration::from_secs(299), 5));
+        assert!(lock_due(Duration::from_secs(300), 5));
+    }
+}
@@ -0,0 +1,312 @@
+use std::fs;
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::models::AppSettingsTransferResult;
+
+use super::{logger, paths, state_store};
+
+const SETTINGS_FORMAT: &str = "openclaw-installer-settings";
+const SETTINGS_VERSION: u64 = 1;
+
+/// One installer settings file as it travels in an export. `local` fields (tokens,
+/// PINs, which profile is active) never leave this machine; an import keeps the
+/// values already here.
+struct SettingsFile {
+    name: &'static str,
+    local: &'static [&'static str],
+    load: Box<dyn Fn() -> Result<Value>>,
+    save: Box<dyn Fn(Value) -> Result<()>>,
+}
+
+fn settings_file<T: Serialize + DeserializeOwned + 'static>(
+    name: &'static str,
+    local: &'static [&'static str],
+    load: fn() -> Result<T>,
+    save: fn(&T) -> Result<()>,
+) -> SettingsFile {
+    SettingsFile {
+        name,
+        local,
+        load: Box::new(move || Ok(serde_json::to_value(load()?)?)),
+        save: Box::new(move |value| save(&serde_json::from_value(value)?)),
+    }
+}
+
+/// Every settings file export and import cover. OpenClaw config, credentials, consents
+/// and install state are intentionally not in it.
+fn settings_files() -> Vec<SettingsFile> {
+    vec![
+        settings_file(
+            "run_prefs",
+            &[],
+            state_store::load_run_prefs,
+            state_store::save_run_prefs,
+        ),
+        settings_file(
+            "tunnel",
+            &["cloudflared_token", "frp_auth_token"],
+            state_store::load_tunnel_config,
+            state_store::save_tunnel_config,
+        ),
+        settings_file(
+            "workspace_versioning",
+            &[],
+            state_store::load_workspace_versioning,
+            state_store::save_workspace_versioning,
+        ),
+        settings_file(
+            "file_audit",
+            &[],
+            state_store::load_file_audit_config,
+            state_store::save_file_audit_config,
+        ),
+        settings_file(
+            "quiet_hours",
+            &[],
+            state_store::load_quiet_hours,
+            state_store::save_quiet_hours,
+        ),
+        settings_file(
+            "config_watch",
+            &[],
+            state_store::load_config_watch,
+            state_store::save_config_watch,
+        ),
+        settings_file(
+            "security_schedule",
+            &[],
+            state_store::load_security_schedule,
+            state_store::save_security_schedule,
+        ),
+        settings_file(
+            "keep_alive",
+            &[],
+            state_store::load_keep_alive,
+            state_store::save_keep_alive,
+        ),
+        settings_file(
+            "backup_storage",
+            &[],
+            state_store::load_backup_storage,
+            state_store::save_backup_storage,
+        ),
+        settings_file(
+            "shortcuts",
+            &[],
+            state_store::load_shortcuts,
+            state_store::save_shortcuts,
+        ),
+        settings_file(
+            "status_api",
+            &["token"],
+            state_store::load_status_api,
+            state_store::save_status_api,
+        ),
+        settings_file(
+            "log_rotation",
+            &[],
+            state_store::load_log_rotation,
+            state_store::save_log_rotation,
+        ),
+        settings_file(
+            "custom_providers",
+            &[],
+            state_store::load_custom_providers,
+            |records: &Vec<_>| state_store::save_custom_providers(records),
+        ),
+        settings_file(
+            "support_share",
+            &["password", "auth_header", "sealed"],
+            state_store::load_support_share,
+            state_store::save_support_share,
+        ),
+        settings_file(
+            "script_scan",
+            &[],
+            state_store::load_script_scan,
+            state_store::save_script_scan,
+        ),
+        settings_file(
+            "profiles",
+            &["active"],
+            state_store::load_profiles,
+            state_store::save_profiles,
+        ),
+        // A lock without its PIN would lock the user out, so `enabled` stays local too.
+        settings_file(
+            "read_only",
+            &["enabled", "pin_salt", "pin_hash", "enabled_at"],
+            state_store::load_read_only,
+            state_store::save_read_only,
+        ),
+        settings_file(
+            "app_lock",
+            &["enabled", "pin_salt", "pin_hash", "sealed"],
+            state_store::load_app_lock,
+            state_store::save_app_lock,
+        ),
+    ]
+}
+
+/// Exports installer-side preferences only, with every `local` field left out.
+pub fn export_app_settings(path: &str) -> Result<AppSettingsTransferResult> {
+    let target = paths::normalize_path(path)?;
+    let mut settings = Map::new();
+    for file in settings_files() {
+        let mut value = (file.load)()?;
+        if let Some(fields) = value.as_object_mut() {
+            for field in file.local {
+                fields.remove(*field);
+            }
+        }
+        settings.insert(file.name.to_string(), value);
+    }
+    let sections = settings.keys().cloned().collect::<Vec<_>>();
+
+    let bundle = json!({
+        "format": SETTINGS_FORMAT,
+        "version": SETTINGS_VERSION,
+        "exported_at": Local::now().to_rfc3339(),
+        "settings": settings,
+    });
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&target, serde_json::to_string_pretty(&bundle)?)?;
+    logger::info(&format!(
+        "Installer settings exported: {}",
+        target.to_string_lossy()
+    ));
+    Ok(AppSettingsTransferResult {
+        path: target.to_string_lossy().to_string(),
+        sections,
+        warnings: vec![],
+    })
+}
+
+pub fn import_app_settings(path: &str) -> Result<AppSettingsTransferResult> {
+    let source = paths::normalize_path(path)?;
+    let raw = fs::read_to_string(&source)
+        .map_err(|err| anyhow!("Cannot read {}: {err}", source.to_string_lossy()))?;
+    let bundle: Value = serde_json::from_str(&raw)?;
+    let settings = validate_bundle(&bundle)?;
+    let files = settings_files();
+
+    let mut sections = Vec::<String>::new();
+    let mut warnings = Vec::<String>::new();
+    for (name, value) in settings {
+        let Some(file) = files.iter().find(|file| file.name == name) else {
+            warnings.push(format!("Unknown settings section skipped: {name}"));
+            continue;
+        };
+        let applied = (file.load)().and_then(|current| {
+            (file.save)(keep_local_fields(value.clone(), &current, file.local))
+        });
+        match applied {
+            Ok(()) => sections.push(name.clone()),
+            Err(err) => warnings.push(format!("Settings section {name} not imported: {err}")),
+        }
+    }
+
+    logger::info(&format!(
+        "Installer settings imported from {}: [{}]",
+        source.to_string_lossy(),
+        sections.join(", ")
+    ));
+    Ok(AppSettingsTransferResult {
+        path: source.to_string_lossy().to_string(),
+        sections,
+        warnings,
+    })
+}
+
+/// The imported section with its `local` fields taken from this machine's copy.
+fn keep_local_fields(mut imported: Value, current: &Value, local: &[&str]) -> Value {
+    if let Some(fields) = imported.as_object_mut() {
+        for field in local {
+            match current.get(*field) {
+                Some(value) => fields.insert(field.to_string(), value.clone()),
+                None => fields.remove(*field),
+            };
+        }
+    }
+    imported
+}
+
+fn validate_bundle(bundle: &Value) -> Result<&Map<String, Value>> {
+    if bundle.get("format").and_then(|v| v.as_str()) != Some(SETTINGS_FORMAT) {
+        return Err(anyhow!(
+            "File is not an OpenClaw Installer settings export."
+        ));
+    }
+    let version = bundle.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version == 0 || version > SETTINGS_VERSION {
+        return Err(anyhow!(
+            "Unsupported settings export version {version}. Upgrade the installer first."
+        ));
+    }
+    bundle
+        .get("settings")
+        .and_then(|v| v.as_object())
+        .ok_or_else(|| anyhow!("Settings export has no settings section."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{keep_local_fields, settings_files, validate_bundle};
+    use serde_json::json;
+
+    #[test]
+    fn validate_bundle_checks_format_and_version() {
+        let ok = json!({
+            "format": "openclaw-installer-settings",
+            "version": 1,
+            "settings": { "run_prefs": { "keep_running": false } }
+        });
+        assert_eq!(validate_bundle(&ok).expect("valid bundle").len(), 1);
+
+        let foreign = json!({ "format": "something-else", "version": 1, "settings": {} });
+        assert!(validate_bundle(&foreign).is_err());
+
+        let future = json!({
+            "format": "openclaw-installer-settings",
+            "version": 99,
+            "settings": {}
+        });
+        assert!(validate_bundle(&future).is_err());
+    }
+
+    #[test]
+    fn every_settings_file_has_a_unique_section() {
+        let names = settings_files()
+            .iter()
+            .map(|file| file.name)
+            .collect::<Vec<_>>();
+        for name in ["config_watch", "status_api", "profiles", "app_lock"] {
+            assert!(names.contains(&name), "{name} missing");
+        }
+        let mut unique = names.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), names.len());
+    }
+
+    #[test]
+    fn import_keeps_local_fields_from_this_machine() {
+        let imported = json!({ "enabled": true, "port": 28800, "token": "from-export" });
+        let current = json!({ "enabled": false, "port": 28790, "token": "local" });
+        let merged = keep_local_fields(imported, &current, &["token"]);
+        assert_eq!(
+            merged,
+            json!({ "enabled": true, "port": 28800, "token": "local" })
+        );
+
+        let merged = keep_local_fields(json!({ "active": "work" }), &json!({}), &["active"]);
+        assert_eq!(merged, json!({}));
+    }
+}
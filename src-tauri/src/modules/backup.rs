@@ -1,45 +1,272 @@
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Result};
 use chrono::Local;
-use uuid::Uuid;
-use walkdir::WalkDir;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
 use zip::write::SimpleFileOptions;
 use zip::{CompressionMethod, ZipArchive, ZipWriter};
 
-use crate::models::{BackupInfo, BackupResult, RollbackResult};
+use crate::models::{
+    BackupCategory, BackupInfo, BackupResult, BackupStorageConfig, BackupStorageResult,
+    BackupVerifyResult, RollbackResult,
+};
 
-use super::{logger, paths};
+use super::{fs_walk, logger, paths, state_store, temp_files};
 
-pub fn backup() -> Result<BackupResult> {
-    let info = backup_with_prefix("manual")?;
-    Ok(BackupResult { backup: info })
+const BACKUP_MANIFEST_NAME: &str = "backup_manifest.json";
+const BYTES_PER_MB: u64 = 1024 * 1024;
+const ZIP64_ENTRY_THRESHOLD: u64 = u32::MAX as u64;
+
+/// Archive being written, with the per-file size limit, exclusion patterns and the
+/// entries it left out.
+struct BackupWriter {
+    zip: ZipWriter<File>,
+    options: SimpleFileOptions,
+    max_file_bytes: Option<u64>,
+    exclude: Vec<String>,
+    excluded: usize,
+    skipped: Vec<String>,
+    /// SHA-256 per archived file, written to the manifest for `verify_backup`.
+    checksums: BTreeMap<String, String>,
+    /// Files archived with substitute content instead of what is on disk; `None` leaves
+    /// the file out.
+    overrides: BTreeMap<PathBuf, Option<Vec<u8>>>,
+}
+
+impl BackupWriter {
+    fn new(file: File, storage: &BackupStorageConfig) -> Self {
+        Self {
+            zip: ZipWriter::new(file),
+            options: SimpleFileOptions::default().compression_method(CompressionMethod::Deflated),
+            max_file_bytes: (storage.max_file_mb > 0).then(|| storage.max_file_mb * BYTES_PER_MB),
+            exclude: storage
+                .exclude_patterns
+                .iter()
+                .map(|p| p.trim().trim_matches('/').replace('\\', "/"))
+                .filter(|p| !p.is_empty())
+                .collect(),
+            excluded: 0,
+            skipped: vec![],
+            checksums: BTreeMap::new(),
+            overrides: BTreeMap::new(),
+        }
+    }
+
+    /// Archives `last_config.json` without its plaintext credentials.
+    fn strip_setup_secrets(&mut self) {
+        match state_store::last_config_for_backup() {
+            Ok(Some((path, stripped))) => {
+                self.overrides.insert(path, Some(stripped));
+            }
+            Ok(None) => {}
+            Err(err) => {
+                logger::warn(&format!(
+                    "Saved setup left out of the backup; it could not be read: {err}"
+                ));
+                self.overrides
+                    .insert(paths::state_dir().join("last_config.json"), None);
+            }
+        }
+    }
+
+    /// Writes the manifest, recording skipped entries so a restore can explain gaps.
+    fn finish(mut self, category: BackupCategory) -> Result<Vec<String>> {
+        self.zip.start_file(BACKUP_MANIFEST_NAME, self.options)?;
+        self.zip.write_all(
+            serde_json::json!({
+                "category": category,
+                "skipped": self.skipped,
+                "excluded_patterns": self.exclude,
+                "checksums": self.checksums,
+            })
+            .to_string()
+            .as_bytes(),
+        )?;
+        self.zip.finish()?;
+        Ok(self.skipped)
+    }
+}
+
+/// `rel` uses `/` separators and is relative to the folder being backed up.
+fn is_excluded(rel: &str, patterns: &[String]) -> bool {
+    patterns.iter().any(|pattern| {
+        if pattern.contains('/') {
+            rel.eq_ignore_ascii_case(pattern)
+                || rel
+                    .to_ascii_lowercase()
+                    .starts_with(&format!("{}/", pattern.to_ascii_lowercase()))
+        } else {
+            rel.split('/')
+                .any(|segment| wildcard_match(pattern, segment))
+        }
+    })
+}
+
+/// Case-insensitive match where `*` stands for any run of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_ascii_lowercase();
+    let text = text.to_ascii_lowercase();
+    let parts = pattern.split('*').collect::<Vec<_>>();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if text.len() < first.len() + last.len() || !text.starts_with(first) || !text.ends_with(last) {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// What a backup category contains. Paths are relative to the OpenClaw home.
+struct CategorySpec {
+    home_paths: &'static [&'static str],
+    installer_state: bool,
+}
+
+fn category_spec(category: BackupCategory) -> Option<CategorySpec> {
+    match category {
+        BackupCategory::Config => Some(CategorySpec {
+            home_paths: &["openclaw.json"],
+            installer_state: true,
+        }),
+        BackupCategory::Secrets => Some(CategorySpec {
+            home_paths: &[".env", "credentials"],
+            installer_state: false,
+        }),
+        BackupCategory::Sessions => Some(CategorySpec {
+            home_paths: &["sessions", "memory", "agents"],
+            installer_state: false,
+        }),
+        BackupCategory::Workspace => Some(CategorySpec {
+            home_paths: &["workspace"],
+            installer_state: false,
+        }),
+        // Full backups snapshot the whole OpenClaw home plus installer state.
+        BackupCategory::Full => None,
+    }
+}
+
+fn category_label(category: BackupCategory) -> &'static str {
+    match category {
+        BackupCategory::Config => "config",
+        BackupCategory::Secrets => "secrets",
+        BackupCategory::Sessions => "sessions",
+        BackupCategory::Workspace => "workspace",
+        BackupCategory::Full => "full",
+    }
+}
+
+/// Full backup. `include_excluded` ignores the exclusion patterns, e.g. to capture
+/// `node_modules` and other binaries for an offline restore.
+pub fn backup(include_excluded: bool) -> Result<BackupResult> {
+    manual_backup(BackupCategory::Full, include_excluded)
+}
+
+pub fn backup_category(category: BackupCategory) -> Result<BackupResult> {
+    manual_backup(category, false)
+}
+
+fn manual_backup(category: BackupCategory, include_excluded: bool) -> Result<BackupResult> {
+    let (info, skipped) = write_backup("manual", category, include_excluded)?;
+    Ok(BackupResult {
+        backup: info,
+        warnings: skipped
+            .into_iter()
+            .map(|entry| format!("Skipped {entry}"))
+            .collect(),
+    })
 }
 
 pub fn backup_with_prefix(prefix: &str) -> Result<BackupInfo> {
+    backup_category_with_prefix(prefix, BackupCategory::Full)
+}
+
+pub fn backup_category_with_prefix(prefix: &str, category: BackupCategory) -> Result<BackupInfo> {
+    write_backup(prefix, category, false).map(|(info, _)| info)
+}
+
+/// Writes the archive and returns it with the entries left out by the per-file limit.
+fn write_backup(
+    prefix: &str,
+    category: BackupCategory,
+    include_excluded: bool,
+) -> Result<(BackupInfo, Vec<String>)> {
     paths::ensure_dirs()?;
-    let id = format!("{}-{}", prefix, Local::now().format("%Y%m%d-%H%M%S"));
+    let stamp = Local::now().format("%Y%m%d-%H%M%S");
+    // Keep the legacy `<prefix>-<timestamp>` id for full backups so existing tooling still matches.
+    let id = match category {
+        BackupCategory::Full => format!("{prefix}-{stamp}"),
+        other => format!("{prefix}-{}-{stamp}", category_label(other)),
+    };
     let zip_path = paths::backups_dir().join(format!("{id}.zip"));
-    let file = File::create(&zip_path)?;
-    let mut zip = ZipWriter::new(file);
-    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut storage = state_store::load_backup_storage().unwrap_or_default();
+    if include_excluded {
+        storage.exclude_patterns.clear();
+    }
+    let mut writer = BackupWriter::new(File::create(&zip_path)?, &storage);
 
-    // Backup includes OpenClaw runtime data + installer state for full rollback.
-    add_folder_to_zip(&mut zip, &paths::openclaw_home(), "openclaw_home", options)?;
-    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
-    add_folder_to_zip(&mut zip, &paths::state_dir(), "installer_state", options)?;
-    zip.finish()?;
+    match category_spec(category) {
+        None => {
+            // Backup includes OpenClaw runtime data + installer state for full rollback.
+            add_folder_to_zip(&mut writer, &paths::openclaw_home(), "openclaw_home")?;
+            writer.strip_setup_secrets();
+            add_folder_to_zip(&mut writer, &paths::state_dir(), "installer_state")?;
+        }
+        Some(spec) => {
+            let home = paths::openclaw_home();
+            for rel in spec.home_paths {
+                add_path_to_zip(
+                    &mut writer,
+                    &home.join(rel),
+                    &format!("openclaw_home/{rel}"),
+                )?;
+            }
+            if spec.installer_state {
+                writer.strip_setup_secrets();
+                add_folder_to_zip(&mut writer, &paths::state_dir(), "installer_state")?;
+            }
+        }
+    }
+    if writer.excluded > 0 {
+        logger::info(&format!(
+            "Backup {id} excluded {} entries matching: {}",
+            writer.excluded,
+            writer.exclude.join(", ")
+        ));
+    }
+    let skipped = writer.finish(category)?;
+    for entry in &skipped {
+        logger::warn(&format!("Backup {id} skipped {entry}"));
+    }
 
     let size = fs::metadata(&zip_path)?.len();
-    logger::info(&format!("Backup created: {}", zip_path.to_string_lossy()));
-    Ok(BackupInfo {
+    logger::info(&format!(
+        "Backup created ({}): {}",
+        category_label(category),
+        zip_path.to_string_lossy()
+    ));
+    if let Err(err) = enforce_size_cap() {
+        logger::warn(&format!("Backup size cap could not be applied: {err}"));
+    }
+    let info = BackupInfo {
         id,
         path: zip_path.to_string_lossy().to_string(),
         created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
         size,
-    })
+        category,
+    };
+    Ok((info, skipped))
 }
 
 pub fn list_backups() -> Result<Vec<BackupInfo>> {
@@ -73,6 +300,7 @@ pub fn list_backups() -> Result<Vec<BackupInfo>> {
             .unwrap_or_else(|| "-".to_string());
         out.push(BackupInfo {
             id,
+            category: read_backup_category(&path),
             path: path.to_string_lossy().to_string(),
             created_at: created,
             size: metadata.len(),
@@ -82,6 +310,159 @@ pub fn list_backups() -> Result<Vec<BackupInfo>> {
     Ok(out)
 }
 
+/// Moves existing archives to the new location (empty = default) and applies the size
+/// cap right away, so lowering it frees space without waiting for the next backup.
+pub fn configure_backup_storage(cfg: &BackupStorageConfig) -> Result<BackupStorageResult> {
+    let mut cfg = cfg.clone();
+    let old_dir = paths::backups_dir();
+    let new_dir = match cfg.location.trim() {
+        "" => paths::default_backups_dir(),
+        raw => paths::normalize_path(raw)?,
+    };
+    validate_backups_location(&new_dir)?;
+    cfg.location = if new_dir == paths::default_backups_dir() {
+        String::new()
+    } else {
+        new_dir.to_string_lossy().to_string()
+    };
+
+    let moved = if same_dir(&old_dir, &new_dir) {
+        0
+    } else {
+        move_archives(&old_dir, &new_dir)?
+    };
+    state_store::save_backup_storage(&cfg)?;
+    let pruned = enforce_size_cap()?;
+    let total_bytes = list_backups()?.iter().map(|b| b.size).sum();
+    logger::info(&format!(
+        "Backup storage set to {} (cap {} MB, moved {moved}, pruned {}).",
+        new_dir.to_string_lossy(),
+        cfg.max_total_mb,
+        pruned.len()
+    ));
+    Ok(BackupStorageResult {
+        config: cfg,
+        dir: new_dir.to_string_lossy().to_string(),
+        moved,
+        pruned,
+        total_bytes,
+    })
+}
+
+/// Deletes the oldest archives until the total fits the configured cap. The newest
+/// archive is always kept, even when it alone exceeds the cap.
+pub fn enforce_size_cap() -> Result<Vec<String>> {
+    let cap_mb = state_store::load_backup_storage()?.max_total_mb;
+    if cap_mb == 0 {
+        return Ok(vec![]);
+    }
+    let backups = list_backups()?;
+    let mut pruned = Vec::new();
+    for backup in backups_over_cap(&backups, cap_mb * 1024 * 1024) {
+        match fs::remove_file(&backup.path) {
+            Ok(()) => pruned.push(backup.id.clone()),
+            Err(err) => logger::warn(&format!("Failed to prune backup {}: {err}", backup.path)),
+        }
+    }
+    if !pruned.is_empty() {
+        logger::info(&format!(
+            "Pruned {} backup(s) to stay under {cap_mb} MB: {}",
+            pruned.len(),
+            pruned.join(", ")
+        ));
+    }
+    Ok(pruned)
+}
+
+/// `backups` is newest first, as returned by `list_backups`.
+fn backups_over_cap(backups: &[BackupInfo], cap_bytes: u64) -> Vec<&BackupInfo> {
+    let mut total = 0u64;
+    backups
+        .iter()
+        .enumerate()
+        .filter(|(index, backup)| {
+            total += backup.size;
+            *index > 0 && total > cap_bytes
+        })
+        .map(|(_, backup)| backup)
+        .collect()
+}
+
+fn validate_backups_location(dir: &Path) -> Result<()> {
+    if !dir.is_absolute() {
+        return Err(anyhow!(
+            "Backup location must be an absolute path: {}",
+            dir.to_string_lossy()
+        ));
+    }
+    // Full backups zip these folders; a backups dir inside them would snapshot itself.
+    for (label, root) in [
+        ("the OpenClaw home", paths::openclaw_home()),
+        ("installer state", paths::state_dir()),
+    ] {
+        if dir.starts_with(&root) {
+            return Err(anyhow!(
+                "Backup location cannot be inside {label} ({}).",
+                root.to_string_lossy()
+            ));
+        }
+    }
+    fs::create_dir_all(dir).map_err(|err| {
+        anyhow!(
+            "Cannot create backup location {}: {err}",
+            dir.to_string_lossy()
+        )
+    })?;
+    let probe = dir.join(".openclaw-write-test");
+    fs::write(&probe, b"ok").map_err(|err| {
+        anyhow!(
+            "Backup location {} is not writable: {err}",
+            dir.to_string_lossy()
+        )
+    })?;
+    let _ = fs::remove_file(probe);
+    Ok(())
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Moves `*.zip` archives between folders; falls back to copy + delete across volumes.
+fn move_archives(from: &Path, to: &Path) -> Result<usize> {
+    let Ok(entries) = fs::read_dir(from) else {
+        return Ok(0);
+    };
+    let mut moved = 0usize;
+    for entry in entries.flatten() {
+        let src = entry.path();
+        let is_zip = src
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("zip"))
+            .unwrap_or(false);
+        if !src.is_file() || !is_zip {
+            continue;
+        }
+        let dst = to.join(entry.file_name());
+        if dst.exists() {
+            logger::warn(&format!(
+                "Backup {} already exists at the new location; keeping the old copy.",
+                dst.to_string_lossy()
+            ));
+            continue;
+        }
+        if fs::rename(&src, &dst).is_err() {
+            fs::copy(&src, &dst)?;
+            fs::remove_file(&src)?;
+        }
+        moved += 1;
+    }
+    Ok(moved)
+}
+
 pub fn rollback(backup_id: &str) -> Result<RollbackResult> {
     // Safety guard: always snapshot current state before restore.
     let auto = backup_with_prefix("pre-rollback")?;
@@ -93,21 +474,91 @@ pub fn rollback(backup_id: &str) -> Result<RollbackResult> {
     })
 }
 
+/// Restores only one category from any backup that contains it, e.g. conversations
+/// from a full pre-upgrade snapshot without rolling back the installed version.
+pub fn restore_category(backup_id: &str, category: BackupCategory) -> Result<RollbackResult> {
+    if category == BackupCategory::Full {
+        return rollback(backup_id);
+    }
+    let spec = category_spec(category).ok_or_else(|| anyhow!("Unsupported backup category"))?;
+    let backup_file = resolve_backup_path(backup_id)?;
+    let auto = backup_category_with_prefix("pre-restore", category)?;
+
+    let temp_dir = temp_files::create_dir(temp_files::RESTORE_PREFIX)?;
+    let result = (|| -> Result<usize> {
+        extract_zip(&backup_file, &temp_dir)?;
+        let mut restored = restore_home_paths(&temp_dir, &spec, &paths::openclaw_home())?;
+        if spec.installer_state {
+            let src = temp_dir.join("installer_state");
+            if src.exists() {
+                restore_installer_state(&src)?;
+                restored += 1;
+            }
+        }
+        Ok(restored)
+    })();
+    temp_files::release(&temp_dir);
+    let restored = result?;
+    if restored == 0 {
+        return Err(anyhow!(
+            "Backup {backup_id} does not contain any {} data.",
+            category_label(category)
+        ));
+    }
+
+    logger::warn(&format!(
+        "Restored {} data from backup {backup_id}.",
+        category_label(category)
+    ));
+    Ok(RollbackResult {
+        from_backup: backup_id.to_string(),
+        auto_backup: auto,
+    })
+}
+
 pub fn restore_backup(backup_id_or_path: &str) -> Result<()> {
     let backup_file = resolve_backup_path(backup_id_or_path)?;
-    let temp_dir = std::env::temp_dir().join(format!("openclaw-restore-{}", Uuid::new_v4()));
-    fs::create_dir_all(&temp_dir)?;
-    extract_zip(&backup_file, &temp_dir)?;
+    let temp_dir = temp_files::create_dir(temp_files::RESTORE_PREFIX)?;
+    let result = (|| -> Result<()> {
+        extract_zip(&backup_file, &temp_dir)?;
+        let restored_home = temp_dir.join("openclaw_home");
+        if restored_home.exists() {
+            copy_dir_overwrite(&restored_home, &paths::openclaw_home())?;
+        }
+        let restored_state = temp_dir.join("installer_state");
+        if restored_state.exists() {
+            restore_installer_state(&restored_state)?;
+        }
+        Ok(())
+    })();
+    temp_files::release(&temp_dir);
+    result
+}
 
-    let restored_home = temp_dir.join("openclaw_home");
-    if restored_home.exists() {
-        copy_dir_overwrite(&restored_home, &paths::openclaw_home())?;
+/// Overlays the category's OpenClaw home paths from an extracted backup; returns how
+/// many of them the backup contained.
+fn restore_home_paths(extracted: &Path, spec: &CategorySpec, home: &Path) -> Result<usize> {
+    let mut restored = 0usize;
+    for rel in spec.home_paths {
+        let src = extracted.join("openclaw_home").join(rel);
+        if !src.exists() {
+            continue;
+        }
+        overlay_path(&src, &home.join(rel))?;
+        restored += 1;
     }
-    let restored_state = temp_dir.join("installer_state");
-    if restored_state.exists() {
-        copy_dir_overwrite(&restored_state, &paths::state_dir())?;
+    Ok(restored)
+}
+
+/// Backups carry the setup payload without plaintext credentials, so the live ones
+/// are kept for every field the restored copy leaves empty.
+fn restore_installer_state(src: &Path) -> Result<()> {
+    let live = state_store::load_last_config().ok().flatten();
+    copy_dir_overwrite(src, &paths::state_dir())?;
+    if let (Some(live), Some(mut restored)) = (live, state_store::load_last_config()?) {
+        state_store::keep_missing_secrets(&mut restored, &live);
+        state_store::save_last_config(&restored)?;
     }
-    let _ = fs::remove_dir_all(temp_dir);
     Ok(())
 }
 
@@ -123,36 +574,188 @@ fn resolve_backup_path(value: &str) -> Result<PathBuf> {
     Err(anyhow!("Backup not found: {value}"))
 }
 
-fn add_folder_to_zip(
-    zip: &mut ZipWriter<File>,
-    folder: &Path,
-    prefix: &str,
-    options: SimpleFileOptions,
-) -> Result<()> {
+/// Re-hashes every archived file listed in the manifest, reading entries in parallel.
+pub fn verify_backup(backup_id_or_path: &str) -> Result<BackupVerifyResult> {
+    let archive_file = resolve_backup_path(backup_id_or_path)?;
+    let checksums: BTreeMap<String, String> = read_manifest(&archive_file)
+        .ok()
+        .and_then(|manifest| serde_json::from_value(manifest["checksums"].clone()).ok())
+        .unwrap_or_default();
+    let outcomes = checksums
+        .par_iter()
+        .map_init(
+            || {
+                File::open(&archive_file)
+                    .ok()
+                    .and_then(|f| ZipArchive::new(f).ok())
+            },
+            |archive, (name, expected)| {
+                let Some(archive) = archive.as_mut() else {
+                    return (name.clone(), None);
+                };
+                let Ok(mut entry) = archive.by_name(name) else {
+                    return (name.clone(), None);
+                };
+                let mut hasher = Sha256::new();
+                let matches = std::io::copy(&mut entry, &mut hasher).is_ok()
+                    && fs_walk::hex_digest(hasher) == *expected;
+                (name.clone(), Some(matches))
+            },
+        )
+        .collect::<Vec<_>>();
+    let mut result = BackupVerifyResult {
+        backup_id: archive_file
+            .file_stem()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        has_checksums: !checksums.is_empty(),
+        checked: outcomes.len(),
+        mismatched: vec![],
+        missing: vec![],
+    };
+    for (name, outcome) in outcomes {
+        match outcome {
+            None => result.missing.push(name),
+            Some(false) => result.mismatched.push(name),
+            Some(true) => {}
+        }
+    }
+    logger::info(&format!(
+        "Backup {} verified: {} checked, {} mismatched, {} missing.",
+        result.backup_id,
+        result.checked,
+        result.mismatched.len(),
+        result.missing.len()
+    ));
+    Ok(result)
+}
+
+fn read_manifest(archive_file: &Path) -> Result<serde_json::Value> {
+    let mut archive = ZipArchive::new(File::open(archive_file)?)?;
+    let mut manifest = archive.by_name(BACKUP_MANIFEST_NAME)?;
+    let mut raw = String::new();
+    manifest.read_to_string(&mut raw)?;
+    Ok(serde_json::from_str(&raw)?)
+}
+
+fn read_backup_category(archive_file: &Path) -> BackupCategory {
+    // Backups created before categories existed have no manifest and are full snapshots.
+    read_manifest(archive_file)
+        .ok()
+        .and_then(|manifest| serde_json::from_value(manifest["category"].clone()).ok())
+        .unwrap_or(BackupCategory::Full)
+}
+
+fn add_path_to_zip(writer: &mut BackupWriter, path: &Path, zip_name: &str) -> Result<()> {
+    if paths::is_reparse_point(path) {
+        warn_skipped_links("backup", &[path.to_path_buf()]);
+        return Ok(());
+    }
+    if path.is_dir() {
+        return add_folder_to_zip(writer, path, zip_name);
+    }
+    if !path.is_file() {
+        return Ok(());
+    }
+    let sha256 = fs_walk::sha256_file(path).ok();
+    add_file_to_zip(writer, path, zip_name.to_string(), sha256)
+}
+
+/// Copies the restored files over the live ones without deleting anything first: a
+/// backup can leave out excluded or oversized files, and those must survive a restore.
+fn overlay_path(src: &Path, dst: &Path) -> Result<()> {
+    if src.is_dir() {
+        copy_dir_overwrite(src, dst)
+    } else {
+        if let Some(parent) = dst.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(src, dst)?;
+        Ok(())
+    }
+}
+
+fn add_folder_to_zip(writer: &mut BackupWriter, folder: &Path, prefix: &str) -> Result<()> {
     if !folder.exists() {
         return Ok(());
     }
-    for entry in WalkDir::new(folder).into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path();
-        let rel = path.strip_prefix(folder)?;
-        if rel.as_os_str().is_empty() {
+    let rel_of = |path: &Path| {
+        path.strip_prefix(folder)
+            .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default()
+    };
+    let (exclude, max_file_bytes) = (writer.exclude.clone(), writer.max_file_bytes);
+    // Files that will be archived are hashed in parallel up front for the manifest.
+    let walk = fs_walk::Walker::new(folder)
+        .hash_if(|file| {
+            max_file_bytes.is_none_or(|limit| file.len <= limit)
+                && !is_excluded(&rel_of(&file.path), &exclude)
+        })
+        .run();
+    warn_skipped_links("backup", &walk.skipped_links);
+    for dir in &walk.dirs {
+        let rel = rel_of(dir);
+        if is_excluded(&rel, &writer.exclude) {
+            writer.excluded += 1;
             continue;
         }
-        let zip_name = format!("{prefix}/{}", rel.to_string_lossy().replace('\\', "/"));
-        if path.is_dir() {
-            zip.add_directory(zip_name, options)?;
+        writer
+            .zip
+            .add_directory(format!("{prefix}/{rel}"), writer.options)?;
+    }
+    for file in walk.files {
+        let rel = rel_of(&file.path);
+        if is_excluded(&rel, &writer.exclude) {
+            writer.excluded += 1;
             continue;
         }
-        zip.start_file(zip_name, options)?;
-        let mut file = File::open(path)?;
-        let mut buffer = Vec::new();
-        file.read_to_end(&mut buffer)?;
-        zip.write_all(&buffer)?;
+        let zip_name = format!("{prefix}/{rel}");
+        match writer.overrides.remove(&file.path) {
+            Some(Some(content)) => add_bytes_to_zip(writer, zip_name, &content)?,
+            Some(None) => {}
+            None => add_file_to_zip(writer, &file.path, zip_name, file.sha256)?,
+        }
     }
     Ok(())
 }
 
-fn extract_zip(archive_file: &Path, destination: &Path) -> Result<()> {
+fn add_bytes_to_zip(writer: &mut BackupWriter, zip_name: String, content: &[u8]) -> Result<()> {
+    writer.zip.start_file(zip_name.as_str(), writer.options)?;
+    writer.zip.write_all(content)?;
+    let sha256 = fs_walk::hex_digest(Sha256::new_with_prefix(content));
+    writer.checksums.insert(zip_name, sha256);
+    Ok(())
+}
+
+/// Streams one file into the archive instead of buffering it, so multi-GB session or
+/// cache files do not have to fit in memory.
+fn add_file_to_zip(
+    writer: &mut BackupWriter,
+    path: &Path,
+    zip_name: String,
+    sha256: Option<String>,
+) -> Result<()> {
+    let mut file = File::open(paths::long_path(path))?;
+    let size = file.metadata()?.len();
+    if let Some(limit) = writer.max_file_bytes.filter(|limit| size > *limit) {
+        writer.skipped.push(format!(
+            "{zip_name} ({} MB, over the {} MB per-file limit)",
+            size / BYTES_PER_MB,
+            limit / BYTES_PER_MB
+        ));
+        return Ok(());
+    }
+    // Entries past 4 GB need zip64 headers; smaller ones keep the classic layout.
+    let options = writer.options.large_file(size >= ZIP64_ENTRY_THRESHOLD);
+    writer.zip.start_file(zip_name.as_str(), options)?;
+    std::io::copy(&mut file, &mut writer.zip)?;
+    if let Some(sha256) = sha256 {
+        writer.checksums.insert(zip_name, sha256);
+    }
+    Ok(())
+}
+
+pub fn extract_zip(archive_file: &Path, destination: &Path) -> Result<()> {
     let file = File::open(archive_file)?;
     let mut archive = ZipArchive::new(file)?;
     for i in 0..archive.len() {
@@ -175,23 +778,211 @@ fn extract_zip(archive_file: &Path, destination: &Path) -> Result<()> {
     Ok(())
 }
 
-fn copy_dir_overwrite(src: &Path, dst: &Path) -> Result<()> {
+fn warn_skipped_links(operation: &str, links: &[PathBuf]) {
+    for link in links {
+        logger::warn(&format!(
+            "Skipped junction/symlink during {operation}: {}",
+            link.to_string_lossy()
+        ));
+    }
+}
+
+pub fn copy_dir_overwrite(src: &Path, dst: &Path) -> Result<()> {
     fs::create_dir_all(dst)?;
-    for entry in WalkDir::new(src).into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path();
-        let rel = path.strip_prefix(src)?;
-        if rel.as_os_str().is_empty() {
-            continue;
-        }
-        let target = dst.join(rel);
-        if path.is_dir() {
-            fs::create_dir_all(&target)?;
-            continue;
+    let walk = fs_walk::Walker::new(src).run();
+    warn_skipped_links("copy", &walk.skipped_links);
+    for dir in &walk.dirs {
+        fs::create_dir_all(dst.join(dir.strip_prefix(src)?))?;
+    }
+    walk.files.par_iter().try_for_each(|file| -> Result<()> {
+        let target = dst.join(file.path.strip_prefix(src)?);
+        fs::copy(paths::long_path(&file.path), paths::long_path(&target))?;
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_folder_to_zip, backups_over_cap, category_spec, is_excluded, overlay_path,
+        read_backup_category, read_manifest, restore_home_paths, BackupWriter,
+        BACKUP_MANIFEST_NAME,
+    };
+    use crate::models::{BackupCategory, BackupInfo, BackupStorageConfig};
+    use std::fs::{self, File};
+    use zip::ZipArchive;
+
+    fn info(id: &str, size: u64) -> BackupInfo {
+        BackupInfo {
+            id: id.to_string(),
+            path: format!("{id}.zip"),
+            created_at: String::new(),
+            size,
+            category: BackupCategory::Full,
         }
-        if let Some(parent) = target.parent() {
-            fs::create_dir_all(parent)?;
+    }
+
+    #[test]
+    fn backups_over_cap_prunes_oldest_and_keeps_newest() {
+        let list = vec![info("new", 60), info("mid", 30), info("old", 20)];
+        let ids = |cap| {
+            backups_over_cap(&list, cap)
+                .iter()
+                .map(|b| b.id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(100), vec!["old"]);
+        assert_eq!(ids(200), Vec::<String>::new());
+        assert_eq!(ids(10), vec!["mid", "old"]);
+    }
+
+    #[test]
+    fn backup_writer_streams_files_and_skips_oversized_ones() {
+        let root = std::env::temp_dir().join(format!("oc-backup-writer-{}", std::process::id()));
+        let source = root.join("home");
+        fs::create_dir_all(source.join("sessions")).expect("dirs");
+        fs::write(source.join("openclaw.json"), "{}").expect("small");
+        fs::write(source.join("sessions").join("huge.jsonl"), "0123456789").expect("big");
+
+        let archive_path = root.join("out.zip");
+        let mut writer = BackupWriter::new(
+            File::create(&archive_path).expect("zip"),
+            &BackupStorageConfig::default(),
+        );
+        writer.max_file_bytes = Some(4);
+        add_folder_to_zip(&mut writer, &source, "openclaw_home").expect("add");
+        let skipped = writer.finish(BackupCategory::Full).expect("finish");
+
+        assert_eq!(skipped.len(), 1);
+        assert!(skipped[0].starts_with("openclaw_home/sessions/huge.jsonl"));
+        let mut archive = ZipArchive::new(File::open(&archive_path).expect("open")).expect("read");
+        assert!(archive.by_name("openclaw_home/openclaw.json").is_ok());
+        assert!(archive
+            .by_name("openclaw_home/sessions/huge.jsonl")
+            .is_err());
+        assert!(archive.by_name(BACKUP_MANIFEST_NAME).is_ok());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn category_specs_keep_secrets_out_of_other_categories() {
+        let config = category_spec(BackupCategory::Config).expect("config");
+        assert_eq!(config.home_paths, ["openclaw.json"]);
+        assert!(config.installer_state);
+        let secrets = category_spec(BackupCategory::Secrets).expect("secrets");
+        assert!(!secrets.installer_state);
+        for category in [
+            BackupCategory::Config,
+            BackupCategory::Sessions,
+            BackupCategory::Workspace,
+        ] {
+            let spec = category_spec(category).expect("spec");
+            assert!(spec
+                .home_paths
+                .iter()
+                .all(|path| !secrets.home_paths.contains(path)));
         }
-        fs::copy(path, target)?;
+        assert!(category_spec(BackupCategory::Full).is_none());
+    }
+
+    #[test]
+    fn manifest_records_category_and_checksums() {
+        let root = std::env::temp_dir().join(format!("oc-backup-manifest-{}", std::process::id()));
+        let source = root.join("home");
+        fs::create_dir_all(&source).expect("dirs");
+        fs::write(source.join("openclaw.json"), "{}").expect("config");
+        fs::write(source.join("last_config.json"), "{\"api_key\":\"sk\"}").expect("setup");
+
+        let archive_path = root.join("out.zip");
+        let mut writer = BackupWriter::new(
+            File::create(&archive_path).expect("zip"),
+            &BackupStorageConfig::default(),
+        );
+        writer.overrides.insert(
+            source.join("last_config.json"),
+            Some(b"{\"api_key\":\"\"}".to_vec()),
+        );
+        add_folder_to_zip(&mut writer, &source, "installer_state").expect("add");
+        writer.finish(BackupCategory::Config).expect("finish");
+
+        assert_eq!(read_backup_category(&archive_path), BackupCategory::Config);
+        let manifest = read_manifest(&archive_path).expect("manifest");
+        assert!(manifest["checksums"]["installer_state/openclaw.json"].is_string());
+        assert!(manifest["checksums"]["installer_state/last_config.json"].is_string());
+        let mut archive = ZipArchive::new(File::open(&archive_path).expect("open")).expect("read");
+        let mut setup = String::new();
+        std::io::Read::read_to_string(
+            &mut archive
+                .by_name("installer_state/last_config.json")
+                .expect("setup entry"),
+            &mut setup,
+        )
+        .expect("read setup");
+        assert!(!setup.contains("sk"));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn restore_category_only_touches_its_own_paths() {
+        let root = std::env::temp_dir().join(format!("oc-backup-category-{}", std::process::id()));
+        let (extracted, home) = (root.join("extracted"), root.join("home"));
+        let backed_up = extracted.join("openclaw_home");
+        fs::create_dir_all(backed_up.join("sessions")).expect("sessions");
+        fs::create_dir_all(backed_up.join("workspace")).expect("workspace");
+        fs::write(backed_up.join("sessions").join("main.jsonl"), "old chat").expect("chat");
+        fs::write(backed_up.join("workspace").join("notes.md"), "old notes").expect("notes");
+        fs::create_dir_all(home.join("workspace")).expect("live workspace");
+        fs::write(home.join("workspace").join("notes.md"), "new notes").expect("live notes");
+
+        let spec = category_spec(BackupCategory::Sessions).expect("spec");
+        assert_eq!(
+            restore_home_paths(&extracted, &spec, &home).expect("restore"),
+            1
+        );
+
+        assert_eq!(
+            fs::read_to_string(home.join("sessions").join("main.jsonl")).expect("chat"),
+            "old chat"
+        );
+        assert_eq!(
+            fs::read_to_string(home.join("workspace").join("notes.md")).expect("notes"),
+            "new notes"
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn restore_overlays_files_and_keeps_ones_the_backup_left_out() {
+        let root = std::env::temp_dir().join(format!("oc-backup-overlay-{}", std::process::id()));
+        let (restored, live) = (root.join("restored"), root.join("live"));
+        fs::create_dir_all(&restored).expect("restored dir");
+        fs::create_dir_all(live.join("node_modules")).expect("live dir");
+        fs::write(restored.join("main.jsonl"), "from backup").expect("restored file");
+        fs::write(live.join("main.jsonl"), "live").expect("live file");
+        fs::write(live.join("node_modules").join("big.bin"), "excluded").expect("excluded");
+
+        overlay_path(&restored, &live).expect("overlay");
+
+        assert_eq!(
+            fs::read_to_string(live.join("main.jsonl")).expect("read"),
+            "from backup"
+        );
+        assert!(live.join("node_modules").join("big.bin").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn is_excluded_matches_segments_wildcards_and_relative_paths() {
+        let patterns = ["node_modules", "*.log", "workspace/tmp"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>();
+        assert!(is_excluded("node_modules", &patterns));
+        assert!(is_excluded("extensions/foo/Node_Modules/x.js", &patterns));
+        assert!(is_excluded("logs/gateway.log", &patterns));
+        assert!(is_excluded("workspace/tmp/a.txt", &patterns));
+        assert!(!is_excluded("workspace/tmpfile", &patterns));
+        assert!(!is_excluded("sessions/main.jsonl", &patterns));
+        assert!(!is_excluded("logbook.md", &patterns));
     }
-    Ok(())
 }
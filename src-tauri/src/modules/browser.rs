@@ -4,7 +4,7 @@ use anyhow::{anyhow, Result};
 use serde_json::Value;
 use url::Url;
 
-use super::{logger, paths, shell, state_store};
+use super::{cli_shim, config, logger, paths, shell, state_store, tls};
 
 pub fn open_management_url(url: &str) -> Result<String> {
     let parsed = Url::parse(url).map_err(|err| anyhow!("Invalid URL '{url}': {err}"))?;
@@ -23,6 +23,25 @@ pub fn open_management_url(url: &str) -> Result<String> {
     Ok(masked)
 }
 
+/// Opens the local dashboard for the configured gateway port (tray and hotkey entry).
+pub fn open_dashboard() -> Result<String> {
+    let port = config::read_current_config()?.port;
+    open_management_url(&format!("{}://127.0.0.1:{port}/", tls::gateway_scheme()))
+}
+
+/// Opens a third-party https page (provider consoles, docs) without attaching any
+/// gateway credentials.
+pub fn open_external_url(url: &str) -> Result<String> {
+    let parsed = Url::parse(url).map_err(|err| anyhow!("Invalid URL '{url}': {err}"))?;
+    if parsed.scheme() != "https" {
+        return Err(anyhow!("Only https URLs can be opened externally."));
+    }
+    webbrowser::open(parsed.as_str())
+        .map_err(|err| anyhow!("Failed to open browser for '{}': {err}", parsed.as_str()))?;
+    logger::info(&format!("Opened external URL: {}", parsed.as_str()));
+    Ok(parsed.to_string())
+}
+
 pub fn open_path(path: &str) -> Result<String> {
     let normalized = paths::normalize_path(path)?;
     if !normalized.exists() {
@@ -96,16 +115,7 @@ fn dashboard_url_from_cli() -> Result<Option<String>> {
         ),
     ];
 
-    let out = if is_npx_command(command.as_str()) {
-        shell::run_command(
-            command.as_str(),
-            &["--yes", "openclaw", "dashboard", "--no-open"],
-            None,
-            &envs,
-        )?
-    } else {
-        shell::run_command(command.as_str(), &["dashboard", "--no-open"], None, &envs)?
-    };
+    let out = shell::run_command(command.as_str(), &["dashboard", "--no-open"], None, &envs)?;
     if out.code != 0 {
         return Ok(None);
     }
@@ -113,30 +123,19 @@ fn dashboard_url_from_cli() -> Result<Option<String>> {
     Ok(parse_dashboard_url_from_output(&merged))
 }
 
+/// Never `npx`: the URL is also built from openclaw.json, so a slow cold start is not
+/// worth it here.
 fn resolve_dashboard_cli_command() -> Option<String> {
     if let Ok(Some(install)) = state_store::load_install_state() {
+        if let Some(shim) = cli_shim::pinned_command(&install) {
+            return Some(shim);
+        }
         let command = install.command_path.trim().trim_matches('"').to_string();
-        if !command.is_empty() {
-            if command.eq_ignore_ascii_case("npx") {
-                return shell::command_exists("npx");
-            }
+        if !command.is_empty() && !cli_shim::is_npx(&command) {
             return Some(command);
         }
     }
-    if let Some(global) = shell::command_exists("openclaw") {
-        return Some(global);
-    }
-    shell::command_exists("npx")
-}
-
-fn is_npx_command(command: &str) -> bool {
-    let trimmed = command.trim().trim_matches('"');
-    let file = std::path::Path::new(trimmed)
-        .file_name()
-        .and_then(|v| v.to_str())
-        .unwrap_or(trimmed);
-    let lower = file.to_ascii_lowercase();
-    lower == "npx" || lower == "npx.cmd" || lower == "npx.exe"
+    shell::command_exists("openclaw")
 }
 
 fn parse_dashboard_url_from_output(output: &str) -> Option<String> {
@@ -159,7 +158,7 @@ fn parse_dashboard_url_from_output(output: &str) -> Option<String> {
     None
 }
 
-fn read_gateway_token_from_config() -> Result<Option<String>> {
+pub fn read_gateway_token_from_config() -> Result<Option<String>> {
     let cfg_path = paths::config_path();
     if !cfg_path.exists() {
         return Ok(None);
@@ -181,6 +180,16 @@ fn read_gateway_token_from_config() -> Result<Option<String>> {
         .filter(|v| !v.is_empty()))
 }
 
+/// `url` with the configured gateway token attached, for links shown to the user.
+/// Returned unchanged when it does not parse or no token is configured.
+pub fn with_config_token(url: &str) -> String {
+    let Ok(parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+    let token = read_gateway_token_from_config().ok().flatten();
+    with_gateway_token_fragment(parsed, token.as_deref()).to_string()
+}
+
 fn has_auth_fragment(fragment: Option<&str>) -> bool {
     let Some(fragment) = fragment else {
         return false;
@@ -0,0 +1,119 @@
+use std::fs;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::models::TestMessageResult;
+
+use super::{config, logger, paths, state_store};
+
+const DEFAULT_TEST_TEXT: &str = "OpenClaw test message: this channel is connected.";
+
+/// Pushes `text` through `channel` to the paired account (or `target` when given), so the
+/// user gets an end-to-end confirmation without messaging the bot first.
+pub fn send_test_message(
+    channel: &str,
+    text: &str,
+    target: Option<&str>,
+) -> Result<TestMessageResult> {
+    let channel = channel.trim().to_ascii_lowercase();
+    if channel.is_empty() {
+        return Err(anyhow!("Channel cannot be empty."));
+    }
+    let target = match config::optional_non_empty(target.map(str::to_string)) {
+        Some(target) => target,
+        None => paired_target(&channel).ok_or_else(|| {
+            anyhow!(
+                "No paired {channel} account found. Approve a pairing request or message the bot once, then retry."
+            )
+        })?,
+    };
+    let text = match text.trim() {
+        "" => DEFAULT_TEST_TEXT,
+        other => other,
+    };
+    let proxy = state_store::load_last_config()
+        .ok()
+        .flatten()
+        .and_then(|cfg| cfg.proxy);
+    let out = config::run_openclaw_cli(
+        &[
+            "message".to_string(),
+            "send".to_string(),
+            "--channel".to_string(),
+            channel.clone(),
+            "--target".to_string(),
+            target.clone(),
+            "--message".to_string(),
+            text.to_string(),
+        ],
+        proxy,
+    )?;
+    let delivered = out.code == 0;
+    let detail = if delivered {
+        format!("Test message sent to {target} via {channel}.")
+    } else if out.stderr.trim().is_empty() {
+        out.stdout.trim().to_string()
+    } else {
+        out.stderr.trim().to_string()
+    };
+    if delivered {
+        logger::info(&detail);
+    } else {
+        logger::warn(&format!("Test message via {channel} failed: {detail}"));
+    }
+    Ok(TestMessageResult {
+        channel,
+        target,
+        delivered,
+        detail,
+    })
+}
+
+/// First concrete sender allowed on `channel`: the pairing store written by
+/// `pairing approve`, then `channels.<channel>.allowFrom` in openclaw.json.
+fn paired_target(channel: &str) -> Option<String> {
+    let store = paths::openclaw_home()
+        .join("credentials")
+        .join(format!("{channel}-allowFrom.json"));
+    let from_store = fs::read_to_string(store)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .and_then(|json| first_allowed(json.get("allowFrom")));
+    from_store.or_else(|| {
+        let raw = fs::read_to_string(paths::config_path()).ok()?;
+        let json = serde_json::from_str::<Value>(&raw).ok()?;
+        first_allowed(json.pointer(&format!("/channels/{channel}/allowFrom")))
+    })
+}
+
+fn first_allowed(list: Option<&Value>) -> Option<String> {
+    list?.as_array()?.iter().find_map(|entry| {
+        let id = match entry {
+            Value::String(s) => s.trim().to_string(),
+            Value::Number(n) => n.to_string(),
+            _ => return None,
+        };
+        (!id.is_empty() && id != "*").then_some(id)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::first_allowed;
+    use serde_json::json;
+
+    #[test]
+    fn first_allowed_skips_wildcards_and_accepts_numeric_ids() {
+        assert_eq!(
+            first_allowed(Some(&json!(["*", " ", 123456789]))).as_deref(),
+            Some("123456789")
+        );
+        assert_eq!(
+            first_allowed(Some(&json!(["ou_abc"]))).as_deref(),
+            Some("ou_abc")
+        );
+        assert_eq!(first_allowed(Some(&json!(["*"]))), None);
+        assert_eq!(first_allowed(None), None);
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::{Local, TimeZone};
+use serde_json::Value;
+
+use crate::models::ChannelStats;
+
+use super::{config, paths, state_store};
+
+// Enough for days of normal chat traffic without reading a runaway log in full.
+const MAX_LOG_SCAN_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Per-channel message counts and last activity, merged from `channels status --json`
+/// (authoritative timestamps) and the gateway stdout log (counts).
+pub fn get_channel_stats() -> Result<Vec<ChannelStats>> {
+    let mut stats = BTreeMap::<String, ChannelStats>::new();
+    let log = read_log_tail(&paths::logs_dir().join("openclaw-stdout.log"));
+    for (channel, entry) in count_log_messages(&log) {
+        stats.insert(channel, entry);
+    }
+    if let Some(status) = channel_status_json() {
+        for (channel, (inbound, outbound)) in status_activity(&status) {
+            let entry = stats
+                .entry(channel.clone())
+                .or_insert_with(|| ChannelStats {
+                    channel,
+                    ..Default::default()
+                });
+            // The gateway knows about traffic from before the retained log window.
+            if inbound.is_some() {
+                entry.last_inbound_at = inbound;
+            }
+            if outbound.is_some() {
+                entry.last_outbound_at = outbound;
+            }
+        }
+    }
+    Ok(stats.into_values().collect())
+}
+
+fn channel_status_json() -> Option<Value> {
+    let proxy = state_store::load_last_config()
+        .ok()
+        .flatten()
+        .and_then(|cfg| cfg.proxy);
+    let out = config::run_openclaw_cli(
+        &[
+            "channels".to_string(),
+            "status".to_string(),
+            "--json".to_string(),
+        ],
+        proxy,
+    )
+    .ok()?;
+    if out.code != 0 {
+        return None;
+    }
+    serde_json::from_str(out.stdout.trim()).ok()
+}
+
+type Activity = (Option<String>, Option<String>);
+
+/// Latest `lastInboundAt`/`lastOutboundAt` per channel, across its accounts.
+fn status_activity(json: &Value) -> BTreeMap<String, Activity> {
+    let mut out = BTreeMap::new();
+    let Some(entries) = json
+        .get("channels")
+        .and_then(|v| v.as_object())
+        .or_else(|| json.as_object())
+    else {
+        return out;
+    };
+    for (name, entry) in entries.iter().filter(|(_, e)| e.is_object()) {
+        let mut candidates = vec![entry];
+        if let Some(accounts) = entry.get("accounts") {
+            match accounts {
+                Value::Array(items) => candidates.extend(items.iter()),
+                Value::Object(map) => candidates.extend(map.values()),
+                _ => {}
+            }
+        }
+        let latest = |key: &str| {
+            candidates
+                .iter()
+                .filter_map(|c| c.get(key).and_then(timestamp_ms))
+                .max()
+                .and_then(format_ms)
+        };
+        out.insert(
+            name.clone(),
+            (latest("lastInboundAt"), latest("lastOutboundAt")),
+        );
+    }
+    out
+}
+
+fn timestamp_ms(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64(),
+        Value::String(text) => chrono::DateTime::parse_from_rfc3339(text)
+            .ok()
+            .map(|t| t.timestamp_millis()),
+        _ => None,
+    }
+}
+
+fn format_ms(ms: i64) -> Option<String> {
+    Local
+        .timestamp_millis_opt(ms)
+        .single()
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// Counts gateway log lines tagged `[<channel>]` that record a message moving in or
+/// out, e.g. `[telegram] inbound message from 123` or `[feishu] sent reply`.
+fn count_log_messages(log: &str) -> BTreeMap<String, ChannelStats> {
+    let mut out = BTreeMap::<String, ChannelStats>::new();
+    for line in log.lines() {
+        let Some(channel) = channel_tag(line) else {
+            continue;
+        };
+        let lower = line.to_ascii_lowercase();
+        let inbound = ["inbound", "received", "incoming"]
+            .iter()
+            .any(|w| lower.contains(w));
+        let outbound = ["outbound", "sent", "delivered", "reply"]
+            .iter()
+            .any(|w| lower.contains(w));
+        if !inbound && !outbound {
+            continue;
+        }
+        let at = line_timestamp(line);
+        let entry = out.entry(channel.clone()).or_insert_with(|| ChannelStats {
+            channel,
+            ..Default::default()
+        });
+        if inbound {
+            entry.inbound_messages += 1;
+            if at.is_some() {
+                entry.last_inbound_at = at;
+            }
+        } else {
+            entry.outbound_messages += 1;
+            if at.is_some() {
+                entry.last_outbound_at = at;
+            }
+        }
+    }
+    out
+}
+
+fn channel_tag(line: &str) -> Option<String> {
+    let start = line.find('[')?;
+    let end = start + line[start..].find(']')?;
+    let tag = line[start + 1..end].trim().to_ascii_lowercase();
+    let known = [
+        "telegram", "feishu", "discord", "slack", "whatsapp", "signal", "dingtalk", "wecom",
+    ];
+    known.contains(&tag.as_str()).then_some(tag)
+}
+
+fn line_timestamp(line: &str) -> Option<String> {
+    let first = line.split_whitespace().next()?;
+    let parsed = chrono::DateTime::parse_from_rfc3339(first).ok()?;
+    format_ms(parsed.timestamp_millis())
+}
+
+fn read_log_tail(path: &Path) -> String {
+    let Ok(mut file) = fs::File::open(path) else {
+        return String::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = len.saturating_sub(MAX_LOG_SCAN_BYTES);
+    let mut buf = Vec::new();
+    if file.seek(SeekFrom::Start(start)).is_err() || file.read_to_end(&mut buf).is_err() {
+        return String::new();
+    }
+    let text = String::from_utf8_lossy(&buf).to_string();
+    // Drop the partial first line when we started mid-file.
+    if start > 0 {
+        text.split_once('\n')
+            .map(|(_, rest)| rest.to_string())
+            .unwrap_or_default()
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_log_messages, status_activity};
+    use serde_json::json;
+
+    #[test]
+    fn count_log_messages_splits_inbound_and_outbound_per_channel() {
+        let log = "2026-03-01T10:00:00Z [telegram] inbound message from 42\n\
+2026-03-01T10:00:02Z [telegram] sent reply to 42\n\
+2026-03-01T10:05:00Z [feishu] received event im.message.receive_v1\n\
+2026-03-01T10:06:00Z [gateway] listening on 28789\n\
+[telegram] polling started\n";
+        let stats = count_log_messages(log);
+        assert_eq!(stats.len(), 2);
+        let telegram = &stats["telegram"];
+        assert_eq!(telegram.inbound_messages, 1);
+        assert_eq!(telegram.outbound_messages, 1);
+        assert!(telegram.last_inbound_at.is_some());
+        assert_eq!(stats["feishu"].inbound_messages, 1);
+        assert_eq!(stats["feishu"].outbound_messages, 0);
+    }
+
+    #[test]
+    fn status_activity_takes_latest_across_accounts() {
+        let raw = json!({
+            "channels": {
+                "telegram": {
+                    "accounts": [
+                        { "lastInboundAt": 1_700_000_000_000i64 },
+                        { "lastInboundAt": 1_700_000_500_000i64, "lastOutboundAt": null }
+                    ]
+                },
+                "feishu": { "lastOutboundAt": "2026-03-01T10:00:00Z" }
+            }
+        });
+        let activity = status_activity(&raw);
+        let (tg_in, tg_out) = &activity["telegram"];
+        assert!(tg_in.is_some());
+        assert!(tg_out.is_none());
+        assert!(activity["feishu"].1.is_some());
+        assert!(activity["feishu"].0.is_none());
+    }
+}
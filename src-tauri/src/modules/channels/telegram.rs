@@ -0,0 +1,637 @@
+use std::fs;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde_json::{json, Deserializer, Value};
+use url::Url;
+use uuid::Uuid;
+
+use crate::models::{
+    OpenClawConfigInput, TelegramBotInfo, TelegramChannelStatus, TelegramPairingRequest,
+    TelegramWebhookInput, TelegramWebhookResult,
+};
+
+use crate::modules::{config, gateway_port, logger, paths, shell, state_store};
+
+const TELEGRAM_BOT_API_BASE: &str = "https://api.telegram.org";
+
+pub async fn configure_telegram_webhook(
+    options: &TelegramWebhookInput,
+) -> Result<TelegramWebhookResult> {
+    let webhook_url = validate_public_url(&options.public_url)?;
+    let Some(last) = state_store::load_last_config()? else {
+        return Err(anyhow!(
+            "No saved install config found. Complete installation first."
+        ));
+    };
+    let bot_token = last.telegram_bot_token.trim().to_string();
+    if bot_token.is_empty() {
+        return Err(anyhow!(
+            "Telegram bot token is missing. Enable the Telegram channel first."
+        ));
+    }
+    let secret = config::optional_non_empty(Some(options.secret_token.clone()))
+        .unwrap_or_else(|| Uuid::new_v4().simple().to_string());
+    if !is_valid_secret_token(&secret) {
+        return Err(anyhow!(
+            "Webhook secret may only contain A-Z, a-z, 0-9, '_' and '-' (1-256 chars)."
+        ));
+    }
+
+    let proxy = last.proxy.clone();
+    let mut warnings = Vec::<String>::new();
+    let secrets = [bot_token.as_str(), secret.as_str()];
+
+    for (path, value) in [
+        ("channels.telegram.webhookUrl", webhook_url.clone()),
+        ("channels.telegram.webhookSecret", secret.clone()),
+    ] {
+        let out = config::run_openclaw_cli(
+            &[
+                "config".to_string(),
+                "set".to_string(),
+                path.to_string(),
+                value,
+            ],
+            proxy.clone(),
+        )?;
+        if out.code != 0 {
+            warnings.push(format!(
+                "Telegram config write failed ({path}): {}",
+                config::redact_known_values(config::cli_output_text(&out), &secrets)
+            ));
+        }
+    }
+
+    let client = bot_api_client(proxy.clone())?;
+    let registered = match set_webhook(&client, &bot_token, &webhook_url, &secret).await {
+        Ok(()) => verify_webhook(&client, &bot_token, &webhook_url).await,
+        Err(err) => Err(err),
+    };
+
+    let mut result = match registered {
+        Ok(info) => {
+            logger::info("Telegram webhook registered and verified.");
+            if let Some(err) = &info.last_error {
+                warnings.push(format!(
+                    "Telegram reports a recent delivery error for the webhook: {err}. Check that the public URL reaches the gateway."
+                ));
+            }
+            TelegramWebhookResult {
+                mode: "webhook".to_string(),
+                webhook_url: Some(webhook_url),
+                pending_update_count: info.pending_update_count,
+                last_error: info.last_error,
+                warnings,
+            }
+        }
+        Err(err) => {
+            let reason = config::redact_known_values(err.to_string(), &secrets);
+            logger::warn(&format!(
+                "Telegram webhook setup failed, falling back to polling: {reason}"
+            ));
+            warnings.push(format!(
+                "Webhook registration failed; Telegram stays in polling mode: {reason}"
+            ));
+            fall_back_to_polling(&client, &bot_token, proxy.clone(), &mut warnings).await?;
+            TelegramWebhookResult {
+                mode: "polling".to_string(),
+                webhook_url: None,
+                pending_update_count: 0,
+                last_error: Some(reason),
+                warnings,
+            }
+        }
+    };
+
+    let restart_out =
+        config::run_openclaw_cli(&["gateway".to_string(), "restart".to_string()], proxy)?;
+    if restart_out.code != 0 {
+        result.warnings.push(format!(
+            "Gateway restart failed after Telegram mode change: {}",
+            config::redact_known_values(config::cli_output_text(&restart_out), &secrets)
+        ));
+    }
+    Ok(result)
+}
+
+/// Checks a bot token against `getMe` before it is saved. With no token, checks the
+/// one already configured.
+pub async fn validate_bot_token(token: Option<String>) -> Result<TelegramBotInfo> {
+    let last = state_store::load_last_config()?;
+    let proxy = last.as_ref().and_then(|cfg| cfg.proxy.clone());
+    let token = config::optional_non_empty(token)
+        .or_else(|| last.and_then(|cfg| config::optional_non_empty(Some(cfg.telegram_bot_token))))
+        .ok_or_else(|| anyhow!("Telegram bot token is missing."))?;
+    if !looks_like_bot_token(&token) {
+        return Err(anyhow!(
+            "This does not look like a bot token. Copy the full token from @BotFather (123456789:AA...)."
+        ));
+    }
+    let client = bot_api_client(proxy)?;
+    let me = call_bot_api(&client, &token, "getMe", json!({}))
+        .await
+        .map_err(|err| anyhow!(config::redact_known_values(err.to_string(), &[&token])))?;
+    parse_bot_info(&me)
+}
+
+/// Pairing requests waiting for approval, newest last.
+pub fn list_pairing_requests() -> Result<Vec<TelegramPairingRequest>> {
+    let out = config::run_openclaw_cli(
+        &[
+            "pairing".to_string(),
+            "list".to_string(),
+            "telegram".to_string(),
+            "--json".to_string(),
+        ],
+        saved_proxy(),
+    )?;
+    if config::is_unknown_command_error(&out, "pairing") {
+        return Err(anyhow!(
+            "This OpenClaw version cannot list pairing requests. Update OpenClaw or enter the pair code manually."
+        ));
+    }
+    shell::ensure_success("openclaw pairing list telegram", &out)?;
+    Ok(parse_pairing_requests(&out.stdout))
+}
+
+/// Approves a pending pairing request by its code. Falls back to the legacy
+/// `channels add --account` flow on OpenClaw versions without `pairing`.
+pub fn approve_pairing(pair_code: &str) -> Result<String> {
+    let code = pair_code.trim();
+    if code.is_empty() {
+        return Err(anyhow!("Telegram pair code cannot be empty."));
+    }
+
+    let Some(last) = state_store::load_last_config()? else {
+        return Err(anyhow!(
+            "No saved install config found. Complete installation first."
+        ));
+    };
+    let args = vec![
+        "pairing".to_string(),
+        "approve".to_string(),
+        "telegram".to_string(),
+        code.to_string(),
+    ];
+    let mut out = config::run_openclaw_cli(&args, last.proxy.clone())?;
+    if out.code != 0 && config::is_unknown_channel_error(&out, "telegram") {
+        let _ = config::run_openclaw_cli(
+            &[
+                "plugins".to_string(),
+                "enable".to_string(),
+                "telegram".to_string(),
+            ],
+            last.proxy.clone(),
+        );
+        let _ = config::run_openclaw_cli(
+            &["gateway".to_string(), "restart".to_string()],
+            last.proxy.clone(),
+        );
+        out = config::run_openclaw_cli(&args, last.proxy.clone())?;
+    }
+    if out.code == 0 && is_pairing_output_error(&out) {
+        out.code = 1;
+    }
+
+    if out.code == 0 {
+        logger::info("Telegram pairing approved from maintenance.");
+        return Ok(format!("Telegram pairing approved: {code}"));
+    }
+
+    if config::is_unknown_command_error(&out, "pairing") {
+        logger::warn(
+            "OpenClaw CLI does not support `pairing` command; fallback to legacy Telegram account pairing.",
+        );
+        return setup_telegram_pair_legacy(code, &last);
+    }
+
+    shell::ensure_success("openclaw pairing approve telegram", &out)?;
+    Ok(format!("Telegram pairing approved: {code}"))
+}
+
+pub fn reject_pairing(pair_code: &str) -> Result<String> {
+    let code = pair_code.trim();
+    if code.is_empty() {
+        return Err(anyhow!("Telegram pair code cannot be empty."));
+    }
+    let mut out = config::run_openclaw_cli(
+        &[
+            "pairing".to_string(),
+            "reject".to_string(),
+            "telegram".to_string(),
+            code.to_string(),
+        ],
+        saved_proxy(),
+    )?;
+    if config::is_unknown_command_error(&out, "pairing")
+        || config::is_unknown_command_error(&out, "reject")
+    {
+        return Err(anyhow!(
+            "This OpenClaw version cannot reject pairing requests; unapproved requests expire on their own."
+        ));
+    }
+    if out.code == 0 && is_pairing_output_error(&out) {
+        out.code = 1;
+    }
+    shell::ensure_success("openclaw pairing reject telegram", &out)?;
+    logger::info("Telegram pairing request rejected from maintenance.");
+    Ok(format!("Telegram pairing rejected: {code}"))
+}
+
+/// Whether Telegram is configured and connected, in which update mode, and how many
+/// pairing requests wait. Gateway or CLI failures show up as `connected: false`.
+pub fn telegram_status() -> Result<TelegramChannelStatus> {
+    let last = state_store::load_last_config()?;
+    let configured = last.as_ref().is_some_and(|cfg| {
+        cfg.enable_telegram_channel && !cfg.telegram_bot_token.trim().is_empty()
+    });
+    let openclaw_json = fs::read_to_string(paths::config_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .unwrap_or(Value::Null);
+    let mode = if openclaw_json
+        .pointer("/channels/telegram/webhookUrl")
+        .and_then(|v| v.as_str())
+        .is_some_and(|url| !url.trim().is_empty())
+    {
+        "webhook"
+    } else {
+        "polling"
+    };
+    if !configured {
+        return Ok(TelegramChannelStatus {
+            configured,
+            connected: false,
+            mode: mode.to_string(),
+            detail: "Telegram channel is not enabled.".to_string(),
+            pending_requests: None,
+        });
+    }
+
+    let channel = config::run_openclaw_cli(
+        &[
+            "channels".to_string(),
+            "status".to_string(),
+            "--json".to_string(),
+        ],
+        saved_proxy(),
+    )
+    .ok()
+    .filter(|out| out.code == 0)
+    .and_then(|out| serde_json::from_str::<Value>(out.stdout.trim()).ok())
+    .and_then(|json| {
+        gateway_port::parse_channel_status(&json)
+            .into_iter()
+            .find(|c| c.channel == "telegram")
+    });
+    let (connected, detail) = match channel {
+        Some(channel) => (channel.connected, channel.detail),
+        None => (
+            false,
+            "Gateway did not report a Telegram channel; is it running?".to_string(),
+        ),
+    };
+    Ok(TelegramChannelStatus {
+        configured,
+        connected,
+        mode: mode.to_string(),
+        detail,
+        pending_requests: list_pairing_requests().ok().map(|items| items.len()),
+    })
+}
+
+struct WebhookInfo {
+    pending_update_count: u64,
+    last_error: Option<String>,
+}
+
+fn bot_api_client(proxy: Option<String>) -> Result<Client> {
+    let mut builder = Client::builder().timeout(Duration::from_secs(10));
+    if let Some(proxy) = config::optional_non_empty(proxy) {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    Ok(builder.build()?)
+}
+
+async fn call_bot_api(client: &Client, token: &str, method: &str, body: Value) -> Result<Value> {
+    let resp: Value = client
+        .post(format!("{TELEGRAM_BOT_API_BASE}/bot{token}/{method}"))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|err| anyhow!("Telegram {method} request failed: {err}"))?
+        .json()
+        .await?;
+    if resp.get("ok").and_then(|v| v.as_bool()) != Some(true) {
+        let description = resp
+            .get("description")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown error");
+        return Err(anyhow!("Telegram {method} rejected: {description}"));
+    }
+    Ok(resp.get("result").cloned().unwrap_or(Value::Null))
+}
+
+async fn set_webhook(client: &Client, token: &str, url: &str, secret: &str) -> Result<()> {
+    call_bot_api(
+        client,
+        token,
+        "setWebhook",
+        json!({ "url": url, "secret_token": secret, "drop_pending_updates": false }),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn verify_webhook(client: &Client, token: &str, expected_url: &str) -> Result<WebhookInfo> {
+    let info = call_bot_api(client, token, "getWebhookInfo", json!({})).await?;
+    parse_webhook_info(&info, expected_url)
+}
+
+fn parse_webhook_info(info: &Value, expected_url: &str) -> Result<WebhookInfo> {
+    let url = info.get("url").and_then(|v| v.as_str()).unwrap_or_default();
+    if url != expected_url {
+        return Err(anyhow!(
+            "Telegram reports webhook url '{url}' instead of '{expected_url}'"
+        ));
+    }
+    let last_error = info
+        .get("last_error_message")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .filter(|s| !s.trim().is_empty());
+    // Telegram keeps the last delivery error until a later delivery succeeds, so it may
+    // predate this registration. Reported to the caller rather than treated as failure.
+    Ok(WebhookInfo {
+        pending_update_count: info
+            .get("pending_update_count")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0),
+        last_error,
+    })
+}
+
+async fn fall_back_to_polling(
+    client: &Client,
+    token: &str,
+    proxy: Option<String>,
+    warnings: &mut Vec<String>,
+) -> Result<()> {
+    for path in [
+        "channels.telegram.webhookUrl",
+        "channels.telegram.webhookSecret",
+    ] {
+        let out = config::run_openclaw_cli(
+            &["config".to_string(), "unset".to_string(), path.to_string()],
+            proxy.clone(),
+        )?;
+        if out.code != 0 {
+            warnings.push(format!(
+                "Failed to clear {path}: {}",
+                config::cli_output_text(&out)
+            ));
+        }
+    }
+    // Polling (getUpdates) is rejected by Telegram while a webhook is still registered.
+    if let Err(err) = call_bot_api(client, token, "deleteWebhook", json!({})).await {
+        warnings.push(config::redact_known_values(err.to_string(), &[token]));
+    }
+    Ok(())
+}
+
+fn saved_proxy() -> Option<String> {
+    state_store::load_last_config()
+        .ok()
+        .flatten()
+        .and_then(|cfg| cfg.proxy)
+}
+
+/// `<bot id>:<secret>` as issued by @BotFather.
+fn looks_like_bot_token(token: &str) -> bool {
+    let Some((id, secret)) = token.split_once(':') else {
+        return false;
+    };
+    !id.is_empty()
+        && id.chars().all(|c| c.is_ascii_digit())
+        && secret.len() >= 30
+        && secret
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn parse_bot_info(me: &Value) -> Result<TelegramBotInfo> {
+    if me.get("is_bot").and_then(|v| v.as_bool()) != Some(true) {
+        return Err(anyhow!("Telegram getMe did not return a bot account."));
+    }
+    let text = |key: &str| {
+        me.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let flag = |key: &str| me.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+    Ok(TelegramBotInfo {
+        id: me.get("id").and_then(|v| v.as_i64()).unwrap_or_default(),
+        username: text("username"),
+        first_name: text("first_name"),
+        can_join_groups: flag("can_join_groups"),
+        can_read_all_group_messages: flag("can_read_all_group_messages"),
+    })
+}
+
+/// Accepts a bare array or `{ "requests": [...] }` / `{ "pending": [...] }`, with
+/// plugin log lines before the JSON.
+fn parse_pairing_requests(raw: &str) -> Vec<TelegramPairingRequest> {
+    // The first `[` or `{` may belong to a log prefix like `[plugins]`.
+    let Some(json) = raw
+        .char_indices()
+        .filter(|(_, c)| *c == '[' || *c == '{')
+        .find_map(|(start, _)| {
+            Deserializer::from_str(&raw[start..])
+                .into_iter::<Value>()
+                .next()
+                .and_then(|value| value.ok())
+        })
+    else {
+        return vec![];
+    };
+    let entries = json
+        .as_array()
+        .or_else(|| json.get("requests").and_then(|v| v.as_array()))
+        .or_else(|| json.get("pending").and_then(|v| v.as_array()))
+        .cloned()
+        .unwrap_or_default();
+    let text = |entry: &Value, keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| entry.pointer(key))
+            .and_then(|v| {
+                v.as_str()
+                    .map(str::to_string)
+                    .or_else(|| v.as_i64().map(|n| n.to_string()))
+            })
+            .filter(|s| !s.trim().is_empty())
+    };
+    entries
+        .iter()
+        .filter_map(|entry| {
+            Some(TelegramPairingRequest {
+                code: text(entry, &["/code"])?,
+                user_id: text(entry, &["/userId", "/senderId", "/id"]),
+                username: text(entry, &["/username", "/meta/username"]),
+                requested_at: text(entry, &["/createdAt", "/requestedAt"]),
+            })
+        })
+        .collect()
+}
+
+fn is_pairing_output_error(out: &shell::CmdOutput) -> bool {
+    let merged = format!(
+        "{}\n{}",
+        out.stdout.to_ascii_lowercase(),
+        out.stderr.to_ascii_lowercase()
+    );
+    merged.contains("failed to start cli:")
+        || merged.contains("no pending pairing request found")
+        || (merged.contains("pairing") && merged.contains("error"))
+}
+
+fn setup_telegram_pair_legacy(code: &str, payload: &OpenClawConfigInput) -> Result<String> {
+    let token = payload.telegram_bot_token.trim();
+    if token.is_empty() {
+        return Err(anyhow!(
+            "Telegram bot token is missing. Re-run install wizard and enable Telegram first."
+        ));
+    }
+
+    let args = vec![
+        "channels".to_string(),
+        "add".to_string(),
+        "--channel".to_string(),
+        "telegram".to_string(),
+        "--token".to_string(),
+        token.to_string(),
+        "--account".to_string(),
+        code.to_string(),
+    ];
+    let mut out = config::run_openclaw_cli(&args, payload.proxy.clone())?;
+    if out.code != 0 && config::is_unknown_channel_error(&out, "telegram") {
+        let _ = config::run_openclaw_cli(
+            &[
+                "plugins".to_string(),
+                "enable".to_string(),
+                "telegram".to_string(),
+            ],
+            payload.proxy.clone(),
+        );
+        let _ = config::run_openclaw_cli(
+            &["gateway".to_string(), "restart".to_string()],
+            payload.proxy.clone(),
+        );
+        out = config::run_openclaw_cli(&args, payload.proxy.clone())?;
+    }
+    shell::ensure_success("openclaw channels add telegram account (legacy)", &out)?;
+    logger::info("Telegram pairing applied via legacy account fallback.");
+    Ok(format!(
+        "Telegram pairing applied (legacy fallback): {code}"
+    ))
+}
+
+fn validate_public_url(raw: &str) -> Result<String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Err(anyhow!("Webhook public URL is required."));
+    }
+    let parsed =
+        Url::parse(trimmed).map_err(|_| anyhow!("Webhook public URL is not a valid URL."))?;
+    if parsed.scheme() != "https" {
+        return Err(anyhow!("Telegram webhooks require an https:// public URL."));
+    }
+    if parsed.host_str().is_none() {
+        return Err(anyhow!("Webhook public URL must include a host."));
+    }
+    Ok(parsed.to_string())
+}
+
+fn is_valid_secret_token(secret: &str) -> bool {
+    !secret.is_empty()
+        && secret.len() <= 256
+        && secret
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_valid_secret_token, looks_like_bot_token, parse_bot_info, parse_pairing_requests,
+        parse_webhook_info, validate_public_url,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn bot_tokens_are_checked_for_shape_and_bot_flag() {
+        assert!(looks_like_bot_token(
+            "123456789:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsaw_"
+        ));
+        assert!(!looks_like_bot_token("123456789"));
+        assert!(!looks_like_bot_token(
+            "bot:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsaw"
+        ));
+        let me = json!({ "id": 42, "is_bot": true, "username": "oc_bot", "can_join_groups": true });
+        let info = parse_bot_info(&me).expect("bot");
+        assert_eq!(info.username, "oc_bot");
+        assert!(info.can_join_groups);
+        assert!(parse_bot_info(&json!({ "id": 7, "is_bot": false })).is_err());
+    }
+
+    #[test]
+    fn pairing_requests_parse_from_list_output() {
+        let raw = r#"[plugins] telegram: Registered
+{"requests":[{"code":"K7P2QX","userId":12345,"meta":{"username":"alice"},"createdAt":"2026-03-01T10:00:00Z"},{"userId":1}]}"#;
+        let requests = parse_pairing_requests(raw);
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].code, "K7P2QX");
+        assert_eq!(requests[0].user_id.as_deref(), Some("12345"));
+        assert_eq!(requests[0].username.as_deref(), Some("alice"));
+        assert!(parse_pairing_requests("no json").is_empty());
+    }
+
+    #[test]
+    fn validate_public_url_requires_https() {
+        assert!(validate_public_url("http://example.com/telegram").is_err());
+        assert_eq!(
+            validate_public_url(" https://bot.example.com/telegram ").expect("valid url"),
+            "https://bot.example.com/telegram"
+        );
+    }
+
+    #[test]
+    fn secret_token_charset_matches_bot_api_rules() {
+        assert!(is_valid_secret_token("abc_DEF-123"));
+        assert!(!is_valid_secret_token("has space"));
+        assert!(!is_valid_secret_token(""));
+    }
+
+    #[test]
+    fn parse_webhook_info_reports_delivery_errors() {
+        let url = "https://bot.example.com/telegram";
+        let ok = json!({ "url": url, "pending_update_count": 2 });
+        assert_eq!(
+            parse_webhook_info(&ok, url)
+                .expect("healthy webhook")
+                .pending_update_count,
+            2
+        );
+        let failing = json!({ "url": url, "last_error_message": "Connection refused" });
+        assert_eq!(
+            parse_webhook_info(&failing, url)
+                .expect("registered webhook")
+                .last_error
+                .as_deref(),
+            Some("Connection refused")
+        );
+        assert!(parse_webhook_info(&ok, "https://other.example.com/telegram").is_err());
+    }
+}
@@ -0,0 +1,252 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::models::{InstallState, ResultWarning};
+
+use super::{logger, shell};
+
+const SHIM_DIR: &str = "installer-bin";
+const SHIM_NAME: &str = "openclaw.cmd";
+
+// Set when a command fell back to `npx openclaw`; reported once as a warning.
+static NPX_FALLBACK: AtomicBool = AtomicBool::new(false);
+
+/// Writes `<install_dir>\installer-bin\openclaw.cmd` and keeps it only if it answers
+/// `--version` according to `usable`. The shim runs the package's bin script with an
+/// absolute `node.exe`, so it works without PATH, npm's `.bin` shims or `npx`; when
+/// the entry script cannot be found it wraps the resolved `command_path` instead.
+pub fn materialize(
+    install_dir: &Path,
+    command_path: &str,
+    usable: impl Fn(&str) -> bool,
+) -> Option<String> {
+    let Some(body) = shim_body(install_dir, command_path) else {
+        logger::warn("No local OpenClaw entry point found; no pinned CLI shim written.");
+        return None;
+    };
+    let path = shim_path(install_dir);
+    if let Err(err) = write_shim(&path, &body) {
+        logger::warn(&format!("Failed to write pinned CLI shim: {err}"));
+        return None;
+    }
+    let text = path.to_string_lossy().to_string();
+    if !usable(&text) {
+        logger::warn(&format!("Pinned CLI shim does not run; removed: {text}"));
+        let _ = fs::remove_file(&path);
+        return None;
+    }
+    logger::info(&format!("Pinned OpenClaw CLI shim: {text}"));
+    Some(text)
+}
+
+/// Default `usable` check for `materialize`: the command exits 0 on `--version`.
+pub fn answers_version(command: &str) -> bool {
+    shell::run_command(command, &["--version"], None, &[]).is_ok_and(|out| out.code == 0)
+}
+
+/// The recorded shim, if it is still on disk and the `node.exe` and script it calls
+/// still exist. Hot paths (catalogs, dashboard URL) use this before anything that
+/// could end up in `npx`. A shim whose node moved (Node reinstalled, version manager
+/// switched) is rewritten for the current node; if that is not possible it is not used
+/// and callers fall back to `command_path`.
+pub fn pinned_command(install: &InstallState) -> Option<String> {
+    let path = install
+        .shim_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty() && Path::new(path).is_file())?;
+    let body = fs::read_to_string(path).ok()?;
+    if targets_exist(&body) {
+        return Some(path.to_string());
+    }
+    let refreshed = shim_body(Path::new(&install.install_dir), &install.command_path)
+        .filter(|body| targets_exist(body));
+    match refreshed.map(|body| write_shim(Path::new(path), &body)) {
+        Some(Ok(())) => {
+            logger::info(&format!(
+                "Pinned CLI shim pointed at a missing node; rewritten: {path}"
+            ));
+            Some(path.to_string())
+        }
+        _ => {
+            logger::warn(&format!(
+                "Pinned CLI shim points at a missing node or script; not used: {path}"
+            ));
+            None
+        }
+    }
+}
+
+/// True when every absolute path the shim calls is still a file.
+fn targets_exist(body: &str) -> bool {
+    shim_targets(body)
+        .iter()
+        .all(|target| Path::new(target).is_file())
+}
+
+/// Absolute paths quoted in the shim: `node.exe` and the entry script, or the wrapped
+/// command.
+fn shim_targets(body: &str) -> Vec<String> {
+    body.lines()
+        .flat_map(|line| line.split('"').skip(1).step_by(2))
+        .filter(|part| Path::new(part).is_absolute())
+        .map(str::to_string)
+        .collect()
+}
+
+/// True when `command` is `npx` (bare or a resolved `npx.cmd` / `npx.exe` path).
+pub fn is_npx(command: &str) -> bool {
+    let trimmed = command.trim().trim_matches('"');
+    let file = Path::new(trimmed)
+        .file_name()
+        .and_then(|v| v.to_str())
+        .unwrap_or(trimmed);
+    let lower = file.to_ascii_lowercase();
+    lower == "npx" || lower == "npx.cmd" || lower == "npx.exe"
+}
+
+/// Records that `context` had to run through `npx` (20-60 s cold start, network).
+pub fn note_npx_fallback(context: &str) {
+    NPX_FALLBACK.store(true, Ordering::SeqCst);
+    logger::warn(&format!(
+        "{context}: no local OpenClaw command works; falling back to npx openclaw (slow, needs network)."
+    ));
+}
+
+/// Warning for the user if an npx fallback happened since the last call.
+pub fn take_npx_fallback_warning() -> Option<ResultWarning> {
+    NPX_FALLBACK.swap(false, Ordering::SeqCst).then(|| {
+        ResultWarning::warning(
+            "npx_fallback",
+            "OpenClaw ran through npx because no local command works; every call is slow and needs network access.",
+        )
+        .with_remediation("Reinstall OpenClaw from Maintenance to restore the local command.")
+    })
+}
+
+fn shim_path(install_dir: &Path) -> PathBuf {
+    install_dir.join(SHIM_DIR).join(SHIM_NAME)
+}
+
+fn write_shim(path: &Path, body: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, body)?;
+    Ok(())
+}
+
+fn shim_body(install_dir: &Path, command_path: &str) -> Option<String> {
+    if let (Some(node), Some(entry)) = (shell::command_exists("node"), package_entry(install_dir)) {
+        return Some(format!(
+            "@echo off\r\n\"{node}\" \"{}\" %*\r\n",
+            entry.to_string_lossy()
+        ));
+    }
+    let command = command_path.trim().trim_matches('"');
+    if command.is_empty() || is_npx(command) || !Path::new(command).is_absolute() {
+        return None;
+    }
+    Some(format!("@echo off\r\ncall \"{command}\" %*\r\n"))
+}
+
+/// The `openclaw` bin script of the package installed in `install_dir` (npm/bun), or of
+/// the checkout itself (git).
+fn package_entry(install_dir: &Path) -> Option<PathBuf> {
+    [
+        install_dir.join("node_modules").join("openclaw"),
+        install_dir.to_path_buf(),
+    ]
+    .into_iter()
+    .find_map(|root| {
+        let raw = fs::read_to_string(root.join("package.json")).ok()?;
+        let manifest = serde_json::from_str::<Value>(&raw).ok()?;
+        if manifest.get("name").and_then(Value::as_str) != Some("openclaw") {
+            return None;
+        }
+        let entry = bin_entry(&manifest)?;
+        Some(root.join(entry)).filter(|path| path.is_file())
+    })
+}
+
+fn bin_entry(manifest: &Value) -> Option<String> {
+    match manifest.get("bin")? {
+        Value::String(path) => Some(path.clone()),
+        Value::Object(map) => map
+            .get("openclaw")
+            .or_else(|| map.values().next())
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bin_entry, is_npx, package_entry, shim_targets, targets_exist};
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn bin_entry_reads_string_and_map_forms() {
+        assert_eq!(
+            bin_entry(&json!({ "bin": "dist/cli.js" })).as_deref(),
+            Some("dist/cli.js")
+        );
+        assert_eq!(
+            bin_entry(&json!({ "bin": { "other": "o.js", "openclaw": "openclaw.mjs" } }))
+                .as_deref(),
+            Some("openclaw.mjs")
+        );
+        assert_eq!(bin_entry(&json!({ "name": "openclaw" })), None);
+    }
+
+    #[test]
+    fn shim_targets_are_checked_on_disk() {
+        let root = std::env::temp_dir().join(format!("oc-cli-shim-targets-{}", std::process::id()));
+        fs::create_dir_all(&root).expect("dir");
+        let (node, entry) = (root.join("node.exe"), root.join("openclaw.mjs"));
+        fs::write(&entry, "").expect("entry");
+        let body = format!(
+            "@echo off\r\n\"{}\" \"{}\" %*\r\n",
+            node.to_string_lossy(),
+            entry.to_string_lossy()
+        );
+        assert_eq!(
+            shim_targets(&body),
+            vec![
+                node.to_string_lossy().to_string(),
+                entry.to_string_lossy().to_string()
+            ]
+        );
+        assert!(!targets_exist(&body));
+        fs::write(&node, "").expect("node");
+        assert!(targets_exist(&body));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn package_entry_finds_the_installed_package_script() {
+        let root = std::env::temp_dir().join(format!("oc-cli-shim-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let pkg = root.join("node_modules").join("openclaw");
+        fs::create_dir_all(&pkg).expect("pkg dir");
+        fs::write(
+            pkg.join("package.json"),
+            r#"{ "name": "openclaw", "bin": { "openclaw": "openclaw.mjs" } }"#,
+        )
+        .expect("manifest");
+        assert_eq!(package_entry(&root), None);
+        fs::write(pkg.join("openclaw.mjs"), "").expect("entry");
+        assert_eq!(package_entry(&root), Some(pkg.join("openclaw.mjs")));
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(is_npx("npx"));
+        assert!(is_npx("C:/Program Files/nodejs/npx.cmd"));
+        assert!(!is_npx("C:\\OpenClaw\\installer-bin\\openclaw.cmd"));
+    }
+}
@@ -8,9 +8,15 @@ use serde_json::{json, Deserializer, Value};
 use url::Url;
 use uuid::Uuid;
 
-use crate::models::{ConfigureResult, ModelChain, OpenClawConfigInput, OpenClawFileConfig};
+use crate::models::{
+    BackupCategory, BackupInfo, ConfigureResult, InstallState, ModelChain, OpenClawConfigInput,
+    OpenClawFileConfig, ResultWarning, AZURE_DEFAULT_API_VERSION,
+};
 
-use super::{logger, model_identity, paths, shell, state_store};
+use super::{
+    backup, bedrock, cli_shim, consent, dotenv, firewall, gateway_service, json_layout, logger,
+    model_identity, paths, profiles, secrets, shell, skills, state_store,
+};
 
 const AUTH_MAPPED_PROVIDERS: &[&str] = &[
     "openai",
@@ -23,13 +29,188 @@ const AUTH_MAPPED_PROVIDERS: &[&str] = &[
     "zai",
     "xiaomi",
     "minimax",
+    "azure",
+];
+// Free OpenRouter models used by the trial. They cost nothing but still need an
+// OpenRouter key (free to create, no payment method); rate-limited and subject to
+// change upstream, so the trial is a stepping stone rather than a supported setup.
+const TRIAL_PROVIDER: &str = "openrouter";
+const TRIAL_MODEL_CHAIN: &[&str] = &[
+    "openrouter/deepseek/deepseek-chat-v3-0324:free",
+    "openrouter/meta-llama/llama-3.3-70b-instruct:free",
 ];
 const KIMI_REGION_CN: &str = "cn";
 const KIMI_REGION_GLOBAL: &str = "global";
 const KIMI_BASE_URL_CN: &str = "https://api.moonshot.cn/v1";
 const KIMI_BASE_URL_GLOBAL: &str = "https://api.moonshot.ai/v1";
+const AZURE_PROVIDER: &str = "azure";
+
+/// Configure phases the wizard can run and retry one at a time. `configure` runs all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigurePhase {
+    OnboardCore,
+    ApplyKeys,
+    ApplyModels,
+    ApplyChannels,
+    ApplySkills,
+}
+
+impl ConfigurePhase {
+    const ALL: [ConfigurePhase; 5] = [
+        ConfigurePhase::OnboardCore,
+        ConfigurePhase::ApplyKeys,
+        ConfigurePhase::ApplyModels,
+        ConfigurePhase::ApplySkills,
+        ConfigurePhase::ApplyChannels,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            ConfigurePhase::OnboardCore => "onboard_core",
+            ConfigurePhase::ApplyKeys => "apply_keys",
+            ConfigurePhase::ApplyModels => "apply_models",
+            ConfigurePhase::ApplyChannels => "apply_channels",
+            ConfigurePhase::ApplySkills => "apply_skills",
+        }
+    }
+
+    /// Backup category covering everything the phase may write.
+    fn snapshot_category(self) -> BackupCategory {
+        match self {
+            ConfigurePhase::ApplyKeys => BackupCategory::Secrets,
+            _ => BackupCategory::Config,
+        }
+    }
+
+    fn apply(self, payload: &OpenClawConfigInput, warnings: &mut Vec<ResultWarning>) -> Result<()> {
+        match self {
+            ConfigurePhase::OnboardCore => run_onboard(payload, warnings),
+            ConfigurePhase::ApplyKeys => apply_provider_keys(payload, warnings),
+            ConfigurePhase::ApplyModels => {
+                apply_model_chain(&payload.model_chain, warnings)?;
+                apply_kimi_region_base_url(payload, warnings)?;
+                apply_azure_provider(payload, warnings)
+            }
+            ConfigurePhase::ApplyChannels => apply_channel_integrations(payload, warnings),
+            ConfigurePhase::ApplySkills => {
+                apply_feature_toggles(payload, warnings)?;
+                apply_selected_skills(payload, warnings)
+            }
+        }
+    }
+}
 
 pub fn configure(payload: &OpenClawConfigInput) -> Result<ConfigureResult> {
+    run_configure_phases(payload, &ConfigurePhase::ALL)
+}
+
+pub fn configure_phase(
+    payload: &OpenClawConfigInput,
+    phase: ConfigurePhase,
+) -> Result<ConfigureResult> {
+    run_configure_phases(payload, &[phase])
+}
+
+fn run_configure_phases(
+    payload: &OpenClawConfigInput,
+    phases: &[ConfigurePhase],
+) -> Result<ConfigureResult> {
+    let labels = phases
+        .iter()
+        .map(|phase| phase.label())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let _trace = tracing::info_span!("configure", phases = %labels).entered();
+    let payload = prepare_configure_payload(payload)?;
+    let snapshots =
+        tracing::info_span!("configure.snapshot").in_scope(|| snapshot_before_phases(phases))?;
+
+    let mut warnings = Vec::<ResultWarning>::new();
+    apply_phases(
+        phases,
+        |phase| {
+            tracing::info_span!("configure.phase", phase = %phase.label())
+                .in_scope(|| phase.apply(&payload, &mut warnings))
+        },
+        |phase, err| {
+            tracing::error!(error = %err, phase = %phase.label(), "configure phase failed");
+            rollback_failed_phase(phase, &snapshots, err)
+        },
+    )?;
+
+    if phases.contains(&ConfigurePhase::OnboardCore) {
+        configure_firewall(&payload, &mut warnings);
+    }
+    warnings.extend(cli_shim::take_npx_fallback_warning());
+
+    let config_path = paths::config_path();
+    warnings.extend(set_windows_acl(&config_path));
+    let env_path = paths::openclaw_home().join(".env");
+    if env_path.exists() {
+        warnings.extend(set_windows_acl(&env_path));
+    }
+
+    if payload.trial_mode {
+        warnings.push(
+            ResultWarning::info(
+                "trial_mode_active",
+                "Running on free trial models. They are rate-limited and may disappear without notice.",
+            )
+            .with_provider(TRIAL_PROVIDER)
+            .with_remediation("Run setup again with a paid model to leave trial mode."),
+        );
+    }
+    state_store::save_last_config(&payload)?;
+
+    logger::info(&format!(
+        "Configuration updated via OpenClaw CLI ({labels}): {}",
+        config_path.to_string_lossy()
+    ));
+
+    Ok(ConfigureResult {
+        config_path: config_path.to_string_lossy().to_string(),
+        warnings,
+    })
+}
+
+/// Applies `phases` in order. The first failure stops the run and goes through
+/// `rollback`, so no later phase builds on a half-applied one.
+fn apply_phases(
+    phases: &[ConfigurePhase],
+    mut apply: impl FnMut(ConfigurePhase) -> Result<()>,
+    rollback: impl FnOnce(ConfigurePhase, anyhow::Error) -> anyhow::Error,
+) -> Result<()> {
+    for phase in phases {
+        if let Err(err) = apply(*phase) {
+            return Err(rollback(*phase, err));
+        }
+    }
+    Ok(())
+}
+
+/// LAN mode is useless while Windows Firewall drops inbound connections, so binding to
+/// all interfaces opens the port for the local subnet; going back to loopback closes it.
+/// Failures only warn: the gateway itself works either way.
+fn configure_firewall(payload: &OpenClawConfigInput, warnings: &mut Vec<ResultWarning>) {
+    let port = payload.port;
+    if bind_address_to_mode(&payload.bind_address) != "lan" {
+        if let Err(err) = firewall::remove_firewall_rule(port) {
+            logger::warn(&format!("Stale firewall rule for TCP {port} kept: {err}"));
+        }
+        return;
+    }
+    if let Err(err) = firewall::add_firewall_rule(port) {
+        warnings.push(
+            ResultWarning::warning("firewall_rule_missing", err.to_string()).with_remediation(
+                format!(
+                    "Allow inbound TCP {port} in Windows Defender Firewall, or rerun the installer as administrator."
+                ),
+            ),
+        );
+    }
+}
+
+fn prepare_configure_payload(payload: &OpenClawConfigInput) -> Result<OpenClawConfigInput> {
     validate_payload(payload)?;
     // Normalize known legacy model ids so old configs don't keep breaking new installs.
     // (Example: "moonshot/kimi-2.5" -> "moonshot/kimi-k2.5")
@@ -45,6 +226,7 @@ pub fn configure(payload: &OpenClawConfigInput) -> Result<ConfigureResult> {
         .collect();
     // Bind all OpenClaw state/config writes to the chosen install directory so we never
     // mix with an existing `%USERPROFILE%\\.openclaw` installation.
+    apply_trial_chain(&mut payload)?;
     let install_dir = paths::normalize_path(&payload.install_dir)?;
     std::env::set_var(
         "OPENCLAW_INSTALLER_OPENCLAW_HOME",
@@ -53,39 +235,114 @@ pub fn configure(payload: &OpenClawConfigInput) -> Result<ConfigureResult> {
     paths::ensure_dirs()?;
     std::fs::create_dir_all(paths::openclaw_home())?;
     std::fs::create_dir_all(&install_dir)?;
+    Ok(payload)
+}
 
-    let mut warnings = Vec::<String>::new();
-
-    run_onboard(&payload, &mut warnings)?;
-    apply_provider_keys(&payload, &mut warnings)?;
-    apply_model_chain(&payload.model_chain, &mut warnings)?;
-    apply_kimi_region_base_url(&payload, &mut warnings)?;
-    apply_feature_toggles(&payload, &mut warnings)?;
-    apply_selected_skills(&payload, &mut warnings)?;
-    apply_channel_integrations(&payload, &mut warnings)?;
-
-    let config_path = paths::config_path();
-    warnings.extend(set_windows_acl(&config_path));
-    let env_path = paths::openclaw_home().join(".env");
-    if env_path.exists() {
-        warnings.extend(set_windows_acl(&env_path));
+/// Swaps in the free trial chain when trial mode is on. The free models still need an
+/// OpenRouter key, so one is required; a key for any other provider means the user is
+/// past the trial, so the flag is dropped.
+fn apply_trial_chain(payload: &mut OpenClawConfigInput) -> Result<()> {
+    if !payload.trial_mode {
+        return Ok(());
     }
+    let primary_is_trial =
+        model_identity::normalize_auth_provider(&payload.provider) == TRIAL_PROVIDER;
+    let other_key = payload
+        .provider_api_keys
+        .iter()
+        .filter(|(provider, _)| provider.as_str() != TRIAL_PROVIDER)
+        .any(|(_, key)| provided_key(key.clone()).is_some())
+        || (!primary_is_trial && provided_key(payload.api_key.clone()).is_some());
+    if other_key {
+        payload.trial_mode = false;
+        return Ok(());
+    }
+    let trial_key = primary_is_trial
+        .then(|| provided_key(payload.api_key.clone()))
+        .flatten()
+        .or_else(|| {
+            payload
+                .provider_api_keys
+                .get(TRIAL_PROVIDER)
+                .and_then(|key| provided_key(key.clone()))
+        })
+        .ok_or_else(|| {
+            anyhow!(
+                "The free trial needs an OpenRouter API key. Create one at https://openrouter.ai/keys (free, no payment method needed) and paste it here."
+            )
+        })?;
+    payload.provider = TRIAL_PROVIDER.to_string();
+    payload.api_key = trial_key;
+    payload.model_chain.primary = TRIAL_MODEL_CHAIN[0].to_string();
+    payload.model_chain.fallbacks = TRIAL_MODEL_CHAIN[1..]
+        .iter()
+        .map(|model| model.to_string())
+        .collect();
+    Ok(())
+}
 
-    state_store::save_last_config(&payload)?;
-
-    logger::info(&format!(
-        "Configuration updated via OpenClaw CLI: {}",
-        config_path.to_string_lossy()
-    ));
+/// Snapshots the files the phases touch so a failed phase can be rolled back.
+/// Nothing is taken on a fresh install since there is nothing to return to.
+fn snapshot_before_phases(phases: &[ConfigurePhase]) -> Result<Vec<(BackupCategory, BackupInfo)>> {
+    let categories = snapshot_categories(phases, |category| match category {
+        BackupCategory::Secrets => paths::openclaw_home().join(".env").exists(),
+        _ => paths::config_path().exists(),
+    });
+    categories
+        .into_iter()
+        .map(|category| {
+            Ok((
+                category,
+                backup::backup_category_with_prefix("pre-configure", category)?,
+            ))
+        })
+        .collect()
+}
 
-    if warnings.is_empty() {
-        warnings.push("No warnings".to_string());
+/// Backup categories the phases write, once each, skipping those with nothing on disk.
+fn snapshot_categories(
+    phases: &[ConfigurePhase],
+    exists: impl Fn(BackupCategory) -> bool,
+) -> Vec<BackupCategory> {
+    let mut categories = Vec::new();
+    for category in phases.iter().map(|phase| phase.snapshot_category()) {
+        if !categories.contains(&category) && exists(category) {
+            categories.push(category);
+        }
     }
+    categories
+}
 
-    Ok(ConfigureResult {
-        config_path: config_path.to_string_lossy().to_string(),
-        warnings,
-    })
+fn rollback_failed_phase(
+    phase: ConfigurePhase,
+    snapshots: &[(BackupCategory, BackupInfo)],
+    err: anyhow::Error,
+) -> anyhow::Error {
+    if snapshots.is_empty() {
+        return anyhow!("Configure step {} failed: {err}", phase.label());
+    }
+    for (category, snapshot) in snapshots {
+        if let Err(restore_err) = backup::restore_category(&snapshot.id, *category) {
+            logger::error(&format!(
+                "Rollback of {} after failed {} did not complete: {restore_err}",
+                snapshot.id,
+                phase.label()
+            ));
+            return anyhow!(
+                "Configure step {} failed: {err}; rollback from {} also failed: {restore_err}",
+                phase.label(),
+                snapshot.id
+            );
+        }
+    }
+    logger::warn(&format!(
+        "Configure step {} failed and was rolled back: {err}",
+        phase.label()
+    ));
+    anyhow!(
+        "Configure step {} failed and previous settings were restored: {err}",
+        phase.label()
+    )
 }
 
 pub fn switch_model(primary: &str, fallbacks: &[String]) -> Result<ConfigureResult> {
@@ -93,7 +350,7 @@ pub fn switch_model(primary: &str, fallbacks: &[String]) -> Result<ConfigureResu
         return Err(anyhow!("Primary model cannot be empty"));
     }
     let primary = model_identity::normalize_known_model_key(primary);
-    let mut warnings = Vec::<String>::new();
+    let mut warnings = Vec::<ResultWarning>::new();
     apply_model_chain(
         &ModelChain {
             primary: primary.clone(),
@@ -126,19 +383,25 @@ pub fn switch_model(primary: &str, fallbacks: &[String]) -> Result<ConfigureResu
 
 pub fn update_provider_api_key(provider: &str, api_key: &str) -> Result<String> {
     let provider_id = model_identity::normalize_auth_provider(provider);
-    let Some(env_name) = model_identity::provider_env_name(provider_id.as_str()) else {
+    let env_names = model_identity::provider_env_names(provider_id.as_str());
+    if env_names.is_empty() {
         return Err(anyhow!(
             "Provider '{}' cannot be converted to a valid API key environment variable.",
             provider
         ));
-    };
+    }
     let env_path = paths::openclaw_home().join(".env");
     if let Some(value) = optional_non_empty(Some(api_key.to_string())) {
-        let mut updates = BTreeMap::<String, String>::new();
-        updates.insert(env_name.clone(), sanitize_env_value(&value));
+        let sanitized = sanitize_env_value(&value);
+        let mut updates = env_names
+            .iter()
+            .map(|name| (name.clone(), sanitized.clone()))
+            .collect::<BTreeMap<_, _>>();
+        secrets::seal_env_values(&mut updates);
         upsert_env_file(&env_path, &updates)?;
     } else {
-        remove_env_keys(&env_path, &[env_name])?;
+        remove_env_keys(&env_path, &env_names)?;
+        secrets::forget(&env_names);
     }
 
     if let Ok(Some(mut last)) = state_store::load_last_config() {
@@ -165,7 +428,12 @@ pub fn update_provider_api_key(provider: &str, api_key: &str) -> Result<String>
 }
 
 pub fn read_current_config() -> Result<OpenClawFileConfig> {
-    let path = paths::config_path();
+    read_config_in(&paths::openclaw_home())
+}
+
+/// Like `read_current_config`, for the openclaw.json of `home` rather than the bound one.
+pub fn read_config_in(home: &Path) -> Result<OpenClawFileConfig> {
+    let path = home.join("openclaw.json");
     if !path.exists() {
         return Err(anyhow!("Config file not found: {}", path.to_string_lossy()));
     }
@@ -262,13 +530,14 @@ pub fn read_current_config() -> Result<OpenClawFileConfig> {
         port: if port == 0 { 28789 } else { port },
         install_dir: install
             .map(|v| v.install_dir)
-            .unwrap_or_else(|| paths::openclaw_home().to_string_lossy().to_string()),
+            .unwrap_or_else(|| home.to_string_lossy().to_string()),
         launch_args: if last.launch_args.trim().is_empty() {
             "gateway".to_string()
         } else {
             last.launch_args
         },
         updated_at,
+        provider_keys: Vec::new(),
     })
 }
 
@@ -281,7 +550,9 @@ pub fn reload_config() -> Result<String> {
     Ok("Configuration reloaded. If process is running, restart for full effect.".to_string())
 }
 
-fn run_onboard(payload: &OpenClawConfigInput, warnings: &mut Vec<String>) -> Result<()> {
+fn run_onboard(payload: &OpenClawConfigInput, warnings: &mut Vec<ResultWarning>) -> Result<()> {
+    // `--accept-risk` below speaks for the user; only pass it once they accepted.
+    consent::require_risk_acceptance()?;
     let flow = normalize_onboard_flow(&payload.onboarding_flow);
     let mode = normalize_onboard_mode(&payload.onboarding_mode);
     let node_manager = normalize_node_manager(&payload.node_manager);
@@ -324,25 +595,28 @@ fn run_onboard(payload: &OpenClawConfigInput, warnings: &mut Vec<String>) -> Res
             args.push("--install-daemon".to_string());
         } else {
             warnings.push(
-                "Install daemon requested but current process is not elevated; using --no-install-daemon."
-                    .to_string(),
+                ResultWarning::warning(
+                    "daemon_install_not_elevated",
+                    "Install daemon requested but current process is not elevated; using --no-install-daemon.",
+                )
+                .with_remediation("Run the installer as administrator to install the daemon."),
             );
             args.push("--no-install-daemon".to_string());
             if !effective_skip_health {
-                warnings.push(
-                    "Onboard health probe is skipped because daemon install was not allowed on this Windows session."
-                        .to_string(),
-                );
+                warnings.push(ResultWarning::info(
+                    "onboard_health_skipped",
+                    "Onboard health probe is skipped because daemon install was not allowed on this Windows session.",
+                ));
                 effective_skip_health = true;
             }
         }
     } else {
         args.push("--no-install-daemon".to_string());
         if !effective_skip_health {
-            warnings.push(
-                "Onboard health probe is skipped because daemon install is disabled. A separate health_check step still runs in installer."
-                    .to_string(),
-            );
+            warnings.push(ResultWarning::info(
+                "onboard_health_skipped",
+                "Onboard health probe is skipped because daemon install is disabled. A separate health_check step still runs in installer.",
+            ));
             effective_skip_health = true;
         }
     }
@@ -363,15 +637,21 @@ fn run_onboard(payload: &OpenClawConfigInput, warnings: &mut Vec<String>) -> Res
     let provider = resolve_provider(payload)?;
     let auth_provider = model_identity::normalize_auth_provider(&provider);
     let primary_key = provider_key_for_payload(payload, auth_provider.as_str())
-        .or_else(|| optional_non_empty(Some(payload.api_key.clone())));
+        .or_else(|| provided_key(payload.api_key.clone()));
     if primary_key.is_none() {
         args.push("--auth-choice".to_string());
         args.push("skip".to_string());
     } else if !AUTH_MAPPED_PROVIDERS.contains(&auth_provider.as_str()) {
-        warnings.push(format!(
-            "Provider '{}' is not mapped for non-interactive auth; skipped API key import.",
-            provider
-        ));
+        warnings.push(
+            ResultWarning::warning(
+                "provider_auth_unmapped",
+                format!(
+                    "Provider '{}' is not mapped for non-interactive auth; skipped API key import.",
+                    provider
+                ),
+            )
+            .with_provider(provider.clone()),
+        );
         args.push("--auth-choice".to_string());
         args.push("skip".to_string());
     } else {
@@ -458,6 +738,11 @@ fn run_onboard(payload: &OpenClawConfigInput, warnings: &mut Vec<String>) -> Res
                     primary_key.clone(),
                 ]);
             }
+            "azure" => {
+                // Onboard has no Azure auth choice: the key goes to .env in ApplyKeys
+                // and the provider entry is written in ApplyModels.
+                args.extend(["--auth-choice".to_string(), "skip".to_string()]);
+            }
             _ => {}
         }
     }
@@ -473,15 +758,20 @@ fn run_onboard(payload: &OpenClawConfigInput, warnings: &mut Vec<String>) -> Res
         out.stderr.clone()
     };
     if is_gateway_1006_error(&err_text) {
-        warnings.push(
-            "Onboard gateway probe failed (1006). Retrying with safer Windows flags.".to_string(),
-        );
+        warnings.push(ResultWarning::warning(
+            "onboard_gateway_1006",
+            "Onboard gateway probe failed (1006). Retrying with safer Windows flags.",
+        ));
         logger::warn("Onboard failed with 1006, retrying with safe fallback flags.");
         let retry_args = force_safe_onboard_retry_args(&args);
         let retry = run_openclaw_cli(&retry_args, payload.proxy.clone())?;
         if retry.code == 0 {
             warnings.push(
-                "Onboard recovered via fallback: --no-install-daemon --skip-health --skip-channels --skip-skills --flow manual".to_string(),
+                ResultWarning::warning(
+                    "onboard_fallback_used",
+                    "Onboard recovered via fallback: --no-install-daemon --skip-health --skip-channels --skip-skills --flow manual",
+                )
+                .with_remediation("Configure channels and skills from Maintenance after install."),
             );
             return Ok(());
         }
@@ -503,7 +793,7 @@ fn run_onboard(payload: &OpenClawConfigInput, warnings: &mut Vec<String>) -> Res
     Ok(())
 }
 
-fn apply_model_chain(model_chain: &ModelChain, warnings: &mut Vec<String>) -> Result<()> {
+fn apply_model_chain(model_chain: &ModelChain, warnings: &mut Vec<ResultWarning>) -> Result<()> {
     let primary = model_identity::normalize_known_model_key(model_chain.primary.as_str());
     if primary.trim().is_empty() {
         return Err(anyhow!("Primary model is required."));
@@ -539,10 +829,17 @@ fn apply_model_chain(model_chain: &ModelChain, warnings: &mut Vec<String>) -> Re
             None,
         )?;
         if out.code != 0 {
-            warnings.push(format!(
-                "Failed to add fallback model '{}': {}",
-                fallback, out.stderr
-            ));
+            let warning = ResultWarning::warning(
+                "fallback_model_add_failed",
+                format!(
+                    "Failed to add fallback model '{}': {}",
+                    fallback, out.stderr
+                ),
+            );
+            warnings.push(match model_identity::provider_from_model_key(&fallback) {
+                Some(provider) => warning.with_provider(provider),
+                None => warning,
+            });
         }
     }
     Ok(())
@@ -550,7 +847,7 @@ fn apply_model_chain(model_chain: &ModelChain, warnings: &mut Vec<String>) -> Re
 
 fn apply_kimi_region_base_url(
     payload: &OpenClawConfigInput,
-    warnings: &mut Vec<String>,
+    warnings: &mut Vec<ResultWarning>,
 ) -> Result<()> {
     let kimi_providers = providers_from_model_chain(&payload.model_chain)
         .into_iter()
@@ -561,10 +858,16 @@ fn apply_kimi_region_base_url(
     }
 
     let region = normalize_kimi_region(payload.kimi_region.trim()).unwrap_or_else(|| {
-        warnings.push(format!(
-            "Invalid kimi_region='{}'; fallback to '{}' (mainland).",
-            payload.kimi_region, KIMI_REGION_CN
-        ));
+        warnings.push(
+            ResultWarning::warning(
+                "kimi_region_invalid",
+                format!(
+                    "Invalid kimi_region='{}'; fallback to '{}' (mainland).",
+                    payload.kimi_region, KIMI_REGION_CN
+                ),
+            )
+            .with_provider("moonshot"),
+        );
         KIMI_REGION_CN.to_string()
     });
     let base_url = match region.as_str() {
@@ -593,10 +896,16 @@ fn apply_kimi_region_base_url(
             payload.proxy.clone(),
         )?;
         if out.code != 0 {
-            warnings.push(format!(
-                "Kimi baseUrl write failed ({path}): {}",
-                cli_output_text(&out)
-            ));
+            warnings.push(
+                ResultWarning::error(
+                    "kimi_base_url_write_failed",
+                    format!(
+                        "Kimi baseUrl write failed ({path}): {}",
+                        cli_output_text(&out)
+                    ),
+                )
+                .with_provider(provider.clone()),
+            );
         } else {
             logger::info(&format!(
                 "Kimi region baseUrl applied: provider={provider}, region={region}, baseUrl={base_url}"
@@ -607,54 +916,202 @@ fn apply_kimi_region_base_url(
     Ok(())
 }
 
-fn apply_provider_keys(payload: &OpenClawConfigInput, warnings: &mut Vec<String>) -> Result<()> {
+/// Writes `models.providers.azure` for the Azure OpenAI v1 endpoint of the resource,
+/// with the deployments as models. Azure takes the key in an `api-key` header; both it
+/// and the endpoint settings are read from `.env`.
+fn apply_azure_provider(
+    payload: &OpenClawConfigInput,
+    warnings: &mut Vec<ResultWarning>,
+) -> Result<()> {
+    let deployments = azure_deployments(payload);
+    if deployments.is_empty() && payload.azure_endpoint.trim().is_empty() {
+        return Ok(());
+    }
+    let endpoint = normalize_azure_endpoint(&payload.azure_endpoint)?;
+    if deployments.is_empty() {
+        return Err(anyhow!(
+            "Azure OpenAI needs a deployment name (the model id is azure/<deployment>)."
+        ));
+    }
+
+    let env_path = paths::openclaw_home().join(".env");
+    upsert_env_file(&env_path, &azure_env_entries(&endpoint, payload))?;
+
+    for (path, value) in azure_provider_writes(&endpoint, &deployments) {
+        let out = run_openclaw_cli(
+            &["config".to_string(), "set".to_string(), path.clone(), value],
+            payload.proxy.clone(),
+        )?;
+        if out.code != 0 {
+            warnings.push(
+                ResultWarning::error(
+                    "azure_provider_write_failed",
+                    format!(
+                        "Azure config write failed ({path}): {}",
+                        cli_output_text(&out)
+                    ),
+                )
+                .with_provider(AZURE_PROVIDER),
+            );
+        }
+    }
+    logger::info(&format!(
+        "Azure OpenAI provider applied: endpoint={endpoint}, deployments={}",
+        deployments.join(",")
+    ));
+    Ok(())
+}
+
+/// The explicit deployment first, then every `azure/<deployment>` of the model chain,
+/// each once.
+fn azure_deployments(payload: &OpenClawConfigInput) -> Vec<String> {
+    let mut seen = HashSet::<String>::new();
+    optional_non_empty(Some(payload.azure_deployment.clone()))
+        .into_iter()
+        .chain(
+            std::iter::once(&payload.model_chain.primary)
+                .chain(&payload.model_chain.fallbacks)
+                .filter(|key| model_identity::provider_from_model_key(key) == Some(AZURE_PROVIDER))
+                .filter_map(|key| key.split_once('/').map(|(_, id)| id.trim().to_string())),
+        )
+        .filter(|id| !id.is_empty() && seen.insert(id.clone()))
+        .collect()
+}
+
+/// `.env` settings for the resource. The v1 endpoint itself is unversioned; the
+/// `api-version` is kept for SDKs and tools that read it from the environment.
+fn azure_env_entries(endpoint: &str, payload: &OpenClawConfigInput) -> BTreeMap<String, String> {
+    let api_version = optional_non_empty(payload.azure_api_version.clone())
+        .unwrap_or_else(|| AZURE_DEFAULT_API_VERSION.to_string());
+    BTreeMap::from([
+        ("AZURE_OPENAI_ENDPOINT".to_string(), endpoint.to_string()),
+        ("AZURE_OPENAI_API_VERSION".to_string(), api_version),
+    ])
+}
+
+/// `openclaw config set` pairs for the provider entry.
+fn azure_provider_writes(endpoint: &str, deployments: &[String]) -> Vec<(String, String)> {
+    let key_ref = format!(
+        "${{{}}}",
+        model_identity::provider_env_name(AZURE_PROVIDER).unwrap_or_default()
+    );
+    let models = deployments
+        .iter()
+        .map(|id| json!({ "id": id, "name": id }))
+        .collect::<Vec<_>>();
+    let prefix = format!("models.providers.{AZURE_PROVIDER}");
+    vec![
+        ("models.mode".to_string(), "merge".to_string()),
+        (format!("{prefix}.baseUrl"), format!("{endpoint}/openai/v1")),
+        (format!("{prefix}.api"), "openai-completions".to_string()),
+        (format!("{prefix}.apiKey"), key_ref.clone()),
+        (format!("{prefix}.headers.api-key"), key_ref),
+        (format!("{prefix}.models"), json!(models).to_string()),
+    ]
+}
+
+/// `https://<resource>.openai.azure.com`, also when a full API URL was pasted.
+fn normalize_azure_endpoint(raw: &str) -> Result<String> {
+    let url = Url::parse(raw.trim())
+        .map_err(|_| anyhow!("Azure OpenAI endpoint is not a valid URL: '{}'", raw.trim()))?;
+    if url.scheme() != "https" {
+        return Err(anyhow!("Azure OpenAI endpoint must use https."));
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow!("Azure OpenAI endpoint has no host."))?;
+    Ok(match url.port() {
+        Some(port) => format!("https://{host}:{port}"),
+        None => format!("https://{host}"),
+    })
+}
+
+fn apply_provider_keys(
+    payload: &OpenClawConfigInput,
+    warnings: &mut Vec<ResultWarning>,
+) -> Result<()> {
     let mut env_values = BTreeMap::<String, String>::new();
     let mut unmapped = HashSet::<String>::new();
 
     for (provider, value) in &payload.provider_api_keys {
-        let Some(key_value) = optional_non_empty(Some(value.clone())) else {
+        let Some(key_value) = provided_key(value.clone()) else {
             continue;
         };
         let normalized = model_identity::normalize_auth_provider(provider);
-        if let Some(env_name) = model_identity::provider_env_name(normalized.as_str()) {
-            env_values.insert(env_name, sanitize_env_value(&key_value));
-        } else {
+        let env_names = model_identity::provider_env_names(normalized.as_str());
+        if env_names.is_empty() {
             unmapped.insert(provider.to_string());
         }
+        for env_name in env_names {
+            env_values.insert(env_name, sanitize_env_value(&key_value));
+        }
     }
 
     // Backward compatibility: if legacy single API key is set, keep binding it to primary provider.
-    if let Some(key_value) = optional_non_empty(Some(payload.api_key.clone())) {
+    if let Some(key_value) = provided_key(payload.api_key.clone()) {
         if let Ok(primary_provider) = resolve_provider(payload) {
             let normalized = model_identity::normalize_auth_provider(primary_provider.as_str());
-            if let Some(env_name) = model_identity::provider_env_name(normalized.as_str()) {
+            let env_names = model_identity::provider_env_names(normalized.as_str());
+            if env_names.is_empty() {
+                unmapped.insert(primary_provider);
+            }
+            for env_name in env_names {
                 env_values
                     .entry(env_name)
                     .or_insert_with(|| sanitize_env_value(&key_value));
-            } else {
-                unmapped.insert(primary_provider);
             }
         }
     }
 
+    let has_aws_keys = !payload.aws_access_key_id.trim().is_empty();
+    if has_aws_keys {
+        apply_aws_credentials(payload, &mut env_values)?;
+    }
+
     // Surface missing key hints for fallback providers so users can fix quickly.
     for provider in providers_from_model_chain(&payload.model_chain) {
+        if provider == bedrock::BEDROCK_PROVIDER {
+            if !has_aws_keys {
+                warnings.push(
+                    ResultWarning::warning(
+                        "bedrock_credentials_missing",
+                        "Bedrock models are in the model chain but no AWS access key is configured.",
+                    )
+                    .with_remediation("Enter an AWS access key ID, secret and region for Bedrock.")
+                    .with_provider(provider.clone()),
+                );
+            }
+            continue;
+        }
         if provider_key_for_payload(payload, provider.as_str()).is_some() {
             continue;
         }
         if model_identity::provider_env_name(provider.as_str()).is_some() {
-            warnings.push(format!(
-                "No API key configured for provider '{}' in model chain; fallback calls to this provider may fail.",
-                provider
-            ));
+            warnings.push(
+                ResultWarning::warning(
+                    "provider_key_missing",
+                    format!(
+                        "No API key configured for provider '{}' in model chain; fallback calls to this provider may fail.",
+                        provider
+                    ),
+                )
+                .with_remediation("Add an API key for this provider, or remove it from the model chain.")
+                .with_provider(provider.clone()),
+            );
         }
     }
 
     for provider in unmapped {
-        warnings.push(format!(
-            "Provider '{}' has no known environment variable mapping; key was not written to .env.",
-            provider
-        ));
+        warnings.push(
+            ResultWarning::warning(
+                "provider_env_unmapped",
+                format!(
+                    "Provider '{}' has no known environment variable mapping; key was not written to .env.",
+                    provider
+                ),
+            )
+            .with_provider(provider.clone()),
+        );
     }
 
     if env_values.is_empty() {
@@ -662,6 +1119,7 @@ fn apply_provider_keys(payload: &OpenClawConfigInput, warnings: &mut Vec<String>
     }
 
     let env_path = paths::openclaw_home().join(".env");
+    secrets::seal_env_values(&mut env_values);
     upsert_env_file(&env_path, &env_values)?;
     logger::info(&format!(
         "Updated provider API keys in {} ({} entries).",
@@ -671,7 +1129,40 @@ fn apply_provider_keys(payload: &OpenClawConfigInput, warnings: &mut Vec<String>
     Ok(())
 }
 
-fn apply_feature_toggles(payload: &OpenClawConfigInput, warnings: &mut Vec<String>) -> Result<()> {
+/// Bedrock authenticates with an AWS key pair rather than an API key. The pair joins
+/// the sealed provider keys; the region is not secret and is written as is. A masked
+/// secret (reconfigure without retyping it) keeps the stored one.
+fn apply_aws_credentials(
+    payload: &OpenClawConfigInput,
+    env_values: &mut BTreeMap<String, String>,
+) -> Result<()> {
+    let access_key_id = payload.aws_access_key_id.trim();
+    let region = optional_non_empty(Some(payload.aws_region.clone()))
+        .unwrap_or_else(|| bedrock::DEFAULT_REGION.to_string());
+    if let Some(secret) = provided_key(payload.aws_secret_access_key.clone()) {
+        bedrock::validate_credentials(access_key_id, &secret, &region)?;
+        env_values.insert(
+            "AWS_SECRET_ACCESS_KEY".to_string(),
+            sanitize_env_value(&secret),
+        );
+    }
+    env_values.insert(
+        "AWS_ACCESS_KEY_ID".to_string(),
+        sanitize_env_value(access_key_id),
+    );
+    upsert_env_file(
+        &paths::openclaw_home().join(".env"),
+        &BTreeMap::from([
+            ("AWS_REGION".to_string(), region.clone()),
+            ("AWS_DEFAULT_REGION".to_string(), region),
+        ]),
+    )
+}
+
+fn apply_feature_toggles(
+    payload: &OpenClawConfigInput,
+    warnings: &mut Vec<ResultWarning>,
+) -> Result<()> {
     let hook_command = if payload.enable_session_memory_hook {
         vec![
             "hooks".to_string(),
@@ -687,13 +1178,16 @@ fn apply_feature_toggles(payload: &OpenClawConfigInput, warnings: &mut Vec<Strin
     };
     let hook_out = run_openclaw_cli(&hook_command, payload.proxy.clone())?;
     if hook_out.code != 0 {
-        warnings.push(format!(
-            "Hook update failed (session-memory): {}",
-            if hook_out.stderr.is_empty() {
-                hook_out.stdout
-            } else {
-                hook_out.stderr
-            }
+        warnings.push(ResultWarning::error(
+            "hook_update_failed",
+            format!(
+                "Hook update failed (session-memory): {}",
+                if hook_out.stderr.is_empty() {
+                    hook_out.stdout
+                } else {
+                    hook_out.stderr
+                }
+            ),
         ));
     }
 
@@ -718,13 +1212,16 @@ fn apply_feature_toggles(payload: &OpenClawConfigInput, warnings: &mut Vec<Strin
         if skills_out.code == 0 {
             logger::info("Skills check completed.");
         } else {
-            warnings.push(format!(
-                "Skills check failed: {}",
-                if skills_out.stderr.is_empty() {
-                    skills_out.stdout
-                } else {
-                    skills_out.stderr
-                }
+            warnings.push(ResultWarning::warning(
+                "skills_check_failed",
+                format!(
+                    "Skills check failed: {}",
+                    if skills_out.stderr.is_empty() {
+                        skills_out.stdout
+                    } else {
+                        skills_out.stderr
+                    }
+                ),
             ));
         }
     }
@@ -732,7 +1229,10 @@ fn apply_feature_toggles(payload: &OpenClawConfigInput, warnings: &mut Vec<Strin
     Ok(())
 }
 
-fn apply_selected_skills(payload: &OpenClawConfigInput, warnings: &mut Vec<String>) -> Result<()> {
+fn apply_selected_skills(
+    payload: &OpenClawConfigInput,
+    warnings: &mut Vec<ResultWarning>,
+) -> Result<()> {
     let selected = normalize_selected_skills(&payload.selected_skills);
     if selected.is_empty() {
         return Ok(());
@@ -740,18 +1240,20 @@ fn apply_selected_skills(payload: &OpenClawConfigInput, warnings: &mut Vec<Strin
 
     let config_path = paths::config_path();
     if !config_path.exists() {
-        warnings.push(
-            "Cannot apply selected skills because openclaw.json does not exist yet.".to_string(),
-        );
+        warnings.push(ResultWarning::error(
+            "skills_config_missing",
+            "Cannot apply selected skills because openclaw.json does not exist yet.",
+        ));
         return Ok(());
     }
 
     let raw = fs::read_to_string(&config_path)?;
     let mut root: Value = serde_json::from_str(&raw)?;
     if !root.is_object() {
-        warnings.push(
-            "openclaw.json has unexpected schema; skipped selected skills apply.".to_string(),
-        );
+        warnings.push(ResultWarning::error(
+            "skills_config_invalid",
+            "openclaw.json has unexpected schema; skipped selected skills apply.",
+        ));
         return Ok(());
     }
 
@@ -767,7 +1269,8 @@ fn apply_selected_skills(payload: &OpenClawConfigInput, warnings: &mut Vec<Strin
         root["skills"]["entries"][name]["enabled"] = Value::Bool(true);
     }
 
-    fs::write(&config_path, serde_json::to_string_pretty(&root)?)?;
+    json_layout::write_preserving_layout(&config_path, &root)?;
+    skills::clear_skill_catalog_cache();
     logger::info(&format!(
         "Applied selected bundled skills to config: {}",
         selected.join(", ")
@@ -782,7 +1285,10 @@ fn apply_selected_skills(payload: &OpenClawConfigInput, warnings: &mut Vec<Strin
         payload.proxy.clone(),
     )?;
     if list_out.code != 0 {
-        warnings.push("Failed to verify selected skills (skills list command failed).".to_string());
+        warnings.push(ResultWarning::warning(
+            "skills_verify_failed",
+            "Failed to verify selected skills (skills list command failed).",
+        ));
         return Ok(());
     }
     let parsed: Value =
@@ -795,9 +1301,12 @@ fn apply_selected_skills(payload: &OpenClawConfigInput, warnings: &mut Vec<Strin
             entry.get("name").and_then(|v| v.as_str()) == Some(selected_name.as_str())
         });
         let Some(item) = item else {
-            warnings.push(format!(
-                "Selected skill '{}' was not found in current OpenClaw skill catalog.",
-                selected_name
+            warnings.push(ResultWarning::warning(
+                "skill_not_found",
+                format!(
+                    "Selected skill '{}' was not found in current OpenClaw skill catalog.",
+                    selected_name
+                ),
             ));
             continue;
         };
@@ -809,10 +1318,16 @@ fn apply_selected_skills(payload: &OpenClawConfigInput, warnings: &mut Vec<Strin
             continue;
         }
         let missing = item.get("missing").cloned().unwrap_or_else(|| json!({}));
-        warnings.push(format!(
-            "Skill '{}' is selected but not ready. Missing requirements: {}",
-            selected_name, missing
-        ));
+        warnings.push(
+            ResultWarning::warning(
+                "skill_not_ready",
+                format!(
+                    "Skill '{}' is selected but not ready. Missing requirements: {}",
+                    selected_name, missing
+                ),
+            )
+            .with_remediation("Install the missing requirements, then run skills check again."),
+        );
     }
 
     Ok(())
@@ -849,78 +1364,25 @@ fn parse_json_value_from_cli_output(raw: &str) -> Option<Value> {
 }
 
 fn upsert_env_file(path: &Path, entries: &BTreeMap<String, String>) -> Result<()> {
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)?;
-    }
-    let existing = if path.exists() {
-        fs::read_to_string(path)?
-    } else {
-        String::new()
-    };
-
-    let mut out_lines = Vec::<String>::new();
-    let mut touched = HashSet::<String>::new();
-
-    for line in existing.lines() {
-        let Some((key_raw, _)) = line.split_once('=') else {
-            out_lines.push(line.to_string());
-            continue;
-        };
-        let key = key_raw.trim();
-        if let Some(next_value) = entries.get(key) {
-            out_lines.push(format!("{key}={next_value}"));
-            touched.insert(key.to_string());
-        } else {
-            out_lines.push(line.to_string());
-        }
-    }
-
+    let mut env = dotenv::EnvFile::load(path)?;
     for (key, value) in entries {
-        if touched.contains(key) {
-            continue;
-        }
-        out_lines.push(format!("{key}={value}"));
-    }
-
-    let mut content = out_lines.join("\n");
-    if !content.ends_with('\n') {
-        content.push('\n');
+        env.set(key, value);
     }
-    fs::write(path, content)?;
-    Ok(())
+    env.save(path)
 }
 
 fn remove_env_keys(path: &Path, keys: &[String]) -> Result<()> {
     if !path.exists() {
         return Ok(());
     }
-    let existing = fs::read_to_string(path)?;
-    let keyset = keys
-        .iter()
-        .map(|k| k.trim().to_string())
-        .filter(|k| !k.is_empty())
-        .collect::<HashSet<_>>();
-    if keyset.is_empty() {
-        return Ok(());
-    }
-
-    let mut lines = Vec::<String>::new();
-    for line in existing.lines() {
-        let Some((key_raw, _)) = line.split_once('=') else {
-            lines.push(line.to_string());
-            continue;
-        };
-        let key = key_raw.trim();
-        if keyset.contains(key) {
-            continue;
-        }
-        lines.push(line.to_string());
+    let mut env = dotenv::EnvFile::load(path)?;
+    let mut removed = false;
+    for key in keys.iter().map(|k| k.trim()).filter(|k| !k.is_empty()) {
+        removed |= env.remove(key);
     }
-    let mut content = lines.join("\n");
-    if !content.is_empty() && !content.ends_with('\n') {
-        content.push('\n');
+    if removed {
+        env.save(path)?;
     }
-    fs::write(path, content)?;
     Ok(())
 }
 
@@ -940,16 +1402,24 @@ fn normalize_selected_skills(skills: &[String]) -> Vec<String> {
 
 fn apply_channel_integrations(
     payload: &OpenClawConfigInput,
-    warnings: &mut Vec<String>,
+    warnings: &mut Vec<ResultWarning>,
 ) -> Result<()> {
     apply_feishu_integration(payload, warnings)?;
+    apply_dingtalk_integration(payload, warnings)?;
+    apply_discord_integration(payload, warnings)?;
 
     if !payload.enable_telegram_channel {
         return Ok(());
     }
     if payload.telegram_bot_token.trim().is_empty() {
-        warnings
-            .push("Telegram enabled but bot token is empty; skipped Telegram setup.".to_string());
+        warnings.push(
+            ResultWarning::warning(
+                "channel_token_missing",
+                "Telegram enabled but bot token is empty; skipped Telegram setup.",
+            )
+            .with_remediation("Enter the bot token from @BotFather.")
+            .with_channel("telegram"),
+        );
         return Ok(());
     }
 
@@ -964,8 +1434,11 @@ fn apply_channel_integrations(
     if !payload.telegram_pair_code.trim().is_empty() {
         // Telegram pair code is intentionally postponed to post-install maintenance flow.
         warnings.push(
-            "Telegram pair code is deferred. Finish install first, then apply pair code from Maintenance."
-                .to_string(),
+            ResultWarning::info(
+                "telegram_pair_deferred",
+                "Telegram pair code is deferred. Finish install first, then apply pair code from Maintenance.",
+            )
+            .with_channel("telegram"),
         );
     }
 
@@ -991,21 +1464,27 @@ fn apply_channel_integrations(
     if out.code == 0 {
         logger::info("Telegram channel configured successfully.");
     } else {
-        warnings.push(format!(
-            "Telegram setup failed: {}",
-            if out.stderr.is_empty() {
-                out.stdout
-            } else {
-                out.stderr
-            }
-        ));
+        warnings.push(
+            ResultWarning::error(
+                "channel_setup_failed",
+                format!(
+                    "Telegram setup failed: {}",
+                    if out.stderr.is_empty() {
+                        out.stdout
+                    } else {
+                        out.stderr
+                    }
+                ),
+            )
+            .with_channel("telegram"),
+        );
     }
     Ok(())
 }
 
 fn apply_feishu_integration(
     payload: &OpenClawConfigInput,
-    warnings: &mut Vec<String>,
+    warnings: &mut Vec<ResultWarning>,
 ) -> Result<()> {
     if !payload.enable_feishu_channel {
         return Ok(());
@@ -1015,7 +1494,12 @@ fn apply_feishu_integration(
     let app_secret = payload.feishu_app_secret.trim();
     if app_id.is_empty() || app_secret.is_empty() {
         warnings.push(
-            "Feishu enabled but app_id/app_secret is empty; skipped Feishu setup.".to_string(),
+            ResultWarning::warning(
+                "channel_token_missing",
+                "Feishu enabled but app_id/app_secret is empty; skipped Feishu setup.",
+            )
+            .with_remediation("Enter the app id and secret from the Feishu developer console.")
+            .with_channel("feishu"),
         );
         return Ok(());
     }
@@ -1027,10 +1511,16 @@ fn apply_feishu_integration(
     ];
     let plugin_enable_out = run_openclaw_cli(&plugin_enable_args, payload.proxy.clone())?;
     if plugin_enable_out.code != 0 {
-        warnings.push(format!(
-            "Feishu plugin enable failed: {}",
-            redact_known_values(cli_output_text(&plugin_enable_out), &[app_secret])
-        ));
+        warnings.push(
+            ResultWarning::warning(
+                "channel_plugin_enable_failed",
+                format!(
+                    "Feishu plugin enable failed: {}",
+                    redact_known_values(cli_output_text(&plugin_enable_out), &[app_secret])
+                ),
+            )
+            .with_channel("feishu"),
+        );
     }
 
     let add_channel_args = vec![
@@ -1049,10 +1539,16 @@ fn apply_feishu_integration(
         add_channel_out = run_openclaw_cli(&add_channel_args, payload.proxy.clone())?;
     }
     if add_channel_out.code != 0 {
-        warnings.push(format!(
-            "Feishu setup failed (channels add): {}",
-            redact_known_values(cli_output_text(&add_channel_out), &[app_secret])
-        ));
+        warnings.push(
+            ResultWarning::error(
+                "channel_setup_failed",
+                format!(
+                    "Feishu setup failed (channels add): {}",
+                    redact_known_values(cli_output_text(&add_channel_out), &[app_secret])
+                ),
+            )
+            .with_channel("feishu"),
+        );
         return Ok(());
     }
 
@@ -1074,10 +1570,16 @@ fn apply_feishu_integration(
             payload.proxy.clone(),
         )?;
         if out.code != 0 {
-            warnings.push(format!(
-                "Feishu config write failed ({path}): {}",
-                redact_known_values(cli_output_text(&out), &[app_secret])
-            ));
+            warnings.push(
+                ResultWarning::error(
+                    "channel_config_write_failed",
+                    format!(
+                        "Feishu config write failed ({path}): {}",
+                        redact_known_values(cli_output_text(&out), &[app_secret])
+                    ),
+                )
+                .with_channel("feishu"),
+            );
         }
     }
 
@@ -1086,10 +1588,17 @@ fn apply_feishu_integration(
         payload.proxy.clone(),
     )?;
     if restart_out.code != 0 {
-        warnings.push(format!(
-            "Feishu gateway restart failed: {}",
-            redact_known_values(cli_output_text(&restart_out), &[app_secret])
-        ));
+        warnings.push(
+            ResultWarning::warning(
+                "gateway_restart_failed",
+                format!(
+                    "Feishu gateway restart failed: {}",
+                    redact_known_values(cli_output_text(&restart_out), &[app_secret])
+                ),
+            )
+            .with_remediation("Restart the gateway from Maintenance.")
+            .with_channel("feishu"),
+        );
     } else {
         logger::info("Feishu channel configured successfully (china direct websocket).");
     }
@@ -1097,133 +1606,329 @@ fn apply_feishu_integration(
     Ok(())
 }
 
-pub fn setup_telegram_pair(pair_code: &str) -> Result<String> {
-    let code = pair_code.trim();
-    if code.is_empty() {
-        return Err(anyhow!("Telegram pair code cannot be empty."));
+/// Same flow as Feishu: the DingTalk robot connects out over Stream mode (a
+/// websocket), so no public callback URL is needed.
+fn apply_dingtalk_integration(
+    payload: &OpenClawConfigInput,
+    warnings: &mut Vec<ResultWarning>,
+) -> Result<()> {
+    if !payload.enable_dingtalk_channel {
+        return Ok(());
     }
 
-    let Some(last) = state_store::load_last_config()? else {
-        return Err(anyhow!(
-            "No saved install config found. Complete installation first."
-        ));
-    };
-    let args = vec![
-        "pairing".to_string(),
-        "approve".to_string(),
-        "telegram".to_string(),
-        code.to_string(),
+    let app_key = payload.dingtalk_app_key.trim();
+    let app_secret = payload.dingtalk_app_secret.trim();
+    if app_key.is_empty() || app_secret.is_empty() {
+        warnings.push(
+            ResultWarning::warning(
+                "channel_token_missing",
+                "DingTalk enabled but app_key/app_secret is empty; skipped DingTalk setup.",
+            )
+            .with_remediation(
+                "Enter the AppKey and AppSecret from the DingTalk developer console (open.dingtalk.com).",
+            )
+            .with_channel("dingtalk"),
+        );
+        return Ok(());
+    }
+
+    let plugin_enable_args = vec![
+        "plugins".to_string(),
+        "enable".to_string(),
+        "dingtalk".to_string(),
     ];
-    let mut out = run_openclaw_cli(&args, last.proxy.clone())?;
-    if out.code != 0 && is_unknown_channel_error(&out, "telegram") {
+    let plugin_enable_out = run_openclaw_cli(&plugin_enable_args, payload.proxy.clone())?;
+    if plugin_enable_out.code != 0 {
+        warnings.push(
+            ResultWarning::warning(
+                "channel_plugin_enable_failed",
+                format!(
+                    "DingTalk plugin enable failed: {}",
+                    redact_known_values(cli_output_text(&plugin_enable_out), &[app_secret])
+                ),
+            )
+            .with_channel("dingtalk"),
+        );
+    }
+
+    let add_channel_args = vec![
+        "channels".to_string(),
+        "add".to_string(),
+        "--channel".to_string(),
+        "dingtalk".to_string(),
+    ];
+    let mut add_channel_out = run_openclaw_cli(&add_channel_args, payload.proxy.clone())?;
+    if add_channel_out.code != 0 && is_unknown_channel_error(&add_channel_out, "dingtalk") {
+        let _ = run_openclaw_cli(&plugin_enable_args, payload.proxy.clone());
         let _ = run_openclaw_cli(
+            &["gateway".to_string(), "restart".to_string()],
+            payload.proxy.clone(),
+        );
+        add_channel_out = run_openclaw_cli(&add_channel_args, payload.proxy.clone())?;
+    }
+    if add_channel_out.code != 0 {
+        warnings.push(
+            ResultWarning::error(
+                "channel_setup_failed",
+                format!(
+                    "DingTalk setup failed (channels add): {}",
+                    redact_known_values(cli_output_text(&add_channel_out), &[app_secret])
+                ),
+            )
+            .with_channel("dingtalk"),
+        );
+        return Ok(());
+    }
+
+    let writes = vec![
+        ("channels.dingtalk.enabled", "true".to_string()),
+        ("channels.dingtalk.appKey", app_key.to_string()),
+        ("channels.dingtalk.appSecret", app_secret.to_string()),
+        ("channels.dingtalk.connectionMode", "websocket".to_string()),
+    ];
+    for (path, value) in writes {
+        let out = run_openclaw_cli(
             &[
-                "plugins".to_string(),
-                "enable".to_string(),
-                "telegram".to_string(),
+                "config".to_string(),
+                "set".to_string(),
+                path.to_string(),
+                value,
             ],
-            last.proxy.clone(),
+            payload.proxy.clone(),
+        )?;
+        if out.code != 0 {
+            warnings.push(
+                ResultWarning::error(
+                    "channel_config_write_failed",
+                    format!(
+                        "DingTalk config write failed ({path}): {}",
+                        redact_known_values(cli_output_text(&out), &[app_secret])
+                    ),
+                )
+                .with_channel("dingtalk"),
+            );
+        }
+    }
+
+    let restart_out = run_openclaw_cli(
+        &["gateway".to_string(), "restart".to_string()],
+        payload.proxy.clone(),
+    )?;
+    if restart_out.code != 0 {
+        warnings.push(
+            ResultWarning::warning(
+                "gateway_restart_failed",
+                format!(
+                    "DingTalk gateway restart failed: {}",
+                    redact_known_values(cli_output_text(&restart_out), &[app_secret])
+                ),
+            )
+            .with_remediation("Restart the gateway from Maintenance.")
+            .with_channel("dingtalk"),
         );
+    } else {
+        logger::info("DingTalk channel configured successfully (stream websocket).");
+    }
+
+    Ok(())
+}
+
+/// Enables the Discord plugin, registers the bot token, restricts it to the chosen
+/// server/channel and restarts the gateway. Mirrors the Feishu flow.
+fn apply_discord_integration(
+    payload: &OpenClawConfigInput,
+    warnings: &mut Vec<ResultWarning>,
+) -> Result<()> {
+    if !payload.enable_discord_channel {
+        return Ok(());
+    }
+    let token = payload.discord_bot_token.trim();
+    if token.is_empty() {
+        warnings.push(
+            ResultWarning::warning(
+                "channel_token_missing",
+                "Discord enabled but bot token is empty; skipped Discord setup.",
+            )
+            .with_remediation(
+                "Copy the bot token from the Discord Developer Portal (Bot > Reset Token).",
+            )
+            .with_channel("discord"),
+        );
+        return Ok(());
+    }
+
+    let plugin_enable_args = vec![
+        "plugins".to_string(),
+        "enable".to_string(),
+        "discord".to_string(),
+    ];
+    let plugin_enable_out = run_openclaw_cli(&plugin_enable_args, payload.proxy.clone())?;
+    if plugin_enable_out.code != 0 {
+        warnings.push(
+            ResultWarning::warning(
+                "channel_plugin_enable_failed",
+                format!(
+                    "Discord plugin enable failed: {}",
+                    redact_known_values(cli_output_text(&plugin_enable_out), &[token])
+                ),
+            )
+            .with_channel("discord"),
+        );
+    }
+
+    let add_channel_args = vec![
+        "channels".to_string(),
+        "add".to_string(),
+        "--channel".to_string(),
+        "discord".to_string(),
+        "--token".to_string(),
+        token.to_string(),
+    ];
+    let mut add_channel_out = run_openclaw_cli(&add_channel_args, payload.proxy.clone())?;
+    if add_channel_out.code != 0 && is_unknown_channel_error(&add_channel_out, "discord") {
+        let _ = run_openclaw_cli(&plugin_enable_args, payload.proxy.clone());
         let _ = run_openclaw_cli(
             &["gateway".to_string(), "restart".to_string()],
-            last.proxy.clone(),
+            payload.proxy.clone(),
         );
-        out = run_openclaw_cli(&args, last.proxy.clone())?;
+        add_channel_out = run_openclaw_cli(&add_channel_args, payload.proxy.clone())?;
     }
-    if out.code == 0 && is_pairing_output_error(&out) {
-        out.code = 1;
+    if add_channel_out.code != 0 {
+        warnings.push(
+            ResultWarning::error(
+                "channel_setup_failed",
+                format!(
+                    "Discord setup failed (channels add): {}",
+                    redact_known_values(cli_output_text(&add_channel_out), &[token])
+                ),
+            )
+            .with_remediation(
+                "Check the token and that the bot has the Message Content intent enabled.",
+            )
+            .with_channel("discord"),
+        );
+        return Ok(());
     }
 
-    if out.code == 0 {
-        logger::info("Telegram pairing approved from maintenance.");
-        return Ok(format!("Telegram pairing approved: {code}"));
+    for (path, value) in discord_config_writes(payload) {
+        let out = run_openclaw_cli(
+            &["config".to_string(), "set".to_string(), path.clone(), value],
+            payload.proxy.clone(),
+        )?;
+        if out.code != 0 {
+            warnings.push(
+                ResultWarning::error(
+                    "channel_config_write_failed",
+                    format!(
+                        "Discord config write failed ({path}): {}",
+                        redact_known_values(cli_output_text(&out), &[token])
+                    ),
+                )
+                .with_channel("discord"),
+            );
+        }
     }
 
-    if is_unknown_command_error(&out, "pairing") {
-        logger::warn(
-            "OpenClaw CLI does not support `pairing` command; fallback to legacy Telegram account pairing.",
+    let restart_out = run_openclaw_cli(
+        &["gateway".to_string(), "restart".to_string()],
+        payload.proxy.clone(),
+    )?;
+    if restart_out.code != 0 {
+        warnings.push(
+            ResultWarning::warning(
+                "gateway_restart_failed",
+                format!(
+                    "Discord gateway restart failed: {}",
+                    redact_known_values(cli_output_text(&restart_out), &[token])
+                ),
+            )
+            .with_remediation("Restart the gateway from Maintenance.")
+            .with_channel("discord"),
         );
-        return setup_telegram_pair_legacy(code, &last);
+    } else {
+        logger::info("Discord channel configured successfully.");
     }
+    Ok(())
+}
 
-    shell::ensure_success("openclaw pairing approve telegram", &out)?;
-    Ok(format!("Telegram pairing approved: {code}"))
+/// With a server id the bot only answers there (allowlist policy); a channel id narrows
+/// it further to that one channel.
+fn discord_config_writes(payload: &OpenClawConfigInput) -> Vec<(String, String)> {
+    let mut writes = vec![("channels.discord.enabled".to_string(), "true".to_string())];
+    let guild = payload.discord_guild_id.trim();
+    if guild.is_empty() {
+        return writes;
+    }
+    writes.push((
+        "channels.discord.groupPolicy".to_string(),
+        "allowlist".to_string(),
+    ));
+    let channel = payload.discord_channel_id.trim();
+    if channel.is_empty() {
+        writes.push((
+            format!("channels.discord.guilds.{guild}.requireMention"),
+            "true".to_string(),
+        ));
+    } else {
+        writes.push((
+            format!("channels.discord.guilds.{guild}.channels.{channel}.allow"),
+            "true".to_string(),
+        ));
+    }
+    writes
 }
 
-fn is_unknown_channel_error(out: &shell::CmdOutput, channel: &str) -> bool {
-    let merged = format!(
-        "{}\n{}",
-        out.stdout.to_ascii_lowercase(),
-        out.stderr.to_ascii_lowercase()
-    );
-    merged.contains("unknown channel") && merged.contains(&channel.to_ascii_lowercase())
+fn is_discord_snowflake(value: &str) -> bool {
+    let value = value.trim();
+    value.is_empty() || (value.len() <= 20 && value.chars().all(|c| c.is_ascii_digit()))
 }
 
-fn is_unknown_command_error(out: &shell::CmdOutput, command: &str) -> bool {
+/// Re-runs the Discord setup from Maintenance, e.g. after the token was reset in the
+/// Developer Portal. A new token replaces the saved one.
+pub fn repair_discord_channel(bot_token: Option<String>) -> Result<Vec<ResultWarning>> {
+    let Some(mut last) = state_store::load_last_config()? else {
+        return Err(anyhow!(
+            "No saved install config found. Complete installation first."
+        ));
+    };
+    if let Some(token) = optional_non_empty(bot_token) {
+        last.discord_bot_token = token;
+        last.enable_discord_channel = true;
+    }
+    if !last.enable_discord_channel {
+        return Err(anyhow!(
+            "Discord is not enabled. Enter a bot token to set it up."
+        ));
+    }
+    validate_payload(&last)?;
+    let mut warnings = Vec::<ResultWarning>::new();
+    apply_discord_integration(&last, &mut warnings)?;
+    state_store::save_last_config(&last)?;
+    Ok(warnings)
+}
+
+pub fn is_unknown_channel_error(out: &shell::CmdOutput, channel: &str) -> bool {
     let merged = format!(
         "{}\n{}",
         out.stdout.to_ascii_lowercase(),
         out.stderr.to_ascii_lowercase()
     );
-    merged.contains("unknown command") && merged.contains(&command.to_ascii_lowercase())
+    merged.contains("unknown channel") && merged.contains(&channel.to_ascii_lowercase())
 }
 
-fn is_pairing_output_error(out: &shell::CmdOutput) -> bool {
+pub fn is_unknown_command_error(out: &shell::CmdOutput, command: &str) -> bool {
     let merged = format!(
         "{}\n{}",
         out.stdout.to_ascii_lowercase(),
         out.stderr.to_ascii_lowercase()
     );
-    merged.contains("failed to start cli:")
-        || merged.contains("no pending pairing request found")
-        || (merged.contains("pairing") && merged.contains("error"))
-}
-
-fn setup_telegram_pair_legacy(code: &str, payload: &OpenClawConfigInput) -> Result<String> {
-    let token = payload.telegram_bot_token.trim();
-    if token.is_empty() {
-        return Err(anyhow!(
-            "Telegram bot token is missing. Re-run install wizard and enable Telegram first."
-        ));
-    }
-
-    let args = vec![
-        "channels".to_string(),
-        "add".to_string(),
-        "--channel".to_string(),
-        "telegram".to_string(),
-        "--token".to_string(),
-        token.to_string(),
-        "--account".to_string(),
-        code.to_string(),
-    ];
-    let mut out = run_openclaw_cli(&args, payload.proxy.clone())?;
-    if out.code != 0 && is_unknown_channel_error(&out, "telegram") {
-        let _ = run_openclaw_cli(
-            &[
-                "plugins".to_string(),
-                "enable".to_string(),
-                "telegram".to_string(),
-            ],
-            payload.proxy.clone(),
-        );
-        let _ = run_openclaw_cli(
-            &["gateway".to_string(), "restart".to_string()],
-            payload.proxy.clone(),
-        );
-        out = run_openclaw_cli(&args, payload.proxy.clone())?;
-    }
-    shell::ensure_success("openclaw channels add telegram account (legacy)", &out)?;
-    logger::info("Telegram pairing applied via legacy account fallback.");
-    Ok(format!(
-        "Telegram pairing applied (legacy fallback): {code}"
-    ))
+    merged.contains("unknown command") && merged.contains(&command.to_ascii_lowercase())
 }
 
-fn run_openclaw_cli(args: &[String], proxy: Option<String>) -> Result<shell::CmdOutput> {
+pub fn run_openclaw_cli(args: &[String], proxy: Option<String>) -> Result<shell::CmdOutput> {
     let install = state_store::load_install_state()?
         .ok_or_else(|| anyhow!("Install state not found. Run install_openclaw first."))?;
-    let command_path = resolve_working_cli_command(&install.command_path)?;
+    let command_path = resolve_working_cli_command(&install)?;
 
     let mut envs = vec![
         (
@@ -1285,6 +1990,8 @@ fn validate_payload(payload: &OpenClawConfigInput) -> Result<()> {
             install_dir.to_string_lossy()
         ));
     }
+    paths::ensure_local_install_dir(&install_dir)?;
+    paths::ensure_spawn_compatible(&install_dir)?;
 
     let provider = resolve_provider(payload)?;
     if provider.trim().is_empty() {
@@ -1296,6 +2003,13 @@ fn validate_payload(payload: &OpenClawConfigInput) -> Result<()> {
     if payload.port == 0 {
         return Err(anyhow!("Port must be within 1-65535"));
     }
+    if let Some(owner) = profiles::port_owner(payload.port) {
+        return Err(anyhow!(
+            "Port {} is used by the gateway of profile '{owner}'. This profile's port is {}.",
+            payload.port,
+            profiles::assigned_port()
+        ));
+    }
     if payload.bind_address.trim().is_empty() {
         return Err(anyhow!("Bind address cannot be empty."));
     }
@@ -1308,11 +2022,14 @@ fn validate_payload(payload: &OpenClawConfigInput) -> Result<()> {
     if normalize_kimi_region(payload.kimi_region.trim()).is_none() {
         return Err(anyhow!("kimi_region must be cn|global"));
     }
+    validate_azure(payload)?;
+    validate_dingtalk(payload)?;
     if payload.enable_telegram_channel && payload.telegram_bot_token.trim().is_empty() {
         return Err(anyhow!(
             "Telegram bot token is required when Telegram channel is enabled."
         ));
     }
+    validate_discord(payload)?;
     if !matches!(
         payload.onboarding_flow.trim(),
         "quickstart" | "advanced" | "manual"
@@ -1335,7 +2052,80 @@ fn validate_payload(payload: &OpenClawConfigInput) -> Result<()> {
     Ok(())
 }
 
-fn optional_non_empty(value: Option<String>) -> Option<String> {
+fn validate_discord(payload: &OpenClawConfigInput) -> Result<()> {
+    if !payload.enable_discord_channel {
+        return Ok(());
+    }
+    if payload.discord_bot_token.trim().is_empty() {
+        return Err(anyhow!(
+            "Discord bot token is required when Discord channel is enabled."
+        ));
+    }
+    for (name, value) in [
+        ("discord_guild_id", &payload.discord_guild_id),
+        ("discord_channel_id", &payload.discord_channel_id),
+    ] {
+        if !is_discord_snowflake(value) {
+            return Err(anyhow!(
+                "{name} must be a numeric Discord id (Developer Mode > Copy ID)."
+            ));
+        }
+    }
+    if payload.discord_guild_id.trim().is_empty() && !payload.discord_channel_id.trim().is_empty() {
+        return Err(anyhow!(
+            "discord_channel_id needs discord_guild_id as well."
+        ));
+    }
+    Ok(())
+}
+
+fn validate_azure(payload: &OpenClawConfigInput) -> Result<()> {
+    let uses_azure = std::iter::once(&payload.model_chain.primary)
+        .chain(&payload.model_chain.fallbacks)
+        .any(|key| model_identity::provider_from_model_key(key) == Some(AZURE_PROVIDER));
+    if !uses_azure && payload.azure_endpoint.trim().is_empty() {
+        return Ok(());
+    }
+    normalize_azure_endpoint(&payload.azure_endpoint)?;
+    if azure_deployments(payload).is_empty() {
+        return Err(anyhow!(
+            "Azure OpenAI needs a deployment name (the model id is azure/<deployment>)."
+        ));
+    }
+    Ok(())
+}
+
+/// DingTalk AppKeys are letters and digits (usually `ding…`); AppSecrets are long
+/// base64url-style strings. Catches the two fields being swapped or pasted with spaces.
+fn validate_dingtalk(payload: &OpenClawConfigInput) -> Result<()> {
+    if !payload.enable_dingtalk_channel {
+        return Ok(());
+    }
+    let app_key = payload.dingtalk_app_key.trim();
+    let app_secret = payload.dingtalk_app_secret.trim();
+    if app_key.is_empty() || app_secret.is_empty() {
+        return Err(anyhow!(
+            "DingTalk AppKey and AppSecret are required when the DingTalk channel is enabled."
+        ));
+    }
+    if app_key.len() > 64 || !app_key.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(anyhow!(
+            "dingtalk_app_key must be the AppKey (letters and digits) from the DingTalk developer console."
+        ));
+    }
+    if app_secret.len() < 32
+        || !app_secret
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "dingtalk_app_secret does not look like a DingTalk AppSecret; copy it again from the developer console."
+        ));
+    }
+    Ok(())
+}
+
+pub fn optional_non_empty(value: Option<String>) -> Option<String> {
     value.and_then(|v| {
         let s = v.trim().to_string();
         if s.is_empty() {
@@ -1366,7 +2156,7 @@ fn existing_gateway_token() -> Option<String> {
         .filter(|v| !v.is_empty())
 }
 
-fn generate_gateway_token(len: usize) -> String {
+pub fn generate_gateway_token(len: usize) -> String {
     let mut out = String::new();
     while out.len() < len {
         out.push_str(&Uuid::new_v4().simple().to_string());
@@ -1389,7 +2179,7 @@ fn normalize_fallbacks(fallbacks: &[String]) -> Vec<String> {
     uniq
 }
 
-fn set_windows_acl(path: &Path) -> Vec<String> {
+pub fn set_windows_acl(path: &Path) -> Vec<ResultWarning> {
     let mut warnings = Vec::new();
     let username = std::env::var("USERNAME").unwrap_or_else(|_| "CurrentUser".to_string());
     let path_text = path.to_string_lossy().to_string();
@@ -1397,17 +2187,32 @@ fn set_windows_acl(path: &Path) -> Vec<String> {
     // Remove inherited broad permissions first, then re-grant current user explicitly.
     match shell::run_command("icacls", &[&path_text, "/inheritance:r"], None, &[]) {
         Ok(out) if out.code == 0 => {}
-        Ok(out) => warnings.push(format!("Failed to disable ACL inheritance: {}", out.stderr)),
-        Err(err) => warnings.push(format!("ACL operation failed: {err}")),
-    }
-    let grant = format!("{username}:(R,W)");
-    match shell::run_command("icacls", &[&path_text, "/grant:r", &grant], None, &[]) {
-        Ok(out) if out.code == 0 => {}
-        Ok(out) => warnings.push(format!(
-            "Failed to grant ACL to current user: {}",
-            out.stderr
+        Ok(out) => warnings.push(ResultWarning::warning(
+            "acl_update_failed",
+            format!("Failed to disable ACL inheritance: {}", out.stderr),
+        )),
+        Err(err) => warnings.push(ResultWarning::warning(
+            "acl_update_failed",
+            format!("ACL operation failed: {err}"),
         )),
-        Err(err) => warnings.push(format!("ACL grant failed: {err}")),
+    }
+    let mut grants = vec![format!("{username}:(R,W)")];
+    // The gateway service runs as its own account and must still read the file.
+    if gateway_service::detect().is_some() {
+        grants.push(format!("{}:(R)", gateway_service::SERVICE_ACCOUNT));
+    }
+    for grant in grants {
+        match shell::run_command("icacls", &[&path_text, "/grant:r", &grant], None, &[]) {
+            Ok(out) if out.code == 0 => {}
+            Ok(out) => warnings.push(ResultWarning::warning(
+                "acl_update_failed",
+                format!("Failed to grant ACL ({grant}): {}", out.stderr),
+            )),
+            Err(err) => warnings.push(ResultWarning::warning(
+                "acl_update_failed",
+                format!("ACL grant failed: {err}"),
+            )),
+        }
     }
     warnings
 }
@@ -1430,7 +2235,7 @@ fn provider_key_for_payload(payload: &OpenClawConfigInput, provider: &str) -> Op
         .get(normalized.as_str())
         .cloned()
         .or_else(|| payload.provider_api_keys.get(provider).cloned())
-        .and_then(|v| optional_non_empty(Some(v)));
+        .and_then(provided_key);
     if direct.is_some() {
         return direct;
     }
@@ -1439,23 +2244,23 @@ fn provider_key_for_payload(payload: &OpenClawConfigInput, provider: &str) -> Op
             .provider_api_keys
             .get("openai-codex")
             .cloned()
-            .and_then(|v| optional_non_empty(Some(v)));
+            .and_then(provided_key);
     }
     if normalized == "kimi-coding" {
         return payload
             .provider_api_keys
             .get("kimi-code")
             .cloned()
-            .and_then(|v| optional_non_empty(Some(v)));
+            .and_then(provided_key);
     }
     None
 }
 
-fn provider_key_for_id(payload: &OpenClawConfigInput, provider_id: &str) -> Option<String> {
+pub fn provider_key_for_id(payload: &OpenClawConfigInput, provider_id: &str) -> Option<String> {
     provider_key_for_payload(payload, provider_id)
 }
 
-fn providers_from_model_chain(model_chain: &ModelChain) -> Vec<String> {
+pub fn providers_from_model_chain(model_chain: &ModelChain) -> Vec<String> {
     let mut providers = HashSet::<String>::new();
     if let Some(provider) = model_identity::provider_from_model_key(&model_chain.primary) {
         providers.insert(model_identity::normalize_auth_provider(provider));
@@ -1470,6 +2275,29 @@ fn providers_from_model_chain(model_chain: &ModelChain) -> Vec<String> {
     out
 }
 
+/// Prefix of keys shown masked in a prefilled form (see `reconfigure`). A masked
+/// value sent back means "keep the existing key", so it is never written out.
+pub const MASKED_KEY_PREFIX: &str = "\u{2022}\u{2022}\u{2022}\u{2022}";
+
+pub fn mask_key(value: &str) -> String {
+    let tail = value
+        .chars()
+        .rev()
+        .take(4)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect::<String>();
+    if value.chars().count() <= 8 {
+        return MASKED_KEY_PREFIX.to_string();
+    }
+    format!("{MASKED_KEY_PREFIX}{tail}")
+}
+
+fn provided_key(value: String) -> Option<String> {
+    optional_non_empty(Some(value)).filter(|v| !v.starts_with(MASKED_KEY_PREFIX))
+}
+
 fn sanitize_env_value(raw: &str) -> String {
     raw.replace('\r', "").replace('\n', "")
 }
@@ -1546,9 +2374,12 @@ fn force_safe_onboard_retry_args(args: &[String]) -> Vec<String> {
     out
 }
 
-fn resolve_working_cli_command(preferred: &str) -> Result<String> {
-    let preferred = preferred.trim().trim_matches('"').to_string();
-    if is_cli_command_usable(preferred.as_str()) {
+fn resolve_working_cli_command(install: &InstallState) -> Result<String> {
+    if let Some(shim) = cli_shim::pinned_command(install) {
+        return Ok(shim);
+    }
+    let preferred = install.command_path.trim().trim_matches('"').to_string();
+    if !cli_shim::is_npx(&preferred) && is_cli_command_usable(preferred.as_str()) {
         return Ok(preferred);
     }
 
@@ -1570,7 +2401,7 @@ fn resolve_working_cli_command(preferred: &str) -> Result<String> {
     }
 
     if is_cli_command_usable("npx") {
-        logger::warn("Falling back to npx openclaw.");
+        cli_shim::note_npx_fallback("OpenClaw CLI");
         return Ok("npx".to_string());
     }
 
@@ -1658,7 +2489,13 @@ fn is_secret_flag(flag: &str) -> bool {
 }
 
 fn is_secret_config_path(path: &str) -> bool {
-    matches!(path, "channels.feishu.appsecret")
+    matches!(
+        path,
+        "channels.feishu.appsecret"
+            | "channels.dingtalk.appsecret"
+            | "channels.telegram.webhooksecret"
+            | "channels.discord.token"
+    )
 }
 
 fn compact_text(raw: &str, max_len: usize) -> String {
@@ -1670,7 +2507,7 @@ fn compact_text(raw: &str, max_len: usize) -> String {
     text
 }
 
-fn cli_output_text(out: &shell::CmdOutput) -> String {
+pub fn cli_output_text(out: &shell::CmdOutput) -> String {
     if out.stderr.is_empty() {
         out.stdout.clone()
     } else {
@@ -1678,7 +2515,7 @@ fn cli_output_text(out: &shell::CmdOutput) -> String {
     }
 }
 
-fn redact_known_values(mut text: String, values: &[&str]) -> String {
+pub fn redact_known_values(mut text: String, values: &[&str]) -> String {
     for value in values {
         let secret = value.trim();
         if !secret.is_empty() {
@@ -1687,3 +2524,336 @@ fn redact_known_values(mut text: String, values: &[&str]) -> String {
     }
     text
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        apply_discord_integration, apply_phases, apply_trial_chain, azure_deployments,
+        azure_env_entries, azure_provider_writes, discord_config_writes, is_cli_command_usable,
+        normalize_azure_endpoint, rollback_failed_phase, snapshot_categories, validate_azure,
+        validate_dingtalk, validate_discord, ConfigurePhase,
+    };
+    use crate::models::{
+        BackupCategory, ModelChain, OpenClawConfigInput, AZURE_DEFAULT_API_VERSION,
+    };
+    use crate::modules::shell::{testing::RecordingRunner, with_runner};
+
+    fn azure_payload(primary: &str, fallbacks: &[&str], deployment: &str) -> OpenClawConfigInput {
+        OpenClawConfigInput {
+            model_chain: ModelChain {
+                primary: primary.to_string(),
+                fallbacks: fallbacks.iter().map(|f| f.to_string()).collect(),
+            },
+            azure_endpoint: "https://contoso.openai.azure.com".to_string(),
+            azure_deployment: deployment.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn azure_endpoint_is_reduced_to_the_resource_origin() {
+        assert_eq!(
+            normalize_azure_endpoint(
+                " https://contoso.openai.azure.com/openai/deployments/gpt-4o/chat/completions?api-version=2024-10-21 "
+            )
+            .unwrap(),
+            "https://contoso.openai.azure.com"
+        );
+        assert_eq!(
+            normalize_azure_endpoint("https://localhost:8443/").unwrap(),
+            "https://localhost:8443"
+        );
+        assert!(normalize_azure_endpoint("http://contoso.openai.azure.com").is_err());
+        assert!(normalize_azure_endpoint("contoso").is_err());
+    }
+
+    #[test]
+    fn azure_deployments_are_unique_and_written_as_provider_models() {
+        let payload = azure_payload(
+            "azure/gpt-4o",
+            &["openai/gpt-5.2", "azure/o4-mini", "azure/gpt-4o"],
+            "gpt-4o",
+        );
+        let deployments = azure_deployments(&payload);
+        assert_eq!(deployments, vec!["gpt-4o", "o4-mini"]);
+
+        let writes = azure_provider_writes("https://contoso.openai.azure.com", &deployments);
+        let value = |path: &str| {
+            writes
+                .iter()
+                .find(|(key, _)| key == path)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(
+            value("models.providers.azure.baseUrl"),
+            Some("https://contoso.openai.azure.com/openai/v1")
+        );
+        assert_eq!(
+            value("models.providers.azure.headers.api-key"),
+            Some("${AZURE_OPENAI_API_KEY}")
+        );
+        let models: serde_json::Value =
+            serde_json::from_str(value("models.providers.azure.models").unwrap()).unwrap();
+        assert_eq!(models[1]["id"], "o4-mini");
+    }
+
+    #[test]
+    fn azure_api_version_defaults_when_left_empty() {
+        let endpoint = "https://contoso.openai.azure.com";
+        let mut payload = azure_payload("azure/gpt-4o", &[], "");
+        let env = azure_env_entries(endpoint, &payload);
+        assert_eq!(
+            env.get("AZURE_OPENAI_API_VERSION").map(String::as_str),
+            Some(AZURE_DEFAULT_API_VERSION)
+        );
+        assert_eq!(
+            env.get("AZURE_OPENAI_ENDPOINT").map(String::as_str),
+            Some(endpoint)
+        );
+
+        payload.azure_api_version = Some(" 2025-04-01-preview ".to_string());
+        let env = azure_env_entries(endpoint, &payload);
+        assert_eq!(
+            env.get("AZURE_OPENAI_API_VERSION").map(String::as_str),
+            Some("2025-04-01-preview")
+        );
+    }
+
+    #[test]
+    fn azure_models_need_an_endpoint_and_a_deployment() {
+        assert!(validate_azure(&azure_payload("azure/gpt-4o", &[], "")).is_ok());
+        assert!(validate_azure(&azure_payload("openai/gpt-5.2", &[], "")).is_err());
+        let mut no_endpoint = azure_payload("azure/gpt-4o", &[], "");
+        no_endpoint.azure_endpoint.clear();
+        assert!(validate_azure(&no_endpoint).is_err());
+        assert!(validate_azure(&OpenClawConfigInput::default()).is_ok());
+    }
+
+    #[test]
+    fn dingtalk_credentials_are_checked_when_enabled() {
+        let mut payload = OpenClawConfigInput {
+            enable_dingtalk_channel: true,
+            dingtalk_app_key: "dingabc123xyz".to_string(),
+            dingtalk_app_secret: "Zx9-_".repeat(8),
+            ..Default::default()
+        };
+        assert!(validate_dingtalk(&payload).is_ok());
+
+        payload.dingtalk_app_secret.clear();
+        assert!(validate_dingtalk(&payload).is_err());
+        payload.dingtalk_app_secret = "short".to_string();
+        assert!(validate_dingtalk(&payload).is_err());
+
+        // Swapped fields: the secret is not a valid AppKey.
+        payload.dingtalk_app_secret = "dingabc123xyz".to_string();
+        payload.dingtalk_app_key = "Zx9-_".repeat(8);
+        assert!(validate_dingtalk(&payload).is_err());
+
+        payload.enable_dingtalk_channel = false;
+        assert!(validate_dingtalk(&payload).is_ok());
+    }
+
+    #[test]
+    fn cli_command_is_usable_only_when_version_succeeds() {
+        let runner = RecordingRunner::new();
+        runner.respond("openclaw", 0, "2026.3.1");
+        runner.respond("openclaw", 1, "");
+        let command = r"C:\OpenClaw\openclaw.cmd";
+        assert!(with_runner(runner.clone(), || is_cli_command_usable(
+            command
+        )));
+        assert!(!with_runner(runner.clone(), || is_cli_command_usable(
+            command
+        )));
+        assert_eq!(
+            runner.lines(),
+            vec![
+                r"C:\OpenClaw\openclaw.cmd --version",
+                r"C:\OpenClaw\openclaw.cmd --version"
+            ]
+        );
+    }
+
+    #[test]
+    fn trial_chain_needs_an_openrouter_key() {
+        let mut keyless = OpenClawConfigInput {
+            trial_mode: true,
+            ..Default::default()
+        };
+        let err = apply_trial_chain(&mut keyless).expect_err("no key");
+        assert!(err.to_string().contains("openrouter.ai/keys"));
+
+        let mut trial = OpenClawConfigInput {
+            trial_mode: true,
+            provider: "anthropic".to_string(),
+            ..Default::default()
+        };
+        trial
+            .provider_api_keys
+            .insert("openrouter".to_string(), "sk-or-v1-free".to_string());
+        apply_trial_chain(&mut trial).expect("trial key");
+        assert!(trial.trial_mode);
+        assert_eq!(trial.provider, "openrouter");
+        assert_eq!(trial.api_key, "sk-or-v1-free");
+        assert!(trial.model_chain.primary.ends_with(":free"));
+        assert!(trial
+            .model_chain
+            .fallbacks
+            .iter()
+            .all(|m| m.ends_with(":free")));
+    }
+
+    #[test]
+    fn a_paid_provider_key_ends_the_trial() {
+        let mut paid = OpenClawConfigInput {
+            trial_mode: true,
+            provider: "anthropic".to_string(),
+            api_key: "sk-ant-123".to_string(),
+            model_chain: ModelChain {
+                primary: "anthropic/claude-sonnet-4-5".to_string(),
+                fallbacks: vec![],
+            },
+            ..Default::default()
+        };
+        apply_trial_chain(&mut paid).expect("paid key");
+        assert!(!paid.trial_mode);
+        assert_eq!(paid.model_chain.primary, "anthropic/claude-sonnet-4-5");
+    }
+
+    #[test]
+    fn configure_phases_run_in_order_and_stop_at_the_first_failure() {
+        let mut applied = Vec::new();
+        apply_phases(
+            &ConfigurePhase::ALL,
+            |phase| {
+                applied.push(phase);
+                Ok(())
+            },
+            |_, _| panic!("nothing failed"),
+        )
+        .expect("all phases applied");
+        assert_eq!(applied, ConfigurePhase::ALL);
+
+        let mut applied = Vec::new();
+        let mut rolled_back = None;
+        let err = apply_phases(
+            &ConfigurePhase::ALL,
+            |phase| {
+                applied.push(phase);
+                match phase {
+                    ConfigurePhase::ApplyModels => Err(anyhow::anyhow!("models set failed")),
+                    _ => Ok(()),
+                }
+            },
+            |phase, err| {
+                rolled_back = Some(phase);
+                rollback_failed_phase(phase, &[], err)
+            },
+        )
+        .expect_err("models phase fails");
+        assert_eq!(
+            applied,
+            [
+                ConfigurePhase::OnboardCore,
+                ConfigurePhase::ApplyKeys,
+                ConfigurePhase::ApplyModels
+            ]
+        );
+        assert_eq!(rolled_back, Some(ConfigurePhase::ApplyModels));
+        assert_eq!(
+            err.to_string(),
+            "Configure step apply_models failed: models set failed"
+        );
+    }
+
+    #[test]
+    fn configure_snapshots_each_existing_category_once() {
+        assert_eq!(
+            snapshot_categories(&ConfigurePhase::ALL, |_| true),
+            [BackupCategory::Config, BackupCategory::Secrets]
+        );
+        assert!(snapshot_categories(&ConfigurePhase::ALL, |_| false).is_empty());
+        assert_eq!(
+            snapshot_categories(&ConfigurePhase::ALL, |category| {
+                category == BackupCategory::Secrets
+            }),
+            [BackupCategory::Secrets]
+        );
+        assert!(
+            snapshot_categories(&[ConfigurePhase::ApplySkills], |category| {
+                category == BackupCategory::Secrets
+            })
+            .is_empty()
+        );
+    }
+
+    fn discord_payload(token: &str, guild: &str, channel: &str) -> OpenClawConfigInput {
+        OpenClawConfigInput {
+            enable_discord_channel: true,
+            discord_bot_token: token.to_string(),
+            discord_guild_id: guild.to_string(),
+            discord_channel_id: channel.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn discord_writes_narrow_to_the_chosen_server_and_channel() {
+        let writes = |guild: &str, channel: &str| {
+            discord_config_writes(&discord_payload("token", guild, channel))
+                .into_iter()
+                .map(|(path, value)| format!("{path}={value}"))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(writes("", ""), ["channels.discord.enabled=true"]);
+        assert_eq!(
+            writes(" 1234567890 ", ""),
+            [
+                "channels.discord.enabled=true",
+                "channels.discord.groupPolicy=allowlist",
+                "channels.discord.guilds.1234567890.requireMention=true",
+            ]
+        );
+        assert_eq!(
+            writes("1234567890", "987654321"),
+            [
+                "channels.discord.enabled=true",
+                "channels.discord.groupPolicy=allowlist",
+                "channels.discord.guilds.1234567890.channels.987654321.allow=true",
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_discord_checks_token_and_ids() {
+        let disabled = OpenClawConfigInput {
+            discord_guild_id: "not-an-id".to_string(),
+            ..Default::default()
+        };
+        assert!(validate_discord(&disabled).is_ok());
+        assert!(validate_discord(&discord_payload("token", "1234567890", "987654321")).is_ok());
+
+        let err =
+            |payload: OpenClawConfigInput| validate_discord(&payload).unwrap_err().to_string();
+        assert!(err(discord_payload(" ", "", "")).contains("bot token is required"));
+        assert!(err(discord_payload("token", "my-server", "")).contains("discord_guild_id"));
+        assert!(err(discord_payload("token", "1", "123456789012345678901"))
+            .contains("discord_channel_id"));
+        assert!(err(discord_payload("token", "", "987654321")).contains("needs discord_guild_id"));
+    }
+
+    #[test]
+    fn discord_setup_without_a_token_only_warns() {
+        let runner = RecordingRunner::new();
+        let mut warnings = Vec::new();
+        with_runner(runner.clone(), || {
+            apply_discord_integration(&OpenClawConfigInput::default(), &mut warnings)
+                .expect("disabled");
+            apply_discord_integration(&discord_payload("", "", ""), &mut warnings)
+                .expect("no token");
+        });
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].code, "channel_token_missing");
+        assert!(runner.calls().is_empty());
+    }
+}
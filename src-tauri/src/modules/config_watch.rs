@@ -0,0 +1,404 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+
+use anyhow::Result;
+use chrono::Local;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+
+use crate::models::{ConfigWatchAlert, ConfigWatchConfig, SecurityIssue, SecuritySeverity};
+
+use super::{logger, paths, secrets, security, state_store};
+
+/// Event carrying the alerts raised by one check.
+pub const EVENT: &str = "config-watch://alert";
+const MAX_STORED_ALERTS: usize = 200;
+const SEALED_PREFIX: &str = "sealed:";
+
+type Sink = Box<dyn Fn(&[ConfigWatchAlert]) + Send + Sync>;
+
+static SINK: OnceLock<Sink> = OnceLock::new();
+static FINGERPRINT_KEY: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Snapshot {
+    len: u64,
+    modified_ms: i64,
+    /// Watched key path -> keyed fingerprint of its value, so secrets never hit disk and
+    /// short ones cannot be guessed back from the snapshot alone.
+    values: BTreeMap<String, String>,
+}
+
+/// Registers where new alerts go; `main` forwards them to the webview as `EVENT`.
+pub fn set_sink(sink: impl Fn(&[ConfigWatchAlert]) + Send + Sync + 'static) {
+    let _ = SINK.set(Box::new(sink));
+}
+
+pub fn configure_config_watch(cfg: &ConfigWatchConfig) -> Result<ConfigWatchConfig> {
+    let mut cfg = cfg.clone();
+    cfg.keys = cfg
+        .keys
+        .iter()
+        .map(|k| k.trim().trim_matches('.').to_string())
+        .filter(|k| !k.is_empty())
+        .collect();
+    cfg.keys.sort();
+    cfg.keys.dedup();
+    state_store::save_config_watch(&cfg)?;
+    // The watched set changed, so rebaseline instead of alerting on newly watched keys.
+    let _ = fs::remove_file(snapshot_path());
+    if cfg.enabled {
+        save_snapshot(&take_snapshot(&cfg))?;
+        logger::info(&format!(
+            "Config watch enabled for {} key pattern(s).",
+            cfg.keys.len()
+        ));
+    }
+    Ok(cfg)
+}
+
+/// Compares watched keys against the last snapshot whenever openclaw.json changed on
+/// disk, whoever wrote it. Called from status polling.
+pub fn check_for_changes() {
+    let Ok(cfg) = state_store::load_config_watch() else {
+        return;
+    };
+    if !cfg.enabled {
+        return;
+    }
+    if let Err(err) = check(&cfg) {
+        logger::warn(&format!("Config watch check failed: {err}"));
+    }
+}
+
+pub fn load_alerts() -> Vec<ConfigWatchAlert> {
+    fs::read_to_string(alerts_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn unacknowledged_count() -> usize {
+    load_alerts().iter().filter(|a| !a.acknowledged).count()
+}
+
+pub fn acknowledge_alerts() -> Result<()> {
+    let mut alerts = load_alerts();
+    for alert in &mut alerts {
+        alert.acknowledged = true;
+    }
+    save_alerts(&alerts)
+}
+
+pub fn clear_alerts() -> Result<()> {
+    let path = alerts_path();
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Unacknowledged alerts rendered as security-check issues.
+pub fn security_issues() -> Vec<SecurityIssue> {
+    load_alerts()
+        .into_iter()
+        .filter(|alert| !alert.acknowledged)
+        .map(|alert| SecurityIssue {
+            severity: SecuritySeverity::Medium,
+            message: format!(
+                "Watched config key {} was {} ({}).",
+                alert.key, alert.change, alert.detected_at
+            ),
+            path: Some(paths::config_path().to_string_lossy().to_string()),
+            suggestion: Some(
+                "Confirm the change was intended; otherwise restore openclaw.json from backup."
+                    .to_string(),
+            ),
+            action: None,
+        })
+        .collect()
+}
+
+fn check(cfg: &ConfigWatchConfig) -> Result<()> {
+    let Some(previous) = load_snapshot() else {
+        return save_snapshot(&take_snapshot(cfg));
+    };
+    let (len, modified_ms) = file_stamp();
+    if previous.len == len && previous.modified_ms == modified_ms {
+        return Ok(());
+    }
+    let current = take_snapshot(cfg);
+    let changes = diff_values(&previous.values, &current.values);
+    if !changes.is_empty() {
+        let detected_at = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let raised = changes
+            .into_iter()
+            .map(|(key, change)| {
+                logger::warn(&format!("Config watch: {key} {change} in openclaw.json."));
+                ConfigWatchAlert {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    detected_at: detected_at.clone(),
+                    key,
+                    change: change.to_string(),
+                    acknowledged: false,
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut alerts = load_alerts();
+        alerts.extend(raised.iter().cloned());
+        let overflow = alerts.len().saturating_sub(MAX_STORED_ALERTS);
+        alerts.drain(..overflow);
+        save_alerts(&alerts)?;
+        notify(&raised);
+    }
+    save_snapshot(&current)
+}
+
+fn notify(raised: &[ConfigWatchAlert]) {
+    let Some(first) = raised.first() else {
+        return;
+    };
+    let mut message = format!("{} was {}.", first.key, first.change);
+    if raised.len() > 1 {
+        message.push_str(&format!(" (+{} more)", raised.len() - 1));
+    }
+    // The toast goes through PowerShell; keep it off the status poll that got here.
+    std::thread::spawn(move || security::show_toast("OpenClaw config changed", &message));
+    if let Some(sink) = SINK.get() {
+        sink(raised);
+    }
+}
+
+fn take_snapshot(cfg: &ConfigWatchConfig) -> Snapshot {
+    let (len, modified_ms) = file_stamp();
+    let root = fs::read_to_string(paths::config_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str::<Value>(raw.trim_start_matches('\u{feff}')).ok())
+        .unwrap_or(Value::Null);
+    let patterns = cfg
+        .keys
+        .iter()
+        .map(|k| k.split('.').collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let mut values = BTreeMap::new();
+    collect_watched(&root, &mut Vec::new(), &patterns, &mut values);
+    Snapshot {
+        len,
+        modified_ms,
+        values,
+    }
+}
+
+fn collect_watched<'a>(
+    value: &'a Value,
+    path: &mut Vec<&'a str>,
+    patterns: &[Vec<&str>],
+    out: &mut BTreeMap<String, String>,
+) {
+    if let Value::Object(map) = value {
+        for (key, child) in map {
+            path.push(key);
+            collect_watched(child, path, patterns, out);
+            path.pop();
+        }
+        return;
+    }
+    if !path.is_empty() && patterns.iter().any(|p| pattern_covers(p, path)) {
+        out.insert(path.join("."), fingerprint(value));
+    }
+}
+
+/// True when `pattern` equals `path` or is one of its ancestors, `*` matching any segment.
+fn pattern_covers(pattern: &[&str], path: &[&str]) -> bool {
+    pattern.len() <= path.len()
+        && pattern
+            .iter()
+            .zip(path)
+            .all(|(want, got)| *want == "*" || want == got)
+}
+
+fn fingerprint(value: &Value) -> String {
+    keyed_fingerprint(fingerprint_key(), value)
+}
+
+fn keyed_fingerprint(key: &str, value: &Value) -> String {
+    let mut mac =
+        <Hmac<Sha256> as Mac>::new_from_slice(key.as_bytes()).expect("HMAC takes any key length");
+    mac.update(value.to_string().as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Random per-install key, sealed at rest where DPAPI is available. A new key makes
+/// every stored fingerprint meaningless, so creating one drops the snapshot and the
+/// next check rebaselines instead of alerting on every key.
+fn fingerprint_key() -> &'static str {
+    FINGERPRINT_KEY.get_or_init(|| {
+        if let Some(key) = load_key() {
+            return key;
+        }
+        let key = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let _ = fs::remove_file(snapshot_path());
+        if let Err(err) = save_key(&key) {
+            logger::warn(&format!("Config watch key could not be saved: {err}"));
+        }
+        key
+    })
+}
+
+fn load_key() -> Option<String> {
+    let raw = fs::read_to_string(key_path()).ok()?;
+    let raw = raw.trim();
+    let key = match raw.strip_prefix(SEALED_PREFIX) {
+        Some(blob) => secrets::open_value(blob).ok()?,
+        None => raw.to_string(),
+    };
+    (!key.is_empty()).then_some(key)
+}
+
+fn save_key(key: &str) -> Result<()> {
+    paths::ensure_dirs()?;
+    let stored = match secrets::seal_value(key) {
+        Some(blob) => format!("{SEALED_PREFIX}{}", blob?),
+        None => key.to_string(),
+    };
+    fs::write(key_path(), stored)?;
+    Ok(())
+}
+
+fn diff_values(
+    old: &BTreeMap<String, String>,
+    new: &BTreeMap<String, String>,
+) -> Vec<(String, &'static str)> {
+    let mut out = Vec::new();
+    for (key, value) in new {
+        match old.get(key) {
+            None => out.push((key.clone(), "added")),
+            Some(prev) if prev != value => out.push((key.clone(), "changed")),
+            Some(_) => {}
+        }
+    }
+    for key in old.keys() {
+        if !new.contains_key(key) {
+            out.push((key.clone(), "removed"));
+        }
+    }
+    out
+}
+
+fn file_stamp() -> (u64, i64) {
+    let Ok(meta) = fs::metadata(paths::config_path()) else {
+        return (0, 0);
+    };
+    let modified_ms = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+    (meta.len(), modified_ms)
+}
+
+fn snapshot_path() -> PathBuf {
+    paths::state_dir().join("config_watch_snapshot.json")
+}
+
+fn key_path() -> PathBuf {
+    paths::state_dir().join("config_watch.key")
+}
+
+fn alerts_path() -> PathBuf {
+    paths::state_dir().join("config_watch_alerts.json")
+}
+
+fn load_snapshot() -> Option<Snapshot> {
+    let raw = fs::read_to_string(snapshot_path()).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn save_snapshot(snapshot: &Snapshot) -> Result<()> {
+    paths::ensure_dirs()?;
+    fs::write(snapshot_path(), serde_json::to_string(snapshot)?)?;
+    Ok(())
+}
+
+fn save_alerts(alerts: &[ConfigWatchAlert]) -> Result<()> {
+    paths::ensure_dirs()?;
+    fs::write(alerts_path(), serde_json::to_string_pretty(alerts)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde_json::json;
+
+    use super::{collect_watched, diff_values, keyed_fingerprint, pattern_covers};
+
+    #[test]
+    fn fingerprints_depend_on_the_install_key() {
+        let value = json!("sk-1234");
+        let first = keyed_fingerprint("key-a", &value);
+        assert_eq!(first, keyed_fingerprint("key-a", &value));
+        assert_ne!(first, keyed_fingerprint("key-b", &value));
+        assert_ne!(first, keyed_fingerprint("key-a", &json!("sk-1235")));
+        assert_eq!(first.len(), 64);
+    }
+
+    #[test]
+    fn collect_watched_matches_wildcards_and_nested_keys() {
+        let root = json!({
+            "gateway": { "port": 28789, "auth": { "mode": "token", "token": "abc" } },
+            "channels": {
+                "telegram": { "enabled": true, "botToken": "123:abc" },
+                "feishu": { "appSecret": "s" }
+            }
+        });
+        let patterns = vec![vec!["gateway", "auth"], vec!["channels", "*", "botToken"]];
+        let mut out = BTreeMap::new();
+        collect_watched(&root, &mut Vec::new(), &patterns, &mut out);
+        assert_eq!(
+            out.keys().cloned().collect::<Vec<_>>(),
+            vec![
+                "channels.telegram.botToken",
+                "gateway.auth.mode",
+                "gateway.auth.token"
+            ]
+        );
+        assert!(out.values().all(|v| !v.contains("abc")));
+        assert!(!pattern_covers(&["gateway", "auth"], &["gateway"]));
+    }
+
+    #[test]
+    fn diff_values_reports_added_changed_and_removed() {
+        let old = BTreeMap::from([
+            ("gateway.auth.token".to_string(), "1".to_string()),
+            ("tools.deny".to_string(), "2".to_string()),
+        ]);
+        let new = BTreeMap::from([
+            ("gateway.auth.token".to_string(), "9".to_string()),
+            ("tools.exec.security".to_string(), "3".to_string()),
+        ]);
+        assert_eq!(
+            diff_values(&old, &new),
+            vec![
+                ("gateway.auth.token".to_string(), "changed"),
+                ("tools.exec.security".to_string(), "added"),
+                ("tools.deny".to_string(), "removed"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use anyhow::{anyhow, Result};
+use chrono::Local;
+
+use crate::models::{ConsentRecord, ConsentStatus, ConsentStore};
+
+use super::{logger, state_store};
+
+/// Bump when the risk statement shown before setup changes; earlier acceptances then no
+/// longer count.
+pub const RISK_STATEMENT_VERSION: &str = "2026.1";
+/// Telemetry opt-in wording; traces stay local either way, the decision is recorded for
+/// any future upload.
+pub const TELEMETRY_VERSION: &str = "1";
+
+pub const RISK_STATEMENT: &str = "risk_statement";
+pub const TELEMETRY: &str = "telemetry";
+
+pub fn get_consents() -> Result<ConsentStatus> {
+    Ok(status(load_migrated()?))
+}
+
+/// Records the user's decision on `kind` (`risk_statement` or `telemetry`). `version`
+/// must be the one the UI showed, so a stale screen cannot accept newer terms.
+pub fn set_consent(kind: &str, accepted: bool, version: Option<&str>) -> Result<ConsentStatus> {
+    let current = match kind {
+        RISK_STATEMENT => RISK_STATEMENT_VERSION,
+        TELEMETRY => TELEMETRY_VERSION,
+        other => return Err(anyhow!("Unknown consent '{other}'.")),
+    };
+    let version = version.map(str::trim).unwrap_or(current);
+    if version != current {
+        return Err(anyhow!(
+            "The {kind} shown was version {version}; the current one is {current}. Reload and review it again."
+        ));
+    }
+    let record = ConsentRecord {
+        version: current.to_string(),
+        accepted,
+        decided_at: Local::now().to_rfc3339(),
+        migrated: false,
+    };
+    let mut store = state_store::load_consents()?;
+    match kind {
+        RISK_STATEMENT => store.risk_statement = Some(record),
+        _ => store.telemetry = Some(record),
+    }
+    state_store::save_consents(&store)?;
+    logger::info(&format!(
+        "Consent recorded: {kind} v{current} {}.",
+        if accepted { "accepted" } else { "declined" }
+    ));
+    Ok(status(store))
+}
+
+/// Gate for everything that passes `--accept-risk` to OpenClaw on the user's behalf.
+pub fn require_risk_acceptance() -> Result<()> {
+    let store = load_migrated()?;
+    if risk_accepted(&store) {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "The OpenClaw risk statement (version {RISK_STATEMENT_VERSION}) has not been accepted. Review and accept it before setup continues."
+    ))
+}
+
+/// Consents on disk, with the risk statement counted as accepted for an existing install
+/// that predates consent records: its onboarding already ran with `--accept-risk`, and
+/// reconfiguring it should not suddenly fail.
+fn load_migrated() -> Result<ConsentStore> {
+    let mut store = state_store::load_consents()?;
+    if !needs_migration(&store, state_store::load_install_state()?.is_some()) {
+        return Ok(store);
+    }
+    store.risk_statement = Some(ConsentRecord {
+        version: RISK_STATEMENT_VERSION.to_string(),
+        accepted: true,
+        decided_at: Local::now().to_rfc3339(),
+        migrated: true,
+    });
+    state_store::save_consents(&store)?;
+    logger::info("Consent recorded: risk_statement carried over from the existing install.");
+    Ok(store)
+}
+
+// Only a missing record is migrated; an explicit decline or an outdated version is the
+// user's answer and has to be renewed through the UI.
+fn needs_migration(store: &ConsentStore, installed: bool) -> bool {
+    installed && store.risk_statement.is_none()
+}
+
+fn status(records: ConsentStore) -> ConsentStatus {
+    ConsentStatus {
+        risk_statement_version: RISK_STATEMENT_VERSION.to_string(),
+        risk_accepted: risk_accepted(&records),
+        records,
+    }
+}
+
+fn risk_accepted(store: &ConsentStore) -> bool {
+    store
+        .risk_statement
+        .as_ref()
+        .is_some_and(|record| record.accepted && record.version == RISK_STATEMENT_VERSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{needs_migration, risk_accepted, RISK_STATEMENT_VERSION};
+    use crate::models::{ConsentRecord, ConsentStore};
+
+    #[test]
+    fn only_an_accepted_current_risk_statement_counts() {
+        let record = |version: &str, accepted: bool| ConsentRecord {
+            version: version.to_string(),
+            accepted,
+            decided_at: "2026-10-18T10:00:00+08:00".to_string(),
+            migrated: false,
+        };
+        let mut store = ConsentStore::default();
+        assert!(!risk_accepted(&store));
+        store.risk_statement = Some(record("2025.1", true));
+        assert!(!risk_accepted(&store));
+        store.risk_statement = Some(record(RISK_STATEMENT_VERSION, false));
+        assert!(!risk_accepted(&store));
+        store.risk_statement = Some(record(RISK_STATEMENT_VERSION, true));
+        assert!(risk_accepted(&store));
+    }
+
+    #[test]
+    fn only_an_install_without_any_risk_record_is_migrated() {
+        let mut store = ConsentStore::default();
+        assert!(!needs_migration(&store, false));
+        assert!(needs_migration(&store, true));
+        store.risk_statement = Some(ConsentRecord {
+            version: RISK_STATEMENT_VERSION.to_string(),
+            accepted: false,
+            decided_at: "2026-10-18T10:00:00+08:00".to_string(),
+            migrated: false,
+        });
+        assert!(!needs_migration(&store, true));
+    }
+}
@@ -0,0 +1,220 @@
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use serde_json::json;
+use url::Url;
+
+use crate::models::{
+    CustomProviderInput, CustomProviderRecord, CustomProviderResult, ResultWarning,
+};
+
+use super::{config, logger, model_catalog, model_identity, state_store};
+
+// OpenAI-compatible servers that ignore auth still need a non-empty key in the request.
+pub const KEYLESS_API_KEY: &str = "local";
+
+pub fn list_custom_providers() -> Result<Vec<CustomProviderRecord>> {
+    state_store::load_custom_providers()
+}
+
+/// Registers an OpenAI-compatible endpoint as `models.providers.<name>` through
+/// `openclaw config set`. The key goes to `.env` under `<NAME>_API_KEY` (the generic
+/// `provider_env_name` mapping) and the config only references it, so it is never
+/// written to `openclaw.json` in clear.
+pub fn add_custom_provider(input: &CustomProviderInput) -> Result<CustomProviderResult> {
+    let input = normalize_input(input)?;
+    let env_var = if input.api_key.is_empty() {
+        None
+    } else {
+        Some(
+            model_identity::provider_env_name(&input.name)
+                .ok_or_else(|| anyhow!("Provider name '{}' has no usable env name.", input.name))?,
+        )
+    };
+    if env_var.is_some() {
+        config::update_provider_api_key(&input.name, &input.api_key)?;
+    }
+
+    let proxy = state_store::load_last_config()?.and_then(|cfg| cfg.proxy);
+    let mut warnings = Vec::<ResultWarning>::new();
+    for (path, value) in provider_config_writes(&input, env_var.as_deref()) {
+        let out = config::run_openclaw_cli(
+            &["config".to_string(), "set".to_string(), path.clone(), value],
+            proxy.clone(),
+        )?;
+        if out.code != 0 {
+            warnings.push(
+                ResultWarning::error(
+                    "custom_provider_write_failed",
+                    format!(
+                        "Writing {path} failed: {}",
+                        config::redact_known_values(
+                            config::cli_output_text(&out),
+                            &[&input.api_key]
+                        )
+                    ),
+                )
+                .with_provider(input.name.clone()),
+            );
+        }
+    }
+    if warnings.is_empty() {
+        let restart =
+            config::run_openclaw_cli(&["gateway".to_string(), "restart".to_string()], proxy)?;
+        if restart.code != 0 {
+            warnings.push(ResultWarning::warning(
+                "gateway_restart_failed",
+                "Gateway restart failed; restart it to use the new provider.",
+            ));
+        }
+    }
+    model_catalog::clear_model_catalog_cache();
+
+    let record = CustomProviderRecord {
+        name: input.name.clone(),
+        base_url: input.base_url.clone(),
+        env_var,
+        model_ids: input.model_ids.clone(),
+        added_at: Local::now().to_rfc3339(),
+    };
+    let mut records = state_store::load_custom_providers()?;
+    records.retain(|existing| existing.name != record.name);
+    records.push(record.clone());
+    records.sort_by(|a, b| a.name.cmp(&b.name));
+    state_store::save_custom_providers(&records)?;
+    logger::info(&format!(
+        "Custom provider {} registered: {} ({} models).",
+        record.name,
+        record.base_url,
+        record.model_ids.len()
+    ));
+    Ok(CustomProviderResult {
+        model_keys: record
+            .model_ids
+            .iter()
+            .map(|id| format!("{}/{id}", record.name))
+            .collect(),
+        provider: record,
+        warnings,
+    })
+}
+
+/// The registered custom provider `name`, if any, for callers that need its endpoint.
+pub fn find_custom_provider(name: &str) -> Option<CustomProviderRecord> {
+    state_store::load_custom_providers()
+        .ok()?
+        .into_iter()
+        .find(|record| record.name == name)
+}
+
+fn normalize_input(input: &CustomProviderInput) -> Result<CustomProviderInput> {
+    let name = input.name.trim().to_ascii_lowercase();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "Provider name may only use letters, digits, '-' and '_' (e.g. ollama, lmstudio)."
+        ));
+    }
+    if model_identity::is_builtin_provider(&name) {
+        return Err(anyhow!(
+            "'{name}' is a built-in provider; pick another name for the custom endpoint."
+        ));
+    }
+    let base_url = input.base_url.trim().trim_end_matches('/').to_string();
+    let url = Url::parse(&base_url).map_err(|_| anyhow!("base_url is not a valid URL"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(anyhow!("base_url must be an http(s) URL."));
+    }
+    let mut model_ids = input
+        .model_ids
+        .iter()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect::<Vec<_>>();
+    model_ids.dedup();
+    if model_ids.is_empty() {
+        return Err(anyhow!(
+            "List at least one model id served by the endpoint (e.g. llama3.1:8b)."
+        ));
+    }
+    Ok(CustomProviderInput {
+        name,
+        base_url,
+        api_key: input.api_key.trim().to_string(),
+        model_ids,
+        context_window: input.context_window.filter(|tokens| *tokens > 0),
+    })
+}
+
+/// `config set` calls for the provider entry. `models.mode=merge` keeps the built-in
+/// providers next to the custom one.
+fn provider_config_writes(
+    input: &CustomProviderInput,
+    env_var: Option<&str>,
+) -> Vec<(String, String)> {
+    let prefix = format!("models.providers.{}", input.name);
+    let api_key = env_var.map_or_else(|| KEYLESS_API_KEY.to_string(), |env| format!("${{{env}}}"));
+    let models = input
+        .model_ids
+        .iter()
+        .map(|id| {
+            let mut model = json!({ "id": id, "name": id });
+            if let Some(tokens) = input.context_window {
+                model["contextWindow"] = json!(tokens);
+            }
+            model
+        })
+        .collect::<Vec<_>>();
+    vec![
+        ("models.mode".to_string(), "merge".to_string()),
+        (format!("{prefix}.baseUrl"), input.base_url.clone()),
+        (format!("{prefix}.api"), "openai-completions".to_string()),
+        (format!("{prefix}.apiKey"), api_key),
+        (format!("{prefix}.models"), json!(models).to_string()),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize_input, provider_config_writes};
+    use crate::models::CustomProviderInput;
+
+    #[test]
+    fn custom_providers_reference_their_key_from_env() {
+        let input = normalize_input(&CustomProviderInput {
+            name: " LMStudio ".to_string(),
+            base_url: "http://127.0.0.1:1234/v1/".to_string(),
+            api_key: String::new(),
+            model_ids: vec!["qwen2.5-7b".to_string(), " ".to_string()],
+            context_window: Some(32_768),
+        })
+        .expect("valid");
+        assert_eq!(input.name, "lmstudio");
+        assert_eq!(input.model_ids, vec!["qwen2.5-7b"]);
+
+        let writes = provider_config_writes(&input, Some("LMSTUDIO_API_KEY"));
+        assert_eq!(
+            writes[1],
+            (
+                "models.providers.lmstudio.baseUrl".to_string(),
+                "http://127.0.0.1:1234/v1".to_string()
+            )
+        );
+        assert_eq!(writes[3].1, "${LMSTUDIO_API_KEY}");
+        assert_eq!(
+            writes[4].1,
+            r#"[{"id":"qwen2.5-7b","name":"qwen2.5-7b","contextWindow":32768}]"#
+        );
+        assert_eq!(provider_config_writes(&input, None)[3].1, "local");
+
+        let builtin = CustomProviderInput {
+            name: "openai".to_string(),
+            base_url: "https://example.org/v1".to_string(),
+            model_ids: vec!["m".to_string()],
+            ..CustomProviderInput::default()
+        };
+        assert!(normalize_input(&builtin).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use super::{config, logger, paths, shell, state_store};
+
+// Scheduled task registered by `openclaw onboard --install-daemon` / `openclaw gateway install`.
+const DAEMON_TASK_NAME: &str = "OpenClaw Gateway";
+const DETECT_CACHE_TTL: Duration = Duration::from_secs(30);
+
+// Keyed by the profile it was taken for, so a switch never reads another profile's answer.
+type DetectCache = Option<(Option<String>, Instant, Option<DaemonInfo>)>;
+
+static DETECT_CACHE: OnceLock<Mutex<DetectCache>> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DaemonInfo {
+    pub task_name: String,
+    /// Scheduled task state as reported by schtasks (e.g. `Running`, `Ready`, `Disabled`).
+    pub state: String,
+}
+
+impl DaemonInfo {
+    pub fn is_running(&self) -> bool {
+        self.state.eq_ignore_ascii_case("running")
+    }
+}
+
+/// Returns the installed OpenClaw daemon, if any. Cached briefly because status polls often.
+pub fn detect() -> Option<DaemonInfo> {
+    let profile = paths::active_profile();
+    let lock = DETECT_CACHE.get_or_init(|| Mutex::new(None));
+    let mut guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_for, at, info)) = guard.as_ref() {
+        if *cached_for == profile && at.elapsed() < DETECT_CACHE_TTL {
+            return info.clone();
+        }
+    }
+    let info = query_task();
+    *guard = Some((profile, Instant::now(), info.clone()));
+    info
+}
+
+pub fn invalidate() {
+    if let Some(lock) = DETECT_CACHE.get() {
+        *lock.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+pub fn start() -> Result<()> {
+    run_gateway_control("start")
+}
+
+pub fn stop() -> Result<()> {
+    run_gateway_control("stop")
+}
+
+pub fn restart() -> Result<()> {
+    run_gateway_control("restart")
+}
+
+fn run_gateway_control(action: &str) -> Result<()> {
+    let proxy = state_store::load_last_config()?.and_then(|cfg| cfg.proxy);
+    let out = config::run_openclaw_cli(&["gateway".to_string(), action.to_string()], proxy)?;
+    invalidate();
+    if out.code != 0 {
+        return Err(anyhow!(
+            "OpenClaw daemon {action} failed: {}",
+            config::cli_output_text(&out)
+        ));
+    }
+    logger::info(&format!("OpenClaw daemon {action} requested."));
+    Ok(())
+}
+
+fn query_task() -> Option<DaemonInfo> {
+    let out = shell::run_command(
+        "schtasks",
+        &["/Query", "/TN", DAEMON_TASK_NAME, "/FO", "LIST"],
+        None,
+        &[],
+    )
+    .ok()?;
+    if out.code != 0 {
+        return None;
+    }
+    Some(DaemonInfo {
+        task_name: DAEMON_TASK_NAME.to_string(),
+        state: parse_task_state(&out.stdout).unwrap_or_else(|| "Unknown".to_string()),
+    })
+}
+
+fn parse_task_state(list_output: &str) -> Option<String> {
+    list_output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        // schtasks localizes the label; "Status" is the English one.
+        if key.trim().eq_ignore_ascii_case("status") {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_task_state, query_task};
+    use crate::modules::shell::{testing::RecordingRunner, with_runner};
+
+    #[test]
+    fn parse_task_state_reads_status_line() {
+        let raw = "Folder: \\\r\nHostName:      PC\r\nTaskName:      \\OpenClaw Gateway\r\nNext Run Time: N/A\r\nStatus:        Running\r\nLogon Mode:    Interactive only\r\n";
+        assert_eq!(parse_task_state(raw), Some("Running".to_string()));
+        assert_eq!(parse_task_state("TaskName: x"), None);
+    }
+
+    #[test]
+    fn query_task_asks_schtasks_for_the_gateway_task() {
+        let runner = RecordingRunner::new();
+        runner.respond(
+            "schtasks",
+            0,
+            "TaskName: \\OpenClaw Gateway\r\nStatus: Ready\r\n",
+        );
+        let info = with_runner(runner.clone(), query_task).expect("task found");
+        assert_eq!(info.state, "Ready");
+        assert!(!info.is_running());
+        assert_eq!(
+            runner.lines(),
+            vec!["schtasks /Query /TN OpenClaw Gateway /FO LIST"]
+        );
+
+        let missing = RecordingRunner::new();
+        missing.respond("schtasks", 1, "");
+        assert!(with_runner(missing, query_task).is_none());
+    }
+}
@@ -0,0 +1,296 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use chrono::Local;
+
+#[cfg(windows)]
+use super::{config, logger};
+
+/// A `.env` file kept line-for-line so edits only touch the entries they change:
+/// comments, blank lines, ordering, `export` prefixes and quote styles survive.
+#[derive(Debug, Default)]
+pub struct EnvFile {
+    lines: Vec<Line>,
+    crlf: bool,
+}
+
+#[derive(Debug)]
+enum Line {
+    Entry(Entry),
+    /// Comments, blanks and anything unparseable, kept verbatim.
+    Other(String),
+}
+
+#[derive(Debug)]
+struct Entry {
+    export: bool,
+    key: String,
+    value: String,
+    quote: Quote,
+    /// Trailing ` # comment` after an unquoted or quoted value.
+    comment: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quote {
+    None,
+    Single,
+    Double,
+}
+
+impl EnvFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Ok(Self::parse(&fs::read_to_string(path)?))
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim_start_matches('\u{feff}');
+        Self {
+            lines: text
+                .lines()
+                .map(|line| match parse_entry(line) {
+                    Some(entry) => Line::Entry(entry),
+                    None => Line::Other(line.to_string()),
+                })
+                .collect(),
+            crlf: text.contains("\r\n"),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        // Later assignments win, matching how dotenv loaders resolve duplicates.
+        self.entries()
+            .filter(|entry| entry.key == key)
+            .last()
+            .map(|entry| entry.value.as_str())
+    }
+
+    /// Distinct keys in file order.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys = Vec::<&str>::new();
+        for entry in self.entries() {
+            if !keys.contains(&entry.key.as_str()) {
+                keys.push(entry.key.as_str());
+            }
+        }
+        keys
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) {
+        let mut found = false;
+        for line in &mut self.lines {
+            if let Line::Entry(entry) = line {
+                if entry.key == key {
+                    entry.quote = quote_for(value, entry.quote);
+                    entry.value = value.to_string();
+                    found = true;
+                }
+            }
+        }
+        if !found {
+            self.lines.push(Line::Entry(Entry {
+                export: false,
+                key: key.to_string(),
+                value: value.to_string(),
+                quote: quote_for(value, Quote::None),
+                comment: String::new(),
+            }));
+        }
+    }
+
+    /// Removes `key`, leaving a dated comment in its place so the removal is visible.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let stamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+        let mut removed = false;
+        for line in &mut self.lines {
+            if matches!(line, Line::Entry(entry) if entry.key == key) {
+                *line = Line::Other(format!("# {key} removed by OpenClaw Installer ({stamp})"));
+                removed = true;
+            }
+        }
+        removed
+    }
+
+    pub fn render(&self) -> String {
+        let newline = if self.crlf { "\r\n" } else { "\n" };
+        let mut out = self
+            .lines
+            .iter()
+            .map(|line| match line {
+                Line::Entry(entry) => render_entry(entry),
+                Line::Other(text) => text.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(newline);
+        if !out.is_empty() {
+            out.push_str(newline);
+        }
+        out
+    }
+
+    /// Writes through a sibling temp file and a rename so a crash never leaves a
+    /// half-written `.env` behind. The rename carries the temp file's ACL (inherited
+    /// from the folder), so the owner-only ACL is put on it before it replaces `.env`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| ".env".to_string());
+        let tmp = path.with_file_name(format!("{file_name}.tmp"));
+        fs::write(&tmp, self.render())?;
+        #[cfg(windows)]
+        for warning in config::set_windows_acl(&tmp) {
+            logger::warn(&format!("{}: {}", path.to_string_lossy(), warning.message));
+        }
+        if let Err(err) = fs::rename(&tmp, path) {
+            let _ = fs::remove_file(&tmp);
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Entry(entry) => Some(entry),
+            Line::Other(_) => None,
+        })
+    }
+}
+
+fn parse_entry(line: &str) -> Option<Entry> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return None;
+    }
+    let (export, rest) = match trimmed.strip_prefix("export ") {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, trimmed),
+    };
+    let (key, raw) = rest.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '.')
+    {
+        return None;
+    }
+    let raw = raw.trim_start();
+    let (value, quote, tail) = match raw.chars().next() {
+        Some(q @ ('"' | '\'')) => {
+            let quote = if q == '"' {
+                Quote::Double
+            } else {
+                Quote::Single
+            };
+            let (value, consumed) = read_quoted(&raw[1..], q)?;
+            (value, quote, &raw[1 + consumed..])
+        }
+        _ => {
+            let end = raw.find(" #").unwrap_or(raw.len());
+            (raw[..end].trim_end().to_string(), Quote::None, &raw[end..])
+        }
+    };
+    Some(Entry {
+        export,
+        key: key.to_string(),
+        value,
+        quote,
+        comment: tail.trim_end().to_string(),
+    })
+}
+
+/// Reads a quoted value after its opening quote; returns the value and the bytes
+/// consumed including the closing quote. Double quotes support `\n`, `\"` and `\\`.
+fn read_quoted(text: &str, quote: char) -> Option<(String, usize)> {
+    let mut value = String::new();
+    let mut chars = text.char_indices();
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            c if c == quote => return Some((value, index + c.len_utf8())),
+            '\\' if quote == '"' => match chars.next()?.1 {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                other => value.push(other),
+            },
+            other => value.push(other),
+        }
+    }
+    None
+}
+
+fn quote_for(value: &str, previous: Quote) -> Quote {
+    let needs_quotes = value.is_empty()
+        || value
+            .chars()
+            .any(|ch| ch.is_whitespace() || matches!(ch, '#' | '"' | '\'' | '\\' | '='));
+    match previous {
+        Quote::Single if !value.contains('\'') && !value.contains('\n') => Quote::Single,
+        Quote::Double => Quote::Double,
+        _ if needs_quotes => Quote::Double,
+        _ => Quote::None,
+    }
+}
+
+fn render_entry(entry: &Entry) -> String {
+    let value = match entry.quote {
+        Quote::None => entry.value.clone(),
+        Quote::Single => format!("'{}'", entry.value),
+        Quote::Double => format!(
+            "\"{}\"",
+            entry
+                .value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r")
+        ),
+    };
+    format!(
+        "{}{}={value}{}",
+        if entry.export { "export " } else { "" },
+        entry.key,
+        entry.comment
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EnvFile;
+
+    #[test]
+    fn set_preserves_comments_order_export_and_quotes() {
+        let raw = "# provider keys\nexport OPENAI_API_KEY='sk-old' # rotated monthly\n\nBASE=\"https://x.test/?a=1\"\nPLAIN=a=b\n";
+        let mut env = EnvFile::parse(raw);
+        assert_eq!(env.get("OPENAI_API_KEY"), Some("sk-old"));
+        assert_eq!(env.get("BASE"), Some("https://x.test/?a=1"));
+        assert_eq!(env.get("PLAIN"), Some("a=b"));
+
+        env.set("OPENAI_API_KEY", "sk-new");
+        env.set("ZAI_API_KEY", "has space");
+        assert_eq!(
+            env.render(),
+            "# provider keys\nexport OPENAI_API_KEY='sk-new' # rotated monthly\n\nBASE=\"https://x.test/?a=1\"\nPLAIN=a=b\nZAI_API_KEY=\"has space\"\n"
+        );
+    }
+
+    #[test]
+    fn remove_leaves_trace_and_round_trips_escapes() {
+        let mut env = EnvFile::parse("A=1\r\nB=\"line\\nnext \\\"q\\\"\"\r\n");
+        assert_eq!(env.get("B"), Some("line\nnext \"q\""));
+        assert!(env.remove("A"));
+        assert!(!env.remove("MISSING"));
+        let out = env.render();
+        assert!(out.starts_with("# A removed by OpenClaw Installer ("));
+        assert!(out.ends_with("B=\"line\\nnext \\\"q\\\"\"\r\n"));
+        assert_eq!(EnvFile::parse(&out).get("B"), Some("line\nnext \"q\""));
+    }
+}
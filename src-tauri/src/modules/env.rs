@@ -1,10 +1,43 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use reqwest::Client;
+use serde::Deserialize;
 use std::time::Duration;
 
-use crate::models::{DependencyStatus, EnvCheckResult, InstallEnvResult};
+use crate::models::{
+    CustomProviderInput, CustomProviderResult, DependencyStatus, EnvCheckResult, InstallEnvResult,
+    OllamaModel, OllamaStatus, ResultWarning,
+};
 
-use super::{logger, paths, port, shell};
+use super::{config, custom_provider, logger, paths, port, shell};
+
+const OLLAMA_URL: &str = "http://127.0.0.1:11434";
+const OLLAMA_PROVIDER: &str = "ollama";
+
+#[derive(Deserialize)]
+struct OllamaTags {
+    #[serde(default)]
+    models: Vec<OllamaTag>,
+}
+
+#[derive(Deserialize)]
+struct OllamaTag {
+    name: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    details: Option<OllamaTagDetails>,
+}
+
+#[derive(Deserialize)]
+struct OllamaTagDetails {
+    #[serde(default)]
+    parameter_size: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OllamaVersion {
+    version: String,
+}
 
 pub async fn check_env(port_number: u16) -> Result<EnvCheckResult> {
     paths::ensure_dirs()?;
@@ -30,7 +63,7 @@ pub async fn check_env(port_number: u16) -> Result<EnvCheckResult> {
 pub fn install_env(_port_number: u16) -> Result<InstallEnvResult> {
     let mut installed = Vec::new();
     let mut skipped = Vec::new();
-    let mut warnings = Vec::new();
+    let mut warnings = Vec::<ResultWarning>::new();
 
     let deps = dependency_status();
     let has_git = deps.iter().any(|d| d.name == "git" && d.found);
@@ -62,25 +95,46 @@ pub fn install_env(_port_number: u16) -> Result<InstallEnvResult> {
             &[],
         ) {
             Ok(out) if out.code == 0 => installed.push("git".to_string()),
-            Ok(out) => warnings.push(format!("git install failed: {}", out.stderr)),
-            Err(err) => warnings.push(format!("git install failed: {err}")),
+            Ok(out) => warnings.push(ResultWarning::error(
+                "dependency_install_failed",
+                format!("git install failed: {}", out.stderr),
+            )),
+            Err(err) => warnings.push(ResultWarning::error(
+                "dependency_install_failed",
+                format!("git install failed: {err}"),
+            )),
         }
     } else if has_choco {
         match shell::run_command("choco", &["install", "git", "-y"], None, &[]) {
             Ok(out) if out.code == 0 => installed.push("git".to_string()),
-            Ok(out) => warnings.push(format!("git install failed: {}", out.stderr)),
-            Err(err) => warnings.push(format!("git install failed: {err}")),
+            Ok(out) => warnings.push(ResultWarning::error(
+                "dependency_install_failed",
+                format!("git install failed: {}", out.stderr),
+            )),
+            Err(err) => warnings.push(ResultWarning::error(
+                "dependency_install_failed",
+                format!("git install failed: {err}"),
+            )),
         }
     } else {
-        warnings.push("Neither winget nor choco found. Install Git manually.".to_string());
+        warnings.push(
+            ResultWarning::error(
+                "package_manager_missing",
+                "Neither winget nor choco found. Install Git manually.",
+            )
+            .with_remediation("Install Git from https://git-scm.com/download/win and rerun."),
+        );
     }
 
     if has_bun || (has_node && has_npm && node_supported) {
         skipped.push("node-or-bun".to_string());
     } else if has_node && has_npm && !node_supported {
-        warnings.push(format!(
-            "Node.js version {:?} detected, OpenClaw requires Node.js 22+; trying upgrade.",
-            node_major
+        warnings.push(ResultWarning::info(
+            "node_version_unsupported",
+            format!(
+                "Node.js version {:?} detected, OpenClaw requires Node.js 22+; trying upgrade.",
+                node_major
+            ),
         ));
         if has_winget {
             match shell::run_command(
@@ -99,19 +153,34 @@ pub fn install_env(_port_number: u16) -> Result<InstallEnvResult> {
                 &[],
             ) {
                 Ok(out) if out.code == 0 => installed.push("nodejs-lts".to_string()),
-                Ok(out) => warnings.push(format!("node upgrade failed: {}", out.stderr)),
-                Err(err) => warnings.push(format!("node upgrade failed: {err}")),
+                Ok(out) => warnings.push(ResultWarning::error(
+                    "dependency_upgrade_failed",
+                    format!("node upgrade failed: {}", out.stderr),
+                )),
+                Err(err) => warnings.push(ResultWarning::error(
+                    "dependency_upgrade_failed",
+                    format!("node upgrade failed: {err}"),
+                )),
             }
         } else if has_choco {
             match shell::run_command("choco", &["upgrade", "nodejs-lts", "-y"], None, &[]) {
                 Ok(out) if out.code == 0 => installed.push("nodejs-lts".to_string()),
-                Ok(out) => warnings.push(format!("node upgrade failed: {}", out.stderr)),
-                Err(err) => warnings.push(format!("node upgrade failed: {err}")),
+                Ok(out) => warnings.push(ResultWarning::error(
+                    "dependency_upgrade_failed",
+                    format!("node upgrade failed: {}", out.stderr),
+                )),
+                Err(err) => warnings.push(ResultWarning::error(
+                    "dependency_upgrade_failed",
+                    format!("node upgrade failed: {err}"),
+                )),
             }
         } else {
             warnings.push(
-                "Node.js is below 22 and no winget/choco is available for auto-upgrade."
-                    .to_string(),
+                ResultWarning::error(
+                    "package_manager_missing",
+                    "Node.js is below 22 and no winget/choco is available for auto-upgrade.",
+                )
+                .with_remediation("Install Node.js 22 LTS from https://nodejs.org and rerun."),
             );
         }
     } else if has_winget {
@@ -131,18 +200,35 @@ pub fn install_env(_port_number: u16) -> Result<InstallEnvResult> {
             &[],
         ) {
             Ok(out) if out.code == 0 => installed.push("nodejs-lts".to_string()),
-            Ok(out) => warnings.push(format!("node install failed: {}", out.stderr)),
-            Err(err) => warnings.push(format!("node install failed: {err}")),
+            Ok(out) => warnings.push(ResultWarning::error(
+                "dependency_install_failed",
+                format!("node install failed: {}", out.stderr),
+            )),
+            Err(err) => warnings.push(ResultWarning::error(
+                "dependency_install_failed",
+                format!("node install failed: {err}"),
+            )),
         }
     } else if has_choco {
         match shell::run_command("choco", &["install", "nodejs-lts", "-y"], None, &[]) {
             Ok(out) if out.code == 0 => installed.push("nodejs-lts".to_string()),
-            Ok(out) => warnings.push(format!("node install failed: {}", out.stderr)),
-            Err(err) => warnings.push(format!("node install failed: {err}")),
+            Ok(out) => warnings.push(ResultWarning::error(
+                "dependency_install_failed",
+                format!("node install failed: {}", out.stderr),
+            )),
+            Err(err) => warnings.push(ResultWarning::error(
+                "dependency_install_failed",
+                format!("node install failed: {err}"),
+            )),
         }
     } else {
-        warnings
-            .push("Neither winget nor choco found. Install Node.js or Bun manually.".to_string());
+        warnings.push(
+            ResultWarning::error(
+                "package_manager_missing",
+                "Neither winget nor choco found. Install Node.js or Bun manually.",
+            )
+            .with_remediation("Install Node.js 22 LTS from https://nodejs.org and rerun."),
+        );
     }
 
     if has_vcredist {
@@ -164,13 +250,22 @@ pub fn install_env(_port_number: u16) -> Result<InstallEnvResult> {
             &[],
         ) {
             Ok(out) if out.code == 0 => installed.push("vcredist".to_string()),
-            Ok(out) => warnings.push(format!("vcredist install failed: {}", out.stderr)),
-            Err(err) => warnings.push(format!("vcredist install failed: {err}")),
+            Ok(out) => warnings.push(ResultWarning::error(
+                "dependency_install_failed",
+                format!("vcredist install failed: {}", out.stderr),
+            )),
+            Err(err) => warnings.push(ResultWarning::error(
+                "dependency_install_failed",
+                format!("vcredist install failed: {err}"),
+            )),
         }
     } else {
         warnings.push(
-            "Visual C++ runtime not detected; install Microsoft VC++ Redistributable x64."
-                .to_string(),
+            ResultWarning::warning(
+                "vcredist_missing",
+                "Visual C++ runtime not detected; install Microsoft VC++ Redistributable x64.",
+            )
+            .with_remediation("Download it from https://aka.ms/vs/17/release/vc_redist.x64.exe."),
         );
     }
 
@@ -179,7 +274,11 @@ pub fn install_env(_port_number: u16) -> Result<InstallEnvResult> {
     } else {
         logger::warn(&format!(
             "Environment installation warnings: {}",
-            warnings.join(" | ")
+            warnings
+                .iter()
+                .map(|w| w.message.as_str())
+                .collect::<Vec<_>>()
+                .join(" | ")
         ));
     }
 
@@ -245,11 +344,209 @@ fn has_vc_runtime() -> bool {
     false
 }
 
-fn node_major_version() -> Option<u32> {
+/// Installed Node.js version without the leading `v`, e.g. `22.12.0`.
+pub fn node_version() -> Option<String> {
     let out = shell::run_command("node", &["--version"], None, &[]).ok()?;
     if out.code != 0 {
         return None;
     }
-    let raw = out.stdout.trim().trim_start_matches('v');
-    raw.split('.').next()?.parse::<u32>().ok()
+    Some(out.stdout.trim().trim_start_matches('v').to_string())
+}
+
+fn node_major_version() -> Option<u32> {
+    node_version()?.split('.').next()?.parse::<u32>().ok()
+}
+
+/// Whether Ollama is installed and serving, with the models it has pulled.
+pub async fn detect_ollama() -> OllamaStatus {
+    let path = shell::command_exists("ollama");
+    let mut status = OllamaStatus {
+        installed: path.is_some(),
+        path,
+        ..OllamaStatus::default()
+    };
+    // Local only: a system proxy must not see (or swallow) loopback requests.
+    let Ok(client) = Client::builder()
+        .timeout(Duration::from_secs(3))
+        .no_proxy()
+        .build()
+    else {
+        return status;
+    };
+    let Ok(resp) = client.get(format!("{OLLAMA_URL}/api/tags")).send().await else {
+        return status;
+    };
+    if !resp.status().is_success() {
+        return status;
+    }
+    status.running = true;
+    status.models = resp
+        .text()
+        .await
+        .ok()
+        .and_then(|body| parse_ollama_tags(&body))
+        .unwrap_or_default();
+    if let Ok(resp) = client.get(format!("{OLLAMA_URL}/api/version")).send().await {
+        status.version = resp.json::<OllamaVersion>().await.ok().map(|v| v.version);
+    }
+    status
+}
+
+fn parse_ollama_tags(body: &str) -> Option<Vec<OllamaModel>> {
+    let tags = serde_json::from_str::<OllamaTags>(body).ok()?;
+    Some(
+        tags.models
+            .into_iter()
+            .map(|tag| OllamaModel {
+                name: tag.name,
+                size_bytes: tag.size,
+                parameter_size: tag.details.and_then(|d| d.parameter_size),
+            })
+            .collect(),
+    )
+}
+
+/// Installs Ollama with winget. Ollama starts its local server after installing.
+pub fn install_ollama() -> Result<InstallEnvResult> {
+    if shell::command_exists("ollama").is_some() {
+        return Ok(InstallEnvResult {
+            installed: Vec::new(),
+            skipped: vec!["ollama".to_string()],
+            warnings: Vec::new(),
+        });
+    }
+    if shell::command_exists("winget").is_none() {
+        return Err(anyhow!(
+            "winget not found. Install Ollama from https://ollama.com/download and rerun."
+        ));
+    }
+    let out = shell::run_command(
+        "winget",
+        &[
+            "install",
+            "--id",
+            "Ollama.Ollama",
+            "-e",
+            "--source",
+            "winget",
+            "--accept-package-agreements",
+            "--accept-source-agreements",
+        ],
+        None,
+        &[],
+    )?;
+    shell::ensure_success("winget install Ollama.Ollama", &out)?;
+    logger::info("Ollama installed via winget.");
+    Ok(InstallEnvResult {
+        installed: vec!["ollama".to_string()],
+        skipped: Vec::new(),
+        warnings: Vec::new(),
+    })
+}
+
+/// Registers the running Ollama as the keyless `ollama` provider with `models` (all
+/// pulled models when empty) and adds them to the model chain: as the primary model
+/// when `make_primary`, otherwise after the existing fallbacks.
+pub async fn configure_ollama(
+    models: &[String],
+    make_primary: bool,
+) -> Result<CustomProviderResult> {
+    let status = detect_ollama().await;
+    if !status.running {
+        return Err(anyhow!(
+            "Ollama is not answering on {OLLAMA_URL}. Start Ollama (or install it) first."
+        ));
+    }
+    let pulled = status
+        .models
+        .into_iter()
+        .map(|model| model.name)
+        .collect::<Vec<_>>();
+    let model_ids = if models.is_empty() {
+        pulled
+    } else {
+        if let Some(missing) = models.iter().find(|model| !pulled.contains(model)) {
+            return Err(anyhow!(
+                "Ollama has no model {missing}; run `ollama pull {missing}` first."
+            ));
+        }
+        models.to_vec()
+    };
+    if model_ids.is_empty() {
+        return Err(anyhow!(
+            "Ollama has no models yet; run e.g. `ollama pull llama3.1:8b` first."
+        ));
+    }
+
+    let mut result = custom_provider::add_custom_provider(&CustomProviderInput {
+        name: OLLAMA_PROVIDER.to_string(),
+        base_url: format!("{OLLAMA_URL}/v1"),
+        model_ids,
+        ..CustomProviderInput::default()
+    })?;
+    let chain = config::read_current_config()?.model_chain;
+    let (primary, fallbacks) = ollama_chain(
+        &chain.primary,
+        &chain.fallbacks,
+        &result.model_keys,
+        make_primary,
+    );
+    let switched = config::switch_model(&primary, &fallbacks)?;
+    result.warnings.extend(switched.warnings);
+    Ok(result)
+}
+
+fn ollama_chain(
+    primary: &str,
+    fallbacks: &[String],
+    ollama_keys: &[String],
+    make_primary: bool,
+) -> (String, Vec<String>) {
+    let existing = std::iter::once(primary.to_string())
+        .filter(|key| !key.trim().is_empty())
+        .chain(fallbacks.iter().cloned());
+    let ordered = if make_primary {
+        ollama_keys
+            .iter()
+            .cloned()
+            .chain(existing)
+            .collect::<Vec<_>>()
+    } else {
+        existing.chain(ollama_keys.iter().cloned()).collect()
+    };
+    let mut chain = Vec::<String>::new();
+    for key in ordered {
+        if !chain.contains(&key) {
+            chain.push(key);
+        }
+    }
+    let primary = chain.remove(0);
+    (primary, chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ollama_chain, parse_ollama_tags};
+
+    #[test]
+    fn ollama_models_are_parsed_and_chained() {
+        let body = r#"{"models":[{"name":"llama3.1:8b","model":"llama3.1:8b","size":4920753328,"details":{"format":"gguf","parameter_size":"8.0B"}},{"name":"qwen2.5:7b"}]}"#;
+        let models = parse_ollama_tags(body).expect("tags");
+        assert_eq!(models[0].name, "llama3.1:8b");
+        assert_eq!(models[0].parameter_size.as_deref(), Some("8.0B"));
+        assert_eq!(models[1].size_bytes, 0);
+
+        let keys = vec!["ollama/llama3.1:8b".to_string()];
+        let fallbacks = vec!["openai/gpt-4o-mini".to_string()];
+        assert_eq!(
+            ollama_chain("anthropic/claude-sonnet-4", &fallbacks, &keys, false),
+            (
+                "anthropic/claude-sonnet-4".to_string(),
+                vec!["openai/gpt-4o-mini".to_string(), keys[0].clone()]
+            )
+        );
+        let (primary, rest) = ollama_chain("anthropic/claude-sonnet-4", &[], &keys, true);
+        assert_eq!(primary, keys[0]);
+        assert_eq!(rest, vec!["anthropic/claude-sonnet-4"]);
+    }
 }
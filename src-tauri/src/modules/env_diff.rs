@@ -0,0 +1,266 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{EnvDiffReport, EnvVarDiff};
+
+use super::{config, logger, paths, process, shell};
+
+const REDACTED: &str = "<redacted>";
+// Variables that change how the gateway and its tools decode text.
+const ENCODING_VARS: [&str; 7] = [
+    "LANG",
+    "LANGUAGE",
+    "LC_ALL",
+    "LC_CTYPE",
+    "NODE_OPTIONS",
+    "PYTHONIOENCODING",
+    "PYTHONUTF8",
+];
+const SECRET_MARKERS: [&str; 6] = ["KEY", "TOKEN", "SECRET", "PASSWORD", "CREDENTIAL", "PROXY"];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SpawnEnv {
+    captured_at: String,
+    vars: BTreeMap<String, String>,
+}
+
+/// Saves the environment the gateway was just spawned with (the installer's own plus
+/// `overrides`), secrets redacted, for `diff_gateway_env`.
+pub fn record_spawn_env(overrides: &[(String, String)]) {
+    let snapshot = SpawnEnv {
+        captured_at: Local::now().to_rfc3339(),
+        vars: redact(merged_env(overrides)),
+    };
+    let result = serde_json::to_string_pretty(&snapshot)
+        .map_err(anyhow::Error::from)
+        .and_then(|text| fs::write(snapshot_path(), text).map_err(anyhow::Error::from));
+    if let Err(err) = result {
+        logger::warn(&format!(
+            "Failed to record gateway spawn environment: {err}"
+        ));
+    }
+}
+
+/// Compares the environment the gateway got from the GUI with what a new terminal gets
+/// (machine + user variables from the registry), for gateways that only misbehave when
+/// started from the installer. Without a recorded spawn, the environment the next start
+/// would use is compared instead.
+pub fn diff_gateway_env() -> Result<EnvDiffReport> {
+    let (gateway, captured_at) = match load_snapshot() {
+        Some(snapshot) => (snapshot.vars, Some(snapshot.captured_at)),
+        None => {
+            let cfg = config::read_current_config()?;
+            (redact(merged_env(&process::runtime_env(&cfg))), None)
+        }
+    };
+    let shell_env = redact(user_shell_env()?);
+    let mut report = diff_env(&gateway, &shell_env);
+    report.captured_at = captured_at;
+    Ok(report)
+}
+
+fn snapshot_path() -> PathBuf {
+    paths::run_dir().join("gateway-env.json")
+}
+
+fn load_snapshot() -> Option<SpawnEnv> {
+    let raw = fs::read_to_string(snapshot_path()).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn merged_env(overrides: &[(String, String)]) -> BTreeMap<String, String> {
+    let mut vars = std::env::vars_os()
+        .map(|(k, v)| {
+            (
+                k.to_string_lossy().to_string(),
+                v.to_string_lossy().to_string(),
+            )
+        })
+        .collect::<BTreeMap<_, _>>();
+    for (key, value) in overrides {
+        // Windows variable names are case-insensitive; replace any spelling.
+        vars.retain(|existing, _| !existing.eq_ignore_ascii_case(key));
+        vars.insert(key.clone(), value.clone());
+    }
+    vars
+}
+
+/// Machine and user variables as a freshly opened terminal sees them; the user `Path`
+/// is appended to the machine one, as Explorer does.
+fn user_shell_env() -> Result<BTreeMap<String, String>> {
+    let script = "$o=@{};foreach($s in 'Machine','User'){$v=[Environment]::GetEnvironmentVariables($s);foreach($k in $v.Keys){if($k -ieq 'Path' -and $o.ContainsKey('Path')){$o['Path']=$o['Path']+';'+$v[$k]}else{$o[$k]=$v[$k]}}};$o|ConvertTo-Json -Compress";
+    let out = shell::run_command(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", script],
+        None,
+        &[],
+    )?;
+    shell::ensure_success("read user environment", &out)?;
+    let value = serde_json::from_str::<serde_json::Value>(out.stdout.trim())
+        .map_err(|err| anyhow!("Unexpected user environment output: {err}"))?;
+    Ok(value
+        .as_object()
+        .map(|map| {
+            map.iter()
+                .map(|(k, v)| (k.clone(), v.as_str().unwrap_or_default().to_string()))
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Env names whose values are never shown: keys, tokens, passwords and proxies (which may
+/// carry credentials).
+pub fn is_secret_name(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    SECRET_MARKERS.iter().any(|marker| upper.contains(marker))
+}
+
+fn redact(vars: BTreeMap<String, String>) -> BTreeMap<String, String> {
+    vars.into_iter()
+        .map(|(k, v)| {
+            if is_secret_name(&k) && !v.is_empty() {
+                (k, REDACTED.to_string())
+            } else {
+                (k, v)
+            }
+        })
+        .collect()
+}
+
+fn diff_env(gateway: &BTreeMap<String, String>, shell: &BTreeMap<String, String>) -> EnvDiffReport {
+    let upper = |vars: &BTreeMap<String, String>| {
+        vars.iter()
+            .map(|(k, v)| (k.to_ascii_uppercase(), (k.clone(), v.clone())))
+            .collect::<BTreeMap<_, _>>()
+    };
+    let gateway = upper(gateway);
+    let shell = upper(shell);
+    let path_of = |vars: &BTreeMap<String, (String, String)>| {
+        vars.get("PATH")
+            .map(|(_, v)| path_entries(v))
+            .unwrap_or_default()
+    };
+    let gateway_path = path_of(&gateway);
+    let shell_path = path_of(&shell);
+    let normalized = |entries: &[String]| {
+        entries
+            .iter()
+            .map(|e| normalize_path_entry(e))
+            .collect::<BTreeSet<_>>()
+    };
+    let (gateway_set, shell_set) = (normalized(&gateway_path), normalized(&shell_path));
+
+    let encoding_vars = ENCODING_VARS
+        .iter()
+        .filter_map(|name| {
+            let gateway_value = gateway.get(*name).map(|(_, v)| v.clone());
+            let shell_value = shell.get(*name).map(|(_, v)| v.clone());
+            (gateway_value != shell_value).then(|| EnvVarDiff {
+                name: name.to_string(),
+                gateway: gateway_value,
+                shell: shell_value,
+            })
+        })
+        .collect();
+    let compared = |key: &String| key != "PATH" && !ENCODING_VARS.contains(&key.as_str());
+    EnvDiffReport {
+        captured_at: None,
+        missing_path_entries: shell_path
+            .iter()
+            .filter(|e| !gateway_set.contains(&normalize_path_entry(e)))
+            .cloned()
+            .collect(),
+        extra_path_entries: gateway_path
+            .iter()
+            .filter(|e| !shell_set.contains(&normalize_path_entry(e)))
+            .cloned()
+            .collect(),
+        encoding_vars,
+        missing_vars: shell
+            .iter()
+            .filter(|(key, _)| compared(key) && !gateway.contains_key(*key))
+            .map(|(_, (name, _))| name.clone())
+            .collect(),
+        changed_vars: shell
+            .iter()
+            .filter(|(key, _)| compared(key))
+            .filter(|(key, (_, value))| {
+                gateway
+                    .get(*key)
+                    .is_some_and(|(_, other)| other != value && other != REDACTED)
+            })
+            .map(|(_, (name, _))| name.clone())
+            .collect(),
+    }
+}
+
+fn path_entries(raw: &str) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    raw.split(';')
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .filter(|e| seen.insert(normalize_path_entry(e)))
+        .map(str::to_string)
+        .collect()
+}
+
+fn normalize_path_entry(entry: &str) -> String {
+    entry
+        .trim()
+        .trim_matches('"')
+        .replace('/', "\\")
+        .trim_end_matches('\\')
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff_env, redact};
+    use std::collections::BTreeMap;
+
+    fn vars(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn diff_reports_path_and_encoding_differences() {
+        let gateway = redact(vars(&[
+            (
+                "Path",
+                "C:\\Windows\\system32;C:\\nodejs\\;C:\\OpenClaw\\bin",
+            ),
+            ("OPENAI_API_KEY", "sk-live"),
+            ("TEMP", "C:\\Temp"),
+        ]));
+        let shell = redact(vars(&[
+            (
+                "PATH",
+                "c:\\windows\\System32;C:\\nodejs;C:\\Program Files\\Git\\cmd",
+            ),
+            ("PYTHONUTF8", "1"),
+            ("OPENAI_API_KEY", "sk-other"),
+            ("TEMP", "D:\\Temp"),
+            ("GOPATH", "C:\\go"),
+        ]));
+        let report = diff_env(&gateway, &shell);
+        assert_eq!(
+            report.missing_path_entries,
+            vec!["C:\\Program Files\\Git\\cmd"]
+        );
+        assert_eq!(report.extra_path_entries, vec!["C:\\OpenClaw\\bin"]);
+        assert_eq!(report.encoding_vars.len(), 1);
+        assert_eq!(report.encoding_vars[0].name, "PYTHONUTF8");
+        assert_eq!(report.encoding_vars[0].gateway, None);
+        assert_eq!(report.missing_vars, vec!["GOPATH"]);
+        // Secrets are compared by name only.
+        assert_eq!(report.changed_vars, vec!["TEMP"]);
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use crate::models::{FeishuSkillsInput, FeishuSkillsResult};
+
+use super::{config, logger, state_store};
+
+const FEISHU_OPEN_API_BASE: &str = "https://open.feishu.cn/open-apis";
+// Scopes required by the feishu_doc / feishu_wiki tools shipped with the Feishu plugin.
+const FEISHU_DOC_SCOPES: &[&str] = &["docx:document", "docx:document:readonly", "drive:drive"];
+const FEISHU_WIKI_SCOPES: &[&str] = &["wiki:wiki", "wiki:wiki:readonly"];
+
+pub async fn configure_feishu_skills(options: &FeishuSkillsInput) -> Result<FeishuSkillsResult> {
+    if !options.enable_doc && !options.enable_wiki {
+        return Err(anyhow!("Select at least one Feishu skill (doc or wiki)."));
+    }
+
+    let last = state_store::load_last_config()?;
+    let proxy = last.as_ref().and_then(|cfg| cfg.proxy.clone());
+    let app_id = config::optional_non_empty(Some(options.app_id.clone()))
+        .or_else(|| {
+            last.as_ref()
+                .map(|cfg| cfg.feishu_app_id.trim().to_string())
+        })
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| anyhow!("Feishu app_id is required. Configure the Feishu channel first."))?;
+    let app_secret = config::optional_non_empty(Some(options.app_secret.clone()))
+        .or_else(|| {
+            last.as_ref()
+                .map(|cfg| cfg.feishu_app_secret.trim().to_string())
+        })
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| {
+            anyhow!("Feishu app_secret is required. Configure the Feishu channel first.")
+        })?;
+
+    let mut warnings = Vec::<String>::new();
+    let mut enabled_tools = Vec::<String>::new();
+
+    let plugin_out = config::run_openclaw_cli(
+        &[
+            "plugins".to_string(),
+            "enable".to_string(),
+            "feishu".to_string(),
+        ],
+        proxy.clone(),
+    )?;
+    if plugin_out.code != 0 {
+        warnings.push(format!(
+            "Feishu plugin enable failed: {}",
+            config::redact_known_values(config::cli_output_text(&plugin_out), &[&app_secret])
+        ));
+    }
+
+    let writes = vec![
+        ("channels.feishu.appId", app_id.clone()),
+        ("channels.feishu.appSecret", app_secret.clone()),
+        ("channels.feishu.tools.doc", options.enable_doc.to_string()),
+        (
+            "channels.feishu.tools.wiki",
+            options.enable_wiki.to_string(),
+        ),
+    ];
+    for (path, value) in writes {
+        let out = config::run_openclaw_cli(
+            &[
+                "config".to_string(),
+                "set".to_string(),
+                path.to_string(),
+                value,
+            ],
+            proxy.clone(),
+        )?;
+        if out.code != 0 {
+            warnings.push(format!(
+                "Feishu config write failed ({path}): {}",
+                config::redact_known_values(config::cli_output_text(&out), &[&app_secret])
+            ));
+        }
+    }
+    if options.enable_doc {
+        enabled_tools.push("feishu_doc".to_string());
+    }
+    if options.enable_wiki {
+        enabled_tools.push("feishu_wiki".to_string());
+    }
+
+    let mut required = Vec::<&str>::new();
+    if options.enable_doc {
+        required.extend_from_slice(FEISHU_DOC_SCOPES);
+    }
+    if options.enable_wiki {
+        required.extend_from_slice(FEISHU_WIKI_SCOPES);
+    }
+
+    let (granted_scopes, missing_scopes) =
+        match fetch_granted_scopes(&app_id, &app_secret, proxy.clone()).await {
+            Ok(granted) => {
+                let missing = missing_scopes(&required, &granted);
+                (granted, missing)
+            }
+            Err(err) => {
+                warnings.push(format!(
+                    "Feishu scope validation skipped: {}",
+                    config::redact_known_values(err.to_string(), &[&app_secret])
+                ));
+                (vec![], vec![])
+            }
+        };
+    if !missing_scopes.is_empty() {
+        warnings.push(format!(
+            "Feishu app is missing permissions: {}. Grant them in the Feishu developer console and publish a new app version.",
+            missing_scopes.join(", ")
+        ));
+    }
+
+    let restart_out = config::run_openclaw_cli(
+        &["gateway".to_string(), "restart".to_string()],
+        proxy.clone(),
+    )?;
+    if restart_out.code != 0 {
+        warnings.push(format!(
+            "Feishu gateway restart failed: {}",
+            config::redact_known_values(config::cli_output_text(&restart_out), &[&app_secret])
+        ));
+    }
+
+    if let Some(mut last) = last {
+        last.feishu_app_id = app_id;
+        last.feishu_app_secret = app_secret;
+        state_store::save_last_config(&last)?;
+    }
+
+    logger::info(&format!(
+        "Feishu skills configured: tools=[{}], missing_scopes={}",
+        enabled_tools.join(", "),
+        missing_scopes.len()
+    ));
+    Ok(FeishuSkillsResult {
+        enabled_tools,
+        granted_scopes,
+        missing_scopes,
+        warnings,
+    })
+}
+
+async fn fetch_granted_scopes(
+    app_id: &str,
+    app_secret: &str,
+    proxy: Option<String>,
+) -> Result<Vec<String>> {
+    let mut builder = Client::builder().timeout(Duration::from_secs(8));
+    if let Some(proxy) = config::optional_non_empty(proxy) {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    let client = builder.build()?;
+
+    let token_resp: Value = client
+        .post(format!(
+            "{FEISHU_OPEN_API_BASE}/auth/v3/tenant_access_token/internal"
+        ))
+        .json(&json!({ "app_id": app_id, "app_secret": app_secret }))
+        .send()
+        .await?
+        .json()
+        .await?;
+    ensure_feishu_ok("tenant_access_token", &token_resp)?;
+    let token = token_resp
+        .get("tenant_access_token")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("Feishu did not return a tenant_access_token"))?;
+
+    let scopes_resp: Value = client
+        .get(format!("{FEISHU_OPEN_API_BASE}/application/v6/scopes"))
+        .bearer_auth(token)
+        .send()
+        .await?
+        .json()
+        .await?;
+    ensure_feishu_ok("application scopes", &scopes_resp)?;
+    Ok(parse_granted_scopes(&scopes_resp))
+}
+
+fn ensure_feishu_ok(op: &str, resp: &Value) -> Result<()> {
+    let code = resp.get("code").and_then(|v| v.as_i64()).unwrap_or(-1);
+    if code == 0 {
+        return Ok(());
+    }
+    let msg = resp
+        .get("msg")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown error");
+    Err(anyhow!("Feishu {op} request failed (code={code}): {msg}"))
+}
+
+fn parse_granted_scopes(resp: &Value) -> Vec<String> {
+    let Some(items) = resp.pointer("/data/scopes").and_then(|v| v.as_array()) else {
+        return vec![];
+    };
+    let mut out = items
+        .iter()
+        .filter(|item| item.get("grant_status").and_then(|v| v.as_i64()) == Some(1))
+        .filter_map(|item| item.get("scope_name").and_then(|v| v.as_str()))
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+    out.sort();
+    out.dedup();
+    out
+}
+
+fn missing_scopes(required: &[&str], granted: &[String]) -> Vec<String> {
+    let granted = granted.iter().map(String::as_str).collect::<HashSet<_>>();
+    let mut out = Vec::<String>::new();
+    for scope in required {
+        if granted.contains(scope) {
+            continue;
+        }
+        // A read-write grant also covers the matching readonly scope.
+        if let Some(base) = scope.strip_suffix(":readonly") {
+            if granted.contains(base) {
+                continue;
+            }
+        }
+        if !out.iter().any(|item| item == scope) {
+            out.push(scope.to_string());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{missing_scopes, parse_granted_scopes};
+    use serde_json::json;
+
+    #[test]
+    fn parse_granted_scopes_keeps_only_granted_entries() {
+        let resp = json!({
+            "code": 0,
+            "data": { "scopes": [
+                { "scope_name": "docx:document", "grant_status": 1 },
+                { "scope_name": "wiki:wiki", "grant_status": 2 }
+            ]}
+        });
+        assert_eq!(
+            parse_granted_scopes(&resp),
+            vec!["docx:document".to_string()]
+        );
+    }
+
+    #[test]
+    fn missing_scopes_accepts_write_grant_for_readonly_requirement() {
+        let granted = vec!["docx:document".to_string()];
+        let missing = missing_scopes(
+            &["docx:document", "docx:document:readonly", "drive:drive"],
+            &granted,
+        );
+        assert_eq!(missing, vec!["drive:drive".to_string()]);
+    }
+}
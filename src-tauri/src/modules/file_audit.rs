@@ -0,0 +1,404 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::UNIX_EPOCH;
+
+use anyhow::Result;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::models::{FileAuditConfig, FileAuditFinding, SecurityIssue, SecuritySeverity};
+
+use super::{logger, paths, state_store};
+
+const SCAN_MIN_INTERVAL_MS: i64 = 5 * 60 * 1000;
+const MAX_WALK_DEPTH: usize = 6;
+const MAX_TRACKED_FILES: usize = 50_000;
+const MAX_STORED_FINDINGS: usize = 200;
+// Transcripts are read only to attribute changes; a busy day's worth is plenty.
+const MAX_TRANSCRIPT_BYTES: u64 = 16 * 1024 * 1024;
+const TRANSCRIPT_DIRS: &[&str] = &["sessions", "agents"];
+
+static LAST_SCAN_MS: OnceLock<Mutex<i64>> = OnceLock::new();
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+struct FileStamp {
+    len: u64,
+    modified_ms: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Snapshot {
+    gateway_running: bool,
+    #[serde(default)]
+    taken_at_ms: i64,
+    entries: BTreeMap<String, FileStamp>,
+}
+
+pub fn configure_file_audit(cfg: &FileAuditConfig) -> Result<FileAuditConfig> {
+    let mut cfg = cfg.clone();
+    cfg.protected_paths = cfg
+        .protected_paths
+        .iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    state_store::save_file_audit_config(&cfg)?;
+    // Protected paths may have changed, so the next diff needs a fresh baseline.
+    let _ = fs::remove_file(snapshot_path());
+    if cfg.enabled {
+        spawn_scan(cfg.clone(), false);
+    }
+    Ok(cfg)
+}
+
+/// Diffs protected paths at most once per interval on a background thread. Called from
+/// status polling, which must not wait for a walk of the user's documents.
+pub fn scan_if_due(gateway_running: bool) {
+    let Ok(cfg) = state_store::load_file_audit_config() else {
+        return;
+    };
+    if !cfg.enabled {
+        return;
+    }
+    let now = Local::now().timestamp_millis();
+    let lock = LAST_SCAN_MS.get_or_init(|| Mutex::new(0));
+    {
+        let mut last = lock.lock().unwrap_or_else(|e| e.into_inner());
+        if now - *last < SCAN_MIN_INTERVAL_MS {
+            return;
+        }
+        *last = now;
+    }
+    spawn_scan(cfg, gateway_running);
+}
+
+fn spawn_scan(cfg: FileAuditConfig, gateway_running: bool) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    thread::spawn(move || {
+        if let Err(err) = scan(&cfg, gateway_running) {
+            logger::warn(&format!("File audit scan failed: {err}"));
+        }
+        RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+pub fn load_findings() -> Vec<FileAuditFinding> {
+    fs::read_to_string(findings_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn clear_findings() -> Result<()> {
+    let path = findings_path();
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Findings rendered as security-check issues.
+pub fn security_issues() -> Vec<SecurityIssue> {
+    load_findings()
+        .into_iter()
+        .map(|finding| SecurityIssue {
+            severity: SecuritySeverity::Medium,
+            message: format!(
+                "OpenClaw {} a file outside the workspace ({}).",
+                finding.change, finding.detected_at
+            ),
+            path: Some(finding.path),
+            suggestion: Some(
+                "Review the change; restrict agent tools or restore the file from backup."
+                    .to_string(),
+            ),
+            action: None,
+        })
+        .collect()
+}
+
+fn scan(cfg: &FileAuditConfig, gateway_running: bool) -> Result<()> {
+    let previous = load_snapshot();
+    let current = take_snapshot(cfg, gateway_running);
+    if previous.is_none() {
+        logger::info(&format!(
+            "File audit baseline: {} files.",
+            current.entries.len()
+        ));
+    }
+    // Only consider the agent when the gateway ran for the whole interval, and only
+    // report paths its session transcripts mention: the user edits these folders too.
+    if let Some(previous) = previous.filter(|p| p.gateway_running && gateway_running) {
+        let mut changes = diff_snapshots(&previous.entries, &current.entries);
+        if !changes.is_empty() {
+            let activity = agent_activity_since(previous.taken_at_ms);
+            let total = changes.len();
+            changes.retain(|(path, _)| is_attributed(path, &activity));
+            if changes.len() < total {
+                logger::info(&format!(
+                    "File audit: {} change(s) outside the workspace not attributed to OpenClaw.",
+                    total - changes.len()
+                ));
+            }
+        }
+        if !changes.is_empty() {
+            let detected_at = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+            let mut findings = load_findings();
+            for (path, change) in changes {
+                logger::warn(&format!("File audit: {path} {change} outside workspace."));
+                findings.push(FileAuditFinding {
+                    detected_at: detected_at.clone(),
+                    path,
+                    change: change.to_string(),
+                });
+            }
+            let overflow = findings.len().saturating_sub(MAX_STORED_FINDINGS);
+            findings.drain(..overflow);
+            paths::ensure_dirs()?;
+            fs::write(findings_path(), serde_json::to_string_pretty(&findings)?)?;
+        }
+    }
+    save_snapshot(&current)
+}
+
+fn take_snapshot(cfg: &FileAuditConfig, gateway_running: bool) -> Snapshot {
+    let approved = approved_roots()
+        .iter()
+        .map(|p| normalize(p))
+        .collect::<Vec<_>>();
+    let mut entries = BTreeMap::new();
+    for root in protected_roots(cfg) {
+        if !root.exists() {
+            continue;
+        }
+        let walker = WalkDir::new(&root)
+            .max_depth(MAX_WALK_DEPTH)
+            .into_iter()
+            .filter_entry(|entry| !is_under_any(&normalize(entry.path()), &approved));
+        for entry in walker.filter_map(|e| e.ok()) {
+            if entries.len() >= MAX_TRACKED_FILES {
+                break;
+            }
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            let modified_ms = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as i64)
+                .unwrap_or(0);
+            entries.insert(
+                entry.path().to_string_lossy().to_string(),
+                FileStamp {
+                    len: meta.len(),
+                    modified_ms,
+                },
+            );
+        }
+    }
+    Snapshot {
+        gateway_running,
+        taken_at_ms: Local::now().timestamp_millis(),
+        entries,
+    }
+}
+
+/// Text of the session transcripts written since `since_ms`, normalized like
+/// `normalize` so file paths in tool calls can be matched against snapshot keys.
+fn agent_activity_since(since_ms: i64) -> String {
+    let home = paths::openclaw_home();
+    let mut budget = MAX_TRANSCRIPT_BYTES;
+    let mut text = String::new();
+    for dir in TRANSCRIPT_DIRS {
+        for entry in WalkDir::new(home.join(dir))
+            .max_depth(MAX_WALK_DEPTH)
+            .into_iter()
+            .filter_map(|e| e.ok())
+        {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            let modified_ms = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as i64)
+                .unwrap_or(0);
+            if modified_ms < since_ms || meta.len() > budget {
+                continue;
+            }
+            if let Ok(raw) = fs::read_to_string(entry.path()) {
+                budget -= meta.len();
+                text.push_str(&raw);
+                text.push('\n');
+            }
+        }
+    }
+    normalize_transcript(&text)
+}
+
+fn normalize_transcript(text: &str) -> String {
+    // JSON escapes backslashes, so `C:\\Users` in a transcript is `C:\Users` on disk.
+    text.replace("\\\\", "\\")
+        .replace('/', "\\")
+        .to_ascii_lowercase()
+}
+
+fn is_attributed(path: &str, activity: &str) -> bool {
+    activity.contains(&normalize(Path::new(path)))
+}
+
+fn protected_roots(cfg: &FileAuditConfig) -> Vec<PathBuf> {
+    if !cfg.protected_paths.is_empty() {
+        return cfg
+            .protected_paths
+            .iter()
+            .filter_map(|p| paths::normalize_path(p).ok())
+            .collect();
+    }
+    ["Desktop", "Documents"]
+        .iter()
+        .filter_map(|name| dirs::home_dir().map(|home| home.join(name)))
+        .collect()
+}
+
+fn approved_roots() -> Vec<PathBuf> {
+    let mut roots = vec![paths::openclaw_home(), paths::appdata_root()];
+    if let Ok(Some(state)) = state_store::load_install_state() {
+        if !state.install_dir.trim().is_empty() {
+            roots.push(PathBuf::from(state.install_dir));
+        }
+    }
+    roots
+}
+
+fn diff_snapshots(
+    old: &BTreeMap<String, FileStamp>,
+    new: &BTreeMap<String, FileStamp>,
+) -> Vec<(String, &'static str)> {
+    let mut out = Vec::new();
+    for (path, stamp) in new {
+        match old.get(path) {
+            None => out.push((path.clone(), "created")),
+            Some(prev) if prev != stamp => out.push((path.clone(), "modified")),
+            Some(_) => {}
+        }
+    }
+    for path in old.keys() {
+        if !new.contains_key(path) {
+            out.push((path.clone(), "deleted"));
+        }
+    }
+    out
+}
+
+fn normalize(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('/', "\\")
+        .trim_end_matches('\\')
+        .to_ascii_lowercase()
+}
+
+fn is_under_any(path: &str, roots: &[String]) -> bool {
+    roots
+        .iter()
+        .any(|root| path == root || path.starts_with(&format!("{root}\\")))
+}
+
+fn snapshot_path() -> PathBuf {
+    paths::state_dir().join("file_audit_snapshot.json")
+}
+
+fn findings_path() -> PathBuf {
+    paths::state_dir().join("file_audit_findings.json")
+}
+
+fn load_snapshot() -> Option<Snapshot> {
+    let raw = fs::read_to_string(snapshot_path()).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn save_snapshot(snapshot: &Snapshot) -> Result<()> {
+    paths::ensure_dirs()?;
+    fs::write(snapshot_path(), serde_json::to_string(snapshot)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{diff_snapshots, is_attributed, is_under_any, normalize_transcript, FileStamp};
+
+    fn stamp(len: u64, modified_ms: i64) -> FileStamp {
+        FileStamp { len, modified_ms }
+    }
+
+    #[test]
+    fn diff_snapshots_reports_created_modified_and_deleted() {
+        let old = BTreeMap::from([
+            ("a.txt".to_string(), stamp(1, 10)),
+            ("b.txt".to_string(), stamp(2, 20)),
+            ("c.txt".to_string(), stamp(3, 30)),
+        ]);
+        let new = BTreeMap::from([
+            ("a.txt".to_string(), stamp(1, 10)),
+            ("b.txt".to_string(), stamp(5, 40)),
+            ("d.txt".to_string(), stamp(1, 50)),
+        ]);
+        assert_eq!(
+            diff_snapshots(&old, &new),
+            vec![
+                ("b.txt".to_string(), "modified"),
+                ("d.txt".to_string(), "created"),
+                ("c.txt".to_string(), "deleted"),
+            ]
+        );
+    }
+
+    #[test]
+    fn is_under_any_matches_whole_path_segments() {
+        let roots = vec!["c:\\users\\me\\openclaw".to_string()];
+        assert!(is_under_any("c:\\users\\me\\openclaw", &roots));
+        assert!(is_under_any(
+            "c:\\users\\me\\openclaw\\workspace\\a.md",
+            &roots
+        ));
+        assert!(!is_under_any("c:\\users\\me\\openclaw-notes\\a.md", &roots));
+    }
+
+    #[test]
+    fn only_paths_named_in_agent_transcripts_are_attributed() {
+        let activity = normalize_transcript(
+            r#"{"type":"tool_call","name":"write","args":{"path":"C:\\Users\\me\\Desktop\\notes.txt"}}
+{"type":"tool_call","name":"edit","args":{"path":"C:/Users/me/Documents/plan.md"}}"#,
+        );
+        assert!(is_attributed(
+            "C:\\Users\\me\\Desktop\\notes.txt",
+            &activity
+        ));
+        assert!(is_attributed(
+            "C:\\Users\\me\\Documents\\plan.md",
+            &activity
+        ));
+        assert!(!is_attributed(
+            "C:\\Users\\me\\Desktop\\budget.xlsx",
+            &activity
+        ));
+    }
+}
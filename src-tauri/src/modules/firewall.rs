@@ -0,0 +1,148 @@
+use anyhow::{anyhow, Result};
+
+use crate::models::FirewallRuleStatus;
+
+use super::{logger, shell};
+
+// Single rule name used before rules were scoped per port; cleaned up when a new rule
+// is added so an old port does not stay open.
+const LEGACY_RULE_NAME: &str = "OpenClaw Gateway";
+
+/// Inbound rule name for the gateway port; one rule per port so profiles on different
+/// ports do not replace each other's rule.
+pub fn rule_name(port: u16) -> String {
+    format!("OpenClaw Gateway (TCP {port})")
+}
+
+/// Whether the inbound rule for `port` exists. Reading rules does not need elevation.
+pub fn rule_exists(port: u16) -> bool {
+    let name_arg = format!("name={}", rule_name(port));
+    // `show rule` exits non-zero with "No rules match the specified criteria."
+    matches!(
+        shell::run_command(
+            "netsh",
+            &["advfirewall", "firewall", "show", "rule", &name_arg],
+            None,
+            &[],
+        ),
+        Ok(out) if out.code == 0
+    )
+}
+
+pub fn firewall_rule_status(port: u16) -> FirewallRuleStatus {
+    status(port, rule_exists(port), false)
+}
+
+/// Allows inbound TCP on `port` from the local subnet on private and domain networks.
+/// Public networks (cafés, airports) stay blocked. A rule that already exists is left
+/// as is.
+pub fn add_firewall_rule(port: u16) -> Result<FirewallRuleStatus> {
+    if rule_exists(port) {
+        return Ok(status(port, true, false));
+    }
+    require_admin("added")?;
+    let name_arg = format!("name={}", rule_name(port));
+    let port_arg = format!("localport={port}");
+    let out = shell::run_command(
+        "netsh",
+        &[
+            "advfirewall",
+            "firewall",
+            "add",
+            "rule",
+            &name_arg,
+            "dir=in",
+            "action=allow",
+            "protocol=TCP",
+            &port_arg,
+            "remoteip=localsubnet",
+            "profile=private,domain",
+        ],
+        None,
+        &[],
+    )?;
+    shell::ensure_success("netsh advfirewall firewall add rule", &out)?;
+    // Deleting a rule that does not exist fails harmlessly.
+    let _ = delete_rule(LEGACY_RULE_NAME);
+    logger::info(&format!("Firewall rule added for inbound TCP {port}."));
+    Ok(status(port, true, true))
+}
+
+/// Removes the inbound rule for `port`. Nothing to do if it does not exist.
+pub fn remove_firewall_rule(port: u16) -> Result<FirewallRuleStatus> {
+    if !rule_exists(port) {
+        return Ok(status(port, false, false));
+    }
+    require_admin("removed")?;
+    let out = delete_rule(&rule_name(port))?;
+    shell::ensure_success("netsh advfirewall firewall delete rule", &out)?;
+    logger::info(&format!("Firewall rule removed for inbound TCP {port}."));
+    Ok(status(port, false, true))
+}
+
+fn status(port: u16, exists: bool, changed: bool) -> FirewallRuleStatus {
+    FirewallRuleStatus {
+        rule_name: rule_name(port),
+        port,
+        exists,
+        changed,
+    }
+}
+
+pub fn delete_rule(name: &str) -> Result<shell::CmdOutput> {
+    let name_arg = format!("name={name}");
+    shell::run_command(
+        "netsh",
+        &["advfirewall", "firewall", "delete", "rule", &name_arg],
+        None,
+        &[],
+    )
+}
+
+fn require_admin(action: &str) -> Result<()> {
+    if shell::is_admin() {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "Firewall rule not {action}: administrator rights are required."
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{add_firewall_rule, remove_firewall_rule};
+    use crate::modules::shell::{testing::RecordingRunner, with_runner};
+
+    #[test]
+    fn missing_rule_is_added_scoped_to_the_local_subnet() {
+        let runner = RecordingRunner::new();
+        runner.respond("netsh", 1, "No rules match the specified criteria.");
+        let status = with_runner(runner.clone(), || add_firewall_rule(30123)).expect("added");
+        assert!(status.changed);
+        assert_eq!(
+            runner.lines(),
+            vec![
+                "netsh advfirewall firewall show rule name=OpenClaw Gateway (TCP 30123)",
+                "net session",
+                "netsh advfirewall firewall add rule name=OpenClaw Gateway (TCP 30123) dir=in action=allow protocol=TCP localport=30123 remoteip=localsubnet profile=private,domain",
+                "netsh advfirewall firewall delete rule name=OpenClaw Gateway",
+            ]
+        );
+    }
+
+    #[test]
+    fn existing_rule_is_detected_and_not_duplicated() {
+        let runner = RecordingRunner::new();
+        let status = with_runner(runner.clone(), || add_firewall_rule(28789)).expect("status");
+        assert!(status.exists);
+        assert!(!status.changed);
+        assert_eq!(runner.lines().len(), 1);
+
+        let runner = RecordingRunner::new();
+        runner.respond("netsh", 1, "No rules match the specified criteria.");
+        let status = with_runner(runner.clone(), || remove_firewall_rule(28789)).expect("status");
+        assert!(!status.exists);
+        assert!(!status.changed);
+        assert_eq!(runner.lines().len(), 1);
+    }
+}
@@ -0,0 +1,230 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, UNIX_EPOCH};
+
+use anyhow::Result;
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+
+use super::paths;
+
+type FileFilter<'a> = Box<dyn Fn(&Path) -> bool + Sync + 'a>;
+type HashFilter<'a> = Box<dyn Fn(&WalkedFile) -> bool + Sync + 'a>;
+
+/// A regular file found by `Walker::run`.
+#[derive(Debug, Clone)]
+pub struct WalkedFile {
+    pub path: PathBuf,
+    pub len: u64,
+    pub modified_ms: i64,
+    /// Lowercase hex SHA-256, when the hash callback asked for it and the read succeeded.
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Walk {
+    /// Sub-directories below the root, sorted.
+    pub dirs: Vec<PathBuf>,
+    /// Files that passed the filter, sorted by path.
+    pub files: Vec<WalkedFile>,
+    /// Links/junctions found under the root. Like `paths::walk_managed`, they are
+    /// skipped rather than followed.
+    pub skipped_links: Vec<PathBuf>,
+    /// The deadline passed before every directory was listed.
+    pub timed_out: bool,
+}
+
+/// Walks a managed tree with sibling directories listed in parallel and optional
+/// parallel SHA-256 hashing, for trees like `node_modules` where a serial walk takes
+/// minutes.
+pub struct Walker<'a> {
+    root: PathBuf,
+    exclude_dirs: Vec<String>,
+    filter: FileFilter<'a>,
+    hash_if: HashFilter<'a>,
+    deadline: Option<Instant>,
+}
+
+impl<'a> Walker<'a> {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            exclude_dirs: vec![],
+            filter: Box::new(|_| true),
+            hash_if: Box::new(|_| false),
+            deadline: None,
+        }
+    }
+
+    /// Stops listing directories once `deadline` passes; the walk reports `timed_out`.
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Folder names (case-insensitive) that are not descended into at any depth.
+    pub fn exclude_dirs(mut self, names: &[String]) -> Self {
+        self.exclude_dirs = names.iter().map(|n| n.to_ascii_lowercase()).collect();
+        self
+    }
+
+    /// Keeps only files for which `filter` returns true.
+    pub fn filter(mut self, filter: impl Fn(&Path) -> bool + Sync + 'a) -> Self {
+        self.filter = Box::new(filter);
+        self
+    }
+
+    /// Hashes the files for which `hash_if` returns true.
+    pub fn hash_if(mut self, hash_if: impl Fn(&WalkedFile) -> bool + Sync + 'a) -> Self {
+        self.hash_if = Box::new(hash_if);
+        self
+    }
+
+    pub fn run(self) -> Walk {
+        let mut walk = if self.root.is_dir() {
+            self.walk_dir(&self.root)
+        } else {
+            Walk::default()
+        };
+        walk.dirs.sort();
+        walk.files.sort_by(|a, b| a.path.cmp(&b.path));
+        walk.skipped_links.sort();
+        walk.files
+            .par_iter_mut()
+            .filter(|file| (self.hash_if)(file))
+            .for_each(|file| file.sha256 = sha256_file(&file.path).ok());
+        walk
+    }
+
+    fn walk_dir(&self, dir: &Path) -> Walk {
+        let mut out = Walk::default();
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            out.timed_out = true;
+            return out;
+        }
+        let Ok(entries) = fs::read_dir(dir) else {
+            return out;
+        };
+        let mut subdirs = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if paths::is_reparse_point(&path) {
+                out.skipped_links.push(path);
+            } else if file_type.is_dir() {
+                let name = entry.file_name().to_string_lossy().to_ascii_lowercase();
+                if !self.exclude_dirs.contains(&name) {
+                    subdirs.push(path);
+                }
+            } else if file_type.is_file() && (self.filter)(&path) {
+                let meta = entry.metadata().ok();
+                out.files.push(WalkedFile {
+                    len: meta.as_ref().map(|m| m.len()).unwrap_or(0),
+                    modified_ms: meta
+                        .and_then(|m| m.modified().ok())
+                        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                        .map(|d| d.as_millis() as i64)
+                        .unwrap_or(0),
+                    path,
+                    sha256: None,
+                });
+            }
+        }
+        let nested = subdirs
+            .par_iter()
+            .map(|sub| self.walk_dir(sub))
+            .collect::<Vec<_>>();
+        out.dirs.extend(subdirs);
+        for walk in nested {
+            out.dirs.extend(walk.dirs);
+            out.files.extend(walk.files);
+            out.skipped_links.extend(walk.skipped_links);
+            out.timed_out |= walk.timed_out;
+        }
+        out
+    }
+}
+
+/// Total size of the regular files under `root` (or of `root` itself if it is a file),
+/// links not followed.
+pub fn total_size(root: &Path) -> u64 {
+    match fs::symlink_metadata(root) {
+        Ok(meta) if meta.is_file() => meta.len(),
+        _ => Walker::new(root).run().files.iter().map(|f| f.len).sum(),
+    }
+}
+
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut fs::File::open(paths::long_path(path))?, &mut hasher)?;
+    Ok(hex_digest(hasher))
+}
+
+pub fn hex_digest(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Walker;
+    use std::fs;
+    use std::time::Instant;
+
+    #[test]
+    fn walker_stops_at_the_deadline() {
+        let root = std::env::temp_dir().join(format!("oc-fs-walk-deadline-{}", std::process::id()));
+        fs::create_dir_all(&root).expect("dir");
+        fs::write(root.join("a.js"), "x").expect("file");
+        let walk = Walker::new(&root).deadline(Instant::now()).run();
+        assert!(walk.timed_out);
+        assert!(walk.files.is_empty());
+        assert!(!Walker::new(&root).run().timed_out);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn walker_filters_excludes_and_hashes() {
+        let root = std::env::temp_dir().join(format!("oc-fs-walk-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("a").join("b")).expect("dirs");
+        fs::create_dir_all(root.join("Node_Modules").join("pkg")).expect("dirs");
+        fs::write(root.join("top.js"), "abc").expect("top");
+        fs::write(root.join("a").join("b").join("deep.js"), "x").expect("deep");
+        fs::write(root.join("a").join("notes.txt"), "skip").expect("txt");
+        fs::write(root.join("Node_Modules").join("pkg").join("i.js"), "x").expect("dep");
+
+        let walk = Walker::new(&root)
+            .exclude_dirs(&["node_modules".to_string()])
+            .filter(|p| p.extension().is_some_and(|ext| ext == "js"))
+            .hash_if(|f| f.len == 3)
+            .run();
+        let names = walk
+            .files
+            .iter()
+            .map(|f| {
+                f.path
+                    .strip_prefix(&root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["a/b/deep.js", "top.js"]);
+        assert_eq!(walk.files[0].sha256, None);
+        assert_eq!(
+            walk.files[1].sha256.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(walk.dirs.len(), 2);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,284 @@
+use std::net::UdpSocket;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use tokio::time::sleep;
+
+use crate::models::{ChannelConnection, GatewayPortChangeResult, ResultWarning};
+
+use super::{config, firewall, health, logger, port, process, shell, state_store, tls, tunnel};
+
+const CHANNEL_VERIFY_ATTEMPTS: usize = 5;
+
+/// Moves the gateway to `new_port` without rerunning the wizard. A gateway that does
+/// not come back healthy on the new port is switched back to the old one.
+pub async fn change_gateway_port(new_port: u16) -> Result<GatewayPortChangeResult> {
+    if new_port < 1024 {
+        return Err(anyhow!("Port {new_port} is reserved; choose 1024-65535."));
+    }
+    let current = config::read_current_config()?;
+    let old_port = current.port;
+    if new_port == old_port {
+        return Err(anyhow!("Gateway already listens on port {new_port}."));
+    }
+    free_port_for_gateway(new_port)?;
+
+    let proxy = state_store::load_last_config()?.and_then(|cfg| cfg.proxy);
+    set_config_port(new_port, proxy.clone())?;
+    let outcome = match process::restart() {
+        Err(err) => Err(format!("restart failed: {err}")),
+        Ok(_) => {
+            let health = health::health_check(&current.bind_address, new_port).await?;
+            if health.ok {
+                Ok(health)
+            } else {
+                Err(health.body)
+            }
+        }
+    };
+    let health = match outcome {
+        Ok(health) => health,
+        Err(reason) => {
+            logger::warn(&format!(
+                "Gateway not healthy on port {new_port}; reverting to {old_port}."
+            ));
+            set_config_port(old_port, proxy)?;
+            if let Err(err) = process::restart() {
+                return Err(anyhow!(
+                    "Gateway did not come up on port {new_port} ({reason}); port {old_port} was restored in the config but the gateway failed to restart: {err}"
+                ));
+            }
+            return Err(anyhow!(
+                "Gateway did not come up on port {new_port} ({reason}); port {old_port} was restored."
+            ));
+        }
+    };
+    if let Some(mut last) = state_store::load_last_config()? {
+        last.port = new_port;
+        state_store::save_last_config(&last)?;
+    }
+
+    let lan = current.bind_address == "0.0.0.0";
+    let mut warnings = Vec::<ResultWarning>::new();
+    if lan {
+        let moved = firewall::add_firewall_rule(new_port)
+            .and_then(|_| firewall::remove_firewall_rule(old_port));
+        if let Err(err) = moved {
+            warnings.push(
+                ResultWarning::warning("firewall_update_failed", err.to_string())
+                    .with_remediation(format!(
+                        "Allow inbound TCP {new_port} in Windows Defender Firewall, or rerun the installer as administrator."
+                    )),
+            );
+        }
+    }
+    if tunnel::tunnel_status().running {
+        // Tunnels forward to the gateway port captured at start.
+        let restarted = tunnel::stop_tunnel().and_then(|_| tunnel::start_tunnel());
+        if let Err(err) = restarted {
+            warnings.push(ResultWarning::warning(
+                "tunnel_restart_failed",
+                format!("Tunnel still points at port {old_port}: {err}"),
+            ));
+        }
+    }
+
+    let channels = verify_channels().await;
+    for channel in channels.iter().filter(|c| !c.connected) {
+        warnings.push(
+            ResultWarning::warning(
+                "channel_not_reconnected",
+                format!(
+                    "Channel {} has not reconnected: {}",
+                    channel.channel, channel.detail
+                ),
+            )
+            .with_channel(channel.channel.clone()),
+        );
+    }
+
+    logger::info(&format!(
+        "Gateway port changed from {old_port} to {new_port}."
+    ));
+    Ok(GatewayPortChangeResult {
+        old_port,
+        new_port,
+        health,
+        urls: dashboard_urls(new_port, lan),
+        channels,
+        warnings,
+    })
+}
+
+fn free_port_for_gateway(new_port: u16) -> Result<()> {
+    let status = port::check_port(new_port)?;
+    if !status.in_use {
+        return Ok(());
+    }
+    let name = status.process_name.clone().unwrap_or_default();
+    // Only the gateway this app manages is stopped; any other node.exe or program on the
+    // port belongs to the user.
+    if !is_managed_gateway(status.pid, process::running_pid()) {
+        return Err(anyhow!(
+            "Port {new_port} is used by {} (PID {}). Choose another port.",
+            if name.is_empty() {
+                "another program"
+            } else {
+                &name
+            },
+            status
+                .pid
+                .map(|pid| pid.to_string())
+                .unwrap_or_else(|| "unknown".to_string())
+        ));
+    }
+    // Waits for the socket to close and errors if the port stays taken.
+    if let Some(pid) = status.pid {
+        port::release_port(new_port, pid)?;
+    }
+    Ok(())
+}
+
+fn is_managed_gateway(holder: Option<u32>, managed: Option<u32>) -> bool {
+    holder.is_some() && holder == managed
+}
+
+fn set_config_port(value: u16, proxy: Option<String>) -> Result<()> {
+    let out = config::run_openclaw_cli(
+        &[
+            "config".to_string(),
+            "set".to_string(),
+            "gateway.port".to_string(),
+            value.to_string(),
+        ],
+        proxy,
+    )?;
+    shell::ensure_success("openclaw config set gateway.port", &out)
+}
+
+fn dashboard_urls(port: u16, lan: bool) -> Vec<String> {
+    let scheme = tls::gateway_scheme();
+    let mut urls = vec![format!("{scheme}://127.0.0.1:{port}/")];
+    if lan {
+        if let Some(ip) = primary_lan_ip() {
+            urls.push(format!("{scheme}://{ip}:{port}/"));
+        }
+    }
+    urls
+}
+
+pub fn primary_lan_ip() -> Option<String> {
+    // Connecting a UDP socket sends nothing; it only selects the outbound interface.
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then(|| ip.to_string())
+}
+
+async fn verify_channels() -> Vec<ChannelConnection> {
+    let proxy = state_store::load_last_config()
+        .ok()
+        .flatten()
+        .and_then(|cfg| cfg.proxy);
+    let mut channels = Vec::new();
+    for attempt in 0..CHANNEL_VERIFY_ATTEMPTS {
+        if attempt > 0 {
+            sleep(Duration::from_secs(2)).await;
+        }
+        let Ok(out) = config::run_openclaw_cli(
+            &[
+                "channels".to_string(),
+                "status".to_string(),
+                "--json".to_string(),
+            ],
+            proxy.clone(),
+        ) else {
+            continue;
+        };
+        if out.code != 0 {
+            continue;
+        }
+        channels = serde_json::from_str::<Value>(out.stdout.trim())
+            .map(|json| parse_channel_status(&json))
+            .unwrap_or_default();
+        if channels.iter().all(|c| c.connected) {
+            break;
+        }
+    }
+    channels
+}
+
+pub fn parse_channel_status(json: &Value) -> Vec<ChannelConnection> {
+    let Some(entries) = json
+        .get("channels")
+        .and_then(|v| v.as_object())
+        .or_else(|| json.as_object())
+    else {
+        return vec![];
+    };
+    entries
+        .iter()
+        .filter(|(_, entry)| entry.is_object())
+        .filter(|(_, entry)| entry.get("enabled").and_then(|v| v.as_bool()) != Some(false))
+        // A channel that reports no connection flag says nothing about its link; treating
+        // it as down would have keep-alive reload healthy channels.
+        .filter_map(|(name, entry)| {
+            let connected = ["connected", "running", "ok"]
+                .iter()
+                .find_map(|key| entry.get(*key).and_then(|v| v.as_bool()))?;
+            let detail = entry
+                .get("error")
+                .or_else(|| entry.get("status"))
+                .and_then(|v| v.as_str())
+                .unwrap_or(if connected {
+                    "connected"
+                } else {
+                    "not connected"
+                })
+                .to_string();
+            Some(ChannelConnection {
+                channel: name.clone(),
+                connected,
+                detail,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_managed_gateway, parse_channel_status};
+    use serde_json::json;
+
+    #[test]
+    fn only_the_managed_gateway_is_stopped_to_free_a_port() {
+        assert!(is_managed_gateway(Some(4242), Some(4242)));
+        assert!(!is_managed_gateway(Some(4242), Some(5151)));
+        assert!(!is_managed_gateway(Some(4242), None));
+        assert!(!is_managed_gateway(None, None));
+    }
+
+    #[test]
+    fn parse_channel_status_reads_connection_flags() {
+        let raw = json!({
+            "channels": {
+                "telegram": { "enabled": true, "connected": true },
+                "feishu": { "running": false, "error": "websocket closed" },
+                "discord": { "enabled": false, "connected": false },
+                "slack": { "enabled": true, "status": "configured" }
+            }
+        });
+        let channels = parse_channel_status(&raw);
+        assert_eq!(channels.len(), 2);
+        let feishu = channels
+            .iter()
+            .find(|c| c.channel == "feishu")
+            .expect("feishu");
+        assert!(!feishu.connected);
+        assert_eq!(feishu.detail, "websocket closed");
+        assert!(channels
+            .iter()
+            .any(|c| c.channel == "telegram" && c.connected));
+    }
+}
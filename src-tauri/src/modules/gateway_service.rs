@@ -0,0 +1,588 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+use super::{logger, paths, process, shell, state_store, uptime};
+
+pub const SERVICE_NAME: &str = "OpenClawGateway";
+const SERVICE_DISPLAY_NAME: &str = "OpenClaw Gateway";
+/// Argument that makes the installer binary run as the service host instead of the GUI.
+pub const SERVICE_HOST_ARG: &str = "--gateway-service";
+const DATA_DIR_ARG: &str = "--data-dir";
+const OPENCLAW_HOME_ARG: &str = "--openclaw-home";
+/// Per-service virtual account: no password and no rights beyond what `install` grants
+/// on the user's folders, unlike LocalSystem.
+pub const SERVICE_ACCOUNT: &str = r"NT SERVICE\OpenClawGateway";
+// Interactive users may only query the service; starting and stopping it from the GUI
+// needs an elevated installer.
+const SERVICE_SDDL: &str = "D:(A;;CCLCSWRPWPDTLOCRRC;;;SY)(A;;CCDCLCSWRPWPDTLOCRSDRCWDWO;;;BA)(A;;CCLCSWLOCRRC;;;IU)(A;;CCLCSWLOCRRC;;;SU)";
+const DETECT_CACHE_TTL: Duration = Duration::from_secs(10);
+const STOP_WAIT: Duration = Duration::from_secs(20);
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(60);
+const STABLE_RUN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceInfo {
+    /// SCM state as reported by `sc query` (e.g. `RUNNING`, `STOPPED`, `START_PENDING`).
+    pub state: String,
+}
+
+impl ServiceInfo {
+    pub fn is_running(&self) -> bool {
+        self.state.eq_ignore_ascii_case("running")
+    }
+}
+
+// Keyed by the profile it was taken for, so a switch never reads another profile's answer.
+type DetectCache = Option<(Option<String>, Instant, Option<ServiceInfo>)>;
+
+static DETECT_CACHE: OnceLock<Mutex<DetectCache>> = OnceLock::new();
+
+/// Returns the gateway service if it runs the active profile's gateway. Cached briefly
+/// because status polls often.
+pub fn detect() -> Option<ServiceInfo> {
+    let profile = paths::active_profile();
+    let lock = DETECT_CACHE.get_or_init(|| Mutex::new(None));
+    let mut guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((cached_for, at, info)) = guard.as_ref() {
+        if *cached_for == profile && at.elapsed() < DETECT_CACHE_TTL {
+            return info.clone();
+        }
+    }
+    let info = query_service().filter(|_| other_profile_dir().is_none());
+    *guard = Some((profile, Instant::now(), info.clone()));
+    info
+}
+
+pub fn invalidate() {
+    if let Some(lock) = DETECT_CACHE.get() {
+        *lock.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+fn query_service() -> Option<ServiceInfo> {
+    let out = shell::run_command("sc", &["query", SERVICE_NAME], None, &[]).ok()?;
+    if out.code != 0 {
+        return None;
+    }
+    Some(ServiceInfo {
+        state: parse_service_state(&out.stdout).unwrap_or_else(|| "UNKNOWN".to_string()),
+    })
+}
+
+/// Data folder on the service command line when it is not the active profile's, i.e.
+/// the service was installed from another profile.
+fn other_profile_dir() -> Option<String> {
+    let out = shell::run_command("sc", &["qc", SERVICE_NAME], None, &[]).ok()?;
+    if out.code != 0 {
+        return None;
+    }
+    let dir = service_data_dir(&out.stdout)?;
+    (!same_folder(&dir, &paths::profile_data_dir())).then_some(dir)
+}
+
+/// Registers the installer binary as an auto-start service host for the gateway. The
+/// service runs as `SERVICE_ACCOUNT`, so the data and OpenClaw home folders of the
+/// current user are passed on its command line and shared with that account.
+pub fn install() -> Result<()> {
+    if !shell::is_admin() {
+        return Err(anyhow!(
+            "Installing the gateway service requires administrator rights. Restart the installer as administrator."
+        ));
+    }
+    if query_service().is_some() {
+        return Err(anyhow!(
+            "The {SERVICE_DISPLAY_NAME} service is already installed."
+        ));
+    }
+    let exe = std::env::current_exe()?;
+    let bin_path = service_command_line(&exe, &paths::profile_data_dir(), &paths::openclaw_home());
+    let out = shell::run_command(
+        "sc",
+        &[
+            "create",
+            SERVICE_NAME,
+            "binPath=",
+            &bin_path,
+            "start=",
+            "delayed-auto",
+            "DisplayName=",
+            SERVICE_DISPLAY_NAME,
+            "obj=",
+            SERVICE_ACCOUNT,
+        ],
+        None,
+        &[],
+    )?;
+    shell::ensure_success("sc create", &out)?;
+    invalidate();
+    if let Err(err) = grant_service_access() {
+        // A service that cannot read its config would only crash-loop; do not leave it.
+        let _ = shell::run_command("sc", &["delete", SERVICE_NAME], None, &[]);
+        invalidate();
+        return Err(err);
+    }
+    let configure = [
+        vec![
+            "description",
+            SERVICE_NAME,
+            "Runs the OpenClaw gateway in the background, across logouts and reboots.",
+        ],
+        vec![
+            "failure",
+            SERVICE_NAME,
+            "reset=",
+            "86400",
+            "actions=",
+            "restart/5000/restart/30000/restart/60000",
+        ],
+        vec!["sdset", SERVICE_NAME, SERVICE_SDDL],
+    ];
+    for args in configure {
+        let out = shell::run_command("sc", &args, None, &[])?;
+        if out.code != 0 {
+            logger::warn(&format!(
+                "sc {} for {SERVICE_NAME} failed: {}",
+                args[0],
+                out.stdout.trim()
+            ));
+        }
+    }
+    start()?;
+    logger::info(&format!(
+        "Gateway service {SERVICE_NAME} installed: {bin_path}"
+    ));
+    Ok(())
+}
+
+pub fn uninstall() -> Result<()> {
+    if query_service().is_none() {
+        return Err(anyhow!(
+            "The {SERVICE_DISPLAY_NAME} service is not installed."
+        ));
+    }
+    if let Some(dir) = other_profile_dir() {
+        return Err(anyhow!(
+            "The {SERVICE_DISPLAY_NAME} service runs the gateway of another profile ({dir}). Switch to that profile to remove it."
+        ));
+    }
+    if !shell::is_admin() {
+        return Err(anyhow!(
+            "Removing the gateway service requires administrator rights. Restart the installer as administrator."
+        ));
+    }
+    stop()?;
+    let out = shell::run_command("sc", &["delete", SERVICE_NAME], None, &[])?;
+    invalidate();
+    shell::ensure_success("sc delete", &out)?;
+    logger::info(&format!("Gateway service {SERVICE_NAME} removed."));
+    Ok(())
+}
+
+pub fn start() -> Result<()> {
+    let out = shell::run_command("sc", &["start", SERVICE_NAME], None, &[])?;
+    invalidate();
+    // 1056: an instance of the service is already running.
+    if out.code != 0 && !out.stdout.contains("1056") {
+        ensure_control("start", &out)?;
+    }
+    Ok(())
+}
+
+/// Asks the SCM to stop the service and waits until it reports STOPPED.
+pub fn stop() -> Result<()> {
+    let out = shell::run_command("sc", &["stop", SERVICE_NAME], None, &[])?;
+    invalidate();
+    // 1062: the service has not been started.
+    if out.code != 0 && !out.stdout.contains("1062") {
+        ensure_control("stop", &out)?;
+    }
+    let deadline = Instant::now() + STOP_WAIT;
+    while Instant::now() < deadline {
+        match query_service() {
+            Some(info) if !info.state.eq_ignore_ascii_case("stopped") => {
+                thread::sleep(Duration::from_millis(500))
+            }
+            _ => return Ok(()),
+        }
+    }
+    Err(anyhow!(
+        "The {SERVICE_DISPLAY_NAME} service did not stop within {} s.",
+        STOP_WAIT.as_secs()
+    ))
+}
+
+pub fn restart() -> Result<()> {
+    stop()?;
+    start()
+}
+
+/// Entry point when the binary is launched by the SCM with `SERVICE_HOST_ARG`.
+pub fn run_host(args: &[String]) -> Result<()> {
+    // Point path resolution at the installing user's folders before anything reads them.
+    if let Some(dir) = arg_value(args, DATA_DIR_ARG) {
+        std::env::set_var("OPENCLAW_INSTALLER_DATA_DIR", dir);
+    }
+    if let Some(home) = arg_value(args, OPENCLAW_HOME_ARG) {
+        std::env::set_var("OPENCLAW_INSTALLER_OPENCLAW_HOME", home);
+    }
+    paths::ensure_dirs()?;
+    logger::info("Gateway service host starting.");
+    process::start_gateway_log_rotation(true);
+    host::run()
+}
+
+/// `sc start`/`sc stop` fail with 5 (access denied) for standard users, since only
+/// administrators may control the service.
+fn ensure_control(action: &str, out: &shell::CmdOutput) -> Result<()> {
+    if out.stdout.contains("FAILED 5:") {
+        return Err(anyhow!(
+            "Controlling the {SERVICE_DISPLAY_NAME} service requires administrator rights. Restart the installer as administrator to {action} it."
+        ));
+    }
+    shell::ensure_success(&format!("sc {action}"), out)
+}
+
+/// Lets the virtual account read and write the data and OpenClaw home folders and run
+/// the installed OpenClaw. `/T` also covers existing files, including the ones
+/// `config::set_windows_acl` cut off from inherited permissions.
+fn grant_service_access() -> Result<()> {
+    let mut grants: Vec<(PathBuf, &str)> = vec![
+        (paths::profile_data_dir(), "(OI)(CI)M"),
+        (paths::openclaw_home(), "(OI)(CI)M"),
+    ];
+    if let Some(install) = state_store::load_install_state()? {
+        grants.push((PathBuf::from(install.install_dir), "(OI)(CI)RX"));
+    }
+    for (dir, rights) in grants {
+        if !dir.exists() {
+            continue;
+        }
+        let dir_text = dir.to_string_lossy().to_string();
+        let grant = format!("{SERVICE_ACCOUNT}:{rights}");
+        let out = shell::run_command(
+            "icacls",
+            &[dir_text.as_str(), "/grant", &grant, "/T", "/C", "/Q"],
+            None,
+            &[],
+        )?;
+        shell::ensure_success(&format!("icacls {dir_text}"), &out)?;
+    }
+    Ok(())
+}
+
+fn service_command_line(exe: &Path, data_dir: &Path, home: &Path) -> String {
+    format!(
+        "\"{}\" {SERVICE_HOST_ARG} {DATA_DIR_ARG} \"{}\" {OPENCLAW_HOME_ARG} \"{}\"",
+        exe.to_string_lossy(),
+        data_dir.to_string_lossy(),
+        home.to_string_lossy()
+    )
+}
+
+fn arg_value(args: &[String], name: &str) -> Option<String> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|index| args.get(index + 1))
+        .filter(|value| !value.trim().is_empty())
+        .cloned()
+}
+
+/// `--data-dir` value from the `BINARY_PATH_NAME` line of `sc qc`.
+fn service_data_dir(qc_output: &str) -> Option<String> {
+    let bin_path = qc_output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case("binary_path_name")
+            .then(|| value.trim())
+    })?;
+    let rest = bin_path.split_once(&format!("{DATA_DIR_ARG} \""))?.1;
+    rest.split('"')
+        .next()
+        .filter(|dir| !dir.is_empty())
+        .map(str::to_string)
+}
+
+fn same_folder(a: &str, b: &Path) -> bool {
+    let trim = |s: &str| s.trim_end_matches(['\\', '/']).to_ascii_lowercase();
+    trim(a) == trim(&b.to_string_lossy())
+}
+
+fn parse_service_state(query_output: &str) -> Option<String> {
+    query_output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if !key.trim().eq_ignore_ascii_case("state") {
+            return None;
+        }
+        // `STATE              : 4  RUNNING`
+        value.split_whitespace().nth(1).map(str::to_string)
+    })
+}
+
+/// Set by the SCM control handler; the supervisor loop waits on it between checks.
+struct StopSignal {
+    stopped: Mutex<bool>,
+    wake: Condvar,
+}
+
+impl StopSignal {
+    #[cfg_attr(not(windows), allow(dead_code))]
+    fn request(&self) {
+        *self.stopped.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        self.wake.notify_all();
+    }
+
+    /// Waits up to `timeout`; returns true once a stop was requested.
+    fn wait(&self, timeout: Duration) -> bool {
+        let guard = self.stopped.lock().unwrap_or_else(|e| e.into_inner());
+        let (guard, _) = self
+            .wake
+            .wait_timeout_while(guard, timeout, |stopped| !*stopped)
+            .unwrap_or_else(|e| e.into_inner());
+        *guard
+    }
+}
+
+fn stop_signal() -> &'static StopSignal {
+    static SIGNAL: OnceLock<StopSignal> = OnceLock::new();
+    SIGNAL.get_or_init(|| StopSignal {
+        stopped: Mutex::new(false),
+        wake: Condvar::new(),
+    })
+}
+
+/// Keeps one gateway child alive until the service is stopped, restarting it with
+/// exponential backoff when it exits. The backoff resets after a stable run.
+fn supervise() {
+    let signal = stop_signal();
+    let mut backoff = Duration::from_secs(2);
+    loop {
+        match process::spawn_service_gateway() {
+            Ok(mut child) => {
+                let pid = child.id();
+                let started = Instant::now();
+                uptime::record_start();
+                logger::info(&format!(
+                    "Gateway service started the gateway at PID {pid}."
+                ));
+                loop {
+                    if signal.wait(Duration::from_secs(1)) {
+                        let pid_text = pid.to_string();
+                        let _ = shell::run_command(
+                            "taskkill",
+                            &["/PID", &pid_text, "/T", "/F"],
+                            None,
+                            &[],
+                        );
+                        let _ = child.wait();
+                        logger::mark_gateway_run("stop", pid, &process::installed_version());
+                        logger::info("Gateway service stopped the gateway.");
+                        return;
+                    }
+                    if let Ok(Some(exit)) = child.try_wait() {
+                        logger::mark_gateway_run(
+                            &format!("exit ({exit})"),
+                            pid,
+                            &process::installed_version(),
+                        );
+                        logger::warn(&format!("Gateway exited under the service: {exit}"));
+                        break;
+                    }
+                }
+                if started.elapsed() >= STABLE_RUN {
+                    backoff = Duration::from_secs(2);
+                }
+            }
+            Err(err) => logger::error(&format!(
+                "Gateway service could not start the gateway: {err}"
+            )),
+        }
+        if signal.wait(backoff) {
+            return;
+        }
+        backoff = (backoff * 2).min(MAX_RESTART_BACKOFF);
+    }
+}
+
+#[cfg(windows)]
+mod host {
+    use std::ffi::c_void;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use anyhow::{anyhow, Result};
+    use windows_sys::Win32::Foundation::{ERROR_CALL_NOT_IMPLEMENTED, NO_ERROR};
+    use windows_sys::Win32::System::Services::{
+        RegisterServiceCtrlHandlerExW, SetServiceStatus, StartServiceCtrlDispatcherW,
+        SERVICE_ACCEPT_SHUTDOWN, SERVICE_ACCEPT_STOP, SERVICE_CONTROL_INTERROGATE,
+        SERVICE_CONTROL_SHUTDOWN, SERVICE_CONTROL_STOP, SERVICE_RUNNING, SERVICE_START_PENDING,
+        SERVICE_STATUS, SERVICE_STATUS_HANDLE, SERVICE_STOPPED, SERVICE_STOP_PENDING,
+        SERVICE_TABLE_ENTRYW, SERVICE_WIN32_OWN_PROCESS,
+    };
+
+    use super::{stop_signal, supervise, SERVICE_NAME};
+
+    static STATUS_HANDLE: AtomicUsize = AtomicUsize::new(0);
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    pub fn run() -> Result<()> {
+        let mut name = wide(SERVICE_NAME);
+        let table = [
+            SERVICE_TABLE_ENTRYW {
+                lpServiceName: name.as_mut_ptr(),
+                lpServiceProc: Some(service_main),
+            },
+            SERVICE_TABLE_ENTRYW {
+                lpServiceName: std::ptr::null_mut(),
+                lpServiceProc: None,
+            },
+        ];
+        // Blocks until the service stops.
+        if unsafe { StartServiceCtrlDispatcherW(table.as_ptr()) } == 0 {
+            return Err(anyhow!(
+                "Service dispatcher failed (not started by the service manager?): {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+
+    unsafe extern "system" fn service_main(_argc: u32, _argv: *mut *mut u16) {
+        let name = wide(SERVICE_NAME);
+        let handle =
+            RegisterServiceCtrlHandlerExW(name.as_ptr(), Some(control_handler), std::ptr::null());
+        if handle as usize == 0 {
+            return;
+        }
+        STATUS_HANDLE.store(handle as usize, Ordering::SeqCst);
+        report(SERVICE_START_PENDING, 10_000);
+        report(SERVICE_RUNNING, 0);
+        supervise();
+        report(SERVICE_STOPPED, 0);
+    }
+
+    unsafe extern "system" fn control_handler(
+        control: u32,
+        _event_type: u32,
+        _event_data: *mut c_void,
+        _context: *mut c_void,
+    ) -> u32 {
+        match control {
+            SERVICE_CONTROL_STOP | SERVICE_CONTROL_SHUTDOWN => {
+                report(SERVICE_STOP_PENDING, 20_000);
+                stop_signal().request();
+                NO_ERROR
+            }
+            SERVICE_CONTROL_INTERROGATE => NO_ERROR,
+            _ => ERROR_CALL_NOT_IMPLEMENTED,
+        }
+    }
+
+    fn report(state: u32, wait_hint_ms: u32) {
+        let handle = STATUS_HANDLE.load(Ordering::SeqCst);
+        if handle == 0 {
+            return;
+        }
+        let status = SERVICE_STATUS {
+            dwServiceType: SERVICE_WIN32_OWN_PROCESS,
+            dwCurrentState: state,
+            dwControlsAccepted: if state == SERVICE_RUNNING {
+                SERVICE_ACCEPT_STOP | SERVICE_ACCEPT_SHUTDOWN
+            } else {
+                0
+            },
+            dwWin32ExitCode: NO_ERROR,
+            dwServiceSpecificExitCode: 0,
+            dwCheckPoint: 0,
+            dwWaitHint: wait_hint_ms,
+        };
+        unsafe {
+            SetServiceStatus(handle as SERVICE_STATUS_HANDLE, &status);
+        }
+    }
+}
+
+#[cfg(not(windows))]
+mod host {
+    use anyhow::Result;
+
+    /// Without an SCM, supervise in the foreground until the process is killed.
+    pub fn run() -> Result<()> {
+        super::supervise();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        arg_value, parse_service_state, query_service, same_folder, service_command_line,
+        service_data_dir,
+    };
+    use crate::modules::shell::{testing::RecordingRunner, with_runner};
+    use std::path::Path;
+
+    #[test]
+    fn parse_service_state_reads_state_name() {
+        let raw = "\r\nSERVICE_NAME: OpenClawGateway\r\n        TYPE               : 10  WIN32_OWN_PROCESS\r\n        STATE              : 4  RUNNING\r\n                                (STOPPABLE, NOT_PAUSABLE, ACCEPTS_SHUTDOWN)\r\n";
+        assert_eq!(parse_service_state(raw), Some("RUNNING".to_string()));
+        assert_eq!(parse_service_state("SERVICE_NAME: x"), None);
+    }
+
+    #[test]
+    fn service_data_dir_reads_the_owning_profile() {
+        let exe = Path::new(r"C:\Program Files\OpenClaw Installer\openclaw-installer.exe");
+        let data = Path::new(r"C:\Users\a\AppData\Roaming\OpenClawInstaller\profiles\work");
+        let line = service_command_line(exe, data, Path::new(r"D:\OpenClaw\.openclaw"));
+        let qc = format!(
+            "[SC] QueryServiceConfig SUCCESS\r\n\r\nSERVICE_NAME: OpenClawGateway\r\n        TYPE               : 10  WIN32_OWN_PROCESS\r\n        BINARY_PATH_NAME   : {line}\r\n"
+        );
+        let dir = service_data_dir(&qc).expect("data dir");
+        assert_eq!(dir, data.to_string_lossy());
+        assert!(same_folder(
+            &dir,
+            Path::new(r"c:\users\a\appdata\roaming\openclawinstaller\profiles\work\")
+        ));
+        assert!(!same_folder(
+            &dir,
+            Path::new(r"C:\Users\a\AppData\Roaming\OpenClawInstaller")
+        ));
+        assert_eq!(service_data_dir("SERVICE_NAME: OpenClawGateway"), None);
+    }
+
+    #[test]
+    fn detect_queries_sc_and_reports_missing_service() {
+        let runner = RecordingRunner::new();
+        runner.respond(
+            "sc",
+            1060,
+            "[SC] EnumQueryServicesStatus:OpenService FAILED 1060",
+        );
+        assert_eq!(with_runner(runner.clone(), query_service), None);
+        assert_eq!(runner.lines(), vec!["sc query OpenClawGateway"]);
+    }
+
+    #[test]
+    fn service_command_line_round_trips_folders() {
+        let line = service_command_line(
+            Path::new(r"C:\Program Files\OpenClaw Installer\openclaw-installer.exe"),
+            Path::new(r"C:\Users\张三\AppData\Roaming\OpenClawInstaller"),
+            Path::new(r"D:\OpenClaw\.openclaw"),
+        );
+        assert!(line.starts_with(
+            "\"C:\\Program Files\\OpenClaw Installer\\openclaw-installer.exe\" --gateway-service"
+        ));
+        let args = vec![
+            "--gateway-service".to_string(),
+            "--data-dir".to_string(),
+            r"C:\data".to_string(),
+        ];
+        assert_eq!(arg_value(&args, "--data-dir").as_deref(), Some(r"C:\data"));
+        assert_eq!(arg_value(&args, "--openclaw-home"), None);
+    }
+}
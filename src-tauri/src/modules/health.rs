@@ -1,83 +1,225 @@
 use anyhow::Result;
-use reqwest::Client;
+use serde_json::Value;
 use std::net::{TcpStream, ToSocketAddrs};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
 
 use crate::models::HealthResult;
 
+use super::tls;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+// Above this the gateway is answering but users will feel it in chat replies.
+const SLUGGISH_THRESHOLD_MS: u64 = 1_500;
+
+/// TCP reachability first, then the HTTP endpoints to make sure what answers is an
+/// OpenClaw gateway: another app or a captive portal on the port is reported with
+/// `foreign_service` instead of as a healthy gateway.
 pub async fn health_check(host: &str, port: u16) -> Result<HealthResult> {
     let resolved_host = normalize_host(host);
-    let mut last_tcp = HealthResult {
+    let mut tcp = HealthResult {
         ok: false,
         status: 0,
         url: format!("tcp://{resolved_host}:{port}"),
         body: "No probe yet".to_string(),
+        ..Default::default()
     };
     for _ in 0..8 {
         if let Some(result) = tcp_probe(&resolved_host, port) {
-            if result.ok {
-                return Ok(result);
+            let ok = result.ok;
+            tcp = result;
+            if ok {
+                break;
             }
-            last_tcp = result;
         }
         sleep(Duration::from_millis(450)).await;
     }
 
-    let base = format!("http://{resolved_host}:{port}");
+    let base = format!("{}://{resolved_host}:{port}", tls::gateway_scheme());
     let endpoints = ["/health", "/v1/health", "/status", "/"];
-    let client = Client::builder().timeout(Duration::from_secs(4)).build()?;
-
-    let mut last = HealthResult {
-        ok: false,
-        status: 0,
-        url: base.clone(),
-        body: String::new(),
-    };
+    // No auth header: until the listener has identified itself it may be anything, and
+    // the gateway token must not be handed to it.
+    let client = tls::gateway_client(Duration::from_secs(4))?;
 
+    let mut foreign: Option<HealthResult> = None;
+    let mut last_http: Option<HealthResult> = None;
     for endpoint in endpoints {
         let url = format!("{base}{endpoint}");
-        match client.get(&url).send().await {
-            Ok(resp) => {
-                let status = resp.status().as_u16();
-                let body = resp
-                    .text()
-                    .await
-                    .unwrap_or_default()
-                    .chars()
-                    .take(240)
-                    .collect::<String>();
-                if (200..300).contains(&status) {
-                    return Ok(HealthResult {
-                        ok: true,
-                        status,
-                        url,
-                        body,
-                    });
-                }
-                last = HealthResult {
+        let resp = match client.get(&url).send().await {
+            Ok(resp) => resp,
+            Err(err) => {
+                last_http = Some(HealthResult {
                     ok: false,
+                    status: 0,
+                    url,
+                    body: err.to_string(),
+                    ..Default::default()
+                });
+                continue;
+            }
+        };
+        let status = resp.status().as_u16();
+        let headers = resp
+            .headers()
+            .iter()
+            .map(|(name, value)| format!("{name}: {}", value.to_str().unwrap_or_default()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let full_body = resp.text().await.unwrap_or_default();
+        let body = full_body.chars().take(240).collect::<String>();
+        match classify(status, &headers, &full_body) {
+            Verdict::OpenClaw => {
+                return Ok(HealthResult {
+                    ok: true,
                     status,
                     url,
                     body,
-                };
+                    ..Default::default()
+                });
             }
-            Err(err) => {
-                last = HealthResult {
+            Verdict::Foreign => {
+                foreign.get_or_insert(HealthResult {
                     ok: false,
-                    status: 0,
+                    status,
                     url,
-                    body: err.to_string(),
-                };
+                    body,
+                    foreign_service: true,
+                    ..Default::default()
+                });
+            }
+            Verdict::Inconclusive => {
+                last_http = Some(HealthResult {
+                    ok: false,
+                    status,
+                    url,
+                    body,
+                    ..Default::default()
+                });
             }
         }
     }
 
-    if last.status == 0 {
-        Ok(last_tcp)
-    } else {
-        Ok(last)
+    Ok(unidentified(port, tcp, foreign, last_http))
+}
+
+/// The result when no answer identified itself as OpenClaw. A listener that answers but
+/// never names itself (generic `{"ok":true}`, auth walls, 404s) may be anything, so it
+/// is reported as unverified rather than healthy.
+fn unidentified(
+    port: u16,
+    tcp: HealthResult,
+    foreign: Option<HealthResult>,
+    last_http: Option<HealthResult>,
+) -> HealthResult {
+    if let Some(mut foreign) = foreign {
+        foreign.body = format!(
+            "Port {port} is answered by another service, not OpenClaw: {}",
+            foreign.body
+        );
+        return foreign;
+    }
+    if let Some(mut http) = last_http.filter(|http| http.status != 0) {
+        http.unverified = true;
+        http.body = format!(
+            "Port {port} answers HTTP {} but did not identify as OpenClaw: {}",
+            http.status, http.body
+        );
+        return http;
+    }
+    if tcp.ok {
+        return HealthResult {
+            ok: false,
+            unverified: true,
+            body: format!("Port {port} accepts connections but no HTTP answer came back."),
+            ..tcp
+        };
+    }
+    tcp
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Verdict {
+    OpenClaw,
+    Foreign,
+    Inconclusive,
+}
+
+/// What one HTTP answer says about who owns the port. Only an explicit banner counts as
+/// OpenClaw: an `x-openclaw-*` header or `Server: openclaw`, a JSON `service`/`name`
+/// field naming it, or the Control UI page title. Generic `{"ok":true}` health JSON is
+/// inconclusive, since plenty of other services answer exactly that.
+fn classify(status: u16, headers: &str, body: &str) -> Verdict {
+    if has_openclaw_banner(headers, body) {
+        return Verdict::OpenClaw;
+    }
+    if !(200..300).contains(&status) {
+        return Verdict::Inconclusive;
+    }
+    match serde_json::from_str::<Value>(body) {
+        Ok(Value::Object(fields)) if fields.contains_key("ok") || fields.contains_key("status") => {
+            Verdict::Inconclusive
+        }
+        // A 2xx page that is not ours: a captive portal, a dev server, another app.
+        _ => Verdict::Foreign,
+    }
+}
+
+fn has_openclaw_banner(headers: &str, body: &str) -> bool {
+    let names_openclaw = |text: &str| text.to_ascii_lowercase().contains("openclaw");
+    let header_banner = headers.lines().any(|line| {
+        let (name, value) = line.split_once(':').unwrap_or((line, ""));
+        let name = name.trim().to_ascii_lowercase();
+        name.starts_with("x-openclaw") || (name == "server" && names_openclaw(value))
+    });
+    if header_banner {
+        return true;
     }
+    if let Ok(Value::Object(fields)) = serde_json::from_str::<Value>(body) {
+        return ["service", "name", "app"].iter().any(|key| {
+            fields
+                .get(*key)
+                .and_then(Value::as_str)
+                .is_some_and(names_openclaw)
+        });
+    }
+    let lower = body.to_ascii_lowercase();
+    lower
+        .split_once("<title>")
+        .and_then(|(_, rest)| rest.split_once("</title>"))
+        .is_some_and(|(title, _)| names_openclaw(title))
+}
+
+/// Times one HTTP round-trip to the gateway and records it on `result`,
+/// so status can tell "up but sluggish" apart from healthy. TCP-only health stays as is.
+pub async fn measure_response_time(host: &str, port: u16, result: &mut HealthResult) {
+    if !result.ok {
+        return;
+    }
+    let Ok(client) = tls::gateway_client(PROBE_TIMEOUT) else {
+        return;
+    };
+    let url = format!(
+        "{}://{}:{port}/health",
+        tls::gateway_scheme(),
+        normalize_host(host)
+    );
+    let started = Instant::now();
+    let elapsed_ms = match client.get(&url).send().await {
+        Ok(resp) => {
+            // Any HTTP answer is a full round-trip through the gateway's request loop.
+            let _ = resp.bytes().await;
+            started.elapsed().as_millis() as u64
+        }
+        Err(err) if err.is_timeout() => PROBE_TIMEOUT.as_millis() as u64,
+        Err(_) => return,
+    };
+    result.latency_ms = Some(elapsed_ms);
+    result.sluggish = elapsed_ms > SLUGGISH_THRESHOLD_MS;
+}
+
+/// Single TCP connect attempt, for callers polling readiness on their own schedule.
+pub fn tcp_ready(host: &str, port: u16) -> bool {
+    tcp_probe(&normalize_host(host), port).is_some_and(|result| result.ok)
 }
 
 fn normalize_host(host: &str) -> String {
@@ -99,6 +241,7 @@ fn tcp_probe(host: &str, port: u16) -> Option<HealthResult> {
                     status: 200,
                     url: format!("tcp://{host}:{port}"),
                     body: "TCP connect succeeded".to_string(),
+                    ..Default::default()
                 })
             }
             Err(err) => last_err = Some(err.to_string()),
@@ -109,5 +252,83 @@ fn tcp_probe(host: &str, port: u16) -> Option<HealthResult> {
         status: 0,
         url: format!("tcp://{host}:{port}"),
         body: last_err.unwrap_or_else(|| "TCP probe failed".to_string()),
+        ..Default::default()
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, unidentified, Verdict};
+    use crate::models::HealthResult;
+
+    fn http(status: u16, body: &str) -> HealthResult {
+        HealthResult {
+            status,
+            url: "http://127.0.0.1:28789/health".to_string(),
+            body: body.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn answers_that_never_name_openclaw_are_unverified() {
+        let tcp = HealthResult {
+            ok: true,
+            url: "tcp://127.0.0.1:28789".to_string(),
+            ..Default::default()
+        };
+        for answer in [http(200, r#"{"ok":true}"#), http(404, "Not Found")] {
+            let result = unidentified(28789, tcp.clone(), None, Some(answer.clone()));
+            assert!(!result.ok, "{} counted as healthy", answer.body);
+            assert!(result.unverified);
+            assert!(!result.foreign_service);
+            assert_eq!(result.status, answer.status);
+        }
+
+        let silent = unidentified(28789, tcp.clone(), None, Some(http(0, "timed out")));
+        assert!(!silent.ok && silent.unverified);
+
+        let closed = HealthResult::default();
+        assert!(!unidentified(28789, closed, None, None).unverified);
+    }
+
+    #[test]
+    fn only_openclaw_answers_count_as_healthy() {
+        assert_eq!(
+            classify(200, "", r#"{"ok":true,"service":"openclaw-gateway"}"#),
+            Verdict::OpenClaw
+        );
+        assert_eq!(
+            classify(401, "x-openclaw-version: 2026.3.1", ""),
+            Verdict::OpenClaw
+        );
+        assert_eq!(classify(200, "", r#"{"ok":true}"#), Verdict::Inconclusive);
+        assert_eq!(
+            classify(200, "", r#"{"status":"up","note":"proxy for openclaw"}"#),
+            Verdict::Inconclusive
+        );
+        assert_eq!(
+            classify(200, "content-type: text/html", "<p>Try OpenClaw!</p>"),
+            Verdict::Foreign
+        );
+        assert_eq!(
+            classify(
+                200,
+                "content-type: text/html",
+                "<title>OpenClaw Control</title>"
+            ),
+            Verdict::OpenClaw
+        );
+        assert_eq!(
+            classify(
+                200,
+                "content-type: text/html",
+                "<title>Hotel Wi-Fi login</title>"
+            ),
+            Verdict::Foreign
+        );
+        assert_eq!(classify(200, "", r#"{"users":[]}"#), Verdict::Foreign);
+        assert_eq!(classify(404, "", "Not Found"), Verdict::Inconclusive);
+        assert_eq!(classify(401, "", ""), Verdict::Inconclusive);
+    }
+}
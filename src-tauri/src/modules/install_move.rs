@@ -0,0 +1,315 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::models::{InstallMoveResult, ResultWarning};
+
+use super::{
+    backup, cli_shim, daemon, fs_walk, gateway_service, json_layout, logger, paths, process,
+    state_store,
+};
+
+// Headroom on top of the install size so the copy never fills the target volume.
+const FREE_SPACE_MARGIN_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Moves the install directory (which is also the OpenClaw home) to `new_path` and
+/// rebinds install state, saved config and absolute paths in openclaw.json to it.
+pub fn move_install_dir(new_path: &str) -> Result<InstallMoveResult> {
+    let state = state_store::load_install_state()?
+        .ok_or_else(|| anyhow!("Install state not found. Install OpenClaw first."))?;
+    let old_dir = paths::normalize_path(&state.install_dir)?;
+    let new_dir = paths::normalize_path(new_path)?;
+    validate_target(&old_dir, &new_dir)?;
+    if daemon::detect().is_some() {
+        return Err(anyhow!(
+            "The OpenClaw daemon task points at the current folder. Remove the daemon before moving the install."
+        ));
+    }
+    // The service's command line carries the OpenClaw home, which lives in the install folder.
+    if gateway_service::detect().is_some() {
+        return Err(anyhow!(
+            "The OpenClaw gateway service points at the current folder. Uninstall the service before moving the install, then install it again."
+        ));
+    }
+
+    let size = fs_walk::total_size(&old_dir);
+    if let Some(free) = free_space_bytes(&new_dir) {
+        if free < size + FREE_SPACE_MARGIN_BYTES {
+            return Err(anyhow!(
+                "Not enough free space at {}: need {} MB, {} MB available.",
+                new_dir.to_string_lossy(),
+                (size + FREE_SPACE_MARGIN_BYTES) / (1024 * 1024),
+                free / (1024 * 1024)
+            ));
+        }
+    }
+
+    let was_running = process::running_pid().is_some();
+    process::stop()?;
+
+    let mut warnings = Vec::<ResultWarning>::new();
+    relocate_dir(&old_dir, &new_dir, &mut warnings)?;
+
+    let old_text = old_dir.to_string_lossy().to_string();
+    let new_text = new_dir.to_string_lossy().to_string();
+    let mut state = state;
+    state.install_dir = new_text.clone();
+    state.command_path = rebase_path(&state.command_path, &old_text, &new_text);
+    // The shim holds absolute paths into the old folder; write it again for the new one.
+    if state.shim_path.is_some() {
+        state.shim_path =
+            cli_shim::materialize(&new_dir, &state.command_path, cli_shim::answers_version);
+    }
+    state_store::save_install_state(&state)?;
+    if let Some(mut last) = state_store::load_last_config()? {
+        last.install_dir = new_text.clone();
+        state_store::save_last_config(&last)?;
+    }
+    std::env::set_var("OPENCLAW_INSTALLER_OPENCLAW_HOME", &new_text);
+    if let Err(err) = rebase_config_paths(&old_text, &new_text) {
+        warnings.push(
+            ResultWarning::warning(
+                "config_paths_not_rebased",
+                format!("openclaw.json still references the old folder: {err}"),
+            )
+            .with_remediation("Rerun configure from Maintenance to refresh the workspace path."),
+        );
+    }
+
+    let mut restarted = false;
+    if was_running {
+        match process::start() {
+            Ok(result) if result.running => restarted = true,
+            Ok(result) => warnings.push(ResultWarning::error(
+                "gateway_restart_failed",
+                format!(
+                    "Gateway did not start from the new folder: {}",
+                    result.message
+                ),
+            )),
+            Err(err) => warnings.push(ResultWarning::error(
+                "gateway_restart_failed",
+                format!("Gateway did not start from the new folder: {err}"),
+            )),
+        }
+    }
+
+    logger::info(&format!(
+        "Install directory moved: {old_text} -> {new_text} ({size} bytes)"
+    ));
+    Ok(InstallMoveResult {
+        old_dir: old_text,
+        new_dir: new_text,
+        bytes_moved: size,
+        restarted,
+        warnings,
+    })
+}
+
+fn validate_target(old_dir: &Path, new_dir: &Path) -> Result<()> {
+    if paths::is_user_profile_default_openclaw_dir(new_dir) {
+        return Err(anyhow!(
+            "Unsafe target directory: {}. Choose a folder outside the default OpenClaw home.",
+            new_dir.to_string_lossy()
+        ));
+    }
+    paths::ensure_local_install_dir(new_dir)?;
+    if new_dir.parent().is_none() {
+        return Err(anyhow!("Choose a folder, not a drive root."));
+    }
+    let old_key = path_key(old_dir);
+    let new_key = path_key(new_dir);
+    if old_key == new_key {
+        return Err(anyhow!("OpenClaw is already installed in that folder."));
+    }
+    if new_key.starts_with(&format!("{old_key}\\")) || old_key.starts_with(&format!("{new_key}\\"))
+    {
+        return Err(anyhow!(
+            "The new folder cannot contain, or be inside, the current install folder."
+        ));
+    }
+    if new_dir.exists()
+        && fs::read_dir(new_dir)
+            .map(|mut entries| entries.next().is_some())
+            .unwrap_or(true)
+    {
+        return Err(anyhow!(
+            "Target folder {} is not empty.",
+            new_dir.to_string_lossy()
+        ));
+    }
+    Ok(())
+}
+
+fn relocate_dir(old_dir: &Path, new_dir: &Path, warnings: &mut Vec<ResultWarning>) -> Result<()> {
+    if let Some(parent) = new_dir.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if new_dir.exists() {
+        fs::remove_dir(new_dir)?;
+    }
+    // Same-volume moves are a cheap rename; across volumes copy first and only
+    // delete the source once the copy is complete.
+    if fs::rename(old_dir, new_dir).is_ok() {
+        return Ok(());
+    }
+    if let Err(err) = backup::copy_dir_overwrite(old_dir, new_dir) {
+        let _ = fs::remove_dir_all(new_dir);
+        return Err(anyhow!(
+            "Copy to the new folder failed, nothing was moved: {err}"
+        ));
+    }
+    if let Err(err) = fs::remove_dir_all(old_dir) {
+        warnings.push(
+            ResultWarning::warning(
+                "old_dir_not_removed",
+                format!(
+                    "Old folder {} could not be fully removed: {err}",
+                    old_dir.to_string_lossy()
+                ),
+            )
+            .with_remediation("Delete the old folder manually once nothing is using it."),
+        );
+    }
+    Ok(())
+}
+
+fn rebase_config_paths(old_dir: &str, new_dir: &str) -> Result<()> {
+    let path = paths::config_path();
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut json: Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    if rebase_json_strings(&mut json, old_dir, new_dir) {
+        json_layout::write_preserving_layout(&path, &json)?;
+    }
+    Ok(())
+}
+
+pub fn rebase_json_strings(value: &mut Value, old_dir: &str, new_dir: &str) -> bool {
+    match value {
+        Value::String(text) => {
+            let rebased = rebase_path(text, old_dir, new_dir);
+            let changed = rebased != *text;
+            *text = rebased;
+            changed
+        }
+        Value::Array(items) => {
+            let mut changed = false;
+            for item in items {
+                changed |= rebase_json_strings(item, old_dir, new_dir);
+            }
+            changed
+        }
+        Value::Object(map) => {
+            let mut changed = false;
+            for item in map.values_mut() {
+                changed |= rebase_json_strings(item, old_dir, new_dir);
+            }
+            changed
+        }
+        _ => false,
+    }
+}
+
+fn rebase_path(value: &str, old_dir: &str, new_dir: &str) -> String {
+    let old_key = old_dir
+        .replace('/', "\\")
+        .trim_end_matches('\\')
+        .to_string();
+    let candidate = value.replace('/', "\\");
+    let lower = candidate.to_ascii_lowercase();
+    let old_lower = old_key.to_ascii_lowercase();
+    if lower == old_lower {
+        return new_dir.to_string();
+    }
+    if lower.starts_with(&format!("{old_lower}\\")) {
+        return format!(
+            "{}{}",
+            new_dir.trim_end_matches('\\'),
+            &candidate[old_key.len()..]
+        );
+    }
+    value.to_string()
+}
+
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('/', "\\")
+        .trim_end_matches('\\')
+        .to_ascii_lowercase()
+}
+
+#[cfg(windows)]
+fn free_space_bytes(target: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    // The target may not exist yet; ask about the closest existing ancestor.
+    let existing = target.ancestors().find(|p| p.exists())?;
+    let wide = existing
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect::<Vec<u16>>();
+    let mut available = 0u64;
+    // SAFETY: `wide` is NUL-terminated and outlives the call; the out pointer is valid.
+    let ok = unsafe {
+        windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW(
+            wide.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (ok != 0).then_some(available)
+}
+
+#[cfg(not(windows))]
+fn free_space_bytes(_target: &Path) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rebase_json_strings, rebase_path};
+    use serde_json::json;
+
+    #[test]
+    fn rebase_path_only_touches_paths_under_old_dir() {
+        let old = "C:\\Users\\a\\AppData\\Local\\OpenClawInstaller\\openclaw";
+        let new = "D:\\OpenClaw";
+        assert_eq!(
+            rebase_path(
+                "c:\\users\\a\\appdata\\local\\OpenClawInstaller\\openclaw\\workspace",
+                old,
+                new
+            ),
+            "D:\\OpenClaw\\workspace"
+        );
+        assert_eq!(rebase_path(old, old, new), new);
+        assert_eq!(
+            rebase_path(
+                "C:\\Users\\a\\AppData\\Local\\OpenClawInstaller\\openclaw-other",
+                old,
+                new
+            ),
+            "C:\\Users\\a\\AppData\\Local\\OpenClawInstaller\\openclaw-other"
+        );
+    }
+
+    #[test]
+    fn rebase_json_strings_walks_nested_values() {
+        let mut cfg = json!({
+            "agents": { "defaults": { "workspace": "C:/oc/workspace" } },
+            "gateway": { "port": 28789, "bind": "loopback" },
+            "paths": ["C:\\oc\\skills", "https://example.com"]
+        });
+        assert!(rebase_json_strings(&mut cfg, "C:\\oc", "E:\\oc"));
+        assert_eq!(cfg["agents"]["defaults"]["workspace"], "E:\\oc\\workspace");
+        assert_eq!(cfg["paths"][0], "E:\\oc\\skills");
+        assert_eq!(cfg["paths"][1], "https://example.com");
+        assert!(!rebase_json_strings(&mut cfg, "C:\\oc", "E:\\oc"));
+    }
+}
@@ -0,0 +1,49 @@
+use std::sync::{Mutex, OnceLock};
+
+use crate::models::InstallProgress;
+
+/// Tauri event the wizard listens on for install progress.
+pub const EVENT: &str = "install://progress";
+
+type Sink = Box<dyn Fn(&InstallProgress) + Send + Sync>;
+
+static SINK: OnceLock<Sink> = OnceLock::new();
+// Last phase and percent, so streamed output lines are tagged with where they belong.
+static CURRENT: Mutex<(String, u8)> = Mutex::new((String::new(), 0));
+
+/// Registers where progress goes; `main` forwards it to the frontend as `EVENT`.
+pub fn set_sink(sink: impl Fn(&InstallProgress) + Send + Sync + 'static) {
+    let _ = SINK.set(Box::new(sink));
+}
+
+pub fn phase(phase: &str, percent: u8, message: &str) {
+    {
+        let mut current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+        *current = (phase.to_string(), percent.min(100));
+    }
+    emit(InstallProgress {
+        phase: phase.to_string(),
+        percent: percent.min(100),
+        message: message.to_string(),
+        stream: None,
+        line: None,
+    });
+}
+
+/// One line of live npm/git/bun output for the current phase.
+pub fn line(stream: &str, text: &str) {
+    let (phase, percent) = CURRENT.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    emit(InstallProgress {
+        phase,
+        percent,
+        message: String::new(),
+        stream: Some(stream.to_string()),
+        line: Some(text.to_string()),
+    });
+}
+
+fn emit(progress: InstallProgress) {
+    if let Some(sink) = SINK.get() {
+        sink(&progress);
+    }
+}
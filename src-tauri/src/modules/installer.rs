@@ -1,15 +1,216 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use anyhow::{anyhow, Context, Result};
 use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::Instrument;
 
 use crate::models::{
-    InstallResult, InstallState, OpenClawConfigInput, SourceMethod, UninstallResult,
+    InstallResult, InstallState, OpenClawConfigInput, ResultWarning, SourceMethod,
+    UninstallItemKind, UninstallPreview, UninstallPreviewItem, UninstallResult, VerifiedPackage,
 };
 
-use super::{logger, paths, process, shell, state_store};
+use super::fs_walk::sha256_file;
+use super::{
+    backup, cli_shim, config, daemon, firewall, fs_walk, gateway_service, install_progress, logger,
+    offline_bundle, paths, process, sbom, shell, state_store,
+};
+
+const LATEST_SPEC: &str = "openclaw@latest";
+const DOWNLOAD_ATTEMPTS: usize = 3;
+// How much is downloaded between two updates of the `.part.json` sidecar.
+const PARTIAL_SAVE_EVERY: u64 = 4 * 1024 * 1024;
+/// Upgrade staging folder inside the install dir, so the final swap is a same-volume rename.
+pub const UPGRADE_STAGING_DIR: &str = ".upgrade-staging";
+const PACKAGE_MANIFESTS: &[&str] = &["package.json", "package-lock.json", "bun.lockb"];
+/// Copy of a binary or offline install taken before an upgrade, inside the install dir
+/// so restoring it is a same-volume rename.
+pub const UPGRADE_SNAPSHOT_DIR: &str = ".upgrade-snapshot";
+/// What a binary or offline install puts into the install dir besides the manifests.
+const SNAPSHOT_PACKAGE_ENTRIES: &[&str] = &["openclaw.exe", "node_modules"];
+
+/// A new package installed beside the live one while the gateway keeps running.
+pub struct StagedUpgrade {
+    install_dir: PathBuf,
+    staging: PathBuf,
+    verified: Option<VerifiedPackage>,
+}
+
+/// The exact package an install resolved to, captured before an upgrade so a failed
+/// upgrade can put the same code back. Binary and offline installs cannot be fetched
+/// again by version, so their files are copied aside instead (`Snapshot` holds the copy).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackagePin {
+    Npm(String),
+    Bun(String),
+    Git(String),
+    Snapshot(PathBuf),
+}
+
+pub fn capture_package_pin(state: &InstallState) -> Result<PackagePin> {
+    let install_dir = paths::normalize_path(&state.install_dir)?;
+    match state.method {
+        SourceMethod::Npm | SourceMethod::Bun => {
+            let version = installed_package_version(&install_dir).ok_or_else(|| {
+                anyhow!(
+                    "Installed openclaw package version not found under {}.",
+                    install_dir.to_string_lossy()
+                )
+            })?;
+            Ok(match state.method {
+                SourceMethod::Bun => PackagePin::Bun(version),
+                _ => PackagePin::Npm(version),
+            })
+        }
+        SourceMethod::Git => {
+            let git_exe = shell::command_exists("git").ok_or_else(|| anyhow!("git not found."))?;
+            let dir = install_dir.to_string_lossy().to_string();
+            let out = shell::run_command(
+                git_exe.as_str(),
+                &["-C", dir.as_str(), "rev-parse", "HEAD"],
+                None,
+                &[],
+            )?;
+            shell::ensure_success("git rev-parse HEAD", &out)?;
+            Ok(PackagePin::Git(out.stdout.trim().to_string()))
+        }
+        SourceMethod::Binary | SourceMethod::OfflineArchive => {
+            snapshot_package(&install_dir).map(PackagePin::Snapshot)
+        }
+    }
+}
+
+fn snapshot_entries() -> impl Iterator<Item = &'static str> {
+    SNAPSHOT_PACKAGE_ENTRIES
+        .iter()
+        .chain(PACKAGE_MANIFESTS)
+        .copied()
+}
+
+/// Copies `openclaw.exe`, `node_modules` and the manifests aside. Copied rather than
+/// moved: the gateway keeps running from them until the upgrade stops it.
+fn snapshot_package(install_dir: &Path) -> Result<PathBuf> {
+    let snapshot = install_dir.join(UPGRADE_SNAPSHOT_DIR);
+    if snapshot.exists() {
+        fs::remove_dir_all(paths::long_path(&snapshot))?;
+    }
+    fs::create_dir_all(&snapshot)?;
+    let copied = snapshot_entries().try_for_each(|name| -> Result<()> {
+        let live = install_dir.join(name);
+        if live.is_dir() {
+            backup::copy_dir_overwrite(&live, &snapshot.join(name))?;
+        } else if live.is_file() {
+            fs::copy(&live, snapshot.join(name))?;
+        }
+        Ok(())
+    });
+    if let Err(err) = copied {
+        let _ = fs::remove_dir_all(paths::long_path(&snapshot));
+        return Err(err.context("failed to copy the installed package aside"));
+    }
+    Ok(snapshot)
+}
+
+/// Puts the snapshot back in place of whatever the failed upgrade left, including
+/// entries the upgrade added.
+fn restore_snapshot(install_dir: &Path, snapshot: &Path) -> Result<()> {
+    if !snapshot.is_dir() {
+        return Err(anyhow!(
+            "The pre-upgrade copy {} is missing.",
+            snapshot.to_string_lossy()
+        ));
+    }
+    for name in snapshot_entries() {
+        let live = install_dir.join(name);
+        if live.is_dir() {
+            fs::remove_dir_all(paths::long_path(&live))?;
+        } else if live.exists() {
+            fs::remove_file(&live)?;
+        }
+        let saved = snapshot.join(name);
+        if saved.exists() {
+            fs::rename(&saved, &live)
+                .with_context(|| format!("failed to restore {}", live.to_string_lossy()))?;
+        }
+    }
+    let _ = fs::remove_dir_all(paths::long_path(snapshot));
+    Ok(())
+}
+
+/// Drops what `capture_package_pin` kept once the upgrade no longer needs it.
+pub fn discard_package_pin(pin: &PackagePin) {
+    if let PackagePin::Snapshot(snapshot) = pin {
+        if let Err(err) = fs::remove_dir_all(paths::long_path(snapshot)) {
+            logger::warn(&format!(
+                "Could not remove the pre-upgrade copy {}: {err}",
+                snapshot.to_string_lossy()
+            ));
+        }
+    }
+}
+
+/// Reinstalls the pinned package into the install dir after a failed upgrade.
+pub async fn restore_package_pin(payload: &OpenClawConfigInput, pin: &PackagePin) -> Result<()> {
+    let (payload, pin) = (payload.clone(), pin.clone());
+    off_runtime(tracing::Span::current(), move || {
+        restore_package_pin_blocking(&payload, &pin)
+    })
+    .await
+}
+
+fn restore_package_pin_blocking(payload: &OpenClawConfigInput, pin: &PackagePin) -> Result<()> {
+    let install_dir = paths::normalize_path(&payload.install_dir)?;
+    let env_vars = proxy_env(payload);
+    logger::info(&format!("Restoring OpenClaw package to {pin:?}"));
+    match pin {
+        PackagePin::Npm(version) => {
+            let probes = VersionProbes::default();
+            install_from_npm(
+                &install_dir,
+                &format!("openclaw@{version}"),
+                &env_vars,
+                &probes,
+            )
+            .map(|_| ())
+        }
+        PackagePin::Bun(version) => {
+            install_from_bun(&install_dir, &format!("openclaw@{version}"), &env_vars)
+        }
+        PackagePin::Git(commit) => {
+            let git_exe = shell::command_exists("git").ok_or_else(|| anyhow!("git not found."))?;
+            let dir = install_dir.to_string_lossy().to_string();
+            let out = shell::run_command(
+                git_exe.as_str(),
+                &["-C", dir.as_str(), "reset", "--hard", commit.as_str()],
+                None,
+                &env_vars,
+            )?;
+            log_command_output("git reset --hard", &out);
+            shell::ensure_success("git reset --hard", &out)?;
+            install_git_dependencies(&install_dir, &env_vars)
+        }
+        PackagePin::Snapshot(snapshot) => restore_snapshot(&install_dir, snapshot),
+    }
+}
+
+pub fn installed_package_version(install_dir: &Path) -> Option<String> {
+    let raw = fs::read_to_string(
+        install_dir
+            .join("node_modules")
+            .join("openclaw")
+            .join("package.json"),
+    )
+    .ok()?;
+    let json: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    json.get("version")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
 
 pub async fn install_openclaw(payload: &OpenClawConfigInput) -> Result<InstallResult> {
     install_openclaw_inner(payload, false).await
@@ -22,6 +223,44 @@ pub async fn install_openclaw_for_upgrade(payload: &OpenClawConfigInput) -> Resu
 async fn install_openclaw_inner(
     payload: &OpenClawConfigInput,
     allow_reinstall: bool,
+) -> Result<InstallResult> {
+    install_progress::phase("preparing", 0, "Checking install directory.");
+    let span = tracing::info_span!(
+        "install",
+        method = ?payload.source_method,
+        reinstall = allow_reinstall
+    );
+    let result = install_steps(payload, allow_reinstall)
+        .instrument(span.clone())
+        .await;
+    match &result {
+        Ok(done) => install_progress::phase(
+            "done",
+            100,
+            &format!("OpenClaw {} installed.", done.version),
+        ),
+        Err(err) => {
+            span.in_scope(|| tracing::error!(error = %err, "install failed"));
+            install_progress::phase("failed", 100, &err.to_string())
+        }
+    }
+    result
+}
+
+/// Runs a blocking step (package manager, CLI probes) on tokio's blocking pool, inside
+/// `span`, so an async command does not stall the runtime while npm works.
+async fn off_runtime<T: Send + 'static>(
+    span: tracing::Span,
+    step: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(move || span.in_scope(step))
+        .await
+        .map_err(|err| anyhow!("Install worker failed: {err}"))?
+}
+
+async fn install_steps(
+    payload: &OpenClawConfigInput,
+    allow_reinstall: bool,
 ) -> Result<InstallResult> {
     if !allow_reinstall {
         // Hard lock: once install state exists, installer flow must not reinstall
@@ -37,10 +276,12 @@ async fn install_openclaw_inner(
     let install_dir = paths::normalize_path(&payload.install_dir)?;
     if paths::is_user_profile_default_openclaw_dir(&install_dir) {
         return Err(anyhow!(
-            "Unsafe install directory detected: {}. For isolation, choose a different folder (recommended: %LOCALAPPDATA%\\\\OpenClawInstaller\\\\openclaw).",
+            "Unsafe install directory detected: {}. For isolation, choose a different folder (recommended: %LOCALAPPDATA%\\\\OpenClawInstaller\\\\openclaw) and use \"Import existing installation\" to bring its config over.",
             install_dir.to_string_lossy()
         ));
     }
+    paths::ensure_local_install_dir(&install_dir)?;
+    paths::ensure_spawn_compatible(&install_dir)?;
     // Keep installer and OpenClaw state strictly bound to the chosen install directory.
     // This prevents mixing with any existing `%USERPROFILE%\\.openclaw` on the machine.
     std::env::set_var(
@@ -51,20 +292,205 @@ async fn install_openclaw_inner(
     fs::create_dir_all(&install_dir)?;
 
     let env_vars = proxy_env(payload);
+    let probes = Arc::new(VersionProbes::default());
 
-    match &payload.source_method {
-        SourceMethod::Npm => install_from_npm(&install_dir, &env_vars)?,
-        SourceMethod::Bun => install_from_bun(&install_dir, &env_vars)?,
-        SourceMethod::Git => install_from_git(&install_dir, payload, &env_vars)?,
-        SourceMethod::Binary => install_from_binary(&install_dir, payload, &env_vars).await?,
+    install_progress::phase(
+        "installing",
+        10,
+        &format!(
+            "Installing OpenClaw via {}.",
+            format!("{:?}", &payload.source_method).to_lowercase()
+        ),
+    );
+    let step = tracing::info_span!("install.package");
+    let verified = match &payload.source_method {
+        SourceMethod::Npm => {
+            let (dir, probes) = (install_dir.clone(), probes.clone());
+            off_runtime(step, move || {
+                install_from_npm(&dir, LATEST_SPEC, &env_vars, &probes)
+            })
+            .await?
+        }
+        SourceMethod::Bun => {
+            let dir = install_dir.clone();
+            off_runtime(step, move || install_from_bun(&dir, LATEST_SPEC, &env_vars)).await?;
+            None
+        }
+        SourceMethod::Git => {
+            let (dir, payload) = (install_dir.clone(), payload.clone());
+            off_runtime(step, move || install_from_git(&dir, &payload, &env_vars)).await?;
+            None
+        }
+        SourceMethod::Binary => {
+            install_from_binary(&install_dir, payload, &env_vars)
+                .instrument(step)
+                .await?;
+            None
+        }
+        SourceMethod::OfflineArchive => {
+            step.in_scope(|| install_from_offline_archive(&install_dir, payload))?;
+            None
+        }
+    };
+
+    let payload = payload.clone();
+    off_runtime(tracing::info_span!("install.record"), move || {
+        record_install(&install_dir, &payload, &probes, verified)
+    })
+    .await
+}
+
+/// npm and bun installs can be staged; git checkouts and binaries are swapped in place.
+pub fn supports_staged_upgrade(method: &SourceMethod) -> bool {
+    matches!(method, SourceMethod::Npm | SourceMethod::Bun)
+}
+
+/// Installs the latest package into a staging folder without touching the live
+/// `node_modules`, so the running gateway is unaffected until `swap_staged_upgrade`.
+pub async fn stage_upgrade(payload: &OpenClawConfigInput) -> Result<StagedUpgrade> {
+    let payload = payload.clone();
+    off_runtime(tracing::Span::current(), move || {
+        stage_upgrade_blocking(&payload)
+    })
+    .await
+}
+
+fn stage_upgrade_blocking(payload: &OpenClawConfigInput) -> Result<StagedUpgrade> {
+    let install_dir = paths::normalize_path(&payload.install_dir)?;
+    let staging = prepare_staging(&install_dir)?;
+    let env_vars = proxy_env(payload);
+    install_progress::phase(
+        "installing",
+        10,
+        "Installing the new OpenClaw version while the gateway keeps running.",
+    );
+    let installed = match payload.source_method {
+        SourceMethod::Npm => {
+            install_from_npm(&staging, LATEST_SPEC, &env_vars, &VersionProbes::default())
+        }
+        SourceMethod::Bun => install_from_bun(&staging, LATEST_SPEC, &env_vars).map(|_| None),
+        _ => Err(anyhow!("Only npm and bun installs can be staged.")),
+    }
+    .and_then(|verified| {
+        // npm may "succeed" by falling back to a global openclaw; that stages nothing.
+        installed_package_version(&staging)
+            .map(|_| verified)
+            .ok_or_else(|| anyhow!("The staged install did not produce an openclaw package."))
+    });
+    let verified = match installed {
+        Ok(verified) => verified,
+        Err(err) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(err);
+        }
+    };
+    Ok(StagedUpgrade {
+        install_dir,
+        staging,
+        verified,
+    })
+}
+
+/// Empties the staging folder and seeds it with the live manifests, so npm and bun
+/// resolve the same dependency tree they would in place.
+fn prepare_staging(install_dir: &Path) -> Result<PathBuf> {
+    let staging = install_dir.join(UPGRADE_STAGING_DIR);
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+    for name in PACKAGE_MANIFESTS {
+        if install_dir.join(name).is_file() {
+            fs::copy(install_dir.join(name), staging.join(name))?;
+        }
+    }
+    Ok(staging)
+}
+
+/// Moves the staged package into place. The gateway must be stopped: Windows keeps
+/// native modules of a running node process locked.
+pub async fn swap_staged_upgrade(
+    staged: StagedUpgrade,
+    payload: &OpenClawConfigInput,
+) -> Result<InstallResult> {
+    let payload = payload.clone();
+    off_runtime(tracing::Span::current(), move || {
+        swap_staged_upgrade_blocking(staged, &payload)
+    })
+    .await
+}
+
+fn swap_staged_upgrade_blocking(
+    staged: StagedUpgrade,
+    payload: &OpenClawConfigInput,
+) -> Result<InstallResult> {
+    move_staged_package(&staged)?;
+    let result = record_install(
+        &staged.install_dir,
+        payload,
+        &VersionProbes::default(),
+        staged.verified,
+    );
+    match &result {
+        Ok(done) => install_progress::phase(
+            "done",
+            100,
+            &format!("OpenClaw {} installed.", done.version),
+        ),
+        Err(err) => install_progress::phase("failed", 100, &err.to_string()),
+    }
+    result
+}
+
+/// Swaps the staged `node_modules` and manifests for the live ones by renaming them in,
+/// so no file is ever left half-copied. If the staged tree cannot be moved in, the live
+/// one is put back.
+fn move_staged_package(staged: &StagedUpgrade) -> Result<()> {
+    let live = staged.install_dir.join("node_modules");
+    let previous = staged.install_dir.join("node_modules.previous");
+    if previous.exists() {
+        fs::remove_dir_all(&previous)?;
+    }
+    if live.exists() {
+        fs::rename(&live, &previous)
+            .with_context(|| format!("failed to move aside {}", live.to_string_lossy()))?;
+    }
+    if let Err(err) = fs::rename(staged.staging.join("node_modules"), &live) {
+        if previous.exists() {
+            let _ = fs::rename(&previous, &live);
+        }
+        let _ = fs::remove_dir_all(&staged.staging);
+        return Err(anyhow!("Could not swap in the new OpenClaw package: {err}"));
+    }
+    for name in PACKAGE_MANIFESTS {
+        let staged_file = staged.staging.join(name);
+        if staged_file.is_file() {
+            fs::rename(&staged_file, staged.install_dir.join(name))
+                .with_context(|| format!("failed to move in the staged {name}"))?;
+        }
     }
+    let _ = fs::remove_dir_all(&previous);
+    let _ = fs::remove_dir_all(&staged.staging);
+    Ok(())
+}
 
+fn record_install(
+    install_dir: &Path,
+    payload: &OpenClawConfigInput,
+    probes: &VersionProbes,
+    verified_package: Option<VerifiedPackage>,
+) -> Result<InstallResult> {
+    install_progress::phase("verifying", 90, "Checking the installed OpenClaw command.");
     let command_path = resolve_command_path(
-        &install_dir,
+        install_dir,
         &payload.source_method,
         payload.source_url.clone(),
+        probes,
     )?;
-    let version = detect_version(&command_path).unwrap_or_else(|_| "unknown".to_string());
+    let version = probes
+        .version(&command_path)
+        .unwrap_or_else(|| "unknown".to_string());
+    let shim_path = cli_shim::materialize(install_dir, &command_path, |cmd| probes.is_usable(cmd));
     let install_state = InstallState {
         method: payload.source_method.clone(),
         install_dir: install_dir.to_string_lossy().to_string(),
@@ -72,12 +498,17 @@ async fn install_openclaw_inner(
         command_path: command_path.clone(),
         version: version.clone(),
         launch_args: payload.launch_args.clone(),
+        shim_path,
+        verified_package,
     };
     state_store::save_install_state(&install_state)?;
     logger::info(&format!(
         "OpenClaw installed using {:?} at {}",
         &payload.source_method, install_state.install_dir
     ));
+    if let Err(err) = sbom::generate_sbom() {
+        logger::warn(&format!("SBOM not generated: {err}"));
+    }
 
     Ok(InstallResult {
         method: format!("{:?}", &payload.source_method).to_lowercase(),
@@ -87,45 +518,206 @@ async fn install_openclaw_inner(
     })
 }
 
-fn install_from_npm(install_dir: &Path, env_vars: &[(String, String)]) -> Result<()> {
+/// Checks the freshly installed `openclaw` package before any of its install scripts
+/// run: the lockfile integrity must equal the registry's `dist.integrity`, and
+/// `npm audit signatures` must not report an invalid registry signature in the tree. A
+/// provenance attestation is noted when the registry publishes one. It runs on the
+/// version that actually landed, so a `latest` that moved during the install cannot
+/// slip through.
+fn verify_npm_package(
+    npm_exe: &str,
+    install_dir: &Path,
+    env_vars: &[(String, String)],
+) -> Result<Option<VerifiedPackage>> {
+    // npm fell back to an existing global openclaw; nothing was installed here.
+    let Some(version) = installed_package_version(install_dir) else {
+        return Ok(None);
+    };
+    let installed = fs::read_to_string(install_dir.join("package-lock.json"))
+        .ok()
+        .and_then(|raw| lock_integrity(&raw));
+    let Some(installed) = installed else {
+        logger::warn("package-lock.json has no integrity for openclaw; package not verified.");
+        return Ok(None);
+    };
+    let spec = format!("openclaw@{version}");
+    let out = shell::run_command(
+        npm_exe,
+        &["view", spec.as_str(), "dist", "--json"],
+        Some(install_dir),
+        env_vars,
+    )?;
+    let Some((published, provenance)) = (out.code == 0)
+        .then(|| parse_npm_dist(&out.stdout))
+        .flatten()
+    else {
+        logger::warn(&format!(
+            "Registry metadata for {spec} unavailable; package not verified: {}",
+            out.stderr.trim()
+        ));
+        return Ok(None);
+    };
+    if published != installed {
+        return Err(anyhow!(
+            "The installed {spec} does not match the registry (installed {installed}, published {published}). The download may have been tampered with; the install was not recorded."
+        ));
+    }
+    let signatures_verified = audit_signatures(npm_exe, install_dir, env_vars)?;
+    logger::info(&format!(
+        "Verified {spec} against the registry ({installed}, provenance: {}, signatures: {}).",
+        if provenance { "yes" } else { "none published" },
+        if signatures_verified {
+            "valid"
+        } else {
+            "not checked"
+        }
+    ));
+    Ok(Some(VerifiedPackage {
+        version,
+        integrity: installed,
+        provenance,
+        signatures_verified,
+        verified_at: chrono::Local::now().to_rfc3339(),
+    }))
+}
+
+/// `npm audit signatures` over the installed tree. An invalid signature fails the
+/// install; a registry or npm that cannot check signatures only loses the flag.
+fn audit_signatures(
+    npm_exe: &str,
+    install_dir: &Path,
+    env_vars: &[(String, String)],
+) -> Result<bool> {
+    let dir = paths::spawn_safe_path(install_dir)
+        .to_string_lossy()
+        .to_string();
+    let out = shell::run_command(
+        npm_exe,
+        &["audit", "signatures", "--prefix", dir.as_str()],
+        Some(install_dir),
+        env_vars,
+    )?;
+    log_command_output("npm audit signatures", &out);
+    match signature_audit_outcome(&out) {
+        SignatureAudit::Valid => Ok(true),
+        SignatureAudit::Invalid => Err(anyhow!(
+            "npm audit signatures found packages whose registry signature does not verify; the install was stopped before their scripts ran: {}",
+            merged_output_lower(&out).trim()
+        )),
+        SignatureAudit::Unavailable => {
+            logger::warn("npm audit signatures could not run; registry signatures not checked.");
+            Ok(false)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum SignatureAudit {
+    Valid,
+    Invalid,
+    Unavailable,
+}
+
+fn signature_audit_outcome(out: &shell::CmdOutput) -> SignatureAudit {
+    let text = merged_output_lower(out);
+    if text.contains("invalid") || text.contains("missing registry signature") {
+        SignatureAudit::Invalid
+    } else if out.code == 0 {
+        SignatureAudit::Valid
+    } else {
+        SignatureAudit::Unavailable
+    }
+}
+
+/// Runs the lifecycle scripts `--ignore-scripts` held back, once the tree is verified.
+fn run_package_scripts(npm_exe: &str, dir: &str, env_vars: &[(String, String)]) -> Result<()> {
+    install_progress::phase("installing", 88, "Running OpenClaw's install scripts.");
+    let out = run_streamed(npm_exe, &["--prefix", dir, "rebuild"], env_vars)
+        .with_context(|| format!("failed to start npm executable: {npm_exe}"))?;
+    log_command_output("npm rebuild", &out);
+    shell::ensure_success("npm rebuild", &out)
+}
+
+fn lock_integrity(lock: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(lock).ok()?;
+    json.pointer("/packages/node_modules~1openclaw/integrity")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+}
+
+/// `(integrity, has provenance)` from `npm view <pkg> dist --json`.
+fn parse_npm_dist(raw: &str) -> Option<(String, bool)> {
+    let json: serde_json::Value = serde_json::from_str(raw).ok()?;
+    let integrity = json.get("integrity")?.as_str()?.to_string();
+    let provenance = json
+        .pointer("/attestations/provenance/predicateType")
+        .and_then(|v| v.as_str())
+        .is_some_and(|kind| kind.contains("slsa.dev/provenance"));
+    Some((integrity, provenance))
+}
+
+/// Installs `spec` with lifecycle scripts held back, verifies what landed and only then
+/// runs the scripts. Returns the verification, or None when nothing was installed here
+/// or the registry could not vouch for it.
+fn install_from_npm(
+    install_dir: &Path,
+    spec: &str,
+    env_vars: &[(String, String)],
+    probes: &VersionProbes,
+) -> Result<Option<VerifiedPackage>> {
     let npm_exe = shell::command_exists("npm")
         .ok_or_else(|| anyhow!("npm not found. Please install Node.js first."))?;
     ensure_local_package_json(install_dir)?;
 
     // IMPORTANT: Never install globally. Global installs can overwrite an existing OpenClaw
     // the user is already using on this machine.
-    let dir = install_dir.to_string_lossy().to_string();
+    let dir = paths::spawn_safe_path(install_dir)
+        .to_string_lossy()
+        .to_string();
     logger::info(&format!(
-        "Installing OpenClaw locally: npm --prefix \"{}\" install openclaw@latest",
-        dir
+        "Installing OpenClaw locally: npm --prefix \"{dir}\" install {spec}"
     ));
     let install_args: Vec<&str> = vec![
         "--prefix",
         dir.as_str(),
         "install",
-        "openclaw@latest",
+        spec,
+        "--ignore-scripts",
         "--no-audit",
         "--no-fund",
         "--loglevel",
         "error",
     ];
     let attempts = npm_install_attempts(env_vars);
+    let total = attempts.len();
     let mut out: Option<shell::CmdOutput> = None;
-    for attempt in attempts {
+    for (index, attempt) in attempts.into_iter().enumerate() {
         logger::info(&format!("npm install attempt: {}", attempt.label));
-        let current = shell::run_command(
-            npm_exe.as_str(),
-            &install_args,
-            None,
-            attempt.env.as_slice(),
-        )
-        .with_context(|| format!("failed to start npm executable: {npm_exe}"))?;
+        install_progress::phase(
+            "installing",
+            attempt_percent(index, total),
+            &format!(
+                "npm install (attempt {} of {total}: {}).",
+                index + 1,
+                attempt.label
+            ),
+        );
+        let current = run_streamed(npm_exe.as_str(), &install_args, attempt.env.as_slice())
+            .with_context(|| format!("failed to start npm executable: {npm_exe}"))?;
         log_command_output(
-            &format!("npm install openclaw@latest (local) [{}]", attempt.label),
+            &format!("npm install {spec} (local) [{}]", attempt.label),
             &current,
         );
         if current.code == 0 {
-            return Ok(());
+            install_progress::phase(
+                "verifying",
+                86,
+                "Verifying the package against the registry.",
+            );
+            let verified =
+                verify_npm_package(npm_exe.as_str(), install_dir, attempt.env.as_slice())?;
+            run_package_scripts(npm_exe.as_str(), dir.as_str(), attempt.env.as_slice())?;
+            return Ok(verified);
         }
         let retry_with_next_route = is_npm_git_fetch_failure(&current);
         out = Some(current);
@@ -137,14 +729,15 @@ fn install_from_npm(install_dir: &Path, env_vars: &[(String, String)]) -> Result
             attempt.label
         ));
     }
-    let out = out.ok_or_else(|| anyhow!("npm install openclaw@latest did not run."))?;
+    let out = out.ok_or_else(|| anyhow!("npm install {spec} did not run."))?;
 
-    if let Some(existing) = shell::command_exists("openclaw") {
-        if command_is_usable(existing.as_str()) {
+    // A rollback must land on the pinned version, so only fresh installs may fall back.
+    if let Some(existing) = shell::command_exists("openclaw").filter(|_| spec == LATEST_SPEC) {
+        if probes.is_usable(existing.as_str()) {
             logger::warn(&format!(
                 "npm local install failed, fallback to existing openclaw binary: {existing}"
             ));
-            return Ok(());
+            return Ok(None);
         }
         logger::warn(&format!(
             "Found global openclaw but it is not runnable: {}",
@@ -153,7 +746,7 @@ fn install_from_npm(install_dir: &Path, env_vars: &[(String, String)]) -> Result
     }
     if is_npm_git_fetch_failure(&out) {
         return Err(anyhow!(
-            "npm install openclaw@latest (local) failed after registry+mirror retries. Git dependencies from GitHub are unreachable or unauthorized in current network. Configure a working HTTP(S) proxy in Wizard -> Advanced, or allow access to github.com / gitclone.com / gh.llkk.cc and npm registry mirrors. Last error: {}",
+            "npm install {spec} (local) failed after registry+mirror retries. Git dependencies from GitHub are unreachable or unauthorized in current network. Configure a working HTTP(S) proxy in Wizard -> Advanced, or allow access to github.com / gitclone.com / gh.llkk.cc and npm registry mirrors. Last error: {}",
             if out.stderr.is_empty() {
                 out.stdout.clone()
             } else {
@@ -161,8 +754,22 @@ fn install_from_npm(install_dir: &Path, env_vars: &[(String, String)]) -> Result
             }
         ));
     }
-    shell::ensure_success("npm install openclaw@latest (local)", &out)?;
-    Ok(())
+    shell::ensure_success(&format!("npm install {spec} (local)"), &out)?;
+    Ok(None)
+}
+
+/// Runs an install step with its output streamed to the progress event.
+fn run_streamed(
+    exe: &str,
+    args: &[&str],
+    env_vars: &[(String, String)],
+) -> Result<shell::CmdOutput> {
+    shell::run_command_streaming(exe, args, None, env_vars, &install_progress::line)
+}
+
+/// Spreads npm retries over 10-85% so a fallback route visibly moves the bar.
+fn attempt_percent(index: usize, total: usize) -> u8 {
+    (10 + index * 75 / total.max(1)) as u8
 }
 
 fn ensure_local_package_json(install_dir: &Path) -> Result<()> {
@@ -244,21 +851,59 @@ fn npm_env_with_registry(base: &[(String, String)], registry: &str) -> Vec<(Stri
     out
 }
 
-fn install_from_bun(install_dir: &Path, env_vars: &[(String, String)]) -> Result<()> {
+fn install_from_bun(install_dir: &Path, spec: &str, env_vars: &[(String, String)]) -> Result<()> {
     let bun_exe = shell::command_exists("bun").ok_or_else(|| anyhow!("bun not found."))?;
-    let dir = install_dir.to_string_lossy().to_string();
-    let out = shell::run_command(
+    let dir = paths::spawn_safe_path(install_dir)
+        .to_string_lossy()
+        .to_string();
+    let out = run_streamed(
         bun_exe.as_str(),
-        &["add", "--cwd", dir.as_str(), "openclaw@latest"],
-        None,
+        &["add", "--cwd", dir.as_str(), spec],
         env_vars,
     )
     .with_context(|| format!("failed to start bun executable: {bun_exe}"))?;
-    log_command_output("bun add openclaw@latest", &out);
-    shell::ensure_success("bun add openclaw@latest", &out)?;
+    log_command_output(&format!("bun add {spec}"), &out);
+    shell::ensure_success(&format!("bun add {spec}"), &out)?;
     Ok(())
 }
 
+/// Installs from a local archive without touching the network: a bundle from
+/// `export_offline_bundle` is unpacked, an `npm pack` tarball goes through
+/// `npm install --offline`, which needs its dependencies bundled or in the npm cache.
+fn install_from_offline_archive(install_dir: &Path, payload: &OpenClawConfigInput) -> Result<()> {
+    let archive = offline_bundle::archive_from_source(payload.source_url.as_deref())?;
+    let archive_text = archive.to_string_lossy().to_string();
+    if !offline_bundle::is_npm_tarball(&archive) {
+        install_progress::phase("installing", 30, &format!("Unpacking {archive_text}."));
+        return offline_bundle::extract_bundle(&archive, install_dir);
+    }
+    let npm_exe = shell::command_exists("npm")
+        .ok_or_else(|| anyhow!("npm not found. Please install Node.js first."))?;
+    ensure_local_package_json(install_dir)?;
+    let dir = paths::spawn_safe_path(install_dir)
+        .to_string_lossy()
+        .to_string();
+    install_progress::phase("installing", 30, "Installing the npm tarball offline.");
+    let out = run_streamed(
+        npm_exe.as_str(),
+        &[
+            "install",
+            "--offline",
+            "--no-audit",
+            "--no-fund",
+            "--prefix",
+            dir.as_str(),
+            archive_text.as_str(),
+        ],
+        &[("npm_config_offline".to_string(), "true".to_string())],
+    )
+    .with_context(|| format!("failed to start npm executable: {npm_exe}"))?;
+    log_command_output("npm install --offline", &out);
+    shell::ensure_success("npm install --offline", &out).map_err(|err| {
+        anyhow!("{err}\nThe tarball's dependencies are not available offline; export a full bundle with export_offline_bundle on a connected machine instead.")
+    })
+}
+
 fn install_from_git(
     install_dir: &Path,
     payload: &OpenClawConfigInput,
@@ -273,10 +918,10 @@ fn install_from_git(
     let git_dir = install_dir.join(".git");
     if git_dir.exists() {
         let dir = install_dir.to_string_lossy().to_string();
-        let out = shell::run_command(
+        install_progress::phase("installing", 15, "Updating the OpenClaw checkout.");
+        let out = run_streamed(
             git_exe.as_str(),
             &["-C", dir.as_str(), "pull", "--ff-only"],
-            None,
             env_vars,
         )
         .with_context(|| format!("failed to start git executable: {git_exe}"))?;
@@ -284,24 +929,30 @@ fn install_from_git(
         shell::ensure_success("git pull", &out)?;
     } else {
         let dir = install_dir.to_string_lossy().to_string();
-        let out = shell::run_command(
+        install_progress::phase("installing", 15, &format!("Cloning {git_url}."));
+        let out = run_streamed(
             git_exe.as_str(),
-            &["clone", git_url.as_str(), dir.as_str()],
-            None,
+            &["clone", "--progress", git_url.as_str(), dir.as_str()],
             env_vars,
         )
         .with_context(|| format!("failed to start git executable: {git_exe}"))?;
         log_command_output("git clone", &out);
         shell::ensure_success("git clone", &out)?;
     }
+    install_git_dependencies(install_dir, env_vars)
+}
+
+fn install_git_dependencies(install_dir: &Path, env_vars: &[(String, String)]) -> Result<()> {
     if install_dir.join("package.json").exists() {
         let npm_exe = shell::command_exists("npm");
         if let Some(npm_exe) = npm_exe {
-            let dir = install_dir.to_string_lossy().to_string();
-            let out = shell::run_command(
+            let dir = paths::spawn_safe_path(install_dir)
+                .to_string_lossy()
+                .to_string();
+            install_progress::phase("installing", 50, "Installing dependencies with npm.");
+            let out = run_streamed(
                 npm_exe.as_str(),
                 &["install", "--prefix", dir.as_str()],
-                None,
                 env_vars,
             )
             .with_context(|| format!("failed to start npm executable: {npm_exe}"))?;
@@ -322,6 +973,11 @@ async fn install_from_binary(
         .clone()
         .filter(|s| !s.trim().is_empty())
         .ok_or_else(|| anyhow!("Binary source_url is required."))?;
+    let expected = payload
+        .source_checksum
+        .as_deref()
+        .map(normalize_checksum)
+        .transpose()?;
     let mut client = Client::builder();
     if let Some(proxy) = env_vars
         .iter()
@@ -331,21 +987,212 @@ async fn install_from_binary(
         client = client.proxy(reqwest::Proxy::https(proxy)?);
     }
     let client = client.build()?;
-    let resp = client.get(url.clone()).send().await?;
-    if !resp.status().is_success() {
-        return Err(anyhow!("Binary download failed: HTTP {}", resp.status()));
-    }
-    let bytes = resp.bytes().await?;
-    let out = install_dir.join("openclaw.exe");
-    fs::write(out, &bytes)?;
-    logger::info("Binary download complete.");
+
+    // The partial file sits next to openclaw.exe so the final rename stays on one volume,
+    // and survives a failed attempt so the next one resumes instead of starting over.
+    let part = install_dir.join("openclaw.exe.part");
+    let meta_path = install_dir.join("openclaw.exe.part.json");
+    let mut meta = fs::read_to_string(&meta_path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<PartialDownload>(&raw).ok())
+        .filter(|meta| meta.url == url && part.exists())
+        .unwrap_or_else(|| {
+            let _ = fs::remove_file(&part);
+            PartialDownload {
+                url: url.clone(),
+                validator: None,
+                bytes: None,
+            }
+        });
+
+    let mut last_err = None;
+    for attempt in 1..=DOWNLOAD_ATTEMPTS {
+        match download_attempt(&client, &part, &meta_path, &mut meta).await {
+            Ok(()) => {
+                last_err = None;
+                break;
+            }
+            Err(err) => {
+                logger::warn(&format!(
+                    "Binary download attempt {attempt}/{DOWNLOAD_ATTEMPTS} failed: {err}"
+                ));
+                save_partial(&meta_path, &meta);
+                last_err = Some(err);
+            }
+        }
+    }
+    if let Some(err) = last_err {
+        return Err(anyhow!(
+            "Binary download failed after {DOWNLOAD_ATTEMPTS} attempts; the next install resumes from {} MB: {err}",
+            fs::metadata(&part).map(|m| m.len()).unwrap_or(0) / (1024 * 1024)
+        ));
+    }
+
+    install_progress::phase("verifying", 86, "Verifying the download.");
+    let actual = sha256_file(&part)?;
+    if let Some(expected) = expected {
+        if actual != expected {
+            let _ = fs::remove_file(&part);
+            let _ = fs::remove_file(&meta_path);
+            return Err(anyhow!(
+                "Checksum mismatch for {url}: expected sha256 {expected}, got {actual}. The download was discarded."
+            ));
+        }
+    }
+    // Rename replaces the old executable in one step, so openclaw.exe is never partial.
+    fs::rename(&part, install_dir.join("openclaw.exe"))
+        .with_context(|| "failed to replace openclaw.exe (is it still running?)")?;
+    let _ = fs::remove_file(&meta_path);
+    logger::info(&format!("Binary download complete (sha256 {actual})."));
     Ok(())
 }
 
+/// Sidecar for `openclaw.exe.part`: the URL it came from and the ETag/Last-Modified
+/// used as `If-Range`, so a changed file on the server restarts instead of splicing.
+/// Rewritten while the download runs, so a crash or kill still leaves it resumable.
+#[derive(Debug, Serialize, Deserialize)]
+struct PartialDownload {
+    url: String,
+    validator: Option<String>,
+    /// Bytes known to be flushed to the part file; anything past it may be torn.
+    #[serde(default)]
+    bytes: Option<u64>,
+}
+
+fn save_partial(meta_path: &Path, meta: &PartialDownload) {
+    if let Ok(raw) = serde_json::to_string(meta) {
+        let _ = fs::write(meta_path, raw);
+    }
+}
+
+/// Where to resume: the flushed length from the sidecar, capped by what the part file
+/// holds. Sidecars from before `bytes` was recorded trust the file.
+fn resume_offset(part_len: u64, flushed: Option<u64>) -> u64 {
+    flushed.map_or(part_len, |bytes| bytes.min(part_len))
+}
+
+async fn download_attempt(
+    client: &Client,
+    part: &Path,
+    meta_path: &Path,
+    meta: &mut PartialDownload,
+) -> Result<()> {
+    use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+    use std::io::Write;
+
+    let part_len = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+    let offset = resume_offset(part_len, meta.bytes);
+    if offset < part_len {
+        fs::OpenOptions::new()
+            .write(true)
+            .open(part)?
+            .set_len(offset)?;
+    }
+    let mut request = client.get(meta.url.as_str());
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={offset}-"));
+        if let Some(validator) = &meta.validator {
+            request = request.header(IF_RANGE, validator.as_str());
+        }
+    }
+    let mut resp = request.send().await?;
+    let status = resp.status();
+    if status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // Either already complete or the file shrank; only the hash can tell, so restart.
+        fs::remove_file(part)?;
+        meta.validator = None;
+        meta.bytes = None;
+        return Err(anyhow!("server rejected the resume range; restarting"));
+    }
+    if !status.is_success() {
+        return Err(anyhow!("HTTP {status}"));
+    }
+    let resumed = status == reqwest::StatusCode::PARTIAL_CONTENT
+        && resp
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with(&format!("bytes {offset}-")));
+    meta.validator = resp
+        .headers()
+        .get(ETAG)
+        .or_else(|| resp.headers().get(LAST_MODIFIED))
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let start = if resumed { offset } else { 0 };
+    meta.bytes = Some(start);
+    save_partial(meta_path, meta);
+    if offset > 0 {
+        logger::info(&format!(
+            "Binary download {} at {} bytes.",
+            if resumed { "resumed" } else { "restarted" },
+            start
+        ));
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part)?;
+    let total = resp.content_length().map(|len| len + start);
+    let mut written = start;
+    let mut last_percent = 0u8;
+    while let Some(chunk) = resp.chunk().await? {
+        file.write_all(&chunk)?;
+        written += chunk.len() as u64;
+        if written - meta.bytes.unwrap_or(0) >= PARTIAL_SAVE_EVERY {
+            file.sync_data()?;
+            meta.bytes = Some(written);
+            save_partial(meta_path, meta);
+        }
+        if let Some(total) = total.filter(|t| *t > 0) {
+            let percent = 10 + (written * 75 / total).min(75) as u8;
+            if percent > last_percent {
+                last_percent = percent;
+                install_progress::phase(
+                    "downloading",
+                    percent,
+                    &format!(
+                        "Downloaded {} of {} MB.",
+                        written / (1024 * 1024),
+                        total / (1024 * 1024)
+                    ),
+                );
+            }
+        }
+    }
+    file.flush()?;
+    meta.bytes = Some(written);
+    if let Some(total) = total {
+        if written < total {
+            return Err(anyhow!("connection closed at {written} of {total} bytes"));
+        }
+    }
+    Ok(())
+}
+
+/// Lowercase hex digest from `abc…` or `sha256:ABC…`.
+fn normalize_checksum(raw: &str) -> Result<String> {
+    let trimmed = raw.trim();
+    let hex = trimmed
+        .strip_prefix("sha256:")
+        .or_else(|| trimmed.strip_prefix("SHA256:"))
+        .unwrap_or(trimmed)
+        .to_ascii_lowercase();
+    if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!(
+            "source_checksum must be a 64-character SHA-256 hex digest."
+        ));
+    }
+    Ok(hex)
+}
+
 fn resolve_command_path(
     install_dir: &Path,
     method: &SourceMethod,
     source_url: Option<String>,
+    probes: &VersionProbes,
 ) -> Result<String> {
     match method {
         SourceMethod::Binary => Ok(install_dir
@@ -367,7 +1214,7 @@ fn resolve_command_path(
             for candidate in candidates {
                 if candidate.exists() {
                     let text = candidate.to_string_lossy().to_string();
-                    if command_is_usable(&text) {
+                    if probes.is_usable(&text) {
                         return Ok(text);
                     }
                     logger::warn(&format!(
@@ -376,7 +1223,7 @@ fn resolve_command_path(
                 }
             }
             if let Some(global) = shell::command_exists("openclaw") {
-                if command_is_usable(global.as_str()) {
+                if probes.is_usable(global.as_str()) {
                     return Ok(global);
                 }
             }
@@ -387,7 +1234,7 @@ fn resolve_command_path(
             }
             Ok("npx".to_string())
         }
-        SourceMethod::Npm => {
+        SourceMethod::Npm | SourceMethod::OfflineArchive => {
             // Prefer the locally installed shim under install_dir so we stay isolated and
             // do not depend on (or override) any global OpenClaw installation.
             let candidates = [
@@ -408,7 +1255,7 @@ fn resolve_command_path(
             for candidate in candidates {
                 if candidate.exists() {
                     let text = candidate.to_string_lossy().to_string();
-                    if command_is_usable(&text) {
+                    if probes.is_usable(&text) {
                         return Ok(text);
                     }
                     logger::warn(&format!(
@@ -417,16 +1264,16 @@ fn resolve_command_path(
                 }
             }
 
-            if let Some(global) = resolve_global_openclaw() {
+            if let Some(global) = resolve_global_openclaw(probes) {
                 return Ok(global);
             }
-            if let Some(local_home_cmd) = resolve_local_home_openclaw() {
+            if let Some(local_home_cmd) = resolve_local_home_openclaw(probes) {
                 return Ok(local_home_cmd);
             }
             Ok("npx".to_string())
         }
         SourceMethod::Bun => {
-            if let Some(global) = resolve_global_openclaw() {
+            if let Some(global) = resolve_global_openclaw(probes) {
                 return Ok(global);
             }
             let candidates = [
@@ -444,7 +1291,7 @@ fn resolve_command_path(
             for candidate in candidates {
                 if candidate.exists() {
                     let text = candidate.to_string_lossy().to_string();
-                    if command_is_usable(&text) {
+                    if probes.is_usable(&text) {
                         return Ok(text);
                     }
                     logger::warn(&format!(
@@ -457,58 +1304,57 @@ fn resolve_command_path(
     }
 }
 
-fn detect_version(command_path: &str) -> Result<String> {
-    if command_path.eq_ignore_ascii_case("npx") {
-        let Some(npx_exe) = shell::command_exists("npx") else {
-            return Ok("unknown".to_string());
-        };
-        let out = shell::run_command(
-            npx_exe.as_str(),
-            &["--yes", "openclaw", "--version"],
-            None,
-            &[],
-        )?;
-        if out.code == 0 {
-            return Ok(first_line_or_unknown(&out.stdout));
+/// `--version` results for one install or upgrade. Checking candidates, falling back
+/// to a global command and recording the version would otherwise spawn the same
+/// (slow to start) node shim several times.
+#[derive(Default)]
+struct VersionProbes(Mutex<HashMap<String, Option<String>>>);
+
+impl VersionProbes {
+    /// First line of a successful `--version`, or None when the command does not run.
+    fn version(&self, command_path: &str) -> Option<String> {
+        let key = command_path.to_ascii_lowercase();
+        if let Some(hit) = self.0.lock().unwrap_or_else(|e| e.into_inner()).get(&key) {
+            return hit.clone();
         }
-        return Ok("unknown".to_string());
+        let result = probe_version(command_path);
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, result.clone());
+        result
     }
-    match shell::run_command(command_path, &["--version"], None, &[]) {
-        Ok(out) if out.code == 0 => Ok(first_line_or_unknown(&out.stdout)),
-        _ => Ok("unknown".to_string()),
+
+    fn is_usable(&self, command_path: &str) -> bool {
+        self.version(command_path).is_some()
     }
 }
 
-fn command_is_usable(command_path: &str) -> bool {
-    if command_path.eq_ignore_ascii_case("npx") {
-        let Some(npx_exe) = shell::command_exists("npx") else {
-            return false;
-        };
-        let Ok(out) = shell::run_command(
+fn probe_version(command_path: &str) -> Option<String> {
+    let out = if command_path.eq_ignore_ascii_case("npx") {
+        let npx_exe = shell::command_exists("npx")?;
+        shell::run_command(
             npx_exe.as_str(),
             &["--yes", "openclaw", "--version"],
             None,
             &[],
-        ) else {
-            return false;
-        };
-        return out.code == 0;
+        )
+    } else {
+        shell::run_command(command_path, &["--version"], None, &[])
     }
-    let Ok(out) = shell::run_command(command_path, &["--version"], None, &[]) else {
-        return false;
-    };
-    out.code == 0
+    .ok()?;
+    (out.code == 0).then(|| first_line_or_unknown(&out.stdout))
 }
 
-fn resolve_global_openclaw() -> Option<String> {
+fn resolve_global_openclaw(probes: &VersionProbes) -> Option<String> {
     let global = shell::command_exists("openclaw")?;
-    if command_is_usable(global.as_str()) {
+    if probes.is_usable(global.as_str()) {
         return Some(global);
     }
     None
 }
 
-fn resolve_local_home_openclaw() -> Option<String> {
+fn resolve_local_home_openclaw(probes: &VersionProbes) -> Option<String> {
     let candidates = [
         paths::openclaw_home().join("openclaw.cmd"),
         paths::openclaw_home().join("openclaw"),
@@ -517,7 +1363,7 @@ fn resolve_local_home_openclaw() -> Option<String> {
     for candidate in candidates {
         if candidate.exists() {
             let text = candidate.to_string_lossy().to_string();
-            if command_is_usable(text.as_str()) {
+            if probes.is_usable(text.as_str()) {
                 return Some(text);
             }
         }
@@ -616,7 +1462,7 @@ pub fn uninstall_openclaw() -> Result<UninstallResult> {
     paths::ensure_dirs()?;
     logger::info("OpenClaw uninstall started.");
 
-    let mut warnings = Vec::<String>::new();
+    let mut warnings = Vec::<ResultWarning>::new();
     let mut removed_paths = Vec::<String>::new();
     let mut stopped_process = false;
 
@@ -624,36 +1470,37 @@ pub fn uninstall_openclaw() -> Result<UninstallResult> {
         Ok(_) => {
             stopped_process = true;
         }
-        Err(err) => warnings.push(format!("Failed to stop running process: {err}")),
-    }
-
-    let install_state = state_store::load_install_state()?;
-    // IMPORTANT: Never uninstall global OpenClaw automatically.
-    // Users may have their own global OpenClaw installation unrelated to this installer.
-
-    let mut targets = HashSet::<String>::new();
-    if let Some(state) = install_state.as_ref() {
-        let dir = paths::normalize_path(&state.install_dir)?;
-        targets.insert(dir.to_string_lossy().to_string());
+        Err(err) => warnings.push(
+            ResultWarning::warning(
+                "process_stop_failed",
+                format!("Failed to stop running process: {err}"),
+            )
+            .with_remediation("Close OpenClaw manually and delete any leftover folders."),
+        ),
     }
-    targets.insert(paths::openclaw_home().to_string_lossy().to_string());
-    targets.insert(paths::run_dir().to_string_lossy().to_string());
-    targets.insert(paths::state_dir().to_string_lossy().to_string());
-    targets.insert(paths::appdata_root().to_string_lossy().to_string());
 
-    for target in targets {
-        remove_dir_best_effort(Path::new(&target), &mut removed_paths, &mut warnings);
+    for target in uninstall_targets()? {
+        remove_dir_best_effort(&target, &mut removed_paths, &mut warnings);
     }
 
     // Ensure state files are removed even if the state dir still exists.
     if let Err(err) = state_store::clear_install_state() {
-        warnings.push(format!("Failed to clear install state file: {err}"));
+        warnings.push(ResultWarning::warning(
+            "state_clear_failed",
+            format!("Failed to clear install state file: {err}"),
+        ));
     }
     if let Err(err) = state_store::clear_last_config() {
-        warnings.push(format!("Failed to clear last config file: {err}"));
+        warnings.push(ResultWarning::warning(
+            "state_clear_failed",
+            format!("Failed to clear last config file: {err}"),
+        ));
     }
     if let Err(err) = state_store::clear_run_prefs() {
-        warnings.push(format!("Failed to clear run prefs file: {err}"));
+        warnings.push(ResultWarning::warning(
+            "state_clear_failed",
+            format!("Failed to clear run prefs file: {err}"),
+        ));
     }
 
     Ok(UninstallResult {
@@ -663,28 +1510,326 @@ pub fn uninstall_openclaw() -> Result<UninstallResult> {
     })
 }
 
+/// Dry run of `uninstall_openclaw`: the folders it deletes with their sizes, plus the
+/// scheduled task, service and firewall rule it leaves registered. The installer writes
+/// no registry entries of its own, so none are listed.
+pub fn preview_uninstall() -> Result<UninstallPreview> {
+    let mut items = uninstall_targets()?
+        .into_iter()
+        .filter(|target| target.exists() || paths::is_reparse_point(target))
+        .map(|target| {
+            let junction = paths::is_reparse_point(&target);
+            UninstallPreviewItem {
+                kind: UninstallItemKind::Directory,
+                size_bytes: Some(if junction {
+                    0
+                } else {
+                    fs_walk::total_size(&target)
+                }),
+                note: if junction {
+                    "Junction: only the link is removed, not its target.".to_string()
+                } else {
+                    String::new()
+                },
+                target: target.to_string_lossy().to_string(),
+                removed: true,
+            }
+        })
+        .collect::<Vec<_>>();
+    if let Some(task) = daemon::detect() {
+        items.push(kept_item(
+            UninstallItemKind::ScheduledTask,
+            task.task_name,
+            "Left registered; remove it with `openclaw gateway uninstall` first.",
+        ));
+    }
+    if gateway_service::detect().is_some() {
+        items.push(kept_item(
+            UninstallItemKind::Service,
+            gateway_service::SERVICE_NAME.to_string(),
+            "Left installed; remove the gateway service (as administrator) first.",
+        ));
+    }
+    if let Ok(cfg) = config::read_current_config() {
+        if firewall::rule_exists(cfg.port) {
+            items.push(kept_item(
+                UninstallItemKind::FirewallRule,
+                firewall::rule_name(cfg.port),
+                "Left in place; remove the firewall rule (as administrator) first.",
+            ));
+        }
+    }
+    let total_bytes = items.iter().filter_map(|item| item.size_bytes).sum();
+    Ok(UninstallPreview { items, total_bytes })
+}
+
+/// Folders `uninstall_openclaw` deletes. Folders inside another target are dropped, so
+/// sizes are not counted twice.
+fn uninstall_targets() -> Result<Vec<PathBuf>> {
+    // IMPORTANT: Never uninstall global OpenClaw automatically.
+    // Users may have their own global OpenClaw installation unrelated to this installer.
+    let mut targets = BTreeSet::<PathBuf>::new();
+    if let Some(state) = state_store::load_install_state()? {
+        targets.insert(paths::normalize_path(&state.install_dir)?);
+    }
+    targets.insert(paths::openclaw_home());
+    targets.insert(paths::run_dir());
+    targets.insert(paths::state_dir());
+    // Named profiles live under the default profile's data folder; keep it while any exist.
+    if paths::active_profile().is_some() || !paths::has_named_profiles() {
+        targets.insert(paths::profile_data_dir());
+    }
+    let mut outermost = Vec::<PathBuf>::new();
+    for target in targets {
+        // Sorted, so a parent always comes before the folders inside it.
+        if !outermost.iter().any(|parent| target.starts_with(parent)) {
+            outermost.push(target);
+        }
+    }
+    Ok(outermost)
+}
+
+fn kept_item(kind: UninstallItemKind, target: String, note: &str) -> UninstallPreviewItem {
+    UninstallPreviewItem {
+        kind,
+        target,
+        size_bytes: None,
+        removed: false,
+        note: note.to_string(),
+    }
+}
+
 fn remove_dir_best_effort(
     path: &Path,
     removed_paths: &mut Vec<String>,
-    warnings: &mut Vec<String>,
+    warnings: &mut Vec<ResultWarning>,
 ) {
+    if paths::is_reparse_point(path) {
+        // Unlink only; the junction target lives outside the managed tree.
+        match fs::remove_dir(path).or_else(|_| fs::remove_file(path)) {
+            Ok(_) => removed_paths.push(path.to_string_lossy().to_string()),
+            Err(err) => warnings.push(ResultWarning::warning(
+                "junction_remove_failed",
+                format!(
+                    "Failed to remove junction '{}': {}",
+                    path.to_string_lossy(),
+                    err
+                ),
+            )),
+        }
+        return;
+    }
     if !path.exists() {
         return;
     }
-    match fs::remove_dir_all(path) {
+    let links = paths::walk_managed(path).skipped_links;
+    if !links.is_empty() {
+        warnings.push(ResultWarning::info(
+            "junctions_unlinked",
+            format!(
+                "Removed {} junction/symlink(s) under '{}' without touching their targets: {}",
+                links.len(),
+                path.to_string_lossy(),
+                links
+                    .iter()
+                    .map(|link| link.to_string_lossy().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        ));
+    }
+    // std's remove_dir_all unlinks reparse points instead of descending into them.
+    match fs::remove_dir_all(paths::long_path(path)) {
         Ok(_) => removed_paths.push(path.to_string_lossy().to_string()),
-        Err(err) => warnings.push(format!(
-            "Failed to remove directory '{}': {}",
-            path.to_string_lossy(),
-            err
-        )),
+        Err(err) => warnings.push(
+            ResultWarning::warning(
+                "directory_remove_failed",
+                format!(
+                    "Failed to remove directory '{}': {}",
+                    path.to_string_lossy(),
+                    err
+                ),
+            )
+            .with_remediation("Close programs using this folder and delete it manually."),
+        ),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{is_npm_git_fetch_failure, npm_git_env, npm_git_env_with_mirror};
-    use crate::modules::shell::CmdOutput;
+    use super::{
+        audit_signatures, discard_package_pin, installed_package_version, is_npm_git_fetch_failure,
+        lock_integrity, move_staged_package, normalize_checksum, npm_git_env,
+        npm_git_env_with_mirror, parse_npm_dist, prepare_staging, restore_snapshot, resume_offset,
+        run_package_scripts, sha256_file, signature_audit_outcome, snapshot_package, PackagePin,
+        SignatureAudit, StagedUpgrade, VersionProbes, UPGRADE_SNAPSHOT_DIR, UPGRADE_STAGING_DIR,
+    };
+    use crate::modules::shell::{testing::RecordingRunner, with_runner, CmdOutput};
+
+    fn write_package(root: &std::path::Path, version: &str) {
+        let package = root.join("node_modules").join("openclaw");
+        std::fs::create_dir_all(&package).unwrap();
+        std::fs::write(
+            package.join("package.json"),
+            format!(r#"{{"name":"openclaw","version":"{version}"}}"#),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn resume_starts_at_the_last_flushed_byte() {
+        assert_eq!(resume_offset(10_000, Some(8_192)), 8_192);
+        // The sidecar is ahead only if the part file was replaced; trust the file.
+        assert_eq!(resume_offset(4_096, Some(8_192)), 4_096);
+        assert_eq!(resume_offset(10_000, None), 10_000);
+        assert_eq!(resume_offset(0, Some(0)), 0);
+    }
+
+    #[test]
+    fn staged_upgrade_swaps_in_the_new_package() {
+        let dir = std::env::temp_dir().join(format!("oc-stage-swap-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        write_package(&dir, "1.0.0");
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"dependencies":{"openclaw":"1.0.0"}}"#,
+        )
+        .unwrap();
+        // Left over from an interrupted run.
+        std::fs::create_dir_all(dir.join(UPGRADE_STAGING_DIR).join("stale")).unwrap();
+
+        let staging = prepare_staging(&dir).expect("staging");
+        assert!(!staging.join("stale").exists());
+        assert!(staging.join("package.json").is_file());
+        assert_eq!(installed_package_version(&dir).as_deref(), Some("1.0.0"));
+
+        write_package(&staging, "2.0.0");
+        std::fs::write(
+            staging.join("package.json"),
+            r#"{"dependencies":{"openclaw":"2.0.0"}}"#,
+        )
+        .unwrap();
+        let staged = StagedUpgrade {
+            install_dir: dir.clone(),
+            staging: staging.clone(),
+            verified: None,
+        };
+        move_staged_package(&staged).expect("swap");
+        assert_eq!(installed_package_version(&dir).as_deref(), Some("2.0.0"));
+        assert!(std::fs::read_to_string(dir.join("package.json"))
+            .unwrap()
+            .contains("2.0.0"));
+        assert!(!staging.exists());
+        assert!(!dir.join("node_modules.previous").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn failed_swap_keeps_the_live_package() {
+        let dir = std::env::temp_dir().join(format!("oc-stage-fail-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        write_package(&dir, "1.0.0");
+        let staging = prepare_staging(&dir).expect("staging");
+        // The staged install produced no node_modules.
+        let staged = StagedUpgrade {
+            install_dir: dir.clone(),
+            staging: staging.clone(),
+            verified: None,
+        };
+        assert!(move_staged_package(&staged).is_err());
+        assert_eq!(installed_package_version(&dir).as_deref(), Some("1.0.0"));
+        assert!(!staging.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn snapshot_pin_puts_the_previous_offline_package_back() {
+        let dir = std::env::temp_dir().join(format!("oc-snapshot-pin-{}", std::process::id()));
+        let package = dir.join("node_modules").join("openclaw");
+        std::fs::create_dir_all(&package).expect("package dir");
+        std::fs::write(package.join("package.json"), r#"{"version":"1.0.0"}"#).expect("old");
+        std::fs::write(dir.join("package.json"), "{}").expect("manifest");
+        let snapshot = snapshot_package(&dir).expect("snapshot");
+        assert_eq!(snapshot, dir.join(UPGRADE_SNAPSHOT_DIR));
+
+        // The failed upgrade replaced the package and added a binary.
+        std::fs::write(package.join("package.json"), r#"{"version":"2.0.0"}"#).expect("new");
+        std::fs::write(dir.join("openclaw.exe"), "new").expect("exe");
+        restore_snapshot(&dir, &snapshot).expect("restore");
+        assert_eq!(installed_package_version(&dir).as_deref(), Some("1.0.0"));
+        assert!(dir.join("package.json").is_file());
+        assert!(!dir.join("openclaw.exe").exists());
+        assert!(!snapshot.exists());
+
+        let pin = PackagePin::Snapshot(snapshot_package(&dir).expect("snapshot again"));
+        discard_package_pin(&pin);
+        assert!(!dir.join(UPGRADE_SNAPSHOT_DIR).exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn version_probes_run_each_command_once() {
+        let probes = VersionProbes::default();
+        let missing = std::env::temp_dir()
+            .join("oc-no-such-dir")
+            .join("openclaw.cmd");
+        let path = missing.to_string_lossy().to_string();
+        assert!(!probes.is_usable(&path));
+        assert_eq!(probes.version(&path.to_ascii_uppercase()), None);
+        assert_eq!(probes.0.lock().expect("lock").len(), 1);
+    }
+
+    #[test]
+    fn checksums_are_normalized_and_verified() {
+        let dir = std::env::temp_dir().join(format!("oc-sha-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("dir");
+        let file = dir.join("openclaw.exe.part");
+        std::fs::write(&file, "abc").expect("write");
+        let expected = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(sha256_file(&file).expect("hash"), expected);
+        assert_eq!(
+            normalize_checksum(&format!("sha256:{}", expected.to_ascii_uppercase()))
+                .expect("valid"),
+            expected
+        );
+        assert!(normalize_checksum("abc123").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn installed_package_version_reads_the_local_package_manifest() {
+        let dir = std::env::temp_dir().join(format!("oc-pin-{}", std::process::id()));
+        let pkg = dir.join("node_modules").join("openclaw");
+        std::fs::create_dir_all(&pkg).expect("dir");
+        assert_eq!(installed_package_version(&dir), None);
+        std::fs::write(
+            pkg.join("package.json"),
+            r#"{ "name": "openclaw", "version": "2026.2.14" }"#,
+        )
+        .expect("manifest");
+        assert_eq!(
+            installed_package_version(&dir).as_deref(),
+            Some("2026.2.14")
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn npm_integrity_and_provenance_are_read() {
+        let lock = r#"{"packages":{"":{},"node_modules/openclaw":{"version":"2026.2.14","integrity":"sha512-abc=="}}}"#;
+        assert_eq!(lock_integrity(lock).as_deref(), Some("sha512-abc=="));
+        assert_eq!(lock_integrity(r#"{"packages":{}}"#), None);
+
+        let dist = r#"{"integrity":"sha512-abc==","shasum":"0f","attestations":{"url":"https://registry.npmjs.org/-/npm/v1/attestations/openclaw@2026.2.14","provenance":{"predicateType":"https://slsa.dev/provenance/v1"}}}"#;
+        assert_eq!(
+            parse_npm_dist(dist),
+            Some(("sha512-abc==".to_string(), true))
+        );
+        assert_eq!(
+            parse_npm_dist(r#"{"integrity":"sha512-abc=="}"#),
+            Some(("sha512-abc==".to_string(), false))
+        );
+    }
 
     #[test]
     fn npm_git_env_includes_direct_rewrite_rules() {
@@ -720,4 +1865,85 @@ mod tests {
         };
         assert!(is_npm_git_fetch_failure(&auth));
     }
+
+    #[test]
+    fn signature_audit_blocks_only_on_invalid_signatures() {
+        let output = |code: i32, stdout: &str| CmdOutput {
+            code,
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        };
+        assert_eq!(
+            signature_audit_outcome(&output(
+                0,
+                "audited 212 packages\n212 packages have verified registry signatures"
+            )),
+            SignatureAudit::Valid
+        );
+        assert_eq!(
+            signature_audit_outcome(&output(
+                1,
+                "1 package has an invalid registry signature:\nopenclaw@1.2.3"
+            )),
+            SignatureAudit::Invalid
+        );
+        assert_eq!(
+            signature_audit_outcome(&output(1, "npm error found no dependencies to audit")),
+            SignatureAudit::Unavailable
+        );
+    }
+
+    #[test]
+    fn audit_signatures_fails_only_on_invalid_signatures() {
+        let dir = std::env::temp_dir().join(format!("oc-audit-sigs-{}", std::process::id()));
+        let env = vec![("npm_config_registry".to_string(), "https://r".to_string())];
+        let runner = RecordingRunner::new();
+        runner.respond(
+            "npm",
+            0,
+            "audited 120 packages\n120 packages have verified signatures",
+        );
+        runner.respond("npm", 1, "1 package has an invalid registry signature");
+        runner.respond("npm", 1, "npm error code EAUDITNOLOCK");
+        let outcomes = with_runner(runner.clone(), || {
+            (0..3)
+                .map(|_| audit_signatures("npm.cmd", &dir, &env).map_err(|err| err.to_string()))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(outcomes[0], Ok(true));
+        assert!(outcomes[1]
+            .as_ref()
+            .is_err_and(|err| err.contains("invalid")));
+        assert_eq!(outcomes[2], Ok(false));
+
+        let calls = runner.calls();
+        assert_eq!(
+            calls[0].args,
+            [
+                "audit",
+                "signatures",
+                "--prefix",
+                dir.to_string_lossy().as_ref()
+            ]
+        );
+        assert!(calls.iter().all(|call| call.env == env));
+    }
+
+    #[test]
+    fn package_scripts_run_npm_rebuild_in_the_install_dir() {
+        let runner = RecordingRunner::new();
+        runner.respond("npm", 0, "rebuilt dependencies successfully");
+        runner.respond("npm", 1, "gyp ERR! build error");
+        with_runner(runner.clone(), || {
+            run_package_scripts("npm.cmd", r"C:\OpenClaw", &[]).expect("rebuild succeeds");
+            assert!(run_package_scripts("npm.cmd", r"C:\OpenClaw", &[]).is_err());
+        });
+        assert_eq!(
+            runner.lines(),
+            vec![
+                r"npm.cmd --prefix C:\OpenClaw rebuild",
+                r"npm.cmd --prefix C:\OpenClaw rebuild"
+            ]
+        );
+    }
 }
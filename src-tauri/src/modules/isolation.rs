@@ -0,0 +1,366 @@
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::models::{GatewayIsolation, IsolationReport};
+
+use super::{logger, paths, process_tree, shell};
+
+const STATE_DIR_VAR: &str = "OPENCLAW_STATE_DIR";
+const CONFIG_PATH_VAR: &str = "OPENCLAW_CONFIG_PATH";
+// Passed to the installer's own service host; it becomes the gateway's state dir.
+const OPENCLAW_HOME_ARG: &str = "--openclaw-home";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawProcess {
+    process_id: u32,
+    name: Option<String>,
+    command_line: Option<String>,
+}
+
+/// Checks that every running gateway uses the installer's isolated OpenClaw home, by
+/// reading `OPENCLAW_STATE_DIR` / `OPENCLAW_CONFIG_PATH` from the live process (its
+/// environment block, or its command line when that is where they were passed). Catches
+/// a stray daemon still writing to `%USERPROFILE%\.openclaw`.
+pub fn verify_isolation() -> Result<IsolationReport> {
+    let expected_state_dir = paths::openclaw_home().to_string_lossy().to_string();
+    let expected_config_path = paths::config_path().to_string_lossy().to_string();
+    let managed_pid = process_tree::gateway_root_pid();
+
+    let mut processes = list_gateway_processes()?
+        .into_iter()
+        .filter(|proc| Some(proc.process_id) == managed_pid || looks_like_gateway(proc))
+        .map(|proc| inspect(proc, managed_pid, &expected_state_dir))
+        .collect::<Vec<_>>();
+    processes.sort_by_key(|proc| (!proc.managed, proc.pid));
+
+    let mut issues = Vec::new();
+    if let Some(pid) = managed_pid.filter(|pid| !processes.iter().any(|p| p.pid == *pid)) {
+        issues.push(format!(
+            "Gateway PID {pid} was not found in the process list; it may have just exited."
+        ));
+    }
+    for proc in &processes {
+        let label = format!(
+            "{} gateway {} (PID {})",
+            if proc.managed { "Managed" } else { "Other" },
+            proc.name,
+            proc.pid
+        );
+        match (proc.isolated, proc.source.as_str()) {
+            (Some(true), _) => {}
+            (Some(false), "default") => issues.push(format!(
+                "{label} has no {STATE_DIR_VAR}, so it uses %USERPROFILE%\\.openclaw."
+            )),
+            (Some(false), _) => issues.push(format!(
+                "{label} uses {} instead of the isolated home.",
+                proc.state_dir.as_deref().unwrap_or_default()
+            )),
+            (None, _) => issues.push(format!(
+                "{label}: environment not readable (try running the installer as administrator)."
+            )),
+        }
+        if let (Some(config_path), Some(true)) = (&proc.config_path, proc.isolated) {
+            if !same_path(config_path, &expected_config_path) {
+                issues.push(format!("{label} reads config from {config_path}."));
+            }
+        }
+    }
+    let isolated = issues.is_empty();
+    if !isolated {
+        logger::warn(&format!(
+            "Isolation audit found {} issue(s): {}",
+            issues.len(),
+            issues.join(" | ")
+        ));
+    }
+    Ok(IsolationReport {
+        isolated,
+        expected_state_dir,
+        expected_config_path,
+        processes,
+        issues,
+    })
+}
+
+fn list_gateway_processes() -> Result<Vec<RawProcess>> {
+    let script = "Get-CimInstance Win32_Process -Filter \"Name='node.exe' OR Name='bun.exe' OR Name='openclaw.exe'\" | Select-Object ProcessId,Name,CommandLine | ConvertTo-Json -Compress";
+    let out = shell::run_command(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", script],
+        None,
+        &[],
+    )?;
+    shell::ensure_success("process list", &out)?;
+    parse_process_list(&out.stdout)
+}
+
+fn parse_process_list(raw: &str) -> Result<Vec<RawProcess>> {
+    let raw = raw.trim();
+    // ConvertTo-Json prints nothing for no matches and a bare object for one.
+    if raw.is_empty() {
+        return Ok(vec![]);
+    }
+    let items = match serde_json::from_str::<serde_json::Value>(raw)? {
+        serde_json::Value::Array(items) => items,
+        other => vec![other],
+    };
+    Ok(items
+        .into_iter()
+        .filter_map(|item| serde_json::from_value::<RawProcess>(item).ok())
+        .collect())
+}
+
+fn looks_like_gateway(proc: &RawProcess) -> bool {
+    let cmd = proc
+        .command_line
+        .as_deref()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    cmd.contains("openclaw") && cmd.contains("gateway")
+}
+
+fn inspect(
+    proc: RawProcess,
+    managed_pid: Option<u32>,
+    expected_state_dir: &str,
+) -> GatewayIsolation {
+    let command_line = proc.command_line.unwrap_or_default();
+    let env = read_process_environment(proc.process_id);
+    let env_value = |name: &str| {
+        env.as_ref().and_then(|vars| {
+            vars.iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.clone())
+        })
+    };
+
+    let (state_dir, source) = if let Some(dir) = env_value(STATE_DIR_VAR) {
+        (Some(dir), "environment")
+    } else if let Some(dir) = value_after(&command_line, STATE_DIR_VAR)
+        .or_else(|| value_after(&command_line, OPENCLAW_HOME_ARG))
+    {
+        (Some(dir), "command_line")
+    } else if env.is_some() {
+        (None, "default")
+    } else {
+        (None, "unknown")
+    };
+    let config_path =
+        env_value(CONFIG_PATH_VAR).or_else(|| value_after(&command_line, CONFIG_PATH_VAR));
+    let isolated = match (&state_dir, source) {
+        (Some(dir), _) => Some(
+            same_path(dir, expected_state_dir)
+                && !paths::is_user_profile_default_openclaw_dir(std::path::Path::new(dir)),
+        ),
+        (None, "default") => Some(false),
+        _ => None,
+    };
+    GatewayIsolation {
+        pid: proc.process_id,
+        name: proc.name.unwrap_or_default(),
+        managed: Some(proc.process_id) == managed_pid,
+        state_dir,
+        config_path,
+        source: source.to_string(),
+        isolated,
+    }
+}
+
+/// Value following `marker` in a command line, as in `OPENCLAW_STATE_DIR='C:\x'`,
+/// `set OPENCLAW_STATE_DIR=C:\x&&` or `--openclaw-home "C:\x"`.
+fn value_after(command_line: &str, marker: &str) -> Option<String> {
+    let start = command_line
+        .to_ascii_lowercase()
+        .find(&marker.to_ascii_lowercase())?
+        + marker.len();
+    let rest = command_line[start..].trim_start_matches([' ', '=']);
+    let value = match rest.chars().next()? {
+        quote @ ('"' | '\'') => rest[1..].split(quote).next()?,
+        _ => rest
+            .split(|c: char| c.is_whitespace() || c == ';' || c == '&')
+            .next()?,
+    };
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn same_path(a: &str, b: &str) -> bool {
+    let normalize = |p: &str| {
+        p.trim()
+            .replace('/', "\\")
+            .trim_end_matches('\\')
+            .to_ascii_lowercase()
+    };
+    normalize(a) == normalize(b)
+}
+
+/// `NAME=value` pairs of a UTF-16 environment block. Entries starting with `=` are the
+/// per-drive current directories cmd keeps (`=C:=C:\`) and are skipped.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_environment_block(block: &[u16]) -> Vec<(String, String)> {
+    block
+        .split(|unit| *unit == 0)
+        .take_while(|entry| !entry.is_empty())
+        .filter_map(|entry| {
+            let text = String::from_utf16_lossy(entry);
+            let (key, value) = text.split_once('=')?;
+            (!key.is_empty()).then(|| (key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Copies the environment block out of another process through its PEB. Needs the same
+/// user (or elevation); returns `None` when access is denied.
+#[cfg(all(windows, target_pointer_width = "64"))]
+fn read_process_environment(pid: u32) -> Option<Vec<(String, String)>> {
+    use std::ffi::c_void;
+    use windows_sys::Wdk::System::Threading::{NtQueryInformationProcess, ProcessBasicInformation};
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Diagnostics::Debug::ReadProcessMemory;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
+    };
+
+    // x64 offsets of PEB.ProcessParameters and of Environment / EnvironmentSize in
+    // RTL_USER_PROCESS_PARAMETERS.
+    const PEB_PROCESS_PARAMETERS: usize = 0x20;
+    const PARAMS_ENVIRONMENT: usize = 0x80;
+    const PARAMS_ENVIRONMENT_SIZE: usize = 0x3F0;
+    const MAX_ENVIRONMENT_BYTES: usize = 512 * 1024;
+
+    // PROCESS_BASIC_INFORMATION, without the PEB type it points to.
+    #[repr(C)]
+    struct BasicInformation {
+        exit_status: i32,
+        peb_base_address: usize,
+        affinity_mask: usize,
+        base_priority: i32,
+        unique_process_id: usize,
+        inherited_from_unique_process_id: usize,
+    }
+
+    // SAFETY: plain handle request; a null result is checked below.
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, pid) };
+    if handle.is_null() {
+        return None;
+    }
+    let read = |address: usize, buf: &mut [u8]| {
+        let mut copied = 0usize;
+        // SAFETY: `buf` is valid for `buf.len()` bytes; the remote address is only read
+        // by the kernel, which fails the call instead of faulting.
+        let ok = unsafe {
+            ReadProcessMemory(
+                handle,
+                address as *const c_void,
+                buf.as_mut_ptr().cast(),
+                buf.len(),
+                &mut copied,
+            )
+        };
+        ok != 0 && copied == buf.len()
+    };
+    let read_usize = |address: usize| {
+        let mut bytes = [0u8; std::mem::size_of::<usize>()];
+        read(address, &mut bytes).then(|| usize::from_le_bytes(bytes))
+    };
+    let environment = (|| {
+        let mut info = BasicInformation {
+            exit_status: 0,
+            peb_base_address: 0,
+            affinity_mask: 0,
+            base_priority: 0,
+            unique_process_id: 0,
+            inherited_from_unique_process_id: 0,
+        };
+        let mut returned = 0u32;
+        // SAFETY: `info` matches the layout the kernel writes for ProcessBasicInformation.
+        let status = unsafe {
+            NtQueryInformationProcess(
+                handle,
+                ProcessBasicInformation,
+                (&mut info as *mut BasicInformation).cast(),
+                std::mem::size_of::<BasicInformation>() as u32,
+                &mut returned,
+            )
+        };
+        if status != 0 || info.peb_base_address == 0 {
+            return None;
+        }
+        let params = read_usize(info.peb_base_address + PEB_PROCESS_PARAMETERS)?;
+        let address = read_usize(params + PARAMS_ENVIRONMENT)?;
+        let size = read_usize(params + PARAMS_ENVIRONMENT_SIZE)?.min(MAX_ENVIRONMENT_BYTES);
+        let mut bytes = vec![0u8; size & !1];
+        if !read(address, &mut bytes) {
+            return None;
+        }
+        let block = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect::<Vec<_>>();
+        Some(parse_environment_block(&block))
+    })();
+    // SAFETY: `handle` came from OpenProcess and is closed exactly once.
+    unsafe { CloseHandle(handle) };
+    environment
+}
+
+#[cfg(not(all(windows, target_pointer_width = "64")))]
+fn read_process_environment(_pid: u32) -> Option<Vec<(String, String)>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_environment_block, parse_process_list, value_after};
+
+    #[test]
+    fn environment_block_skips_drive_entries() {
+        let block = "=C:=C:\\work\0OPENCLAW_STATE_DIR=C:\\iso\\openclaw\0Path=C:\\a;C:\\b\0\0"
+            .encode_utf16()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parse_environment_block(&block),
+            vec![
+                (
+                    "OPENCLAW_STATE_DIR".to_string(),
+                    "C:\\iso\\openclaw".to_string()
+                ),
+                ("Path".to_string(), "C:\\a;C:\\b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn command_line_values_are_found_quoted_or_bare() {
+        assert_eq!(
+            value_after(
+                "powershell -Command $env:OPENCLAW_STATE_DIR='C:\\Users\\a b\\iso';Start-Process",
+                "OPENCLAW_STATE_DIR"
+            )
+            .as_deref(),
+            Some("C:\\Users\\a b\\iso")
+        );
+        assert_eq!(
+            value_after(
+                "cmd /c set openclaw_state_dir=C:\\iso&& node gateway",
+                "OPENCLAW_STATE_DIR"
+            )
+            .as_deref(),
+            Some("C:\\iso")
+        );
+        assert_eq!(
+            value_after(
+                "\"openclaw-installer.exe\" --service-host --openclaw-home \"D:\\oc home\"",
+                "--openclaw-home"
+            )
+            .as_deref(),
+            Some("D:\\oc home")
+        );
+        assert_eq!(
+            value_after("node openclaw.mjs gateway", "--openclaw-home"),
+            None
+        );
+        assert!(parse_process_list("").expect("empty").is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+// The OpenClaw CLI writes JSON.stringify(cfg, null, 2) with a trailing newline.
+const DEFAULT_INDENT: &str = "  ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Layout {
+    indent: String,
+    crlf: bool,
+    trailing_newline: bool,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            indent: DEFAULT_INDENT.to_string(),
+            crlf: false,
+            trailing_newline: true,
+        }
+    }
+}
+
+/// Rewrites a JSON file keeping its key order, indent width, line endings and trailing
+/// newline, so config-history diffs only show the values that actually changed.
+/// Key order relies on serde_json's `preserve_order` feature.
+pub fn write_preserving_layout(path: &Path, value: &Value) -> Result<()> {
+    let previous = fs::read_to_string(path).ok();
+    let layout = previous.as_deref().map(detect_layout).unwrap_or_default();
+    let text = render(value, &layout)?;
+    if previous.as_deref() == Some(text.as_str()) {
+        return Ok(());
+    }
+    fs::write(path, text)?;
+    Ok(())
+}
+
+fn detect_layout(raw: &str) -> Layout {
+    let raw = raw.trim_start_matches('\u{feff}');
+    let indent = raw
+        .lines()
+        .skip(1)
+        .find(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.chars()
+                .take_while(|c| *c == ' ' || *c == '\t')
+                .collect::<String>()
+        })
+        .filter(|indent| !indent.is_empty())
+        .unwrap_or_else(|| DEFAULT_INDENT.to_string());
+    Layout {
+        indent,
+        crlf: raw.contains("\r\n"),
+        trailing_newline: raw.is_empty() || raw.ends_with('\n'),
+    }
+}
+
+fn render(value: &Value, layout: &Layout) -> Result<String> {
+    let mut buf = Vec::new();
+    let formatter = PrettyFormatter::with_indent(layout.indent.as_bytes());
+    let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
+    value.serialize(&mut ser)?;
+    let mut text = String::from_utf8(buf)?;
+    if layout.trailing_newline {
+        text.push('\n');
+    }
+    if layout.crlf {
+        text = text.replace('\n', "\r\n");
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{detect_layout, render, Layout};
+    use serde_json::Value;
+
+    #[test]
+    fn render_round_trips_cli_layout_and_key_order() {
+        let raw = "{\n  \"gateway\": {\n    \"port\": 28789,\n    \"bind\": \"loopback\"\n  },\n  \"agents\": {}\n}\n";
+        let value: Value = serde_json::from_str(raw).expect("json");
+        let layout = detect_layout(raw);
+        assert_eq!(layout, Layout::default());
+        assert_eq!(render(&value, &layout).expect("render"), raw);
+    }
+
+    #[test]
+    fn detect_layout_keeps_tabs_crlf_and_missing_newline() {
+        let raw = "{\r\n\t\"zeta\": 1,\r\n\t\"alpha\": 2\r\n}";
+        let layout = detect_layout(raw);
+        assert_eq!(layout.indent, "\t");
+        assert!(layout.crlf);
+        assert!(!layout.trailing_newline);
+        let mut value: Value = serde_json::from_str(raw).expect("json");
+        value["alpha"] = Value::from(3);
+        assert_eq!(
+            render(&value, &layout).expect("render"),
+            "{\r\n\t\"zeta\": 1,\r\n\t\"alpha\": 3\r\n}"
+        );
+    }
+}
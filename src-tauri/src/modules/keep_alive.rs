@@ -0,0 +1,307 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use serde_json::Value;
+
+use crate::models::{ChannelConnection, KeepAliveConfig, KeepAliveStatus};
+
+use super::{config, gateway_port, logger, process, profiles, state_store};
+
+const MIN_INTERVAL_SECS: u64 = 60;
+const MAX_ACTIONS: usize = 20;
+// How often a disabled task looks again whether the user turned it on.
+const IDLE_POLL: Duration = Duration::from_secs(60);
+
+/// What the loop does next for a channel that is still stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Reconnect {
+    /// Disable and re-enable the channel so the gateway reloads only its plugin.
+    ReloadChannel,
+    /// The reload did not help; restart the whole gateway.
+    RestartGateway,
+}
+
+#[derive(Debug, Default)]
+struct Tracker {
+    /// Consecutive checks each channel was seen disconnected.
+    misses: BTreeMap<String, u32>,
+    /// Channels already reloaded once without recovering.
+    reloaded: BTreeMap<String, bool>,
+}
+
+static TRACKER: Mutex<Option<Tracker>> = Mutex::new(None);
+static LAST_STATUS: Mutex<Option<KeepAliveStatus>> = Mutex::new(None);
+
+pub fn configure_keep_alive(cfg: &KeepAliveConfig) -> Result<KeepAliveConfig> {
+    let mut cfg = cfg.clone();
+    if cfg.interval_secs < MIN_INTERVAL_SECS {
+        return Err(anyhow!(
+            "Keep-alive interval must be at least {MIN_INTERVAL_SECS} seconds."
+        ));
+    }
+    cfg.stale_after_checks = cfg.stale_after_checks.max(1);
+    state_store::save_keep_alive(&cfg)?;
+    *TRACKER.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    logger::info(&format!(
+        "Channel keep-alive {} (every {} s).",
+        if cfg.enabled { "enabled" } else { "disabled" },
+        cfg.interval_secs
+    ));
+    Ok(cfg)
+}
+
+pub fn keep_alive_status() -> Result<KeepAliveStatus> {
+    let config = state_store::load_keep_alive()?;
+    let last = LAST_STATUS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    Ok(KeepAliveStatus {
+        config,
+        ..last.unwrap_or_default()
+    })
+}
+
+/// Forgets the miss counts and last result, e.g. after a profile switch.
+pub fn reset() {
+    *TRACKER.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    *LAST_STATUS.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Runs one check now, whether or not the background task is enabled.
+pub fn run_keep_alive_check() -> Result<KeepAliveStatus> {
+    let cfg = state_store::load_keep_alive()?;
+    check(&cfg)?;
+    keep_alive_status()
+}
+
+/// Background loop; does nothing until the user enables keep-alive.
+pub fn start() {
+    thread::spawn(|| loop {
+        let tick = profiles::worker_tick();
+        let cfg = state_store::load_keep_alive().unwrap_or_default();
+        if !cfg.enabled {
+            drop(tick);
+            thread::sleep(IDLE_POLL);
+            continue;
+        }
+        if let Err(err) = check(&cfg) {
+            logger::warn(&format!("Channel keep-alive check failed: {err}"));
+        }
+        drop(tick);
+        thread::sleep(Duration::from_secs(
+            cfg.interval_secs.max(MIN_INTERVAL_SECS),
+        ));
+    });
+}
+
+fn check(cfg: &KeepAliveConfig) -> Result<()> {
+    // A stopped gateway is the watchdog's business, not a stale connection.
+    if process::running_pid().is_none() {
+        return Ok(());
+    }
+    let proxy = state_store::load_last_config()?.and_then(|cfg| cfg.proxy);
+    let out = config::run_openclaw_cli(
+        &[
+            "channels".to_string(),
+            "status".to_string(),
+            "--json".to_string(),
+        ],
+        proxy.clone(),
+    )?;
+    if out.code != 0 {
+        return Err(anyhow!(
+            "channels status failed: {}",
+            config::cli_output_text(&out)
+        ));
+    }
+    let channels = serde_json::from_str::<Value>(out.stdout.trim())
+        .map(|json| gateway_port::parse_channel_status(&json))
+        .map_err(|err| anyhow!("channels status returned invalid JSON: {err}"))?;
+
+    let plan = {
+        let mut tracker = TRACKER.lock().unwrap_or_else(|e| e.into_inner());
+        plan_reconnects(
+            tracker.get_or_insert_with(Tracker::default),
+            &channels,
+            cfg.stale_after_checks.max(1),
+        )
+    };
+    let mut actions = Vec::new();
+    let mut restart_gateway = false;
+    for (channel, step) in plan {
+        match step {
+            Reconnect::ReloadChannel => actions.push(reload_channel(&channel, proxy.clone())),
+            Reconnect::RestartGateway => restart_gateway = true,
+        }
+    }
+    if restart_gateway {
+        let out = config::run_openclaw_cli(
+            &["gateway".to_string(), "restart".to_string()],
+            proxy.clone(),
+        )?;
+        actions.push(if out.code == 0 {
+            "gateway restarted for stale channel(s)".to_string()
+        } else {
+            format!("gateway restart failed: {}", config::cli_output_text(&out))
+        });
+    }
+    for action in &actions {
+        logger::warn(&format!("Channel keep-alive: {action}"));
+    }
+    record(channels, actions);
+    Ok(())
+}
+
+/// Updates the miss counts and returns the channels to reconnect. A channel is reloaded
+/// on reaching `threshold` misses; if it is still down `threshold` checks later the
+/// gateway is restarted. A connected check clears its history.
+fn plan_reconnects(
+    tracker: &mut Tracker,
+    channels: &[ChannelConnection],
+    threshold: u32,
+) -> Vec<(String, Reconnect)> {
+    let seen = |name: &String| channels.iter().any(|c| &c.channel == name);
+    tracker.misses.retain(|name, _| seen(name));
+    tracker.reloaded.retain(|name, _| seen(name));
+    let mut plan = Vec::new();
+    for channel in channels {
+        if channel.connected {
+            tracker.misses.remove(&channel.channel);
+            tracker.reloaded.remove(&channel.channel);
+            continue;
+        }
+        let misses = tracker.misses.entry(channel.channel.clone()).or_default();
+        *misses += 1;
+        if *misses < threshold {
+            continue;
+        }
+        *misses = 0;
+        let reloaded = tracker.reloaded.entry(channel.channel.clone()).or_default();
+        let step = if *reloaded {
+            *reloaded = false;
+            Reconnect::RestartGateway
+        } else {
+            *reloaded = true;
+            Reconnect::ReloadChannel
+        };
+        plan.push((channel.channel.clone(), step));
+    }
+    plan
+}
+
+fn reload_channel(channel: &str, proxy: Option<String>) -> String {
+    reload_with(channel, |enabled| {
+        let out = config::run_openclaw_cli(
+            &[
+                "config".to_string(),
+                "set".to_string(),
+                format!("channels.{channel}.enabled"),
+                enabled.to_string(),
+            ],
+            proxy.clone(),
+        )
+        .map_err(|err| err.to_string())?;
+        if out.code == 0 {
+            Ok(())
+        } else {
+            Err(config::cli_output_text(&out))
+        }
+    })
+}
+
+/// Toggles the channel off and on. Whatever happens to the first write, the channel is
+/// switched back on (with one retry), so a failed reload never leaves it disabled.
+fn reload_with(channel: &str, set_enabled: impl Fn(&str) -> Result<(), String>) -> String {
+    let disabled = set_enabled("false");
+    let enabled = set_enabled("true").or_else(|_| set_enabled("true"));
+    match (disabled, enabled) {
+        (Ok(()), Ok(())) => format!("{channel}: stale connection, channel reloaded"),
+        (Err(err), Ok(())) => format!("{channel}: reload failed: {err}"),
+        (_, Err(err)) => {
+            format!("{channel}: reload failed and the channel could not be re-enabled: {err}")
+        }
+    }
+}
+
+fn record(channels: Vec<ChannelConnection>, actions: Vec<String>) {
+    let mut last = LAST_STATUS.lock().unwrap_or_else(|e| e.into_inner());
+    let status = last.get_or_insert_with(KeepAliveStatus::default);
+    status.last_check_at = Some(Local::now().to_rfc3339());
+    status.channels = channels;
+    let stamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+    status
+        .actions
+        .extend(actions.into_iter().map(|a| format!("{stamp} {a}")));
+    let overflow = status.actions.len().saturating_sub(MAX_ACTIONS);
+    status.actions.drain(..overflow);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plan_reconnects, reload_with, Reconnect, Tracker};
+    use crate::models::ChannelConnection;
+    use std::cell::RefCell;
+
+    fn channel(name: &str, connected: bool) -> ChannelConnection {
+        ChannelConnection {
+            channel: name.to_string(),
+            connected,
+            detail: String::new(),
+        }
+    }
+
+    #[test]
+    fn stale_channel_is_reloaded_then_escalated_to_a_gateway_restart() {
+        let mut tracker = Tracker::default();
+        let down = [channel("feishu", false), channel("telegram", true)];
+        assert!(plan_reconnects(&mut tracker, &down, 2).is_empty());
+        assert_eq!(
+            plan_reconnects(&mut tracker, &down, 2),
+            vec![("feishu".to_string(), Reconnect::ReloadChannel)]
+        );
+        assert!(plan_reconnects(&mut tracker, &down, 2).is_empty());
+        assert_eq!(
+            plan_reconnects(&mut tracker, &down, 2),
+            vec![("feishu".to_string(), Reconnect::RestartGateway)]
+        );
+
+        // Recovering clears the history, so the next outage starts with a reload again.
+        plan_reconnects(&mut tracker, &[channel("feishu", true)], 2);
+        assert!(plan_reconnects(&mut tracker, &down, 1)
+            .iter()
+            .all(|(_, step)| *step == Reconnect::ReloadChannel));
+    }
+
+    #[test]
+    fn failed_reload_still_switches_the_channel_back_on() {
+        let writes = RefCell::new(Vec::new());
+        let result = reload_with("feishu", |enabled| {
+            writes.borrow_mut().push(enabled.to_string());
+            if enabled == "false" {
+                Err("config locked".to_string())
+            } else {
+                Ok(())
+            }
+        });
+        assert_eq!(result, "feishu: reload failed: config locked");
+        assert_eq!(writes.borrow().last().map(String::as_str), Some("true"));
+
+        let attempts = RefCell::new(0);
+        let result = reload_with("feishu", |enabled| {
+            if enabled == "true" {
+                *attempts.borrow_mut() += 1;
+                if *attempts.borrow() == 1 {
+                    return Err("busy".to_string());
+                }
+            }
+            Ok(())
+        });
+        assert_eq!(result, "feishu: stale connection, channel reloaded");
+        assert_eq!(*attempts.borrow(), 2);
+    }
+}
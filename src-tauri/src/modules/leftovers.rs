@@ -0,0 +1,392 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+
+use crate::models::{InstallState, LeftoverCleanResult, LeftoverItem, LeftoverKind};
+
+use super::{
+    config, daemon, firewall, fs_walk, gateway_service, installer, logger, paths, profiles, shell,
+    state_store,
+};
+
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+const NPM_SHIMS: &[&str] = &["openclaw", "openclaw.cmd", "openclaw.ps1"];
+
+/// Finds OpenClaw artifacts nothing owns any more: upgrade folders left by an interrupted
+/// upgrade, a package from an earlier install location, a service or scheduled task
+/// without an install, Run keys and npm shims pointing at missing files, and firewall
+/// rules for a port the gateway no longer uses. Only looks; see `clean_leftover`.
+pub fn scan_leftovers() -> Result<Vec<LeftoverItem>> {
+    let install_dir = state_store::load_install_state()?
+        .map(|state| paths::normalize_path(&state.install_dir))
+        .transpose()?;
+    let in_use = profile_install_dirs();
+    let mut items = Vec::new();
+    scan_directories(
+        install_dir.as_deref(),
+        &in_use,
+        &paths::isolated_openclaw_home_for(None),
+        &mut items,
+    );
+    // The service and scheduled task are per machine/user, not per profile.
+    if in_use.is_empty() {
+        if let Some(task) = daemon::detect() {
+            items.push(item(
+                LeftoverKind::ScheduledTask,
+                task.task_name,
+                "Gateway task registered without an installed OpenClaw.",
+                false,
+            ));
+        }
+        if gateway_service::detect().is_some() {
+            items.push(item(
+                LeftoverKind::Service,
+                gateway_service::SERVICE_NAME.to_string(),
+                "Gateway service registered without an installed OpenClaw.",
+                true,
+            ));
+        }
+    }
+    for (name, command) in run_entries() {
+        items.push(item(
+            LeftoverKind::RunKey,
+            name,
+            &format!("Starts {command}, which no longer exists."),
+            false,
+        ));
+    }
+    for rule in stale_firewall_rules(firewall_rules(), &profile_gateway_ports()) {
+        items.push(item(
+            LeftoverKind::FirewallRule,
+            rule,
+            "Opens a port no profile's gateway listens on.",
+            true,
+        ));
+    }
+    for shim in broken_npm_shims() {
+        items.push(item(
+            LeftoverKind::NpmLink,
+            shim.to_string_lossy().to_string(),
+            "Global npm shim for an openclaw package that is no longer installed.",
+            false,
+        ));
+    }
+    Ok(items)
+}
+
+/// Removes one item returned by `scan_leftovers`, identified by its `id`. The scan is
+/// repeated first so only something still orphaned is touched.
+pub fn clean_leftover(id: &str) -> Result<LeftoverCleanResult> {
+    let found = scan_leftovers()?
+        .into_iter()
+        .find(|item| item.id == id)
+        .ok_or_else(|| anyhow!("{id} is no longer a leftover; scan again."))?;
+    if found.needs_admin && !shell::is_admin() {
+        return Err(anyhow!(
+            "Removing {} requires administrator rights. Restart the installer as administrator.",
+            found.target
+        ));
+    }
+    match found.kind {
+        LeftoverKind::Directory => fs::remove_dir_all(paths::long_path(Path::new(&found.target)))?,
+        LeftoverKind::NpmLink => fs::remove_file(&found.target)?,
+        LeftoverKind::Service => gateway_service::uninstall()?,
+        LeftoverKind::ScheduledTask => {
+            let out = shell::run_command(
+                "schtasks",
+                &["/Delete", "/TN", &found.target, "/F"],
+                None,
+                &[],
+            )?;
+            shell::ensure_success("schtasks /Delete", &out)?;
+            daemon::invalidate();
+        }
+        LeftoverKind::RunKey => {
+            let out = shell::run_command(
+                "reg",
+                &["delete", RUN_KEY, "/v", &found.target, "/f"],
+                None,
+                &[],
+            )?;
+            shell::ensure_success("reg delete", &out)?;
+        }
+        LeftoverKind::FirewallRule => {
+            let out = firewall::delete_rule(&found.target)?;
+            shell::ensure_success("netsh advfirewall firewall delete rule", &out)?;
+        }
+    }
+    logger::info(&format!("Leftover removed: {}", found.id));
+    Ok(LeftoverCleanResult {
+        cleaned: found,
+        remaining: scan_leftovers()?,
+    })
+}
+
+/// Install folders recorded by any profile, so nothing another profile runs from is
+/// offered for removal.
+fn profile_install_dirs() -> Vec<PathBuf> {
+    let mut names = vec![None];
+    if let Ok(registry) = state_store::load_profiles() {
+        names.extend(
+            registry
+                .profiles
+                .into_iter()
+                .map(|profile| Some(profile.name)),
+        );
+    }
+    names
+        .into_iter()
+        .filter_map(|name| profile_install_state(name.as_deref()))
+        .filter_map(|state| paths::normalize_path(&state.install_dir).ok())
+        .collect()
+}
+
+fn profile_install_state(name: Option<&str>) -> Option<InstallState> {
+    state_store::load_install_state_in(&paths::profile_root(name).join("state"))
+        .ok()
+        .flatten()
+}
+
+/// Gateway ports of every profile with an install, plus the port the active config uses.
+/// Each profile has its own port, so any of them may still own a live LAN rule.
+fn profile_gateway_ports() -> Vec<u16> {
+    let mut ports = profiles::gateway_ports()
+        .into_iter()
+        .filter(|(name, _)| {
+            let name = Some(name.as_str()).filter(|name| *name != profiles::DEFAULT_PROFILE);
+            profile_install_state(name).is_some()
+        })
+        .map(|(_, port)| port)
+        .collect::<Vec<_>>();
+    if let Ok(cfg) = config::read_current_config() {
+        ports.push(cfg.port);
+    }
+    ports
+}
+
+fn stale_firewall_rules(rules: Vec<String>, ports_in_use: &[u16]) -> Vec<String> {
+    rules
+        .into_iter()
+        .filter(|rule| {
+            !ports_in_use
+                .iter()
+                .any(|port| firewall::rule_name(*port) == *rule)
+        })
+        .collect()
+}
+
+fn scan_directories(
+    install_dir: Option<&Path>,
+    in_use: &[PathBuf],
+    default_dir: &Path,
+    items: &mut Vec<LeftoverItem>,
+) {
+    let mut candidates = Vec::<(PathBuf, &str)>::new();
+    if let Some(dir) = install_dir {
+        candidates.push((
+            dir.join(installer::UPGRADE_STAGING_DIR),
+            "Staging folder of an interrupted upgrade.",
+        ));
+        candidates.push((
+            dir.join("node_modules.previous"),
+            "Previous package kept by an interrupted upgrade.",
+        ));
+        candidates.push((
+            dir.join(installer::UPGRADE_SNAPSHOT_DIR),
+            "Pre-upgrade copy kept by an interrupted upgrade.",
+        ));
+    }
+    // The config in the default folder may still be in use; only its package is offered,
+    // and only when no profile installed there.
+    if install_dir.is_some_and(|dir| dir != default_dir)
+        && !in_use.iter().any(|dir| dir == default_dir)
+        && installer::installed_package_version(default_dir).is_some()
+    {
+        candidates.push((
+            default_dir.join("node_modules"),
+            "OpenClaw package from an earlier install location.",
+        ));
+    }
+    for (path, reason) in candidates {
+        if path.is_dir() {
+            let mut found = item(
+                LeftoverKind::Directory,
+                path.to_string_lossy().to_string(),
+                reason,
+                false,
+            );
+            found.size_bytes = Some(fs_walk::total_size(&path));
+            items.push(found);
+        }
+    }
+}
+
+/// Run key values mentioning OpenClaw whose program is gone, as (name, command).
+fn run_entries() -> Vec<(String, String)> {
+    let Ok(out) = shell::run_command("reg", &["query", RUN_KEY], None, &[]) else {
+        return Vec::new();
+    };
+    if out.code != 0 {
+        return Vec::new();
+    }
+    parse_run_entries(&out.stdout)
+        .into_iter()
+        .filter(|(_, command)| command.to_ascii_lowercase().contains("openclaw"))
+        .filter(|(_, command)| !Path::new(&program_of(command)).exists())
+        .collect()
+}
+
+/// `    Name    REG_SZ    "C:\path\app.exe" --flag` lines of `reg query`.
+fn parse_run_entries(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, rest) = line.trim().split_once("    REG_")?;
+            let (_, command) = rest.split_once("    ")?;
+            Some((name.trim().to_string(), command.trim().to_string()))
+        })
+        .collect()
+}
+
+fn program_of(command: &str) -> String {
+    let command = command.trim();
+    match command.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next().unwrap_or_default().to_string(),
+        None => command
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+    }
+}
+
+/// Names of the installer's inbound rules, for any port.
+fn firewall_rules() -> Vec<String> {
+    let Ok(out) = shell::run_command(
+        "netsh",
+        &[
+            "advfirewall",
+            "firewall",
+            "show",
+            "rule",
+            "name=all",
+            "dir=in",
+        ],
+        None,
+        &[],
+    ) else {
+        return Vec::new();
+    };
+    parse_rule_names(&out.stdout)
+}
+
+fn parse_rule_names(output: &str) -> Vec<String> {
+    let mut names = output
+        .lines()
+        // netsh localizes the "Rule Name" label; match on the value instead.
+        .filter_map(|line| line.split_once(':').map(|(_, value)| value.trim()))
+        .filter(|value| value.starts_with("OpenClaw Gateway"))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    names.dedup();
+    names
+}
+
+/// Shims in the npm global folder whose `node_modules\openclaw` package is gone.
+fn broken_npm_shims() -> Vec<PathBuf> {
+    let Some(npm_dir) = dirs::data_dir().map(|dir| dir.join("npm")) else {
+        return Vec::new();
+    };
+    if npm_dir.join("node_modules").join("openclaw").exists() {
+        return Vec::new();
+    }
+    NPM_SHIMS
+        .iter()
+        .map(|name| npm_dir.join(name))
+        .filter(|shim| shim.is_file())
+        .collect()
+}
+
+fn item(kind: LeftoverKind, target: String, reason: &str, needs_admin: bool) -> LeftoverItem {
+    LeftoverItem {
+        id: format!("{}:{target}", kind_key(&kind)),
+        kind,
+        target,
+        size_bytes: None,
+        reason: reason.to_string(),
+        needs_admin,
+    }
+}
+
+fn kind_key(kind: &LeftoverKind) -> &'static str {
+    match kind {
+        LeftoverKind::Directory => "directory",
+        LeftoverKind::ScheduledTask => "scheduled_task",
+        LeftoverKind::Service => "service",
+        LeftoverKind::RunKey => "run_key",
+        LeftoverKind::FirewallRule => "firewall_rule",
+        LeftoverKind::NpmLink => "npm_link",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_rule_names, parse_run_entries, program_of, scan_directories, stale_firewall_rules,
+    };
+
+    #[test]
+    fn firewall_rules_of_any_profile_port_are_kept() {
+        let rules = [28789, 28799, 30000]
+            .map(|port| format!("OpenClaw Gateway (TCP {port})"))
+            .to_vec();
+        assert_eq!(
+            stale_firewall_rules(rules.clone(), &[28789, 28799]),
+            ["OpenClaw Gateway (TCP 30000)"]
+        );
+        assert_eq!(stale_firewall_rules(rules, &[]).len(), 3);
+    }
+
+    #[test]
+    fn run_keys_and_firewall_rules_are_parsed() {
+        let reg = "\r\nHKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Run\r\n    OneDrive    REG_SZ    \"C:\\Program Files\\OneDrive\\OneDrive.exe\" /background\r\n    OpenClaw Tray    REG_EXPAND_SZ    C:\\Old\\openclaw-installer.exe --tray\r\n";
+        let entries = parse_run_entries(reg);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].0, "OpenClaw Tray");
+        assert_eq!(
+            program_of(&entries[0].1),
+            r"C:\Program Files\OneDrive\OneDrive.exe"
+        );
+        assert_eq!(program_of(&entries[1].1), r"C:\Old\openclaw-installer.exe");
+
+        let netsh = "\r\nRule Name:                            OpenClaw Gateway (TCP 28789)\r\n----\r\nEnabled:                              Yes\r\n\r\nRegelname:                            OpenClaw Gateway\r\nRule Name:                            Other app\r\n";
+        assert_eq!(
+            parse_rule_names(netsh),
+            vec!["OpenClaw Gateway (TCP 28789)", "OpenClaw Gateway"]
+        );
+    }
+
+    #[test]
+    fn package_in_a_folder_another_profile_installed_to_is_kept() {
+        let root = std::env::temp_dir().join(format!("oc-leftovers-{}", std::process::id()));
+        let default_dir = root.join("default");
+        let current = root.join("work");
+        let package = default_dir.join("node_modules").join("openclaw");
+        std::fs::create_dir_all(&package).expect("package dir");
+        std::fs::write(package.join("package.json"), r#"{"version":"2026.1.0"}"#)
+            .expect("package.json");
+
+        let mut items = Vec::new();
+        let in_use = [current.clone()];
+        scan_directories(Some(&current), &in_use, &default_dir, &mut items);
+        assert_eq!(items.len(), 1);
+        assert!(items[0].target.ends_with("node_modules"));
+
+        let mut items = Vec::new();
+        let in_use = [current.clone(), default_dir.clone()];
+        scan_directories(Some(&current), &in_use, &default_dir, &mut items);
+        assert!(items.is_empty());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
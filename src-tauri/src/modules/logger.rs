@@ -1,50 +1,263 @@
 use std::fs::{self, OpenOptions};
-use std::io::Write;
-use std::path::Path;
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
-use anyhow::Result;
-use chrono::Local;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, SecondsFormat, TimeZone};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
 
-use crate::models::LogSummary;
+use crate::models::{
+    GatewayLogSplit, LogEntry, LogPage, LogPurgeResult, LogQuery, LogRotationConfig, LogSummary,
+};
 
-use super::paths;
+use super::{paths, state_store};
+
+const DEFAULT_QUERY_LIMIT: usize = 400;
+const READ_BLOCK: u64 = 64 * 1024;
+// Stops a page at this size even without enough newlines (e.g. one huge minified line).
+const MAX_PAGE_BYTES: u64 = 8 * 1024 * 1024;
+/// The gateway's own output, appended to across runs until split by `prepare_gateway_logs`.
+pub const GATEWAY_LOGS: [&str; 2] = ["openclaw-stdout.log", "openclaw-stderr.log"];
+// Separator lines the installer writes into the gateway logs (run start/stop and the
+// begin/end of a correlated operation).
+const MARKER_PREFIX: &str = "===== openclaw";
 
 static LOG_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+static CORRELATION_ID: Mutex<Option<String>> = Mutex::new(None);
+static ROTATION: Lazy<Mutex<LogRotationConfig>> =
+    Lazy::new(|| Mutex::new(state_store::load_log_rotation().unwrap_or_default()));
 
+// `track_caller` lets every entry carry the calling module without touching call sites.
+#[track_caller]
 pub fn info(message: &str) {
-    let _ = write_line("INFO", message);
+    let _ = write_line("INFO", caller_module(), message);
 }
 
+#[track_caller]
 pub fn warn(message: &str) {
-    let _ = write_line("WARN", message);
+    let _ = write_line("WARN", caller_module(), message);
 }
 
+#[track_caller]
 pub fn error(message: &str) {
-    let _ = write_line("ERROR", message);
+    let _ = write_line("ERROR", caller_module(), message);
+}
+
+/// Tags every following entry with a fresh id until `end_run`, so one wizard run can
+/// be pulled out of the log with `query_logs`. The id also brackets the matching part
+/// of the gateway logs (see `gateway_segments`).
+pub fn begin_run() -> String {
+    let id = uuid::Uuid::new_v4().simple().to_string()[..12].to_string();
+    *CORRELATION_ID.lock().unwrap_or_else(|e| e.into_inner()) = Some(id.clone());
+    info(&format!("Run {id} started."));
+    append_gateway_marker(&format!(
+        "op begin {id} {}",
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    ));
+    id
+}
+
+pub fn end_run() {
+    let previous = current_correlation_id();
+    if let Some(id) = previous {
+        info(&format!("Run {id} finished."));
+        append_gateway_marker(&format!(
+            "op end {id} {}",
+            Local::now().format("%Y-%m-%d %H:%M:%S")
+        ));
+        *CORRELATION_ID.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+pub fn current_correlation_id() -> Option<String> {
+    CORRELATION_ID
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+#[track_caller]
+fn caller_module() -> &'static str {
+    let file = std::panic::Location::caller().file();
+    let name = file.rsplit(['/', '\\']).next().unwrap_or(file);
+    name.strip_suffix(".rs").unwrap_or(name)
 }
 
-fn write_line(level: &str, message: &str) -> Result<()> {
+fn write_line(level: &str, module: &str, message: &str) -> Result<()> {
     let _guard = LOG_LOCK
         .lock()
         .map_err(|_| anyhow::anyhow!("failed to lock logger"))?;
     paths::ensure_dirs()?;
     let log_file = paths::logs_dir().join(format!("{}.log", Local::now().format("%Y-%m-%d")));
+    // A failed rotation must not cost us the line itself.
+    let _ = rotate_with(&log_file, &rotation_config());
     let mut file = OpenOptions::new()
         .create(true)
         .append(true)
         .open(log_file)?;
-    let line = format!(
-        "{} [{}] {}\n",
-        Local::now().format("%Y-%m-%d %H:%M:%S"),
-        level,
-        message
-    );
+    let entry = LogEntry {
+        ts: Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+        level: level.to_string(),
+        module: module.to_string(),
+        correlation_id: current_correlation_id(),
+        message: message.to_string(),
+    };
+    let mut line = serde_json::to_string(&entry)?;
+    line.push('\n');
     file.write_all(line.as_bytes())?;
     Ok(())
 }
 
+/// Returns the newest installer log entries matching `query`, newest first.
+pub fn query_logs(query: &LogQuery) -> Result<Vec<LogEntry>> {
+    paths::ensure_dirs()?;
+    let since = query.since.as_deref().map(parse_bound).transpose()?;
+    let until = query.until.as_deref().map(parse_bound).transpose()?;
+    let min_level = query.level.as_deref().map(level_rank).unwrap_or(0);
+    let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT).max(1);
+
+    // Daily files (`2026-10-18.log`, rotations `2026-10-18.1.log[.gz]`), newest day first;
+    // rotations of the same day are older the higher their index.
+    let mut files = fs::read_dir(paths::logs_dir())?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            let day = NaiveDateTime::parse_from_str(
+                &format!("{} 00:00:00", name.get(..10)?),
+                "%Y-%m-%d %H:%M:%S",
+            )
+            .ok()?;
+            let rotation = name[10..]
+                .trim_start_matches('.')
+                .split('.')
+                .next()
+                .and_then(|part| part.parse::<u32>().ok())
+                .unwrap_or(0);
+            Some((day, rotation, path))
+        })
+        .collect::<Vec<_>>();
+    files.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    let mut out = Vec::new();
+    for (day, _, path) in files {
+        if let Some(until) = until {
+            if local_time(day).is_some_and(|start| start > until) {
+                continue;
+            }
+        }
+        if let Some(since) = since {
+            // Whole day ended before `since`; older days can only be older still.
+            if local_time(day + chrono::Duration::days(1)).is_some_and(|end| end < since) {
+                break;
+            }
+        }
+        let Ok(content) = read_text(&path) else {
+            continue;
+        };
+        for entry in content.lines().rev().filter_map(parse_entry) {
+            if !entry_matches(&entry, query, min_level, since, until) {
+                continue;
+            }
+            out.push(entry);
+            if out.len() >= limit {
+                return Ok(out);
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn entry_matches(
+    entry: &LogEntry,
+    query: &LogQuery,
+    min_level: u8,
+    since: Option<DateTime<FixedOffset>>,
+    until: Option<DateTime<FixedOffset>>,
+) -> bool {
+    if level_rank(&entry.level) < min_level {
+        return false;
+    }
+    if let Some(module) = query.module.as_deref().filter(|m| !m.trim().is_empty()) {
+        if !entry.module.eq_ignore_ascii_case(module.trim()) {
+            return false;
+        }
+    }
+    if let Some(id) = query.correlation_id.as_deref().filter(|id| !id.is_empty()) {
+        if entry.correlation_id.as_deref() != Some(id) {
+            return false;
+        }
+    }
+    if let Some(needle) = query.contains.as_deref().filter(|n| !n.is_empty()) {
+        if !entry
+            .message
+            .to_lowercase()
+            .contains(&needle.to_lowercase())
+        {
+            return false;
+        }
+    }
+    if since.is_some() || until.is_some() {
+        let Ok(ts) = DateTime::parse_from_rfc3339(&entry.ts) else {
+            return false;
+        };
+        if since.is_some_and(|since| ts < since) || until.is_some_and(|until| ts > until) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Parses a JSON entry, or a pre-structured `2026-01-02 10:00:00 [WARN] text` line.
+fn parse_entry(line: &str) -> Option<LogEntry> {
+    if line.starts_with('{') {
+        return serde_json::from_str(line).ok();
+    }
+    let (stamp, rest) = (line.get(..19)?, line.get(19..)?.trim_start());
+    let naive = NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d %H:%M:%S").ok()?;
+    let (level, message) = rest.strip_prefix('[')?.split_once("] ")?;
+    Some(LogEntry {
+        ts: local_time(naive)?.to_rfc3339_opts(SecondsFormat::Millis, false),
+        level: level.to_string(),
+        module: String::new(),
+        correlation_id: None,
+        message: message.to_string(),
+    })
+}
+
+fn parse_bound(raw: &str) -> Result<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(raw.trim())
+        .map_err(|err| anyhow!("'{raw}' is not an RFC 3339 timestamp: {err}"))
+}
+
+fn local_time(naive: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.fixed_offset())
+}
+
+fn level_rank(level: &str) -> u8 {
+    match level.trim().to_ascii_uppercase().as_str() {
+        "ERROR" => 2,
+        "WARN" | "WARNING" => 1,
+        _ => 0,
+    }
+}
+
+pub fn read_text(path: &Path) -> Result<String> {
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        let mut text = String::new();
+        GzDecoder::new(fs::File::open(path)?).read_to_string(&mut text)?;
+        return Ok(text);
+    }
+    Ok(fs::read_to_string(path)?)
+}
+
 pub fn list_logs() -> Result<Vec<LogSummary>> {
     paths::ensure_dirs()?;
     let mut out = Vec::new();
@@ -63,11 +276,18 @@ pub fn list_logs() -> Result<Vec<LogSummary>> {
                 dt.format("%Y-%m-%d %H:%M:%S").to_string()
             })
             .unwrap_or_else(|| "-".to_string());
+        let name = path
+            .file_name()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown.log".to_string());
         out.push(LogSummary {
-            name: path
-                .file_name()
-                .map(|v| v.to_string_lossy().to_string())
-                .unwrap_or_else(|| "unknown.log".to_string()),
+            kind: if is_gateway_log(&name) {
+                "gateway"
+            } else {
+                "installer"
+            }
+            .to_string(),
+            name,
             path: path.to_string_lossy().to_string(),
             size: metadata.len(),
             modified_at: modified,
@@ -78,21 +298,92 @@ pub fn list_logs() -> Result<Vec<LogSummary>> {
 }
 
 pub fn read_log(name: &str, max_lines: usize) -> Result<String> {
-    let path = paths::logs_dir().join(name);
+    let path = log_path(name)?;
     if !path.exists() {
         return Ok(String::new());
     }
-    let content = fs::read_to_string(path)?;
-    let lines: Vec<&str> = content.lines().collect();
-    if lines.len() <= max_lines {
-        return Ok(content);
+    Ok(read_log_page(name, None, max_lines)?.text)
+}
+
+/// Returns up to `max_lines` lines ending at byte `before` (default: end of file). Plain
+/// logs are read backwards in blocks, so a 500 MB gateway log costs only the lines shown.
+pub fn read_log_page(name: &str, before: Option<u64>, max_lines: usize) -> Result<LogPage> {
+    let path = log_path(name)?;
+    if !path.exists() {
+        anyhow::bail!("Log file not found: {}", path.to_string_lossy());
+    }
+    // Rotated .gz files are capped by the rotation size, so inflating them is bounded.
+    let (file_size, end, (start, bytes)) = if path.extension().is_some_and(|ext| ext == "gz") {
+        let mut data = Vec::new();
+        GzDecoder::new(fs::File::open(&path)?).read_to_end(&mut data)?;
+        let size = data.len() as u64;
+        let end = before.unwrap_or(size).min(size);
+        let page = lines_before(&mut Cursor::new(data), end, max_lines, READ_BLOCK)?;
+        (size, end, page)
+    } else {
+        let mut file = fs::File::open(&path)?;
+        let size = file.metadata()?.len();
+        let end = before.unwrap_or(size).min(size);
+        let page = lines_before(&mut file, end, max_lines, READ_BLOCK)?;
+        (size, end, page)
+    };
+    let text = String::from_utf8_lossy(&bytes);
+    Ok(LogPage {
+        name: name.to_string(),
+        text: text.trim_end_matches(['\r', '\n']).to_string(),
+        start_offset: start,
+        end_offset: end,
+        file_size,
+        has_more: start > 0,
+    })
+}
+
+/// Walks back from `end` one block at a time until `max_lines` whole lines are covered
+/// and returns where they start plus their bytes.
+fn lines_before<R: Read + Seek>(
+    reader: &mut R,
+    end: u64,
+    max_lines: usize,
+    block: u64,
+) -> Result<(u64, Vec<u8>)> {
+    let max_lines = max_lines.max(1);
+    let mut start = end;
+    let mut chunks = Vec::<Vec<u8>>::new();
+    let mut newlines = 0usize;
+    // `>` because the newline closing the last line does not begin another line.
+    while start > 0 && newlines <= max_lines && end - start < MAX_PAGE_BYTES {
+        let len = block.min(start);
+        start -= len;
+        reader.seek(SeekFrom::Start(start))?;
+        let mut chunk = vec![0u8; len as usize];
+        reader.read_exact(&mut chunk)?;
+        newlines += chunk.iter().filter(|b| **b == b'\n').count();
+        chunks.push(chunk);
     }
-    let start = lines.len().saturating_sub(max_lines);
-    Ok(lines[start..].join("\n"))
+    chunks.reverse();
+    let bytes = chunks.concat();
+    let body = bytes.strip_suffix(b"\n").unwrap_or(&bytes);
+    let cut = body
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, b)| **b == b'\n')
+        .nth(max_lines - 1)
+        .map(|(index, _)| index + 1)
+        .unwrap_or(0);
+    Ok((start + cut as u64, bytes[cut..].to_vec()))
+}
+
+/// Resolves a log by file name, refusing anything that would leave the logs folder.
+fn log_path(name: &str) -> Result<PathBuf> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        anyhow::bail!("Invalid log name: {name}");
+    }
+    Ok(paths::logs_dir().join(name))
 }
 
 pub fn export_log(name: &str, output: &Path) -> Result<String> {
-    let src = paths::logs_dir().join(name);
+    let src = log_path(name)?;
     if !src.exists() {
         anyhow::bail!("Log file not found: {}", src.to_string_lossy());
     }
@@ -107,3 +398,531 @@ pub fn logs_dir_path() -> Result<String> {
     paths::ensure_dirs()?;
     Ok(paths::logs_dir().to_string_lossy().to_string())
 }
+
+pub fn get_log_rotation() -> LogRotationConfig {
+    rotation_config()
+}
+
+pub fn set_log_rotation(cfg: &LogRotationConfig) -> Result<LogRotationConfig> {
+    if cfg.max_file_mb == 0 || cfg.max_files == 0 {
+        anyhow::bail!("Log size and retained file count must both be at least 1.");
+    }
+    state_store::save_log_rotation(cfg)?;
+    if let Ok(mut current) = ROTATION.lock() {
+        *current = cfg.clone();
+    }
+    info(&format!(
+        "Log rotation set: {} MB per file, {} rotated file(s), compress={}, gateway split {:?}, gateway retention {} day(s).",
+        cfg.max_file_mb, cfg.max_files, cfg.compress, cfg.gateway_split, cfg.gateway_retention_days
+    ));
+    Ok(cfg.clone())
+}
+
+/// Rotates `path` when it has outgrown the configured size. Gateway logs are held open
+/// by the child process; use `rotate_live_gateway_logs` for those while it runs.
+pub fn rotate_if_oversized(path: &Path) -> Result<()> {
+    rotate_with(path, &rotation_config())
+}
+
+/// Size rotation for gateway logs the running gateway still writes to. The gateway's
+/// handle cannot be moved to a new file, so the content is copied to the rotation and
+/// the live file truncated; the gateway appends, so it carries on at the new end.
+pub fn rotate_live_gateway_logs() {
+    let cfg = rotation_config();
+    let dir = paths::logs_dir();
+    for name in GATEWAY_LOGS {
+        if let Err(err) = rotate_live(&dir.join(name), &cfg) {
+            warn(&format!(
+                "Failed to rotate {name} while the gateway runs: {err}"
+            ));
+        }
+    }
+}
+
+/// Splits the gateway logs before a new run according to `gateway_split`, applies the
+/// size rotation and drops archives past `gateway_retention_days`.
+pub fn prepare_gateway_logs() {
+    let cfg = rotation_config();
+    let dir = paths::logs_dir();
+    for name in GATEWAY_LOGS {
+        let path = dir.join(name);
+        let result =
+            archive_gateway_log(&path, &cfg, Local::now()).and_then(|_| rotate_with(&path, &cfg));
+        if let Err(err) = result {
+            warn(&format!("Failed to rotate {name}: {err}"));
+        }
+    }
+    if cfg.gateway_retention_days > 0 {
+        let cutoff = SystemTime::now()
+            .checked_sub(Duration::from_secs(
+                u64::from(cfg.gateway_retention_days) * 86_400,
+            ))
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        purge_gateway_archives(&dir, cutoff);
+    }
+}
+
+/// Appends a separator such as `===== openclaw run start ... pid=42 version=... =====`
+/// to both gateway logs, with the current correlation id when one is active.
+pub fn mark_gateway_run(event: &str, pid: u32, version: &str) {
+    let version = if version.trim().is_empty() {
+        "unknown"
+    } else {
+        version.trim()
+    };
+    let op = current_correlation_id()
+        .map(|id| format!(" op={id}"))
+        .unwrap_or_default();
+    append_gateway_marker(&format!(
+        "run {event} {} pid={pid} version={version}{op}",
+        Local::now().format("%Y-%m-%d %H:%M:%S")
+    ));
+}
+
+fn append_gateway_marker(text: &str) {
+    let line = format!("{MARKER_PREFIX} {text} =====\n");
+    for name in GATEWAY_LOGS {
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(paths::logs_dir().join(name))
+            .and_then(|mut file| file.write_all(line.as_bytes()));
+        if let Err(err) = result {
+            warn(&format!("Failed to mark {name}: {err}"));
+        }
+    }
+}
+
+pub fn is_gateway_marker(line: &str) -> bool {
+    line.starts_with(MARKER_PREFIX)
+}
+
+/// The parts of each gateway log (archives included, oldest first) written between the
+/// begin and end markers of operation `id`, as `(log name, text)`.
+pub fn gateway_segments(id: &str) -> Vec<(String, String)> {
+    let Ok(entries) = fs::read_dir(paths::logs_dir()) else {
+        return vec![];
+    };
+    let mut logs = entries
+        .flatten()
+        .filter(|entry| is_gateway_log(&entry.file_name().to_string_lossy()))
+        .map(|entry| {
+            let modified = entry
+                .metadata()
+                .and_then(|meta| meta.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            (modified, entry.path())
+        })
+        .collect::<Vec<_>>();
+    logs.sort();
+    logs.into_iter()
+        .filter_map(|(_, path)| {
+            let segment = slice_segment(&read_text(&path).ok()?, id)?;
+            let name = path.file_name()?.to_string_lossy().to_string();
+            Some((name, segment))
+        })
+        .collect()
+}
+
+/// Lines from the `op begin <id>` marker through `op end <id>`, or to the end of the
+/// text when the operation never finished (e.g. the installer crashed).
+fn slice_segment(text: &str, id: &str) -> Option<String> {
+    let begin = format!("{MARKER_PREFIX} op begin {id} ");
+    let end = format!("{MARKER_PREFIX} op end {id} ");
+    let mut lines = text.lines().skip_while(|line| !line.starts_with(&begin));
+    let mut out = vec![lines.next()?];
+    for line in lines {
+        out.push(line);
+        if line.starts_with(&end) {
+            break;
+        }
+    }
+    Some(out.join("\n"))
+}
+
+fn is_gateway_log(name: &str) -> bool {
+    GATEWAY_LOGS.iter().any(|log| {
+        let stem = log.trim_end_matches(".log");
+        name == *log || name.starts_with(&format!("{stem}."))
+    })
+}
+
+/// Moves a gateway log that belongs to an earlier day (or, per run, any earlier run) to
+/// a name carrying the time it was last written.
+fn archive_gateway_log(
+    path: &Path,
+    cfg: &LogRotationConfig,
+    now: chrono::DateTime<Local>,
+) -> Result<()> {
+    let Ok(metadata) = fs::metadata(path) else {
+        return Ok(());
+    };
+    if metadata.len() == 0 {
+        return Ok(());
+    }
+    let last_written: chrono::DateTime<Local> = metadata.modified()?.into();
+    let stamp = match cfg.gateway_split {
+        GatewayLogSplit::Size => return Ok(()),
+        GatewayLogSplit::Daily if last_written.date_naive() >= now.date_naive() => return Ok(()),
+        GatewayLogSplit::Daily => last_written.format("%Y-%m-%d").to_string(),
+        GatewayLogSplit::PerRun => last_written.format("%Y-%m-%d_%H%M%S").to_string(),
+    };
+    let stem = path
+        .file_stem()
+        .map(|value| value.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut target = path.with_file_name(format!("{stem}.{stamp}.log"));
+    let mut n = 2;
+    while target.exists() || Path::new(&format!("{}.gz", target.to_string_lossy())).exists() {
+        target = path.with_file_name(format!("{stem}.{stamp}-{n}.log"));
+        n += 1;
+    }
+    fs::rename(path, &target)?;
+    if cfg.compress {
+        gzip_in_place(&target)?;
+    }
+    Ok(())
+}
+
+fn purge_gateway_archives(dir: &Path, cutoff: SystemTime) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if GATEWAY_LOGS.contains(&name.as_str()) || !is_gateway_log(&name) {
+            continue;
+        }
+        let stale = entry
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .is_ok_and(|modified| modified < cutoff);
+        if stale {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Deletes log files (rotated or not) last written more than `older_than_days` ago.
+pub fn purge_logs(older_than_days: u32) -> Result<LogPurgeResult> {
+    if older_than_days == 0 {
+        anyhow::bail!("Choose an age of at least one day; today's logs are still in use.");
+    }
+    paths::ensure_dirs()?;
+    let cutoff = SystemTime::now()
+        .checked_sub(Duration::from_secs(u64::from(older_than_days) * 86_400))
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    let mut result = LogPurgeResult {
+        removed: Vec::new(),
+        freed_bytes: 0,
+    };
+    for entry in fs::read_dir(paths::logs_dir())? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let stale = metadata
+            .modified()
+            .map(|modified| modified < cutoff)
+            .unwrap_or(false);
+        if !metadata.is_file() || !stale {
+            continue;
+        }
+        // A log still held open by a running process cannot be deleted on Windows; skip it.
+        if fs::remove_file(entry.path()).is_ok() {
+            result.freed_bytes += metadata.len();
+            result
+                .removed
+                .push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    info(&format!(
+        "Purged {} log file(s) older than {older_than_days} day(s), {} bytes freed.",
+        result.removed.len(),
+        result.freed_bytes
+    ));
+    Ok(result)
+}
+
+/// Re-reads the rotation settings, e.g. after the active profile changed.
+pub fn reload_log_rotation() {
+    if let Ok(mut current) = ROTATION.lock() {
+        *current = state_store::load_log_rotation().unwrap_or_default();
+    }
+}
+
+fn rotation_config() -> LogRotationConfig {
+    ROTATION.lock().map(|cfg| cfg.clone()).unwrap_or_default()
+}
+
+fn rotate_with(path: &Path, cfg: &LogRotationConfig) -> Result<()> {
+    if !oversized(path, cfg) {
+        return Ok(());
+    }
+    shift_rotations(path, cfg)?;
+    let first = rotated_path(path, 1, false);
+    fs::rename(path, &first)?;
+    if cfg.compress {
+        gzip_in_place(&first)?;
+    }
+    Ok(())
+}
+
+fn rotate_live(path: &Path, cfg: &LogRotationConfig) -> Result<()> {
+    if !oversized(path, cfg) {
+        return Ok(());
+    }
+    shift_rotations(path, cfg)?;
+    let first = rotated_path(path, 1, false);
+    fs::copy(path, &first)?;
+    OpenOptions::new().write(true).open(path)?.set_len(0)?;
+    if cfg.compress {
+        gzip_in_place(&first)?;
+    }
+    Ok(())
+}
+
+fn oversized(path: &Path, cfg: &LogRotationConfig) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| {
+        metadata.len() > 0 && metadata.len() >= cfg.max_file_mb.saturating_mul(1024 * 1024)
+    })
+}
+
+/// Drops the oldest rotation and moves the others up one index, freeing index 1.
+fn shift_rotations(path: &Path, cfg: &LogRotationConfig) -> Result<()> {
+    let keep = cfg.max_files.max(1);
+    for compressed in [false, true] {
+        let oldest = rotated_path(path, keep, compressed);
+        if oldest.exists() {
+            fs::remove_file(oldest)?;
+        }
+    }
+    for index in (1..keep).rev() {
+        for compressed in [false, true] {
+            let from = rotated_path(path, index, compressed);
+            if from.exists() {
+                fs::rename(&from, rotated_path(path, index + 1, compressed))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `openclaw-stdout.log` -> `openclaw-stdout.<index>.log[.gz]`.
+fn rotated_path(path: &Path, index: u32, compressed: bool) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|value| value.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|value| value.to_string_lossy().to_string())
+        .unwrap_or_else(|| "log".to_string());
+    let suffix = if compressed { ".gz" } else { "" };
+    path.with_file_name(format!("{stem}.{index}.{ext}{suffix}"))
+}
+
+fn gzip_in_place(path: &Path) -> Result<()> {
+    let mut target = path.as_os_str().to_owned();
+    target.push(".gz");
+    let mut input = fs::File::open(path)?;
+    let mut encoder = GzEncoder::new(fs::File::create(&target)?, Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?;
+    drop(input);
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        archive_gateway_log, entry_matches, is_gateway_log, level_rank, lines_before, parse_entry,
+        rotate_live, rotate_with, rotated_path, slice_segment,
+    };
+    use crate::models::{GatewayLogSplit, LogQuery, LogRotationConfig};
+    use std::fs;
+    use std::io::Cursor;
+
+    #[test]
+    fn reverse_reads_page_through_whole_lines() {
+        let text = (1..=10).map(|n| format!("line {n}\n")).collect::<String>();
+        let size = text.len() as u64;
+        let mut reader = Cursor::new(text.into_bytes());
+
+        let (start, bytes) = lines_before(&mut reader, size, 3, 7).expect("last page");
+        assert_eq!(
+            String::from_utf8(bytes).expect("utf8"),
+            "line 8\nline 9\nline 10\n"
+        );
+        let (older, bytes) = lines_before(&mut reader, start, 3, 7).expect("older page");
+        assert_eq!(
+            String::from_utf8(bytes).expect("utf8"),
+            "line 5\nline 6\nline 7\n"
+        );
+        let (first, bytes) = lines_before(&mut reader, older, 50, 7).expect("rest");
+        assert_eq!(first, 0);
+        assert!(String::from_utf8(bytes)
+            .expect("utf8")
+            .starts_with("line 1\n"));
+    }
+
+    #[test]
+    fn structured_and_legacy_lines_are_queryable() {
+        let json = r#"{"ts":"2026-10-18T09:30:00.000+08:00","level":"WARN","module":"process","correlation_id":"abc123","message":"Auto-start OpenClaw failed"}"#;
+        let entry = parse_entry(json).expect("json entry");
+        assert_eq!(entry.module, "process");
+
+        let legacy = parse_entry("2026-01-02 10:00:00 [ERROR] Install failed").expect("legacy");
+        assert_eq!(legacy.level, "ERROR");
+        assert_eq!(legacy.message, "Install failed");
+        assert!(legacy.module.is_empty());
+        assert!(parse_entry("not a log line").is_none());
+
+        let query = LogQuery {
+            level: Some("warn".to_string()),
+            module: Some("PROCESS".to_string()),
+            correlation_id: Some("abc123".to_string()),
+            contains: Some("auto-start".to_string()),
+            ..LogQuery::default()
+        };
+        let min = level_rank("warn");
+        assert!(entry_matches(&entry, &query, min, None, None));
+        let since = chrono::DateTime::parse_from_rfc3339("2026-10-18T10:00:00+08:00").ok();
+        assert!(!entry_matches(&entry, &query, min, since, None));
+        let errors_only = LogQuery {
+            level: Some("error".to_string()),
+            ..LogQuery::default()
+        };
+        assert!(!entry_matches(
+            &entry,
+            &errors_only,
+            level_rank("error"),
+            None,
+            None
+        ));
+    }
+
+    #[test]
+    fn oversized_logs_rotate_and_old_rotations_are_dropped() {
+        let dir = std::env::temp_dir().join(format!("oc-log-rotate-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("dir");
+        let log = dir.join("openclaw-stdout.log");
+        let cfg = LogRotationConfig {
+            max_file_mb: 0,
+            max_files: 2,
+            compress: false,
+            ..LogRotationConfig::default()
+        };
+        for round in 1..=3 {
+            fs::write(&log, format!("round {round}\n")).expect("write");
+            rotate_with(&log, &cfg).expect("rotate");
+        }
+        assert!(!log.exists());
+        assert_eq!(
+            fs::read_to_string(rotated_path(&log, 1, false)).expect("first"),
+            "round 3\n"
+        );
+        assert_eq!(
+            fs::read_to_string(rotated_path(&log, 2, false)).expect("second"),
+            "round 2\n"
+        );
+        assert!(!rotated_path(&log, 3, false).exists());
+
+        fs::write(&log, "compressed\n").expect("write");
+        let gz = LogRotationConfig {
+            compress: true,
+            ..cfg
+        };
+        rotate_with(&log, &gz).expect("rotate");
+        assert!(rotated_path(&log, 1, true).exists());
+        assert!(!rotated_path(&log, 1, false).exists());
+        assert!(rotated_path(&log, 2, false).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn live_logs_are_copied_and_truncated_in_place() {
+        let dir = std::env::temp_dir().join(format!("oc-log-live-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("dir");
+        let log = dir.join("openclaw-stdout.log");
+        let cfg = LogRotationConfig {
+            max_file_mb: 0,
+            max_files: 2,
+            compress: false,
+            ..LogRotationConfig::default()
+        };
+        // The handle a running gateway would hold, opened for appending.
+        let mut writer = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log)
+            .expect("open");
+        std::io::Write::write_all(&mut writer, b"before rotation\n").expect("write");
+        rotate_live(&log, &cfg).expect("rotate");
+        assert_eq!(fs::metadata(&log).expect("live file").len(), 0);
+        assert_eq!(
+            fs::read_to_string(rotated_path(&log, 1, false)).expect("rotation"),
+            "before rotation\n"
+        );
+        std::io::Write::write_all(&mut writer, b"after\n").expect("write");
+        assert_eq!(fs::read_to_string(&log).expect("live"), "after\n");
+        drop(writer);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn gateway_logs_are_archived_by_day_or_run() {
+        let dir = std::env::temp_dir().join(format!("oc-gw-logs-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).expect("dir");
+        let log = dir.join("openclaw-stderr.log");
+        let now = chrono::Local::now();
+        let yesterday = now - chrono::Duration::days(1);
+        let cfg = LogRotationConfig {
+            compress: false,
+            ..LogRotationConfig::default()
+        };
+
+        fs::write(&log, "today\n").expect("write");
+        archive_gateway_log(&log, &cfg, now).expect("daily");
+        assert!(log.exists(), "a log written today stays in place");
+
+        fs::File::options()
+            .append(true)
+            .open(&log)
+            .and_then(|file| file.set_modified(yesterday.into()))
+            .expect("backdate");
+        archive_gateway_log(&log, &cfg, now).expect("daily");
+        let dated = dir.join(format!(
+            "openclaw-stderr.{}.log",
+            yesterday.format("%Y-%m-%d")
+        ));
+        assert!(!log.exists());
+        assert_eq!(fs::read_to_string(&dated).expect("archive"), "today\n");
+
+        fs::write(&log, "run\n").expect("write");
+        let per_run = LogRotationConfig {
+            gateway_split: GatewayLogSplit::PerRun,
+            ..cfg.clone()
+        };
+        archive_gateway_log(&log, &per_run, now).expect("per run");
+        assert!(!log.exists());
+        assert_eq!(fs::read_dir(&dir).expect("list").count(), 2);
+
+        assert!(is_gateway_log("openclaw-stdout.2026-10-17.log.gz"));
+        assert!(!is_gateway_log("installer.log"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn segments_are_cut_at_the_operation_markers() {
+        let text = "boot\n===== openclaw op begin abc =====\nlistening\n===== openclaw op begin zzz =====\nerror: 1006\n===== openclaw op end abc =====\nlater\n";
+        assert_eq!(
+            slice_segment(text, "abc").as_deref(),
+            Some("===== openclaw op begin abc =====\nlistening\n===== openclaw op begin zzz =====\nerror: 1006\n===== openclaw op end abc =====")
+        );
+        assert_eq!(
+            slice_segment(text, "zzz").as_deref(),
+            Some("===== openclaw op begin zzz =====\nerror: 1006\n===== openclaw op end abc =====\nlater")
+        );
+        assert_eq!(slice_segment(text, "ab"), None);
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use serde_json::Value;
+
+use crate::models::{MigrationRecord, MigrationResult, OpenClawConfigInput, ResultWarning};
+
+use super::{
+    backup, dotenv::EnvFile, install_move, json_layout, logger, paths, port, process, secrets,
+    state_store,
+};
+
+/// Config file names used by the CLI under its earlier names.
+const CONFIG_FILE_NAMES: &[&str] = &["openclaw.json", "clawdbot.json"];
+/// Data folders carried over, relative to the legacy home.
+const DATA_DIRS: &[&str] = &["credentials", "sessions", "memory", "agents", "workspace"];
+
+/// First legacy home under the user profile that holds a config, if any.
+pub fn detect_legacy_home() -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    [".openclaw", ".clawdbot"]
+        .iter()
+        .map(|name| home.join(name))
+        .find(|dir| legacy_config_file(dir).is_some())
+}
+
+/// Copies a legacy `~/.openclaw` (or `.clawdbot`) setup into the installer-managed home.
+/// The legacy folder is only read; an existing installer config is replaced only with
+/// `overwrite` and is snapshotted first.
+pub fn migrate_existing_install(
+    source_dir: Option<&str>,
+    overwrite: bool,
+) -> Result<MigrationResult> {
+    let source = match source_dir.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(raw) => paths::normalize_path(raw)?,
+        None => detect_legacy_home().ok_or_else(|| {
+            anyhow!("No existing OpenClaw installation found in the user profile.")
+        })?,
+    };
+    let source_config = legacy_config_file(&source).ok_or_else(|| {
+        anyhow!(
+            "{} does not contain an OpenClaw config.",
+            source.to_string_lossy()
+        )
+    })?;
+    let target = paths::openclaw_home();
+    if paths::is_user_profile_default_openclaw_dir(&target) {
+        return Err(anyhow!(
+            "The installer home points at the legacy folder; nothing to migrate."
+        ));
+    }
+    if same_dir(&source, &target) {
+        return Err(anyhow!("Source and installer home are the same folder."));
+    }
+
+    let target_config = target.join("openclaw.json");
+    let mut backup_id = None;
+    if target_config.exists() {
+        if !overwrite {
+            return Err(anyhow!(
+                "The installer home already has a config. Confirm overwrite to replace it with the existing installation."
+            ));
+        }
+        backup_id = Some(backup::backup_with_prefix("pre-migrate")?.id);
+    }
+
+    let was_running = process::running_pid().is_some();
+    if was_running {
+        process::stop()?;
+    }
+
+    let mut warnings = Vec::<ResultWarning>::new();
+    let mut items = Vec::<String>::new();
+    fs::create_dir_all(&target)?;
+
+    let source_text = source.to_string_lossy().to_string();
+    let target_text = target.to_string_lossy().to_string();
+    let mut json: Value = serde_json::from_str(&fs::read_to_string(&source_config)?)?;
+    install_move::rebase_json_strings(&mut json, &source_text, &target_text);
+    let new_port = installer_port();
+    let old_port = remap_port(&mut json, new_port);
+    json_layout::write_preserving_layout(&target_config, &json)?;
+    items.push("openclaw.json".to_string());
+
+    let env_keys = merge_env(&source.join(".env"), &target.join(".env"), overwrite)?;
+    if !env_keys.is_empty() {
+        items.push(".env".to_string());
+    }
+
+    for name in DATA_DIRS {
+        let from = source.join(name);
+        if !from.is_dir() {
+            continue;
+        }
+        match backup::copy_dir_overwrite(&from, &target.join(name)) {
+            Ok(()) => items.push(name.to_string()),
+            Err(err) => warnings.push(
+                ResultWarning::warning(
+                    "migration_copy_failed",
+                    format!("{name} was not copied: {err}"),
+                )
+                .with_remediation("Close OpenClaw in the old folder and run the import again."),
+            ),
+        }
+    }
+
+    if old_port.is_some_and(|port| port != new_port) {
+        if let Ok(status) = port::check_port(new_port) {
+            if status.in_use {
+                warnings.push(
+                    ResultWarning::warning(
+                        "migration_port_in_use",
+                        format!("Port {new_port} is already in use."),
+                    )
+                    .with_remediation("Change the gateway port from Maintenance before starting."),
+                );
+            }
+        }
+    }
+
+    let record = MigrationRecord {
+        source_dir: source_text,
+        target_dir: target_text,
+        migrated_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        items,
+        env_keys,
+        old_port,
+        new_port,
+    };
+    state_store::save_migration_record(&record)?;
+
+    if was_running {
+        if let Err(err) = process::start() {
+            warnings.push(ResultWarning::error(
+                "gateway_restart_failed",
+                format!("Gateway did not start after the import: {err}"),
+            ));
+        }
+    }
+    logger::info(&format!(
+        "Migrated existing install {} -> {} ({} item(s), port {:?} -> {}).",
+        record.source_dir,
+        record.target_dir,
+        record.items.len(),
+        record.old_port,
+        record.new_port
+    ));
+    Ok(MigrationResult {
+        record,
+        backup_id,
+        warnings,
+    })
+}
+
+fn legacy_config_file(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+fn installer_port() -> u16 {
+    state_store::load_last_config()
+        .ok()
+        .flatten()
+        .map(|cfg| cfg.port)
+        .unwrap_or_else(|| OpenClawConfigInput::default().port)
+}
+
+/// Points `gateway.port` at the installer port so the migrated gateway never collides
+/// with a legacy one that may still be running. Returns the port it replaced.
+fn remap_port(json: &mut Value, new_port: u16) -> Option<u16> {
+    let old_port = json
+        .pointer("/gateway/port")
+        .and_then(Value::as_u64)
+        .and_then(|port| u16::try_from(port).ok());
+    let root = json.as_object_mut()?;
+    let gateway = root
+        .entry("gateway")
+        .or_insert_with(|| Value::Object(Default::default()));
+    if let Some(gateway) = gateway.as_object_mut() {
+        gateway.insert("port".to_string(), Value::from(new_port));
+    }
+    old_port
+}
+
+/// Imports legacy `.env` keys. Keys the installer home already has are kept unless
+/// `overwrite` is set. Credentials are sealed like the ones setup writes. Returns the
+/// imported key names.
+fn merge_env(source: &Path, target: &Path, overwrite: bool) -> Result<Vec<String>> {
+    let from = EnvFile::load(source)?;
+    let mut into = EnvFile::load(target)?;
+    let mut imported = Vec::<String>::new();
+    let mut credentials = BTreeMap::new();
+    for key in from.keys() {
+        let Some(value) = from.get(key) else {
+            continue;
+        };
+        if !overwrite && into.get(key).is_some() {
+            continue;
+        }
+        if secrets::is_credential_name(key) {
+            credentials.insert(key.to_string(), value.to_string());
+        } else {
+            into.set(key, value);
+        }
+        imported.push(key.to_string());
+    }
+    secrets::seal_env_values(&mut credentials);
+    for (key, value) in &credentials {
+        into.set(key, value);
+    }
+    if !imported.is_empty() {
+        into.save(target)?;
+    }
+    Ok(imported)
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    let key = |p: &Path| {
+        p.to_string_lossy()
+            .replace('/', "\\")
+            .trim_end_matches('\\')
+            .to_ascii_lowercase()
+    };
+    key(a) == key(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{merge_env, remap_port};
+    use serde_json::json;
+
+    #[test]
+    fn remap_port_replaces_the_legacy_gateway_port() {
+        let mut cfg = json!({ "gateway": { "port": 18789, "bind": "loopback" } });
+        assert_eq!(remap_port(&mut cfg, 28789), Some(18789));
+        assert_eq!(cfg["gateway"]["port"], 28789);
+        assert_eq!(cfg["gateway"]["bind"], "loopback");
+
+        let mut bare = json!({});
+        assert_eq!(remap_port(&mut bare, 28789), None);
+        assert_eq!(bare["gateway"]["port"], 28789);
+    }
+
+    #[test]
+    fn merge_env_keeps_existing_keys_unless_overwriting() {
+        let dir = std::env::temp_dir().join(format!("oc-migrate-env-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("dir");
+        let source = dir.join("legacy.env");
+        let target = dir.join("target.env");
+        std::fs::write(&source, "OPENAI_API_KEY=sk-old\nMOONSHOT_API_KEY=sk-moon\n").expect("src");
+        std::fs::write(&target, "OPENAI_API_KEY=sk-new\n").expect("dst");
+
+        let imported = merge_env(&source, &target, false).expect("merge");
+        assert_eq!(imported, vec!["MOONSHOT_API_KEY"]);
+        let merged = std::fs::read_to_string(&target).expect("read");
+        assert!(merged.contains("OPENAI_API_KEY=sk-new"));
+        assert!(merged.contains("MOONSHOT_API_KEY=sk-moon"));
+
+        let imported = merge_env(&source, &target, true).expect("overwrite");
+        assert_eq!(imported.len(), 2);
+        assert!(std::fs::read_to_string(&target)
+            .expect("read")
+            .contains("OPENAI_API_KEY=sk-old"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,18 +1,74 @@
+pub mod app_lock;
+pub mod app_settings;
 pub mod backup;
+pub mod bedrock;
 pub mod browser;
+pub mod channel_message;
+pub mod channel_stats;
+pub mod channels;
+pub mod cli_shim;
 pub mod config;
+pub mod config_watch;
+pub mod consent;
+pub mod custom_provider;
+pub mod daemon;
 pub mod donate;
+pub mod dotenv;
 pub mod env;
+pub mod env_diff;
+pub mod feishu;
+pub mod file_audit;
+pub mod firewall;
+pub mod fs_walk;
+pub mod gateway_port;
+pub mod gateway_service;
 pub mod health;
+pub mod install_move;
+pub mod install_progress;
 pub mod installer;
+pub mod isolation;
+pub mod json_layout;
+pub mod keep_alive;
+pub mod leftovers;
 pub mod logger;
+pub mod migration;
 pub mod model_catalog;
 pub mod model_identity;
+pub mod model_presets;
+pub mod network_wait;
+pub mod offline_bundle;
 pub mod paths;
 pub mod port;
 pub mod process;
+pub mod process_tree;
+pub mod profiles;
+pub mod provider_check;
+pub mod provider_signup;
+pub mod quiet_hours;
+pub mod read_only;
+pub mod reconfigure;
+pub mod sbom;
+pub mod script_scan;
+pub mod secret_reveal;
+pub mod secrets;
 pub mod security;
+pub mod setup_presets;
 pub mod shell;
+pub mod shortcuts;
 pub mod skills;
+pub mod startup;
 pub mod state_store;
+pub mod status_api;
+pub mod status_stream;
+pub mod support_bundle;
+pub mod support_share;
+pub mod telemetry;
+pub mod temp_files;
+pub mod tls;
+pub mod tool_policy;
+pub mod tunnel;
 pub mod upgrade;
+pub mod uptime;
+pub mod warm_up;
+pub mod watchdog;
+pub mod workspace_git;
@@ -6,12 +6,13 @@ use anyhow::{anyhow, Result};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use serde_json::{Deserializer, Value};
+use sha2::{Digest, Sha256};
 use std::sync::{mpsc, Mutex};
 use std::thread;
 
-use crate::models::ModelCatalogItem;
+use crate::models::{ModelCatalogItem, ModelCatalogPage};
 
-use super::{logger, model_identity, paths, shell, state_store};
+use super::{cli_shim, fs_walk, logger, model_identity, paths, shell, state_store};
 
 #[derive(Debug, Deserialize)]
 struct ModelsListPayload {
@@ -28,11 +29,110 @@ struct ModelsListEntry {
     available: Option<bool>,
     #[serde(default)]
     missing: bool,
+    #[serde(default, rename = "contextWindow", alias = "context_window")]
+    context_window: Option<u64>,
+    /// Accepted input, `"text+image"` or `["text", "image"]` depending on the CLI version.
+    #[serde(default)]
+    input: Option<Value>,
+    #[serde(default)]
+    cost: Option<ModelsListCost>,
 }
 
+/// USD per million tokens, as in OpenClaw model definitions.
+#[derive(Debug, Deserialize)]
+struct ModelsListCost {
+    #[serde(default)]
+    input: Option<f64>,
+    #[serde(default)]
+    output: Option<f64>,
+}
+
+/// Bundled metadata for well-known models, used where the CLI does not report it:
+/// `(key, context window, input USD/Mtok, output USD/Mtok, modalities)`. Prices are
+/// list prices at the time of the table's last update and only meant for comparison.
+type ModelMetadata = (
+    &'static str,
+    u64,
+    Option<f64>,
+    Option<f64>,
+    &'static [&'static str],
+);
+
+const TEXT: &[&str] = &["text"];
+const TEXT_IMAGE: &[&str] = &["text", "image"];
+const MULTIMODAL: &[&str] = &["text", "image", "audio", "video"];
+
+const MODEL_METADATA: &[ModelMetadata] = &[
+    ("openai/gpt-5.2", 400_000, None, None, TEXT_IMAGE),
+    (
+        "openai/gpt-4.1",
+        1_047_576,
+        Some(2.0),
+        Some(8.0),
+        TEXT_IMAGE,
+    ),
+    ("openai/o3", 200_000, Some(2.0), Some(8.0), TEXT_IMAGE),
+    ("openai/o4-mini", 200_000, Some(1.1), Some(4.4), TEXT_IMAGE),
+    (
+        "anthropic/claude-opus-4-6",
+        200_000,
+        Some(5.0),
+        Some(25.0),
+        TEXT_IMAGE,
+    ),
+    (
+        "anthropic/claude-sonnet-4-5",
+        200_000,
+        Some(3.0),
+        Some(15.0),
+        TEXT_IMAGE,
+    ),
+    (
+        "anthropic/claude-3-7-sonnet-latest",
+        200_000,
+        Some(3.0),
+        Some(15.0),
+        TEXT_IMAGE,
+    ),
+    (
+        "google/gemini-2.5-pro",
+        1_048_576,
+        Some(1.25),
+        Some(10.0),
+        MULTIMODAL,
+    ),
+    (
+        "google/gemini-2.5-flash",
+        1_048_576,
+        Some(0.3),
+        Some(2.5),
+        MULTIMODAL,
+    ),
+    (
+        "google/gemini-2.0-flash",
+        1_048_576,
+        Some(0.1),
+        Some(0.4),
+        MULTIMODAL,
+    ),
+    ("kimi-coding/k2p5", 262_144, None, None, TEXT_IMAGE),
+    ("kimi-coding/kimi-k2-thinking", 262_144, None, None, TEXT),
+    ("moonshot/kimi-k2.5", 262_144, None, None, TEXT_IMAGE),
+    ("xai/grok-4", 256_000, Some(3.0), Some(15.0), TEXT_IMAGE),
+    ("xai/grok-3", 131_072, Some(3.0), Some(15.0), TEXT),
+    ("zai/glm-4.5", 131_072, Some(0.6), Some(2.2), TEXT),
+    ("zai/glm-4.5-air", 131_072, Some(0.2), Some(1.1), TEXT),
+    ("minimax/MiniMax-M2", 204_800, Some(0.3), Some(1.2), TEXT),
+    // Azure deployments named after the model, as the portal suggests.
+    ("azure/gpt-4.1", 1_047_576, Some(2.0), Some(8.0), TEXT_IMAGE),
+    ("azure/gpt-4o", 128_000, Some(2.5), Some(10.0), TEXT_IMAGE),
+    ("azure/o4-mini", 200_000, Some(1.1), Some(4.4), TEXT_IMAGE),
+];
+
 const MODEL_CATALOG_CACHE_TTL: Duration = Duration::from_secs(45);
 const MODEL_CATALOG_DISK_CACHE_TTL: Duration = Duration::from_secs(60 * 60 * 24 * 7);
 const MODEL_CATALOG_MIN_ACCEPTABLE_COUNT: usize = 50;
+const MODEL_CATALOG_MAX_PAGE: usize = 500;
 // First load can be slow on Windows when OpenClaw CLI needs to initialize
 // (or when `npx` needs to warm up). Keep a generous timeout so the UI can
 // show a loader instead of permanently falling back to a tiny built-in list.
@@ -103,6 +203,86 @@ pub fn list_model_catalog() -> Result<Vec<ModelCatalogItem>> {
     Ok(merged)
 }
 
+/// Same catalog as `list_model_catalog`, one page at a time. `filter` matches key,
+/// name or provider, case-insensitively; `modality` (`image`, `audio`, ...) keeps models
+/// known to accept it. `sort` is `price` (cheapest input first) or `context` (largest
+/// first); models without the figure go last. Otherwise items stay in key order.
+pub fn get_model_catalog_page(
+    offset: usize,
+    limit: usize,
+    filter: Option<&str>,
+    sort: Option<&str>,
+    modality: Option<&str>,
+) -> Result<ModelCatalogPage> {
+    Ok(paginate(
+        &list_model_catalog()?,
+        offset,
+        limit,
+        filter,
+        sort,
+        modality,
+    ))
+}
+
+fn paginate(
+    items: &[ModelCatalogItem],
+    offset: usize,
+    limit: usize,
+    filter: Option<&str>,
+    sort: Option<&str>,
+    modality: Option<&str>,
+) -> ModelCatalogPage {
+    let needle = filter
+        .map(|f| f.trim().to_ascii_lowercase())
+        .filter(|f| !f.is_empty());
+    let modality = modality
+        .map(|m| m.trim().to_ascii_lowercase())
+        .filter(|m| !m.is_empty());
+    let mut matching = items
+        .iter()
+        .filter(|item| {
+            needle.as_deref().is_none_or(|needle| {
+                [&item.key, &item.name, &item.provider]
+                    .iter()
+                    .any(|field| field.to_ascii_lowercase().contains(needle))
+            })
+        })
+        .filter(|item| {
+            modality
+                .as_deref()
+                .is_none_or(|modality| item.modalities.iter().any(|m| m == modality))
+        })
+        .collect::<Vec<_>>();
+    match sort.map(str::trim) {
+        Some("price") => matching.sort_by(|a, b| {
+            let price = |item: &ModelCatalogItem| item.input_price_per_mtok.unwrap_or(f64::MAX);
+            price(a).total_cmp(&price(b))
+        }),
+        Some("context") => {
+            matching.sort_by_key(|item| std::cmp::Reverse(item.context_window.unwrap_or(0)))
+        }
+        _ => {}
+    }
+    let limit = limit.clamp(1, MODEL_CATALOG_MAX_PAGE);
+    let mut hasher = Sha256::new();
+    for item in &matching {
+        hasher.update(serde_json::to_vec(item).unwrap_or_default());
+        hasher.update([b'\n']);
+    }
+    ModelCatalogPage {
+        items: matching
+            .iter()
+            .skip(offset)
+            .take(limit)
+            .map(|item| (*item).clone())
+            .collect(),
+        offset,
+        limit,
+        total: matching.len(),
+        content_hash: fs_walk::hex_digest(hasher),
+    }
+}
+
 fn list_from_openclaw_cli_with_timeout(timeout: Duration) -> Result<Vec<ModelCatalogItem>> {
     let (tx, rx) = mpsc::channel::<Result<Vec<ModelCatalogItem>>>();
     thread::spawn(move || {
@@ -200,7 +380,55 @@ fn merge_catalog_sources(sources: &[Vec<ModelCatalogItem>]) -> Vec<ModelCatalogI
             map.entry(item.key.clone()).or_insert_with(|| item.clone());
         }
     }
-    map.into_values().collect()
+    let mut items = map.into_values().collect::<Vec<_>>();
+    fill_metadata(&mut items);
+    items
+}
+
+/// Known output price (USD per million tokens) of a model key. OpenRouter `:free`
+/// variants cost nothing.
+pub fn known_output_price(key: &str) -> Option<f64> {
+    if key.ends_with(":free") {
+        return Some(0.0);
+    }
+    MODEL_METADATA
+        .iter()
+        .find(|entry| entry.0 == key)
+        .and_then(|entry| entry.3)
+}
+
+/// Fills context window, prices and modalities the source did not report from
+/// `MODEL_METADATA`.
+fn fill_metadata(items: &mut [ModelCatalogItem]) {
+    for item in items {
+        let Some((_, context, input, output, modalities)) =
+            MODEL_METADATA.iter().find(|entry| entry.0 == item.key)
+        else {
+            continue;
+        };
+        item.context_window = item.context_window.or(Some(*context));
+        item.input_price_per_mtok = item.input_price_per_mtok.or(*input);
+        item.output_price_per_mtok = item.output_price_per_mtok.or(*output);
+        if item.modalities.is_empty() {
+            item.modalities = modalities.iter().map(|m| m.to_string()).collect();
+        }
+    }
+}
+
+fn parse_modalities(input: Option<&Value>) -> Vec<String> {
+    let parts = match input {
+        Some(Value::String(text)) => text.split(['+', ',', ' ']).map(str::to_string).collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::<String>::new(),
+    };
+    parts
+        .into_iter()
+        .map(|part| part.trim().to_ascii_lowercase())
+        .filter(|part| !part.is_empty())
+        .collect()
 }
 
 fn list_from_openclaw_cli() -> Result<Vec<ModelCatalogItem>> {
@@ -287,29 +515,12 @@ fn list_from_openclaw_cli() -> Result<Vec<ModelCatalogItem>> {
 }
 
 fn run_models_list_json(command: &str, envs: &[(String, String)]) -> Result<Vec<ModelCatalogItem>> {
-    let output = if is_npx_command(command) {
-        shell::run_command(
-            command,
-            &[
-                "--yes",
-                "openclaw",
-                "--no-color",
-                "models",
-                "list",
-                "--all",
-                "--json",
-            ],
-            None,
-            envs,
-        )?
-    } else {
-        shell::run_command(
-            command,
-            &["--no-color", "models", "list", "--all", "--json"],
-            None,
-            envs,
-        )?
-    };
+    let output = shell::run_command(
+        command,
+        &["--no-color", "models", "list", "--all", "--json"],
+        None,
+        envs,
+    )?;
 
     if output.code != 0 {
         logger::warn(&format!(
@@ -351,6 +562,10 @@ fn run_models_list_json(command: &str, envs: &[(String, String)]) -> Result<Vec<
                 },
                 available: entry.available,
                 missing: entry.missing,
+                context_window: entry.context_window,
+                input_price_per_mtok: entry.cost.as_ref().and_then(|cost| cost.input),
+                output_price_per_mtok: entry.cost.as_ref().and_then(|cost| cost.output),
+                modalities: parse_modalities(entry.input.as_ref()),
             }
         })
         .collect::<Vec<_>>();
@@ -364,29 +579,12 @@ fn run_models_list_plain(
     command: &str,
     envs: &[(String, String)],
 ) -> Result<Vec<ModelCatalogItem>> {
-    let output = if is_npx_command(command) {
-        shell::run_command(
-            command,
-            &[
-                "--yes",
-                "openclaw",
-                "--no-color",
-                "models",
-                "list",
-                "--all",
-                "--plain",
-            ],
-            None,
-            envs,
-        )?
-    } else {
-        shell::run_command(
-            command,
-            &["--no-color", "models", "list", "--all", "--plain"],
-            None,
-            envs,
-        )?
-    };
+    let output = shell::run_command(
+        command,
+        &["--no-color", "models", "list", "--all", "--plain"],
+        None,
+        envs,
+    )?;
 
     if output.code != 0 {
         logger::warn(&format!(
@@ -426,6 +624,10 @@ fn parse_models_plain(raw: &str) -> Vec<ModelCatalogItem> {
                 name: key,
                 available: None,
                 missing: false,
+                context_window: None,
+                input_price_per_mtok: None,
+                output_price_per_mtok: None,
+                modalities: vec![],
             })
         })
         .collect()
@@ -438,16 +640,6 @@ fn looks_like_model_key(value: &str) -> bool {
     !provider.trim().is_empty() && !model.trim().is_empty()
 }
 
-fn is_npx_command(command: &str) -> bool {
-    let trimmed = command.trim().trim_matches('"');
-    let file = std::path::Path::new(trimmed)
-        .file_name()
-        .and_then(|v| v.to_str())
-        .unwrap_or(trimmed);
-    let lower = file.to_ascii_lowercase();
-    lower == "npx" || lower == "npx.cmd" || lower == "npx.exe"
-}
-
 fn parse_models_payload(raw: &str) -> Result<ModelsListPayload> {
     if let Ok(parsed) = serde_json::from_str::<ModelsListPayload>(raw) {
         return Ok(parsed);
@@ -481,15 +673,15 @@ fn parse_models_payload(raw: &str) -> Result<ModelsListPayload> {
 fn resolve_openclaw_commands() -> Vec<String> {
     let mut out = Vec::<String>::new();
 
+    // No `npx` here: a cold `npx openclaw models list` takes up to a minute and needs the
+    // network. Without a local command the catalog comes from openclaw.json instead.
     if let Ok(Some(state)) = state_store::load_install_state() {
-        let mut deferred_npx: Option<String> = None;
+        if let Some(shim) = cli_shim::pinned_command(&state) {
+            out.push(shim);
+        }
         let cmd = state.command_path.trim().trim_matches('"').to_string();
-        if !cmd.is_empty() {
-            if is_npx_command(cmd.as_str()) {
-                deferred_npx = shell::command_exists("npx");
-            } else {
-                out.push(cmd);
-            }
+        if !cmd.is_empty() && !cli_shim::is_npx(&cmd) {
+            out.push(cmd);
         }
 
         let install_dir = state.install_dir.trim();
@@ -498,18 +690,11 @@ fn resolve_openclaw_commands() -> Vec<String> {
                 install_dir,
             )));
         }
-
-        if let Some(npx) = deferred_npx {
-            out.push(npx);
-        }
     }
 
     if let Some(openclaw) = shell::command_exists("openclaw") {
         out.push(openclaw);
     }
-    if let Some(npx) = shell::command_exists("npx") {
-        out.push(npx);
-    }
 
     let mut dedup = HashSet::<String>::new();
     out.retain(|command| {
@@ -549,13 +734,6 @@ fn local_openclaw_command_candidates(install_dir: &std::path::Path) -> Vec<Strin
 }
 
 fn is_model_list_command_usable(command: &str) -> bool {
-    if is_npx_command(command) {
-        // Do not run `npx openclaw --version` as a "usability check" here:
-        // it can be slow and it can fail due to transient npm cache issues.
-        // We'll attempt the real `models list` and fall back if it fails.
-        return shell::command_exists("npx").is_some();
-    }
-
     let Ok(out) = shell::run_command(command, &["--version"], None, &[]) else {
         return false;
     };
@@ -591,6 +769,10 @@ fn list_from_config_json() -> Vec<ModelCatalogItem> {
                 name,
                 available: None,
                 missing: false,
+                context_window: None,
+                input_price_per_mtok: None,
+                output_price_per_mtok: None,
+                modalities: vec![],
             });
         }
     }
@@ -607,6 +789,10 @@ fn list_from_config_json() -> Vec<ModelCatalogItem> {
             name: primary,
             available: None,
             missing: false,
+            context_window: None,
+            input_price_per_mtok: None,
+            output_price_per_mtok: None,
+            modalities: vec![],
         });
     }
     if let Some(fallbacks) = json
@@ -622,6 +808,10 @@ fn list_from_config_json() -> Vec<ModelCatalogItem> {
                     name: model_key,
                     available: None,
                     missing: false,
+                    context_window: None,
+                    input_price_per_mtok: None,
+                    output_price_per_mtok: None,
+                    modalities: vec![],
                 });
             }
         }
@@ -671,6 +861,9 @@ fn fallback_catalog() -> Vec<ModelCatalogItem> {
             "amazon-bedrock/anthropic.claude-sonnet-4-5-20250929-v1:0",
             "Bedrock Claude Sonnet 4.5",
         ),
+        catalog_item("azure/gpt-4.1", "Azure OpenAI GPT-4.1"),
+        catalog_item("azure/gpt-4o", "Azure OpenAI GPT-4o"),
+        catalog_item("azure/o4-mini", "Azure OpenAI o4-mini"),
     ];
     items.sort_by(|a, b| a.key.cmp(&b.key));
     items.dedup_by(|a, b| a.key == b.key);
@@ -684,6 +877,10 @@ fn catalog_item(key: &str, name: &str) -> ModelCatalogItem {
         name: name.to_string(),
         available: None,
         missing: false,
+        context_window: None,
+        input_price_per_mtok: None,
+        output_price_per_mtok: None,
+        modalities: vec![],
     }
 }
 
@@ -693,7 +890,79 @@ fn provider_from_key(model_key: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{fallback_catalog, parse_models_payload, parse_models_plain, provider_from_key};
+    use super::{
+        catalog_item, fallback_catalog, merge_catalog_sources, paginate, parse_models_payload,
+        parse_models_plain, provider_from_key,
+    };
+
+    #[test]
+    fn catalog_pages_filter_and_hash_the_matching_list() {
+        let items = (0..7)
+            .map(|i| catalog_item(&format!("openrouter/vendor/model-{i}"), "Model"))
+            .chain([catalog_item("openai/gpt-5.2", "GPT 5.2")])
+            .collect::<Vec<_>>();
+        let page = paginate(&items, 5, 3, Some("OpenRouter"), None, None);
+        assert_eq!(page.total, 7);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(page.items[0].key, "openrouter/vendor/model-5");
+
+        let again = paginate(&items, 0, 3, Some("openrouter"), None, None);
+        assert_eq!(again.content_hash, page.content_hash);
+        let mut changed = items.clone();
+        changed[1].available = Some(true);
+        assert_ne!(
+            paginate(&changed, 0, 3, Some("openrouter"), None, None).content_hash,
+            page.content_hash
+        );
+        assert_eq!(paginate(&items, 0, 0, None, None, None).limit, 1);
+    }
+
+    #[test]
+    fn catalog_metadata_comes_from_the_cli_then_the_bundled_table() {
+        let raw = r#"{"models":[{"key":"custom/big","name":"Big","input":"text+image","contextWindow":500000,"cost":{"input":9.0,"output":30.0}}]}"#;
+        let cli = parse_models_payload(raw).expect("payload").models;
+        assert_eq!(cli[0].context_window, Some(500_000));
+        let mut big = catalog_item("custom/big", "Big");
+        big.context_window = Some(500_000);
+        big.input_price_per_mtok = Some(9.0);
+        big.modalities = vec!["text".to_string(), "image".to_string()];
+        let items = merge_catalog_sources(&[vec![
+            big,
+            catalog_item("google/gemini-2.0-flash", "Gemini 2.0 Flash"),
+            catalog_item("openai/gpt-4.1", "GPT-4.1"),
+            catalog_item("unknown/model", "Unknown"),
+        ]]);
+        let flash = items
+            .iter()
+            .find(|item| item.key == "google/gemini-2.0-flash")
+            .expect("flash");
+        assert_eq!(flash.input_price_per_mtok, Some(0.1));
+        assert!(flash.modalities.iter().any(|m| m == "audio"));
+
+        let keys = |page: crate::models::ModelCatalogPage| {
+            page.items.into_iter().map(|i| i.key).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            keys(paginate(&items, 0, 10, None, Some("price"), None)),
+            vec![
+                "google/gemini-2.0-flash",
+                "openai/gpt-4.1",
+                "custom/big",
+                "unknown/model"
+            ]
+        );
+        assert_eq!(
+            keys(paginate(
+                &items,
+                0,
+                10,
+                None,
+                Some("context"),
+                Some("image")
+            ))[0],
+            "google/gemini-2.0-flash"
+        );
+    }
 
     #[test]
     fn parse_models_payload_works_for_pure_json() {
@@ -34,21 +34,76 @@ pub fn normalize_auth_provider(provider: &str) -> String {
     }
 }
 
+/// Providers OpenClaw ships with, and the env name each reads its key from.
+const BUILTIN_PROVIDER_ENV: &[(&str, &str)] = &[
+    ("openai", "OPENAI_API_KEY"),
+    ("google", "GEMINI_API_KEY"),
+    ("moonshot", "MOONSHOT_API_KEY"),
+    ("kimi-coding", "KIMI_API_KEY"),
+    ("xai", "XAI_API_KEY"),
+    ("anthropic", "ANTHROPIC_API_KEY"),
+    ("openrouter", "OPENROUTER_API_KEY"),
+    ("azure", "AZURE_OPENAI_API_KEY"),
+    ("zai", "ZAI_API_KEY"),
+    ("xiaomi", "XIAOMI_API_KEY"),
+    ("minimax", "MINIMAX_API_KEY"),
+];
+
+/// Built-in providers map to their fixed env name; any other provider (including the
+/// ones registered with `add_custom_provider`) gets `<NAME>_API_KEY`.
 pub fn provider_env_name(provider: &str) -> Option<String> {
-    match normalize_auth_provider(provider).as_str() {
-        "openai" => Some("OPENAI_API_KEY".to_string()),
-        "google" => Some("GEMINI_API_KEY".to_string()),
-        "moonshot" => Some("MOONSHOT_API_KEY".to_string()),
-        "kimi-coding" => Some("KIMI_API_KEY".to_string()),
-        "xai" => Some("XAI_API_KEY".to_string()),
-        "anthropic" => Some("ANTHROPIC_API_KEY".to_string()),
-        "openrouter" => Some("OPENROUTER_API_KEY".to_string()),
-        "azure" => Some("AZURE_OPENAI_API_KEY".to_string()),
-        "zai" => Some("ZAI_API_KEY".to_string()),
-        "xiaomi" => Some("XIAOMI_API_KEY".to_string()),
-        "minimax" => Some("MINIMAX_API_KEY".to_string()),
-        other => generic_provider_env_name(other),
+    let normalized = normalize_auth_provider(provider);
+    BUILTIN_PROVIDER_ENV
+        .iter()
+        .find(|(id, _)| *id == normalized)
+        .map(|(_, env)| env.to_string())
+        .or_else(|| generic_provider_env_name(&normalized))
+}
+
+pub fn builtin_providers() -> impl Iterator<Item = &'static str> {
+    BUILTIN_PROVIDER_ENV.iter().map(|(id, _)| *id)
+}
+
+pub fn is_builtin_provider(provider: &str) -> bool {
+    let normalized = normalize_auth_provider(provider);
+    BUILTIN_PROVIDER_ENV.iter().any(|(id, _)| *id == normalized)
+}
+
+/// Other env names OpenClaw plugins and SDKs read for the same provider key.
+const PROVIDER_ENV_ALIASES: &[(&str, &[&str])] = &[
+    (
+        "google",
+        &["GOOGLE_API_KEY", "GOOGLE_GENERATIVE_AI_API_KEY"],
+    ),
+    ("kimi-coding", &["KIMICODE_API_KEY"]),
+    ("azure", &["AZURE_API_KEY"]),
+    ("zai", &["Z_AI_API_KEY"]),
+];
+
+/// Every env name a provider key is written under, primary name first.
+pub fn provider_env_names(provider: &str) -> Vec<String> {
+    let Some(primary) = provider_env_name(provider) else {
+        return Vec::new();
+    };
+    let normalized = normalize_auth_provider(provider);
+    let mut names = vec![primary];
+    if let Some((_, aliases)) = PROVIDER_ENV_ALIASES
+        .iter()
+        .find(|(id, _)| *id == normalized)
+    {
+        names.extend(aliases.iter().map(|alias| alias.to_string()));
     }
+    names
+}
+
+/// Maps an alias back to its provider's primary env name so the same key stored under
+/// several names counts as one credential. Unknown names are returned unchanged.
+pub fn canonical_env_name(name: &str) -> String {
+    PROVIDER_ENV_ALIASES
+        .iter()
+        .find(|(_, aliases)| aliases.contains(&name))
+        .and_then(|(provider, _)| provider_env_name(provider))
+        .unwrap_or_else(|| name.to_string())
 }
 
 fn generic_provider_env_name(provider: &str) -> Option<String> {
@@ -73,8 +128,8 @@ fn generic_provider_env_name(provider: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::{
-        normalize_auth_provider, normalize_known_model_key, provider_env_name,
-        provider_from_model_key, provider_from_model_key_or_unknown,
+        canonical_env_name, normalize_auth_provider, normalize_known_model_key, provider_env_name,
+        provider_env_names, provider_from_model_key, provider_from_model_key_or_unknown,
     };
 
     #[test]
@@ -130,4 +185,21 @@ mod tests {
             Some("CUSTOM_PROVIDER_API_KEY".to_string())
         );
     }
+
+    #[test]
+    fn provider_env_names_put_primary_first_and_aliases_resolve_back() {
+        assert_eq!(
+            provider_env_names("google"),
+            vec![
+                "GEMINI_API_KEY",
+                "GOOGLE_API_KEY",
+                "GOOGLE_GENERATIVE_AI_API_KEY"
+            ]
+        );
+        assert_eq!(provider_env_names("openai-codex"), vec!["OPENAI_API_KEY"]);
+        assert!(provider_env_names("").is_empty());
+        assert_eq!(canonical_env_name("GOOGLE_API_KEY"), "GEMINI_API_KEY");
+        assert_eq!(canonical_env_name("Z_AI_API_KEY"), "ZAI_API_KEY");
+        assert_eq!(canonical_env_name("OPENAI_API_KEY"), "OPENAI_API_KEY");
+    }
 }
@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use serde_json::{json, Value};
+
+use crate::models::{ModelPreset, ModelPresetImportResult};
+
+use super::{config, logger, model_catalog, model_identity, paths, state_store};
+
+const PRESET_FORMAT: &str = "openclaw-model-preset";
+const PRESET_VERSION: u64 = 1;
+
+/// Writes the current model chain and aliases as a shareable preset. Never includes keys.
+pub fn export_model_preset(path: &str, name: &str, description: &str) -> Result<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("Preset name is required."));
+    }
+    let current = config::read_current_config()?;
+    let last = state_store::load_last_config()?.unwrap_or_default();
+    let preset = ModelPreset {
+        name: name.to_string(),
+        description: description.trim().to_string(),
+        model_chain: current.model_chain,
+        aliases: read_model_aliases(),
+        base_url: config::optional_non_empty(last.base_url).filter(|url| !url_has_credentials(url)),
+        kimi_region: config::optional_non_empty(Some(last.kimi_region)),
+    };
+
+    let target = paths::normalize_path(path)?;
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let bundle = json!({
+        "format": PRESET_FORMAT,
+        "version": PRESET_VERSION,
+        "exported_at": Local::now().to_rfc3339(),
+        "preset": preset,
+    });
+    fs::write(&target, serde_json::to_string_pretty(&bundle)?)?;
+    logger::info(&format!(
+        "Model preset '{name}' exported: {}",
+        target.to_string_lossy()
+    ));
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Reads a preset file and validates it. When `apply` is set, the model chain and aliases are written.
+pub fn import_model_preset(path: &str, apply: bool) -> Result<ModelPresetImportResult> {
+    let source = paths::normalize_path(path)?;
+    let raw = fs::read_to_string(&source)
+        .map_err(|err| anyhow!("Cannot read {}: {err}", source.to_string_lossy()))?;
+    let mut preset = parse_preset_bundle(&serde_json::from_str(&raw)?)?;
+    preset.model_chain.primary =
+        model_identity::normalize_known_model_key(&preset.model_chain.primary);
+    preset.model_chain.fallbacks = preset
+        .model_chain
+        .fallbacks
+        .iter()
+        .map(|item| model_identity::normalize_known_model_key(item))
+        .filter(|item| !item.trim().is_empty())
+        .collect();
+
+    let catalog = model_catalog::list_model_catalog()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|item| item.key)
+        .collect::<HashSet<_>>();
+    let unknown_models = unknown_models(&preset, &catalog);
+
+    let last = state_store::load_last_config()?.unwrap_or_default();
+    let env_content = fs::read_to_string(paths::openclaw_home().join(".env")).unwrap_or_default();
+    let missing_keys = config::providers_from_model_chain(&preset.model_chain)
+        .into_iter()
+        .filter(|provider| {
+            let in_state = config::provider_key_for_id(&last, provider).is_some();
+            let in_env = model_identity::provider_env_names(provider)
+                .iter()
+                .any(|env| env_has_value(&env_content, env));
+            !in_state && !in_env
+        })
+        .collect::<Vec<_>>();
+
+    let mut warnings = Vec::<String>::new();
+    if !unknown_models.is_empty() {
+        warnings.push(format!(
+            "Models not found in the catalog: {}",
+            unknown_models.join(", ")
+        ));
+    }
+    if !missing_keys.is_empty() {
+        warnings.push(format!(
+            "Add API keys for: {} before using this preset.",
+            missing_keys.join(", ")
+        ));
+    }
+
+    let mut applied = false;
+    if apply {
+        let result =
+            config::switch_model(&preset.model_chain.primary, &preset.model_chain.fallbacks)?;
+        warnings.extend(result.warnings.into_iter().map(|w| w.message));
+        for (alias, model) in &preset.aliases {
+            let out = config::run_openclaw_cli(
+                &[
+                    "config".to_string(),
+                    "set".to_string(),
+                    format!("agents.defaults.models[\"{model}\"].alias"),
+                    alias.clone(),
+                ],
+                last.proxy.clone(),
+            )?;
+            if out.code != 0 {
+                warnings.push(format!(
+                    "Alias '{alias}' for {model} not applied: {}",
+                    config::cli_output_text(&out)
+                ));
+            }
+        }
+        applied = true;
+        logger::info(&format!("Model preset '{}' applied.", preset.name));
+    }
+
+    Ok(ModelPresetImportResult {
+        preset,
+        unknown_models,
+        missing_keys,
+        applied,
+        warnings,
+    })
+}
+
+fn parse_preset_bundle(bundle: &Value) -> Result<ModelPreset> {
+    if bundle.get("format").and_then(|v| v.as_str()) != Some(PRESET_FORMAT) {
+        return Err(anyhow!("File is not an OpenClaw model preset."));
+    }
+    let version = bundle.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version == 0 || version > PRESET_VERSION {
+        return Err(anyhow!("Unsupported model preset version {version}."));
+    }
+    let preset: ModelPreset = serde_json::from_value(
+        bundle
+            .get("preset")
+            .cloned()
+            .ok_or_else(|| anyhow!("Model preset file has no preset section."))?,
+    )?;
+    if model_identity::provider_from_model_key(&preset.model_chain.primary).is_none() {
+        return Err(anyhow!(
+            "Preset primary model must be in provider/model form: '{}'",
+            preset.model_chain.primary
+        ));
+    }
+    Ok(preset)
+}
+
+fn unknown_models(preset: &ModelPreset, catalog: &HashSet<String>) -> Vec<String> {
+    // An empty catalog means the CLI was unavailable; nothing can be validated then.
+    if catalog.is_empty() {
+        return vec![];
+    }
+    let mut out = Vec::<String>::new();
+    let referenced = std::iter::once(&preset.model_chain.primary)
+        .chain(preset.model_chain.fallbacks.iter())
+        .chain(preset.aliases.values());
+    for model in referenced {
+        if !catalog.contains(model) && !out.contains(model) {
+            out.push(model.clone());
+        }
+    }
+    out
+}
+
+fn read_model_aliases() -> BTreeMap<String, String> {
+    let Ok(raw) = fs::read_to_string(paths::config_path()) else {
+        return BTreeMap::new();
+    };
+    let Ok(json) = serde_json::from_str::<Value>(&raw) else {
+        return BTreeMap::new();
+    };
+    json.pointer("/agents/defaults/models")
+        .and_then(|v| v.as_object())
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|(model, entry)| {
+                    let alias = entry.get("alias")?.as_str()?.trim();
+                    (!alias.is_empty()).then(|| (alias.to_string(), model.clone()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn env_has_value(content: &str, key: &str) -> bool {
+    content.lines().any(|line| {
+        line.trim()
+            .strip_prefix(key)
+            .and_then(|rest| rest.trim_start().strip_prefix('='))
+            .map(|value| !value.trim().is_empty())
+            .unwrap_or(false)
+    })
+}
+
+fn url_has_credentials(url: &str) -> bool {
+    url::Url::parse(url)
+        .map(|parsed| !parsed.username().is_empty() || parsed.password().is_some())
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{env_has_value, parse_preset_bundle, unknown_models};
+    use serde_json::json;
+
+    #[test]
+    fn parse_preset_bundle_validates_format_and_primary() {
+        let bundle = json!({
+            "format": "openclaw-model-preset",
+            "version": 1,
+            "preset": {
+                "name": "cheap-cn",
+                "model_chain": { "primary": "moonshot/kimi-k2.5", "fallbacks": ["zai/glm-4.7"] },
+                "aliases": { "fast": "zai/glm-4.7" }
+            }
+        });
+        let preset = parse_preset_bundle(&bundle).expect("valid preset");
+        assert_eq!(preset.name, "cheap-cn");
+        assert_eq!(
+            preset.aliases.get("fast").map(String::as_str),
+            Some("zai/glm-4.7")
+        );
+
+        let bad = json!({
+            "format": "openclaw-model-preset",
+            "version": 1,
+            "preset": { "name": "x", "model_chain": { "primary": "gpt", "fallbacks": [] } }
+        });
+        assert!(parse_preset_bundle(&bad).is_err());
+    }
+
+    #[test]
+    fn unknown_models_skips_validation_without_catalog() {
+        let preset = parse_preset_bundle(&json!({
+            "format": "openclaw-model-preset",
+            "version": 1,
+            "preset": { "model_chain": { "primary": "a/x", "fallbacks": ["b/y"] } }
+        }))
+        .expect("valid preset");
+        assert!(unknown_models(&preset, &HashSet::new()).is_empty());
+        let catalog = HashSet::from(["a/x".to_string()]);
+        assert_eq!(unknown_models(&preset, &catalog), vec!["b/y".to_string()]);
+    }
+
+    #[test]
+    fn env_has_value_requires_non_empty_assignment() {
+        let env = "OPENAI_API_KEY=sk-1\nZAI_API_KEY=\n";
+        assert!(env_has_value(env, "OPENAI_API_KEY"));
+        assert!(!env_has_value(env, "ZAI_API_KEY"));
+        assert!(!env_has_value(env, "XAI_API_KEY"));
+    }
+}
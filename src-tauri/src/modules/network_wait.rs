@@ -0,0 +1,130 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use chrono::Local;
+use reqwest::Client;
+
+use super::{logger, state_store::RunPrefs};
+
+// Same endpoint Windows NCSI uses to decide whether the machine is online.
+const NCSI_PROBE_URL: &str = "http://www.msftconnecttest.com/connecttest.txt";
+const NCSI_EXPECTED_BODY: &str = "Microsoft Connect Test";
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+static WAITING_SINCE_MS: OnceLock<Mutex<Option<i64>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decision {
+    Start,
+    /// Network came up after waiting this many milliseconds.
+    StartAfterWait(i64),
+    /// Gave up waiting after this many milliseconds; start anyway.
+    StartAfterTimeout(i64),
+    Defer,
+}
+
+/// Non-blocking gate for the auto-start path: returns false while the network is
+/// still down, so status polling retries later instead of spawning a gateway
+/// that fails its first provider/channel connections right after login.
+pub async fn ready_for_autostart(prefs: &RunPrefs) -> bool {
+    if prefs.network_wait_secs == 0 {
+        return true;
+    }
+    let online = probe(&prefs.network_probe_url).await;
+    let now = Local::now().timestamp_millis();
+    let wait_ms = i64::try_from(prefs.network_wait_secs)
+        .unwrap_or(i64::MAX / 1000)
+        .saturating_mul(1000);
+
+    let lock = WAITING_SINCE_MS.get_or_init(|| Mutex::new(None));
+    let mut waiting_since = lock.lock().unwrap_or_else(|e| e.into_inner());
+    if waiting_since.is_none() && !online {
+        logger::info("Auto-start deferred: waiting for network connectivity.");
+    }
+    let (decision, next) = decide(now, *waiting_since, wait_ms, online);
+    *waiting_since = next;
+    match decision {
+        Decision::Start => true,
+        Decision::StartAfterWait(ms) => {
+            logger::info(&format!(
+                "Delayed auto-start: network became available after {}s.",
+                ms / 1000
+            ));
+            true
+        }
+        Decision::StartAfterTimeout(ms) => {
+            logger::warn(&format!(
+                "Delayed auto-start: network still unavailable after {}s, starting anyway.",
+                ms / 1000
+            ));
+            true
+        }
+        Decision::Defer => false,
+    }
+}
+
+fn decide(
+    now: i64,
+    waiting_since: Option<i64>,
+    wait_ms: i64,
+    online: bool,
+) -> (Decision, Option<i64>) {
+    match (online, waiting_since) {
+        (true, None) => (Decision::Start, None),
+        (true, Some(since)) => (Decision::StartAfterWait(now - since), None),
+        (false, None) => (Decision::Defer, Some(now)),
+        (false, Some(since)) if now - since >= wait_ms => {
+            (Decision::StartAfterTimeout(now - since), None)
+        }
+        (false, Some(since)) => (Decision::Defer, Some(since)),
+    }
+}
+
+async fn probe(custom_url: &str) -> bool {
+    let Ok(client) = Client::builder().timeout(PROBE_TIMEOUT).build() else {
+        return false;
+    };
+    let custom = custom_url.trim();
+    let url = if custom.is_empty() {
+        NCSI_PROBE_URL
+    } else {
+        custom
+    };
+    let Ok(resp) = client.get(url).send().await else {
+        return false;
+    };
+    if !resp.status().is_success() {
+        return false;
+    }
+    if !custom.is_empty() {
+        return true;
+    }
+    // Captive portals answer 200 with their own page; only the exact NCSI body counts.
+    resp.text()
+        .await
+        .map(|body| body.trim() == NCSI_EXPECTED_BODY)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decide, Decision};
+
+    #[test]
+    fn decide_defers_until_online_or_timeout() {
+        assert_eq!(decide(0, None, 90_000, true), (Decision::Start, None));
+        assert_eq!(decide(0, None, 90_000, false), (Decision::Defer, Some(0)));
+        assert_eq!(
+            decide(30_000, Some(0), 90_000, false),
+            (Decision::Defer, Some(0))
+        );
+        assert_eq!(
+            decide(45_000, Some(0), 90_000, true),
+            (Decision::StartAfterWait(45_000), None)
+        );
+        assert_eq!(
+            decide(90_000, Some(0), 90_000, false),
+            (Decision::StartAfterTimeout(90_000), None)
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::models::{OfflineBundleResult, SourceMethod};
+
+use super::{backup, env, fs_walk, logger, paths, state_store};
+
+/// Written at the root of every bundle made by `export_offline_bundle`.
+pub const MANIFEST_NAME: &str = "openclaw-offline.json";
+
+const PACKAGE_MANIFEST: &str = "node_modules/openclaw/package.json";
+const ZIP64_ENTRY_THRESHOLD: u64 = u32::MAX as u64;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct BundleManifest {
+    format: u32,
+    version: String,
+    created_at: String,
+    node_version: Option<String>,
+    /// `std::env::consts::ARCH` of the exporting machine; native addons in
+    /// `node_modules` only load on the same architecture.
+    arch: String,
+}
+
+impl Default for BundleManifest {
+    fn default() -> Self {
+        Self {
+            format: 1,
+            version: String::new(),
+            created_at: String::new(),
+            node_version: None,
+            arch: String::new(),
+        }
+    }
+}
+
+/// Packs the current npm/bun install (`package.json`, lockfile and the whole
+/// `node_modules`) into a zip that `SourceMethod::OfflineArchive` installs on a
+/// machine without network access. Written to `dest_dir`, or the profile's
+/// `offline-bundles` folder.
+pub fn export_offline_bundle(dest_dir: Option<String>) -> Result<OfflineBundleResult> {
+    let state = state_store::load_install_state()?
+        .ok_or_else(|| anyhow!("OpenClaw is not installed; nothing to export."))?;
+    if !matches!(
+        state.method,
+        SourceMethod::Npm | SourceMethod::Bun | SourceMethod::OfflineArchive
+    ) {
+        return Err(anyhow!(
+            "Only npm, bun and offline installs can be exported; this one used {:?}.",
+            state.method
+        ));
+    }
+    let install_dir = paths::normalize_path(&state.install_dir)?;
+    if !install_dir.join(PACKAGE_MANIFEST).is_file() {
+        return Err(anyhow!(
+            "No openclaw package found under {}.",
+            install_dir.to_string_lossy()
+        ));
+    }
+
+    let dest_dir = match dest_dir.filter(|d| !d.trim().is_empty()) {
+        Some(dir) => paths::normalize_path(&dir)?,
+        None => paths::profile_data_dir().join("offline-bundles"),
+    };
+    fs::create_dir_all(&dest_dir)?;
+    let version = state.version.trim().to_string();
+    let file_name = format!(
+        "openclaw-offline-{}-{}.zip",
+        sanitize(&version),
+        Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let path = dest_dir.join(file_name);
+    let part = path.with_extension("zip.part");
+
+    let written = write_bundle(&install_dir, &part, &version);
+    let files = match written {
+        Ok(files) => files,
+        Err(err) => {
+            let _ = fs::remove_file(&part);
+            return Err(err);
+        }
+    };
+    fs::rename(&part, &path)?;
+    let size_bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    logger::info(&format!(
+        "Offline bundle exported: {} ({files} files, {} MB).",
+        path.to_string_lossy(),
+        size_bytes / (1024 * 1024)
+    ));
+    Ok(OfflineBundleResult {
+        path: path.to_string_lossy().to_string(),
+        version,
+        files,
+        size_bytes,
+    })
+}
+
+/// Unpacks a bundle into `install_dir`. The package is checked before anything is
+/// written, so a wrong archive leaves the folder untouched.
+pub fn extract_bundle(archive_path: &Path, install_dir: &Path) -> Result<()> {
+    let mut archive = ZipArchive::new(File::open(archive_path)?)?;
+    if archive.by_name(PACKAGE_MANIFEST).is_err() {
+        return Err(anyhow!(
+            "{} is not an OpenClaw offline bundle (no {PACKAGE_MANIFEST}).",
+            archive_path.to_string_lossy()
+        ));
+    }
+    let manifest = archive.by_name(MANIFEST_NAME).ok().and_then(|mut entry| {
+        let mut raw = String::new();
+        entry.read_to_string(&mut raw).ok()?;
+        serde_json::from_str::<BundleManifest>(&raw).ok()
+    });
+    if let Some(manifest) = &manifest {
+        if !manifest.arch.is_empty() && manifest.arch != std::env::consts::ARCH {
+            logger::warn(&format!(
+                "Offline bundle was made on {} but this machine is {}; native modules may not load.",
+                manifest.arch,
+                std::env::consts::ARCH
+            ));
+        }
+    }
+    drop(archive);
+    backup::extract_zip(archive_path, install_dir)?;
+    let _ = fs::remove_file(install_dir.join(MANIFEST_NAME));
+    Ok(())
+}
+
+/// Archive kind by extension: a zip bundle, or an `npm pack` tarball.
+pub fn is_npm_tarball(path: &Path) -> bool {
+    let name = path.to_string_lossy().to_ascii_lowercase();
+    name.ends_with(".tgz") || name.ends_with(".tar.gz")
+}
+
+/// Resolves the archive path given as `source_url` for an offline install.
+pub fn archive_from_source(source: Option<&str>) -> Result<PathBuf> {
+    let source = source
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| anyhow!("Offline install needs the archive path in source_url."))?;
+    let path = paths::normalize_path(source.trim_start_matches("file://"))?;
+    if !path.is_file() {
+        return Err(anyhow!(
+            "Offline archive not found: {}",
+            path.to_string_lossy()
+        ));
+    }
+    Ok(path)
+}
+
+fn write_bundle(install_dir: &Path, part: &Path, version: &str) -> Result<usize> {
+    let mut zip = ZipWriter::new(File::create(part)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut files = 0usize;
+    for name in ["package.json", "package-lock.json", "bun.lockb"] {
+        let path = install_dir.join(name);
+        if path.is_file() {
+            add_file(&mut zip, options, &path, name)?;
+            files += 1;
+        }
+    }
+    let modules = install_dir.join("node_modules");
+    let walk = fs_walk::Walker::new(&modules).run();
+    for link in &walk.skipped_links {
+        logger::warn(&format!(
+            "Offline bundle skips link: {}",
+            link.to_string_lossy()
+        ));
+    }
+    for file in &walk.files {
+        let relative = file.path.strip_prefix(install_dir)?;
+        let name = relative.to_string_lossy().replace('\\', "/");
+        add_file(&mut zip, options, &file.path, &name)?;
+        files += 1;
+    }
+    let manifest = BundleManifest {
+        version: version.to_string(),
+        created_at: Local::now().to_rfc3339(),
+        node_version: env::node_version(),
+        arch: std::env::consts::ARCH.to_string(),
+        ..BundleManifest::default()
+    };
+    zip.start_file(MANIFEST_NAME, options)?;
+    zip.write_all(serde_json::to_string_pretty(&manifest)?.as_bytes())?;
+    zip.finish()?;
+    Ok(files)
+}
+
+fn add_file(
+    zip: &mut ZipWriter<File>,
+    options: SimpleFileOptions,
+    path: &Path,
+    name: &str,
+) -> Result<()> {
+    let mut file = File::open(paths::long_path(path))?;
+    let size = file.metadata()?.len();
+    zip.start_file(name, options.large_file(size >= ZIP64_ENTRY_THRESHOLD))?;
+    std::io::copy(&mut file, zip)?;
+    Ok(())
+}
+
+fn sanitize(version: &str) -> String {
+    let cleaned = version
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    if cleaned.is_empty() {
+        "unknown".to_string()
+    } else {
+        cleaned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract_bundle, is_npm_tarball, MANIFEST_NAME};
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::path::Path;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn write_zip(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = ZipWriter::new(File::create(path).expect("zip"));
+        for (name, body) in entries {
+            zip.start_file(*name, SimpleFileOptions::default())
+                .expect("entry");
+            zip.write_all(body.as_bytes()).expect("write");
+        }
+        zip.finish().expect("finish");
+    }
+
+    #[test]
+    fn only_archives_with_the_openclaw_package_are_extracted() {
+        let root = std::env::temp_dir().join(format!("oc-offline-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).expect("root");
+        let install_dir = root.join("install");
+
+        let wrong = root.join("wrong.zip");
+        write_zip(&wrong, &[("node_modules/other/package.json", "{}")]);
+        assert!(extract_bundle(&wrong, &install_dir).is_err());
+        assert!(!install_dir.exists());
+
+        let bundle = root.join("bundle.zip");
+        write_zip(
+            &bundle,
+            &[
+                (
+                    "node_modules/openclaw/package.json",
+                    r#"{"name":"openclaw"}"#,
+                ),
+                (MANIFEST_NAME, r#"{"format":1,"version":"2026.2.1"}"#),
+            ],
+        );
+        extract_bundle(&bundle, &install_dir).expect("extract");
+        assert!(install_dir
+            .join("node_modules/openclaw/package.json")
+            .is_file());
+        assert!(!install_dir.join(MANIFEST_NAME).exists());
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(is_npm_tarball(Path::new("openclaw-2026.2.1.tgz")));
+        assert!(!is_npm_tarball(Path::new("bundle.zip")));
+    }
+}
@@ -1,9 +1,51 @@
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
 
 use anyhow::{anyhow, Result};
 use regex::Regex;
 
+use super::logger;
+
+// Named profile in use. `None` is the default profile, which keeps the original layout
+// directly under the app data root.
+static ACTIVE_PROFILE: RwLock<Option<String>> = RwLock::new(None);
+
+pub fn active_profile() -> Option<String> {
+    ACTIVE_PROFILE
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+pub fn set_active_profile(name: Option<String>) {
+    *ACTIVE_PROFILE.write().unwrap_or_else(|e| e.into_inner()) = name;
+}
+
+pub fn profiles_root() -> PathBuf {
+    appdata_root().join("profiles")
+}
+
+/// Data folder of a profile: `profiles/<name>` for named ones, the app data root for
+/// the default profile.
+pub fn profile_root(name: Option<&str>) -> PathBuf {
+    match name {
+        Some(name) => profiles_root().join(name),
+        None => appdata_root(),
+    }
+}
+
+/// Data folder of the active profile; state, logs, run files and backups live under it.
+pub fn profile_data_dir() -> PathBuf {
+    profile_root(active_profile().as_deref())
+}
+
+pub fn has_named_profiles() -> bool {
+    std::fs::read_dir(profiles_root())
+        .map(|entries| entries.flatten().any(|entry| entry.path().is_dir()))
+        .unwrap_or(false)
+}
+
 pub fn appdata_root() -> PathBuf {
     if let Ok(value) = env::var("OPENCLAW_INSTALLER_DATA_DIR") {
         let trimmed = value.trim();
@@ -17,19 +59,33 @@ pub fn appdata_root() -> PathBuf {
 }
 
 pub fn logs_dir() -> PathBuf {
-    appdata_root().join("logs")
+    profile_data_dir().join("logs")
 }
 
+/// The configured backups folder, falling back to the default under the app data root.
 pub fn backups_dir() -> PathBuf {
-    appdata_root().join("backups")
+    super::state_store::load_backup_storage()
+        .ok()
+        .map(|cfg| cfg.location.trim().to_string())
+        .filter(|location| !location.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(default_backups_dir)
+}
+
+pub fn default_backups_dir() -> PathBuf {
+    profile_data_dir().join("backups")
 }
 
 pub fn state_dir() -> PathBuf {
-    appdata_root().join("state")
+    profile_data_dir().join("state")
 }
 
 pub fn run_dir() -> PathBuf {
-    appdata_root().join("run")
+    profile_data_dir().join("run")
+}
+
+pub fn tools_dir() -> PathBuf {
+    appdata_root().join("tools")
 }
 
 pub fn openclaw_home() -> PathBuf {
@@ -47,6 +103,14 @@ pub fn config_path() -> PathBuf {
 }
 
 pub fn default_isolated_openclaw_home() -> PathBuf {
+    isolated_openclaw_home_for(active_profile().as_deref())
+}
+
+/// Named profiles keep their OpenClaw home inside the profile folder.
+pub fn isolated_openclaw_home_for(profile: Option<&str>) -> PathBuf {
+    if let Some(name) = profile {
+        return profiles_root().join(name).join("openclaw-home");
+    }
     // Default to an isolated per-user directory so the installer never
     // touches an existing `%USERPROFILE%\\.openclaw` installation unless
     // the user explicitly points to it via Wizard -> Install directory.
@@ -85,9 +149,57 @@ pub fn is_user_profile_default_openclaw_dir(path: &std::path::Path) -> bool {
         .any(|candidate| needle == normalize(candidate))
 }
 
+/// True for symlinks and for NTFS junctions/mount points, which Windows reports as
+/// reparse points. Managed-tree walks never descend into these.
+#[cfg(windows)]
+pub fn is_reparse_point(path: &Path) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+    std::fs::symlink_metadata(path)
+        .map(|meta| meta.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(windows))]
+pub fn is_reparse_point(path: &Path) -> bool {
+    std::fs::symlink_metadata(path)
+        .map(|meta| meta.file_type().is_symlink())
+        .unwrap_or(false)
+}
+
+pub struct ManagedWalk {
+    pub entries: Vec<walkdir::DirEntry>,
+    /// Links/junctions found under the root. They are skipped, not followed, so a
+    /// junction can neither loop the walk nor pull in files outside the tree.
+    pub skipped_links: Vec<PathBuf>,
+}
+
+pub fn walk_managed(root: &Path) -> ManagedWalk {
+    let mut walk = ManagedWalk {
+        entries: vec![],
+        skipped_links: vec![],
+    };
+    let mut it = walkdir::WalkDir::new(root).follow_links(false).into_iter();
+    while let Some(entry) = it.next() {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        if entry.depth() > 0 && is_reparse_point(entry.path()) {
+            if entry.file_type().is_dir() {
+                it.skip_current_dir();
+            }
+            walk.skipped_links.push(entry.path().to_path_buf());
+            continue;
+        }
+        walk.entries.push(entry);
+    }
+    walk
+}
+
 pub fn ensure_dirs() -> Result<()> {
     for dir in [
         appdata_root(),
+        profile_data_dir(),
         logs_dir(),
         backups_dir(),
         state_dir(),
@@ -101,7 +213,7 @@ pub fn ensure_dirs() -> Result<()> {
 
 pub fn normalize_path(raw: &str) -> Result<PathBuf> {
     let expanded = expand_env_vars(raw)?;
-    let expanded = expanded.replace('/', "\\");
+    let expanded = strip_verbatim_prefix(&expanded.replace('/', "\\"));
     let with_home = if expanded.starts_with("~\\") || expanded == "~" {
         let home = dirs::home_dir().ok_or_else(|| anyhow!("Cannot resolve home directory"))?;
         let tail = expanded.trim_start_matches('~').trim_start_matches('\\');
@@ -113,7 +225,199 @@ pub fn normalize_path(raw: &str) -> Result<PathBuf> {
     } else {
         PathBuf::from(expanded)
     };
-    Ok(with_home)
+    Ok(resolve_subst_drive(&with_home).unwrap_or(with_home))
+}
+
+/// Turns `\\?\C:\x` into `C:\x` and `\\?\UNC\server\share` into `\\server\share`, so
+/// paths from canonicalize or pasted from Explorer compare and validate like plain ones.
+pub fn strip_verbatim_prefix(raw: &str) -> String {
+    if let Some(rest) = raw.strip_prefix("\\\\?\\UNC\\") {
+        return format!("\\\\{rest}");
+    }
+    raw.strip_prefix("\\\\?\\")
+        .map(|rest| rest.to_string())
+        .unwrap_or_else(|| raw.to_string())
+}
+
+pub fn is_unc_path(path: &Path) -> bool {
+    let text = path.to_string_lossy().replace('/', "\\");
+    text.starts_with("\\\\") && !text.starts_with("\\\\?\\") && !text.starts_with("\\\\.\\")
+}
+
+/// Rejects install locations npm, cmd.exe and the gateway cannot run from reliably:
+/// UNC shares and mapped network drives.
+pub fn ensure_local_install_dir(path: &Path) -> Result<()> {
+    if is_unc_path(path) {
+        return Err(anyhow!(
+            "Network share paths ({}) are not supported for the install directory. Choose a folder on a local drive; backups can still be copied to the share.",
+            path.to_string_lossy()
+        ));
+    }
+    if is_network_drive(path) {
+        return Err(anyhow!(
+            "{} is on a mapped network drive. Choose a folder on a local drive instead.",
+            path.to_string_lossy()
+        ));
+    }
+    Ok(())
+}
+
+/// Rejects characters cmd.exe expands even inside quotes (`%`, `!`) or cannot quote
+/// at all (`"`). Spaces and non-ASCII are fine: `.cmd` launches use short paths.
+pub fn ensure_spawn_compatible(path: &Path) -> Result<()> {
+    let text = path.to_string_lossy();
+    if let Some(bad) = text.chars().find(|ch| matches!(ch, '%' | '!' | '"')) {
+        return Err(anyhow!(
+            "Install directory {text} contains '{bad}', which breaks npm and cmd scripts. Choose a folder name without % ! or quotes."
+        ));
+    }
+    if needs_short_path(path) && path.exists() && short_path(path).is_none() {
+        logger::warn(&format!(
+            "No 8.3 short name for {text}; npm scripts will receive the full path."
+        ));
+    }
+    Ok(())
+}
+
+/// Paths with spaces or non-ASCII characters (e.g. CJK user names) that some
+/// cmd/npm layers mangle.
+pub fn needs_short_path(path: &Path) -> bool {
+    path.to_string_lossy()
+        .chars()
+        .any(|ch| !ch.is_ascii() || ch.is_whitespace())
+}
+
+/// Returns the 8.3 short form of an existing path when it needs one, else the path itself.
+pub fn spawn_safe_path(path: &Path) -> PathBuf {
+    if needs_short_path(path) {
+        if let Some(short) = short_path(path) {
+            return short;
+        }
+    }
+    path.to_path_buf()
+}
+
+#[cfg(windows)]
+fn short_path(path: &Path) -> Option<PathBuf> {
+    let long = to_wide(&path.to_string_lossy());
+    let mut buffer = vec![0u16; 1024];
+    // SAFETY: `long` is NUL-terminated; `buffer` is valid for the length passed.
+    let len = unsafe {
+        windows_sys::Win32::Storage::FileSystem::GetShortPathNameW(
+            long.as_ptr(),
+            buffer.as_mut_ptr(),
+            buffer.len() as u32,
+        )
+    } as usize;
+    if len == 0 || len >= buffer.len() {
+        return None;
+    }
+    let short = PathBuf::from(String::from_utf16_lossy(&buffer[..len]));
+    // Volumes with 8.3 names disabled hand back the long path unchanged.
+    (!needs_short_path(&short)).then_some(short)
+}
+
+#[cfg(not(windows))]
+fn short_path(_path: &Path) -> Option<PathBuf> {
+    None
+}
+
+/// Adds the `\\?\` prefix to absolute paths near MAX_PATH so deep node_modules and
+/// backup trees can be read, copied and deleted. Short paths are returned unchanged.
+pub fn long_path(path: &Path) -> PathBuf {
+    if cfg!(windows) {
+        PathBuf::from(long_path_text(&path.to_string_lossy()))
+    } else {
+        path.to_path_buf()
+    }
+}
+
+fn long_path_text(raw: &str) -> String {
+    const LONG_PATH_THRESHOLD: usize = 240;
+    if raw.len() < LONG_PATH_THRESHOLD || raw.starts_with("\\\\?\\") {
+        return raw.to_string();
+    }
+    let raw = raw.replace('/', "\\");
+    if let Some(share) = raw.strip_prefix("\\\\") {
+        return format!("\\\\?\\UNC\\{share}");
+    }
+    let bytes = raw.as_bytes();
+    if bytes.len() > 2 && bytes[1] == b':' && bytes[2] == b'\\' {
+        return format!("\\\\?\\{raw}");
+    }
+    raw
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn drive_root(path: &Path) -> Option<String> {
+    let text = path.to_string_lossy();
+    let bytes = text.as_bytes();
+    (bytes.len() >= 2 && bytes[1] == b':' && bytes[0].is_ascii_alphabetic())
+        .then(|| format!("{}:", (bytes[0] as char).to_ascii_uppercase()))
+}
+
+#[cfg(windows)]
+fn to_wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+#[cfg(windows)]
+fn is_network_drive(path: &Path) -> bool {
+    const DRIVE_REMOTE: u32 = 4;
+    let Some(drive) = drive_root(path) else {
+        return false;
+    };
+    let root = to_wide(&format!("{drive}\\"));
+    // SAFETY: `root` is NUL-terminated and outlives the call.
+    unsafe { windows_sys::Win32::Storage::FileSystem::GetDriveTypeW(root.as_ptr()) == DRIVE_REMOTE }
+}
+
+#[cfg(not(windows))]
+fn is_network_drive(_path: &Path) -> bool {
+    false
+}
+
+/// Maps a path on a `subst` drive to its real location so tools that resolve the
+/// drive differently (npm, services) all see the same folder.
+#[cfg(windows)]
+fn resolve_subst_drive(path: &Path) -> Option<PathBuf> {
+    let drive = drive_root(path)?;
+    let device = to_wide(&drive);
+    let mut buffer = vec![0u16; 1024];
+    // SAFETY: both buffers are valid for the lengths passed.
+    let len = unsafe {
+        windows_sys::Win32::Storage::FileSystem::QueryDosDeviceW(
+            device.as_ptr(),
+            buffer.as_mut_ptr(),
+            buffer.len() as u32,
+        )
+    };
+    if len == 0 {
+        return None;
+    }
+    let end = buffer.iter().position(|&c| c == 0).unwrap_or(len as usize);
+    let target = String::from_utf16_lossy(&buffer[..end]);
+    subst_target(&target, path, &drive)
+}
+
+#[cfg(not(windows))]
+fn resolve_subst_drive(_path: &Path) -> Option<PathBuf> {
+    None
+}
+
+// Subst drives map to `\??\<real path>`; real volumes map to `\Device\...`.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn subst_target(device_target: &str, path: &Path, drive: &str) -> Option<PathBuf> {
+    let real = device_target.strip_prefix("\\??\\")?;
+    let text = path.to_string_lossy();
+    let tail = text[drive.len()..].trim_start_matches('\\');
+    let real = strip_verbatim_prefix(real);
+    let real = real.trim_end_matches('\\');
+    Some(if tail.is_empty() {
+        PathBuf::from(format!("{real}\\"))
+    } else {
+        PathBuf::from(format!("{real}\\{tail}"))
+    })
 }
 
 pub fn expand_env_vars(raw: &str) -> Result<String> {
@@ -128,3 +432,90 @@ pub fn expand_env_vars(raw: &str) -> Result<String> {
     }
     Ok(output)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ensure_spawn_compatible, is_reparse_point, is_unc_path, long_path_text, needs_short_path,
+        spawn_safe_path, strip_verbatim_prefix, subst_target, walk_managed,
+    };
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn verbatim_and_unc_prefixes_are_recognized() {
+        assert_eq!(strip_verbatim_prefix("\\\\?\\C:\\oc"), "C:\\oc");
+        assert_eq!(
+            strip_verbatim_prefix("\\\\?\\UNC\\nas\\share\\oc"),
+            "\\\\nas\\share\\oc"
+        );
+        assert!(is_unc_path(Path::new("\\\\nas\\share\\oc")));
+        assert!(is_unc_path(Path::new("//nas/share")));
+        assert!(!is_unc_path(Path::new("\\\\?\\C:\\oc")));
+        assert!(!is_unc_path(Path::new("D:\\oc")));
+    }
+
+    #[test]
+    fn spawn_compatibility_of_cjk_and_space_paths() {
+        let dir = std::env::temp_dir().join(format!("用户 数据 {}", std::process::id()));
+        fs::create_dir_all(&dir).expect("cjk temp dir");
+        assert!(needs_short_path(&dir));
+        assert!(ensure_spawn_compatible(&dir).is_ok());
+        // Without 8.3 names available the original path is used as-is.
+        assert!(spawn_safe_path(&dir).exists());
+        assert!(!needs_short_path(Path::new("C:\\OpenClaw\\home")));
+        assert!(ensure_spawn_compatible(Path::new("C:\\100%\\oc")).is_err());
+        assert!(ensure_spawn_compatible(Path::new("D:\\wow!\\oc")).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn long_paths_get_extended_prefix() {
+        let deep = format!("C:\\oc\\{}", "node_modules\\".repeat(20));
+        assert_eq!(long_path_text(&deep), format!("\\\\?\\{deep}"));
+        let share = format!("\\\\nas\\share\\{}", "x\\".repeat(130));
+        assert!(long_path_text(&share).starts_with("\\\\?\\UNC\\nas\\share\\"));
+        assert_eq!(long_path_text("C:\\short"), "C:\\short");
+    }
+
+    #[test]
+    fn subst_target_maps_drive_to_real_folder() {
+        assert_eq!(
+            subst_target("\\??\\C:\\Work\\oc", Path::new("S:\\openclaw"), "S:"),
+            Some(Path::new("C:\\Work\\oc\\openclaw").to_path_buf())
+        );
+        assert_eq!(
+            subst_target("\\Device\\HarddiskVolume3", Path::new("C:\\x"), "C:"),
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn walk_managed_skips_links_instead_of_following() {
+        let root = std::env::temp_dir().join(format!("oc-walk-{}", std::process::id()));
+        let outside = std::env::temp_dir().join(format!("oc-walk-out-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&outside);
+        fs::create_dir_all(root.join("sub")).expect("root");
+        fs::create_dir_all(&outside).expect("outside");
+        fs::write(root.join("sub").join("a.txt"), "a").expect("file");
+        fs::write(outside.join("secret.txt"), "s").expect("outside file");
+        std::os::unix::fs::symlink(&outside, root.join("link")).expect("symlink");
+        // A link back to the root would loop a walker that follows links.
+        std::os::unix::fs::symlink(&root, root.join("sub").join("loop")).expect("loop");
+
+        let walk = walk_managed(&root);
+        assert!(is_reparse_point(&root.join("link")));
+        assert!(!is_reparse_point(&root.join("sub")));
+        assert_eq!(walk.skipped_links.len(), 2);
+        assert!(walk
+            .entries
+            .iter()
+            .all(|e| !e.path().to_string_lossy().contains("secret")));
+        assert!(walk.entries.iter().any(|e| e.path().ends_with("a.txt")));
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&outside);
+    }
+}
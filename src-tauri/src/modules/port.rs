@@ -3,49 +3,85 @@ use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 
-use crate::models::PortStatus;
+use serde::Deserialize;
 
-use super::shell;
+use crate::models::{PortInspection, PortStatus};
 
-pub fn check_port(port: u16) -> Result<PortStatus> {
-    let target = format!(":{port}");
-    let out = shell::run_command("netstat", &["-ano", "-p", "tcp"], None, &[])?;
-    if out.code != 0 {
-        return Ok(PortStatus {
-            port,
-            in_use: false,
-            pid: None,
-            process_name: None,
-        });
-    }
+use super::{process, shell};
 
-    for line in out.stdout.lines() {
-        let compact = line.trim();
-        if !(compact.contains(&target) && compact.contains("LISTENING")) {
-            continue;
-        }
-        let parts: Vec<&str> = compact.split_whitespace().collect();
-        if let Some(last) = parts.last() {
-            if let Ok(pid) = last.parse::<u32>() {
-                return Ok(PortStatus {
-                    port,
-                    in_use: true,
-                    pid: Some(pid),
-                    process_name: shell::process_name_by_pid(pid),
-                });
-            }
-        }
-    }
+// System (PID 4) owns http.sys listeners; terminating it is never an option.
+const SYSTEM_PID_MAX: u32 = 4;
+const SUGGEST_PORT_RANGE: u16 = 50;
 
+pub fn check_port(port: u16) -> Result<PortStatus> {
+    let out = shell::run_command("netstat", &["-ano", "-p", "tcp"], None, &[])?;
+    let owner = if out.code == 0 {
+        parse_listeners(&out.stdout)
+            .into_iter()
+            .find(|(listen_port, _)| *listen_port == port)
+            .map(|(_, pid)| pid)
+    } else {
+        None
+    };
     Ok(PortStatus {
         port,
-        in_use: false,
-        pid: None,
-        process_name: None,
+        in_use: owner.is_some(),
+        pid: owner,
+        process_name: owner.and_then(shell::process_name_by_pid),
     })
 }
 
-pub fn release_port(port: u16) -> Result<String> {
+/// Describes who owns `port` so the UI can offer reuse, another port, or termination
+/// with the user's informed consent instead of killing the owner outright.
+pub fn inspect_port(port: u16) -> Result<PortInspection> {
+    let out = shell::run_command("netstat", &["-ano", "-p", "tcp"], None, &[])?;
+    let listeners = if out.code == 0 {
+        parse_listeners(&out.stdout)
+    } else {
+        vec![]
+    };
+    let owner = listeners
+        .iter()
+        .find(|(listen_port, _)| *listen_port == port)
+        .map(|(_, pid)| *pid);
+    let mut inspection = PortInspection {
+        port,
+        in_use: owner.is_some(),
+        pid: owner,
+        process_name: None,
+        executable_path: None,
+        command_line: None,
+        publisher: None,
+        signature_status: None,
+        looks_like_openclaw: false,
+        is_installer_gateway: false,
+        actions: vec![],
+        suggested_port: suggest_free_port(port, &listeners),
+    };
+    let Some(pid) = owner else {
+        return Ok(inspection);
+    };
+
+    inspection.process_name = shell::process_name_by_pid(pid);
+    if let Some(details) = owner_details(pid) {
+        inspection.executable_path = details.path;
+        inspection.command_line = details.command_line;
+        inspection.publisher = details.publisher.as_deref().and_then(signer_name);
+        inspection.signature_status = details.signature;
+    }
+    inspection.is_installer_gateway = process::running_pid() == Some(pid);
+    inspection.looks_like_openclaw = inspection.is_installer_gateway
+        || looks_like_openclaw(
+            inspection.process_name.as_deref().unwrap_or_default(),
+            inspection.command_line.as_deref().unwrap_or_default(),
+        );
+    inspection.actions = port_actions(pid, &inspection);
+    Ok(inspection)
+}
+
+/// Terminates the owner of `port`, but only while it is still `expected_pid`, the owner
+/// the user was shown. A listener that changed hands since is left alone.
+pub fn release_port(port: u16, expected_pid: u32) -> Result<String> {
     let status = check_port(port)?;
     if !status.in_use {
         return Ok(format!("Port {port} is already free."));
@@ -53,6 +89,7 @@ pub fn release_port(port: u16) -> Result<String> {
     let pid = status
         .pid
         .ok_or_else(|| anyhow!("Port {port} is in use but PID cannot be resolved."))?;
+    ensure_releasable(port, pid, expected_pid)?;
     let pid_text = pid.to_string();
     let out = shell::run_command("taskkill", &["/PID", &pid_text, "/T", "/F"], None, &[])?;
     if out.code != 0 {
@@ -78,3 +115,200 @@ pub fn release_port(port: u16) -> Result<String> {
         "Port {port} is still in use after terminating PID {pid}."
     ))
 }
+
+fn ensure_releasable(port: u16, owner: u32, expected_pid: u32) -> Result<()> {
+    if owner <= SYSTEM_PID_MAX {
+        return Err(anyhow!(
+            "Port {port} is held by a Windows system process (PID {owner}) and cannot be released."
+        ));
+    }
+    if owner != expected_pid {
+        return Err(anyhow!(
+            "Port {port} is now owned by PID {owner}, not PID {expected_pid}. Check the port again before releasing it."
+        ));
+    }
+    Ok(())
+}
+
+/// Listening TCP sockets from `netstat -ano` as (local port, owning PID).
+fn parse_listeners(netstat: &str) -> Vec<(u16, u32)> {
+    netstat
+        .lines()
+        .filter_map(|line| {
+            let parts = line.split_whitespace().collect::<Vec<_>>();
+            if parts.len() < 5 || !parts[0].eq_ignore_ascii_case("tcp") {
+                return None;
+            }
+            // The state column is localized on some Windows builds, so rely on the
+            // foreign address: listeners always show port 0 there.
+            if !parts[2].ends_with(":0") && !parts[3].eq_ignore_ascii_case("LISTENING") {
+                return None;
+            }
+            let port = parts[1].rsplit(':').next()?.parse::<u16>().ok()?;
+            let pid = parts.last()?.parse::<u32>().ok()?;
+            Some((port, pid))
+        })
+        .collect()
+}
+
+fn suggest_free_port(port: u16, listeners: &[(u16, u32)]) -> Option<u16> {
+    (1..=SUGGEST_PORT_RANGE)
+        .filter_map(|offset| port.checked_add(offset))
+        .find(|candidate| !listeners.iter().any(|(used, _)| used == candidate))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct OwnerDetails {
+    path: Option<String>,
+    command_line: Option<String>,
+    publisher: Option<String>,
+    signature: Option<String>,
+}
+
+fn owner_details(pid: u32) -> Option<OwnerDetails> {
+    let script = format!(
+        "$p = Get-CimInstance Win32_Process -Filter 'ProcessId={pid}'; if ($p) {{ $s = if ($p.ExecutablePath) {{ Get-AuthenticodeSignature -LiteralPath $p.ExecutablePath }}; [pscustomobject]@{{ Path = $p.ExecutablePath; CommandLine = $p.CommandLine; Publisher = $s.SignerCertificate.Subject; Signature = if ($s) {{ [string]$s.Status }} }} | ConvertTo-Json -Compress }}"
+    );
+    let out = shell::run_command(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", &script],
+        None,
+        &[],
+    )
+    .ok()?;
+    if out.code != 0 {
+        return None;
+    }
+    serde_json::from_str(out.stdout.trim()).ok()
+}
+
+/// Reduces a certificate subject to its organization or common name.
+fn signer_name(subject: &str) -> Option<String> {
+    let field = |key: &str| {
+        subject.split(',').find_map(|part| {
+            let (k, v) = part.trim().split_once('=')?;
+            k.trim()
+                .eq_ignore_ascii_case(key)
+                .then(|| v.trim().trim_matches('"').to_string())
+        })
+    };
+    field("O")
+        .or_else(|| field("CN"))
+        .filter(|name| !name.is_empty())
+}
+
+fn looks_like_openclaw(process_name: &str, command_line: &str) -> bool {
+    let cmd = command_line.to_ascii_lowercase();
+    process::is_gateway_runtime_name(process_name)
+        && cmd.contains("openclaw")
+        && cmd.contains("gateway")
+}
+
+fn port_actions(pid: u32, inspection: &PortInspection) -> Vec<String> {
+    let mut actions = vec![];
+    if inspection.looks_like_openclaw {
+        actions.push("reuse_gateway".to_string());
+    }
+    if inspection.suggested_port.is_some() {
+        actions.push("pick_port".to_string());
+    }
+    if pid > SYSTEM_PID_MAX {
+        actions.push("terminate".to_string());
+    }
+    actions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_port, parse_listeners, release_port, signer_name, suggest_free_port};
+    use crate::modules::shell::{testing::RecordingRunner, with_runner};
+
+    const NETSTAT_LISTENING: &str = "  Proto  Local Address          Foreign Address        State           PID\n  TCP    127.0.0.1:28789        0.0.0.0:0              LISTENING       4321\n";
+
+    #[test]
+    fn check_port_resolves_listener_pid_and_name() {
+        let runner = RecordingRunner::new();
+        runner.respond("netstat", 0, NETSTAT_LISTENING);
+        runner.respond(
+            "tasklist",
+            0,
+            "\"node.exe\",\"4321\",\"Console\",\"1\",\"80,000 K\"",
+        );
+        let status = with_runner(runner.clone(), || check_port(28789)).expect("port status");
+        assert!(status.in_use);
+        assert_eq!(status.pid, Some(4321));
+        assert_eq!(status.process_name.as_deref(), Some("node.exe"));
+        assert_eq!(
+            runner.lines(),
+            vec![
+                "netstat -ano -p tcp",
+                "tasklist /FI PID eq 4321 /FO CSV /NH",
+            ]
+        );
+    }
+
+    #[test]
+    fn release_port_kills_listener_tree_then_rechecks() {
+        let runner = RecordingRunner::new();
+        runner.respond("netstat", 0, NETSTAT_LISTENING);
+        runner.respond("netstat", 0, "");
+        let message = with_runner(runner.clone(), || release_port(28789, 4321)).expect("released");
+        assert!(message.contains("4321"));
+        let lines = runner.lines();
+        assert_eq!(lines[2], "taskkill /PID 4321 /T /F");
+        assert_eq!(
+            lines.last().map(String::as_str),
+            Some("netstat -ano -p tcp")
+        );
+    }
+
+    #[test]
+    fn release_port_refuses_a_changed_owner_or_system_pid() {
+        let runner = RecordingRunner::new();
+        runner.respond("netstat", 0, NETSTAT_LISTENING);
+        let err = with_runner(runner.clone(), || release_port(28789, 999)).unwrap_err();
+        assert!(err.to_string().contains("PID 4321"));
+        assert!(!runner
+            .lines()
+            .iter()
+            .any(|line| line.starts_with("taskkill")));
+
+        let runner = RecordingRunner::new();
+        runner.respond(
+            "netstat",
+            0,
+            "  TCP    0.0.0.0:80             0.0.0.0:0              LISTENING       4\n",
+        );
+        assert!(with_runner(runner.clone(), || release_port(80, 4)).is_err());
+        assert!(!runner
+            .lines()
+            .iter()
+            .any(|line| line.starts_with("taskkill")));
+    }
+
+    #[test]
+    fn parse_listeners_matches_exact_local_port() {
+        let netstat = "  TCP    0.0.0.0:8080           0.0.0.0:0              LISTENING       900\n  TCP    127.0.0.1:80           0.0.0.0:0              ABHÖREN         4\n  TCP    127.0.0.1:50000        10.0.0.2:80            ESTABLISHED     77\n  TCP    [::]:28789             [::]:0                 LISTENING       4321\n";
+        assert_eq!(
+            parse_listeners(netstat),
+            vec![(8080, 900), (80, 4), (28789, 4321)]
+        );
+        let listeners = parse_listeners(netstat);
+        assert_eq!(suggest_free_port(8079, &listeners), Some(8081));
+        assert_eq!(suggest_free_port(65535, &listeners), None);
+    }
+
+    #[test]
+    fn signer_name_prefers_organization() {
+        assert_eq!(
+            signer_name("CN=OpenJS Foundation, O=OpenJS Foundation, L=San Francisco, C=US"),
+            Some("OpenJS Foundation".to_string())
+        );
+        assert_eq!(
+            signer_name("CN=Contoso Dev"),
+            Some("Contoso Dev".to_string())
+        );
+        assert_eq!(signer_name(""), None);
+    }
+}
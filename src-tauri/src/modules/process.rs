@@ -1,27 +1,65 @@
 use std::collections::BTreeMap;
 use std::fs::{self, OpenOptions};
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::{Mutex, OnceLock};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 
-use crate::models::{HealthResult, InstallerStatus, OpenClawFileConfig, ProcessControlResult};
+use crate::models::{
+    BootReport, HealthResult, InstallState, InstallerStatus, OpenClawFileConfig,
+    ProcessControlResult, StartOutcome,
+};
 
-use super::{config, health, logger, model_identity, paths, shell, state_store};
+use super::{
+    cli_shim, config, config_watch, daemon, env_diff, file_audit, gateway_service, health, logger,
+    model_identity, network_wait, paths, profiles, quiet_hours, secrets, shell, startup,
+    state_store, status_stream, tunnel, uptime, watchdog, workspace_git,
+};
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(400);
+const STDERR_TAIL_LINES: usize = 20;
+const LIVE_LOG_ROTATION_POLL: Duration = Duration::from_secs(60);
+const STDERR_TAIL_BYTES: u64 = 16 * 1024;
+// Two status probes apart, so a single failed health check never triggers autostart.
+const AUTOSTART_DEBOUNCE_MS: u128 = 5_000;
 const DETACHED_PROCESS: u32 = 0x00000008;
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 // Break away from parent job to survive dev-runner/job kill-on-close on Windows.
 const CREATE_BREAKAWAY_FROM_JOB: u32 = 0x01000000;
 
 static LAST_AUTOSTART_ATTEMPT_MS: OnceLock<Mutex<u128>> = OnceLock::new();
+static DOWN_SINCE_MS: OnceLock<Mutex<Option<u128>>> = OnceLock::new();
+
+/// How long the gateway has been down across consecutive probes. Autostart waits for
+/// `AUTOSTART_DEBOUNCE_MS` so one missed health check does not spawn a second gateway.
+fn down_for_ms(now_ms: u128, running: bool) -> u128 {
+    let lock = DOWN_SINCE_MS.get_or_init(|| Mutex::new(None));
+    let mut since = lock.lock().unwrap_or_else(|e| e.into_inner());
+    if running {
+        *since = None;
+        return 0;
+    }
+    now_ms.saturating_sub(*since.get_or_insert(now_ms))
+}
+
+/// Clears the down-time and autostart debounce, which belong to the previous profile's
+/// gateway after a profile switch.
+pub fn forget_down_time() {
+    if let Some(lock) = DOWN_SINCE_MS.get() {
+        *lock.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+    if let Some(lock) = LAST_AUTOSTART_ATTEMPT_MS.get() {
+        *lock.lock().unwrap_or_else(|e| e.into_inner()) = 0;
+    }
+}
 
 fn should_attempt_autostart(now_ms: u128, min_interval_ms: u128) -> bool {
     let lock = LAST_AUTOSTART_ATTEMPT_MS.get_or_init(|| Mutex::new(0u128));
@@ -35,12 +73,20 @@ fn should_attempt_autostart(now_ms: u128, min_interval_ms: u128) -> bool {
 
 pub fn start() -> Result<ProcessControlResult> {
     paths::ensure_dirs()?;
+    if let Some(info) = gateway_service::detect() {
+        return start_via_service(&info);
+    }
+    if let Some(info) = daemon::detect() {
+        return start_via_daemon(&info);
+    }
     // Idempotent start: if PID is alive, do not spawn a duplicate process.
     if let Some(pid) = running_pid() {
         return Ok(ProcessControlResult {
             running: true,
             pid: Some(pid),
             message: format!("OpenClaw already running (PID {pid})"),
+            boot: None,
+            warm_up: None,
         });
     }
 
@@ -48,32 +94,14 @@ pub fn start() -> Result<ProcessControlResult> {
         .ok_or_else(|| anyhow!("Install state not found. Run install_openclaw first."))?;
     let cfg = config::read_current_config()?;
     let args = build_gateway_args(&cfg);
-    let runtime_command = resolve_runtime_command(&install.command_path)?;
-
-    let spawn_with_flags = |creation_flags: u32| -> Result<std::process::Child> {
-        let stdout_log = paths::logs_dir().join("openclaw-stdout.log");
-        let stderr_log = paths::logs_dir().join("openclaw-stderr.log");
-        let stdout = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(stdout_log)?;
-        let stderr = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(stderr_log)?;
-
-        let mut cmd = build_process_command(&runtime_command, &args)?;
-        cmd.stdout(Stdio::from(stdout));
-        cmd.stderr(Stdio::from(stderr));
-        cmd.current_dir(&install.install_dir);
-        for (k, v) in runtime_env(&cfg) {
-            cmd.env(k, v);
-        }
-        #[cfg(windows)]
-        {
-            cmd.creation_flags(creation_flags);
-        }
-        Ok(cmd.spawn()?)
+    let runtime_command = resolve_runtime_command(&install)?;
+
+    rotate_gateway_logs();
+    let stdout_log = paths::logs_dir().join("openclaw-stdout.log");
+    let stderr_log = paths::logs_dir().join("openclaw-stderr.log");
+    let log_offsets = (file_len(&stdout_log), file_len(&stderr_log));
+    let spawn_with_flags = |creation_flags: u32| {
+        spawn_gateway(&install, &cfg, &runtime_command, &args, creation_flags)
     };
 
     // Some job configurations disallow breakaway. In that case, prefer a detached
@@ -113,6 +141,10 @@ pub fn start() -> Result<ProcessControlResult> {
             }
         };
     write_pid(pid)?;
+    logger::mark_gateway_run("start", pid, &install.version);
+    env_diff::record_spawn_env(&runtime_env(&cfg));
+    uptime::record_start();
+    watchdog::note_started();
     // User intention: once started, keep it running unless explicitly ended via Maintenance.
     let _ = state_store::set_keep_running(true);
     logger::info(&format!(
@@ -120,26 +152,257 @@ pub fn start() -> Result<ProcessControlResult> {
         runtime_command
     ));
 
-    thread::sleep(Duration::from_millis(650));
+    let timeout_secs = state_store::load_run_prefs()
+        .map(|prefs| prefs.start_ready_timeout_secs)
+        .unwrap_or(20);
+    let boot = wait_until_ready(
+        pid,
+        &cfg,
+        log_offsets,
+        Duration::from_secs(timeout_secs.max(1)),
+    );
+    let message = match boot.outcome {
+        StartOutcome::Started => "OpenClaw process started.".to_string(),
+        StartOutcome::Starting => format!(
+            "OpenClaw process is still starting after {} s.",
+            boot.waited_ms / 1000
+        ),
+        StartOutcome::FailedAtBoot => {
+            remove_pid();
+            logger::error(&format!(
+                "OpenClaw exited during startup: {}",
+                boot.stderr_tail.as_deref().unwrap_or("no stderr output")
+            ));
+            "OpenClaw exited during startup. See the stderr tail for the cause.".to_string()
+        }
+    };
+    let message = if cli_shim::is_npx(&runtime_command) {
+        format!("{message} It runs through npx, which is slow; reinstall OpenClaw to restore the local command.")
+    } else {
+        message
+    };
+    if boot.outcome != StartOutcome::FailedAtBoot {
+        tunnel::start_if_enabled();
+    }
     Ok(ProcessControlResult {
-        running: true,
+        running: boot.outcome != StartOutcome::FailedAtBoot,
         pid: Some(pid),
-        message: "OpenClaw process started.".to_string(),
+        message,
+        boot: Some(boot),
+        warm_up: None,
+    })
+}
+
+/// Starts the gateway for the Windows service host, which keeps the child handle and
+/// supervises it directly instead of going through the PID file.
+pub fn spawn_service_gateway() -> Result<std::process::Child> {
+    let install = state_store::load_install_state()?
+        .ok_or_else(|| anyhow!("Install state not found. Run install_openclaw first."))?;
+    let cfg = config::read_current_config()?;
+    let args = build_gateway_args(&cfg);
+    let runtime_command = resolve_runtime_command(&install)?;
+    rotate_gateway_logs();
+    let child = spawn_gateway(&install, &cfg, &runtime_command, &args, CREATE_NO_WINDOW)?;
+    logger::mark_gateway_run("start", child.id(), &install.version);
+    env_diff::record_spawn_env(&runtime_env(&cfg));
+    Ok(child)
+}
+
+/// Splits and rotates the logs before a run; `start_gateway_log_rotation` keeps them
+/// within size while it runs.
+fn rotate_gateway_logs() {
+    logger::prepare_gateway_logs();
+}
+
+/// Rotates the gateway logs by size while the gateway runs. A service-managed gateway's
+/// logs are left to the service host (`service_host`), so two processes never rotate
+/// the same files.
+pub fn start_gateway_log_rotation(service_host: bool) {
+    thread::spawn(move || loop {
+        thread::sleep(LIVE_LOG_ROTATION_POLL);
+        let _tick = profiles::worker_tick();
+        if service_host || gateway_service::detect().is_none() {
+            logger::rotate_live_gateway_logs();
+        }
+    });
+}
+
+/// Spawns the gateway with stdout/stderr appended to the installer log files.
+fn spawn_gateway(
+    install: &InstallState,
+    cfg: &OpenClawFileConfig,
+    runtime_command: &str,
+    args: &[String],
+    creation_flags: u32,
+) -> Result<std::process::Child> {
+    let stdout = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(paths::logs_dir().join("openclaw-stdout.log"))?;
+    let stderr = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(paths::logs_dir().join("openclaw-stderr.log"))?;
+
+    let mut cmd = build_process_command(runtime_command, args)?;
+    cmd.stdout(Stdio::from(stdout));
+    cmd.stderr(Stdio::from(stderr));
+    cmd.current_dir(&install.install_dir);
+    for (k, v) in runtime_env(cfg) {
+        cmd.env(k, v);
+    }
+    #[cfg(windows)]
+    {
+        cmd.creation_flags(creation_flags);
+    }
+    Ok(cmd.spawn()?)
+}
+
+/// Moves gateway supervision to a Windows service so it survives logouts and reboots.
+pub fn install_service() -> Result<String> {
+    if daemon::detect().is_some() {
+        return Err(anyhow!(
+            "The OpenClaw scheduled-task daemon already manages the gateway. Remove it with `openclaw gateway uninstall` first."
+        ));
+    }
+    // The service runs its own gateway; a detached copy would hold the port.
+    if running_pid().is_some() {
+        stop()?;
+    }
+    gateway_service::install()?;
+    let _ = state_store::set_keep_running(true);
+    Ok(format!(
+        "Gateway service {} installed and started.",
+        gateway_service::SERVICE_NAME
+    ))
+}
+
+/// Removes the service; the installer's keep-running autostart takes over again.
+pub fn uninstall_service() -> Result<String> {
+    gateway_service::uninstall()?;
+    Ok(format!(
+        "Gateway service {} removed.",
+        gateway_service::SERVICE_NAME
+    ))
+}
+
+/// Polls until the spawned gateway answers on its port or logs that it is listening,
+/// exits, or `timeout` passes.
+fn wait_until_ready(
+    pid: u32,
+    cfg: &OpenClawFileConfig,
+    (stdout_offset, stderr_offset): (u64, u64),
+    timeout: Duration,
+) -> BootReport {
+    let stdout_log = paths::logs_dir().join("openclaw-stdout.log");
+    let stderr_log = paths::logs_dir().join("openclaw-stderr.log");
+    let started = Instant::now();
+    let outcome = loop {
+        thread::sleep(READY_POLL_INTERVAL);
+        if shell::process_name_by_pid(pid).is_none() {
+            break StartOutcome::FailedAtBoot;
+        }
+        if health::tcp_ready(&cfg.bind_address, cfg.port)
+            || logged_listening(&read_from(&stdout_log, stdout_offset))
+        {
+            break StartOutcome::Started;
+        }
+        if started.elapsed() >= timeout {
+            break StartOutcome::Starting;
+        }
+    };
+    BootReport {
+        outcome,
+        waited_ms: started.elapsed().as_millis() as u64,
+        stderr_tail: tail_lines(&read_from(&stderr_log, stderr_offset), STDERR_TAIL_LINES),
+    }
+}
+
+fn logged_listening(stdout: &str) -> bool {
+    stdout.lines().any(|line| {
+        let line = line.to_ascii_lowercase();
+        line.contains("gateway") && line.contains("listening")
     })
 }
 
+fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Text appended to `path` since `offset`.
+fn read_from(path: &Path, offset: u64) -> String {
+    let Ok(mut file) = fs::File::open(path) else {
+        return String::new();
+    };
+    let mut buf = Vec::new();
+    if file.seek(SeekFrom::Start(offset)).is_err() || file.read_to_end(&mut buf).is_err() {
+        return String::new();
+    }
+    String::from_utf8_lossy(&buf).to_string()
+}
+
+/// Last lines the gateway wrote to stderr, for crash reports.
+pub fn gateway_stderr_tail() -> Option<String> {
+    let path = paths::logs_dir().join("openclaw-stderr.log");
+    let text = read_from(&path, file_len(&path).saturating_sub(STDERR_TAIL_BYTES));
+    tail_lines(&text, STDERR_TAIL_LINES)
+}
+
+fn tail_lines(text: &str, count: usize) -> Option<String> {
+    let lines = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty() && !logger::is_gateway_marker(line))
+        .collect::<Vec<_>>();
+    let start = lines.len().saturating_sub(count);
+    let tail = lines[start..].join("\n");
+    (!tail.is_empty()).then_some(tail)
+}
+
 pub fn stop() -> Result<ProcessControlResult> {
-    if let Some(pid) = read_pid() {
+    status_stream::note_stop_requested();
+    watchdog::reset();
+    // The tunnel only forwards to the gateway, so it never outlives it.
+    if let Err(err) = tunnel::stop_tunnel() {
+        logger::warn(&format!("Failed to stop tunnel: {err}"));
+    }
+    if gateway_service::detect().is_some() {
+        gateway_service::stop()?;
+        remove_pid();
+        return Ok(ProcessControlResult {
+            running: false,
+            pid: None,
+            message: "OpenClaw service stopped.".to_string(),
+            boot: None,
+            warm_up: None,
+        });
+    }
+    if daemon::detect().is_some() {
+        daemon::stop()?;
+        // A PID file left from before the daemon was installed no longer applies.
+        remove_pid();
+        return Ok(ProcessControlResult {
+            running: false,
+            pid: None,
+            message: "OpenClaw daemon stopped.".to_string(),
+            boot: None,
+            warm_up: None,
+        });
+    }
+    if let Some(pid) = running_pid() {
         let pid_text = pid.to_string();
         // /T ensures child processes are also terminated.
         let out = shell::run_command("taskkill", &["/PID", &pid_text, "/T", "/F"], None, &[])?;
         if out.code == 0 {
             remove_pid();
+            logger::mark_gateway_run("stop", pid, &installed_version());
             logger::info(&format!("OpenClaw process stopped, PID {pid}."));
             return Ok(ProcessControlResult {
                 running: false,
                 pid: Some(pid),
                 message: "Process stopped.".to_string(),
+                boot: None,
+                warm_up: None,
             });
         }
         return Err(anyhow!(
@@ -155,6 +418,8 @@ pub fn stop() -> Result<ProcessControlResult> {
         running: false,
         pid: None,
         message: "Process is not running.".to_string(),
+        boot: None,
+        warm_up: None,
     })
 }
 
@@ -167,14 +432,84 @@ pub fn end_openclaw() -> Result<ProcessControlResult> {
         pid: result.pid,
         message: "OpenClaw ended by user. It will stay stopped until you click Start again."
             .to_string(),
+        boot: None,
+        warm_up: None,
     })
 }
 
 pub fn restart() -> Result<ProcessControlResult> {
+    if gateway_service::detect().is_some() {
+        gateway_service::restart()?;
+        tunnel::start_if_enabled();
+        return Ok(ProcessControlResult {
+            running: true,
+            pid: None,
+            message: "OpenClaw service restarted.".to_string(),
+            boot: None,
+            warm_up: None,
+        });
+    }
+    if daemon::detect().is_some() {
+        daemon::restart()?;
+        uptime::record_start();
+        tunnel::start_if_enabled();
+        return Ok(ProcessControlResult {
+            running: true,
+            pid: None,
+            message: "OpenClaw daemon restarted.".to_string(),
+            boot: None,
+            warm_up: None,
+        });
+    }
     let _ = stop();
     start()
 }
 
+fn start_via_service(info: &gateway_service::ServiceInfo) -> Result<ProcessControlResult> {
+    if !info.is_running() {
+        // The service host records the start in the uptime log itself.
+        gateway_service::start()?;
+    }
+    let _ = state_store::set_keep_running(true);
+    tunnel::start_if_enabled();
+    Ok(ProcessControlResult {
+        running: true,
+        pid: None,
+        message: if info.is_running() {
+            "OpenClaw service already running.".to_string()
+        } else {
+            "OpenClaw service started.".to_string()
+        },
+        boot: None,
+        warm_up: None,
+    })
+}
+
+fn start_via_daemon(info: &daemon::DaemonInfo) -> Result<ProcessControlResult> {
+    if info.is_running() {
+        return Ok(ProcessControlResult {
+            running: true,
+            pid: None,
+            message: format!("OpenClaw daemon already running ({}).", info.task_name),
+            boot: None,
+            warm_up: None,
+        });
+    }
+    // The daemon owns the gateway lifecycle; spawning our own copy would fight it for the port.
+    daemon::start()?;
+    uptime::record_start();
+    watchdog::note_started();
+    let _ = state_store::set_keep_running(true);
+    tunnel::start_if_enabled();
+    Ok(ProcessControlResult {
+        running: true,
+        pid: None,
+        message: "OpenClaw daemon started.".to_string(),
+        boot: None,
+        warm_up: None,
+    })
+}
+
 pub async fn status() -> Result<InstallerStatus> {
     // Best-effort: keep OpenClaw running unless user explicitly ended it.
     // This is throttled to avoid repeated spawn storms on misconfiguration.
@@ -194,6 +529,7 @@ pub async fn status() -> Result<InstallerStatus> {
         install_dir: String::new(),
         launch_args: "gateway".to_string(),
         updated_at: String::new(),
+        provider_keys: vec![],
     });
     let install = state_store::load_install_state()?.unwrap_or(crate::models::InstallState {
         method: crate::models::SourceMethod::Npm,
@@ -202,39 +538,55 @@ pub async fn status() -> Result<InstallerStatus> {
         command_path: String::new(),
         version: "unknown".to_string(),
         launch_args: "gateway".to_string(),
+        shim_path: None,
+        verified_package: None,
     });
     let pid = running_pid();
     let health_result = health::health_check(&cfg.bind_address, cfg.port)
         .await
         .unwrap_or_else(|_| HealthResult::default());
     let running = pid.is_some() || health_result.ok;
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0u128);
+    let down_ms = down_for_ms(now_ms, running);
 
-    if !running && prefs.keep_running {
-        let now_ms = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| d.as_millis())
-            .unwrap_or(0u128);
-        if should_attempt_autostart(now_ms, 20_000) {
-            if let Ok(Some(_)) = state_store::load_install_state() {
-                if paths::config_path().exists() {
-                    if let Err(err) = start() {
-                        logger::warn(&format!("Auto-start OpenClaw failed: {err}"));
-                    }
+    // Autostart and the CLI/service probes below launch processes; the polls made while
+    // the window is still loading skip them so it paints first.
+    let settled = startup::settled();
+    if !running
+        && settled
+        && prefs.keep_running
+        && down_ms >= AUTOSTART_DEBOUNCE_MS
+        && watchdog::may_autostart()
+        && should_attempt_autostart(now_ms, 20_000)
+        && network_wait::ready_for_autostart(&prefs).await
+    {
+        if let Ok(Some(_)) = state_store::load_install_state() {
+            if paths::config_path().exists() {
+                if let Err(err) = start() {
+                    logger::warn(&format!("Auto-start OpenClaw failed: {err}"));
                 }
             }
         }
     }
 
-    let version = if install.version.trim().is_empty() || install.version == "unknown" {
-        detect_global_version().unwrap_or_else(|| "unknown".to_string())
-    } else {
-        install.version
-    };
+    let version = status_version(&install.version, settled, detect_global_version);
     let pid = running_pid();
-    let health_result = health::health_check(&cfg.bind_address, cfg.port)
+    let mut health_result = health::health_check(&cfg.bind_address, cfg.port)
         .await
         .unwrap_or_else(|_| HealthResult::default());
+    health::measure_response_time(&cfg.bind_address, cfg.port, &mut health_result).await;
     let running = pid.is_some() || health_result.ok;
+    watchdog::observe(running, prefs.keep_running);
+    // Polls during launch only report; the disk-heavy housekeeping waits for first paint.
+    if settled {
+        workspace_git::auto_commit_if_due();
+        file_audit::scan_if_due(running);
+        config_watch::check_for_changes();
+        quiet_hours::enforce_if_due();
+    }
     Ok(InstallerStatus {
         running,
         pid,
@@ -243,13 +595,29 @@ pub async fn status() -> Result<InstallerStatus> {
         current_model: cfg.model_chain.primary,
         port: cfg.port,
         health: health_result,
+        tunnel: tunnel::tunnel_status(),
+        managed_by: if !settled {
+            "installer".to_string()
+        } else if gateway_service::detect().is_some() {
+            "service".to_string()
+        } else if daemon::detect().is_some() {
+            "daemon".to_string()
+        } else {
+            "installer".to_string()
+        },
+        config_alerts: config_watch::unacknowledged_count(),
+        trial_mode: state_store::load_last_config()
+            .ok()
+            .flatten()
+            .is_some_and(|last| last.trial_mode),
+        watchdog: watchdog::current(),
     })
 }
 
 pub fn clear_cache() -> Result<String> {
     let cache = paths::openclaw_home().join("cache");
     if cache.exists() {
-        fs::remove_dir_all(&cache)?;
+        fs::remove_dir_all(paths::long_path(&cache))?;
     }
     fs::create_dir_all(&cache)?;
     logger::info("Cache directory reset.");
@@ -259,12 +627,12 @@ pub fn clear_cache() -> Result<String> {
 pub fn clear_sessions() -> Result<String> {
     let sessions = paths::openclaw_home().join("sessions");
     if sessions.exists() {
-        fs::remove_dir_all(&sessions)?;
+        fs::remove_dir_all(paths::long_path(&sessions))?;
     }
     fs::create_dir_all(&sessions)?;
     let memory = paths::openclaw_home().join("memory");
     if memory.exists() {
-        fs::remove_dir_all(&memory)?;
+        fs::remove_dir_all(paths::long_path(&memory))?;
     }
     fs::create_dir_all(&memory)?;
     logger::info("Session and memory directories reset.");
@@ -272,11 +640,20 @@ pub fn clear_sessions() -> Result<String> {
 }
 
 pub fn running_pid() -> Option<u32> {
-    let pid = read_pid()?;
-    if shell::is_process_alive(pid) {
-        Some(pid)
+    let record = read_pid()?;
+    if is_our_process(&record) {
+        if record.start_ticks.is_none() {
+            // Verified the slow way once; record the creation time for the next polls.
+            if let Some(ticks) = shell::process_start_ticks(record.pid) {
+                let _ = write_pid_record(&PidRecord {
+                    start_ticks: Some(ticks),
+                    ..record.clone()
+                });
+            }
+        }
+        Some(record.pid)
     } else {
-        // Remove stale PID from crash/forced kill cases.
+        // Remove stale PID from crash/forced kill cases, or a PID reused by another process.
         remove_pid();
         None
     }
@@ -433,7 +810,17 @@ fn launch_detached_via_powershell(
     Ok(pid)
 }
 
-fn runtime_env(cfg: &OpenClawFileConfig) -> Vec<(String, String)> {
+/// Version recorded at install, for run markers.
+pub fn installed_version() -> String {
+    state_store::load_install_state()
+        .ok()
+        .flatten()
+        .map(|state| state.version)
+        .unwrap_or_default()
+}
+
+/// Environment overrides every gateway spawn gets on top of the installer's own.
+pub fn runtime_env(cfg: &OpenClawFileConfig) -> Vec<(String, String)> {
     let mut envs = vec![
         (
             "OPENCLAW_CONFIG_PATH".to_string(),
@@ -458,19 +845,23 @@ fn runtime_env(cfg: &OpenClawFileConfig) -> Vec<(String, String)> {
             if value.is_empty() {
                 continue;
             }
-            if let Some(name) = model_identity::provider_env_name(provider.as_str()) {
+            for name in model_identity::provider_env_names(provider.as_str()) {
                 provider_env.insert(name, value.to_string());
             }
         }
     }
     // Backward compatibility for old single-key payloads.
     if !cfg.api_key.trim().is_empty() {
-        if let Some(name) = model_identity::provider_env_name(cfg.provider.as_str()) {
+        for name in model_identity::provider_env_names(cfg.provider.as_str()) {
             provider_env
                 .entry(name)
                 .or_insert_with(|| cfg.api_key.clone());
         }
     }
+    // Keys sealed with DPAPI sit in .env as markers; hand the gateway the real values.
+    for (name, value) in secrets::resolve_env_file(&paths::openclaw_home().join(".env")) {
+        provider_env.insert(name, value);
+    }
     for (k, v) in provider_env {
         envs.push((k, v));
     }
@@ -489,6 +880,19 @@ fn has_arg(args: &[String], name: &str) -> bool {
     args.iter().any(|item| item.eq_ignore_ascii_case(name))
 }
 
+/// The recorded version, else what a global `openclaw --version` reports. The probe
+/// waits until startup settled.
+fn status_version(recorded: &str, settled: bool, probe: impl FnOnce() -> Option<String>) -> String {
+    let recorded = recorded.trim();
+    if !recorded.is_empty() && recorded != "unknown" {
+        return recorded.to_string();
+    }
+    settled
+        .then(probe)
+        .flatten()
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
 fn detect_global_version() -> Option<String> {
     let cmd = shell::command_exists("openclaw")?;
     let out = shell::run_command(cmd.as_str(), &["--version"], None, &[]).ok()?;
@@ -506,9 +910,12 @@ fn parse_args(raw: &str) -> Vec<String> {
     raw.split_whitespace().map(|s| s.to_string()).collect()
 }
 
-fn resolve_runtime_command(preferred: &str) -> Result<String> {
-    let preferred = preferred.trim().trim_matches('"').to_string();
-    if is_runtime_command_usable(preferred.as_str()) {
+fn resolve_runtime_command(install: &InstallState) -> Result<String> {
+    if let Some(shim) = cli_shim::pinned_command(install) {
+        return Ok(shim);
+    }
+    let preferred = install.command_path.trim().trim_matches('"').to_string();
+    if !cli_shim::is_npx(&preferred) && is_runtime_command_usable(preferred.as_str()) {
         return Ok(preferred);
     }
 
@@ -530,7 +937,7 @@ fn resolve_runtime_command(preferred: &str) -> Result<String> {
     }
 
     if is_runtime_command_usable("npx") {
-        logger::warn("Runtime fallback to npx openclaw.");
+        cli_shim::note_npx_fallback("Gateway start");
         return Ok("npx".to_string());
     }
 
@@ -565,23 +972,247 @@ fn pid_file() -> PathBuf {
     paths::run_dir().join("openclaw.pid")
 }
 
+/// PID plus the identity of the process it referred to when written, so a
+/// reused PID after reboot is never mistaken for our gateway.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct PidRecord {
+    pid: u32,
+    created_at: Option<String>,
+    cmdline_hash: Option<String>,
+    /// Native creation time (`shell::process_start_ticks`); lets liveness checks skip the
+    /// PowerShell identity query. Missing in records written by older versions.
+    #[serde(default)]
+    start_ticks: Option<u64>,
+}
+
 fn write_pid(pid: u32) -> Result<()> {
+    let identity = shell::process_identity(pid);
+    if identity.is_none() {
+        logger::warn(&format!(
+            "Could not read process identity for PID {pid}; liveness checks fall back to process name."
+        ));
+    }
+    let record = PidRecord {
+        pid,
+        created_at: identity.as_ref().map(|id| id.created_at.clone()),
+        cmdline_hash: identity.as_ref().map(|id| cmdline_hash(&id.command_line)),
+        start_ticks: shell::process_start_ticks(pid),
+    };
+    write_pid_record(&record)
+}
+
+fn write_pid_record(record: &PidRecord) -> Result<()> {
     let path = pid_file();
     let mut file = OpenOptions::new()
         .create(true)
         .write(true)
         .truncate(true)
         .open(path)?;
-    file.write_all(pid.to_string().as_bytes())?;
+    file.write_all(serde_json::to_string(record)?.as_bytes())?;
     Ok(())
 }
 
-fn read_pid() -> Option<u32> {
+fn read_pid() -> Option<PidRecord> {
     let path = pid_file();
     let raw = fs::read_to_string(path).ok()?;
-    raw.trim().parse::<u32>().ok()
+    parse_pid_record(&raw)
+}
+
+/// PID of the gateway started from another profile's run folder, if it is still ours.
+pub fn gateway_pid_in(run_dir: &Path) -> Option<u32> {
+    let raw = fs::read_to_string(run_dir.join("openclaw.pid")).ok()?;
+    parse_pid_record(&raw)
+        .filter(is_our_process)
+        .map(|record| record.pid)
+}
+
+fn parse_pid_record(raw: &str) -> Option<PidRecord> {
+    if let Ok(record) = serde_json::from_str::<PidRecord>(raw) {
+        return Some(record);
+    }
+    // Legacy PID files hold only the number.
+    raw.trim().parse::<u32>().ok().map(|pid| PidRecord {
+        pid,
+        created_at: None,
+        cmdline_hash: None,
+        start_ticks: None,
+    })
+}
+
+fn is_our_process(record: &PidRecord) -> bool {
+    // Same creation time means the same process incarnation; no process spawn needed.
+    if let (Some(expected), Some(ticks)) =
+        (record.start_ticks, shell::process_start_ticks(record.pid))
+    {
+        return expected == ticks;
+    }
+    if !shell::is_process_alive(record.pid) {
+        return false;
+    }
+    match (&record.created_at, shell::process_identity(record.pid)) {
+        (Some(created_at), Some(identity)) => identity_matches(record, created_at, &identity),
+        // A bare legacy PID: the name alone would accept any reused node or cmd process.
+        (None, Some(identity)) => legacy_identity_matches(&identity),
+        // Nothing readable to compare; only trust runtimes that can host the gateway.
+        (_, None) => shell::process_name_by_pid(record.pid)
+            .map(|name| is_gateway_runtime_name(&name))
+            .unwrap_or(false),
+    }
+}
+
+fn legacy_identity_matches(identity: &shell::ProcessIdentity) -> bool {
+    let command_line = identity.command_line.to_ascii_lowercase();
+    command_line.contains("openclaw") && command_line.contains("gateway")
+}
+
+fn identity_matches(
+    record: &PidRecord,
+    created_at: &str,
+    identity: &shell::ProcessIdentity,
+) -> bool {
+    if identity.created_at != created_at {
+        return false;
+    }
+    match &record.cmdline_hash {
+        Some(hash) => *hash == cmdline_hash(&identity.command_line),
+        None => true,
+    }
+}
+
+pub fn is_gateway_runtime_name(name: &str) -> bool {
+    let lower = name.trim().to_ascii_lowercase();
+    [
+        "node.exe",
+        "bun.exe",
+        "openclaw.exe",
+        "powershell.exe",
+        "cmd.exe",
+    ]
+    .iter()
+    .any(|candidate| lower == *candidate)
+}
+
+fn cmdline_hash(command_line: &str) -> String {
+    // FNV-1a: stable across toolchains, unlike std's DefaultHasher.
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in command_line.trim().as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{hash:016x}")
 }
 
 fn remove_pid() {
     let _ = fs::remove_file(pid_file());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        cmdline_hash, identity_matches, is_runtime_command_usable, legacy_identity_matches,
+        logged_listening, parse_pid_record, status_version, tail_lines, PidRecord,
+    };
+    use crate::modules::shell::{testing::RecordingRunner, with_runner, ProcessIdentity};
+
+    #[test]
+    fn status_version_probes_the_cli_only_after_startup() {
+        let probe = || Some("2026.3.1".to_string());
+        assert_eq!(status_version("2026.2.9", false, probe), "2026.2.9");
+        assert_eq!(
+            status_version("unknown", false, || panic!("probed during startup")),
+            "unknown"
+        );
+        assert_eq!(status_version(" ", true, probe), "2026.3.1");
+        assert_eq!(status_version("", true, || None), "unknown");
+    }
+
+    #[test]
+    fn parse_pid_record_accepts_legacy_and_json() {
+        assert_eq!(
+            parse_pid_record("1234\n"),
+            Some(PidRecord {
+                pid: 1234,
+                created_at: None,
+                cmdline_hash: None,
+                start_ticks: None,
+            })
+        );
+        let json = r#"{"pid":42,"created_at":"2026-01-01T00:00:00.0000000Z","cmdline_hash":"ab"}"#;
+        let record = parse_pid_record(json).expect("json record");
+        assert_eq!(record.pid, 42);
+        assert_eq!(record.cmdline_hash.as_deref(), Some("ab"));
+        assert_eq!(record.start_ticks, None);
+        assert_eq!(parse_pid_record("garbage"), None);
+    }
+
+    #[test]
+    fn identity_must_match_creation_time_and_command_line() {
+        let identity = ProcessIdentity {
+            created_at: "2026-01-01T00:00:00.0000000Z".to_string(),
+            command_line: "node openclaw.mjs gateway".to_string(),
+        };
+        let record = PidRecord {
+            pid: 42,
+            created_at: Some(identity.created_at.clone()),
+            cmdline_hash: Some(cmdline_hash(&identity.command_line)),
+            start_ticks: None,
+        };
+        assert!(identity_matches(&record, &identity.created_at, &identity));
+
+        let reused = ProcessIdentity {
+            created_at: "2026-02-01T00:00:00.0000000Z".to_string(),
+            ..identity.clone()
+        };
+        assert!(!identity_matches(&record, &identity.created_at, &reused));
+
+        let other_cmd = ProcessIdentity {
+            command_line: "notepad.exe".to_string(),
+            ..identity.clone()
+        };
+        assert!(!identity_matches(&record, &identity.created_at, &other_cmd));
+    }
+
+    #[test]
+    fn legacy_pid_needs_a_gateway_command_line() {
+        let identity = |command_line: &str| ProcessIdentity {
+            created_at: "2026-01-01T00:00:00.0000000Z".to_string(),
+            command_line: command_line.to_string(),
+        };
+        assert!(legacy_identity_matches(&identity(
+            r#"cmd /D /C "C:\oc\installer-bin\openclaw.cmd" gateway --port 28789"#
+        )));
+        assert!(!legacy_identity_matches(&identity("node server.js")));
+        assert!(!legacy_identity_matches(&identity("cmd.exe /c build.bat")));
+    }
+
+    #[test]
+    fn boot_log_helpers_detect_listening_and_keep_stderr_tail() {
+        assert!(logged_listening(
+            "[plugins] loaded\n[gateway] listening on ws://127.0.0.1:28789\n"
+        ));
+        assert!(!logged_listening("[gateway] starting\n"));
+
+        let stderr = "line1\n\nline2\r\nline3\n";
+        assert_eq!(tail_lines(stderr, 2).as_deref(), Some("line2\nline3"));
+        assert_eq!(tail_lines("\n\n", 5), None);
+    }
+
+    #[test]
+    fn runtime_command_is_probed_with_version() {
+        let runner = RecordingRunner::new();
+        runner.respond("node", 1, "");
+        assert!(!with_runner(runner.clone(), || {
+            is_runtime_command_usable("node_modules/.bin/openclaw.cmd")
+        }));
+        assert!(with_runner(runner.clone(), || is_runtime_command_usable(
+            "openclaw"
+        )));
+        assert_eq!(
+            runner.lines(),
+            vec![
+                "node_modules/.bin/openclaw.cmd --version",
+                "openclaw --version"
+            ]
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+use crate::models::{ProcessNode, ProcessTree};
+
+use super::{config, logger, port, process, shell};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RawProcess {
+    process_id: u32,
+    parent_process_id: u32,
+    name: Option<String>,
+    command_line: Option<String>,
+    working_set_size: Option<u64>,
+    /// 100ns units.
+    user_mode_time: Option<u64>,
+    kernel_mode_time: Option<u64>,
+}
+
+pub fn get_process_tree() -> Result<ProcessTree> {
+    let Some(root) = gateway_root_pid() else {
+        return Ok(ProcessTree {
+            root_pid: None,
+            processes: vec![],
+        });
+    };
+    let snapshot = list_processes()?;
+    Ok(ProcessTree {
+        root_pid: Some(root),
+        processes: build_tree(root, &snapshot),
+    })
+}
+
+/// Kills one descendant of the gateway (and its own children), e.g. a hung headless
+/// browser. The gateway itself must be stopped through the normal stop/restart controls.
+pub fn kill_child_process(pid: u32) -> Result<String> {
+    // Re-read the tree right before killing so a PID that exited and was reused is rejected.
+    let tree = get_process_tree()?;
+    let node = find_killable(&tree, pid)?.clone();
+    let pid_text = pid.to_string();
+    let out = shell::run_command("taskkill", &["/PID", &pid_text, "/T", "/F"], None, &[])?;
+    shell::ensure_success("taskkill", &out)?;
+    logger::warn(&format!(
+        "Killed OpenClaw child process {} (PID {pid}).",
+        node.name
+    ));
+
+    let gateway_alive = tree.root_pid.map(shell::is_process_alive).unwrap_or(false);
+    if !gateway_alive {
+        logger::warn("OpenClaw gateway exited after killing a child process.");
+        return Ok(format!(
+            "Stopped {} (PID {pid}), but the gateway exited too. Start OpenClaw again.",
+            node.name
+        ));
+    }
+    Ok(format!(
+        "Stopped {} (PID {pid}). The gateway is still running.",
+        node.name
+    ))
+}
+
+fn find_killable(tree: &ProcessTree, pid: u32) -> Result<&ProcessNode> {
+    let Some(root) = tree.root_pid else {
+        return Err(anyhow!("OpenClaw gateway is not running."));
+    };
+    if pid == root {
+        return Err(anyhow!(
+            "PID {pid} is the gateway itself. Use Stop or Restart instead."
+        ));
+    }
+    tree.processes
+        .iter()
+        .find(|node| node.pid == pid && node.depth > 0)
+        .ok_or_else(|| {
+            anyhow!("PID {pid} is not a child of the OpenClaw gateway; refusing to kill it.")
+        })
+}
+
+pub fn gateway_root_pid() -> Option<u32> {
+    if let Some(pid) = process::running_pid() {
+        return Some(pid);
+    }
+    // Daemon-managed gateways have no PID file; find the listener on the gateway port.
+    let cfg = config::read_current_config().ok()?;
+    port::check_port(cfg.port).ok()?.pid
+}
+
+fn list_processes() -> Result<Vec<RawProcess>> {
+    let script = "Get-CimInstance Win32_Process | Select-Object ProcessId,ParentProcessId,Name,CommandLine,WorkingSetSize,UserModeTime,KernelModeTime | ConvertTo-Json -Compress";
+    let out = shell::run_command(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", script],
+        None,
+        &[],
+    )?;
+    shell::ensure_success("process list", &out)?;
+    parse_process_list(&out.stdout)
+}
+
+fn parse_process_list(raw: &str) -> Result<Vec<RawProcess>> {
+    let value: serde_json::Value = serde_json::from_str(raw.trim())?;
+    // ConvertTo-Json emits a bare object when only one item matches.
+    let items = match value {
+        serde_json::Value::Array(items) => items,
+        other => vec![other],
+    };
+    Ok(items
+        .into_iter()
+        .filter_map(|item| serde_json::from_value::<RawProcess>(item).ok())
+        .collect())
+}
+
+fn build_tree(root: u32, snapshot: &[RawProcess]) -> Vec<ProcessNode> {
+    let mut children = BTreeMap::<u32, Vec<&RawProcess>>::new();
+    for proc in snapshot {
+        // PID 0 (System Idle) reports itself as its own parent.
+        if proc.process_id != proc.parent_process_id {
+            children
+                .entry(proc.parent_process_id)
+                .or_default()
+                .push(proc);
+        }
+    }
+    let mut out = Vec::new();
+    let Some(root_proc) = snapshot.iter().find(|p| p.process_id == root) else {
+        return out;
+    };
+    let mut stack = vec![(root_proc, 0u32)];
+    while let Some((proc, depth)) = stack.pop() {
+        // Guard against PID-reuse cycles in the parent chain.
+        if out
+            .iter()
+            .any(|node: &ProcessNode| node.pid == proc.process_id)
+        {
+            continue;
+        }
+        out.push(to_node(proc, depth));
+        if let Some(kids) = children.get(&proc.process_id) {
+            for kid in kids.iter().rev() {
+                stack.push((kid, depth + 1));
+            }
+        }
+    }
+    out
+}
+
+fn to_node(proc: &RawProcess, depth: u32) -> ProcessNode {
+    let cpu_100ns = proc.user_mode_time.unwrap_or(0) + proc.kernel_mode_time.unwrap_or(0);
+    ProcessNode {
+        pid: proc.process_id,
+        parent_pid: proc.parent_process_id,
+        name: proc.name.clone().unwrap_or_default(),
+        command_line: proc.command_line.clone().unwrap_or_default(),
+        memory_bytes: proc.working_set_size.unwrap_or(0),
+        cpu_seconds: cpu_100ns as f64 / 10_000_000.0,
+        depth,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_tree, find_killable, parse_process_list};
+    use crate::models::ProcessTree;
+
+    #[test]
+    fn build_tree_collects_descendants_depth_first() {
+        let raw = r#"[
+            {"ProcessId":0,"ParentProcessId":0,"Name":"Idle"},
+            {"ProcessId":10,"ParentProcessId":1,"Name":"node.exe","WorkingSetSize":1000,"UserModeTime":20000000,"KernelModeTime":10000000},
+            {"ProcessId":11,"ParentProcessId":10,"Name":"chrome.exe"},
+            {"ProcessId":12,"ParentProcessId":11,"Name":"chrome.exe"},
+            {"ProcessId":13,"ParentProcessId":10,"Name":"cmd.exe"},
+            {"ProcessId":20,"ParentProcessId":1,"Name":"explorer.exe"}
+        ]"#;
+        let snapshot = parse_process_list(raw).expect("valid json");
+        let tree = build_tree(10, &snapshot);
+        let pids = tree.iter().map(|n| (n.pid, n.depth)).collect::<Vec<_>>();
+        assert_eq!(pids, vec![(10, 0), (11, 1), (12, 2), (13, 1)]);
+        assert_eq!(tree[0].cpu_seconds, 3.0);
+        assert_eq!(tree[0].memory_bytes, 1000);
+    }
+
+    #[test]
+    fn parse_process_list_accepts_single_object() {
+        let raw = r#"{"ProcessId":5,"ParentProcessId":4,"Name":"node.exe"}"#;
+        assert_eq!(parse_process_list(raw).expect("valid json").len(), 1);
+        assert!(build_tree(99, &parse_process_list(raw).expect("valid json")).is_empty());
+    }
+
+    #[test]
+    fn find_killable_only_accepts_descendants() {
+        let raw = r#"[
+            {"ProcessId":10,"ParentProcessId":1,"Name":"node.exe"},
+            {"ProcessId":11,"ParentProcessId":10,"Name":"chrome.exe"},
+            {"ProcessId":20,"ParentProcessId":1,"Name":"explorer.exe"}
+        ]"#;
+        let snapshot = parse_process_list(raw).expect("valid json");
+        let tree = ProcessTree {
+            root_pid: Some(10),
+            processes: build_tree(10, &snapshot),
+        };
+        assert_eq!(find_killable(&tree, 11).expect("child").name, "chrome.exe");
+        assert!(find_killable(&tree, 10).is_err());
+        assert!(find_killable(&tree, 20).is_err());
+
+        let stopped = ProcessTree {
+            root_pid: None,
+            processes: vec![],
+        };
+        assert!(find_killable(&stopped, 11).is_err());
+    }
+}
@@ -0,0 +1,377 @@
+use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{RwLock, RwLockReadGuard};
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+
+use crate::models::{ProfileInfo, ProfileRecord, ProfileRegistry};
+
+use super::{
+    daemon, gateway_service, keep_alive, logger, model_catalog, paths, process, skills,
+    state_store, watchdog,
+};
+
+/// Name shown for the profile that keeps the original layout at the app data root.
+pub const DEFAULT_PROFILE: &str = "default";
+
+const MAX_NAME_LEN: usize = 32;
+const OPENCLAW_HOME_ENV: &str = "OPENCLAW_INSTALLER_OPENCLAW_HOME";
+/// Gateway port of the default profile; new profiles count up from here.
+pub const BASE_GATEWAY_PORT: u16 = 28789;
+// The gateway also binds a few ports right above its own (browser relay, canvas).
+const PORT_STEP: u16 = 10;
+
+// Background workers hold this for one tick; a switch takes it exclusively, so no tick
+// reads one profile's state and acts on the other's.
+static SWITCH_LOCK: RwLock<()> = RwLock::new(());
+// Bumped on every switch so workers can drop what they remember about the old profile.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Held by a background worker for one iteration of its loop.
+pub fn worker_tick() -> RwLockReadGuard<'static, ()> {
+    SWITCH_LOCK.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Changes on every profile switch.
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::SeqCst)
+}
+
+/// Activates the profile selected in a previous session. Call before anything
+/// touches state or logs.
+pub fn restore_active() {
+    let registry = state_store::load_profiles().unwrap_or_default();
+    let active = registry
+        .active
+        .filter(|name| registry.profiles.iter().any(|p| &p.name == name));
+    paths::set_active_profile(active);
+}
+
+/// Points `OPENCLAW_INSTALLER_OPENCLAW_HOME` at the active profile's OpenClaw home.
+/// With `respect_external`, a value set before launch (e.g. dev scripts) is kept.
+pub fn bind_openclaw_home(respect_external: bool) {
+    // 1) Respect explicit overrides (e.g. custom dev launch scripts).
+    if respect_external {
+        if let Ok(value) = std::env::var(OPENCLAW_HOME_ENV) {
+            if !value.trim().is_empty() {
+                return;
+            }
+        }
+    }
+
+    // 2) If this installer has already installed OpenClaw, pin the home to that install_dir
+    //    to keep future runs consistent and isolated from any other OpenClaw on the machine.
+    if let Ok(Some(state)) = state_store::load_install_state() {
+        if !state.install_dir.trim().is_empty() {
+            if let Ok(dir) = paths::normalize_path(&state.install_dir) {
+                if !paths::is_user_profile_default_openclaw_dir(&dir) {
+                    std::env::set_var(OPENCLAW_HOME_ENV, dir.to_string_lossy().to_string());
+                    return;
+                }
+                logger::warn(&format!(
+                    "Ignoring legacy install_dir (unsafe): {}",
+                    dir.to_string_lossy()
+                ));
+            }
+        }
+    }
+
+    // 3) Default: an isolated per-user directory under LocalAppData (or the profile folder).
+    //    This avoids touching `%USERPROFILE%\\.openclaw` by default.
+    let fallback = paths::default_isolated_openclaw_home();
+    std::env::set_var(OPENCLAW_HOME_ENV, fallback.to_string_lossy().to_string());
+}
+
+pub fn list_profiles() -> Result<Vec<ProfileInfo>> {
+    let registry = state_store::load_profiles()?;
+    let active = paths::active_profile();
+    let mut out = vec![describe(&registry, None, active.is_none())];
+    for record in &registry.profiles {
+        out.push(describe(
+            &registry,
+            Some(&record.name),
+            active.as_deref() == Some(record.name.as_str()),
+        ));
+    }
+    Ok(out)
+}
+
+/// Creates `profiles/<name>/{state,logs,run,openclaw-home}`. The new profile starts
+/// empty; install and configure run against it once it is switched to, on a gateway
+/// port no other profile uses.
+pub fn create_profile(name: &str) -> Result<ProfileInfo> {
+    let name = validate_name(name)?;
+    let mut registry = state_store::load_profiles()?;
+    if registry.profiles.iter().any(|p| p.name == name) {
+        return Err(anyhow!("Profile '{name}' already exists."));
+    }
+    let root = paths::profile_root(Some(&name));
+    for dir in ["state", "logs", "run", "backups", "openclaw-home"] {
+        fs::create_dir_all(root.join(dir))?;
+    }
+    let taken: Vec<u16> = ports_in_use(&registry)
+        .into_iter()
+        .map(|(_, port)| port)
+        .collect();
+    let port = next_free_port(&taken);
+    registry.profiles.push(ProfileRecord {
+        name: name.clone(),
+        created_at: Local::now().to_rfc3339(),
+        port: Some(port),
+    });
+    state_store::save_profiles(&registry)?;
+    logger::info(&format!("Profile created: {name} (gateway port {port})"));
+    Ok(describe(&registry, Some(&name), false))
+}
+
+/// Makes `name` the active profile. A gateway started from the previous profile keeps
+/// running; status, start and stop now act on the new profile's gateway. Background
+/// workers are held off while the switch happens and forget what they tracked for the
+/// previous profile.
+pub fn switch_profile(name: &str) -> Result<ProfileInfo> {
+    let mut registry = state_store::load_profiles()?;
+    let target = if name.trim().eq_ignore_ascii_case(DEFAULT_PROFILE) {
+        None
+    } else {
+        let name = validate_name(name)?;
+        if !registry.profiles.iter().any(|p| p.name == name) {
+            return Err(anyhow!("Profile '{name}' does not exist."));
+        }
+        Some(name)
+    };
+    if target == paths::active_profile() {
+        return Ok(describe(&registry, target.as_deref(), true));
+    }
+
+    let _switching = SWITCH_LOCK.write().unwrap_or_else(|e| e.into_inner());
+    registry.active = target.clone();
+    state_store::save_profiles(&registry)?;
+    paths::set_active_profile(target.clone());
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    paths::ensure_dirs()?;
+    bind_openclaw_home(false);
+    logger::reload_log_rotation();
+    model_catalog::clear_model_catalog_cache();
+    skills::clear_skill_catalog_cache();
+    daemon::invalidate();
+    gateway_service::invalidate();
+    watchdog::forget_last_probe();
+    process::forget_down_time();
+    keep_alive::reset();
+    logger::info(&format!(
+        "Switched to profile {} (OpenClaw home: {}).",
+        target.as_deref().unwrap_or(DEFAULT_PROFILE),
+        paths::openclaw_home().to_string_lossy()
+    ));
+    Ok(describe(&registry, target.as_deref(), true))
+}
+
+/// Name of another profile whose gateway uses `port`, if any.
+pub fn port_owner(port: u16) -> Option<String> {
+    let registry = state_store::load_profiles().ok()?;
+    owner_of(port, &other_profiles_ports(&registry))
+}
+
+/// Port set aside for the active profile: the one assigned at creation, or the first
+/// free one for profiles created before ports were assigned.
+pub fn assigned_port() -> u16 {
+    let registry = state_store::load_profiles().unwrap_or_default();
+    let active = paths::active_profile();
+    let others = other_profiles_ports(&registry);
+    let assigned = match active.as_deref() {
+        Some(name) => record(&registry, Some(name)).and_then(|p| p.port),
+        None => Some(BASE_GATEWAY_PORT),
+    };
+    assigned
+        .filter(|port| owner_of(*port, &others).is_none())
+        .unwrap_or_else(|| {
+            let taken: Vec<u16> = others.iter().map(|(_, port)| *port).collect();
+            next_free_port(&taken)
+        })
+}
+
+/// `(profile, port)` of every registered profile, the default one included.
+pub fn gateway_ports() -> Vec<(String, u16)> {
+    ports_in_use(&state_store::load_profiles().unwrap_or_default())
+}
+
+fn other_profiles_ports(registry: &ProfileRegistry) -> Vec<(String, u16)> {
+    let active = paths::active_profile();
+    let active = active.as_deref().unwrap_or(DEFAULT_PROFILE);
+    ports_in_use(registry)
+        .into_iter()
+        .filter(|(name, _)| name != active)
+        .collect()
+}
+
+/// Removes a profile and everything under its folder. The active profile, the default
+/// profile and profiles whose gateway is still running are refused.
+pub fn delete_profile(name: &str) -> Result<()> {
+    let name = validate_name(name)?;
+    let mut registry = state_store::load_profiles()?;
+    if !registry.profiles.iter().any(|p| p.name == name) {
+        return Err(anyhow!("Profile '{name}' does not exist."));
+    }
+    if paths::active_profile().as_deref() == Some(name.as_str()) {
+        return Err(anyhow!(
+            "Profile '{name}' is active. Switch to another profile before deleting it."
+        ));
+    }
+    let root = paths::profile_root(Some(&name));
+    if let Some(pid) = process::gateway_pid_in(&root.join("run")) {
+        return Err(anyhow!(
+            "The gateway of profile '{name}' is still running (PID {pid}). Stop it first."
+        ));
+    }
+    if root.exists() {
+        fs::remove_dir_all(&root)?;
+    }
+    registry.profiles.retain(|p| p.name != name);
+    state_store::save_profiles(&registry)?;
+    logger::info(&format!("Profile deleted: {name}"));
+    Ok(())
+}
+
+fn describe(registry: &ProfileRegistry, name: Option<&str>, active: bool) -> ProfileInfo {
+    let data_dir = paths::profile_root(name);
+    let install = state_store::load_install_state_in(&data_dir.join("state"))
+        .ok()
+        .flatten();
+    let openclaw_home = if active {
+        paths::openclaw_home()
+    } else {
+        match &install {
+            Some(state) if !state.install_dir.trim().is_empty() => state.install_dir.clone().into(),
+            _ => paths::isolated_openclaw_home_for(name),
+        }
+    };
+    ProfileInfo {
+        name: name.unwrap_or(DEFAULT_PROFILE).to_string(),
+        active,
+        created_at: record(registry, name).map(|p| p.created_at.clone()),
+        installed: install.is_some(),
+        version: install.map(|state| state.version),
+        gateway_running: process::gateway_pid_in(&data_dir.join("run")).is_some(),
+        data_dir: data_dir.to_string_lossy().to_string(),
+        openclaw_home: openclaw_home.to_string_lossy().to_string(),
+        port: profile_port(registry, name),
+    }
+}
+
+fn record<'a>(registry: &'a ProfileRegistry, name: Option<&str>) -> Option<&'a ProfileRecord> {
+    let name = name?;
+    registry.profiles.iter().find(|p| p.name == name)
+}
+
+/// The port from the profile's last setup, else the one assigned when it was created.
+fn profile_port(registry: &ProfileRegistry, name: Option<&str>) -> u16 {
+    let state_dir = paths::profile_root(name).join("state");
+    let configured = fs::read_to_string(state_dir.join("last_config.json"))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+        .and_then(|value| value.get("port")?.as_u64())
+        .and_then(|port| u16::try_from(port).ok())
+        .filter(|port| *port != 0);
+    configured
+        .or_else(|| record(registry, name).and_then(|p| p.port))
+        .unwrap_or(BASE_GATEWAY_PORT)
+}
+
+/// `(profile, port)` of every profile, the default one included.
+fn ports_in_use(registry: &ProfileRegistry) -> Vec<(String, u16)> {
+    let mut out = vec![(DEFAULT_PROFILE.to_string(), profile_port(registry, None))];
+    for record in &registry.profiles {
+        out.push((
+            record.name.clone(),
+            profile_port(registry, Some(&record.name)),
+        ));
+    }
+    out
+}
+
+fn owner_of(port: u16, in_use: &[(String, u16)]) -> Option<String> {
+    in_use
+        .iter()
+        .find(|(_, used)| *used == port)
+        .map(|(name, _)| name.clone())
+}
+
+/// First port above the base that keeps `PORT_STEP` clear of every taken one.
+fn next_free_port(taken: &[u16]) -> u16 {
+    let mut port = BASE_GATEWAY_PORT;
+    while taken.iter().any(|used| used.abs_diff(port) < PORT_STEP) {
+        port = port.saturating_add(PORT_STEP);
+    }
+    port
+}
+
+/// Profile names become folder names: lowercase letters, digits, `-` and `_`.
+fn validate_name(name: &str) -> Result<String> {
+    let name = name.trim().to_ascii_lowercase();
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(anyhow!(
+            "Profile names must be 1 to {MAX_NAME_LEN} characters long."
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "Profile names may only contain letters, digits, '-' and '_'."
+        ));
+    }
+    if name == DEFAULT_PROFILE {
+        return Err(anyhow!("'{DEFAULT_PROFILE}' is reserved."));
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{next_free_port, owner_of, validate_name, BASE_GATEWAY_PORT};
+
+    #[test]
+    fn profile_names_are_safe_folder_names() {
+        assert_eq!(validate_name(" Work ").unwrap(), "work");
+        assert_eq!(validate_name("client_a-2").unwrap(), "client_a-2");
+        assert!(validate_name("").is_err());
+        assert!(validate_name("default").is_err());
+        assert!(validate_name("../state").is_err());
+        assert!(validate_name("a b").is_err());
+        assert!(validate_name(&"x".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn new_profiles_get_a_port_clear_of_the_others() {
+        assert_eq!(next_free_port(&[]), BASE_GATEWAY_PORT);
+        assert_eq!(next_free_port(&[BASE_GATEWAY_PORT]), BASE_GATEWAY_PORT + 10);
+        assert_eq!(
+            next_free_port(&[
+                BASE_GATEWAY_PORT,
+                BASE_GATEWAY_PORT + 10,
+                BASE_GATEWAY_PORT + 25
+            ]),
+            BASE_GATEWAY_PORT + 40
+        );
+        // A custom port right above the base blocks it as well.
+        assert_eq!(
+            next_free_port(&[BASE_GATEWAY_PORT + 3]),
+            BASE_GATEWAY_PORT + 20
+        );
+    }
+
+    #[test]
+    fn owner_of_names_the_profile_on_a_port() {
+        let in_use = vec![
+            ("default".to_string(), BASE_GATEWAY_PORT),
+            ("work".to_string(), BASE_GATEWAY_PORT + 10),
+        ];
+        assert_eq!(
+            owner_of(BASE_GATEWAY_PORT + 10, &in_use).as_deref(),
+            Some("work")
+        );
+        assert_eq!(owner_of(BASE_GATEWAY_PORT + 20, &in_use), None);
+    }
+}
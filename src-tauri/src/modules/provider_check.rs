@@ -0,0 +1,548 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde_json::json;
+
+use crate::models::{
+    ModelChainTestResult, ModelProbeResult, ProviderEndpoint, ProviderKeyCheck, ProviderKeyStatus,
+    ProviderSignupInfo,
+};
+
+use super::{
+    bedrock, config, custom_provider, dotenv::EnvFile, logger, model_identity, paths, process,
+    provider_signup, secrets, state_store,
+};
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+// A completion needs more headroom than a model list: the first token of a large model
+// can take several seconds.
+const COMPLETION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Request style of a provider's API. Every supported provider lists models for free
+/// with a valid key, and takes a one-token completion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Api {
+    /// OpenAI-compatible, bearer token.
+    OpenAi,
+    /// OpenAI itself: as `OpenAi`, but its current models reject `max_tokens` and
+    /// take `max_completion_tokens`.
+    OpenAiPlatform,
+    /// `x-api-key` plus `anthropic-version`.
+    Anthropic,
+    /// `x-goog-api-key` (not `?key=`, which would end up in error messages).
+    Gemini,
+    /// OpenAI-compatible, but `/models` is public there, so the key is checked
+    /// against `/key` instead.
+    OpenRouter,
+}
+
+/// Lists models with `api_key` against the provider's API (in `region` when it has
+/// several), through `proxy` or the saved one, so a mistyped key is caught before it is
+/// written to `.env`. Network failures and unexpected answers come back as
+/// `Unverified` rather than as an invalid key.
+pub async fn validate_provider_key(
+    provider: &str,
+    api_key: &str,
+    region: Option<&str>,
+    proxy: Option<String>,
+) -> Result<ProviderKeyCheck> {
+    let api_key = api_key.trim();
+    if api_key.is_empty() {
+        return Err(anyhow!("API key is missing."));
+    }
+    let info = provider_signup::get_provider_signup_info(provider)?;
+    let endpoint = endpoint_for(&info, region)
+        .ok_or_else(|| anyhow!("No API endpoint known for {provider}."))?;
+    let proxy = config::optional_non_empty(proxy).or_else(saved_proxy);
+    let client = client(Duration::from_secs(10), proxy.as_deref())?;
+    let api = api_for(&info.provider);
+
+    let (status, http_status, message) =
+        match models_request(&client, api, &endpoint.api_base_url, api_key)
+            .send()
+            .await
+        {
+            Ok(resp) => {
+                let code = resp.status();
+                let status = classify(code);
+                let message = match status {
+                    ProviderKeyStatus::Valid => format!("{} accepted the key.", info.display_name),
+                    ProviderKeyStatus::Invalid => format!(
+                        "{} rejected the key (HTTP {}). Check it was copied completely.",
+                        info.display_name,
+                        code.as_u16()
+                    ),
+                    ProviderKeyStatus::Unverified => format!(
+                        "{} answered HTTP {}; the key could not be checked.",
+                        info.display_name,
+                        code.as_u16()
+                    ),
+                };
+                (status, Some(code.as_u16()), message)
+            }
+            Err(err) => (
+                ProviderKeyStatus::Unverified,
+                None,
+                format!(
+                    "Could not reach {}{}: {}",
+                    endpoint.api_base_url,
+                    if proxy.is_some() {
+                        " through the proxy"
+                    } else {
+                        ""
+                    },
+                    config::redact_known_values(err.to_string(), &[api_key])
+                ),
+            ),
+        };
+    logger::info(&format!(
+        "API key check for {}: {status:?} ({})",
+        info.provider,
+        http_status.map_or_else(|| "no response".to_string(), |code| code.to_string())
+    ));
+    Ok(ProviderKeyCheck {
+        region: endpoint.region.clone(),
+        provider: info.provider.clone(),
+        env_var: model_identity::provider_env_name(provider),
+        status,
+        http_status,
+        message,
+    })
+}
+
+/// Lists Bedrock foundation models with an AWS access key pair in `region`. A key that
+/// is accepted but lacks `bedrock:ListFoundationModels` comes back as `Unverified`: the
+/// model calls may still be allowed.
+pub async fn validate_bedrock_credentials(
+    access_key_id: &str,
+    secret_access_key: &str,
+    region: &str,
+    proxy: Option<String>,
+) -> Result<ProviderKeyCheck> {
+    let (access_key_id, secret_access_key, region) = (
+        access_key_id.trim(),
+        secret_access_key.trim(),
+        region.trim(),
+    );
+    bedrock::validate_credentials(access_key_id, secret_access_key, region)?;
+    let proxy = config::optional_non_empty(proxy).or_else(saved_proxy);
+    let client = client(Duration::from_secs(10), proxy.as_deref())?;
+    let request = bedrock::list_models_request(
+        &client,
+        access_key_id,
+        secret_access_key,
+        region,
+        chrono::Utc::now(),
+    );
+    let (status, http_status, message) = match request.send().await {
+        Ok(resp) => {
+            let code = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            match classify(code) {
+                ProviderKeyStatus::Invalid if body.contains("AccessDenied") => (
+                    ProviderKeyStatus::Unverified,
+                    Some(code.as_u16()),
+                    "AWS accepted the keys, but they may not list Bedrock models; check the IAM policy allows Bedrock.".to_string(),
+                ),
+                ProviderKeyStatus::Invalid => (
+                    ProviderKeyStatus::Invalid,
+                    Some(code.as_u16()),
+                    format!(
+                        "AWS rejected the keys (HTTP {}): {}",
+                        code.as_u16(),
+                        config::redact_known_values(error_excerpt(&body), &[secret_access_key])
+                    ),
+                ),
+                ProviderKeyStatus::Valid => (
+                    ProviderKeyStatus::Valid,
+                    Some(code.as_u16()),
+                    format!("Bedrock in {region} accepted the keys."),
+                ),
+                ProviderKeyStatus::Unverified => (
+                    ProviderKeyStatus::Unverified,
+                    Some(code.as_u16()),
+                    format!(
+                        "Bedrock answered HTTP {}; the keys could not be checked.",
+                        code.as_u16()
+                    ),
+                ),
+            }
+        }
+        Err(err) => (
+            ProviderKeyStatus::Unverified,
+            None,
+            format!(
+                "Could not reach Bedrock in {region}: {}",
+                config::redact_known_values(err.to_string(), &[secret_access_key])
+            ),
+        ),
+    };
+    logger::info(&format!(
+        "AWS credential check for Bedrock ({region}): {status:?}"
+    ));
+    Ok(ProviderKeyCheck {
+        provider: bedrock::BEDROCK_PROVIDER.to_string(),
+        region: region.to_string(),
+        env_var: Some("AWS_ACCESS_KEY_ID".to_string()),
+        status,
+        http_status,
+        message,
+    })
+}
+
+/// Sends a one-token prompt straight to the provider of the primary model and of each
+/// fallback, with the keys the gateway would get, so a broken fallback shows up now
+/// rather than when the primary fails in production. Models are tried one after the
+/// other; a failure does not stop the rest.
+pub async fn test_model_chain() -> Result<ModelChainTestResult> {
+    let cfg = config::read_current_config()?;
+    let keys = provider_keys(&process::runtime_env(&cfg));
+    let client = client(COMPLETION_TIMEOUT, cfg.proxy.as_deref())?;
+
+    let mut models = vec![("primary", cfg.model_chain.primary.clone())];
+    models.extend(
+        cfg.model_chain
+            .fallbacks
+            .iter()
+            .map(|model| ("fallback", model.clone())),
+    );
+    let mut results = Vec::new();
+    for (role, model) in models
+        .into_iter()
+        .filter(|(_, model)| !model.trim().is_empty())
+    {
+        let mut result = probe_model(&client, &model, &keys).await;
+        result.role = role.to_string();
+        if result.ok {
+            logger::info(&format!(
+                "Model chain test: {model} answered in {} ms.",
+                result.latency_ms.unwrap_or_default()
+            ));
+        } else {
+            logger::warn(&format!(
+                "Model chain test: {model} failed: {}",
+                result.message
+            ));
+        }
+        results.push(result);
+    }
+    if results.is_empty() {
+        return Err(anyhow!("No model is configured."));
+    }
+    Ok(ModelChainTestResult {
+        all_ok: results.iter().all(|r| r.ok),
+        results,
+    })
+}
+
+async fn probe_model(
+    client: &Client,
+    model: &str,
+    keys: &BTreeMap<String, String>,
+) -> ModelProbeResult {
+    let mut result = ModelProbeResult {
+        model: model.to_string(),
+        role: String::new(),
+        ok: false,
+        latency_ms: None,
+        http_status: None,
+        message: String::new(),
+    };
+    let Some(provider) = model_identity::provider_from_model_key(model) else {
+        result.message = "Not a provider/model key; only the gateway can route it.".to_string();
+        return result;
+    };
+    // Registered custom endpoints are OpenAI-compatible; keyless ones take any key.
+    let custom = custom_provider::find_custom_provider(provider);
+    let Some(base_url) = provider_signup::get_provider_signup_info(provider)
+        .ok()
+        .and_then(|info| endpoint_for(&info, None).map(|e| e.api_base_url.clone()))
+        .or_else(|| custom.as_ref().map(|c| c.base_url.clone()))
+    else {
+        result.message = format!(
+            "No direct endpoint known for {provider}; it can only be tested through the gateway."
+        );
+        return result;
+    };
+    let Some(api_key) = model_identity::provider_env_names(provider)
+        .iter()
+        .find_map(|name| keys.get(name).cloned())
+        .or_else(|| {
+            custom
+                .as_ref()
+                .filter(|c| c.env_var.is_none())
+                .map(|_| custom_provider::KEYLESS_API_KEY.to_string())
+        })
+    else {
+        result.message = format!("No API key for {provider} in the gateway environment.");
+        return result;
+    };
+
+    let name = model.split_once('/').map_or("", |(_, name)| name.trim());
+    let started = Instant::now();
+    let request = completion_request(
+        client,
+        api_for(&model_identity::normalize_auth_provider(provider)),
+        &base_url,
+        name,
+        &api_key,
+    );
+    match request.send().await {
+        Ok(resp) => {
+            let code = resp.status();
+            result.http_status = Some(code.as_u16());
+            if code.is_success() {
+                result.ok = true;
+                result.latency_ms = Some(started.elapsed().as_millis() as u64);
+                result.message = "Answered.".to_string();
+            } else {
+                let body = resp.text().await.unwrap_or_default();
+                result.message = format!(
+                    "HTTP {}: {}",
+                    code.as_u16(),
+                    config::redact_known_values(error_excerpt(&body), &[&api_key])
+                );
+            }
+        }
+        Err(err) => {
+            result.message = config::redact_known_values(err.to_string(), &[&api_key]);
+        }
+    }
+    result
+}
+
+/// Provider keys by env name: the gateway's runtime env, then plaintext `.env` values,
+/// which the gateway loads itself.
+fn provider_keys(runtime_env: &[(String, String)]) -> BTreeMap<String, String> {
+    let mut keys = BTreeMap::new();
+    if let Ok(env) = EnvFile::load(&paths::openclaw_home().join(".env")) {
+        for name in env.keys() {
+            if let Some(value) = env.get(name).filter(|v| !secrets::is_reference(v)) {
+                keys.insert(name.to_string(), value.trim().to_string());
+            }
+        }
+    }
+    for (name, value) in runtime_env {
+        keys.insert(name.clone(), value.clone());
+    }
+    keys.retain(|_, value| !value.is_empty());
+    keys
+}
+
+fn endpoint_for<'a>(
+    info: &'a ProviderSignupInfo,
+    region: Option<&str>,
+) -> Option<&'a ProviderEndpoint> {
+    let region = region
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .unwrap_or(&info.recommended_region);
+    info.endpoints
+        .iter()
+        .find(|e| e.region == region)
+        .or_else(|| info.endpoints.first())
+}
+
+fn saved_proxy() -> Option<String> {
+    state_store::load_last_config()
+        .ok()
+        .flatten()
+        .and_then(|cfg| config::optional_non_empty(cfg.proxy))
+}
+
+fn client(timeout: Duration, proxy: Option<&str>) -> Result<Client> {
+    let mut builder = Client::builder().timeout(timeout);
+    if let Some(proxy) = config::optional_non_empty(proxy.map(str::to_string)) {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    Ok(builder.build()?)
+}
+
+fn api_for(provider: &str) -> Api {
+    match provider {
+        "anthropic" => Api::Anthropic,
+        "google" => Api::Gemini,
+        "openrouter" => Api::OpenRouter,
+        "openai" => Api::OpenAiPlatform,
+        _ => Api::OpenAi,
+    }
+}
+
+fn models_url(api: Api, api_base_url: &str) -> String {
+    let base = api_base_url.trim_end_matches('/');
+    match api {
+        Api::OpenAi | Api::OpenAiPlatform => format!("{base}/models"),
+        Api::Anthropic => format!("{base}/v1/models"),
+        Api::Gemini => format!("{base}/v1beta/models"),
+        Api::OpenRouter => format!("{base}/key"),
+    }
+}
+
+fn completion_url(api: Api, api_base_url: &str, model: &str) -> String {
+    let base = api_base_url.trim_end_matches('/');
+    match api {
+        Api::OpenAi | Api::OpenAiPlatform | Api::OpenRouter => {
+            format!("{base}/chat/completions")
+        }
+        Api::Anthropic => format!("{base}/v1/messages"),
+        Api::Gemini => format!("{base}/v1beta/models/{model}:generateContent"),
+    }
+}
+
+fn authorize(request: RequestBuilder, api: Api, api_key: &str) -> RequestBuilder {
+    match api {
+        Api::OpenAi | Api::OpenAiPlatform | Api::OpenRouter => request.bearer_auth(api_key),
+        Api::Anthropic => request
+            .header("x-api-key", api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION),
+        Api::Gemini => request.header("x-goog-api-key", api_key),
+    }
+}
+
+fn models_request(client: &Client, api: Api, api_base_url: &str, api_key: &str) -> RequestBuilder {
+    authorize(client.get(models_url(api, api_base_url)), api, api_key)
+}
+
+fn completion_request(
+    client: &Client,
+    api: Api,
+    api_base_url: &str,
+    model: &str,
+    api_key: &str,
+) -> RequestBuilder {
+    authorize(
+        client
+            .post(completion_url(api, api_base_url, model))
+            .json(&completion_body(api, model)),
+        api,
+        api_key,
+    )
+}
+
+fn completion_body(api: Api, model: &str) -> serde_json::Value {
+    match api {
+        Api::Gemini => json!({
+            "contents": [{ "parts": [{ "text": "ping" }] }],
+            "generationConfig": { "maxOutputTokens": 1 }
+        }),
+        // Reasoning models spend part of the budget before answering; a few tokens
+        // keep them from failing on the limit.
+        Api::OpenAiPlatform => json!({
+            "model": model,
+            "max_completion_tokens": 16,
+            "messages": [{ "role": "user", "content": "ping" }]
+        }),
+        Api::OpenAi | Api::Anthropic | Api::OpenRouter => json!({
+            "model": model,
+            "max_tokens": 1,
+            "messages": [{ "role": "user", "content": "ping" }]
+        }),
+    }
+}
+
+/// The provider's error message when the body is the usual `{"error": {"message"}}`,
+/// otherwise the start of the body.
+fn error_excerpt(body: &str) -> String {
+    let message = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| {
+            value
+                .pointer("/error/message")
+                .and_then(|v| v.as_str())
+                .map(str::to_string)
+        });
+    message.unwrap_or_else(|| body.trim().chars().take(200).collect())
+}
+
+/// 401/403 mean the key itself was refused (Gemini answers 400 for a malformed key).
+/// A rate limit still proves the key was accepted.
+fn classify(code: StatusCode) -> ProviderKeyStatus {
+    match code.as_u16() {
+        200..=299 | 429 => ProviderKeyStatus::Valid,
+        400 | 401 | 403 => ProviderKeyStatus::Invalid,
+        _ => ProviderKeyStatus::Unverified,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        api_for, classify, completion_body, completion_url, error_excerpt, models_url, Api,
+    };
+    use crate::models::ProviderKeyStatus;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn urls_follow_each_providers_api_style() {
+        assert_eq!(
+            models_url(api_for("openai"), "https://api.openai.com/v1"),
+            "https://api.openai.com/v1/models"
+        );
+        assert_eq!(
+            models_url(api_for("anthropic"), "https://api.anthropic.com/"),
+            "https://api.anthropic.com/v1/models"
+        );
+        assert_eq!(
+            models_url(
+                api_for("google"),
+                "https://generativelanguage.googleapis.com"
+            ),
+            "https://generativelanguage.googleapis.com/v1beta/models"
+        );
+        // OpenRouter lists models without a key; `GET /api/v1/key` needs a valid one.
+        assert_eq!(
+            models_url(api_for("openrouter"), "https://openrouter.ai/api/v1"),
+            "https://openrouter.ai/api/v1/key"
+        );
+        assert_eq!(
+            completion_url(Api::OpenRouter, "https://openrouter.ai/api/v1", "x"),
+            "https://openrouter.ai/api/v1/chat/completions"
+        );
+        assert_eq!(
+            completion_url(Api::OpenAi, "https://api.moonshot.cn/v1", "kimi-k2.5"),
+            "https://api.moonshot.cn/v1/chat/completions"
+        );
+        assert_eq!(
+            completion_url(
+                Api::Gemini,
+                "https://generativelanguage.googleapis.com",
+                "gemini-2.5-flash"
+            ),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-flash:generateContent"
+        );
+        assert_eq!(classify(StatusCode::OK), ProviderKeyStatus::Valid);
+        assert_eq!(
+            classify(StatusCode::TOO_MANY_REQUESTS),
+            ProviderKeyStatus::Valid
+        );
+        assert_eq!(
+            classify(StatusCode::UNAUTHORIZED),
+            ProviderKeyStatus::Invalid
+        );
+        assert_eq!(
+            classify(StatusCode::BAD_GATEWAY),
+            ProviderKeyStatus::Unverified
+        );
+    }
+
+    #[test]
+    fn openai_completions_use_max_completion_tokens() {
+        let openai = completion_body(api_for("openai"), "gpt-5.2");
+        assert_eq!(openai["max_completion_tokens"], 16);
+        assert!(openai.get("max_tokens").is_none());
+        let moonshot = completion_body(api_for("moonshot"), "kimi-k2.5");
+        assert_eq!(moonshot["max_tokens"], 1);
+        assert!(moonshot.get("max_completion_tokens").is_none());
+    }
+
+    #[test]
+    fn error_excerpt_prefers_the_provider_message() {
+        assert_eq!(
+            error_excerpt(r#"{"error":{"message":"model not found","type":"x"}}"#),
+            "model not found"
+        );
+        assert_eq!(error_excerpt("  upstream timeout \n"), "upstream timeout");
+    }
+}
@@ -0,0 +1,231 @@
+use anyhow::{anyhow, Result};
+
+use crate::models::{ProviderEndpoint, ProviderSignupInfo};
+
+use super::{browser, model_identity, state_store};
+
+const REGION_GLOBAL: &str = "global";
+const REGION_CN: &str = "cn";
+
+struct SignupSpec {
+    provider: &'static str,
+    display_name: &'static str,
+    /// (region, api base url, api key console url)
+    endpoints: &'static [(&'static str, &'static str, &'static str)],
+    free_tier_note: Option<&'static str>,
+    proxy_usually_required_in_cn: bool,
+}
+
+const SIGNUP_SPECS: &[SignupSpec] = &[
+    SignupSpec {
+        provider: "openai",
+        display_name: "OpenAI",
+        endpoints: &[(
+            REGION_GLOBAL,
+            "https://api.openai.com/v1",
+            "https://platform.openai.com/api-keys",
+        )],
+        free_tier_note: None,
+        proxy_usually_required_in_cn: true,
+    },
+    SignupSpec {
+        provider: "anthropic",
+        display_name: "Anthropic",
+        endpoints: &[(
+            REGION_GLOBAL,
+            "https://api.anthropic.com",
+            "https://console.anthropic.com/settings/keys",
+        )],
+        free_tier_note: None,
+        proxy_usually_required_in_cn: true,
+    },
+    SignupSpec {
+        provider: "google",
+        display_name: "Google Gemini",
+        endpoints: &[(
+            REGION_GLOBAL,
+            "https://generativelanguage.googleapis.com",
+            "https://aistudio.google.com/app/apikey",
+        )],
+        free_tier_note: Some("Gemini API has a rate-limited free tier in AI Studio."),
+        proxy_usually_required_in_cn: true,
+    },
+    SignupSpec {
+        provider: "moonshot",
+        display_name: "Moonshot (Kimi)",
+        endpoints: &[
+            (
+                REGION_CN,
+                "https://api.moonshot.cn/v1",
+                "https://platform.moonshot.cn/console/api-keys",
+            ),
+            (
+                REGION_GLOBAL,
+                "https://api.moonshot.ai/v1",
+                "https://platform.moonshot.ai/console/api-keys",
+            ),
+        ],
+        free_tier_note: Some("New accounts may receive trial credit."),
+        proxy_usually_required_in_cn: false,
+    },
+    SignupSpec {
+        provider: "kimi-coding",
+        display_name: "Kimi Code",
+        endpoints: &[(
+            REGION_GLOBAL,
+            "https://api.kimi.com/coding/v1",
+            "https://www.kimi.com/coding",
+        )],
+        free_tier_note: None,
+        proxy_usually_required_in_cn: false,
+    },
+    SignupSpec {
+        provider: "xai",
+        display_name: "xAI Grok",
+        endpoints: &[(REGION_GLOBAL, "https://api.x.ai/v1", "https://console.x.ai")],
+        free_tier_note: None,
+        proxy_usually_required_in_cn: true,
+    },
+    SignupSpec {
+        provider: "openrouter",
+        display_name: "OpenRouter",
+        endpoints: &[(
+            REGION_GLOBAL,
+            "https://openrouter.ai/api/v1",
+            "https://openrouter.ai/keys",
+        )],
+        free_tier_note: Some("Models tagged :free work without credit, with tight rate limits."),
+        proxy_usually_required_in_cn: true,
+    },
+    SignupSpec {
+        provider: "zai",
+        display_name: "Z.AI (GLM)",
+        endpoints: &[
+            (
+                REGION_CN,
+                "https://open.bigmodel.cn/api/paas/v4",
+                "https://open.bigmodel.cn/usercenter/apikeys",
+            ),
+            (
+                REGION_GLOBAL,
+                "https://api.z.ai/api/paas/v4",
+                "https://z.ai/manage-apikey/apikey-list",
+            ),
+        ],
+        free_tier_note: Some("GLM Flash models are free to call."),
+        proxy_usually_required_in_cn: false,
+    },
+    SignupSpec {
+        provider: "xiaomi",
+        display_name: "Xiaomi MiMo",
+        endpoints: &[(
+            REGION_GLOBAL,
+            "https://api.xiaomimimo.com/v1",
+            "https://platform.xiaomimimo.com",
+        )],
+        free_tier_note: None,
+        proxy_usually_required_in_cn: false,
+    },
+    SignupSpec {
+        provider: "minimax",
+        display_name: "MiniMax",
+        endpoints: &[
+            (
+                REGION_CN,
+                "https://api.minimaxi.com/v1",
+                "https://platform.minimaxi.com/user-center/basic-information/interface-key",
+            ),
+            (
+                REGION_GLOBAL,
+                "https://api.minimax.io/v1",
+                "https://platform.minimax.io/user-center/basic-information/interface-key",
+            ),
+        ],
+        free_tier_note: None,
+        proxy_usually_required_in_cn: false,
+    },
+];
+
+pub fn get_provider_signup_info(provider: &str) -> Result<ProviderSignupInfo> {
+    let spec = find_spec(provider)?;
+    let region_hint = state_store::load_last_config()
+        .ok()
+        .flatten()
+        .map(|cfg| cfg.kimi_region);
+    Ok(signup_info(spec, region_hint.as_deref()))
+}
+
+/// Opens the API key console for `provider`, in `region` when it has one.
+pub fn open_provider_signup(provider: &str, region: Option<&str>) -> Result<String> {
+    let info = get_provider_signup_info(provider)?;
+    let region = region.unwrap_or(&info.recommended_region);
+    let endpoint = info
+        .endpoints
+        .iter()
+        .find(|e| e.region == region)
+        .or_else(|| info.endpoints.first())
+        .ok_or_else(|| anyhow!("No console URL known for {provider}."))?;
+    browser::open_external_url(&endpoint.console_url)
+}
+
+fn find_spec(provider: &str) -> Result<&'static SignupSpec> {
+    let normalized = model_identity::normalize_auth_provider(provider);
+    SIGNUP_SPECS
+        .iter()
+        .find(|spec| spec.provider == normalized)
+        .ok_or_else(|| anyhow!("No signup information for provider '{provider}'."))
+}
+
+fn signup_info(spec: &SignupSpec, region_hint: Option<&str>) -> ProviderSignupInfo {
+    let endpoints = spec
+        .endpoints
+        .iter()
+        .map(|(region, api_base_url, console_url)| ProviderEndpoint {
+            region: region.to_string(),
+            api_base_url: api_base_url.to_string(),
+            console_url: console_url.to_string(),
+        })
+        .collect::<Vec<_>>();
+    let recommended_region = match region_hint.map(str::trim) {
+        Some(REGION_CN) if endpoints.iter().any(|e| e.region == REGION_CN) => REGION_CN,
+        _ => REGION_GLOBAL,
+    }
+    .to_string();
+    ProviderSignupInfo {
+        provider: spec.provider.to_string(),
+        display_name: spec.display_name.to_string(),
+        endpoints,
+        recommended_region,
+        free_tier_note: spec.free_tier_note.map(str::to_string),
+        proxy_usually_required_in_cn: spec.proxy_usually_required_in_cn,
+        env_var: model_identity::provider_env_name(spec.provider),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{find_spec, signup_info, SIGNUP_SPECS};
+
+    #[test]
+    fn signup_specs_use_https_consoles() {
+        for spec in SIGNUP_SPECS {
+            assert!(!spec.endpoints.is_empty(), "{}", spec.provider);
+            for (_, api, console) in spec.endpoints {
+                assert!(api.starts_with("https://"), "{api}");
+                assert!(console.starts_with("https://"), "{console}");
+            }
+        }
+    }
+
+    #[test]
+    fn signup_info_prefers_cn_only_when_available() {
+        let zai = find_spec("zai").expect("zai");
+        assert_eq!(signup_info(zai, Some("cn")).recommended_region, "cn");
+        assert_eq!(signup_info(zai, None).recommended_region, "global");
+        let openai = find_spec("openai-codex").expect("alias");
+        let info = signup_info(openai, Some("cn"));
+        assert_eq!(info.recommended_region, "global");
+        assert_eq!(info.env_var.as_deref(), Some("OPENAI_API_KEY"));
+        assert!(find_spec("nope").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{anyhow, Result};
+use chrono::{Local, NaiveTime};
+
+use crate::models::QuietHoursConfig;
+
+use super::{config, logger, paths, state_store};
+
+const ENFORCE_MIN_INTERVAL_MS: i64 = 60_000;
+
+static LAST_ENFORCE_MS: OnceLock<Mutex<i64>> = OnceLock::new();
+
+pub fn set_quiet_hours(cfg: &QuietHoursConfig) -> Result<QuietHoursConfig> {
+    let mut cfg = cfg.clone();
+    for rule in &mut cfg.rules {
+        rule.channel = rule.channel.trim().to_ascii_lowercase();
+        if rule.channel.is_empty()
+            || !rule
+                .channel
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+        {
+            return Err(anyhow!("Invalid channel id: '{}'", rule.channel));
+        }
+        rule.start = parse_hhmm(&rule.start)?.format("%H:%M").to_string();
+        rule.end = parse_hhmm(&rule.end)?.format("%H:%M").to_string();
+    }
+    state_store::save_quiet_hours(&cfg)?;
+    enforce(&cfg)?;
+    Ok(cfg)
+}
+
+/// Applies quiet-hours transitions at most once per minute. Called from status polling.
+pub fn enforce_if_due() {
+    let now = Local::now().timestamp_millis();
+    let lock = LAST_ENFORCE_MS.get_or_init(|| Mutex::new(0));
+    {
+        let mut last = lock.lock().unwrap_or_else(|e| e.into_inner());
+        if now - *last < ENFORCE_MIN_INTERVAL_MS {
+            return;
+        }
+        *last = now;
+    }
+    let result = state_store::load_quiet_hours().and_then(|cfg| enforce(&cfg));
+    if let Err(err) = result {
+        logger::warn(&format!("Quiet hours enforcement failed: {err}"));
+    }
+}
+
+fn enforce(cfg: &QuietHoursConfig) -> Result<()> {
+    let now = Local::now().time();
+    let should_pause = cfg
+        .rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .filter(
+            |rule| match (parse_hhmm(&rule.start), parse_hhmm(&rule.end)) {
+                (Ok(start), Ok(end)) => is_within_window(now, start, end),
+                _ => false,
+            },
+        )
+        .map(|rule| rule.channel.clone())
+        .collect::<BTreeSet<_>>();
+    let paused = load_paused_channels();
+    if should_pause == paused {
+        return Ok(());
+    }
+
+    let proxy = state_store::load_last_config()?.and_then(|cfg| cfg.proxy);
+    let (applied, failures) = apply_transitions(&should_pause, &paused, |channel, enabled| {
+        let out = config::run_openclaw_cli(
+            &[
+                "config".to_string(),
+                "set".to_string(),
+                format!("channels.{channel}.enabled"),
+                enabled.to_string(),
+            ],
+            proxy.clone(),
+        )?;
+        if out.code != 0 {
+            return Err(anyhow!(config::cli_output_text(&out)));
+        }
+        logger::info(&format!(
+            "Quiet hours: channel {channel} {}.",
+            if enabled { "resumed" } else { "paused" }
+        ));
+        Ok(())
+    });
+    save_paused_channels(&applied)?;
+
+    // Nothing was written, so there is nothing for a restart to pick up.
+    if applied != paused {
+        let restart_out =
+            config::run_openclaw_cli(&["gateway".to_string(), "restart".to_string()], proxy)?;
+        if restart_out.code != 0 {
+            return Err(anyhow!(
+                "Gateway restart after quiet hours change failed: {}",
+                config::cli_output_text(&restart_out)
+            ));
+        }
+    }
+    if !failures.is_empty() {
+        return Err(anyhow!(
+            "Quiet hours could not update every channel: {}",
+            failures.join("; ")
+        ));
+    }
+    Ok(())
+}
+
+/// Pauses channels entering quiet hours and resumes those leaving it through `set`.
+/// Only channels this module paused are resumed, so a channel the user disabled by
+/// hand stays disabled after quiet hours end. Returns the channels now paused and one
+/// message per failed write.
+fn apply_transitions(
+    should_pause: &BTreeSet<String>,
+    paused: &BTreeSet<String>,
+    mut set: impl FnMut(&str, bool) -> Result<()>,
+) -> (BTreeSet<String>, Vec<String>) {
+    let mut applied = paused.clone();
+    let mut failures = Vec::new();
+    for (channel, enabled) in should_pause
+        .difference(paused)
+        .map(|c| (c, false))
+        .chain(paused.difference(should_pause).map(|c| (c, true)))
+    {
+        if let Err(err) = set(channel, enabled) {
+            let action = if enabled { "resume" } else { "pause" };
+            logger::warn(&format!(
+                "Quiet hours could not {action} channel {channel}: {err}"
+            ));
+            failures.push(format!("{action} {channel}: {err}"));
+            continue;
+        }
+        if enabled {
+            applied.remove(channel);
+        } else {
+            applied.insert(channel.clone());
+        }
+    }
+    (applied, failures)
+}
+
+fn parse_hhmm(raw: &str) -> Result<NaiveTime> {
+    NaiveTime::parse_from_str(raw.trim(), "%H:%M")
+        .map_err(|_| anyhow!("Invalid time '{raw}', expected HH:MM"))
+}
+
+fn is_within_window(now: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start == end {
+        return false;
+    }
+    if start < end {
+        now >= start && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+fn paused_channels_path() -> PathBuf {
+    paths::state_dir().join("quiet_hours_paused.json")
+}
+
+fn load_paused_channels() -> BTreeSet<String> {
+    fs::read_to_string(paused_channels_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_paused_channels(channels: &BTreeSet<String>) -> Result<()> {
+    paths::ensure_dirs()?;
+    fs::write(paused_channels_path(), serde_json::to_string(channels)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_transitions, is_within_window, parse_hhmm};
+    use anyhow::anyhow;
+    use std::collections::BTreeSet;
+
+    fn t(raw: &str) -> chrono::NaiveTime {
+        parse_hhmm(raw).expect("valid time")
+    }
+
+    #[test]
+    fn window_spanning_midnight() {
+        assert!(is_within_window(t("23:30"), t("23:00"), t("07:00")));
+        assert!(is_within_window(t("06:59"), t("23:00"), t("07:00")));
+        assert!(!is_within_window(t("07:00"), t("23:00"), t("07:00")));
+        assert!(!is_within_window(t("12:00"), t("23:00"), t("07:00")));
+    }
+
+    #[test]
+    fn window_within_same_day() {
+        assert!(is_within_window(t("13:00"), t("12:00"), t("14:00")));
+        assert!(!is_within_window(t("14:00"), t("12:00"), t("14:00")));
+        assert!(!is_within_window(t("12:00"), t("12:00"), t("12:00")));
+    }
+
+    #[test]
+    fn failed_writes_leave_the_paused_set_unchanged() {
+        let set = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<BTreeSet<_>>();
+        let should_pause = set(&["discord", "telegram"]);
+        let paused = set(&["slack"]);
+
+        let (applied, failures) =
+            apply_transitions(&should_pause, &paused, |_, _| Err(anyhow!("cli missing")));
+        assert_eq!(applied, paused);
+        assert_eq!(failures.len(), 3);
+
+        let mut calls = Vec::new();
+        let (applied, failures) = apply_transitions(&should_pause, &paused, |channel, enabled| {
+            calls.push(format!("{channel}={enabled}"));
+            if channel == "telegram" {
+                return Err(anyhow!("locked"));
+            }
+            Ok(())
+        });
+        assert_eq!(calls, vec!["discord=false", "telegram=false", "slack=true"]);
+        assert_eq!(applied, set(&["discord"]));
+        assert_eq!(failures, vec!["pause telegram: locked"]);
+    }
+
+    #[test]
+    fn parse_hhmm_rejects_garbage() {
+        assert!(parse_hhmm("7am").is_err());
+        assert!(parse_hhmm("25:00").is_err());
+        assert_eq!(t(" 07:05 ").format("%H:%M").to_string(), "07:05");
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use sha2::{Digest, Sha256};
+
+use crate::models::{ReadOnlyConfig, ReadOnlyStatus};
+
+use super::{app_lock, fs_walk, logger, secrets, state_store};
+
+const PIN_MIN_LEN: usize = 4;
+const PIN_MAX_LEN: usize = 12;
+
+pub fn read_only_status() -> ReadOnlyStatus {
+    let cfg = state_store::load_read_only().unwrap_or_default();
+    ReadOnlyStatus {
+        enabled: cfg.enabled,
+        enabled_at: cfg.enabled_at,
+    }
+}
+
+/// Locks destructive commands (uninstall, rollback, clears, config writes) until the
+/// same PIN is entered again. Status, logs and health stay available.
+pub fn enable_read_only(pin: &str) -> Result<ReadOnlyStatus> {
+    validate_pin(pin)?;
+    let mut cfg = state_store::load_read_only()?;
+    if cfg.enabled {
+        return Err(anyhow!("Read-only mode is already on."));
+    }
+    cfg.pin_salt = uuid::Uuid::new_v4().simple().to_string();
+    let hash = hash_pin(&cfg.pin_salt, pin.trim());
+    (cfg.pin_hash, cfg.sealed) = match secrets::seal_value(&hash) {
+        Some(sealed) => (sealed?, true),
+        None => (hash, false),
+    };
+    cfg.enabled = true;
+    cfg.enabled_at = Some(Local::now().to_rfc3339());
+    state_store::save_read_only(&cfg)?;
+    logger::info("Read-only mode enabled.");
+    Ok(read_only_status())
+}
+
+pub fn disable_read_only(pin: &str) -> Result<ReadOnlyStatus> {
+    let cfg = state_store::load_read_only()?;
+    if !cfg.enabled {
+        return Ok(read_only_status());
+    }
+    let stored = if cfg.sealed {
+        secrets::open_value(&cfg.pin_hash)?
+    } else {
+        cfg.pin_hash.clone()
+    };
+    app_lock::check_pin(&cfg.pin_salt, &stored, pin).inspect_err(|_| {
+        logger::warn("Read-only mode: wrong PIN entered.");
+    })?;
+    state_store::save_read_only(&ReadOnlyConfig::default())?;
+    logger::info("Read-only mode disabled.");
+    Ok(read_only_status())
+}
+
+/// Guard for commands that change or delete anything. Fails closed when the state
+/// file cannot be read.
+pub fn ensure_writable(action: &str) -> Result<()> {
+    let enabled = state_store::load_read_only()
+        .map(|cfg| cfg.enabled)
+        .unwrap_or(true);
+    if enabled {
+        logger::warn(&format!("Blocked in read-only mode: {action}"));
+        return Err(anyhow!(
+            "{action} is not available in read-only mode. Unlock it with the PIN first."
+        ));
+    }
+    Ok(())
+}
+
+pub fn hash_pin(salt: &str, pin: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(salt.as_bytes());
+    hasher.update(pin.as_bytes());
+    fs_walk::hex_digest(hasher)
+}
+
+/// Compares `pin` against a stored hash without an early exit on the first differing byte.
+pub fn pin_matches(salt: &str, pin: &str, stored_hash: &str) -> bool {
+    let hash = hash_pin(salt, pin.trim());
+    hash.len() == stored_hash.len()
+        && hash
+            .bytes()
+            .zip(stored_hash.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+pub fn validate_pin(pin: &str) -> Result<()> {
+    let pin = pin.trim();
+    if pin.len() < PIN_MIN_LEN
+        || pin.len() > PIN_MAX_LEN
+        || !pin.chars().all(|c| c.is_ascii_digit())
+    {
+        return Err(anyhow!("PIN must be {PIN_MIN_LEN}-{PIN_MAX_LEN} digits."));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_pin, pin_matches, validate_pin};
+
+    #[test]
+    fn pins_are_validated_and_salted() {
+        assert!(validate_pin(" 2468 ").is_ok());
+        assert!(validate_pin("123").is_err());
+        assert!(validate_pin("12ab").is_err());
+        assert_eq!(hash_pin("salt", "2468"), hash_pin("salt", "2468"));
+        assert_ne!(hash_pin("salt", "2468"), hash_pin("other", "2468"));
+        assert!(pin_matches("salt", " 2468", &hash_pin("salt", "2468")));
+        assert!(!pin_matches("salt", "2469", &hash_pin("salt", "2468")));
+        assert!(!pin_matches("salt", "2468", ""));
+    }
+}
@@ -0,0 +1,164 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::models::{ExistingConfigPrefill, OpenClawConfigInput};
+
+use super::{config, dotenv, logger, model_identity, paths, state_store};
+
+/// Finds an existing openclaw.json (e.g. after the installer app was reinstalled) and
+/// turns it into a prefilled wizard payload, so the user can reconfigure instead of
+/// running a fresh onboard over their settings.
+pub fn reconfigure_from_existing(install_dir: Option<&str>) -> Result<ExistingConfigPrefill> {
+    let home = locate_existing_home(install_dir)?
+        .ok_or_else(|| anyhow!("No existing openclaw.json was found."))?;
+    // Only read from the found home here. It becomes the bound home when the user
+    // confirms and configure runs with `payload.install_dir`, never just by looking.
+    let config_path = home.join("openclaw.json");
+    let current = config::read_config_in(&home)?;
+    let raw: Value = serde_json::from_str(&fs::read_to_string(&config_path)?)?;
+    let env_content = fs::read_to_string(home.join(".env")).unwrap_or_default();
+    let last = state_store::load_last_config()?.unwrap_or_default();
+
+    let mut payload = OpenClawConfigInput {
+        install_dir: home.to_string_lossy().to_string(),
+        provider: current.provider.clone(),
+        model_chain: current.model_chain.clone(),
+        base_url: current.base_url,
+        proxy: current.proxy,
+        port: current.port,
+        bind_address: current.bind_address,
+        // Channels are already set up in openclaw.json; the payload must not re-add them.
+        enable_feishu_channel: false,
+        enable_dingtalk_channel: false,
+        enable_telegram_channel: false,
+        enable_discord_channel: false,
+        ..last
+    };
+    if let Some(skills) = bundled_skills(&raw) {
+        payload.selected_skills = skills;
+    }
+
+    // Nothing from the last payload's credentials goes back to the UI; provider keys
+    // that exist are shown masked below.
+    state_store::for_each_secret(&mut payload, |value| {
+        value.clear();
+        Ok(())
+    })?;
+    payload.provider_api_keys.clear();
+    let mut masked_keys = Vec::<String>::new();
+    for provider in config::providers_from_model_chain(&payload.model_chain) {
+        // A key written by another tool may only exist under one of the aliases.
+        let found = model_identity::provider_env_names(&provider)
+            .iter()
+            .find_map(|name| env_value(&env_content, name));
+        if let Some(value) = found {
+            payload
+                .provider_api_keys
+                .insert(provider.clone(), config::mask_key(&value));
+            masked_keys.push(provider);
+        }
+    }
+    payload.telegram_pair_code.clear();
+
+    let install_state_missing = state_store::load_install_state()?.is_none();
+    let configured_channels = configured_channels(&raw);
+    logger::info(&format!(
+        "Existing OpenClaw config loaded for reconfigure: {} (keys: {}, channels: {})",
+        config_path.to_string_lossy(),
+        masked_keys.join(", "),
+        configured_channels.join(", ")
+    ));
+    Ok(ExistingConfigPrefill {
+        config_path: config_path.to_string_lossy().to_string(),
+        openclaw_home: home.to_string_lossy().to_string(),
+        install_state_missing,
+        payload,
+        masked_keys,
+        configured_channels,
+    })
+}
+
+fn locate_existing_home(install_dir: Option<&str>) -> Result<Option<PathBuf>> {
+    let mut candidates = Vec::<PathBuf>::new();
+    if let Some(dir) = install_dir.filter(|dir| !dir.trim().is_empty()) {
+        candidates.push(paths::normalize_path(dir)?);
+    }
+    if let Some(state) = state_store::load_install_state()? {
+        candidates.push(paths::normalize_path(&state.install_dir)?);
+    }
+    candidates.push(paths::openclaw_home());
+    candidates.push(paths::default_isolated_openclaw_home());
+    Ok(candidates
+        .into_iter()
+        .find(|home| home.join("openclaw.json").is_file()))
+}
+
+fn bundled_skills(raw: &Value) -> Option<Vec<String>> {
+    let items = raw.pointer("/skills/allowBundled")?.as_array()?;
+    Some(
+        items
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect(),
+    )
+}
+
+fn configured_channels(raw: &Value) -> Vec<String> {
+    raw.get("channels")
+        .and_then(|v| v.as_object())
+        .map(|channels| {
+            channels
+                .iter()
+                .filter(|(_, entry)| entry.get("enabled").and_then(|v| v.as_bool()) != Some(false))
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>()
+        })
+        .map(|mut names| {
+            names.sort();
+            names
+        })
+        .unwrap_or_default()
+}
+
+fn env_value(content: &str, key: &str) -> Option<String> {
+    dotenv::EnvFile::parse(content)
+        .get(key)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{configured_channels, env_value};
+    use crate::modules::config::{mask_key, MASKED_KEY_PREFIX};
+    use serde_json::json;
+
+    #[test]
+    fn env_value_reads_assignment_and_mask_hides_it() {
+        let env = "OPENAI_API_KEY=\"sk-test-123456789\"\nZAI_API_KEY=\n";
+        let value = env_value(env, "OPENAI_API_KEY").expect("key present");
+        assert_eq!(value, "sk-test-123456789");
+        assert_eq!(env_value(env, "ZAI_API_KEY"), None);
+
+        let masked = mask_key(&value);
+        assert!(masked.starts_with(MASKED_KEY_PREFIX));
+        assert!(masked.ends_with("6789"));
+        assert!(!masked.contains("sk-test"));
+        assert_eq!(mask_key("short"), MASKED_KEY_PREFIX);
+    }
+
+    #[test]
+    fn configured_channels_skips_disabled_entries() {
+        let raw = json!({
+            "channels": {
+                "telegram": { "enabled": true },
+                "feishu": { "enabled": false },
+                "discord": {}
+            }
+        });
+        assert_eq!(configured_channels(&raw), vec!["discord", "telegram"]);
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use chrono::Local;
+use serde_json::{json, Value};
+
+use crate::models::SbomResult;
+
+use super::{logger, paths, state_store};
+
+const SBOM_FILE: &str = "openclaw-sbom.cdx.json";
+
+/// One npm package of the installed tree.
+#[derive(Debug, Clone, PartialEq)]
+struct Package {
+    name: String,
+    version: String,
+    integrity: Option<String>,
+    license: Option<String>,
+}
+
+/// Writes a CycloneDX 1.5 inventory of the packages installed with OpenClaw next to the
+/// install, so scanners (`grype`, `osv-scanner`, Dependency-Track) can cross-reference
+/// it. Read from `package-lock.json`, or from `node_modules` for installs without one.
+pub fn generate_sbom() -> Result<SbomResult> {
+    let install = state_store::load_install_state()?
+        .ok_or_else(|| anyhow!("OpenClaw is not installed; nothing to inventory."))?;
+    let install_dir = paths::normalize_path(&install.install_dir)?;
+    let packages = match fs::read_to_string(install_dir.join("package-lock.json")) {
+        Ok(raw) => lock_packages(&raw)?,
+        Err(_) => node_modules_packages(&install_dir.join("node_modules")),
+    };
+    if packages.is_empty() {
+        return Err(anyhow!(
+            "No npm packages found under {}.",
+            install_dir.to_string_lossy()
+        ));
+    }
+    let generated_at = Local::now().to_rfc3339();
+    let bom = cyclonedx(&install.version, &packages, &generated_at);
+    let path = install_dir.join(SBOM_FILE);
+    fs::write(&path, serde_json::to_string_pretty(&bom)?)?;
+    logger::info(&format!(
+        "SBOM written ({} packages): {}",
+        packages.len(),
+        path.to_string_lossy()
+    ));
+    Ok(SbomResult {
+        path: path.to_string_lossy().to_string(),
+        component_count: packages.len(),
+        generated_at,
+    })
+}
+
+/// The SBOM of the current install, if one was generated.
+pub fn sbom_path() -> Option<PathBuf> {
+    let install = state_store::load_install_state().ok().flatten()?;
+    let path = paths::normalize_path(&install.install_dir)
+        .ok()?
+        .join(SBOM_FILE);
+    path.is_file().then_some(path)
+}
+
+/// Packages of a lockfile v2/v3 (`packages` keyed by `node_modules/...` path).
+fn lock_packages(raw: &str) -> Result<Vec<Package>> {
+    let lock: Value = serde_json::from_str(raw)?;
+    let entries = lock
+        .get("packages")
+        .and_then(Value::as_object)
+        .ok_or_else(|| {
+            anyhow!("package-lock.json has no `packages` (lockfile v1 is not supported).")
+        })?;
+    let mut packages = BTreeMap::<(String, String), Package>::new();
+    for (path, entry) in entries {
+        if path.is_empty() || entry.get("link").and_then(Value::as_bool) == Some(true) {
+            continue;
+        }
+        let Some(version) = entry.get("version").and_then(Value::as_str) else {
+            continue;
+        };
+        let name = entry
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .or_else(|| {
+                path.rsplit_once("node_modules/")
+                    .map(|(_, name)| name.to_string())
+            })
+            .unwrap_or_else(|| path.clone());
+        packages
+            .entry((name.clone(), version.to_string()))
+            .or_insert(Package {
+                name,
+                version: version.to_string(),
+                integrity: entry
+                    .get("integrity")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                license: entry
+                    .get("license")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            });
+    }
+    Ok(packages.into_values().collect())
+}
+
+/// Top-level packages (scoped ones included) when there is no lockfile.
+fn node_modules_packages(node_modules: &Path) -> Vec<Package> {
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(node_modules).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('@') {
+            dirs.extend(
+                fs::read_dir(&path)
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .map(|e| e.path()),
+            );
+        } else {
+            dirs.push(path);
+        }
+    }
+    let mut packages = dirs
+        .iter()
+        .filter_map(|dir| fs::read_to_string(dir.join("package.json")).ok())
+        .filter_map(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .filter_map(|manifest| {
+            Some(Package {
+                name: manifest.get("name")?.as_str()?.to_string(),
+                version: manifest.get("version")?.as_str()?.to_string(),
+                integrity: None,
+                license: manifest
+                    .get("license")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            })
+        })
+        .collect::<Vec<_>>();
+    packages.sort_by(|a, b| a.name.cmp(&b.name).then(a.version.cmp(&b.version)));
+    packages
+}
+
+fn cyclonedx(openclaw_version: &str, packages: &[Package], generated_at: &str) -> Value {
+    let components = packages
+        .iter()
+        .map(|package| {
+            let purl = purl(&package.name, &package.version);
+            let mut component = json!({
+                "type": "library",
+                "bom-ref": purl,
+                "name": package.name,
+                "version": package.version,
+                "purl": purl,
+            });
+            if let Some(hash) = package.integrity.as_deref().and_then(integrity_hash) {
+                component["hashes"] = json!([hash]);
+            }
+            if let Some(license) = &package.license {
+                component["licenses"] = json!([{ "expression": license }]);
+            }
+            component
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": format!("urn:uuid:{}", uuid::Uuid::new_v4()),
+        "version": 1,
+        "metadata": {
+            "timestamp": generated_at,
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "openclaw-installer",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }],
+            },
+            "component": {
+                "type": "application",
+                "bom-ref": purl("openclaw", openclaw_version),
+                "name": "openclaw",
+                "version": openclaw_version,
+                "purl": purl("openclaw", openclaw_version),
+            },
+        },
+        "components": components,
+    })
+}
+
+/// `pkg:npm/%40scope/name@1.0.0`.
+fn purl(name: &str, version: &str) -> String {
+    format!("pkg:npm/{}@{version}", name.replacen('@', "%40", 1))
+}
+
+/// SSRI `sha512-<base64>` as a CycloneDX hash with hex content.
+fn integrity_hash(integrity: &str) -> Option<Value> {
+    // Several hashes may be listed; the first is the strongest npm recorded.
+    let (alg, digest) = integrity.split_whitespace().next()?.split_once('-')?;
+    let alg = match alg {
+        "sha512" => "SHA-512",
+        "sha384" => "SHA-384",
+        "sha256" => "SHA-256",
+        "sha1" => "SHA-1",
+        _ => return None,
+    };
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(digest)
+        .ok()?;
+    let content = bytes.iter().map(|b| format!("{b:02x}")).collect::<String>();
+    Some(json!({ "alg": alg, "content": content }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cyclonedx, lock_packages, purl};
+
+    #[test]
+    fn lockfile_packages_become_cyclonedx_components() {
+        let lock = r#"{
+            "lockfileVersion": 3,
+            "packages": {
+                "": { "dependencies": { "openclaw": "^2026.2.0" } },
+                "node_modules/openclaw": { "version": "2026.2.14", "integrity": "sha512-AAEC", "license": "MIT" },
+                "node_modules/@slack/bolt": { "version": "4.1.0" },
+                "node_modules/openclaw/node_modules/ws": { "version": "8.18.0" },
+                "node_modules/ws": { "version": "8.18.0" },
+                "node_modules/local-thing": { "link": true, "resolved": "../thing" }
+            }
+        }"#;
+        let packages = lock_packages(lock).expect("packages");
+        let names = packages.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["@slack/bolt", "openclaw", "ws"]);
+        assert_eq!(purl("@slack/bolt", "4.1.0"), "pkg:npm/%40slack/bolt@4.1.0");
+
+        let bom = cyclonedx("2026.2.14", &packages, "2026-10-18T10:00:00+02:00");
+        assert_eq!(bom["specVersion"], "1.5");
+        let openclaw = &bom["components"][1];
+        assert_eq!(openclaw["purl"], "pkg:npm/openclaw@2026.2.14");
+        assert_eq!(openclaw["hashes"][0]["alg"], "SHA-512");
+        assert_eq!(openclaw["hashes"][0]["content"], "000102");
+        assert_eq!(openclaw["licenses"][0]["expression"], "MIT");
+        assert!(lock_packages(r#"{"lockfileVersion":1,"dependencies":{}}"#).is_err());
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::Local;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{
+    ScriptScanConfig, ScriptScanProgress, ScriptScanReport, SecurityIssue, SecuritySeverity,
+};
+
+use super::{fs_walk, logger, paths, state_store};
+
+/// Tauri event carrying a `ScriptScanProgress` while a scan runs.
+pub const EVENT: &str = "security://scan-progress";
+
+const SCRIPT_EXTENSIONS: &[&str] = &["ps1", "bat", "cmd", "vbs", "js"];
+const PROGRESS_EVERY: u64 = 500;
+
+type Sink = Box<dyn Fn(&ScriptScanProgress) + Send + Sync>;
+
+static SINK: OnceLock<Sink> = OnceLock::new();
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(invoke-expression|downloadstring|frombase64string|powershell\s+-enc)")
+        .unwrap_or_else(|_| Regex::new("$^").unwrap())
+});
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+struct FileStamp {
+    len: u64,
+    modified_ms: i64,
+    suspicious: bool,
+}
+
+/// Verdicts of earlier scans; a file whose size and mtime are unchanged is not re-read.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ScanCache {
+    files: BTreeMap<String, FileStamp>,
+    last_report: Option<ScriptScanReport>,
+}
+
+#[derive(Default)]
+struct Counters {
+    seen: AtomicU64,
+    read: AtomicU64,
+    reused: AtomicU64,
+    flagged: AtomicU64,
+}
+
+impl Counters {
+    fn progress(&self, started: Instant) -> ScriptScanProgress {
+        ScriptScanProgress {
+            files_seen: self.seen.load(Ordering::Relaxed),
+            files_read: self.read.load(Ordering::Relaxed),
+            files_reused: self.reused.load(Ordering::Relaxed),
+            flagged: self.flagged.load(Ordering::Relaxed),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            ..ScriptScanProgress::default()
+        }
+    }
+}
+
+/// Registers where progress goes; `main` forwards it to the webview as `EVENT`.
+pub fn set_sink(sink: impl Fn(&ScriptScanProgress) + Send + Sync + 'static) {
+    let _ = SINK.set(Box::new(sink));
+}
+
+pub fn set_script_scan_config(cfg: &ScriptScanConfig) -> Result<ScriptScanConfig> {
+    if cfg.max_files == 0 || cfg.max_secs == 0 {
+        anyhow::bail!("Scan file and time budgets must both be at least 1.");
+    }
+    let mut cfg = cfg.clone();
+    cfg.exclude_dirs = cfg
+        .exclude_dirs
+        .iter()
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+        .collect();
+    state_store::save_script_scan(&cfg)?;
+    Ok(cfg)
+}
+
+/// Starts a scan on a background thread. Returns false if one is already running.
+pub fn start() -> bool {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return false;
+    }
+    thread::spawn(|| {
+        let cfg = state_store::load_script_scan().unwrap_or_default();
+        if let Err(err) = run(&cfg) {
+            logger::warn(&format!("Script scan failed: {err}"));
+            emit(ScriptScanProgress {
+                done: true,
+                error: Some(err.to_string()),
+                ..ScriptScanProgress::default()
+            });
+        }
+        RUNNING.store(false, Ordering::SeqCst);
+    });
+    true
+}
+
+pub fn last_report() -> Option<ScriptScanReport> {
+    load_cache().last_report
+}
+
+/// Findings of the last finished scan, for the security check. Kicks off a fresh scan
+/// in the background so the next check sees current results without waiting on it.
+pub fn security_issues() -> Vec<SecurityIssue> {
+    let report = last_report();
+    start();
+    issues_from(report)
+}
+
+/// Before the first scan has finished there is nothing to report yet; say so instead
+/// of letting an empty list read as "no suspicious scripts".
+fn issues_from(report: Option<ScriptScanReport>) -> Vec<SecurityIssue> {
+    match report {
+        Some(report) => report.findings,
+        None => vec![SecurityIssue {
+            severity: SecuritySeverity::Low,
+            message: "Script scan has not finished yet; scripts are not checked.".to_string(),
+            path: None,
+            suggestion: Some(
+                "Run the security check again in a minute to include the script scan.".to_string(),
+            ),
+            action: None,
+        }],
+    }
+}
+
+fn run(cfg: &ScriptScanConfig) -> Result<()> {
+    let started = Instant::now();
+    let deadline = started + Duration::from_secs(cfg.max_secs);
+    let mut cache = load_cache();
+    let mut findings = Vec::<SecurityIssue>::new();
+    let mut files = Vec::new();
+    let out_of_time = AtomicBool::new(false);
+    for root in scan_roots() {
+        let walk = fs_walk::Walker::new(&root)
+            .exclude_dirs(&cfg.exclude_dirs)
+            .filter(is_script)
+            .deadline(deadline)
+            .run();
+        // node_modules is normally excluded, but the shims npm puts in `.bin` are what
+        // actually runs when the CLI starts, so they are always read.
+        let bins = fs_walk::Walker::new(&root.join("node_modules").join(".bin"))
+            .deadline(deadline)
+            .run();
+        for walk in [walk, bins] {
+            if walk.timed_out {
+                out_of_time.store(true, Ordering::Relaxed);
+            }
+            findings.extend(walk.skipped_links.iter().map(|link| link_issue(link)));
+            files.extend(walk.files);
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files.dedup_by(|a, b| a.path == b.path);
+
+    let counters = Counters::default();
+    let out_of_files = AtomicBool::new(false);
+    let verdicts = files
+        .par_iter()
+        .filter_map(|file| {
+            if Instant::now() >= deadline {
+                out_of_time.store(true, Ordering::Relaxed);
+                return None;
+            }
+            let key = file.path.to_string_lossy().to_string();
+            let stamp = match cache.files.get(&key) {
+                Some(prev) if prev.len == file.len && prev.modified_ms == file.modified_ms => {
+                    counters.reused.fetch_add(1, Ordering::Relaxed);
+                    *prev
+                }
+                _ => {
+                    if counters.read.fetch_add(1, Ordering::Relaxed) >= cfg.max_files {
+                        counters.read.fetch_sub(1, Ordering::Relaxed);
+                        out_of_files.store(true, Ordering::Relaxed);
+                        return None;
+                    }
+                    let text = fs::read_to_string(&file.path).unwrap_or_default();
+                    FileStamp {
+                        len: file.len,
+                        modified_ms: file.modified_ms,
+                        suspicious: PATTERN.is_match(&text),
+                    }
+                }
+            };
+            if stamp.suspicious {
+                counters.flagged.fetch_add(1, Ordering::Relaxed);
+            }
+            if (counters.seen.fetch_add(1, Ordering::Relaxed) + 1) % PROGRESS_EVERY == 0 {
+                emit(counters.progress(started));
+            }
+            Some((key, stamp))
+        })
+        .collect::<Vec<_>>();
+
+    let mut seen = BTreeMap::<String, FileStamp>::new();
+    for (key, stamp) in verdicts {
+        if stamp.suspicious {
+            findings.push(script_issue(&key));
+        }
+        seen.insert(key, stamp);
+    }
+    let truncated = if out_of_time.load(Ordering::Relaxed) {
+        Some(format!("time budget of {}s reached", cfg.max_secs))
+    } else if out_of_files.load(Ordering::Relaxed) {
+        Some(format!("file budget of {} reached", cfg.max_files))
+    } else {
+        None
+    };
+
+    // A cut-short scan keeps earlier verdicts for files it did not reach, so their
+    // findings stay visible and the next run picks up from the cache.
+    if truncated.is_some() {
+        for (key, stamp) in &cache.files {
+            if seen.contains_key(key) || !Path::new(key).exists() {
+                continue;
+            }
+            if stamp.suspicious {
+                findings.push(script_issue(key));
+            }
+            seen.insert(key.clone(), *stamp);
+        }
+    }
+
+    let mut progress = counters.progress(started);
+    progress.done = true;
+    progress.truncated = truncated.clone();
+    let report = ScriptScanReport {
+        finished_at: Local::now().to_rfc3339(),
+        files_seen: progress.files_seen,
+        files_read: progress.files_read,
+        files_reused: progress.files_reused,
+        elapsed_ms: progress.elapsed_ms,
+        truncated,
+        findings,
+    };
+    cache.files = seen;
+    cache.last_report = Some(report.clone());
+    save_cache(&cache)?;
+    logger::info(&format!(
+        "Script scan finished: {} script(s), {} read, {} finding(s) in {} ms{}.",
+        report.files_seen,
+        report.files_read,
+        report.findings.len(),
+        report.elapsed_ms,
+        report
+            .truncated
+            .as_ref()
+            .map(|why| format!(" ({why})"))
+            .unwrap_or_default()
+    ));
+    emit(progress);
+    Ok(())
+}
+
+fn scan_roots() -> Vec<PathBuf> {
+    let mut roots = vec![paths::openclaw_home()];
+    if let Ok(Some(state)) = state_store::load_install_state() {
+        if !state.install_dir.trim().is_empty() {
+            let dir = PathBuf::from(state.install_dir);
+            if !roots.contains(&dir) {
+                roots.push(dir);
+            }
+        }
+    }
+    roots
+}
+
+fn is_script(path: &Path) -> bool {
+    path.extension()
+        .map(|v| v.to_string_lossy().to_ascii_lowercase())
+        .is_some_and(|ext| SCRIPT_EXTENSIONS.contains(&ext.as_str()))
+}
+
+fn script_issue(path: &str) -> SecurityIssue {
+    SecurityIssue {
+        severity: SecuritySeverity::High,
+        message: "Suspicious script pattern detected.".to_string(),
+        path: Some(path.to_string()),
+        suggestion: Some("Review this script before execution.".to_string()),
+        action: None,
+    }
+}
+
+fn link_issue(path: &Path) -> SecurityIssue {
+    SecurityIssue {
+        severity: SecuritySeverity::Medium,
+        message: "Junction or symlink inside a managed directory (not followed).".to_string(),
+        path: Some(path.to_string_lossy().to_string()),
+        suggestion: Some(
+            "Remove it unless you created it; backups and uninstall skip its target.".to_string(),
+        ),
+        action: None,
+    }
+}
+
+fn emit(progress: ScriptScanProgress) {
+    if let Some(sink) = SINK.get() {
+        sink(&progress);
+    }
+}
+
+fn cache_path() -> PathBuf {
+    paths::state_dir().join("script_scan_cache.json")
+}
+
+fn load_cache() -> ScanCache {
+    fs::read_to_string(cache_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(cache: &ScanCache) -> Result<()> {
+    paths::ensure_dirs()?;
+    fs::write(cache_path(), serde_json::to_string(cache)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::issues_from;
+    use crate::models::{ScriptScanReport, SecuritySeverity};
+
+    #[test]
+    fn missing_report_is_flagged_as_pending_not_clean() {
+        let pending = issues_from(None);
+        assert_eq!(pending.len(), 1);
+        assert!(matches!(pending[0].severity, SecuritySeverity::Low));
+        let clean = ScriptScanReport {
+            finished_at: String::new(),
+            files_seen: 10,
+            files_read: 10,
+            files_reused: 0,
+            elapsed_ms: 5,
+            truncated: None,
+            findings: vec![],
+        };
+        assert!(issues_from(Some(clean)).is_empty());
+    }
+}
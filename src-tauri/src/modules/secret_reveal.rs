@@ -0,0 +1,213 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+
+use crate::models::{OpenClawFileConfig, ProviderKeyState, SecretKind, SecretReveal};
+
+use super::{
+    browser, config, custom_provider, dotenv, logger, model_identity, paths, secrets, state_store,
+};
+
+const REVEAL_WINDOW: Duration = Duration::from_secs(30);
+
+// Secrets the user unmasked in this session, with when the window closes.
+static REVEALED: Mutex<BTreeMap<String, Instant>> = Mutex::new(BTreeMap::new());
+
+/// Returns `kind` masked. With `reveal`, the raw value is included for the next
+/// `REVEAL_WINDOW`; calls within the window keep returning it so the UI can redraw,
+/// after that it is masked again. Every reveal is written to the log.
+pub fn reveal_secret(
+    kind: SecretKind,
+    provider: Option<&str>,
+    reveal: bool,
+) -> Result<SecretReveal> {
+    let id = secret_id(kind, provider)?;
+    let raw =
+        read_secret(kind, provider)?.ok_or_else(|| anyhow!("No {} is configured.", label(kind)))?;
+    let mut revealed = REVEALED.lock().unwrap_or_else(|e| e.into_inner());
+    let now = Instant::now();
+    revealed.retain(|_, until| *until > now);
+    if reveal {
+        revealed.insert(id.clone(), now + REVEAL_WINDOW);
+        logger::info(&format!("Secret revealed: {id}"));
+    }
+    let remaining = revealed
+        .get(&id)
+        .map(|until| until.saturating_duration_since(now));
+    Ok(SecretReveal {
+        kind,
+        provider: provider.map(str::to_string),
+        masked: config::mask_key(&raw),
+        value: remaining.map(|_| raw.clone()),
+        expires_at: remaining.and_then(|left| {
+            chrono::Duration::from_std(left)
+                .ok()
+                .map(|left| (Local::now() + left).to_rfc3339())
+        }),
+    })
+}
+
+/// `read_current_config` for the webview: the key is masked, and every provider that is
+/// in the model chain or has a stored key (in the installer state or `.env`) only
+/// reports whether it has one.
+pub fn masked_current_config() -> Result<OpenClawFileConfig> {
+    let mut cfg = config::read_current_config()?;
+    if !cfg.api_key.trim().is_empty() {
+        cfg.api_key = config::mask_key(&cfg.api_key);
+    }
+    let last = state_store::load_last_config()?.unwrap_or_default();
+    let mut candidates = model_identity::builtin_providers()
+        .map(str::to_string)
+        .chain(last.provider_api_keys.keys().cloned())
+        .chain(Some(last.provider.clone()))
+        .collect::<Vec<_>>();
+    if let Ok(custom) = custom_provider::list_custom_providers() {
+        candidates.extend(custom.into_iter().map(|record| record.name));
+    }
+    cfg.provider_keys = provider_key_states(
+        &config::providers_from_model_chain(&cfg.model_chain),
+        candidates,
+        |provider| {
+            read_secret(SecretKind::ApiKey, Some(provider))
+                .ok()
+                .flatten()
+        },
+    );
+    Ok(cfg)
+}
+
+/// Model-chain providers are always listed (so a missing key shows up); any other
+/// candidate only when a key is stored for it.
+fn provider_key_states(
+    chain: &[String],
+    candidates: impl IntoIterator<Item = String>,
+    key_for: impl Fn(&str) -> Option<String>,
+) -> Vec<ProviderKeyState> {
+    let providers = chain
+        .iter()
+        .cloned()
+        .chain(candidates)
+        .map(|provider| model_identity::normalize_auth_provider(&provider))
+        .filter(|provider| !provider.is_empty())
+        .collect::<BTreeSet<_>>();
+    providers
+        .into_iter()
+        .filter_map(|provider| {
+            let key = key_for(&provider);
+            if key.is_none() && !chain.contains(&provider) {
+                return None;
+            }
+            Some(ProviderKeyState {
+                has_key: key.is_some(),
+                masked: key.as_deref().map(config::mask_key),
+                provider,
+            })
+        })
+        .collect()
+}
+
+/// Ends every open reveal, e.g. when the window goes to the tray or gets locked.
+pub fn hide_all() {
+    REVEALED.lock().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+fn secret_id(kind: SecretKind, provider: Option<&str>) -> Result<String> {
+    match (kind, provider.map(str::trim).filter(|p| !p.is_empty())) {
+        (SecretKind::ApiKey, Some(provider)) => Ok(format!(
+            "api_key:{}",
+            model_identity::normalize_auth_provider(provider)
+        )),
+        (SecretKind::ApiKey, None) => Err(anyhow!("Choose the provider whose API key to show.")),
+        (kind, _) => Ok(label(kind).replace(' ', "_")),
+    }
+}
+
+fn read_secret(kind: SecretKind, provider: Option<&str>) -> Result<Option<String>> {
+    let last = state_store::load_last_config()?.unwrap_or_default();
+    let value = match kind {
+        SecretKind::ApiKey => {
+            let provider = model_identity::normalize_auth_provider(provider.unwrap_or_default());
+            config::provider_key_for_id(&last, &provider).or_else(|| env_key(&provider))
+        }
+        SecretKind::GatewayToken => browser::read_gateway_token_from_config()?,
+        SecretKind::TelegramBotToken => Some(last.telegram_bot_token),
+        SecretKind::DiscordBotToken => Some(last.discord_bot_token),
+    };
+    Ok(value.filter(|v| !v.trim().is_empty()))
+}
+
+/// The provider's key from `.env`, unsealed when it is a DPAPI marker.
+fn env_key(provider: &str) -> Option<String> {
+    let names = model_identity::provider_env_names(provider);
+    let env_path = paths::openclaw_home().join(".env");
+    let sealed = secrets::resolve_env_file(&env_path);
+    let plain = dotenv::EnvFile::load(&env_path).ok()?;
+    names.iter().find_map(|name| {
+        sealed
+            .iter()
+            .find(|(sealed_name, _)| sealed_name == name)
+            .map(|(_, value)| value.clone())
+            .or_else(|| {
+                plain
+                    .get(name)
+                    .filter(|v| !secrets::is_reference(v))
+                    .map(str::to_string)
+            })
+    })
+}
+
+fn label(kind: SecretKind) -> &'static str {
+    match kind {
+        SecretKind::ApiKey => "API key",
+        SecretKind::GatewayToken => "gateway token",
+        SecretKind::TelegramBotToken => "Telegram bot token",
+        SecretKind::DiscordBotToken => "Discord bot token",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{provider_key_states, secret_id};
+    use crate::models::SecretKind;
+
+    #[test]
+    fn api_keys_need_a_provider_and_other_secrets_do_not() {
+        assert!(secret_id(SecretKind::ApiKey, None).is_err());
+        assert!(secret_id(SecretKind::ApiKey, Some(" ")).is_err());
+        assert_eq!(
+            secret_id(SecretKind::ApiKey, Some("openai-codex")).unwrap(),
+            "api_key:openai"
+        );
+        assert_eq!(
+            secret_id(SecretKind::GatewayToken, Some("openai")).unwrap(),
+            "gateway_token"
+        );
+    }
+
+    #[test]
+    fn provider_keys_cover_the_chain_and_every_stored_key() {
+        let chain = vec!["anthropic".to_string(), "openai".to_string()];
+        let candidates = ["openai-codex", "openrouter", "xai", ""].map(str::to_string);
+        let states = provider_key_states(&chain, candidates, |provider| match provider {
+            "openai" => Some("sk-proj-1234567890abcd".to_string()),
+            "openrouter" => Some("sk-or-v1-0987654321wxyz".to_string()),
+            _ => None,
+        });
+        let listed = states
+            .iter()
+            .map(|state| (state.provider.as_str(), state.has_key))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            listed,
+            vec![("anthropic", false), ("openai", true), ("openrouter", true)]
+        );
+        for state in &states {
+            let masked = state.masked.as_deref().unwrap_or_default();
+            assert!(!masked.contains("1234567890") && !masked.contains("0987654321"));
+        }
+        assert!(states[0].masked.is_none());
+    }
+}
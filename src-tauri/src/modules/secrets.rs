@@ -0,0 +1,293 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use regex::Regex;
+
+use super::{daemon, dotenv::EnvFile, gateway_service, logger, paths};
+
+/// `.env` value standing in for a key sealed in the secret store, e.g. `dpapi:OPENAI_API_KEY`.
+pub const MARKER_PREFIX: &str = "dpapi:";
+
+pub fn is_reference(value: &str) -> bool {
+    value.trim().starts_with(MARKER_PREFIX)
+}
+
+/// Env names that hold credentials: `*API_KEY*` and `*TOKEN*`.
+pub fn is_credential_name(name: &str) -> bool {
+    Regex::new(r"(?i)^[A-Z0-9_]*(?:API_KEY|TOKEN)[A-Z0-9_]*$")
+        .map(|re| re.is_match(name))
+        .unwrap_or(false)
+}
+
+/// Replaces credential values with markers once they are sealed with DPAPI. Values are
+/// left as-is when DPAPI is unavailable or the OpenClaw daemon or gateway service is
+/// installed: those start the gateway outside this user's session and could not unseal.
+pub fn seal_env_values(values: &mut BTreeMap<String, String>) {
+    if !sealing_enabled() {
+        return;
+    }
+    let original = values.clone();
+    let mut store = load_store();
+    let mut sealed = 0usize;
+    for (name, value) in values.iter_mut() {
+        if is_reference(value) || value.trim().is_empty() {
+            continue;
+        }
+        match dpapi::protect(value.as_bytes()) {
+            Ok(blob) => {
+                store.insert(
+                    name.clone(),
+                    base64::engine::general_purpose::STANDARD.encode(blob),
+                );
+                *value = format!("{MARKER_PREFIX}{name}");
+                sealed += 1;
+            }
+            Err(err) => logger::warn(&format!("DPAPI could not seal {name}: {err}")),
+        }
+    }
+    if sealed > 0 {
+        if let Err(err) = save_store(&store) {
+            // Without the store the markers would point nowhere; keep the raw values.
+            logger::error(&format!("Failed to save secret store: {err}"));
+            *values = original;
+        }
+    }
+}
+
+/// Drops sealed values for env names that were removed from `.env`.
+pub fn forget(names: &[String]) {
+    let mut store = load_store();
+    let before = store.len();
+    store.retain(|name, _| !names.contains(name));
+    if store.len() != before {
+        if let Err(err) = save_store(&store) {
+            logger::warn(&format!("Failed to update secret store: {err}"));
+        }
+    }
+}
+
+/// Decrypted values for every marker in `env_path`, for the gateway's runtime env only.
+pub fn resolve_env_file(env_path: &Path) -> Vec<(String, String)> {
+    let Ok(env) = EnvFile::load(env_path) else {
+        return Vec::new();
+    };
+    let store = load_store();
+    let mut out = Vec::new();
+    for name in env.keys() {
+        let Some(value) = env.get(name).filter(|v| is_reference(v)) else {
+            continue;
+        };
+        let id = value.trim().trim_start_matches(MARKER_PREFIX);
+        match store.get(id).map(|blob| open(blob)) {
+            Some(Ok(raw)) => out.push((name.to_string(), raw)),
+            Some(Err(err)) => logger::warn(&format!("Could not unseal {name}: {err}")),
+            None => logger::warn(&format!("{name} references a missing sealed value.")),
+        }
+    }
+    out
+}
+
+/// Seals plaintext credentials already in `.env` (from older installs or manual edits).
+/// Returns the env names that were converted.
+pub fn seal_existing_env() -> Result<Vec<String>> {
+    if !sealing_enabled() {
+        return Err(anyhow!(
+            "Keys cannot be sealed here: DPAPI is unavailable or the OpenClaw daemon or gateway service is installed."
+        ));
+    }
+    let env_path = paths::openclaw_home().join(".env");
+    let mut env = EnvFile::load(&env_path)?;
+    let mut values = env
+        .keys()
+        .into_iter()
+        .filter(|name| is_credential_name(name))
+        .filter_map(|name| {
+            env.get(name)
+                .filter(|v| !v.trim().is_empty() && !is_reference(v))
+                .map(|v| (name.to_string(), v.to_string()))
+        })
+        .collect::<BTreeMap<_, _>>();
+    seal_env_values(&mut values);
+    let sealed = values
+        .into_iter()
+        .filter(|(_, value)| is_reference(value))
+        .collect::<Vec<_>>();
+    for (name, marker) in &sealed {
+        env.set(name, marker);
+    }
+    if !sealed.is_empty() {
+        env.save(&env_path)?;
+    }
+    logger::info(&format!(
+        "Sealed {} plaintext key(s) in .env.",
+        sealed.len()
+    ));
+    Ok(sealed.into_iter().map(|(name, _)| name).collect())
+}
+
+/// Seals a single value outside `.env` (e.g. a PIN hash in a state file) as a base64
+/// DPAPI blob. `None` where DPAPI is unavailable.
+pub fn seal_value(raw: &str) -> Option<Result<String>> {
+    if !dpapi::AVAILABLE {
+        return None;
+    }
+    Some(
+        dpapi::protect(raw.as_bytes())
+            .map(|blob| base64::engine::general_purpose::STANDARD.encode(blob)),
+    )
+}
+
+/// Whether `seal_value` seals anything here.
+pub fn sealing_available() -> bool {
+    dpapi::AVAILABLE
+}
+
+pub fn open_value(blob: &str) -> Result<String> {
+    open(blob)
+}
+
+fn sealing_enabled() -> bool {
+    dpapi::AVAILABLE && daemon::detect().is_none() && gateway_service::detect().is_none()
+}
+
+fn open(blob: &str) -> Result<String> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(blob.trim())?;
+    Ok(String::from_utf8(dpapi::unprotect(&bytes)?)?)
+}
+
+fn store_path() -> PathBuf {
+    paths::state_dir().join("secrets.json")
+}
+
+fn load_store() -> BTreeMap<String, String> {
+    fs::read_to_string(store_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_store(store: &BTreeMap<String, String>) -> Result<()> {
+    paths::ensure_dirs()?;
+    fs::write(store_path(), serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
+
+#[cfg(windows)]
+mod dpapi {
+    use anyhow::{anyhow, Result};
+    use windows_sys::Win32::Foundation::LocalFree;
+    use windows_sys::Win32::Security::Cryptography::{
+        CryptProtectData, CryptUnprotectData, CRYPTPROTECT_UI_FORBIDDEN, CRYPT_INTEGER_BLOB,
+    };
+
+    pub const AVAILABLE: bool = true;
+
+    // Binds blobs to this app so other tools running as the user cannot unseal them
+    // with a bare CryptUnprotectData call.
+    const ENTROPY: &[u8] = b"OpenClawInstaller.secrets.v1";
+
+    pub fn protect(data: &[u8]) -> Result<Vec<u8>> {
+        transform(data, true)
+    }
+
+    pub fn unprotect(data: &[u8]) -> Result<Vec<u8>> {
+        transform(data, false)
+    }
+
+    fn transform(data: &[u8], seal: bool) -> Result<Vec<u8>> {
+        let input = CRYPT_INTEGER_BLOB {
+            cbData: data.len() as u32,
+            pbData: data.as_ptr() as *mut u8,
+        };
+        let entropy = CRYPT_INTEGER_BLOB {
+            cbData: ENTROPY.len() as u32,
+            pbData: ENTROPY.as_ptr() as *mut u8,
+        };
+        let mut output = CRYPT_INTEGER_BLOB {
+            cbData: 0,
+            pbData: std::ptr::null_mut(),
+        };
+        // SAFETY: input and entropy point at live slices the calls only read; on
+        // success `output` receives a LocalAlloc buffer that is copied and freed below.
+        let ok = unsafe {
+            if seal {
+                CryptProtectData(
+                    &input,
+                    std::ptr::null(),
+                    &entropy,
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    CRYPTPROTECT_UI_FORBIDDEN,
+                    &mut output,
+                )
+            } else {
+                CryptUnprotectData(
+                    &input,
+                    std::ptr::null_mut(),
+                    &entropy,
+                    std::ptr::null(),
+                    std::ptr::null(),
+                    CRYPTPROTECT_UI_FORBIDDEN,
+                    &mut output,
+                )
+            }
+        };
+        if ok == 0 {
+            return Err(anyhow!(
+                "DPAPI call failed: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        // SAFETY: on success pbData/cbData describe a buffer owned by us until LocalFree.
+        let bytes = unsafe {
+            let out = std::slice::from_raw_parts(output.pbData, output.cbData as usize).to_vec();
+            LocalFree(output.pbData as _);
+            out
+        };
+        Ok(bytes)
+    }
+}
+
+#[cfg(not(windows))]
+mod dpapi {
+    use anyhow::{anyhow, Result};
+
+    pub const AVAILABLE: bool = false;
+
+    pub fn protect(_data: &[u8]) -> Result<Vec<u8>> {
+        Err(anyhow!("DPAPI is only available on Windows."))
+    }
+
+    pub fn unprotect(_data: &[u8]) -> Result<Vec<u8>> {
+        Err(anyhow!("DPAPI is only available on Windows."))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_credential_name, is_reference};
+
+    #[test]
+    fn markers_and_credential_names_are_recognized() {
+        assert!(is_reference("dpapi:OPENAI_API_KEY"));
+        assert!(is_reference("  dpapi:OPENAI_API_KEY"));
+        assert!(!is_reference("sk-live-123"));
+        assert!(is_credential_name("OPENAI_API_KEY"));
+        assert!(is_credential_name("TELEGRAM_BOT_TOKEN"));
+        assert!(!is_credential_name("HTTP_PROXY"));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn values_stay_plaintext_without_dpapi() {
+        use super::seal_env_values;
+        use std::collections::BTreeMap;
+
+        let mut values = BTreeMap::from([("OPENAI_API_KEY".to_string(), "sk-1".to_string())]);
+        seal_env_values(&mut values);
+        assert_eq!(values["OPENAI_API_KEY"], "sk-1");
+    }
+}
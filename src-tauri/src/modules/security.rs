@@ -1,13 +1,36 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::Path;
 
-use anyhow::Result;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Local};
 use regex::Regex;
-use walkdir::WalkDir;
+use serde_json::Value;
+
+use crate::models::{
+    SecurityAction, SecurityAlert, SecurityCheckRecord, SecurityIssue, SecurityResult,
+    SecurityScheduleConfig, SecurityScheduleStatus, SecuritySeverity,
+};
+
+use super::{
+    config, config_watch, dotenv, file_audit, firewall, json_layout, logger, model_identity, paths,
+    port, process, process_tree, profiles, script_scan, secrets, shell, state_store,
+};
 
-use crate::models::{SecurityIssue, SecurityResult, SecuritySeverity};
+pub const ALERT_EVENT: &str = "security-alert";
+// How often the scheduler looks whether a check is due.
+const SCHEDULE_POLL: Duration = Duration::from_secs(300);
+// Windows PowerShell's AppUserModelID; toasts need a registered one to show.
+const TOAST_APP_ID: &str =
+    "{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}\\WindowsPowerShell\\v1.0\\powershell.exe";
 
-use super::{logger, paths, shell, state_store};
+type AlertSink = Box<dyn Fn(&SecurityAlert) + Send + Sync>;
+
+static ALERT_SINK: OnceLock<AlertSink> = OnceLock::new();
 
 pub fn run_security_check() -> Result<SecurityResult> {
     let mut issues = Vec::<SecurityIssue>::new();
@@ -24,6 +47,7 @@ pub fn run_security_check() -> Result<SecurityResult> {
                 suggestion: Some(
                     "Restrict ACL to current user and rotate key regularly.".to_string(),
                 ),
+                action: None,
             });
             score -= 15;
         }
@@ -33,6 +57,7 @@ pub fn run_security_check() -> Result<SecurityResult> {
                 message: "Config ACL appears to allow broad read access.".to_string(),
                 path: Some(config_path.to_string_lossy().to_string()),
                 suggestion: Some("Run configure again or tighten ACL with icacls.".to_string()),
+                action: None,
             });
             score -= 35;
         }
@@ -42,6 +67,7 @@ pub fn run_security_check() -> Result<SecurityResult> {
             message: "Config file does not exist yet.".to_string(),
             path: Some(config_path.to_string_lossy().to_string()),
             suggestion: Some("Run setup wizard to generate config.".to_string()),
+            action: None,
         });
         score -= 5;
     }
@@ -49,33 +75,86 @@ pub fn run_security_check() -> Result<SecurityResult> {
     let env_path = paths::openclaw_home().join(".env");
     if env_path.exists() {
         let content = fs::read_to_string(&env_path).unwrap_or_default();
-        if contains_plaintext_env_key(&content) {
+        let credentials = plaintext_env_credentials(&content);
+        if !credentials.is_empty() {
             issues.push(SecurityIssue {
                 severity: SecuritySeverity::Medium,
-                message: "API key/token appears in plaintext in .env.".to_string(),
+                message: format!(
+                    "API key/token appears in plaintext in .env ({} credential(s): {}).",
+                    credentials.len(),
+                    credentials
+                        .keys()
+                        .map(String::as_str)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
                 path: Some(env_path.to_string_lossy().to_string()),
                 suggestion: Some(
-                    "Restrict .env ACL to current user and rotate leaked keys.".to_string(),
+                    "Seal the keys with Windows DPAPI from Maintenance, and rotate any that leaked."
+                        .to_string(),
                 ),
+                action: None,
             });
             score -= 15;
         }
+        let diverging = credentials
+            .iter()
+            .filter(|(_, values)| values.len() > 1)
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        if !diverging.is_empty() {
+            issues.push(SecurityIssue {
+                severity: SecuritySeverity::Low,
+                message: format!(
+                    "Env aliases of {} hold different keys; OpenClaw components may use different credentials.",
+                    diverging.join(", ")
+                ),
+                path: Some(env_path.to_string_lossy().to_string()),
+                suggestion: Some(
+                    "Update the provider key from maintenance so every alias is rewritten."
+                        .to_string(),
+                ),
+                action: None,
+            });
+            score -= 5;
+        }
         if acl_is_wide_open(&env_path)? {
             issues.push(SecurityIssue {
                 severity: SecuritySeverity::High,
                 message: ".env ACL appears to allow broad read access.".to_string(),
                 path: Some(env_path.to_string_lossy().to_string()),
                 suggestion: Some("Re-run setup or tighten ACL with icacls.".to_string()),
+                action: None,
             });
             score -= 35;
         }
     }
 
-    for finding in suspicious_scripts() {
-        score -= 20;
+    for finding in script_scan::security_issues() {
+        // The "scan still pending" note is Low and says nothing about the machine.
+        if !matches!(finding.severity, SecuritySeverity::Low) {
+            score -= 20;
+        }
         issues.push(finding);
     }
 
+    let audit_issues = file_audit::security_issues();
+    if !audit_issues.is_empty() {
+        score -= 25;
+        issues.extend(audit_issues);
+    }
+
+    let watch_issues = config_watch::security_issues();
+    if !watch_issues.is_empty() {
+        score -= 15;
+        issues.extend(watch_issues);
+    }
+
+    for issue in gateway_exposure_issues() {
+        score -= severity_penalty(&issue.severity);
+        issues.push(issue);
+    }
+
     score = score.clamp(0, 100);
     logger::info(&format!(
         "Security check completed. score={score}, issues={}",
@@ -93,10 +172,28 @@ fn contains_plaintext_key(content: &str) -> bool {
     re.is_match(content)
 }
 
-fn contains_plaintext_env_key(content: &str) -> bool {
-    let re = Regex::new(r"(?im)^(?:[A-Z0-9_]*(?:API_KEY|TOKEN)[A-Z0-9_]*)\s*=\s*.+$")
-        .unwrap_or_else(|_| Regex::new("$^").unwrap());
-    re.is_match(content)
+/// Plaintext keys/tokens in `.env`, with provider aliases folded into their primary name
+/// so one key written under several names counts once. Values are the distinct secrets.
+fn plaintext_env_credentials(content: &str) -> BTreeMap<String, BTreeSet<String>> {
+    let env = dotenv::EnvFile::parse(content);
+    let mut out = BTreeMap::<String, BTreeSet<String>>::new();
+    for name in env
+        .keys()
+        .into_iter()
+        .filter(|name| secrets::is_credential_name(name))
+    {
+        // DPAPI markers are references, not keys.
+        let Some(value) = env
+            .get(name)
+            .filter(|value| !value.trim().is_empty() && !secrets::is_reference(value))
+        else {
+            continue;
+        };
+        out.entry(model_identity::canonical_env_name(name))
+            .or_default()
+            .insert(value.to_string());
+    }
+    out
 }
 
 fn acl_is_wide_open(path: &Path) -> Result<bool> {
@@ -110,41 +207,397 @@ fn acl_is_wide_open(path: &Path) -> Result<bool> {
     Ok(lower.contains("everyone:(r)") || lower.contains("builtin\\users:(r)"))
 }
 
-fn suspicious_scripts() -> Vec<SecurityIssue> {
-    let mut out = Vec::new();
-    let mut roots = vec![paths::openclaw_home()];
-    if let Ok(Some(state)) = state_store::load_install_state() {
-        roots.push(Path::new(&state.install_dir).to_path_buf());
-    }
-    let pattern =
-        Regex::new(r"(?i)(invoke-expression|downloadstring|frombase64string|powershell\s+-enc)")
-            .unwrap_or_else(|_| Regex::new("$^").unwrap());
-    for root in roots {
-        if !root.exists() {
-            continue;
+/// Applies the fix attached to a security issue and checks again.
+pub fn apply_security_action(action: SecurityAction) -> Result<SecurityResult> {
+    let cfg = config::read_current_config()?;
+    match action {
+        SecurityAction::AddFirewallRule => {
+            firewall::add_firewall_rule(cfg.port)?;
         }
-        for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
-            let path = entry.path();
-            if !path.is_file() {
-                continue;
+        SecurityAction::BindLoopback => {
+            set_gateway_config(&[("gateway.bind", "loopback")])?;
+            if let Some(mut last) = state_store::load_last_config()? {
+                last.bind_address = "127.0.0.1".to_string();
+                state_store::save_last_config(&last)?;
             }
-            let ext = path
-                .extension()
-                .map(|v| v.to_string_lossy().to_ascii_lowercase())
-                .unwrap_or_default();
-            if !["ps1", "bat", "cmd", "vbs", "js"].contains(&ext.as_str()) {
-                continue;
+            // No longer reachable from the LAN, so the inbound rule has no purpose.
+            if firewall::rule_exists(cfg.port) {
+                let _ = firewall::remove_firewall_rule(cfg.port);
             }
-            let text = fs::read_to_string(path).unwrap_or_default();
-            if pattern.is_match(&text) {
-                out.push(SecurityIssue {
-                    severity: SecuritySeverity::High,
-                    message: "Suspicious script pattern detected.".to_string(),
-                    path: Some(path.to_string_lossy().to_string()),
-                    suggestion: Some("Review this script before execution.".to_string()),
-                });
+            process::restart()?;
+        }
+        SecurityAction::EnableTokenAuth => {
+            // Written into openclaw.json directly: `openclaw config set` would put the new
+            // token on a command line, readable by every local process.
+            let path = paths::config_path();
+            let mut json: Value = serde_json::from_str(&fs::read_to_string(&path)?)?;
+            set_token_auth(&mut json, &config::generate_gateway_token(40))?;
+            json_layout::write_preserving_layout(&path, &json)?;
+            process::restart()?;
+        }
+    }
+    logger::info(&format!("Security action applied: {action:?}"));
+    run_security_check()
+}
+
+fn set_gateway_config(values: &[(&str, &str)]) -> Result<()> {
+    let proxy = state_store::load_last_config()?.and_then(|cfg| cfg.proxy);
+    for (key, value) in values {
+        let out = config::run_openclaw_cli(
+            &[
+                "config".to_string(),
+                "set".to_string(),
+                key.to_string(),
+                value.to_string(),
+            ],
+            proxy.clone(),
+        )?;
+        shell::ensure_success(&format!("openclaw config set {key}"), &out)?;
+    }
+    Ok(())
+}
+
+fn set_token_auth(json: &mut Value, token: &str) -> Result<()> {
+    let mut node = json;
+    for key in ["gateway", "auth"] {
+        node = node
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("openclaw.json has an unexpected shape at '{key}'."))?
+            .entry(key)
+            .or_insert_with(|| Value::Object(Default::default()));
+    }
+    let auth = node
+        .as_object_mut()
+        .ok_or_else(|| anyhow!("openclaw.json has an unexpected shape at 'auth'."))?;
+    auth.insert("mode".to_string(), Value::from("token"));
+    auth.insert("token".to_string(), Value::from(token));
+    Ok(())
+}
+
+/// Bind mode, auth mode and the owner of the gateway port, from openclaw.json and the
+/// live listener table.
+fn gateway_exposure_issues() -> Vec<SecurityIssue> {
+    let Ok(raw) = fs::read_to_string(paths::config_path()) else {
+        return Vec::new();
+    };
+    let Ok(json) = serde_json::from_str::<Value>(&raw) else {
+        return Vec::new();
+    };
+    let Ok(cfg) = config::read_current_config() else {
+        return Vec::new();
+    };
+    let lan = cfg.bind_address == "0.0.0.0";
+    let mut issues =
+        config_exposure_issues(&json, cfg.port, lan, lan && firewall::rule_exists(cfg.port));
+    if let Some(issue) = foreign_listener_issue(cfg.port) {
+        issues.push(issue);
+    }
+    issues
+}
+
+fn config_exposure_issues(
+    json: &Value,
+    port: u16,
+    lan: bool,
+    has_firewall_rule: bool,
+) -> Vec<SecurityIssue> {
+    let mut issues = Vec::new();
+    let config_path = Some(paths::config_path().to_string_lossy().to_string());
+    if lan && !has_firewall_rule {
+        issues.push(SecurityIssue {
+            severity: SecuritySeverity::Medium,
+            message: format!(
+                "Gateway listens on all interfaces (0.0.0.0:{port}) without the installer's firewall rule; it is reachable on any network Windows allowed node.exe on."
+            ),
+            path: config_path.clone(),
+            suggestion: Some(
+                "Add the scoped firewall rule, or bind to 127.0.0.1 if LAN access is not needed."
+                    .to_string(),
+            ),
+            action: Some(SecurityAction::AddFirewallRule),
+        });
+    }
+    let auth_mode = json
+        .pointer("/gateway/auth/mode")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if auth_mode.eq_ignore_ascii_case("none") {
+        issues.push(SecurityIssue {
+            severity: if lan {
+                SecuritySeverity::High
+            } else {
+                SecuritySeverity::Medium
+            },
+            message: if lan {
+                format!("Gateway auth is \"none\" while port {port} is open to the LAN; anyone on the network can drive the agent.")
+            } else {
+                "Gateway auth is \"none\"; any local program can drive the agent.".to_string()
+            },
+            path: config_path,
+            suggestion: Some("Require a gateway token.".to_string()),
+            action: Some(SecurityAction::EnableTokenAuth),
+        });
+    }
+    issues
+}
+
+/// The process listening on the gateway port must be the managed gateway (or one of
+/// its children); anything else may be impersonating it to collect tokens.
+fn foreign_listener_issue(port: u16) -> Option<SecurityIssue> {
+    let listener = port::check_port(port).ok()?;
+    let pid = listener.pid?;
+    let managed = match process::running_pid() {
+        Some(_) => process_tree::get_process_tree()
+            .map(|tree| tree.processes.iter().any(|node| node.pid == pid))
+            .unwrap_or(true),
+        // Service/daemon-run gateways have no PID file; fall back to what the owner is.
+        None => port::inspect_port(port)
+            .map(|owner| owner.looks_like_openclaw)
+            .unwrap_or(true),
+    };
+    if managed {
+        return None;
+    }
+    Some(SecurityIssue {
+        severity: SecuritySeverity::High,
+        message: format!(
+            "Port {port} is held by {} (PID {pid}), not the OpenClaw gateway this installer manages.",
+            listener
+                .process_name
+                .unwrap_or_else(|| "an unknown process".to_string())
+        ),
+        path: None,
+        suggestion: Some(format!(
+            "Find PID {pid} in Task Manager (Details tab); stop it if you do not recognize it, or move the gateway to another port."
+        )),
+        action: None,
+    })
+}
+
+fn severity_penalty(severity: &SecuritySeverity) -> i32 {
+    match severity {
+        SecuritySeverity::High => 35,
+        SecuritySeverity::Medium => 15,
+        SecuritySeverity::Low => 5,
+    }
+}
+
+/// Registers where alerts go; `main` forwards them to the webview as `ALERT_EVENT`.
+pub fn set_alert_sink(sink: impl Fn(&SecurityAlert) + Send + Sync + 'static) {
+    let _ = ALERT_SINK.set(Box::new(sink));
+}
+
+pub fn configure_security_schedule(cfg: &SecurityScheduleConfig) -> Result<SecurityScheduleConfig> {
+    if cfg.interval_hours == 0 {
+        return Err(anyhow!(
+            "Security check interval must be at least one hour."
+        ));
+    }
+    state_store::save_security_schedule(cfg)?;
+    logger::info(&format!(
+        "Scheduled security check {} (every {} h).",
+        if cfg.enabled { "enabled" } else { "disabled" },
+        cfg.interval_hours
+    ));
+    Ok(cfg.clone())
+}
+
+pub fn security_schedule_status() -> Result<SecurityScheduleStatus> {
+    Ok(SecurityScheduleStatus {
+        config: state_store::load_security_schedule()?,
+        last: state_store::load_security_last_check()?,
+    })
+}
+
+/// Background loop; runs a check whenever the last recorded one is older than the
+/// interval, so the schedule survives installer restarts.
+pub fn start_scheduler() {
+    thread::spawn(|| loop {
+        let tick = profiles::worker_tick();
+        let cfg = state_store::load_security_schedule().unwrap_or_default();
+        if cfg.enabled && check_due(&cfg) {
+            if let Err(err) = run_scheduled_check() {
+                logger::warn(&format!("Scheduled security check failed: {err}"));
             }
         }
+        drop(tick);
+        thread::sleep(SCHEDULE_POLL);
+    });
+}
+
+fn check_due(cfg: &SecurityScheduleConfig) -> bool {
+    let Ok(Some(last)) = state_store::load_security_last_check() else {
+        return true;
+    };
+    DateTime::parse_from_rfc3339(&last.checked_at)
+        .map(|at| {
+            Local::now().signed_duration_since(at)
+                >= chrono::Duration::hours(i64::from(cfg.interval_hours))
+        })
+        .unwrap_or(true)
+}
+
+fn run_scheduled_check() -> Result<()> {
+    let previous = state_store::load_security_last_check()?;
+    let result = run_security_check()?;
+    let alert = alert_for(previous.as_ref().map(|record| &record.result), &result);
+    state_store::save_security_last_check(&SecurityCheckRecord {
+        checked_at: Local::now().to_rfc3339(),
+        result,
+    })?;
+    if let Some(alert) = alert {
+        logger::warn(&format!("Security alert: {}", alert.message));
+        show_toast(&alert.title, &alert.message);
+        if let Some(sink) = ALERT_SINK.get() {
+            sink(&alert);
+        }
+    }
+    Ok(())
+}
+
+/// An alert when the score went down or a High issue appeared that the previous check
+/// did not report. The first check only alerts on High issues.
+fn alert_for(previous: Option<&SecurityResult>, current: &SecurityResult) -> Option<SecurityAlert> {
+    let known_high = previous
+        .map(|prev| high_issues(prev).collect::<BTreeSet<_>>())
+        .unwrap_or_default();
+    let new_high = high_issues(current)
+        .filter(|message| !known_high.contains(message))
+        .collect::<Vec<_>>();
+    let dropped = previous.is_some_and(|prev| current.score < prev.score);
+    if new_high.is_empty() && !dropped {
+        return None;
+    }
+    let message = match new_high.first() {
+        Some(first) if new_high.len() > 1 => {
+            format!(
+                "{first} (+{} more high severity issues)",
+                new_high.len() - 1
+            )
+        }
+        Some(first) => first.to_string(),
+        None => format!(
+            "Security score dropped from {} to {}.",
+            previous.map(|prev| prev.score).unwrap_or_default(),
+            current.score
+        ),
+    };
+    Some(SecurityAlert {
+        title: format!("OpenClaw security score: {}", current.score),
+        message,
+        score: current.score,
+        previous_score: previous.map(|prev| prev.score),
+    })
+}
+
+fn high_issues(result: &SecurityResult) -> impl Iterator<Item = &str> {
+    result
+        .issues
+        .iter()
+        .filter(|issue| matches!(issue.severity, SecuritySeverity::High))
+        .map(|issue| issue.message.as_str())
+}
+
+/// Windows toast through the WinRT API from PowerShell; text is passed in the
+/// environment so it needs no quoting.
+pub fn show_toast(title: &str, message: &str) {
+    let script = "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+        $t = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+        $n = $t.GetElementsByTagName('text'); \
+        $n.Item(0).AppendChild($t.CreateTextNode($env:OPENCLAW_TOAST_TITLE)) > $null; \
+        $n.Item(1).AppendChild($t.CreateTextNode($env:OPENCLAW_TOAST_MESSAGE)) > $null; \
+        [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($env:OPENCLAW_TOAST_APP).Show([Windows.UI.Notifications.ToastNotification]::new($t))";
+    let envs = vec![
+        ("OPENCLAW_TOAST_TITLE".to_string(), title.to_string()),
+        ("OPENCLAW_TOAST_MESSAGE".to_string(), message.to_string()),
+        ("OPENCLAW_TOAST_APP".to_string(), TOAST_APP_ID.to_string()),
+    ];
+    match shell::run_command(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", script],
+        None,
+        &envs,
+    ) {
+        Ok(out) if out.code == 0 => {}
+        Ok(out) => logger::warn(&format!("Toast notification failed: {}", out.stderr.trim())),
+        Err(err) => logger::warn(&format!("Toast notification failed: {err}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{alert_for, config_exposure_issues, plaintext_env_credentials, set_token_auth};
+    use crate::models::{SecurityAction, SecurityIssue, SecurityResult, SecuritySeverity};
+
+    #[test]
+    fn plaintext_env_credentials_fold_provider_aliases() {
+        let env = "GEMINI_API_KEY=g-1\nGOOGLE_API_KEY=g-1\nGOOGLE_GENERATIVE_AI_API_KEY=g-2\nOPENAI_API_KEY=\nMOONSHOT_API_KEY=dpapi:MOONSHOT_API_KEY\nHTTP_PROXY=http://127.0.0.1:7890\n";
+        let found = plaintext_env_credentials(env);
+        assert_eq!(found.keys().collect::<Vec<_>>(), vec!["GEMINI_API_KEY"]);
+        assert_eq!(found["GEMINI_API_KEY"].len(), 2);
+    }
+
+    #[test]
+    fn lan_binds_and_missing_auth_are_flagged_with_fixes() {
+        let token = serde_json::json!({ "gateway": { "auth": { "mode": "token" } } });
+        let open = serde_json::json!({ "gateway": { "auth": { "mode": "none" } } });
+        assert!(config_exposure_issues(&token, 28789, false, false).is_empty());
+        assert!(config_exposure_issues(&token, 28789, true, true).is_empty());
+
+        let issues = config_exposure_issues(&open, 28789, true, false);
+        let actions = issues.iter().map(|i| i.action).collect::<Vec<_>>();
+        assert_eq!(
+            actions,
+            vec![
+                Some(SecurityAction::AddFirewallRule),
+                Some(SecurityAction::EnableTokenAuth)
+            ]
+        );
+        assert!(matches!(issues[1].severity, SecuritySeverity::High));
+        let local = config_exposure_issues(&open, 28789, false, false);
+        assert!(matches!(local[0].severity, SecuritySeverity::Medium));
+    }
+
+    #[test]
+    fn token_auth_is_written_into_the_config() {
+        let mut json =
+            serde_json::json!({ "gateway": { "port": 28789, "auth": { "mode": "none" } } });
+        set_token_auth(&mut json, "t0ken").expect("set token auth");
+        assert_eq!(json.pointer("/gateway/auth/mode"), Some(&"token".into()));
+        assert_eq!(json.pointer("/gateway/auth/token"), Some(&"t0ken".into()));
+        assert_eq!(json.pointer("/gateway/port"), Some(&28789.into()));
+
+        let mut empty = serde_json::json!({});
+        set_token_auth(&mut empty, "t0ken").expect("create gateway.auth");
+        assert_eq!(empty.pointer("/gateway/auth/mode"), Some(&"token".into()));
+        assert!(set_token_auth(&mut serde_json::json!({ "gateway": 1 }), "t0ken").is_err());
+    }
+
+    #[test]
+    fn alerts_fire_on_score_drops_and_new_high_issues() {
+        let issue = |severity, message: &str| SecurityIssue {
+            severity,
+            message: message.to_string(),
+            path: None,
+            suggestion: None,
+            action: None,
+        };
+        let clean = SecurityResult {
+            score: 95,
+            issues: vec![issue(SecuritySeverity::Low, "Logs are readable.")],
+        };
+        let exposed = SecurityResult {
+            score: 60,
+            issues: vec![issue(SecuritySeverity::High, "Gateway bound to 0.0.0.0.")],
+        };
+        assert!(alert_for(None, &clean).is_none());
+        assert!(alert_for(Some(&clean), &clean).is_none());
+
+        let alert = alert_for(Some(&clean), &exposed).expect("alert");
+        assert_eq!(alert.message, "Gateway bound to 0.0.0.0.");
+        assert_eq!(alert.previous_score, Some(95));
+        // Same High issue again with the same score: already reported.
+        assert!(alert_for(Some(&exposed), &exposed).is_none());
+        assert!(alert_for(None, &exposed).is_some());
     }
-    out
 }
@@ -0,0 +1,164 @@
+use anyhow::{anyhow, Result};
+
+use crate::models::{OpenClawConfigInput, SetupPreset};
+
+/// Wizard defaults a preset controls. Everything user-specific (provider, keys, install
+/// dir, channel credentials, port) is left to the wizard.
+struct PresetSpec {
+    name: &'static str,
+    title: &'static str,
+    description: &'static str,
+    skills: &'static [&'static str],
+    onboarding_flow: &'static str,
+    install_daemon: bool,
+    skip_channels: bool,
+    skip_skills: bool,
+    enable_skills_scan: bool,
+    enable_session_memory_hook: bool,
+    enable_workspace_memory: bool,
+    auto_open_dashboard: bool,
+}
+
+const PRESETS: &[PresetSpec] = &[
+    PresetSpec {
+        name: "minimal",
+        title: "Minimal",
+        description:
+            "Gateway and model only. No skills, memory hooks, channels or background task.",
+        skills: &[],
+        onboarding_flow: "quickstart",
+        install_daemon: false,
+        skip_channels: true,
+        skip_skills: true,
+        enable_skills_scan: false,
+        enable_session_memory_hook: false,
+        enable_workspace_memory: false,
+        auto_open_dashboard: true,
+    },
+    PresetSpec {
+        name: "standard",
+        title: "Standard",
+        description:
+            "Recommended: health and skill-creator skills, session memory, runs in the background.",
+        skills: &["healthcheck", "skill-creator"],
+        onboarding_flow: "quickstart",
+        install_daemon: true,
+        skip_channels: false,
+        skip_skills: false,
+        enable_skills_scan: true,
+        enable_session_memory_hook: true,
+        enable_workspace_memory: true,
+        auto_open_dashboard: true,
+    },
+    PresetSpec {
+        name: "power-user",
+        title: "Power user",
+        description:
+            "Standard plus GitHub, weather and ClawHub skills with the advanced onboarding flow.",
+        skills: &[
+            "healthcheck",
+            "skill-creator",
+            "github",
+            "weather",
+            "clawhub",
+        ],
+        onboarding_flow: "advanced",
+        install_daemon: true,
+        skip_channels: false,
+        skip_skills: false,
+        enable_skills_scan: true,
+        enable_session_memory_hook: true,
+        enable_workspace_memory: true,
+        auto_open_dashboard: false,
+    },
+];
+
+pub fn list_setup_presets() -> Vec<SetupPreset> {
+    PRESETS
+        .iter()
+        .map(|spec| SetupPreset {
+            name: spec.name.to_string(),
+            title: spec.title.to_string(),
+            description: spec.description.to_string(),
+            config: apply(spec, OpenClawConfigInput::default()),
+        })
+        .collect()
+}
+
+/// Returns `base` (or the default wizard payload) with the named preset's defaults applied.
+pub fn apply_setup_preset(
+    name: &str,
+    base: Option<OpenClawConfigInput>,
+) -> Result<OpenClawConfigInput> {
+    let spec = PRESETS
+        .iter()
+        .find(|spec| spec.name.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| {
+            anyhow!(
+                "Unknown setup preset: {name}. Available: {}",
+                PRESETS
+                    .iter()
+                    .map(|spec| spec.name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+    Ok(apply(spec, base.unwrap_or_default()))
+}
+
+fn apply(spec: &PresetSpec, mut payload: OpenClawConfigInput) -> OpenClawConfigInput {
+    payload.selected_skills = spec.skills.iter().map(|s| s.to_string()).collect();
+    payload.onboarding_flow = spec.onboarding_flow.to_string();
+    payload.install_daemon = spec.install_daemon;
+    payload.skip_channels = spec.skip_channels;
+    payload.skip_skills = spec.skip_skills;
+    payload.enable_skills_scan = spec.enable_skills_scan;
+    payload.enable_session_memory_hook = spec.enable_session_memory_hook;
+    payload.enable_workspace_memory = spec.enable_workspace_memory;
+    payload.auto_open_dashboard = spec.auto_open_dashboard;
+    if spec.skip_channels {
+        payload.enable_feishu_channel = false;
+        payload.enable_dingtalk_channel = false;
+        payload.enable_telegram_channel = false;
+        payload.enable_discord_channel = false;
+    }
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_setup_preset, list_setup_presets};
+    use crate::models::OpenClawConfigInput;
+
+    #[test]
+    fn apply_setup_preset_keeps_user_fields() {
+        let base = OpenClawConfigInput {
+            provider: "anthropic".to_string(),
+            api_key: "sk-ant-test".to_string(),
+            enable_telegram_channel: true,
+            port: 30000,
+            ..Default::default()
+        };
+        let minimal = apply_setup_preset("Minimal", Some(base)).expect("preset");
+        assert_eq!(minimal.provider, "anthropic");
+        assert_eq!(minimal.api_key, "sk-ant-test");
+        assert_eq!(minimal.port, 30000);
+        assert!(minimal.selected_skills.is_empty());
+        assert!(!minimal.install_daemon);
+        assert!(!minimal.enable_telegram_channel);
+        assert!(apply_setup_preset("unknown", None).is_err());
+    }
+
+    #[test]
+    fn standard_preset_matches_wizard_defaults() {
+        let presets = list_setup_presets();
+        let standard = presets.iter().find(|p| p.name == "standard").expect("std");
+        let defaults = OpenClawConfigInput::default();
+        assert_eq!(standard.config.selected_skills, defaults.selected_skills);
+        assert_eq!(standard.config.install_daemon, defaults.install_daemon);
+        assert_eq!(
+            standard.config.enable_skills_scan,
+            defaults.enable_skills_scan
+        );
+    }
+}
@@ -1,10 +1,12 @@
-use std::path::Path;
-use std::process::Command;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 use anyhow::{anyhow, Result};
 
-#[cfg(windows)]
-use encoding_rs::GBK;
+use encoding_rs::Encoding;
+
+use super::{paths, telemetry};
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
@@ -18,19 +20,177 @@ pub struct CmdOutput {
     pub stderr: String,
 }
 
+/// Executes external commands. Tests swap in a recording implementation via
+/// `with_runner` to assert CLI sequences without spawning anything.
+pub trait CommandRunner {
+    fn run(
+        &self,
+        exe: &str,
+        args: &[String],
+        cwd: Option<&Path>,
+        extra_env: &[(String, String)],
+    ) -> Result<CmdOutput>;
+}
+
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(
+        &self,
+        exe: &str,
+        args: &[String],
+        cwd: Option<&Path>,
+        extra_env: &[(String, String)],
+    ) -> Result<CmdOutput> {
+        spawn_command(exe, args, cwd, extra_env)
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    static RUNNER_OVERRIDE: std::cell::RefCell<Option<std::rc::Rc<dyn CommandRunner>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Routes every command spawned on the current thread through `runner` while `f` runs.
+#[cfg(test)]
+pub fn with_runner<R>(runner: std::rc::Rc<dyn CommandRunner>, f: impl FnOnce() -> R) -> R {
+    struct Reset;
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            RUNNER_OVERRIDE.with(|slot| *slot.borrow_mut() = None);
+        }
+    }
+    RUNNER_OVERRIDE.with(|slot| *slot.borrow_mut() = Some(runner));
+    let _reset = Reset;
+    f()
+}
+
 pub fn run_command<S: AsRef<str>>(
     exe: S,
     args: &[S],
     cwd: Option<&Path>,
     extra_env: &[(String, String)],
 ) -> Result<CmdOutput> {
-    let exe_ref = exe.as_ref();
+    let args = args
+        .iter()
+        .map(|arg| arg.as_ref().to_string())
+        .collect::<Vec<_>>();
+    let span = telemetry::command_span(exe.as_ref(), &args);
+    let _entered = span.enter();
+    #[cfg(test)]
+    {
+        if let Some(runner) = RUNNER_OVERRIDE.with(|slot| slot.borrow().clone()) {
+            return runner.run(exe.as_ref(), &args, cwd, extra_env);
+        }
+    }
+    let out = SystemRunner.run(exe.as_ref(), &args, cwd, extra_env)?;
+    span.record("exit_code", out.code);
+    Ok(out)
+}
+
+/// Like `run_command`, but hands each stdout/stderr line to `on_line` as it is printed,
+/// so long npm/git runs can report live progress. `on_line` receives `"stdout"` or
+/// `"stderr"` and the decoded line.
+pub fn run_command_streaming<S: AsRef<str>>(
+    exe: S,
+    args: &[S],
+    cwd: Option<&Path>,
+    extra_env: &[(String, String)],
+    on_line: &(dyn Fn(&str, &str) + Sync),
+) -> Result<CmdOutput> {
+    let args = args
+        .iter()
+        .map(|arg| arg.as_ref().to_string())
+        .collect::<Vec<_>>();
+    let span = telemetry::command_span(exe.as_ref(), &args);
+    let _entered = span.enter();
+    #[cfg(test)]
+    {
+        if let Some(runner) = RUNNER_OVERRIDE.with(|slot| slot.borrow().clone()) {
+            let out = runner.run(exe.as_ref(), &args, cwd, extra_env)?;
+            for (stream, text) in [("stdout", &out.stdout), ("stderr", &out.stderr)] {
+                text.lines().for_each(|line| on_line(stream, line));
+            }
+            return Ok(out);
+        }
+    }
+    let mut cmd = build_command(exe.as_ref(), &args, cwd, extra_env);
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = cmd.spawn()?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let (stdout, stderr) = std::thread::scope(|scope| {
+        let out = scope.spawn(|| pump_lines(stdout, "stdout", on_line));
+        let err = scope.spawn(|| pump_lines(stderr, "stderr", on_line));
+        (
+            out.join().unwrap_or_default(),
+            err.join().unwrap_or_default(),
+        )
+    });
+    let status = child.wait()?;
+    span.record("exit_code", status.code().unwrap_or(-1));
+    Ok(CmdOutput {
+        code: status.code().unwrap_or(-1),
+        stdout: decode_output(&stdout),
+        stderr: decode_output(&stderr),
+    })
+}
+
+/// Forwards each line of `pipe` to `on_line` and returns everything read.
+fn pump_lines(
+    pipe: Option<impl Read>,
+    stream: &str,
+    on_line: &(dyn Fn(&str, &str) + Sync),
+) -> Vec<u8> {
+    let Some(pipe) = pipe else {
+        return Vec::new();
+    };
+    let mut all = Vec::new();
+    for chunk in BufReader::new(pipe)
+        .split(b'\n')
+        .map_while(|chunk| chunk.ok())
+    {
+        let line = decode_output(&chunk);
+        if !line.is_empty() {
+            on_line(stream, &line);
+        }
+        all.extend_from_slice(&chunk);
+        all.push(b'\n');
+    }
+    all
+}
+
+fn spawn_command(
+    exe_ref: &str,
+    args: &[String],
+    cwd: Option<&Path>,
+    extra_env: &[(String, String)],
+) -> Result<CmdOutput> {
+    let output = build_command(exe_ref, args, cwd, extra_env).output()?;
+    Ok(CmdOutput {
+        code: output.status.code().unwrap_or(-1),
+        stdout: decode_output(&output.stdout),
+        stderr: decode_output(&output.stderr),
+    })
+}
+
+fn build_command(
+    exe_ref: &str,
+    args: &[String],
+    cwd: Option<&Path>,
+    extra_env: &[(String, String)],
+) -> Command {
     let mut cmd = if is_cmd_script(exe_ref) {
         let mut wrapped = Command::new("cmd");
-        wrapped.arg("/D").arg("/C").arg(exe_ref);
-        for arg in args {
-            wrapped.arg(arg.as_ref());
-        }
+        let exe = paths::spawn_safe_path(Path::new(exe_ref));
+        // /S strips exactly one outer quote pair, so quoted paths with spaces survive.
+        let line = cmd_script_command_line(&exe.to_string_lossy(), args);
+        wrapped.arg("/D").arg("/S").arg("/C");
+        #[cfg(windows)]
+        wrapped.raw_arg(line);
+        #[cfg(not(windows))]
+        wrapped.arg(line);
         wrapped
     } else if is_powershell_script(exe_ref) {
         // Some npm global shims on Windows are .ps1 only.
@@ -44,18 +204,18 @@ pub fn run_command<S: AsRef<str>>(
             .arg("-File")
             .arg(exe_ref);
         for arg in args {
-            wrapped.arg(arg.as_ref());
+            wrapped.arg(arg);
         }
         wrapped
     } else {
         let mut direct = Command::new(exe_ref);
         for arg in args {
-            direct.arg(arg.as_ref());
+            direct.arg(arg);
         }
         direct
     };
     if let Some(dir) = cwd {
-        cmd.current_dir(dir);
+        cmd.current_dir(paths::spawn_safe_path(dir));
     }
     for (k, v) in extra_env {
         cmd.env(k, v);
@@ -65,12 +225,31 @@ pub fn run_command<S: AsRef<str>>(
         // Prevent console flashing when GUI process invokes CLI tools.
         cmd.creation_flags(CREATE_NO_WINDOW);
     }
-    let output = cmd.output()?;
-    Ok(CmdOutput {
-        code: output.status.code().unwrap_or(-1),
-        stdout: decode_output(&output.stdout),
-        stderr: decode_output(&output.stderr),
-    })
+    cmd
+}
+
+fn cmd_script_command_line(exe: &str, args: &[String]) -> String {
+    let parts = std::iter::once(exe)
+        .chain(args.iter().map(String::as_str))
+        .map(quote_cmd_arg)
+        .collect::<Vec<_>>();
+    format!("\"{}\"", parts.join(" "))
+}
+
+fn quote_cmd_arg(arg: &str) -> String {
+    let needs_quotes = arg.is_empty()
+        || arg.chars().any(|ch| {
+            ch.is_whitespace()
+                || matches!(
+                    ch,
+                    '"' | '&' | '|' | '<' | '>' | '^' | '(' | ')' | ',' | ';' | '='
+                )
+        });
+    if needs_quotes {
+        format!("\"{}\"", arg.replace('"', "\"\""))
+    } else {
+        arg.to_string()
+    }
 }
 
 fn is_cmd_script(exe: &str) -> bool {
@@ -89,35 +268,147 @@ fn decode_output(raw: &[u8]) -> String {
     if let Ok(text) = std::str::from_utf8(raw) {
         return text.trim().to_string();
     }
-    #[cfg(windows)]
-    {
-        // npm/cmd errors on CN Windows are often emitted as GBK/CP936.
-        let (decoded, _, _) = GBK.decode(raw);
-        return decoded.trim().to_string();
-    }
-    #[cfg(not(windows))]
-    {
-        String::from_utf8_lossy(raw).trim().to_string()
+    // npm/cmd errors are emitted in the console (OEM) code page, e.g. GBK on
+    // Simplified Chinese, Big5 on Traditional Chinese, Shift-JIS on Japanese Windows.
+    match active_oem_code_page().and_then(encoding_for_code_page) {
+        Some(encoding) => {
+            let (decoded, _, _) = encoding.decode(raw);
+            decoded.trim().to_string()
+        }
+        None => String::from_utf8_lossy(raw).trim().to_string(),
     }
 }
 
+#[cfg(windows)]
+fn active_oem_code_page() -> Option<u32> {
+    static OEM_CP: std::sync::OnceLock<u32> = std::sync::OnceLock::new();
+    // SAFETY: GetOEMCP takes no arguments and only reads process locale state.
+    let cp = *OEM_CP.get_or_init(|| unsafe { windows_sys::Win32::Globalization::GetOEMCP() });
+    Some(cp)
+}
+
+#[cfg(not(windows))]
+fn active_oem_code_page() -> Option<u32> {
+    None
+}
+
+fn encoding_for_code_page(code_page: u32) -> Option<&'static Encoding> {
+    let encoding = match code_page {
+        65001 => encoding_rs::UTF_8,
+        936 => encoding_rs::GBK,
+        54936 => encoding_rs::GB18030,
+        950 => encoding_rs::BIG5,
+        932 => encoding_rs::SHIFT_JIS,
+        949 => encoding_rs::EUC_KR,
+        866 => encoding_rs::IBM866,
+        874 => encoding_rs::WINDOWS_874,
+        1250 => encoding_rs::WINDOWS_1250,
+        1251 => encoding_rs::WINDOWS_1251,
+        1253 => encoding_rs::WINDOWS_1253,
+        1254 => encoding_rs::WINDOWS_1254,
+        1255 => encoding_rs::WINDOWS_1255,
+        1256 => encoding_rs::WINDOWS_1256,
+        1257 => encoding_rs::WINDOWS_1257,
+        1258 => encoding_rs::WINDOWS_1258,
+        // Western OEM pages (437/850/...) have no exact decoder; 1252 keeps ASCII and most accents.
+        437 | 850 | 858 | 1252 => encoding_rs::WINDOWS_1252,
+        _ => return None,
+    };
+    Some(encoding)
+}
+
+/// Resolves a command the way cmd.exe does: PATH order, then PATHEXT order within
+/// each directory. Runs in-process so probes never depend on a working where.exe.
 pub fn command_exists(name: &str) -> Option<String> {
-    match run_command("where", &[name], None, &[]) {
-        Ok(out) if out.code == 0 => {
-            let mut lines: Vec<String> = out
-                .stdout
-                .lines()
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
-            if !lines.is_empty() {
-                lines.sort_by_key(|item| executable_rank(item));
-                return lines.into_iter().next();
+    let name = name.trim().trim_matches('"');
+    if name.is_empty() {
+        return None;
+    }
+    let exts = path_extensions();
+    let is_file = |p: &Path| p.is_file();
+    if name.contains(['\\', '/']) {
+        let path = Path::new(name);
+        return resolve_in_dirs(
+            path.file_name()?.to_str()?,
+            &[path.parent()?.to_path_buf()],
+            &exts,
+            is_file,
+        );
+    }
+    resolve_in_dirs(name, &path_dirs(), &exts, is_file)
+        .or_else(|| {
+            // GUI processes launched before npm was installed miss its prefix in PATH.
+            npm_prefix_dir().and_then(|dir| resolve_in_dirs(name, &[dir], &exts, is_file))
+        })
+        .or_else(|| fallback_command_exists(name))
+}
+
+fn path_dirs() -> Vec<PathBuf> {
+    std::env::var_os("PATH")
+        .map(|raw| {
+            std::env::split_paths(&raw)
+                .map(|dir| PathBuf::from(dir.to_string_lossy().trim().trim_matches('"')))
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn path_extensions() -> Vec<String> {
+    let raw = std::env::var("PATHEXT").unwrap_or_else(|_| {
+        if cfg!(windows) {
+            ".COM;.EXE;.BAT;.CMD".to_string()
+        } else {
+            String::new()
+        }
+    });
+    raw.split(';')
+        .map(|ext| ext.trim().to_ascii_lowercase())
+        .filter(|ext| ext.starts_with('.'))
+        .collect()
+}
+
+fn resolve_in_dirs(
+    name: &str,
+    dirs: &[PathBuf],
+    exts: &[String],
+    is_file: impl Fn(&Path) -> bool,
+) -> Option<String> {
+    let lower = name.to_ascii_lowercase();
+    let has_known_ext = exts.iter().any(|ext| lower.ends_with(ext.as_str()));
+    for dir in dirs {
+        // An explicit executable extension is used as-is; extensionless files
+        // (npm's sh shims) are never runnable from Windows.
+        if has_known_ext || exts.is_empty() {
+            let candidate = dir.join(name);
+            if is_file(&candidate) {
+                return Some(candidate.to_string_lossy().to_string());
+            }
+            continue;
+        }
+        for ext in exts {
+            let candidate = dir.join(format!("{name}{ext}"));
+            if is_file(&candidate) {
+                return Some(candidate.to_string_lossy().to_string());
             }
         }
-        _ => {}
     }
-    fallback_command_exists(name)
+    None
+}
+
+fn npm_prefix_dir() -> Option<PathBuf> {
+    static NPM_PREFIX: std::sync::OnceLock<Option<PathBuf>> = std::sync::OnceLock::new();
+    NPM_PREFIX
+        .get_or_init(|| {
+            let npm = resolve_in_dirs("npm", &path_dirs(), &path_extensions(), |p| p.is_file())?;
+            let out = run_command(npm.as_str(), &["config", "get", "prefix"], None, &[]).ok()?;
+            if out.code != 0 {
+                return None;
+            }
+            let prefix = out.stdout.lines().next()?.trim().to_string();
+            (!prefix.is_empty()).then(|| PathBuf::from(prefix))
+        })
+        .clone()
 }
 
 #[cfg(windows)]
@@ -167,26 +458,6 @@ fn fallback_command_exists(_name: &str) -> Option<String> {
     None
 }
 
-fn executable_rank(path: &str) -> u8 {
-    let lower = path.to_ascii_lowercase();
-    if lower.ends_with(".exe") {
-        return 0;
-    }
-    if lower.ends_with(".cmd") {
-        return 1;
-    }
-    if lower.ends_with(".bat") {
-        return 2;
-    }
-    if lower.ends_with(".com") {
-        return 3;
-    }
-    if lower.ends_with(".ps1") {
-        return 9;
-    }
-    5
-}
-
 pub fn is_admin() -> bool {
     match run_command("net", &["session"], None, &[]) {
         Ok(out) => out.code == 0,
@@ -229,6 +500,73 @@ pub fn process_name_by_pid(pid: u32) -> Option<String> {
     parts.next().map(|s| s.to_string())
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessIdentity {
+    /// Process creation time in UTC, ISO 8601. Unique per PID incarnation.
+    pub created_at: String,
+    pub command_line: String,
+}
+
+pub fn process_identity(pid: u32) -> Option<ProcessIdentity> {
+    let script = format!(
+        "$p = Get-CimInstance Win32_Process -Filter 'ProcessId={pid}'; if ($p) {{ $p.CreationDate.ToUniversalTime().ToString('o'); $p.CommandLine }}"
+    );
+    let out = run_command(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", &script],
+        None,
+        &[],
+    )
+    .ok()?;
+    if out.code != 0 {
+        return None;
+    }
+    let mut lines = out.stdout.lines();
+    let created_at = lines.next()?.trim().to_string();
+    if created_at.is_empty() {
+        return None;
+    }
+    Some(ProcessIdentity {
+        created_at,
+        command_line: lines.next().unwrap_or_default().trim().to_string(),
+    })
+}
+
+/// Creation time of a live `pid` in FILETIME ticks, read through the process handle so
+/// status polls do not have to spawn PowerShell. None when the process has exited or
+/// cannot be opened.
+#[cfg(windows)]
+pub fn process_start_ticks(pid: u32) -> Option<u64> {
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME, STILL_ACTIVE};
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let empty = || FILETIME {
+        dwLowDateTime: 0,
+        dwHighDateTime: 0,
+    };
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return None;
+        }
+        let mut exit_code = 0u32;
+        let (mut created, mut exited, mut kernel, mut user) = (empty(), empty(), empty(), empty());
+        let alive =
+            GetExitCodeProcess(handle, &mut exit_code) != 0 && exit_code == STILL_ACTIVE as u32;
+        let read = GetProcessTimes(handle, &mut created, &mut exited, &mut kernel, &mut user) != 0;
+        CloseHandle(handle);
+        (alive && read)
+            .then(|| (u64::from(created.dwHighDateTime) << 32) | u64::from(created.dwLowDateTime))
+    }
+}
+
+#[cfg(not(windows))]
+pub fn process_start_ticks(_pid: u32) -> Option<u64> {
+    None
+}
+
 pub fn ensure_success(op: &str, output: &CmdOutput) -> Result<()> {
     if output.code == 0 {
         Ok(())
@@ -245,10 +583,225 @@ pub fn ensure_success(op: &str, output: &CmdOutput) -> Result<()> {
     }
 }
 
+#[cfg(test)]
+pub mod testing {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::path::Path;
+    use std::rc::Rc;
+
+    use anyhow::Result;
+
+    use super::{CmdOutput, CommandRunner};
+
+    #[derive(Debug, Clone)]
+    pub struct RecordedCommand {
+        pub exe: String,
+        pub args: Vec<String>,
+        pub env: Vec<(String, String)>,
+    }
+
+    impl RecordedCommand {
+        pub fn line(&self) -> String {
+            std::iter::once(self.exe.as_str())
+                .chain(self.args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" ")
+        }
+    }
+
+    /// Records every command and answers from a queue of scripted outputs.
+    /// Unscripted commands succeed with empty output.
+    #[derive(Default)]
+    pub struct RecordingRunner {
+        calls: RefCell<Vec<RecordedCommand>>,
+        responses: RefCell<VecDeque<(String, CmdOutput)>>,
+    }
+
+    impl RecordingRunner {
+        pub fn new() -> Rc<Self> {
+            Rc::new(Self::default())
+        }
+
+        /// Queues an output for the next command whose executable contains `exe`.
+        pub fn respond(&self, exe: &str, code: i32, stdout: &str) {
+            self.responses.borrow_mut().push_back((
+                exe.to_ascii_lowercase(),
+                CmdOutput {
+                    code,
+                    stdout: stdout.to_string(),
+                    stderr: String::new(),
+                },
+            ));
+        }
+
+        pub fn calls(&self) -> Vec<RecordedCommand> {
+            self.calls.borrow().clone()
+        }
+
+        pub fn lines(&self) -> Vec<String> {
+            self.calls().iter().map(RecordedCommand::line).collect()
+        }
+    }
+
+    impl CommandRunner for RecordingRunner {
+        fn run(
+            &self,
+            exe: &str,
+            args: &[String],
+            _cwd: Option<&Path>,
+            extra_env: &[(String, String)],
+        ) -> Result<CmdOutput> {
+            self.calls.borrow_mut().push(RecordedCommand {
+                exe: exe.to_string(),
+                args: args.to_vec(),
+                env: extra_env.to_vec(),
+            });
+            let lower = exe.to_ascii_lowercase();
+            let mut responses = self.responses.borrow_mut();
+            let scripted = responses
+                .iter()
+                .position(|(matcher, _)| lower.contains(matcher.as_str()))
+                .and_then(|idx| responses.remove(idx))
+                .map(|(_, out)| out);
+            Ok(scripted.unwrap_or(CmdOutput {
+                code: 0,
+                stdout: String::new(),
+                stderr: String::new(),
+            }))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::run_command;
+    use super::testing::RecordingRunner;
+    use super::{cmd_script_command_line, encoding_for_code_page, resolve_in_dirs, with_runner};
+    use super::{run_command, run_command_streaming};
     use std::fs;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn run_command_routes_through_injected_runner() {
+        let runner = RecordingRunner::new();
+        runner.respond("npm", 0, "10.9.0");
+        let env = vec![(
+            "HTTPS_PROXY".to_string(),
+            "http://127.0.0.1:7890".to_string(),
+        )];
+        let out = with_runner(runner.clone(), || {
+            run_command("npm", &["--version"], None, &env).expect("scripted output")
+        });
+        assert_eq!(out.stdout, "10.9.0");
+        let unscripted = with_runner(runner.clone(), || {
+            run_command("node", &["-v"], None, &[]).expect("default output")
+        });
+        assert_eq!(unscripted.code, 0);
+
+        let calls = runner.calls();
+        assert_eq!(runner.lines(), vec!["npm --version", "node -v"]);
+        assert_eq!(calls[0].env, env);
+        assert!(calls[1].env.is_empty());
+    }
+
+    #[test]
+    fn streaming_run_reports_each_output_line() {
+        let runner = RecordingRunner::new();
+        runner.respond("npm", 0, "added 12 packages\nfound 0 vulnerabilities");
+        let seen = std::sync::Mutex::new(Vec::<String>::new());
+        let out = with_runner(runner, || {
+            run_command_streaming("npm", &["install"], None, &[], &|stream, line| {
+                seen.lock().unwrap().push(format!("{stream}: {line}"));
+            })
+            .expect("scripted output")
+        });
+        assert_eq!(out.code, 0);
+        assert_eq!(
+            seen.into_inner().unwrap(),
+            vec![
+                "stdout: added 12 packages",
+                "stdout: found 0 vulnerabilities"
+            ]
+        );
+    }
+
+    #[test]
+    fn cmd_script_command_line_quotes_spaces_and_cjk_paths() {
+        let args = vec![
+            "--prefix".to_string(),
+            "C:\\Users\\张三\\Open Claw".to_string(),
+            "install".to_string(),
+            "a&b".to_string(),
+            String::new(),
+        ];
+        assert_eq!(
+            cmd_script_command_line("C:\\Program Files\\nodejs\\npm.cmd", &args),
+            "\"\"C:\\Program Files\\nodejs\\npm.cmd\" --prefix \"C:\\Users\\张三\\Open Claw\" install \"a&b\" \"\"\""
+        );
+        assert_eq!(
+            cmd_script_command_line("npm.cmd", &["-v".to_string()]),
+            "\"npm.cmd -v\""
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn run_command_handles_cjk_and_space_paths() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("openclaw 测试 {}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("cjk temp dir");
+        let script = dir.join("回显 tool");
+        fs::write(&script, "#!/bin/sh\nprintf '%s|%s' \"$1\" \"$(pwd)\"\n").expect("script");
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).expect("chmod");
+
+        let out = run_command(
+            script.to_string_lossy().as_ref(),
+            &["参数 一"],
+            Some(&dir),
+            &[],
+        )
+        .expect("script runs");
+        assert_eq!(out.code, 0);
+        let (arg, cwd) = out.stdout.split_once('|').expect("two fields");
+        assert_eq!(arg, "参数 一");
+        assert!(cwd.contains("openclaw 测试"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn resolve_in_dirs_follows_path_then_pathext_order() {
+        let dirs = vec![PathBuf::from("/a"), PathBuf::from("/b")];
+        let exts = vec![".exe".to_string(), ".cmd".to_string()];
+        let present = ["/a/npm", "/a/npm.cmd", "/b/npm.exe"];
+        let is_file = |p: &Path| present.iter().any(|item| Path::new(item) == p);
+
+        // First PATH directory wins even though a later one has a preferred extension.
+        assert_eq!(
+            resolve_in_dirs("npm", &dirs, &exts, is_file).map(PathBuf::from),
+            Some(PathBuf::from("/a/npm.cmd"))
+        );
+        assert_eq!(
+            resolve_in_dirs("npm.exe", &dirs, &exts, is_file).map(PathBuf::from),
+            Some(PathBuf::from("/b/npm.exe"))
+        );
+        assert_eq!(resolve_in_dirs("node", &dirs, &exts, is_file), None);
+    }
+
+    #[test]
+    fn encoding_for_code_page_decodes_cjk_consoles() {
+        let big5 = encoding_for_code_page(950).expect("big5");
+        let (text, _, had_errors) = big5.decode(&[0xa4, 0xa4, 0xa4, 0xe5]);
+        assert!(!had_errors);
+        assert_eq!(text, "中文");
+
+        let sjis = encoding_for_code_page(932).expect("shift-jis");
+        let (text, _, _) = sjis.decode(&[0x83, 0x65, 0x83, 0x58, 0x83, 0x67]);
+        assert_eq!(text, "テスト");
+
+        assert!(encoding_for_code_page(12345).is_none());
+    }
 
     #[cfg(windows)]
     #[test]
@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+
+use anyhow::{anyhow, Result};
+use tauri::{plugin::TauriPlugin, AppHandle, Wry};
+use tauri_plugin_global_shortcut::{Builder, GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::models::{ShortcutConfig, ShortcutConflict, ShortcutResult};
+
+use super::{logger, state_store};
+
+pub const ACTION_TOGGLE_WINDOW: &str = "toggle_window";
+pub const ACTION_RESTART_GATEWAY: &str = "restart_gateway";
+pub const ACTION_OPEN_DASHBOARD: &str = "open_dashboard";
+const ACTIONS: &[&str] = &[
+    ACTION_TOGGLE_WINDOW,
+    ACTION_RESTART_GATEWAY,
+    ACTION_OPEN_DASHBOARD,
+];
+
+type Handler = Box<dyn Fn(&str) + Send + Sync>;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+static HANDLER: OnceLock<Handler> = OnceLock::new();
+/// Shortcuts this app registered, by action; the plugin may hold others.
+static ACTIVE: Mutex<Vec<(String, Shortcut)>> = Mutex::new(Vec::new());
+static APPLYING: Mutex<()> = Mutex::new(());
+
+/// The global-shortcut plugin, routing presses to the registered actions.
+pub fn plugin() -> TauriPlugin<Wry> {
+    Builder::new()
+        .with_handler(|_, shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                dispatch(shortcut);
+            }
+        })
+        .build()
+}
+
+/// Registers what a hotkey does; `main` maps actions to the window, gateway and browser.
+pub fn set_handler(app: AppHandle, handler: impl Fn(&str) + Send + Sync + 'static) {
+    let _ = APP.set(app);
+    let _ = HANDLER.set(Box::new(handler));
+}
+
+/// Registers the saved shortcuts at startup.
+pub fn start() {
+    let cfg = state_store::load_shortcuts().unwrap_or_default();
+    match apply(&cfg) {
+        Ok(result) => {
+            for conflict in result.conflicts {
+                logger::warn(&format!(
+                    "Shortcut {} ({}) not registered: {}",
+                    conflict.accelerator, conflict.action, conflict.reason
+                ));
+            }
+        }
+        Err(err) => logger::warn(&format!("Saved shortcuts are invalid: {err}")),
+    }
+}
+
+/// Validates, registers and saves `bindings`. Malformed or duplicate accelerators are
+/// rejected outright; hotkeys another app already owns are saved and reported.
+pub fn set_shortcuts(bindings: &BTreeMap<String, String>) -> Result<ShortcutResult> {
+    let cfg = ShortcutConfig {
+        bindings: bindings
+            .iter()
+            .filter(|(_, accelerator)| !accelerator.trim().is_empty())
+            .map(|(action, accelerator)| {
+                (action.trim().to_string(), accelerator.trim().to_string())
+            })
+            .collect(),
+    };
+    let result = apply(&cfg)?;
+    state_store::save_shortcuts(&cfg)?;
+    logger::info(&format!(
+        "Shortcuts updated: {} registered, {} conflict(s).",
+        result.registered.len(),
+        result.conflicts.len()
+    ));
+    Ok(result)
+}
+
+fn apply(cfg: &ShortcutConfig) -> Result<ShortcutResult> {
+    let parsed = validate(&cfg.bindings)?;
+    let app = APP
+        .get()
+        .ok_or_else(|| anyhow!("Shortcuts are not available before the app has started."))?;
+    let shortcuts = app.global_shortcut();
+    // Serializes rebinding. ACTIVE itself is never held while the plugin registers: that
+    // waits on the main thread, which may be dispatching a press and reading ACTIVE.
+    let _applying = APPLYING.lock().unwrap_or_else(|e| e.into_inner());
+    let previous = std::mem::take(&mut *ACTIVE.lock().unwrap_or_else(|e| e.into_inner()));
+    // Release the old hotkeys first so rebinding an action to the same keys works.
+    let previous = previous.into_iter().map(|(_, shortcut)| shortcut);
+    if let Err(err) = shortcuts.unregister_multiple(previous.collect::<Vec<_>>()) {
+        logger::warn(&format!("Releasing previous shortcuts failed: {err}"));
+    }
+    let mut registered = Vec::new();
+    let mut conflicts = Vec::new();
+    for (action, shortcut) in parsed {
+        match shortcuts.register(shortcut) {
+            Ok(()) => registered.push((action, shortcut)),
+            Err(err) => conflicts.push(ShortcutConflict {
+                accelerator: cfg.bindings.get(&action).cloned().unwrap_or_default(),
+                action,
+                reason: format!("already in use by another application ({err})"),
+            }),
+        }
+    }
+    let actions = registered
+        .iter()
+        .map(|(action, _)| action.clone())
+        .collect();
+    *ACTIVE.lock().unwrap_or_else(|e| e.into_inner()) = registered;
+    Ok(ShortcutResult {
+        config: cfg.clone(),
+        registered: actions,
+        conflicts,
+    })
+}
+
+fn validate(bindings: &BTreeMap<String, String>) -> Result<Vec<(String, Shortcut)>> {
+    let mut parsed = Vec::<(String, Shortcut)>::new();
+    for (action, accelerator) in bindings {
+        if !ACTIONS.contains(&action.as_str()) {
+            return Err(anyhow!(
+                "Unknown shortcut action '{action}'. Expected one of: {}.",
+                ACTIONS.join(", ")
+            ));
+        }
+        let shortcut = parse_accelerator(accelerator)?;
+        if let Some((other, _)) = parsed
+            .iter()
+            .find(|(_, existing)| existing.id() == shortcut.id())
+        {
+            return Err(anyhow!(
+                "{accelerator} is assigned to both {other} and {action}."
+            ));
+        }
+        parsed.push((action.clone(), shortcut));
+    }
+    Ok(parsed)
+}
+
+/// Parses `Ctrl+Alt+O`-style accelerators. At least one modifier is required so a
+/// global hotkey never swallows plain typing.
+fn parse_accelerator(raw: &str) -> Result<Shortcut> {
+    // The plugin knows the Windows key as Super.
+    let normalized = raw
+        .split('+')
+        .map(|part| match part.trim().to_ascii_uppercase().as_str() {
+            "WIN" | "META" => "Super".to_string(),
+            _ => part.trim().to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("+");
+    let shortcut =
+        Shortcut::from_str(&normalized).map_err(|err| anyhow!("'{raw}' is not valid: {err}"))?;
+    if shortcut.mods.is_empty() {
+        return Err(anyhow!(
+            "'{raw}' needs at least one of Ctrl, Alt, Shift or Win."
+        ));
+    }
+    Ok(shortcut)
+}
+
+/// The plugin reports every press of every registered shortcut here.
+fn dispatch(shortcut: &Shortcut) {
+    let action = ACTIVE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(_, registered)| registered.id() == shortcut.id())
+        .map(|(action, _)| action.clone());
+    let Some(action) = action else {
+        return;
+    };
+    // Handlers may block (gateway restart); keep the plugin's event loop responsive.
+    thread::spawn(move || match HANDLER.get() {
+        Some(handler) => {
+            logger::info(&format!("Shortcut triggered: {action}"));
+            handler(&action);
+        }
+        None => logger::warn(&format!(
+            "Shortcut {action} fired before a handler was set."
+        )),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_accelerator, validate};
+    use std::collections::BTreeMap;
+    use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
+
+    #[test]
+    fn accelerators_parse_and_duplicates_are_rejected() {
+        assert_eq!(
+            parse_accelerator("Ctrl+Alt+O").expect("parsed"),
+            Shortcut::new(Some(Modifiers::CONTROL | Modifiers::ALT), Code::KeyO)
+        );
+        assert_eq!(
+            parse_accelerator("shift + f12").expect("parsed").key,
+            Code::F12
+        );
+        assert_eq!(
+            parse_accelerator("Win+Shift+F12").expect("parsed").mods,
+            Modifiers::SUPER | Modifiers::SHIFT
+        );
+        assert!(parse_accelerator("O").is_err());
+        assert!(parse_accelerator("Ctrl+A+B").is_err());
+        assert!(parse_accelerator("Ctrl+Banana").is_err());
+
+        let bindings = BTreeMap::from([
+            ("toggle_window".to_string(), "Ctrl+Alt+O".to_string()),
+            ("open_dashboard".to_string(), "alt+ctrl+o".to_string()),
+        ]);
+        let err = validate(&bindings).expect_err("duplicate");
+        assert!(err.to_string().contains("assigned to both"));
+
+        let unknown = BTreeMap::from([("launch_rockets".to_string(), "Ctrl+R".to_string())]);
+        assert!(validate(&unknown).is_err());
+    }
+}
@@ -1,15 +1,31 @@
 use anyhow::{anyhow, Result};
-use serde::Deserialize;
+use once_cell::sync::Lazy;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use serde_json::{Deserializer, Value};
-use std::sync::mpsc;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::task::JoinSet;
 
 use crate::models::SkillCatalogItem;
 
-use super::{logger, shell};
+use super::{config, fs_walk, logger, paths, shell, state_store};
 
-const SKILL_CATALOG_CLI_TIMEOUT: Duration = Duration::from_millis(1_600);
+const SKILL_CATALOG_CACHE_TTL: Duration = Duration::from_secs(45);
+const SKILL_CATALOG_DISK_CACHE_TTL: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+// Background refreshes can wait for a slow CLI; only the very first load (nothing on
+// disk yet) blocks the picker, and a cold CLI start routinely takes several seconds.
+const SKILL_CATALOG_CLI_TIMEOUT: Duration = Duration::from_millis(12_000);
+const SKILL_CATALOG_CLI_COLD_TIMEOUT: Duration = Duration::from_millis(8_000);
+// Registry lookups run on every call, so they stay short whatever the CLI did.
+const CLAWHUB_TIMEOUT: Duration = Duration::from_millis(1_500);
+const CLAWHUB_SKILL_API: &str = "https://clawhub.ai/api/v1/skills";
+// Lookups in flight at once; a large skill list must not open a connection per skill.
+const CLAWHUB_CONCURRENCY: usize = 6;
 
 #[derive(Debug, Deserialize)]
 struct SkillsListPayload {
@@ -28,19 +44,160 @@ struct SkillEntry {
     bundled: bool,
     #[serde(default)]
     source: String,
+    #[serde(default)]
+    requires: SkillRequirements,
+    #[serde(default)]
+    missing: SkillRequirements,
+    #[serde(default, alias = "baseDir")]
+    base_dir: Option<String>,
+    #[serde(default)]
+    permissions: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SkillRequirements {
+    #[serde(default)]
+    bins: Vec<String>,
+    #[serde(default, alias = "anyBins")]
+    any_bins: Vec<String>,
+    #[serde(default)]
+    env: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SkillCatalogDiskCache {
+    saved_at_unix_ms: u128,
+    items: Vec<SkillCatalogItem>,
 }
 
-pub fn list_skill_catalog() -> Result<Vec<SkillCatalogItem>> {
-    if let Ok(items) = list_from_openclaw_cli_with_timeout(SKILL_CATALOG_CLI_TIMEOUT) {
-        if !items.is_empty() {
-            return Ok(items);
+#[derive(Clone)]
+struct SkillCatalogCache {
+    loaded_at: Instant,
+    items: Vec<SkillCatalogItem>,
+}
+
+static SKILL_CATALOG_CACHE: Lazy<Mutex<Option<SkillCatalogCache>>> = Lazy::new(|| Mutex::new(None));
+static REFRESHING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Default, PartialEq)]
+struct ClawHubMeta {
+    version: Option<String>,
+    stars: Option<u64>,
+    downloads: Option<u64>,
+}
+
+pub fn clear_skill_catalog_cache() {
+    if let Ok(mut guard) = SKILL_CATALOG_CACHE.lock() {
+        *guard = None;
+    }
+    let _ = fs::remove_file(skill_catalog_cache_path());
+}
+
+/// Skills from `openclaw skills list`, served from memory or the last good result on
+/// disk (refreshed in the background) so the picker does not wait for the CLI. The
+/// built-in list is only used when the CLI has never answered.
+pub async fn list_skill_catalog() -> Result<Vec<SkillCatalogItem>> {
+    let mut items = tokio::task::spawn_blocking(load_catalog)
+        .await
+        .map_err(|err| anyhow!("Skills catalog worker failed: {err}"))?;
+    enrich_from_clawhub(&mut items).await;
+    Ok(items)
+}
+
+fn load_catalog() -> Vec<SkillCatalogItem> {
+    if let Some(items) = load_cached_catalog() {
+        return items;
+    }
+    if let Some(items) = load_disk_cached_catalog() {
+        save_cached_catalog(items.clone());
+        refresh_catalog_in_background();
+        return items;
+    }
+    match list_from_openclaw_cli_with_timeout(SKILL_CATALOG_CLI_COLD_TIMEOUT) {
+        Ok(items) if !items.is_empty() => {
+            save_cached_catalog(items.clone());
+            save_disk_cached_catalog(&items);
+            items
         }
+        _ => {
+            logger::warn(
+                "Falling back to static skill catalog because OpenClaw CLI skill list is unavailable.",
+            );
+            // Not cached, so the next call tries the CLI again.
+            fallback_catalog()
+        }
+    }
+}
+
+fn load_cached_catalog() -> Option<Vec<SkillCatalogItem>> {
+    let guard = SKILL_CATALOG_CACHE.lock().ok()?;
+    let cached = guard.as_ref()?;
+    (cached.loaded_at.elapsed() <= SKILL_CATALOG_CACHE_TTL).then(|| cached.items.clone())
+}
+
+fn save_cached_catalog(items: Vec<SkillCatalogItem>) {
+    if let Ok(mut guard) = SKILL_CATALOG_CACHE.lock() {
+        *guard = Some(SkillCatalogCache {
+            loaded_at: Instant::now(),
+            items,
+        });
+    }
+}
+
+fn skill_catalog_cache_path() -> PathBuf {
+    paths::state_dir().join("skill_catalog_cache.json")
+}
+
+fn save_disk_cached_catalog(items: &[SkillCatalogItem]) {
+    let payload = SkillCatalogDiskCache {
+        saved_at_unix_ms: now_unix_ms(),
+        items: items.to_vec(),
+    };
+    if let Ok(text) = serde_json::to_string(&payload) {
+        let _ = fs::write(skill_catalog_cache_path(), text);
     }
+}
+
+fn load_disk_cached_catalog() -> Option<Vec<SkillCatalogItem>> {
+    let raw = fs::read_to_string(skill_catalog_cache_path()).ok()?;
+    let parsed = serde_json::from_str::<SkillCatalogDiskCache>(&raw).ok()?;
+    fresh_disk_items(parsed, now_unix_ms())
+}
+
+fn fresh_disk_items(cache: SkillCatalogDiskCache, now_ms: u128) -> Option<Vec<SkillCatalogItem>> {
+    let age_ms = now_ms.saturating_sub(cache.saved_at_unix_ms);
+    if cache.items.is_empty() || age_ms > SKILL_CATALOG_DISK_CACHE_TTL.as_millis() {
+        return None;
+    }
+    Some(cache.items)
+}
+
+/// At most one refresh runs at a time; every picker open within the memory TTL after a
+/// restart would otherwise start its own CLI process.
+fn refresh_catalog_in_background() {
+    if !begin_refresh(&REFRESHING) {
+        return;
+    }
+    thread::spawn(|| {
+        if let Ok(items) = list_from_openclaw_cli_with_timeout(SKILL_CATALOG_CLI_TIMEOUT) {
+            if !items.is_empty() {
+                save_cached_catalog(items.clone());
+                save_disk_cached_catalog(&items);
+            }
+        }
+        REFRESHING.store(false, Ordering::SeqCst);
+    });
+}
+
+fn begin_refresh(flag: &AtomicBool) -> bool {
+    !flag.swap(true, Ordering::SeqCst)
+}
 
-    logger::warn(
-        "Falling back to static skill catalog because OpenClaw CLI skill list is unavailable.",
-    );
-    Ok(fallback_catalog())
+fn now_unix_ms() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
 }
 
 fn list_from_openclaw_cli_with_timeout(timeout: Duration) -> Result<Vec<SkillCatalogItem>> {
@@ -91,13 +248,7 @@ fn list_from_openclaw_cli() -> Result<Vec<SkillCatalogItem>> {
     let mut out = parsed
         .skills
         .into_iter()
-        .map(|item| SkillCatalogItem {
-            name: item.name,
-            description: item.description,
-            eligible: item.eligible,
-            bundled: item.bundled,
-            source: item.source,
-        })
+        .map(catalog_item)
         .collect::<Vec<_>>();
 
     out.sort_by(|a, b| {
@@ -138,49 +289,238 @@ fn parse_skills_payload(raw: &str) -> Result<SkillsListPayload> {
     ))
 }
 
+fn catalog_item(entry: SkillEntry) -> SkillCatalogItem {
+    let mut required_bins = entry.requires.bins;
+    required_bins.extend(entry.requires.any_bins);
+    let required_env = entry.requires.env;
+    let mut missing = entry.missing.bins;
+    missing.extend(entry.missing.any_bins);
+    missing.extend(entry.missing.env);
+    let footprint_bytes = entry
+        .base_dir
+        .as_deref()
+        .map(Path::new)
+        .filter(|dir| dir.is_dir())
+        .map(fs_walk::total_size);
+    let permissions = skill_permissions(&required_bins, &required_env, entry.permissions);
+    SkillCatalogItem {
+        name: entry.name,
+        description: entry.description,
+        eligible: entry.eligible,
+        bundled: entry.bundled,
+        source: entry.source,
+        required_bins,
+        required_env,
+        missing,
+        footprint_bytes,
+        permissions,
+        version: None,
+        stars: None,
+        downloads: None,
+    }
+}
+
+/// Declared permissions plus the ones implied by requirements: every required binary
+/// means the skill shells out, every env var is a credential it will read.
+fn skill_permissions(bins: &[String], env: &[String], declared: Vec<String>) -> Vec<String> {
+    let mut out = declared;
+    out.extend(bins.iter().map(|bin| format!("exec:{bin}")));
+    out.extend(env.iter().map(|var| format!("env:{var}")));
+    out.sort();
+    out.dedup();
+    out
+}
+
+async fn enrich_from_clawhub(items: &mut [SkillCatalogItem]) {
+    let proxy = state_store::load_last_config()
+        .ok()
+        .flatten()
+        .and_then(|cfg| config::optional_non_empty(cfg.proxy));
+    let mut builder = Client::builder().timeout(CLAWHUB_TIMEOUT);
+    if let Some(proxy) = proxy {
+        let Ok(proxy) = reqwest::Proxy::all(proxy) else {
+            return;
+        };
+        builder = builder.proxy(proxy);
+    }
+    let Ok(client) = builder.build() else {
+        return;
+    };
+    let mut names = items
+        .iter()
+        .map(|item| item.name.clone())
+        .enumerate()
+        .collect::<Vec<_>>()
+        .into_iter();
+    let mut lookups = JoinSet::new();
+    let collect = async {
+        let mut found = 0usize;
+        loop {
+            while lookups.len() < CLAWHUB_CONCURRENCY {
+                let Some((index, name)) = names.next() else {
+                    break;
+                };
+                let client = client.clone();
+                lookups.spawn(async move { (index, fetch_clawhub_meta(&client, &name).await) });
+            }
+            let Some(joined) = lookups.join_next().await else {
+                break;
+            };
+            if let Ok((index, Some(meta))) = joined {
+                let item = &mut items[index];
+                item.version = meta.version;
+                item.stars = meta.stars;
+                item.downloads = meta.downloads;
+                found += 1;
+            }
+        }
+        found
+    };
+    match tokio::time::timeout(CLAWHUB_TIMEOUT, collect).await {
+        Ok(0) => logger::warn("ClawHub metadata unavailable; skill picker shows local data only."),
+        Ok(_) => {}
+        Err(_) => logger::warn("ClawHub metadata lookup timed out; partial results kept."),
+    }
+}
+
+async fn fetch_clawhub_meta(client: &Client, name: &str) -> Option<ClawHubMeta> {
+    let resp = client
+        .get(format!("{CLAWHUB_SKILL_API}/{name}"))
+        .send()
+        .await
+        .ok()?;
+    if !resp.status().is_success() {
+        return None;
+    }
+    let body: Value = resp.json().await.ok()?;
+    parse_clawhub_meta(&body)
+}
+
+fn parse_clawhub_meta(body: &Value) -> Option<ClawHubMeta> {
+    let skill = body.get("skill").unwrap_or(body);
+    let version = ["latestVersion", "version"]
+        .iter()
+        .find_map(|key| match skill.get(*key) {
+            Some(Value::String(v)) => Some(v.clone()),
+            Some(Value::Object(v)) => v.get("version")?.as_str().map(str::to_string),
+            _ => None,
+        });
+    let stats = skill.get("stats").unwrap_or(skill);
+    let stars = stats.get("stars").and_then(Value::as_u64);
+    let downloads = stats.get("downloads").and_then(Value::as_u64);
+    let meta = ClawHubMeta {
+        version,
+        stars,
+        downloads,
+    };
+    (meta != ClawHubMeta::default()).then_some(meta)
+}
+
+fn fallback_item(
+    name: &str,
+    description: &str,
+    eligible: bool,
+    bins: &[&str],
+    env: &[&str],
+) -> SkillCatalogItem {
+    let required_bins = bins.iter().map(|b| b.to_string()).collect::<Vec<_>>();
+    let required_env = env.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+    SkillCatalogItem {
+        name: name.to_string(),
+        description: description.to_string(),
+        eligible,
+        bundled: true,
+        source: "openclaw-bundled".to_string(),
+        permissions: skill_permissions(&required_bins, &required_env, vec![]),
+        // Without the CLI we cannot tell what is installed locally.
+        missing: vec![],
+        required_bins,
+        required_env,
+        footprint_bytes: None,
+        version: None,
+        stars: None,
+        downloads: None,
+    }
+}
+
 fn fallback_catalog() -> Vec<SkillCatalogItem> {
     vec![
-        SkillCatalogItem {
-            name: "healthcheck".to_string(),
-            description: "Host security hardening and periodic security checks.".to_string(),
-            eligible: true,
-            bundled: true,
-            source: "openclaw-bundled".to_string(),
-        },
-        SkillCatalogItem {
-            name: "skill-creator".to_string(),
-            description: "Create or update AgentSkills.".to_string(),
-            eligible: true,
-            bundled: true,
-            source: "openclaw-bundled".to_string(),
-        },
-        SkillCatalogItem {
-            name: "github".to_string(),
-            description: "GitHub CLI integration for issues/PRs/actions.".to_string(),
-            eligible: false,
-            bundled: true,
-            source: "openclaw-bundled".to_string(),
-        },
-        SkillCatalogItem {
-            name: "weather".to_string(),
-            description: "Weather queries and forecast helper.".to_string(),
-            eligible: false,
-            bundled: true,
-            source: "openclaw-bundled".to_string(),
-        },
-        SkillCatalogItem {
-            name: "clawhub".to_string(),
-            description: "Manage extra skills from clawhub.com.".to_string(),
-            eligible: false,
-            bundled: true,
-            source: "openclaw-bundled".to_string(),
-        },
+        fallback_item(
+            "healthcheck",
+            "Host security hardening and periodic security checks.",
+            true,
+            &[],
+            &[],
+        ),
+        fallback_item(
+            "skill-creator",
+            "Create or update AgentSkills.",
+            true,
+            &[],
+            &[],
+        ),
+        fallback_item(
+            "github",
+            "GitHub CLI integration for issues/PRs/actions.",
+            false,
+            &["gh"],
+            &[],
+        ),
+        fallback_item(
+            "weather",
+            "Weather queries and forecast helper.",
+            false,
+            &["curl"],
+            &[],
+        ),
+        fallback_item(
+            "clawhub",
+            "Manage extra skills from clawhub.com.",
+            false,
+            &["clawhub"],
+            &[],
+        ),
     ]
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parse_skills_payload;
+    use super::{
+        begin_refresh, catalog_item, fresh_disk_items, parse_clawhub_meta, parse_skills_payload,
+        SkillCatalogDiskCache, SKILL_CATALOG_DISK_CACHE_TTL,
+    };
+    use serde_json::json;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn only_one_background_refresh_runs_at_a_time() {
+        let flag = AtomicBool::new(false);
+        assert!(begin_refresh(&flag));
+        assert!(!begin_refresh(&flag));
+        flag.store(false, Ordering::SeqCst);
+        assert!(begin_refresh(&flag));
+    }
+
+    #[test]
+    fn disk_cache_is_used_until_it_expires() {
+        let raw = r#"{"skills":[{"name":"healthcheck","eligible":true}]}"#;
+        let items = parse_skills_payload(raw)
+            .expect("payload should parse")
+            .skills
+            .into_iter()
+            .map(catalog_item)
+            .collect::<Vec<_>>();
+        let saved_at = 1_000_000u128;
+        let cache = |items| SkillCatalogDiskCache {
+            saved_at_unix_ms: saved_at,
+            items,
+        };
+        let fresh = fresh_disk_items(cache(items.clone()), saved_at + 60_000);
+        assert_eq!(fresh.map(|items| items.len()), Some(1));
+        let expired = saved_at + SKILL_CATALOG_DISK_CACHE_TTL.as_millis() + 1;
+        assert!(fresh_disk_items(cache(items), expired).is_none());
+        assert!(fresh_disk_items(cache(vec![]), saved_at).is_none());
+    }
 
     #[test]
     fn parse_skills_payload_works_for_pure_json() {
@@ -199,4 +539,31 @@ mod tests {
         assert_eq!(parsed.skills.len(), 1);
         assert_eq!(parsed.skills[0].name, "feishu-doc");
     }
+
+    #[test]
+    fn catalog_item_carries_requirements_and_permissions() {
+        let raw = r#"{"skills":[{"name":"github","description":"gh","eligible":false,"bundled":true,"source":"openclaw-bundled","requires":{"bins":["gh"],"env":["GITHUB_TOKEN"]},"missing":{"bins":["gh"]},"permissions":["network"]}]}"#;
+        let parsed = parse_skills_payload(raw).expect("payload should parse");
+        let item = catalog_item(parsed.skills.into_iter().next().unwrap());
+        assert_eq!(item.required_bins, vec!["gh"]);
+        assert_eq!(item.required_env, vec!["GITHUB_TOKEN"]);
+        assert_eq!(item.missing, vec!["gh"]);
+        assert_eq!(
+            item.permissions,
+            vec!["env:GITHUB_TOKEN", "exec:gh", "network"]
+        );
+        assert_eq!(item.footprint_bytes, None);
+    }
+
+    #[test]
+    fn parse_clawhub_meta_accepts_nested_shapes() {
+        let meta = parse_clawhub_meta(&json!({
+            "skill": { "latestVersion": { "version": "1.4.0" }, "stats": { "stars": 45, "downloads": 1200 } }
+        }))
+        .expect("metadata should parse");
+        assert_eq!(meta.version.as_deref(), Some("1.4.0"));
+        assert_eq!(meta.stars, Some(45));
+        assert_eq!(meta.downloads, Some(1200));
+        assert!(parse_clawhub_meta(&json!({ "error": "not found" })).is_none());
+    }
 }
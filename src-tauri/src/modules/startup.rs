@@ -0,0 +1,144 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+use crate::models::{StartupPhase, StartupReport};
+
+use super::logger;
+
+/// Window paint target on a cold HDD start; exceeding it is logged as a warning.
+const WINDOW_BUDGET_MS: u64 = 1000;
+/// Deferred work still runs if the page-load event never arrives (e.g. webview crash).
+const DEFER_FALLBACK: Duration = Duration::from_secs(5);
+
+type Task = (&'static str, Box<dyn FnOnce() + Send>);
+
+static STARTED: Lazy<Instant> = Lazy::new(Instant::now);
+static PHASES: Mutex<Vec<StartupPhase>> = Mutex::new(Vec::new());
+static DEFERRED: Mutex<Option<Vec<Task>>> = Mutex::new(Some(Vec::new()));
+static WINDOW_READY_MS: Mutex<Option<u64>> = Mutex::new(None);
+static SETTLED: AtomicBool = AtomicBool::new(false);
+
+/// Starts the startup clock. Call first thing in `main`.
+pub fn begin() {
+    Lazy::force(&STARTED);
+    thread::spawn(|| {
+        thread::sleep(DEFER_FALLBACK);
+        run_deferred();
+    });
+}
+
+/// Records that `phase` finished, in ms since `begin`.
+pub fn mark(phase: &str) {
+    let at_ms = elapsed_ms();
+    PHASES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(StartupPhase {
+            name: phase.to_string(),
+            at_ms,
+        });
+}
+
+/// Queues work that is not needed to show the window; it runs on a background thread
+/// once the page has loaded. Runs immediately if startup already settled.
+pub fn defer(name: &'static str, task: impl FnOnce() + Send + 'static) {
+    let mut deferred = DEFERRED.lock().unwrap_or_else(|e| e.into_inner());
+    match deferred.as_mut() {
+        Some(queue) => queue.push((name, Box::new(task))),
+        None => {
+            drop(deferred);
+            thread::spawn(task);
+        }
+    }
+}
+
+/// Called when the main window's page finished loading.
+pub fn window_ready() {
+    let at_ms = elapsed_ms();
+    {
+        let mut ready = WINDOW_READY_MS.lock().unwrap_or_else(|e| e.into_inner());
+        if ready.is_some() {
+            return;
+        }
+        *ready = Some(at_ms);
+    }
+    mark("window_ready");
+    if at_ms > WINDOW_BUDGET_MS {
+        logger::warn(&format!(
+            "Startup: window ready after {at_ms} ms (budget {WINDOW_BUDGET_MS} ms)."
+        ));
+    } else {
+        logger::info(&format!("Startup: window ready after {at_ms} ms."));
+    }
+    run_deferred();
+}
+
+/// True once deferred startup work has run; status polls hold back housekeeping
+/// (audits, auto-commits) until then so they do not compete with first paint.
+pub fn settled() -> bool {
+    SETTLED.load(Ordering::SeqCst)
+}
+
+pub fn report() -> StartupReport {
+    StartupReport {
+        budget_ms: WINDOW_BUDGET_MS,
+        window_ready_ms: *WINDOW_READY_MS.lock().unwrap_or_else(|e| e.into_inner()),
+        settled: settled(),
+        phases: PHASES.lock().unwrap_or_else(|e| e.into_inner()).clone(),
+    }
+}
+
+fn run_deferred() {
+    let Some(tasks) = DEFERRED.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return;
+    };
+    thread::spawn(move || {
+        for (name, task) in tasks {
+            task();
+            mark(&format!("deferred:{name}"));
+        }
+        SETTLED.store(true, Ordering::SeqCst);
+    });
+}
+
+fn elapsed_ms() -> u64 {
+    STARTED.elapsed().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{defer, report, run_deferred, settled};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn deferred_work_waits_for_the_window_and_runs_in_order() {
+        let (tx, rx) = mpsc::channel();
+        for name in ["first", "second"] {
+            let tx = tx.clone();
+            defer(name, move || tx.send(name).unwrap());
+        }
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+        assert!(!settled());
+
+        run_deferred();
+        let wait = Duration::from_secs(2);
+        assert_eq!(rx.recv_timeout(wait).unwrap(), "first");
+        assert_eq!(rx.recv_timeout(wait).unwrap(), "second");
+        let deadline = std::time::Instant::now() + wait;
+        while !settled() && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(settled());
+        let phases: Vec<String> = report().phases.into_iter().map(|p| p.name).collect();
+        assert!(phases.contains(&"deferred:second".to_string()));
+
+        // Once startup is over, deferred work starts right away.
+        defer("late", move || tx.send("late").unwrap());
+        assert_eq!(rx.recv_timeout(wait).unwrap(), "late");
+    }
+}
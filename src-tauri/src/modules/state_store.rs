@@ -1,17 +1,27 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
-use crate::models::{InstallState, OpenClawConfigInput};
+use crate::models::{
+    AppLockConfig, BackupStorageConfig, ConfigWatchConfig, ConsentStore, CustomProviderRecord,
+    FileAuditConfig, InstallState, KeepAliveConfig, LogRotationConfig, MigrationRecord,
+    OpenClawConfigInput, ProfileRegistry, QuietHoursConfig, ReadOnlyConfig, ScriptScanConfig,
+    SecurityCheckRecord, SecurityScheduleConfig, ShortcutConfig, StatusApiConfig,
+    SupportShareConfig, TunnelConfigInput, WatchdogState, WorkspaceVersioningConfig,
+};
 
-use super::paths;
+use super::{logger, paths, secrets};
 
 fn install_state_path() -> PathBuf {
     paths::state_dir().join("install_state.json")
 }
 
+// Credentials in last_config.json are stored as `sealed:<DPAPI blob>` where DPAPI is
+// available, so the file (and backups of it) do not carry them in plain text.
+const SEALED_PREFIX: &str = "sealed:";
+
 fn config_state_path() -> PathBuf {
     paths::state_dir().join("last_config.json")
 }
@@ -20,17 +30,122 @@ fn run_prefs_path() -> PathBuf {
     paths::state_dir().join("run_prefs.json")
 }
 
+fn tunnel_config_path() -> PathBuf {
+    paths::state_dir().join("tunnel.json")
+}
+
+fn workspace_versioning_path() -> PathBuf {
+    paths::state_dir().join("workspace_versioning.json")
+}
+
+fn file_audit_config_path() -> PathBuf {
+    paths::state_dir().join("file_audit.json")
+}
+
+fn config_watch_path() -> PathBuf {
+    paths::state_dir().join("config_watch.json")
+}
+
+fn quiet_hours_path() -> PathBuf {
+    paths::state_dir().join("quiet_hours.json")
+}
+
+fn security_schedule_path() -> PathBuf {
+    paths::state_dir().join("security_schedule.json")
+}
+
+fn security_last_check_path() -> PathBuf {
+    paths::state_dir().join("security_last_check.json")
+}
+
+fn keep_alive_path() -> PathBuf {
+    paths::state_dir().join("keep_alive.json")
+}
+
+fn backup_storage_path() -> PathBuf {
+    paths::state_dir().join("backup_storage.json")
+}
+
+fn shortcuts_path() -> PathBuf {
+    paths::state_dir().join("shortcuts.json")
+}
+
+fn status_api_path() -> PathBuf {
+    paths::state_dir().join("status_api.json")
+}
+
+fn log_rotation_path() -> PathBuf {
+    paths::state_dir().join("log_rotation.json")
+}
+
+fn custom_providers_path() -> PathBuf {
+    paths::state_dir().join("custom_providers.json")
+}
+
+fn support_share_path() -> PathBuf {
+    paths::state_dir().join("support_share.json")
+}
+
+fn script_scan_path() -> PathBuf {
+    paths::state_dir().join("script_scan.json")
+}
+
+// Shared by all profiles, so it lives at the app data root rather than a state dir.
+fn profiles_path() -> PathBuf {
+    paths::appdata_root().join("profiles.json")
+}
+
+// Given by the person at the keyboard, not per profile.
+fn consents_path() -> PathBuf {
+    paths::appdata_root().join("consents.json")
+}
+
+// Machine-level like consents: switching profile must not lift it.
+fn read_only_path() -> PathBuf {
+    paths::appdata_root().join("read_only.json")
+}
+
+fn app_lock_path() -> PathBuf {
+    paths::appdata_root().join("app_lock.json")
+}
+
+fn migration_record_path() -> PathBuf {
+    paths::state_dir().join("migration.json")
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct RunPrefs {
     /// When true, the installer will try to keep OpenClaw gateway running in the background.
     /// "End OpenClaw" sets this to false so it stays off until user explicitly starts again.
     pub keep_running: bool,
+    /// Max seconds auto-start waits for network connectivity before starting anyway. 0 disables the wait.
+    pub network_wait_secs: u64,
+    /// Connectivity probe URL. Empty uses the Windows NCSI endpoint.
+    pub network_probe_url: String,
+    /// Send a one-token completion through the gateway after start to pre-warm it.
+    pub warm_up_after_start: bool,
+    /// How long start waits for the gateway to become ready before reporting "starting".
+    pub start_ready_timeout_secs: u64,
+    /// A crash this soon after a start counts toward the crash loop.
+    pub rapid_crash_secs: u64,
+    /// Rapid crashes in a row after which autostart gives up.
+    pub crash_loop_threshold: u32,
+    pub watchdog: WatchdogState,
 }
 
 impl Default for RunPrefs {
     fn default() -> Self {
-        Self { keep_running: true }
+        Self {
+            keep_running: true,
+            network_wait_secs: 90,
+            network_probe_url: String::new(),
+            warm_up_after_start: false,
+            start_ready_timeout_secs: 20,
+            rapid_crash_secs: 60,
+            crash_loop_threshold: 5,
+            watchdog: WatchdogState::default(),
+        }
     }
 }
 
@@ -42,7 +157,12 @@ pub fn save_install_state(state: &InstallState) -> Result<()> {
 }
 
 pub fn load_install_state() -> Result<Option<InstallState>> {
-    let path = install_state_path();
+    load_install_state_in(&paths::state_dir())
+}
+
+/// Install state of a profile other than the active one.
+pub fn load_install_state_in(state_dir: &Path) -> Result<Option<InstallState>> {
+    let path = state_dir.join("install_state.json");
     if !path.exists() {
         return Ok(None);
     }
@@ -53,21 +173,143 @@ pub fn load_install_state() -> Result<Option<InstallState>> {
 
 pub fn save_last_config(payload: &OpenClawConfigInput) -> Result<()> {
     paths::ensure_dirs()?;
-    let data = serde_json::to_string_pretty(payload)?;
+    let mut stored = payload.clone();
+    for_each_secret(&mut stored, seal_field)?;
+    let data = serde_json::to_string_pretty(&stored)?;
     fs::write(config_state_path(), data)?;
     Ok(())
 }
 
+/// The last setup payload with its credentials opened. A value that no longer opens
+/// (e.g. the data folder came from another Windows account) reads as empty so setup
+/// asks for it again.
 pub fn load_last_config() -> Result<Option<OpenClawConfigInput>> {
     let path = config_state_path();
     if !path.exists() {
         return Ok(None);
     }
     let raw = fs::read_to_string(path)?;
-    let value = serde_json::from_str::<OpenClawConfigInput>(&raw)?;
+    let mut value = serde_json::from_str::<OpenClawConfigInput>(&raw)?;
+    let mut plain = false;
+    for_each_secret(&mut value, |field| {
+        plain |= open_field(field);
+        Ok(())
+    })?;
+    // Files written before credentials were sealed are sealed on first read.
+    if plain && secrets::sealing_available() {
+        if let Err(err) = save_last_config(&value) {
+            logger::warn(&format!("Failed to seal saved setup credentials: {err}"));
+        }
+    }
     Ok(Some(value))
 }
 
+/// `last_config.json` as it goes into a backup: sealed credentials stay (they only open
+/// for this Windows account), plaintext ones are blanked so an archive copied off the
+/// machine carries no usable secret.
+pub fn last_config_for_backup() -> Result<Option<(PathBuf, Vec<u8>)>> {
+    let path = config_state_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut value = serde_json::from_str::<OpenClawConfigInput>(&fs::read_to_string(&path)?)?;
+    for_each_secret(&mut value, |field| {
+        if !field.starts_with(SEALED_PREFIX) {
+            field.clear();
+        }
+        Ok(())
+    })?;
+    Ok(Some((path, serde_json::to_vec_pretty(&value)?)))
+}
+
+/// Puts back credentials a restored setup payload lacks, i.e. the ones
+/// `last_config_for_backup` blanked, from the payload that was live before the restore.
+pub fn keep_missing_secrets(restored: &mut OpenClawConfigInput, live: &OpenClawConfigInput) {
+    let fill = |value: &mut String, live: &str| {
+        if value.is_empty() {
+            *value = live.to_string();
+        }
+    };
+    fill(&mut restored.api_key, &live.api_key);
+    fill(
+        &mut restored.aws_secret_access_key,
+        &live.aws_secret_access_key,
+    );
+    fill(&mut restored.feishu_app_secret, &live.feishu_app_secret);
+    fill(&mut restored.dingtalk_app_secret, &live.dingtalk_app_secret);
+    fill(&mut restored.telegram_bot_token, &live.telegram_bot_token);
+    fill(&mut restored.discord_bot_token, &live.discord_bot_token);
+    if let (Some(token), Some(live)) = (restored.remote_token.as_mut(), &live.remote_token) {
+        fill(token, live);
+    }
+    for (provider, key) in restored.provider_api_keys.iter_mut() {
+        if let Some(live) = live.provider_api_keys.get(provider) {
+            fill(key, live);
+        }
+    }
+}
+
+/// Seals a credential in place as `sealed:<blob>`; empty or already sealed values and
+/// machines without DPAPI are left as they are.
+fn seal_field(value: &mut String) -> Result<()> {
+    if value.is_empty() || value.starts_with(SEALED_PREFIX) {
+        return Ok(());
+    }
+    if let Some(blob) = secrets::seal_value(value) {
+        *value = format!("{SEALED_PREFIX}{}", blob?);
+    }
+    Ok(())
+}
+
+/// Opens a sealed credential in place; one that no longer opens reads as empty.
+/// Returns true for a plaintext value that still needs sealing.
+fn open_field(field: &mut String) -> bool {
+    match field.strip_prefix(SEALED_PREFIX) {
+        Some(blob) => {
+            *field = secrets::open_value(blob).unwrap_or_else(|err| {
+                logger::warn(&format!("A saved credential could not be opened: {err}"));
+                String::new()
+            });
+            false
+        }
+        None => !field.is_empty(),
+    }
+}
+
+/// Every credential field of the setup payload.
+pub fn for_each_secret(
+    payload: &mut OpenClawConfigInput,
+    mut f: impl FnMut(&mut String) -> Result<()>,
+) -> Result<()> {
+    let OpenClawConfigInput {
+        api_key,
+        provider_api_keys,
+        remote_token,
+        aws_secret_access_key,
+        feishu_app_secret,
+        dingtalk_app_secret,
+        telegram_bot_token,
+        discord_bot_token,
+        ..
+    } = payload;
+    let fields = [
+        api_key,
+        aws_secret_access_key,
+        feishu_app_secret,
+        dingtalk_app_secret,
+        telegram_bot_token,
+        discord_bot_token,
+    ];
+    for value in fields
+        .into_iter()
+        .chain(remote_token.as_mut())
+        .chain(provider_api_keys.values_mut())
+    {
+        f(value)?;
+    }
+    Ok(())
+}
+
 pub fn clear_install_state() -> Result<()> {
     let path = install_state_path();
     if path.exists() {
@@ -108,6 +350,13 @@ pub fn set_keep_running(value: bool) -> Result<()> {
     Ok(())
 }
 
+pub fn set_warm_up_after_start(value: bool) -> Result<()> {
+    let mut prefs = load_run_prefs()?;
+    prefs.warm_up_after_start = value;
+    save_run_prefs(&prefs)?;
+    Ok(())
+}
+
 pub fn clear_run_prefs() -> Result<()> {
     let path = run_prefs_path();
     if path.exists() {
@@ -115,3 +364,427 @@ pub fn clear_run_prefs() -> Result<()> {
     }
     Ok(())
 }
+
+pub fn load_tunnel_config() -> Result<TunnelConfigInput> {
+    let path = tunnel_config_path();
+    if !path.exists() {
+        return Ok(TunnelConfigInput::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let mut value = serde_json::from_str::<TunnelConfigInput>(&raw)?;
+    let mut plain = false;
+    for field in [&mut value.cloudflared_token, &mut value.frp_auth_token] {
+        plain |= open_field(field);
+    }
+    if plain && secrets::sealing_available() {
+        if let Err(err) = save_tunnel_config(&value) {
+            logger::warn(&format!("Failed to seal tunnel credentials: {err}"));
+        }
+    }
+    Ok(value)
+}
+
+/// Saves tunnel settings with the cloudflared and frp tokens sealed.
+pub fn save_tunnel_config(cfg: &TunnelConfigInput) -> Result<()> {
+    paths::ensure_dirs()?;
+    let mut stored = cfg.clone();
+    seal_field(&mut stored.cloudflared_token)?;
+    seal_field(&mut stored.frp_auth_token)?;
+    let data = serde_json::to_string_pretty(&stored)?;
+    fs::write(tunnel_config_path(), data)?;
+    Ok(())
+}
+
+pub fn load_workspace_versioning() -> Result<WorkspaceVersioningConfig> {
+    let path = workspace_versioning_path();
+    if !path.exists() {
+        return Ok(WorkspaceVersioningConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<WorkspaceVersioningConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_workspace_versioning(cfg: &WorkspaceVersioningConfig) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(workspace_versioning_path(), data)?;
+    Ok(())
+}
+
+pub fn load_file_audit_config() -> Result<FileAuditConfig> {
+    let path = file_audit_config_path();
+    if !path.exists() {
+        return Ok(FileAuditConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<FileAuditConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_file_audit_config(cfg: &FileAuditConfig) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(file_audit_config_path(), data)?;
+    Ok(())
+}
+
+pub fn load_config_watch() -> Result<ConfigWatchConfig> {
+    let path = config_watch_path();
+    if !path.exists() {
+        return Ok(ConfigWatchConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<ConfigWatchConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_config_watch(cfg: &ConfigWatchConfig) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(config_watch_path(), data)?;
+    Ok(())
+}
+
+pub fn load_quiet_hours() -> Result<QuietHoursConfig> {
+    let path = quiet_hours_path();
+    if !path.exists() {
+        return Ok(QuietHoursConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<QuietHoursConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_quiet_hours(cfg: &QuietHoursConfig) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(quiet_hours_path(), data)?;
+    Ok(())
+}
+
+pub fn load_security_schedule() -> Result<SecurityScheduleConfig> {
+    let path = security_schedule_path();
+    if !path.exists() {
+        return Ok(SecurityScheduleConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<SecurityScheduleConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_security_schedule(cfg: &SecurityScheduleConfig) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(security_schedule_path(), data)?;
+    Ok(())
+}
+
+pub fn load_security_last_check() -> Result<Option<SecurityCheckRecord>> {
+    let path = security_last_check_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<SecurityCheckRecord>(&raw)?;
+    Ok(Some(value))
+}
+
+pub fn save_security_last_check(record: &SecurityCheckRecord) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(record)?;
+    fs::write(security_last_check_path(), data)?;
+    Ok(())
+}
+
+pub fn load_custom_providers() -> Result<Vec<CustomProviderRecord>> {
+    let path = custom_providers_path();
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<Vec<CustomProviderRecord>>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_custom_providers(records: &[CustomProviderRecord]) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(records)?;
+    fs::write(custom_providers_path(), data)?;
+    Ok(())
+}
+
+pub fn load_support_share() -> Result<SupportShareConfig> {
+    let path = support_share_path();
+    if !path.exists() {
+        return Ok(SupportShareConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<SupportShareConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_support_share(cfg: &SupportShareConfig) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(support_share_path(), data)?;
+    Ok(())
+}
+
+pub fn load_keep_alive() -> Result<KeepAliveConfig> {
+    let path = keep_alive_path();
+    if !path.exists() {
+        return Ok(KeepAliveConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<KeepAliveConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_keep_alive(cfg: &KeepAliveConfig) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(keep_alive_path(), data)?;
+    Ok(())
+}
+
+pub fn load_backup_storage() -> Result<BackupStorageConfig> {
+    let path = backup_storage_path();
+    if !path.exists() {
+        return Ok(BackupStorageConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<BackupStorageConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_backup_storage(cfg: &BackupStorageConfig) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(backup_storage_path(), data)?;
+    Ok(())
+}
+
+pub fn save_migration_record(record: &MigrationRecord) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(record)?;
+    fs::write(migration_record_path(), data)?;
+    Ok(())
+}
+
+pub fn load_migration_record() -> Result<Option<MigrationRecord>> {
+    let path = migration_record_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<MigrationRecord>(&raw)?;
+    Ok(Some(value))
+}
+
+pub fn load_status_api() -> Result<StatusApiConfig> {
+    let path = status_api_path();
+    if !path.exists() {
+        return Ok(StatusApiConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<StatusApiConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_status_api(cfg: &StatusApiConfig) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(status_api_path(), data)?;
+    Ok(())
+}
+
+pub fn load_shortcuts() -> Result<ShortcutConfig> {
+    let path = shortcuts_path();
+    if !path.exists() {
+        return Ok(ShortcutConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<ShortcutConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_shortcuts(cfg: &ShortcutConfig) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(shortcuts_path(), data)?;
+    Ok(())
+}
+
+pub fn load_log_rotation() -> Result<LogRotationConfig> {
+    let path = log_rotation_path();
+    if !path.exists() {
+        return Ok(LogRotationConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<LogRotationConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_log_rotation(cfg: &LogRotationConfig) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(log_rotation_path(), data)?;
+    Ok(())
+}
+
+pub fn load_profiles() -> Result<ProfileRegistry> {
+    let path = profiles_path();
+    if !path.exists() {
+        return Ok(ProfileRegistry::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<ProfileRegistry>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_profiles(registry: &ProfileRegistry) -> Result<()> {
+    fs::create_dir_all(paths::appdata_root())?;
+    let data = serde_json::to_string_pretty(registry)?;
+    fs::write(profiles_path(), data)?;
+    Ok(())
+}
+
+pub fn load_consents() -> Result<ConsentStore> {
+    let path = consents_path();
+    if !path.exists() {
+        return Ok(ConsentStore::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<ConsentStore>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_consents(store: &ConsentStore) -> Result<()> {
+    fs::create_dir_all(paths::appdata_root())?;
+    let data = serde_json::to_string_pretty(store)?;
+    fs::write(consents_path(), data)?;
+    Ok(())
+}
+
+pub fn load_read_only() -> Result<ReadOnlyConfig> {
+    let path = read_only_path();
+    if !path.exists() {
+        return Ok(ReadOnlyConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<ReadOnlyConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_read_only(cfg: &ReadOnlyConfig) -> Result<()> {
+    fs::create_dir_all(paths::appdata_root())?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(read_only_path(), data)?;
+    Ok(())
+}
+
+pub fn load_app_lock() -> Result<AppLockConfig> {
+    let path = app_lock_path();
+    if !path.exists() {
+        return Ok(AppLockConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<AppLockConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_app_lock(cfg: &AppLockConfig) -> Result<()> {
+    fs::create_dir_all(paths::appdata_root())?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(app_lock_path(), data)?;
+    Ok(())
+}
+
+pub fn load_script_scan() -> Result<ScriptScanConfig> {
+    let path = script_scan_path();
+    if !path.exists() {
+        return Ok(ScriptScanConfig::default());
+    }
+    let raw = fs::read_to_string(path)?;
+    let value = serde_json::from_str::<ScriptScanConfig>(&raw)?;
+    Ok(value)
+}
+
+pub fn save_script_scan(cfg: &ScriptScanConfig) -> Result<()> {
+    paths::ensure_dirs()?;
+    let data = serde_json::to_string_pretty(cfg)?;
+    fs::write(script_scan_path(), data)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{for_each_secret, keep_missing_secrets};
+    use crate::models::OpenClawConfigInput;
+
+    #[test]
+    fn keep_missing_secrets_refills_every_blanked_credential() {
+        let mut live = OpenClawConfigInput {
+            api_key: "a".to_string(),
+            remote_token: Some("b".to_string()),
+            aws_secret_access_key: "c".to_string(),
+            feishu_app_secret: "d".to_string(),
+            dingtalk_app_secret: "e".to_string(),
+            telegram_bot_token: "f".to_string(),
+            discord_bot_token: "g".to_string(),
+            ..OpenClawConfigInput::default()
+        };
+        live.provider_api_keys
+            .insert("openai".to_string(), "h".to_string());
+        let mut restored = live.clone();
+        for_each_secret(&mut restored, |value| {
+            value.clear();
+            Ok(())
+        })
+        .expect("blank");
+        restored.provider = "restored".to_string();
+
+        keep_missing_secrets(&mut restored, &live);
+
+        let mut refilled = Vec::new();
+        for_each_secret(&mut restored, |value| {
+            refilled.push(value.clone());
+            Ok(())
+        })
+        .expect("visit");
+        refilled.sort();
+        assert_eq!(refilled, vec!["a", "b", "c", "d", "e", "f", "g", "h"]);
+        assert_eq!(restored.provider, "restored");
+    }
+
+    #[test]
+    fn for_each_secret_covers_every_credential_field() {
+        let mut payload = OpenClawConfigInput {
+            api_key: "a".to_string(),
+            remote_token: Some("b".to_string()),
+            aws_secret_access_key: "c".to_string(),
+            feishu_app_secret: "d".to_string(),
+            dingtalk_app_secret: "e".to_string(),
+            telegram_bot_token: "f".to_string(),
+            discord_bot_token: "g".to_string(),
+            aws_access_key_id: "AKIA".to_string(),
+            ..OpenClawConfigInput::default()
+        };
+        payload
+            .provider_api_keys
+            .insert("openai".to_string(), "h".to_string());
+        let mut seen = Vec::new();
+        for_each_secret(&mut payload, |value| {
+            seen.push(value.clone());
+            *value = format!("sealed:{value}");
+            Ok(())
+        })
+        .expect("visit");
+        seen.sort();
+        assert_eq!(seen, vec!["a", "b", "c", "d", "e", "f", "g", "h"]);
+        assert_eq!(payload.remote_token.as_deref(), Some("sealed:b"));
+        assert_eq!(payload.provider_api_keys["openai"], "sealed:h");
+        assert_eq!(payload.aws_access_key_id, "AKIA");
+    }
+}
@@ -0,0 +1,261 @@
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::models::{InstallerStatus, StatusChange};
+
+use super::{config, health, logger, process, profiles, startup, uptime};
+
+/// Tauri event carrying a `StatusChange` whenever the gateway state moves.
+pub const EVENT: &str = "status://changed";
+
+const PROBE_INTERVAL: Duration = Duration::from_secs(3);
+// The full status also runs autostart and housekeeping; build it this often, or as soon
+// as the cheap probe moves.
+const FULL_STATUS_INTERVAL: Duration = Duration::from_secs(15);
+// A stop that lands this long after the installer asked for it is treated as a crash.
+const STOP_EXPECTATION: Duration = Duration::from_secs(60);
+
+type Sink = Box<dyn Fn(&StatusChange) + Send + Sync>;
+
+static SINK: OnceLock<Sink> = OnceLock::new();
+static STOP_REQUESTED_AT: Mutex<Option<Instant>> = Mutex::new(None);
+static LATEST: Mutex<Option<InstallerStatus>> = Mutex::new(None);
+
+/// Registers where changes go; `main` forwards them to the webview as `EVENT`.
+pub fn set_sink(sink: impl Fn(&StatusChange) + Send + Sync + 'static) {
+    let _ = SINK.set(Box::new(sink));
+}
+
+/// Marks the next stop as deliberate so it is reported as `user_stopped`, not `crashed`.
+pub fn note_stop_requested() {
+    *STOP_REQUESTED_AT.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+}
+
+/// The last full status the stream built, for readers that must not run
+/// `process::status` themselves (it may autostart the gateway).
+pub fn latest() -> Option<InstallerStatus> {
+    LATEST.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// What the stream checks every tick: the PID file (verified natively) and one health
+/// request, without the disk and process work of `process::status`.
+#[derive(Debug, Clone, PartialEq)]
+struct Probe {
+    running: bool,
+    healthy: bool,
+}
+
+async fn probe() -> Probe {
+    let running = process::running_pid().is_some();
+    let healthy = match config::read_current_config() {
+        Ok(cfg) => health::health_check(&cfg.bind_address, cfg.port)
+            .await
+            .is_ok_and(|result| result.ok),
+        Err(_) => false,
+    };
+    Probe {
+        running: running || healthy,
+        healthy,
+    }
+}
+
+fn needs_full_status(last_full: Option<Instant>, previous: Option<&Probe>, next: &Probe) -> bool {
+    previous != Some(next) || last_full.is_none_or(|at| at.elapsed() >= FULL_STATUS_INTERVAL)
+}
+
+/// Probes PID and gateway health in the background and pushes an event on every
+/// transition, so the UI does not have to poll `get_status`.
+pub fn start() {
+    thread::spawn(|| {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(err) => {
+                logger::error(&format!("Status stream runtime failed: {err}"));
+                return;
+            }
+        };
+        let mut previous: Option<InstallerStatus> = None;
+        let mut last_probe: Option<Probe> = None;
+        let mut last_full: Option<Instant> = None;
+        let mut last_error = String::new();
+        let mut generation = profiles::generation();
+        let mut was_settled = startup::settled();
+        loop {
+            let tick = profiles::worker_tick();
+            if profiles::generation() != generation {
+                // Another profile is active: start over instead of reporting its gateway
+                // as a transition of the previous one.
+                generation = profiles::generation();
+                previous = None;
+                last_probe = None;
+                last_full = None;
+                *LATEST.lock().unwrap_or_else(|e| e.into_inner()) = None;
+                STOP_REQUESTED_AT
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .take();
+            }
+            let next_probe = runtime.block_on(probe());
+            uptime::record_sample(next_probe.running);
+            // Statuses built before startup settled skip the slow probes; redo it once.
+            let settled = startup::settled();
+            let due = (settled && !was_settled)
+                || needs_full_status(last_full, last_probe.as_ref(), &next_probe);
+            was_settled = settled;
+            last_probe = Some(next_probe);
+            if !due {
+                drop(tick);
+                thread::sleep(PROBE_INTERVAL);
+                continue;
+            }
+            last_full = Some(Instant::now());
+            match runtime.block_on(process::status()) {
+                Ok(status) => {
+                    last_error.clear();
+                    let stop_requested = matches!(
+                        (&previous, status.running),
+                        (Some(prev), false) if prev.running
+                    ) && take_stop_request();
+                    if let Some(reason) = transition(previous.as_ref(), &status, stop_requested) {
+                        if let Some(sink) = SINK.get() {
+                            sink(&StatusChange {
+                                reason: reason.to_string(),
+                                status: status.clone(),
+                            });
+                        }
+                    }
+                    *LATEST.lock().unwrap_or_else(|e| e.into_inner()) = Some(status.clone());
+                    previous = Some(status);
+                }
+                Err(err) => {
+                    // Log each distinct failure once instead of every 3 seconds.
+                    let text = err.to_string();
+                    if text != last_error {
+                        logger::warn(&format!("Status probe failed: {text}"));
+                        last_error = text;
+                    }
+                }
+            }
+            drop(tick);
+            thread::sleep(PROBE_INTERVAL);
+        }
+    });
+}
+
+fn take_stop_request() -> bool {
+    STOP_REQUESTED_AT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+        .is_some_and(|at| at.elapsed() < STOP_EXPECTATION)
+}
+
+fn transition(
+    previous: Option<&InstallerStatus>,
+    next: &InstallerStatus,
+    stop_requested: bool,
+) -> Option<&'static str> {
+    let Some(previous) = previous else {
+        return Some("initial");
+    };
+    if next.watchdog.crash_loop && !previous.watchdog.crash_loop {
+        return Some("crash_loop");
+    }
+    match (previous.running, next.running) {
+        (false, true) => Some("started"),
+        (true, false) if stop_requested => Some("user_stopped"),
+        (true, false) => Some("crashed"),
+        (true, true) if previous.health.ok && !next.health.ok => Some("health_failed"),
+        (true, true) if !previous.health.ok && next.health.ok => Some("health_recovered"),
+        _ if without_health(previous) != without_health(next) => Some("updated"),
+        _ => None,
+    }
+}
+
+/// Health latency changes on every probe; only its `ok` flag is a transition.
+fn without_health(status: &InstallerStatus) -> Value {
+    let mut value = serde_json::to_value(status).unwrap_or(Value::Null);
+    if let Some(map) = value.as_object_mut() {
+        map.remove("health");
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{needs_full_status, transition, Probe, FULL_STATUS_INTERVAL};
+    use crate::models::{HealthResult, InstallerStatus, TunnelStatus, WatchdogState};
+    use std::time::{Duration, Instant};
+
+    fn status(running: bool, healthy: bool, latency_ms: u64) -> InstallerStatus {
+        InstallerStatus {
+            running,
+            pid: running.then_some(4242),
+            version: "1.0.0".to_string(),
+            provider: "openai".to_string(),
+            current_model: "openai/gpt-4o".to_string(),
+            port: 28789,
+            health: HealthResult {
+                ok: healthy,
+                latency_ms: Some(latency_ms),
+                ..HealthResult::default()
+            },
+            tunnel: TunnelStatus::default(),
+            managed_by: "installer".to_string(),
+            config_alerts: 0,
+            trial_mode: false,
+            watchdog: WatchdogState::default(),
+        }
+    }
+
+    #[test]
+    fn transitions_carry_a_reason_and_latency_noise_is_ignored() {
+        let up = status(true, true, 20);
+        assert_eq!(transition(None, &up, false), Some("initial"));
+        assert_eq!(transition(Some(&up), &status(true, true, 90), false), None);
+        assert_eq!(
+            transition(Some(&up), &status(true, false, 0), false),
+            Some("health_failed")
+        );
+        assert_eq!(
+            transition(Some(&status(true, false, 0)), &up, false),
+            Some("health_recovered")
+        );
+        let down = status(false, false, 0);
+        assert_eq!(transition(Some(&up), &down, false), Some("crashed"));
+        assert_eq!(transition(Some(&up), &down, true), Some("user_stopped"));
+        assert_eq!(transition(Some(&down), &up, false), Some("started"));
+        let mut alerted = up.clone();
+        alerted.config_alerts = 2;
+        assert_eq!(transition(Some(&up), &alerted, false), Some("updated"));
+        let mut looping = down.clone();
+        looping.watchdog.crash_loop = true;
+        assert_eq!(transition(Some(&down), &looping, false), Some("crash_loop"));
+    }
+
+    #[test]
+    fn full_status_only_when_the_probe_moves_or_is_stale() {
+        let up = Probe {
+            running: true,
+            healthy: true,
+        };
+        let unhealthy = Probe {
+            healthy: false,
+            ..up.clone()
+        };
+        let now = Instant::now();
+        assert!(needs_full_status(None, None, &up));
+        assert!(!needs_full_status(Some(now), Some(&up), &up));
+        assert!(needs_full_status(Some(now), Some(&up), &unhealthy));
+        let stale = now.checked_sub(FULL_STATUS_INTERVAL + Duration::from_secs(1));
+        if let Some(stale) = stale {
+            assert!(needs_full_status(Some(stale), Some(&up), &up));
+        }
+    }
+}
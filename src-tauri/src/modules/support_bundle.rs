@@ -0,0 +1,264 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use flate2::read::GzDecoder;
+use serde_json::json;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::models::LogQuery;
+
+use super::{logger, paths, sbom, state_store, telemetry};
+
+const MANIFEST_NAME: &str = "support_manifest.json";
+
+// Enough to hold every installer entry of one wizard run.
+const CORRELATED_ENTRY_LIMIT: usize = 20_000;
+
+/// Zips the installer, gateway and trace logs (rotations included) into one archive a
+/// user can attach to a bug report. Config and `.env` are never included. With a
+/// `correlation_id` (from `begin_run`), the installer entries of that run and the
+/// gateway log lines written while it ran are also added under `correlation/<id>/`. The
+/// install's SBOM is included when one was generated.
+pub fn export_support_bundle(output: &Path, correlation_id: Option<&str>) -> Result<String> {
+    write_bundle(output, correlation_id, &[])?;
+    Ok(output.to_string_lossy().to_string())
+}
+
+/// Same bundle with every occurrence of `secrets` replaced, for bundles that leave the
+/// machine. Gzipped rotations are stored decompressed so they can be scrubbed too.
+/// Returns the number of replacements.
+pub fn export_redacted_support_bundle(
+    output: &Path,
+    correlation_id: Option<&str>,
+    secrets: &[String],
+) -> Result<usize> {
+    write_bundle(output, correlation_id, secrets)
+}
+
+struct BundleWriter<'a> {
+    zip: ZipWriter<File>,
+    options: SimpleFileOptions,
+    files: Vec<String>,
+    secrets: &'a [String],
+    redactions: usize,
+}
+
+impl BundleWriter<'_> {
+    fn add(&mut self, name: String, data: &[u8]) -> Result<()> {
+        self.zip.start_file(name.as_str(), self.options)?;
+        if self.secrets.is_empty() {
+            self.zip.write_all(data)?;
+        } else {
+            let (text, count) = redact(&String::from_utf8_lossy(data), self.secrets);
+            self.redactions += count;
+            self.zip.write_all(text.as_bytes())?;
+        }
+        self.files.push(name);
+        Ok(())
+    }
+
+    /// Copies `reader` into the zip without holding the file in memory. Redaction works
+    /// line by line; a credential never spans a line break.
+    fn add_reader(&mut self, name: String, reader: impl Read) -> Result<()> {
+        self.zip.start_file(name.as_str(), self.options)?;
+        if self.secrets.is_empty() {
+            io::copy(&mut { reader }, &mut self.zip)?;
+        } else {
+            let mut reader = BufReader::new(reader);
+            let mut line = Vec::new();
+            while reader.read_until(b'\n', &mut line)? > 0 {
+                let (text, count) = redact(&String::from_utf8_lossy(&line), self.secrets);
+                self.redactions += count;
+                self.zip.write_all(text.as_bytes())?;
+                line.clear();
+            }
+        }
+        self.files.push(name);
+        Ok(())
+    }
+}
+
+fn write_bundle(output: &Path, correlation_id: Option<&str>, secrets: &[String]) -> Result<usize> {
+    let correlation_id = correlation_id.map(str::trim).filter(|id| !id.is_empty());
+    if let Some(id) = correlation_id {
+        // The id names a folder inside the zip; only accept what `begin_run` hands out.
+        if !is_valid_correlation_id(id) {
+            return Err(anyhow!("Invalid correlation id: {id}"));
+        }
+    }
+    paths::ensure_dirs()?;
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut bundle = BundleWriter {
+        zip: ZipWriter::new(File::create(output)?),
+        options: SimpleFileOptions::default().compression_method(CompressionMethod::Deflated),
+        files: Vec::new(),
+        secrets,
+        redactions: 0,
+    };
+    for entry in fs::read_dir(paths::logs_dir())? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let file_name = path
+            .file_name()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let gzipped = file_name.ends_with(".gz");
+        // The gateway keeps its logs open; read what is there instead of failing the bundle.
+        let added = File::open(&path).map_err(Into::into).and_then(|file| {
+            if !secrets.is_empty() && gzipped {
+                let name = file_name.trim_end_matches(".gz");
+                bundle.add_reader(format!("logs/{name}"), GzDecoder::new(file))
+            } else {
+                bundle.add_reader(format!("logs/{file_name}"), file)
+            }
+        });
+        if let Err(err) = added {
+            logger::warn(&format!(
+                "Support bundle skipped {}: {err}",
+                path.to_string_lossy()
+            ));
+        }
+    }
+    let mut segments = Vec::<String>::new();
+    if let Some(id) = correlation_id {
+        let entries = logger::query_logs(&LogQuery {
+            correlation_id: Some(id.to_string()),
+            limit: Some(CORRELATED_ENTRY_LIMIT),
+            ..LogQuery::default()
+        })?;
+        let mut journal = String::new();
+        for entry in &entries {
+            journal.push_str(&serde_json::to_string(entry)?);
+            journal.push('\n');
+        }
+        bundle.add(
+            format!("correlation/{id}/installer.jsonl"),
+            journal.as_bytes(),
+        )?;
+        for (log, text) in logger::gateway_segments(id) {
+            let name = format!("correlation/{id}/{}.segment.log", log_stem(&log));
+            segments.push(name.clone());
+            bundle.add(name, text.as_bytes())?;
+        }
+        if segments.is_empty() {
+            logger::warn(&format!(
+                "Support bundle: no gateway log lines found for run {id}."
+            ));
+        }
+    }
+    if let Some(path) = sbom::sbom_path() {
+        match fs::read(&path) {
+            Ok(data) => bundle.add("sbom.cdx.json".to_string(), &data)?,
+            Err(err) => logger::warn(&format!("Support bundle skipped the SBOM: {err}")),
+        }
+    }
+    let install = state_store::load_install_state().ok().flatten();
+    let manifest = serde_json::to_string_pretty(&json!({
+        "generated_at": Local::now().to_rfc3339(),
+        "installer_version": env!("CARGO_PKG_VERSION"),
+        "openclaw_version": install.as_ref().map(|state| state.version.clone()),
+        "install_method": install.as_ref().map(|state| format!("{:?}", state.method).to_lowercase()),
+        "traces": telemetry::traces_path()
+            .file_name()
+            .map(|v| format!("logs/{}", v.to_string_lossy())),
+        "correlation_id": correlation_id,
+        "gateway_segments": segments,
+        "redacted": !secrets.is_empty(),
+        "files": bundle.files,
+    }))?;
+    bundle.zip.start_file(MANIFEST_NAME, bundle.options)?;
+    bundle.zip.write_all(manifest.as_bytes())?;
+    bundle.zip.finish()?;
+    logger::info(&format!(
+        "Support bundle exported ({} files{}): {}",
+        bundle.files.len(),
+        if secrets.is_empty() { "" } else { ", redacted" },
+        output.to_string_lossy()
+    ));
+    Ok(bundle.redactions)
+}
+
+fn redact(text: &str, secrets: &[String]) -> (String, usize) {
+    let mut out = text.to_string();
+    let mut count = 0;
+    for secret in secrets.iter().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let hits = out.matches(secret).count();
+        if hits > 0 {
+            count += hits;
+            out = out.replace(secret, "******");
+        }
+    }
+    (out, count)
+}
+
+/// `openclaw-stdout.2026-10-17.log.gz` -> `openclaw-stdout.2026-10-17`.
+fn log_stem(name: &str) -> &str {
+    let name = name.strip_suffix(".gz").unwrap_or(name);
+    name.strip_suffix(".log").unwrap_or(name)
+}
+
+/// A `logger::begin_run` id (12 hex digits) or a full UUID.
+fn is_valid_correlation_id(id: &str) -> bool {
+    let hex = |text: &str| !text.is_empty() && text.chars().all(|c| c.is_ascii_hexdigit());
+    (id.len() == 12 && hex(id)) || uuid::Uuid::parse_str(id).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_valid_correlation_id, BundleWriter};
+    use std::fs::{self, File};
+    use std::io::Read;
+    use zip::write::SimpleFileOptions;
+    use zip::{ZipArchive, ZipWriter};
+
+    #[test]
+    fn correlation_ids_are_hex_or_uuid() {
+        assert!(is_valid_correlation_id("3f9a0c21b7de"));
+        assert!(is_valid_correlation_id(
+            "67e55044-10b1-426f-9247-bb680e5fe0c8"
+        ));
+        assert!(!is_valid_correlation_id("../../evil"));
+        assert!(!is_valid_correlation_id("3f9a0c21b7dg"));
+        assert!(!is_valid_correlation_id("abc"));
+    }
+
+    #[test]
+    fn logs_are_streamed_and_redacted_line_by_line() {
+        let dir = std::env::temp_dir().join(format!("oc-bundle-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("bundle.zip");
+        let secrets = vec!["sk-live-123".to_string()];
+        let mut bundle = BundleWriter {
+            zip: ZipWriter::new(File::create(&output).unwrap()),
+            options: SimpleFileOptions::default(),
+            files: Vec::new(),
+            secrets: &secrets,
+            redactions: 0,
+        };
+        let log = "start\nkey=sk-live-123 sk-live-123\nno newline at end sk-live-123";
+        bundle
+            .add_reader("logs/gateway.log".to_string(), log.as_bytes())
+            .unwrap();
+        assert_eq!(bundle.redactions, 3);
+        bundle.zip.finish().unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        let mut text = String::new();
+        archive
+            .by_name("logs/gateway.log")
+            .unwrap()
+            .read_to_string(&mut text)
+            .unwrap();
+        assert_eq!(text, "start\nkey=****** ******\nno newline at end ******");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
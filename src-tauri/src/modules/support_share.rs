@@ -0,0 +1,326 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+use reqwest::Client;
+use url::Url;
+
+use crate::models::{SupportShareConfig, SupportShareKind, SupportShareResult};
+
+use super::{
+    browser, config, dotenv::EnvFile, env_diff, logger, paths, process, secrets, state_store,
+    support_bundle, temp_files,
+};
+
+// Large gateway logs over a slow uplink.
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+// Shorter values (ports, `true`) would shred the logs without protecting anything.
+const MIN_SECRET_LEN: usize = 8;
+
+/// The saved destination with the password and auth header masked.
+pub fn get_support_share() -> Result<SupportShareConfig> {
+    Ok(masked(load_unsealed()?))
+}
+
+/// Saves the upload destination. Only `https` is accepted, except for a destination on
+/// this machine. A masked password or auth header (as returned by
+/// `get_support_share`) keeps the saved one.
+pub fn configure_support_share(cfg: &SupportShareConfig) -> Result<SupportShareConfig> {
+    let mut cfg = cfg.clone();
+    let current = load_unsealed()?;
+    if cfg.password.starts_with(config::MASKED_KEY_PREFIX) {
+        cfg.password = current.password;
+    }
+    if cfg.auth_header.starts_with(config::MASKED_KEY_PREFIX) {
+        cfg.auth_header = current.auth_header;
+    }
+    cfg.url = cfg.url.trim().trim_end_matches('/').to_string();
+    cfg.public_url = cfg.public_url.trim().trim_end_matches('/').to_string();
+    if cfg.enabled || !cfg.url.is_empty() {
+        validate_destination(&cfg.url)?;
+    }
+    if !cfg.public_url.is_empty() {
+        validate_destination(&cfg.public_url)?;
+    }
+    state_store::save_support_share(&sealed(&cfg)?)?;
+    logger::info(&format!(
+        "Diagnostic sharing {} ({:?}).",
+        if cfg.enabled { "enabled" } else { "disabled" },
+        cfg.kind
+    ));
+    Ok(masked(cfg))
+}
+
+/// The stored config with its credentials opened, for uploading.
+fn load_unsealed() -> Result<SupportShareConfig> {
+    let mut cfg = state_store::load_support_share()?;
+    if cfg.sealed {
+        for value in [&mut cfg.password, &mut cfg.auth_header] {
+            if !value.is_empty() {
+                *value = secrets::open_value(value)?;
+            }
+        }
+        cfg.sealed = false;
+    }
+    Ok(cfg)
+}
+
+/// Seals the credentials where DPAPI is available; elsewhere they stay as entered.
+fn sealed(cfg: &SupportShareConfig) -> Result<SupportShareConfig> {
+    let plain = SupportShareConfig {
+        sealed: false,
+        ..cfg.clone()
+    };
+    let mut out = plain.clone();
+    for value in [&mut out.password, &mut out.auth_header] {
+        if value.is_empty() {
+            continue;
+        }
+        match secrets::seal_value(value) {
+            Some(blob) => *value = blob?,
+            None => return Ok(plain),
+        }
+        out.sealed = true;
+    }
+    Ok(out)
+}
+
+fn masked(mut cfg: SupportShareConfig) -> SupportShareConfig {
+    for value in [&mut cfg.password, &mut cfg.auth_header] {
+        if !value.is_empty() {
+            *value = config::mask_key(value);
+        }
+    }
+    cfg
+}
+
+/// Builds a support bundle with every known secret replaced, uploads it to the
+/// user-configured destination and returns the link to hand to a helper. The bundle
+/// is never kept on disk.
+pub async fn share_support_bundle(correlation_id: Option<&str>) -> Result<SupportShareResult> {
+    let cfg = load_unsealed()?;
+    if !cfg.enabled || cfg.url.is_empty() {
+        return Err(anyhow!(
+            "Diagnostic sharing is off. Set your own upload destination in Settings first."
+        ));
+    }
+    validate_destination(&cfg.url)?;
+
+    let share_id = uuid::Uuid::new_v4().simple().to_string()[..12].to_string();
+    let file_name = format!(
+        "openclaw-support-{}-{share_id}.zip",
+        Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let staged = temp_files::create_file(temp_files::SHARE_PREFIX, "zip");
+    let result = upload(&cfg, &staged, &file_name, correlation_id).await;
+    temp_files::release(&staged);
+    let (link, size_bytes, redactions) = result?;
+    logger::info(&format!(
+        "Support bundle {share_id} shared ({size_bytes} bytes, {redactions} values redacted)."
+    ));
+    Ok(SupportShareResult {
+        share_id,
+        link,
+        size_bytes,
+        redactions,
+    })
+}
+
+async fn upload(
+    cfg: &SupportShareConfig,
+    staged: &Path,
+    file_name: &str,
+    correlation_id: Option<&str>,
+) -> Result<(String, u64, usize)> {
+    let secrets = known_secrets(cfg);
+    let redactions =
+        support_bundle::export_redacted_support_bundle(staged, correlation_id, &secrets)?;
+    let data = fs::read(staged)?;
+    let size_bytes = data.len() as u64;
+
+    let proxy = state_store::load_last_config()
+        .ok()
+        .flatten()
+        .and_then(|last| last.proxy);
+    let mut builder = Client::builder().timeout(UPLOAD_TIMEOUT);
+    if let Some(proxy) = config::optional_non_empty(proxy) {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    let client = builder.build()?;
+    let target = format!("{}/{file_name}", cfg.url);
+    let mut request = client
+        .put(&target)
+        .header("Content-Type", "application/zip")
+        .body(data);
+    match cfg.kind {
+        SupportShareKind::WebDav => {
+            request = request.basic_auth(&cfg.username, Some(&cfg.password));
+        }
+        SupportShareKind::Http if !cfg.auth_header.trim().is_empty() => {
+            request = request.header("Authorization", cfg.auth_header.trim());
+        }
+        SupportShareKind::Http => {}
+    }
+    let resp = request.send().await.map_err(|err| {
+        anyhow!(
+            "Upload failed: {}",
+            config::redact_known_values(err.to_string(), &[&cfg.password, &cfg.auth_header])
+        )
+    })?;
+    let status = resp.status();
+    let body = resp.text().await.unwrap_or_default();
+    if !status.is_success() {
+        return Err(anyhow!(
+            "Upload destination answered HTTP {}: {}",
+            status.as_u16(),
+            body.trim().chars().take(200).collect::<String>()
+        ));
+    }
+    Ok((share_link(cfg, file_name, &body), size_bytes, redactions))
+}
+
+/// The link to share: what an `http` endpoint answered when that is a URL (transfer.sh
+/// replies with the download link), otherwise the file under `public_url` or `url`.
+fn share_link(cfg: &SupportShareConfig, file_name: &str, response: &str) -> String {
+    let answered = response.trim();
+    if cfg.kind == SupportShareKind::Http
+        && (answered.starts_with("https://") || answered.starts_with("http://"))
+        && !answered.contains(char::is_whitespace)
+    {
+        return answered.to_string();
+    }
+    let base = if cfg.public_url.is_empty() {
+        &cfg.url
+    } else {
+        &cfg.public_url
+    };
+    format!("{base}/{file_name}")
+}
+
+fn validate_destination(raw: &str) -> Result<()> {
+    let url = Url::parse(raw).map_err(|err| anyhow!("Invalid upload URL {raw}: {err}"))?;
+    let local = matches!(
+        url.host_str(),
+        Some("localhost") | Some("127.0.0.1") | Some("[::1]")
+    );
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" if local => Ok(()),
+        _ => Err(anyhow!(
+            "Upload URL must use https so diagnostics are not sent in clear text: {raw}"
+        )),
+    }
+}
+
+/// Every secret value the logs could contain: provider keys and channel secrets, the
+/// gateway token, tunnel tokens, credentials in `.env` and the share credentials.
+fn known_secrets(share: &SupportShareConfig) -> Vec<String> {
+    let mut values = vec![share.password.clone(), share.auth_header.clone()];
+    if let Ok(Some(last)) = state_store::load_last_config() {
+        values.extend([
+            last.api_key.clone(),
+            last.telegram_bot_token.clone(),
+            last.feishu_app_secret.clone(),
+            last.dingtalk_app_secret.clone(),
+            last.discord_bot_token.clone(),
+            last.aws_secret_access_key.clone(),
+        ]);
+        values.extend(last.provider_api_keys.values().cloned());
+        values.extend(last.proxy.clone().filter(|proxy| proxy.contains('@')));
+    }
+    if let Ok(cfg) = config::read_current_config() {
+        values.extend(
+            process::runtime_env(&cfg)
+                .into_iter()
+                .filter(|(name, value)| is_secret_env(name, value))
+                .map(|(_, value)| value),
+        );
+    }
+    if let Ok(env) = EnvFile::load(&paths::openclaw_home().join(".env")) {
+        for name in env.keys() {
+            if let Some(value) = env.get(name).filter(|value| is_secret_env(name, value)) {
+                values.push(value.to_string());
+            }
+        }
+    }
+    if let Ok(Some(token)) = browser::read_gateway_token_from_config() {
+        values.push(token);
+    }
+    if let Ok(tunnel) = state_store::load_tunnel_config() {
+        values.extend([tunnel.cloudflared_token, tunnel.frp_auth_token]);
+    }
+    let mut values = values
+        .into_iter()
+        .map(|v| v.trim().to_string())
+        .filter(|v| v.len() >= MIN_SECRET_LEN)
+        .collect::<Vec<_>>();
+    // Longest first, so a value containing another is replaced whole.
+    values.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+    values.dedup();
+    values
+}
+
+/// Proxy variables only hold a secret when they carry `user:password@`; a plain proxy
+/// address is useful to whoever reads the bundle.
+fn is_secret_env(name: &str, value: &str) -> bool {
+    env_diff::is_secret_name(name)
+        && (!name.to_ascii_uppercase().ends_with("PROXY") || value.contains('@'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{masked, sealed, share_link, validate_destination};
+    use crate::models::{SupportShareConfig, SupportShareKind};
+
+    #[test]
+    fn links_come_from_the_endpoint_or_the_public_url() {
+        let mut cfg = SupportShareConfig {
+            enabled: true,
+            url: "https://transfer.example.org".to_string(),
+            ..SupportShareConfig::default()
+        };
+        assert_eq!(
+            share_link(&cfg, "b.zip", "https://transfer.example.org/x1/b.zip\n"),
+            "https://transfer.example.org/x1/b.zip"
+        );
+        assert_eq!(
+            share_link(&cfg, "b.zip", "Created"),
+            "https://transfer.example.org/b.zip"
+        );
+        cfg.kind = SupportShareKind::WebDav;
+        cfg.public_url = "https://cloud.example.org/s/abc".to_string();
+        assert_eq!(
+            share_link(&cfg, "b.zip", "https://ignored"),
+            "https://cloud.example.org/s/abc/b.zip"
+        );
+
+        assert!(validate_destination("https://dav.example.org/remote.php").is_ok());
+        assert!(validate_destination("http://127.0.0.1:8080/up").is_ok());
+        assert!(validate_destination("http://dav.example.org").is_err());
+        assert!(validate_destination("not a url").is_err());
+    }
+
+    #[test]
+    fn credentials_are_masked_and_never_stored_as_entered_when_sealing_works() {
+        let cfg = SupportShareConfig {
+            password: "hunter2-webdav".to_string(),
+            auth_header: "Bearer abcdefghijkl".to_string(),
+            sealed: true,
+            ..Default::default()
+        };
+        let shown = masked(cfg.clone());
+        assert!(shown.password.ends_with("bdav") && !shown.password.contains("hunter2"));
+        assert!(!shown.auth_header.contains("abcdefgh"));
+
+        let stored = sealed(&cfg).expect("seal");
+        if stored.sealed {
+            assert_ne!(stored.password, cfg.password);
+            assert_ne!(stored.auth_header, cfg.auth_header);
+        } else {
+            // No DPAPI on this platform: kept as entered, and not flagged as sealed.
+            assert_eq!(stored.password, cfg.password);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::TempSweepResult;
+
+use super::{fs_walk, logger, paths};
+
+pub const RESTORE_PREFIX: &str = "openclaw-restore-";
+pub const DOWNLOAD_PREFIX: &str = "openclaw-download-";
+pub const SHARE_PREFIX: &str = "openclaw-share-";
+
+const STALE_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+const SWEEP_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+static REGISTRY_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TempEntry {
+    path: String,
+    created_at: String,
+}
+
+/// Creates a uniquely named directory under the system temp dir and records it, so a
+/// crash or early return between create and cleanup is caught by the next sweep.
+pub fn create_dir(prefix: &str) -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!("{prefix}{}", Uuid::new_v4()));
+    fs::create_dir_all(&dir)?;
+    register(&dir);
+    Ok(dir)
+}
+
+/// Reserves a registered temp file path; the caller creates the file.
+pub fn create_file(prefix: &str, extension: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("{prefix}{}.{extension}", Uuid::new_v4()));
+    register(&path);
+    path
+}
+
+/// Deletes a path from `create_dir`/`create_file` and drops it from the registry.
+pub fn release(path: &Path) {
+    let _ = remove_path(path);
+    update_registry(|entries| entries.retain(|e| Path::new(&e.path) != path));
+}
+
+/// Removes registered and prefix-matched temp paths older than a day. Younger paths
+/// may belong to an operation that is still running and are left alone.
+pub fn sweep_stale() -> TempSweepResult {
+    let mut result = TempSweepResult::default();
+    let mut candidates = Vec::<PathBuf>::new();
+    update_registry(|entries| {
+        entries.retain(|entry| {
+            let path = PathBuf::from(&entry.path);
+            if !path.exists() {
+                return false;
+            }
+            if is_stale(&path) {
+                candidates.push(path);
+                return false;
+            }
+            true
+        });
+    });
+    if let Ok(read) = fs::read_dir(std::env::temp_dir()) {
+        for entry in read.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            if is_installer_temp(&name) && is_stale(&path) && !candidates.contains(&path) {
+                candidates.push(path);
+            }
+        }
+    }
+    for path in candidates {
+        let size = fs_walk::total_size(&path);
+        match remove_path(&path) {
+            Ok(()) => {
+                result.freed_bytes += size;
+                result.removed.push(path.to_string_lossy().to_string());
+            }
+            Err(err) => result
+                .failed
+                .push(format!("{}: {err}", path.to_string_lossy())),
+        }
+    }
+    if !result.removed.is_empty() || !result.failed.is_empty() {
+        logger::info(&format!(
+            "Temp sweep removed {} path(s), freed {} bytes, {} failed.",
+            result.removed.len(),
+            result.freed_bytes,
+            result.failed.len()
+        ));
+    }
+    result
+}
+
+/// Sweeps once at startup, then every few hours for long-running tray sessions.
+pub fn start_background_sweeper() {
+    thread::spawn(|| loop {
+        sweep_stale();
+        thread::sleep(SWEEP_INTERVAL);
+    });
+}
+
+fn is_installer_temp(name: &str) -> bool {
+    name.starts_with(RESTORE_PREFIX)
+        || name.starts_with(DOWNLOAD_PREFIX)
+        || name.starts_with(SHARE_PREFIX)
+}
+
+fn is_stale(path: &Path) -> bool {
+    fs::symlink_metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age >= STALE_AFTER)
+        .unwrap_or(false)
+}
+
+fn remove_path(path: &Path) -> std::io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+fn register(path: &Path) {
+    let entry = TempEntry {
+        path: path.to_string_lossy().to_string(),
+        created_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+    };
+    update_registry(|entries| entries.push(entry));
+}
+
+fn update_registry(edit: impl FnOnce(&mut Vec<TempEntry>)) {
+    let lock = REGISTRY_LOCK.get_or_init(|| Mutex::new(()));
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+    let path = registry_path();
+    let mut entries = fs::read_to_string(&path)
+        .ok()
+        .and_then(|raw| serde_json::from_str::<Vec<TempEntry>>(&raw).ok())
+        .unwrap_or_default();
+    edit(&mut entries);
+    let write = paths::ensure_dirs().and_then(|_| {
+        fs::write(&path, serde_json::to_string_pretty(&entries)?)?;
+        Ok(())
+    });
+    if let Err(err) = write {
+        logger::warn(&format!("Failed to update temp registry: {err}"));
+    }
+}
+
+fn registry_path() -> PathBuf {
+    paths::state_dir().join("temp_registry.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_installer_temp, is_stale, remove_path};
+
+    #[test]
+    fn only_installer_prefixes_are_swept_and_fresh_paths_are_kept() {
+        assert!(is_installer_temp("openclaw-restore-1f2e"));
+        assert!(is_installer_temp("openclaw-download-1f2e.exe"));
+        assert!(!is_installer_temp("openclaw installer tests"));
+
+        let dir = std::env::temp_dir().join(format!("oc-temp-sweep-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested")).expect("dir");
+        assert!(!is_stale(&dir));
+        remove_path(&dir).expect("remove");
+        assert!(!dir.exists());
+        remove_path(&dir).expect("missing path is fine");
+    }
+}
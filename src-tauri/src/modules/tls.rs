@@ -0,0 +1,435 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::models::{ResultWarning, TlsStatus};
+
+use super::{config, gateway_port, logger, paths, process, shell, state_store};
+
+const CERT_FILE: &str = "gateway-cert.pem";
+const KEY_FILE: &str = "gateway-key.pem";
+const CERT_DAYS: &str = "825";
+const PFX_PASSWORD_ENV: &str = "OPENCLAW_PFX_PASSWORD";
+
+/// Whether the gateway serves https, from `gateway.tls.enabled` in openclaw.json.
+pub fn gateway_scheme() -> &'static str {
+    if tls_enabled_in(&read_config_json()) {
+        "https"
+    } else {
+        "http"
+    }
+}
+
+/// Client for probing this machine's gateway. With TLS on, the certificate is usually
+/// self-signed (or issued for a LAN name), so it is not validated here.
+pub fn gateway_client(timeout: Duration) -> Result<Client> {
+    Ok(Client::builder()
+        .timeout(timeout)
+        .danger_accept_invalid_certs(gateway_scheme() == "https")
+        .build()?)
+}
+
+pub fn tls_status() -> TlsStatus {
+    let json = read_config_json();
+    let cert_path = json
+        .pointer("/gateway/tls/certPath")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let key_path = json
+        .pointer("/gateway/tls/keyPath")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let (subject, expires_at) = cert_path
+        .as_deref()
+        .and_then(|path| describe_cert(Path::new(path)).ok())
+        .unwrap_or_default();
+    TlsStatus {
+        enabled: tls_enabled_in(&json),
+        cert_path,
+        key_path,
+        subject,
+        expires_at,
+        warnings: Vec::new(),
+    }
+}
+
+/// Creates a self-signed certificate for localhost, 127.0.0.1 and the current LAN address,
+/// points `gateway.tls` at it and restarts the gateway, so a LAN-exposed gateway stops
+/// sending its auth token in cleartext. Browsers will ask once to trust the certificate.
+pub fn generate_self_signed() -> Result<TlsStatus> {
+    let openssl = resolve_openssl()?;
+    let mut san = "DNS:localhost,IP:127.0.0.1".to_string();
+    if let Some(ip) = gateway_port::primary_lan_ip() {
+        san.push_str(&format!(",IP:{ip}"));
+    }
+    let host = std::env::var("COMPUTERNAME").unwrap_or_default();
+    if !host.trim().is_empty() {
+        san.push_str(&format!(",DNS:{}", host.trim().to_ascii_lowercase()));
+    }
+    let (cert, key, warnings) =
+        install_pair(|cert, key| write_self_signed(&openssl, &san, cert, key))?;
+    logger::info(&format!("Self-signed gateway certificate created ({san})."));
+    enable(&cert, &key, warnings)
+}
+
+/// Converts a user-supplied PFX/P12 (e.g. from a company CA) to the PEM pair the gateway
+/// reads and enables TLS with it. The password reaches OpenSSL through the environment,
+/// never the command line.
+pub fn import_pfx(pfx_path: &str, password: &str) -> Result<TlsStatus> {
+    let pfx = paths::normalize_path(pfx_path)?;
+    if !pfx.is_file() {
+        return Err(anyhow!(
+            "Certificate file not found: {}",
+            pfx.to_string_lossy()
+        ));
+    }
+    let openssl = resolve_openssl()?;
+    let (cert, key, warnings) =
+        install_pair(|cert, key| convert_pfx(&openssl, &pfx, password, cert, key))?;
+    logger::info(&format!(
+        "Gateway certificate imported from {}",
+        pfx.to_string_lossy()
+    ));
+    enable(&cert, &key, warnings)
+}
+
+/// Switches the gateway back to plain http; the certificate files are kept.
+pub fn disable_tls() -> Result<TlsStatus> {
+    set_tls_key("gateway.tls.enabled", "false")?;
+    logger::info("Gateway TLS disabled.");
+    let mut status = tls_status();
+    if let Err(err) = process::restart() {
+        status.warnings.push(
+            ResultWarning::warning("gateway_restart_failed", err.to_string())
+                .with_remediation("Restart the gateway to go back to http."),
+        );
+    }
+    Ok(status)
+}
+
+/// Writes a new certificate/key pair next to the live one and swaps both in only after
+/// `write` succeeded, so a failed import never leaves a mismatched pair behind. The key
+/// file is created and locked down before OpenSSL writes the private key into it.
+fn install_pair(
+    write: impl FnOnce(&Path, &Path) -> Result<()>,
+) -> Result<(PathBuf, PathBuf, Vec<ResultWarning>)> {
+    let (cert, key) = tls_paths()?;
+    let staged_cert = cert.with_extension("pem.new");
+    let staged_key = key.with_extension("pem.new");
+    fs::write(&staged_key, "")?;
+    let warnings = config::set_windows_acl(&staged_key);
+    let written = write(&staged_cert, &staged_key)
+        .and_then(|_| swap_in_pair((&staged_cert, &staged_key), (&cert, &key)));
+    if let Err(err) = written {
+        let _ = fs::remove_file(&staged_cert);
+        let _ = fs::remove_file(&staged_key);
+        return Err(err);
+    }
+    Ok((cert, key, warnings))
+}
+
+/// Renames the staged pair over the live one. The previous key is parked until the
+/// certificate is in place and restored if that rename fails, so the key on disk always
+/// belongs to the certificate on disk.
+fn swap_in_pair(
+    (staged_cert, staged_key): (&Path, &Path),
+    (cert, key): (&Path, &Path),
+) -> Result<()> {
+    let parked_key = key.with_extension("pem.old");
+    let had_key = key.is_file();
+    if had_key {
+        fs::rename(key, &parked_key)?;
+    }
+    if let Err(err) = fs::rename(staged_key, key) {
+        if had_key {
+            let _ = fs::rename(&parked_key, key);
+        }
+        return Err(err.into());
+    }
+    if let Err(err) = fs::rename(staged_cert, cert) {
+        let _ = fs::remove_file(key);
+        if had_key {
+            let _ = fs::rename(&parked_key, key);
+        }
+        return Err(err.into());
+    }
+    if had_key {
+        let _ = fs::remove_file(&parked_key);
+    }
+    Ok(())
+}
+
+fn write_self_signed(openssl: &str, san: &str, cert: &Path, key: &Path) -> Result<()> {
+    let cert_text = cert.to_string_lossy().to_string();
+    let key_text = key.to_string_lossy().to_string();
+    let san_arg = format!("subjectAltName={san}");
+    let out = shell::run_command(
+        openssl,
+        &[
+            "req",
+            "-x509",
+            "-newkey",
+            "rsa:2048",
+            "-sha256",
+            "-nodes",
+            "-days",
+            CERT_DAYS,
+            "-subj",
+            "/CN=OpenClaw Gateway",
+            "-addext",
+            &san_arg,
+            "-keyout",
+            &key_text,
+            "-out",
+            &cert_text,
+        ],
+        None,
+        &[],
+    )?;
+    shell::ensure_success("openssl req -x509", &out)
+}
+
+fn convert_pfx(openssl: &str, pfx: &Path, password: &str, cert: &Path, key: &Path) -> Result<()> {
+    let pfx_text = pfx.to_string_lossy().to_string();
+    let envs = vec![(PFX_PASSWORD_ENV.to_string(), password.to_string())];
+    let passin = format!("env:{PFX_PASSWORD_ENV}");
+    for (part, target) in [("-nokeys", cert), ("-nocerts", key)] {
+        let target_text = target.to_string_lossy().to_string();
+        let mut args: Vec<&str> = vec![
+            "pkcs12",
+            "-in",
+            &pfx_text,
+            part,
+            "-nodes",
+            "-passin",
+            &passin,
+            "-out",
+            &target_text,
+        ];
+        let mut out = shell::run_command(openssl, &args, None, &envs)?;
+        if out.code != 0 && out.stderr.contains("unsupported") {
+            // PFX files exported by older Windows use RC2/3DES, which OpenSSL 3 only
+            // reads with the legacy provider.
+            args.push("-legacy");
+            out = shell::run_command(openssl, &args, None, &envs)?;
+        }
+        if out.code != 0 {
+            return Err(anyhow!(
+                "Could not read {} (wrong password?): {}",
+                pfx.to_string_lossy(),
+                config::redact_known_values(config::cli_output_text(&out), &[password])
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn enable(cert: &Path, key: &Path, mut warnings: Vec<ResultWarning>) -> Result<TlsStatus> {
+    set_tls_key("gateway.tls.certPath", &cert.to_string_lossy())?;
+    set_tls_key("gateway.tls.keyPath", &key.to_string_lossy())?;
+    set_tls_key("gateway.tls.enabled", "true")?;
+    if let Err(err) = process::restart() {
+        warnings.push(
+            ResultWarning::warning("gateway_restart_failed", err.to_string())
+                .with_remediation("Restart the gateway to start serving https."),
+        );
+    }
+    let mut status = tls_status();
+    status.warnings = warnings;
+    Ok(status)
+}
+
+fn set_tls_key(key: &str, value: &str) -> Result<()> {
+    let proxy = state_store::load_last_config()?.and_then(|cfg| cfg.proxy);
+    let out = config::run_openclaw_cli(
+        &[
+            "config".to_string(),
+            "set".to_string(),
+            key.to_string(),
+            value.to_string(),
+        ],
+        proxy,
+    )?;
+    shell::ensure_success(&format!("openclaw config set {key}"), &out)
+}
+
+fn tls_paths() -> Result<(PathBuf, PathBuf)> {
+    let dir = paths::openclaw_home().join("tls");
+    fs::create_dir_all(&dir)?;
+    Ok((dir.join(CERT_FILE), dir.join(KEY_FILE)))
+}
+
+/// OpenSSL on PATH, else the copy bundled with Git for Windows.
+fn resolve_openssl() -> Result<String> {
+    if let Some(found) = shell::command_exists("openssl") {
+        return Ok(found);
+    }
+    let git_root = shell::command_exists("git")
+        .map(PathBuf::from)
+        // `<root>\cmd\git.exe` or `<root>\bin\git.exe`.
+        .and_then(|git| git.parent()?.parent().map(Path::to_path_buf));
+    git_root
+        .into_iter()
+        .flat_map(|root| {
+            [
+                root.join("usr").join("bin").join("openssl.exe"),
+                root.join("mingw64").join("bin").join("openssl.exe"),
+            ]
+        })
+        .find(|candidate| candidate.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .ok_or_else(|| {
+            anyhow!(
+                "OpenSSL was not found. Install Git for Windows (it ships openssl.exe) and retry."
+            )
+        })
+}
+
+/// `(subject, notAfter as RFC 3339)` of a PEM certificate.
+fn describe_cert(cert: &Path) -> Result<(Option<String>, Option<String>)> {
+    let openssl = resolve_openssl()?;
+    let cert_text = cert.to_string_lossy().to_string();
+    let out = shell::run_command(
+        openssl.as_str(),
+        &["x509", "-in", &cert_text, "-noout", "-subject", "-enddate"],
+        None,
+        &[],
+    )?;
+    shell::ensure_success("openssl x509", &out)?;
+    Ok(parse_x509_text(&out.stdout))
+}
+
+fn parse_x509_text(output: &str) -> (Option<String>, Option<String>) {
+    let mut subject = None;
+    let mut expires_at = None;
+    for line in output.lines() {
+        if let Some(value) = line.trim().strip_prefix("subject=") {
+            subject = Some(value.trim().to_string());
+        } else if let Some(value) = line.trim().strip_prefix("notAfter=") {
+            // `Jan 21 10:00:00 2029 GMT`; the day is space-padded.
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            expires_at = NaiveDateTime::parse_from_str(&value, "%b %d %H:%M:%S %Y GMT")
+                .ok()
+                .map(|at| Utc.from_utc_datetime(&at).to_rfc3339());
+        }
+    }
+    (subject, expires_at)
+}
+
+fn read_config_json() -> Value {
+    fs::read_to_string(paths::config_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or(Value::Null)
+}
+
+fn tls_enabled_in(json: &Value) -> bool {
+    json.pointer("/gateway/tls/enabled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{convert_pfx, parse_x509_text, swap_in_pair, tls_enabled_in, write_self_signed};
+    use crate::modules::shell;
+
+    #[test]
+    fn x509_text_and_tls_flag_are_read() {
+        let out = "subject=CN = OpenClaw Gateway\nnotAfter=Jan  5 10:00:00 2029 GMT\n";
+        let (subject, expires_at) = parse_x509_text(out);
+        assert_eq!(subject.as_deref(), Some("CN = OpenClaw Gateway"));
+        assert_eq!(expires_at.as_deref(), Some("2029-01-05T10:00:00+00:00"));
+
+        assert!(tls_enabled_in(
+            &serde_json::json!({ "gateway": { "tls": { "enabled": true } } })
+        ));
+        assert!(!tls_enabled_in(
+            &serde_json::json!({ "gateway": { "port": 28789 } })
+        ));
+    }
+
+    #[test]
+    fn failed_cert_swap_restores_the_previous_key() {
+        let dir = std::env::temp_dir().join(format!("oc-tls-swap-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("create temp test dir");
+        let (cert, key) = (dir.join("cert.pem"), dir.join("key.pem"));
+        let (staged_cert, staged_key) = (dir.join("cert.pem.new"), dir.join("key.pem.new"));
+        fs::write(&key, "old key").expect("write key");
+        fs::write(&staged_cert, "new cert").expect("write staged cert");
+        fs::write(&staged_key, "new key").expect("write staged key");
+        // A directory in the certificate's place makes its rename fail.
+        fs::create_dir_all(&cert).expect("block cert path");
+
+        assert!(swap_in_pair((&staged_cert, &staged_key), (&cert, &key)).is_err());
+        assert_eq!(fs::read_to_string(&key).expect("read key"), "old key");
+        assert!(!dir.join("key.pem.old").exists());
+
+        fs::remove_dir(&cert).expect("unblock cert path");
+        fs::write(&staged_key, "new key").expect("write staged key");
+        swap_in_pair((&staged_cert, &staged_key), (&cert, &key)).expect("swap pair");
+        assert_eq!(fs::read_to_string(&key).expect("read key"), "new key");
+        assert_eq!(fs::read_to_string(&cert).expect("read cert"), "new cert");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    #[ignore = "needs openssl on PATH"]
+    fn self_signed_pair_round_trips_through_pfx() {
+        let openssl = shell::command_exists("openssl").expect("openssl on PATH");
+        let dir = std::env::temp_dir().join(format!("openclaw-tls-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("create temp test dir");
+        let (cert, key) = (dir.join("cert.pem"), dir.join("key.pem"));
+        write_self_signed(&openssl, "DNS:localhost,IP:127.0.0.1", &cert, &key)
+            .expect("generate self-signed pair");
+        assert!(fs::read_to_string(&key)
+            .expect("read key")
+            .contains("PRIVATE KEY"));
+
+        let pfx = dir.join("bundle.pfx");
+        let (cert_text, key_text, pfx_text) = (
+            cert.to_string_lossy().to_string(),
+            key.to_string_lossy().to_string(),
+            pfx.to_string_lossy().to_string(),
+        );
+        let out = shell::run_command(
+            openssl.as_str(),
+            &[
+                "pkcs12",
+                "-export",
+                "-in",
+                &cert_text,
+                "-inkey",
+                &key_text,
+                "-out",
+                &pfx_text,
+                "-passout",
+                "pass:s3cret",
+            ],
+            None,
+            &[],
+        )
+        .expect("export pfx");
+        assert_eq!(out.code, 0, "{}", out.stderr);
+
+        let (imported_cert, imported_key) = (dir.join("in-cert.pem"), dir.join("in-key.pem"));
+        assert!(convert_pfx(&openssl, &pfx, "wrong", &imported_cert, &imported_key).is_err());
+        convert_pfx(&openssl, &pfx, "s3cret", &imported_cert, &imported_key).expect("import pfx");
+        assert!(fs::read_to_string(&imported_cert)
+            .expect("read cert")
+            .contains("BEGIN CERTIFICATE"));
+        assert!(fs::read_to_string(&imported_key)
+            .expect("read key")
+            .contains("PRIVATE KEY"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,182 @@
+use std::fs;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+use crate::models::{ToolPermissions, ToolPermissionsResult};
+
+use super::{config, logger, paths, state_store};
+
+const EXEC_SECURITY_MODES: &[&str] = &["deny", "allowlist", "full"];
+const EXEC_ASK_MODES: &[&str] = &["off", "on-miss", "always"];
+const SANDBOX_MODES: &[&str] = &["off", "non-main", "all"];
+
+pub fn get_tool_permissions() -> Result<ToolPermissions> {
+    let path = paths::config_path();
+    if !path.exists() {
+        return Ok(ToolPermissions::default());
+    }
+    let json: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    Ok(parse_tool_permissions(&json))
+}
+
+pub fn set_tool_permissions(permissions: &ToolPermissions) -> Result<ToolPermissionsResult> {
+    validate_permissions(permissions)?;
+    let proxy = state_store::load_last_config()?.and_then(|cfg| cfg.proxy);
+    let mut warnings = Vec::<String>::new();
+
+    let mut denied = permissions
+        .denied_tools
+        .iter()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>();
+    denied.sort();
+    denied.dedup();
+
+    let writes = vec![
+        ("tools.exec.security", permissions.exec_security.clone()),
+        ("tools.exec.ask", permissions.exec_ask.clone()),
+        (
+            "tools.elevated.enabled",
+            permissions.elevated_enabled.to_string(),
+        ),
+        (
+            "tools.fs.workspaceOnly",
+            permissions.fs_workspace_only.to_string(),
+        ),
+        ("browser.enabled", permissions.browser_enabled.to_string()),
+        (
+            "agents.defaults.sandbox.mode",
+            permissions.sandbox_mode.clone(),
+        ),
+        ("tools.deny", serde_json::to_string(&denied)?),
+    ];
+    for (path, value) in writes {
+        let out = config::run_openclaw_cli(
+            &[
+                "config".to_string(),
+                "set".to_string(),
+                path.to_string(),
+                value,
+            ],
+            proxy.clone(),
+        )?;
+        if out.code != 0 {
+            warnings.push(format!(
+                "Tool permission write failed ({path}): {}",
+                config::cli_output_text(&out)
+            ));
+        }
+    }
+
+    let restart_out =
+        config::run_openclaw_cli(&["gateway".to_string(), "restart".to_string()], proxy)?;
+    if restart_out.code != 0 {
+        warnings.push(format!(
+            "Gateway restart failed after tool permission change: {}",
+            config::cli_output_text(&restart_out)
+        ));
+    }
+
+    logger::info(&format!(
+        "Tool permissions updated: exec={}, ask={}, sandbox={}, browser={}",
+        permissions.exec_security,
+        permissions.exec_ask,
+        permissions.sandbox_mode,
+        permissions.browser_enabled
+    ));
+    Ok(ToolPermissionsResult {
+        permissions: get_tool_permissions()?,
+        warnings,
+    })
+}
+
+fn validate_permissions(permissions: &ToolPermissions) -> Result<()> {
+    for (label, value, allowed) in [
+        (
+            "exec security",
+            &permissions.exec_security,
+            EXEC_SECURITY_MODES,
+        ),
+        ("exec approval", &permissions.exec_ask, EXEC_ASK_MODES),
+        ("sandbox mode", &permissions.sandbox_mode, SANDBOX_MODES),
+    ] {
+        if !allowed.contains(&value.as_str()) {
+            return Err(anyhow!(
+                "Invalid {label} '{value}'. Expected one of: {}",
+                allowed.join(", ")
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn parse_tool_permissions(json: &Value) -> ToolPermissions {
+    let defaults = ToolPermissions::default();
+    let str_at = |pointer: &str, fallback: &str| {
+        json.pointer(pointer)
+            .and_then(|v| v.as_str())
+            .unwrap_or(fallback)
+            .to_string()
+    };
+    let bool_at = |pointer: &str, fallback: bool| {
+        json.pointer(pointer)
+            .and_then(|v| v.as_bool())
+            .unwrap_or(fallback)
+    };
+    ToolPermissions {
+        exec_security: str_at("/tools/exec/security", &defaults.exec_security),
+        exec_ask: str_at("/tools/exec/ask", &defaults.exec_ask),
+        elevated_enabled: bool_at("/tools/elevated/enabled", defaults.elevated_enabled),
+        fs_workspace_only: bool_at("/tools/fs/workspaceOnly", defaults.fs_workspace_only),
+        browser_enabled: bool_at("/browser/enabled", defaults.browser_enabled),
+        sandbox_mode: str_at("/agents/defaults/sandbox/mode", &defaults.sandbox_mode),
+        denied_tools: json
+            .pointer("/tools/deny")
+            .and_then(|v| v.as_array())
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_tool_permissions, validate_permissions};
+    use crate::models::ToolPermissions;
+    use serde_json::json;
+
+    #[test]
+    fn parse_tool_permissions_reads_config_and_defaults() {
+        let json = json!({
+            "tools": {
+                "exec": { "security": "allowlist" },
+                "fs": { "workspaceOnly": true },
+                "deny": ["browser", "exec"]
+            },
+            "browser": { "enabled": false }
+        });
+        let parsed = parse_tool_permissions(&json);
+        assert_eq!(parsed.exec_security, "allowlist");
+        assert_eq!(parsed.exec_ask, "on-miss");
+        assert!(parsed.fs_workspace_only);
+        assert!(!parsed.browser_enabled);
+        assert_eq!(parsed.sandbox_mode, "off");
+        assert_eq!(parsed.denied_tools, vec!["browser", "exec"]);
+    }
+
+    #[test]
+    fn validate_permissions_rejects_unknown_modes() {
+        assert!(validate_permissions(&ToolPermissions::default()).is_ok());
+        let invalid = ToolPermissions {
+            sandbox_mode: "strict".to_string(),
+            ..ToolPermissions::default()
+        };
+        assert!(validate_permissions(&invalid).is_err());
+    }
+}
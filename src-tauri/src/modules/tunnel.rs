@@ -0,0 +1,504 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use reqwest::Client;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use url::Url;
+use zip::ZipArchive;
+
+use crate::models::{TunnelConfigInput, TunnelStatus};
+
+use super::{browser, config, fs_walk, logger, paths, shell, state_store, temp_files, tls};
+
+#[cfg(windows)]
+use std::os::windows::process::CommandExt;
+
+#[cfg(windows)]
+const DETACHED_PROCESS: u32 = 0x00000008;
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+const PROVIDER_CLOUDFLARED: &str = "cloudflared";
+const PROVIDER_FRPC: &str = "frpc";
+// Both clients are pinned by version and by SHA-256. The digests live here rather than
+// being fetched next to the download, so a compromised release origin cannot vouch for
+// its own binary. Bump a digest together with its version; an empty or malformed pin
+// refuses the download instead of skipping the check.
+const CLOUDFLARED_VERSION: &str = "2024.12.2";
+const CLOUDFLARED_ASSET: &str = "cloudflared-windows-amd64.exe";
+/// SHA-256 of `CLOUDFLARED_ASSET` from the `CLOUDFLARED_VERSION` release.
+const CLOUDFLARED_SHA256: &str = "";
+// Pin frp so the generated TOML config keeps matching the client's schema.
+const FRP_VERSION: &str = "0.61.1";
+/// SHA-256 of `frp_archive_name()` from the `FRP_VERSION` release.
+const FRP_ARCHIVE_SHA256: &str = "";
+// frpc expands this from its environment, so the token never sits in frpc.toml.
+const FRP_TOKEN_ENV: &str = "OPENCLAW_FRP_AUTH_TOKEN";
+
+pub async fn configure_tunnel(input: &TunnelConfigInput) -> Result<TunnelStatus> {
+    let cfg = normalize_tunnel_config(input)?;
+    // Settings change: restart with the new provider/credentials on next start.
+    stop_tunnel()?;
+    state_store::save_tunnel_config(&cfg)?;
+    if cfg.enabled {
+        ensure_binary(&cfg).await?;
+    }
+    logger::info(&format!(
+        "Tunnel settings saved: provider={}, enabled={}",
+        cfg.provider, cfg.enabled
+    ));
+    Ok(tunnel_status())
+}
+
+pub fn start_tunnel() -> Result<TunnelStatus> {
+    let cfg = state_store::load_tunnel_config()?;
+    if !cfg.enabled {
+        return Err(anyhow!("Tunnel is disabled. Configure it first."));
+    }
+    if gateway_auth_disabled()? {
+        return Err(anyhow!(
+            "Gateway authentication is off. Set a gateway token before exposing the dashboard through a tunnel."
+        ));
+    }
+    if let Some(pid) = running_tunnel_pid() {
+        logger::info(&format!("Tunnel already running (PID {pid})."));
+        return Ok(tunnel_status());
+    }
+    let binary = binary_path(&cfg.provider);
+    if !binary.exists() {
+        return Err(anyhow!(
+            "{} is not downloaded yet. Save tunnel settings again to download it.",
+            cfg.provider
+        ));
+    }
+
+    let port = config::read_current_config()
+        .map(|c| c.port)
+        .unwrap_or(28789);
+    let mut cmd = Command::new(&binary);
+    match cfg.provider.as_str() {
+        PROVIDER_FRPC => {
+            let config_file = write_frpc_config(&cfg, port)?;
+            cmd.arg("-c").arg(config_file);
+            cmd.env(FRP_TOKEN_ENV, cfg.frp_auth_token.trim());
+        }
+        _ => {
+            cmd.arg("tunnel").arg("--no-autoupdate");
+            if cfg.cloudflared_token.trim().is_empty() {
+                let scheme = tls::gateway_scheme();
+                cmd.arg("--url").arg(format!("{scheme}://127.0.0.1:{port}"));
+                if scheme == "https" {
+                    // The gateway certificate is self-signed for localhost/LAN names.
+                    cmd.arg("--no-tls-verify");
+                }
+            } else {
+                // Pass the token via env so it does not show up in process listings.
+                cmd.arg("run");
+                cmd.env("TUNNEL_TOKEN", cfg.cloudflared_token.trim());
+            }
+        }
+    }
+
+    let log = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(tunnel_log_path())?;
+    cmd.stdout(Stdio::from(log.try_clone()?));
+    cmd.stderr(Stdio::from(log));
+    #[cfg(windows)]
+    {
+        cmd.creation_flags(DETACHED_PROCESS | CREATE_NO_WINDOW);
+    }
+    let child = cmd.spawn()?;
+    let pid = child.id();
+    fs::write(tunnel_pid_file(), pid.to_string())?;
+    logger::info(&format!(
+        "Tunnel started: provider={}, PID {pid}.",
+        cfg.provider
+    ));
+    Ok(tunnel_status())
+}
+
+pub fn stop_tunnel() -> Result<TunnelStatus> {
+    if let Some(pid) = read_tunnel_pid() {
+        if shell::is_process_alive(pid) {
+            // The PID file can outlive the client; never kill whatever reused the PID.
+            let name = shell::process_name_by_pid(pid).unwrap_or_default();
+            if !is_tunnel_client_name(&name) {
+                logger::warn(&format!(
+                    "Tunnel PID {pid} now belongs to '{name}', not a tunnel client; leaving it running."
+                ));
+                let _ = fs::remove_file(tunnel_pid_file());
+                return Ok(tunnel_status());
+            }
+            let pid_text = pid.to_string();
+            let out = shell::run_command("taskkill", &["/PID", &pid_text, "/T", "/F"], None, &[])?;
+            shell::ensure_success("taskkill tunnel", &out)?;
+            logger::info(&format!("Tunnel stopped, PID {pid}."));
+        }
+        let _ = fs::remove_file(tunnel_pid_file());
+    }
+    Ok(tunnel_status())
+}
+
+/// Best-effort hook used by the gateway lifecycle: tunnel follows the gateway.
+pub fn start_if_enabled() {
+    let enabled = state_store::load_tunnel_config()
+        .map(|cfg| cfg.enabled)
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+    if let Err(err) = start_tunnel() {
+        logger::warn(&format!("Tunnel auto-start failed: {err}"));
+    }
+}
+
+pub fn tunnel_status() -> TunnelStatus {
+    let cfg = state_store::load_tunnel_config().unwrap_or_default();
+    let binary = binary_path(&cfg.provider);
+    let pid = running_tunnel_pid();
+    let public_url = if cfg.public_url.trim().is_empty() {
+        // Quick tunnels get a random trycloudflare.com hostname on every start.
+        pid.and(quick_tunnel_url_from_log())
+    } else {
+        Some(cfg.public_url.trim().to_string())
+    }
+    // The remote dashboard asks for the token just like the local one does.
+    .map(|url| browser::with_config_token(&url));
+    TunnelStatus {
+        provider: cfg.provider,
+        enabled: cfg.enabled,
+        running: pid.is_some(),
+        pid,
+        public_url,
+        binary_path: binary
+            .exists()
+            .then(|| binary.to_string_lossy().to_string()),
+    }
+}
+
+fn normalize_tunnel_config(input: &TunnelConfigInput) -> Result<TunnelConfigInput> {
+    let mut cfg = input.clone();
+    cfg.provider = cfg.provider.trim().to_ascii_lowercase();
+    if !matches!(cfg.provider.as_str(), PROVIDER_CLOUDFLARED | PROVIDER_FRPC) {
+        return Err(anyhow!("Tunnel provider must be cloudflared|frpc"));
+    }
+    cfg.public_url = cfg.public_url.trim().trim_end_matches('/').to_string();
+    if !cfg.public_url.is_empty() {
+        let parsed = Url::parse(&cfg.public_url)
+            .map_err(|_| anyhow!("Tunnel public URL is not a valid URL."))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(anyhow!("Tunnel public URL must be http(s)."));
+        }
+    }
+    if !cfg.enabled {
+        return Ok(cfg);
+    }
+    if cfg.provider == PROVIDER_CLOUDFLARED
+        && !cfg.cloudflared_token.trim().is_empty()
+        && cfg.public_url.is_empty()
+    {
+        return Err(anyhow!(
+            "A named Cloudflare Tunnel needs the public hostname URL configured for it."
+        ));
+    }
+    if cfg.provider == PROVIDER_FRPC {
+        if cfg.frp_server_addr.trim().is_empty() || cfg.frp_server_port == 0 {
+            return Err(anyhow!("frp server address and port are required."));
+        }
+        if cfg.frp_custom_domain.trim().is_empty() {
+            return Err(anyhow!(
+                "frp custom domain is required for the dashboard route."
+            ));
+        }
+        if cfg.public_url.is_empty() {
+            cfg.public_url = format!("http://{}", cfg.frp_custom_domain.trim());
+        }
+    }
+    Ok(cfg)
+}
+
+async fn ensure_binary(cfg: &TunnelConfigInput) -> Result<PathBuf> {
+    let target = binary_path(&cfg.provider);
+    if target.exists() {
+        return Ok(target);
+    }
+    fs::create_dir_all(paths::tools_dir())?;
+    let proxy = state_store::load_last_config()
+        .ok()
+        .flatten()
+        .and_then(|last| last.proxy);
+    let mut builder = Client::builder().timeout(Duration::from_secs(300));
+    if let Some(proxy) = config::optional_non_empty(proxy) {
+        builder = builder.proxy(reqwest::Proxy::all(proxy)?);
+    }
+    let client = builder.build()?;
+
+    let (url, pin) = match cfg.provider.as_str() {
+        PROVIDER_FRPC => (
+            format!(
+                "https://github.com/fatedier/frp/releases/download/v{FRP_VERSION}/{}",
+                frp_archive_name()
+            ),
+            FRP_ARCHIVE_SHA256,
+        ),
+        _ => (
+            format!(
+                "https://github.com/cloudflare/cloudflared/releases/download/{CLOUDFLARED_VERSION}/{CLOUDFLARED_ASSET}"
+            ),
+            CLOUDFLARED_SHA256,
+        ),
+    };
+    let expected = sha256_hex(pin).ok_or_else(|| {
+        anyhow!(
+            "No pinned SHA-256 for the {} client in this build; refusing to download it.",
+            cfg.provider
+        )
+    })?;
+    logger::info(&format!("Downloading tunnel client: {url}"));
+    let resp = client.get(&url).send().await?;
+    if !resp.status().is_success() {
+        return Err(anyhow!(
+            "Tunnel client download failed: HTTP {}",
+            resp.status()
+        ));
+    }
+    let bytes = resp.bytes().await?;
+    let actual = fs_walk::hex_digest(Sha256::new_with_prefix(&bytes));
+    if actual != expected {
+        return Err(anyhow!(
+            "Checksum mismatch for {url}: expected sha256 {expected}, got {actual}. The download was discarded."
+        ));
+    }
+
+    let staged = temp_files::create_file(temp_files::DOWNLOAD_PREFIX, "download");
+    // Written next to the target and renamed, so a crash never leaves a half-written exe.
+    let part = target.with_extension("exe.part");
+    let result = (|| -> Result<()> {
+        fs::write(&staged, &bytes)?;
+        if cfg.provider == PROVIDER_FRPC {
+            let mut archive = ZipArchive::new(File::open(&staged)?)?;
+            let inner = format!("frp_{FRP_VERSION}_windows_amd64/frpc.exe");
+            let mut entry = archive
+                .by_name(&inner)
+                .map_err(|_| anyhow!("frpc.exe not found in frp release archive"))?;
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            let mut file = File::create(&part)?;
+            file.write_all(&data)?;
+            file.sync_all()?;
+        } else {
+            fs::copy(&staged, &part)?;
+        }
+        fs::rename(&part, &target)?;
+        Ok(())
+    })();
+    temp_files::release(&staged);
+    if result.is_err() {
+        let _ = fs::remove_file(&part);
+    }
+    result?;
+    // Unpinned clients from earlier versions are never started again.
+    let legacy = paths::tools_dir().join(format!("{}.exe", cfg.provider));
+    let _ = fs::remove_file(legacy);
+    logger::info(&format!(
+        "Tunnel client installed: {}",
+        target.to_string_lossy()
+    ));
+    Ok(target)
+}
+
+fn write_frpc_config(cfg: &TunnelConfigInput, local_port: u16) -> Result<PathBuf> {
+    let path = paths::run_dir().join("frpc.toml");
+    let mut lines = vec![
+        format!("serverAddr = {}", toml_string(cfg.frp_server_addr.trim())),
+        format!("serverPort = {}", cfg.frp_server_port),
+    ];
+    if !cfg.frp_auth_token.trim().is_empty() {
+        lines.push(format!("auth.token = \"{{{{ .Envs.{FRP_TOKEN_ENV} }}}}\""));
+    }
+    lines.extend([
+        String::new(),
+        "[[proxies]]".to_string(),
+        "name = \"openclaw-dashboard\"".to_string(),
+        "type = \"http\"".to_string(),
+        format!(
+            "customDomains = [{}]",
+            toml_string(cfg.frp_custom_domain.trim())
+        ),
+    ]);
+    if tls::gateway_scheme() == "https" {
+        // frps terminates http; the plugin re-encrypts towards the local https gateway.
+        lines.extend([
+            "[proxies.plugin]".to_string(),
+            "type = \"http2https\"".to_string(),
+            format!("localAddr = \"127.0.0.1:{local_port}\""),
+        ]);
+    } else {
+        lines.extend([
+            "localIP = \"127.0.0.1\"".to_string(),
+            format!("localPort = {local_port}"),
+        ]);
+    }
+    fs::write(&path, lines.join("\n") + "\n")?;
+    Ok(path)
+}
+
+fn toml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn frp_archive_name() -> String {
+    format!("frp_{FRP_VERSION}_windows_amd64.zip")
+}
+
+fn sha256_hex(raw: &str) -> Option<String> {
+    (raw.len() == 64 && raw.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| raw.to_ascii_lowercase())
+}
+
+fn binary_path(provider: &str) -> PathBuf {
+    match provider {
+        PROVIDER_FRPC => paths::tools_dir().join(format!("frpc-{FRP_VERSION}.exe")),
+        _ => paths::tools_dir().join(format!("cloudflared-{CLOUDFLARED_VERSION}.exe")),
+    }
+}
+
+/// Image names of the pinned clients, as tasklist reports them.
+fn is_tunnel_client_name(name: &str) -> bool {
+    [PROVIDER_CLOUDFLARED, PROVIDER_FRPC]
+        .into_iter()
+        .filter_map(|provider| {
+            binary_path(provider)
+                .file_name()
+                .map(|f| f.to_string_lossy().to_string())
+        })
+        .any(|file| file.eq_ignore_ascii_case(name.trim()))
+}
+
+fn gateway_auth_disabled() -> Result<bool> {
+    let path = paths::config_path();
+    if !path.exists() {
+        return Ok(false);
+    }
+    let json: Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+    Ok(auth_mode_is_none(&json))
+}
+
+fn auth_mode_is_none(json: &Value) -> bool {
+    json.pointer("/gateway/auth/mode")
+        .and_then(Value::as_str)
+        .map(|mode| mode.trim().eq_ignore_ascii_case("none"))
+        .unwrap_or(false)
+}
+
+fn tunnel_pid_file() -> PathBuf {
+    paths::run_dir().join("tunnel.pid")
+}
+
+fn tunnel_log_path() -> PathBuf {
+    paths::logs_dir().join("tunnel.log")
+}
+
+fn read_tunnel_pid() -> Option<u32> {
+    let raw = fs::read_to_string(tunnel_pid_file()).ok()?;
+    raw.trim().parse::<u32>().ok()
+}
+
+fn running_tunnel_pid() -> Option<u32> {
+    let pid = read_tunnel_pid()?;
+    if shell::is_process_alive(pid) {
+        Some(pid)
+    } else {
+        let _ = fs::remove_file(tunnel_pid_file());
+        None
+    }
+}
+
+fn quick_tunnel_url_from_log() -> Option<String> {
+    let raw = fs::read_to_string(tunnel_log_path()).ok()?;
+    parse_quick_tunnel_url(&raw)
+}
+
+fn parse_quick_tunnel_url(raw: &str) -> Option<String> {
+    let re = Regex::new(r"https://[a-z0-9-]+\.trycloudflare\.com").ok()?;
+    re.find_iter(raw).last().map(|m| m.as_str().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        auth_mode_is_none, is_tunnel_client_name, normalize_tunnel_config, parse_quick_tunnel_url,
+        sha256_hex, toml_string, CLOUDFLARED_VERSION, FRP_VERSION,
+    };
+    use crate::models::TunnelConfigInput;
+    use serde_json::json;
+
+    #[test]
+    fn pinned_digests_must_be_plain_sha256_hex() {
+        let digest = "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08";
+        assert_eq!(sha256_hex(digest), Some(digest.to_ascii_lowercase()));
+        assert_eq!(sha256_hex(""), None);
+        assert_eq!(sha256_hex(&format!("sha256:{digest}")), None);
+    }
+
+    #[test]
+    fn only_pinned_client_images_count_as_the_tunnel() {
+        assert!(is_tunnel_client_name(&format!(
+            "cloudflared-{CLOUDFLARED_VERSION}.exe"
+        )));
+        assert!(is_tunnel_client_name(&format!("FRPC-{FRP_VERSION}.EXE")));
+        assert!(!is_tunnel_client_name("node.exe"));
+        assert!(!is_tunnel_client_name(""));
+    }
+
+    #[test]
+    fn tunnels_are_refused_only_when_auth_is_off() {
+        assert!(auth_mode_is_none(
+            &json!({ "gateway": { "auth": { "mode": "None" } } })
+        ));
+        assert!(!auth_mode_is_none(
+            &json!({ "gateway": { "auth": { "mode": "token" } } })
+        ));
+        assert!(!auth_mode_is_none(&json!({ "gateway": {} })));
+    }
+
+    #[test]
+    fn parse_quick_tunnel_url_reads_cloudflared_banner() {
+        let raw =
+            "INF |  https://brave-lion-42.trycloudflare.com  |\nINF Registered tunnel connection";
+        assert_eq!(
+            parse_quick_tunnel_url(raw).as_deref(),
+            Some("https://brave-lion-42.trycloudflare.com")
+        );
+        assert_eq!(parse_quick_tunnel_url("no url here"), None);
+    }
+
+    #[test]
+    fn frpc_config_requires_domain_and_derives_public_url() {
+        let mut input = TunnelConfigInput {
+            provider: "FRPC".to_string(),
+            enabled: true,
+            frp_server_addr: "frp.example.com".to_string(),
+            ..TunnelConfigInput::default()
+        };
+        assert!(normalize_tunnel_config(&input).is_err());
+        input.frp_custom_domain = "claw.example.com".to_string();
+        let cfg = normalize_tunnel_config(&input).expect("valid frp config");
+        assert_eq!(cfg.provider, "frpc");
+        assert_eq!(cfg.public_url, "http://claw.example.com");
+    }
+
+    #[test]
+    fn toml_string_escapes_quotes() {
+        assert_eq!(toml_string("a\"b"), "\"a\\\"b\"");
+    }
+}
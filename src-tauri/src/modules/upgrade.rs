@@ -1,12 +1,290 @@
+use std::fs;
+use std::time::{Duration, Instant};
+
 use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde_json::Value;
+use tracing::Instrument;
+
+use crate::models::{
+    InstallState, OpenClawFileConfig, ResultWarning, SourceMethod, UpgradeCheck, UpgradeResult,
+    WarningSeverity,
+};
+
+use super::{
+    backup, config, env, installer, logger, model_catalog, model_identity, paths, process, skills,
+    state_store,
+};
+
+const REGISTRIES: &[&str] = &[
+    "https://registry.npmjs.org",
+    "https://registry.npmmirror.com",
+];
+
+/// Checks the latest published OpenClaw against this machine before upgrading: the
+/// package's `engines.node` range and config the new CLI is known to reject.
+pub async fn check_upgrade() -> Result<UpgradeCheck> {
+    let install_state = state_store::load_install_state()?
+        .ok_or_else(|| anyhow!("Install state not found. Install OpenClaw first."))?;
+    let proxy = state_store::load_last_config()?.and_then(|cfg| cfg.proxy);
+    let manifest = fetch_latest_manifest(proxy).await;
+    Ok(evaluate_upgrade(
+        &install_state,
+        manifest.as_ref(),
+        env::node_version(),
+    ))
+}
+
+fn evaluate_upgrade(
+    install_state: &InstallState,
+    manifest: Option<&Value>,
+    node_version: Option<String>,
+) -> UpgradeCheck {
+    let mut warnings = Vec::<ResultWarning>::new();
+    let target_version = manifest
+        .and_then(|m| m.get("version"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let required_node = manifest
+        .and_then(|m| m.pointer("/engines/node"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    if manifest.is_none() {
+        warnings.push(
+            ResultWarning::warning(
+                "registry_unreachable",
+                "Could not read the latest OpenClaw release from the npm registry; compatibility was not checked.",
+            )
+            .with_remediation("Check the network or proxy setting, then check again."),
+        );
+    }
+    if let Some(target) = &target_version {
+        if is_same_version(&install_state.version, target) {
+            warnings.push(ResultWarning::info(
+                "already_latest",
+                format!("OpenClaw {target} is already installed."),
+            ));
+        }
+    }
+
+    let needs_node = !matches!(install_state.method, SourceMethod::Binary);
+    if needs_node {
+        match (&node_version, &required_node) {
+            (None, _) => warnings.push(
+                ResultWarning::error("node_missing", "Node.js was not found on PATH.")
+                    .with_remediation("Install Node.js 22 LTS from https://nodejs.org and rerun."),
+            ),
+            (Some(node), Some(range)) if !node_satisfies(range, node) => warnings.push(
+                ResultWarning::error(
+                    "node_incompatible",
+                    format!(
+                        "OpenClaw {} requires Node {range}; your Node is {node}.",
+                        target_version.as_deref().unwrap_or("latest")
+                    ),
+                )
+                .with_remediation(format!(
+                    "Install a Node.js release matching {range} from https://nodejs.org, then upgrade."
+                )),
+            ),
+            _ => {}
+        }
+    }
+    warnings.extend(config_compatibility_warnings());
+
+    UpgradeCheck {
+        current_version: install_state.version.clone(),
+        target_version,
+        node_version,
+        required_node,
+        blocked: warnings
+            .iter()
+            .any(|w| w.severity == WarningSeverity::Error),
+        warnings,
+    }
+}
 
-use crate::models::UpgradeResult;
+async fn fetch_latest_manifest(proxy: Option<String>) -> Option<Value> {
+    let mut builder = Client::builder().timeout(Duration::from_secs(8));
+    if let Some(proxy) = config::optional_non_empty(proxy) {
+        builder = builder.proxy(reqwest::Proxy::all(proxy).ok()?);
+    }
+    let client = builder.build().ok()?;
+    for registry in REGISTRIES {
+        let url = format!("{registry}/openclaw/latest");
+        match client.get(&url).send().await {
+            Ok(resp) if resp.status().is_success() => {
+                if let Ok(json) = resp.json::<Value>().await {
+                    return Some(json);
+                }
+            }
+            Ok(resp) => logger::warn(&format!("{url} returned HTTP {}", resp.status())),
+            Err(err) => logger::warn(&format!("{url} unreachable: {err}")),
+        }
+    }
+    None
+}
 
-use super::{backup, config, installer, logger, model_catalog, state_store};
+/// Config the current CLI no longer reads: the installer's old flat schema and model
+/// ids that were renamed upstream.
+fn config_compatibility_warnings() -> Vec<ResultWarning> {
+    let mut warnings = Vec::new();
+    let Ok(raw) = fs::read_to_string(paths::config_path()) else {
+        return warnings;
+    };
+    if serde_json::from_str::<OpenClawFileConfig>(&raw).is_ok() {
+        warnings.push(
+            ResultWarning::warning(
+                "legacy_config_schema",
+                "openclaw.json still uses the installer's old flat layout, which newer OpenClaw releases do not read.",
+            )
+            .with_remediation("Rerun configure from Maintenance before upgrading."),
+        );
+        return warnings;
+    }
+    let Ok(json) = serde_json::from_str::<Value>(&raw) else {
+        return warnings;
+    };
+    let model = json.pointer("/agents/defaults/model");
+    let keys = model
+        .and_then(Value::as_str)
+        .into_iter()
+        .chain(model.and_then(|m| m.get("primary")).and_then(Value::as_str))
+        .chain(
+            model
+                .and_then(|m| m.get("fallbacks"))
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str),
+        );
+    for key in keys {
+        let normalized = model_identity::normalize_known_model_key(key);
+        if normalized != key.trim() {
+            warnings.push(
+                ResultWarning::warning(
+                    "legacy_model_id",
+                    format!("Model id {key} was renamed to {normalized}."),
+                )
+                .with_remediation("Rerun configure from Maintenance to rewrite the model chain."),
+            );
+        }
+    }
+    warnings
+}
+
+/// Minimal npm range check for `engines.node`: `||` alternatives of space-separated
+/// comparators (`>=`, `>`, `<=`, `<`, `=`, `^`, `~`, bare or `x` versions). Anything
+/// unparseable counts as satisfied so an odd range never blocks an upgrade.
+fn node_satisfies(range: &str, version: &str) -> bool {
+    let Some(current) = parse_version(version) else {
+        return true;
+    };
+    range.split("||").any(|set| {
+        let mut set = set.trim().to_string();
+        for op in [">=", "<=", ">", "<", "=", "^", "~"] {
+            set = set.replace(&format!("{op} "), op);
+        }
+        set.split_whitespace()
+            .all(|comparator| comparator_allows(comparator, current))
+    })
+}
+
+fn comparator_allows(comparator: &str, current: (u64, u64, u64)) -> bool {
+    if comparator == "*" || comparator.eq_ignore_ascii_case("x") {
+        return true;
+    }
+    let (op, rest) = [">=", "<=", ">", "<", "=", "^", "~"]
+        .iter()
+        .find_map(|op| comparator.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", comparator));
+    let Some(wanted) = parse_version(rest) else {
+        return true;
+    };
+    let parts = rest
+        .trim_start_matches('v')
+        .split('.')
+        .take_while(|p| p.parse::<u64>().is_ok())
+        .count();
+    match op {
+        ">=" => current >= wanted,
+        ">" => current > wanted,
+        "<=" => current <= wanted,
+        "<" => current < wanted,
+        "^" => current >= wanted && current.0 == wanted.0,
+        "~" if parts >= 2 => current >= wanted && (current.0, current.1) == (wanted.0, wanted.1),
+        "~" => current >= wanted && current.0 == wanted.0,
+        _ => match parts {
+            1 => current.0 == wanted.0,
+            2 => (current.0, current.1) == (wanted.0, wanted.1),
+            _ => current == wanted,
+        },
+    }
+}
+
+/// Whether `installed` (which may carry a prefix such as `OpenClaw v2026.3.1`) is exactly
+/// `target`. Pre-release tags count: `2026.3.1-beta.1` is not `2026.3.1`.
+fn is_same_version(installed: &str, target: &str) -> bool {
+    let normalize = |raw: &str| raw.trim().trim_start_matches('v').to_ascii_lowercase();
+    installed
+        .split_whitespace()
+        .map(normalize)
+        .any(|token| token == normalize(target))
+}
+
+fn parse_version(raw: &str) -> Option<(u64, u64, u64)> {
+    let core = raw
+        .trim()
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()?;
+    let mut parts = core.split('.');
+    let major = parts.next()?.parse::<u64>().ok()?;
+    let mut next = || {
+        parts
+            .next()
+            .and_then(|p| p.parse::<u64>().ok())
+            .unwrap_or(0)
+    };
+    Some((major, next(), next()))
+}
 
 pub async fn upgrade() -> Result<UpgradeResult> {
+    let span = tracing::info_span!("upgrade");
+    let result = upgrade_steps().instrument(span.clone()).await;
+    span.in_scope(|| match &result {
+        Ok(done) if done.rolled_back => tracing::error!(message = %done.message),
+        Ok(done) => tracing::info!(
+            old_version = %done.old_version,
+            new_version = %done.new_version,
+            downtime_ms = done.downtime_ms,
+            "upgrade finished"
+        ),
+        Err(err) => tracing::error!(error = %err, "upgrade failed"),
+    });
+    result
+}
+
+async fn upgrade_steps() -> Result<UpgradeResult> {
     let install_state = state_store::load_install_state()?
         .ok_or_else(|| anyhow!("Install state not found. Install OpenClaw first."))?;
+    let check = check_upgrade()
+        .instrument(tracing::info_span!("upgrade.check"))
+        .await?;
+    if let Some(blocker) = check
+        .warnings
+        .iter()
+        .find(|w| w.severity == WarningSeverity::Error)
+    {
+        return Err(anyhow!(
+            "Upgrade blocked: {}{}",
+            blocker.message,
+            blocker
+                .remediation
+                .as_deref()
+                .map(|r| format!(" {r}"))
+                .unwrap_or_default()
+        ));
+    }
 
     // Rebuild upgrade payload from last config, then fallback to current config if needed.
     let mut payload = state_store::load_last_config()?.unwrap_or_default();
@@ -20,6 +298,8 @@ pub async fn upgrade() -> Result<UpgradeResult> {
     if payload.source_url.is_none() {
         payload.source_url = install_state.source_url.clone();
     }
+    // The checksum pinned the originally downloaded binary, not whatever the URL serves now.
+    payload.source_checksum = None;
     let current = config::read_current_config()?;
     if payload.provider.trim().is_empty() {
         payload.provider = current.provider;
@@ -42,15 +322,69 @@ pub async fn upgrade() -> Result<UpgradeResult> {
 
     let old_version = install_state.version.clone();
     // Upgrade is guarded by a pre-upgrade snapshot for automatic rollback.
-    let pre_upgrade = backup::backup_with_prefix("pre-upgrade")?;
+    let pre_upgrade = tracing::info_span!("upgrade.backup")
+        .in_scope(|| backup::backup_with_prefix("pre-upgrade"))?;
     let backup_id = pre_upgrade.id.clone();
+    // The snapshot leaves out node_modules, so pin the installed package separately.
+    let mut warnings = check
+        .warnings
+        .into_iter()
+        .filter(|w| w.code != "already_latest")
+        .collect::<Vec<_>>();
+    let pin = match installer::capture_package_pin(&install_state) {
+        Ok(pin) => Some(pin),
+        Err(err) => {
+            logger::warn(&format!("Could not pin the installed package: {err}"));
+            warnings.push(
+                ResultWarning::warning(
+                    "package_not_pinned",
+                    format!("A failed upgrade cannot restore the previous package: {err}"),
+                )
+                .with_remediation("If the upgrade fails, reinstall OpenClaw from Maintenance."),
+            );
+            None
+        }
+    };
 
-    match installer::install_openclaw_for_upgrade(&payload).await {
+    // Stop late, start early: npm/bun installs are staged beside the live package, so
+    // the gateway is only down for the swap (or the in-place install for git/binary).
+    let was_running = process::status()
+        .await
+        .map(|status| status.running)
+        .unwrap_or(false);
+    let mut stopped_at = None;
+    let mut touched = false;
+    let outcome = if installer::supports_staged_upgrade(&payload.source_method) {
+        match installer::stage_upgrade(&payload)
+            .instrument(tracing::info_span!("upgrade.stage"))
+            .await
+        {
+            Ok(staged) => {
+                stopped_at = stop_gateway(was_running);
+                touched = true;
+                installer::swap_staged_upgrade(staged, &payload)
+                    .instrument(tracing::info_span!("upgrade.swap"))
+                    .await
+            }
+            Err(err) => Err(err),
+        }
+    } else {
+        stopped_at = stop_gateway(was_running);
+        touched = true;
+        installer::install_openclaw_for_upgrade(&payload).await
+    };
+
+    match outcome {
         Ok(result) => {
+            if let Some(pin) = &pin {
+                installer::discard_package_pin(pin);
+            }
             model_catalog::clear_model_catalog_cache();
+            skills::clear_skill_catalog_cache();
+            let downtime_ms = restart_gateway(stopped_at, &mut warnings);
             logger::info(&format!(
-                "Upgrade completed from {} to {}",
-                old_version, result.version
+                "Upgrade completed from {} to {} (downtime {:?} ms)",
+                old_version, result.version, downtime_ms
             ));
             Ok(UpgradeResult {
                 old_version,
@@ -58,6 +392,31 @@ pub async fn upgrade() -> Result<UpgradeResult> {
                 rolled_back: false,
                 backup_id,
                 message: "Upgrade completed successfully.".to_string(),
+                warnings,
+                downtime_ms,
+            })
+        }
+        Err(err) if !touched => {
+            // Staging failed: the live package and the running gateway were never touched.
+            logger::error(&format!("Upgrade staging failed: {err}"));
+            if let Some(pin) = &pin {
+                installer::discard_package_pin(pin);
+            }
+            warnings.push(
+                ResultWarning::error(
+                    "upgrade_failed",
+                    format!("Upgrade failed before the installed version was changed: {err}"),
+                )
+                .with_remediation("Check the installer log, then retry the upgrade."),
+            );
+            Ok(UpgradeResult {
+                new_version: old_version.clone(),
+                old_version,
+                rolled_back: false,
+                backup_id,
+                message: format!("Upgrade failed; OpenClaw was left unchanged: {err}"),
+                warnings,
+                downtime_ms: None,
             })
         }
         Err(err) => {
@@ -65,14 +424,138 @@ pub async fn upgrade() -> Result<UpgradeResult> {
             logger::error(&format!(
                 "Upgrade failed, restoring backup {backup_id}: {err}"
             ));
-            backup::restore_backup(&backup_id)?;
+            let rollback = tracing::info_span!("upgrade.rollback");
+            rollback.in_scope(|| backup::restore_backup(&backup_id))?;
+            warnings.push(
+                ResultWarning::error(
+                    "upgrade_rolled_back",
+                    format!("Upgrade failed; restored backup {backup_id}: {err}"),
+                )
+                .with_remediation("Check the installer log, then retry the upgrade."),
+            );
+            let mut message = format!("Upgrade failed and rollback completed: {err}");
+            if let Some(pin) = &pin {
+                let restored = installer::restore_package_pin(&payload, pin)
+                    .instrument(rollback)
+                    .await;
+                if let Err(restore_err) = restored {
+                    logger::error(&format!(
+                        "Rollback could not reinstall {pin:?}: {restore_err}"
+                    ));
+                    message = format!(
+                        "Upgrade failed; config was restored but the previous package was not: {restore_err}"
+                    );
+                    warnings.push(
+                        ResultWarning::error(
+                            "package_not_restored",
+                            format!("Previous OpenClaw package was not reinstalled: {restore_err}"),
+                        )
+                        .with_remediation(
+                            "Reinstall OpenClaw from Maintenance before starting the gateway.",
+                        ),
+                    );
+                }
+            }
+            let downtime_ms = restart_gateway(stopped_at, &mut warnings);
             Ok(UpgradeResult {
                 old_version,
                 new_version: "rollback".to_string(),
                 rolled_back: true,
                 backup_id,
-                message: format!("Upgrade failed and rollback completed: {err}"),
+                message,
+                warnings,
+                downtime_ms,
             })
         }
     }
 }
+
+/// Stops a running gateway for the swap and returns when the downtime started.
+fn stop_gateway(was_running: bool) -> Option<Instant> {
+    if !was_running {
+        return None;
+    }
+    if let Err(err) = process::stop() {
+        logger::warn(&format!("Stopping the gateway for upgrade failed: {err}"));
+    }
+    Some(Instant::now())
+}
+
+/// Restarts a gateway stopped by `stop_gateway` and returns the downtime in ms.
+fn restart_gateway(stopped_at: Option<Instant>, warnings: &mut Vec<ResultWarning>) -> Option<u64> {
+    let stopped_at = stopped_at?;
+    let _span = tracing::info_span!("upgrade.restart_gateway").entered();
+    match process::start() {
+        Ok(result) if !result.running => warnings.push(ResultWarning::error(
+            "gateway_restart_failed",
+            format!(
+                "Gateway did not start after the upgrade: {}",
+                result.message
+            ),
+        )),
+        Ok(_) => {}
+        Err(err) => warnings.push(ResultWarning::error(
+            "gateway_restart_failed",
+            format!("Gateway did not start after the upgrade: {err}"),
+        )),
+    }
+    Some(stopped_at.elapsed().as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{evaluate_upgrade, is_same_version, node_satisfies};
+    use crate::models::{InstallState, SourceMethod};
+    use serde_json::json;
+
+    #[test]
+    fn installed_version_must_match_exactly() {
+        assert!(is_same_version("2026.3.1", "2026.3.1"));
+        assert!(is_same_version("OpenClaw v2026.3.1", "2026.3.1"));
+        assert!(!is_same_version("2026.3.11", "2026.3.1"));
+        assert!(!is_same_version("2026.3.1-beta.1", "2026.3.1"));
+        assert!(!is_same_version("2026.3.1", "2026.3.1-beta.1"));
+        assert!(!is_same_version("", "2026.3.1"));
+    }
+
+    #[test]
+    fn node_satisfies_common_engine_ranges() {
+        assert!(node_satisfies(">=22.12.0", "22.12.0"));
+        assert!(!node_satisfies(">=22.12.0", "22.11.1"));
+        assert!(!node_satisfies(">= 22", "20.18.0"));
+        assert!(node_satisfies("^20.19.0 || >=22.12.0", "20.19.5"));
+        assert!(!node_satisfies("^20.19.0 || >=22.12.0", "21.7.3"));
+        assert!(node_satisfies(">=18 <23", "22.1.0"));
+        assert!(!node_satisfies(">=18 <23", "24.0.0"));
+        assert!(node_satisfies("22.x", "22.4.1"));
+        assert!(node_satisfies("~22.4", "22.4.9"));
+        assert!(!node_satisfies("~22.4", "22.5.0"));
+        assert!(node_satisfies("something odd", "10.0.0"));
+    }
+
+    #[test]
+    fn old_node_blocks_npm_upgrades_but_not_binary_ones() {
+        let manifest = json!({ "version": "2026.3.1", "engines": { "node": ">=22.12.0" } });
+        let mut state = InstallState {
+            method: SourceMethod::Npm,
+            install_dir: "C:\\OpenClaw".to_string(),
+            source_url: None,
+            command_path: "openclaw".to_string(),
+            version: "2026.1.5".to_string(),
+            launch_args: "gateway".to_string(),
+            shim_path: None,
+            verified_package: None,
+        };
+        let check = evaluate_upgrade(&state, Some(&manifest), Some("20.18.0".to_string()));
+        assert!(check.blocked);
+        assert!(check
+            .warnings
+            .iter()
+            .any(|w| w.code == "node_incompatible" && w.message.contains("Node >=22.12.0")));
+
+        state.method = SourceMethod::Binary;
+        let check = evaluate_upgrade(&state, Some(&manifest), None);
+        assert!(!check.blocked);
+        assert_eq!(check.target_version.as_deref(), Some("2026.3.1"));
+    }
+}
@@ -0,0 +1,246 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::Result;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{AvailabilityReport, AvailabilityWindow};
+
+use super::{logger, paths};
+
+const SAMPLE_MIN_INTERVAL_MS: i64 = 60_000;
+const RETENTION_MS: i64 = 30 * 24 * 60 * 60 * 1000;
+const REPORT_WINDOWS: &[(&str, i64)] = &[
+    ("24h", 24 * 60 * 60 * 1000),
+    ("7d", 7 * 24 * 60 * 60 * 1000),
+    ("30d", RETENTION_MS),
+];
+
+static LAST_SAMPLE_MS: OnceLock<Mutex<i64>> = OnceLock::new();
+static UPTIME_FILE_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum UptimeEvent {
+    Sample { ts: i64, ok: bool },
+    Start { ts: i64 },
+}
+
+impl UptimeEvent {
+    fn ts(&self) -> i64 {
+        match self {
+            Self::Sample { ts, .. } | Self::Start { ts } => *ts,
+        }
+    }
+}
+
+/// Records a gateway health sample. Throttled to one a minute; the status stream calls
+/// it on every background probe, whether or not the window is open.
+pub fn record_sample(ok: bool) {
+    let now = now_ms();
+    let lock = LAST_SAMPLE_MS.get_or_init(|| Mutex::new(0));
+    {
+        let mut last = lock.lock().unwrap_or_else(|e| e.into_inner());
+        if now - *last < SAMPLE_MIN_INTERVAL_MS {
+            return;
+        }
+        *last = now;
+    }
+    append_event(&UptimeEvent::Sample { ts: now, ok });
+}
+
+/// Records a gateway (re)start so restart counts can be reported per window.
+pub fn record_start() {
+    append_event(&UptimeEvent::Start { ts: now_ms() });
+}
+
+pub fn get_availability_report() -> Result<AvailabilityReport> {
+    let now = now_ms();
+    let events = load_and_prune_events(now)?;
+    let windows = REPORT_WINDOWS
+        .iter()
+        .map(|(label, span)| compute_window(label, &events, now - span, now))
+        .collect();
+    Ok(AvailabilityReport {
+        generated_at: Local::now().to_rfc3339(),
+        windows,
+    })
+}
+
+fn compute_window(label: &str, events: &[UptimeEvent], from: i64, to: i64) -> AvailabilityWindow {
+    let in_window = events
+        .iter()
+        .filter(|event| event.ts() >= from && event.ts() <= to)
+        .collect::<Vec<_>>();
+    let samples = in_window
+        .iter()
+        .filter_map(|event| match event {
+            UptimeEvent::Sample { ts, ok } => Some((*ts, *ok)),
+            UptimeEvent::Start { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    let restart_count = in_window
+        .iter()
+        .filter(|event| matches!(event, UptimeEvent::Start { .. }))
+        .count();
+
+    let up = samples.iter().filter(|(_, ok)| *ok).count();
+    let availability_percent = if samples.is_empty() {
+        None
+    } else {
+        Some(((up as f64 / samples.len() as f64) * 10_000.0).round() / 100.0)
+    };
+
+    // An outage spans from its first failed sample to the next healthy sample
+    // (or to the last failed sample while it is still ongoing).
+    let mut outages = Vec::<(i64, bool)>::new();
+    let mut outage_start: Option<i64> = None;
+    let mut last_down = 0i64;
+    for (ts, ok) in &samples {
+        match (ok, outage_start) {
+            (false, None) => {
+                outage_start = Some(*ts);
+                last_down = *ts;
+            }
+            (false, Some(_)) => last_down = *ts,
+            (true, Some(start)) => {
+                outages.push((ts - start, true));
+                outage_start = None;
+            }
+            (true, None) => {}
+        }
+    }
+    if let Some(start) = outage_start {
+        outages.push((last_down - start, false));
+    }
+
+    let longest_outage_ms = outages.iter().map(|(ms, _)| *ms).max().unwrap_or(0);
+    let recovered = outages
+        .iter()
+        .filter(|(_, recovered)| *recovered)
+        .map(|(ms, _)| *ms)
+        .collect::<Vec<_>>();
+    let mttr_secs = if recovered.is_empty() {
+        None
+    } else {
+        Some((recovered.iter().sum::<i64>() / recovered.len() as i64 / 1000) as u64)
+    };
+
+    AvailabilityWindow {
+        label: label.to_string(),
+        availability_percent,
+        sample_count: samples.len(),
+        outage_count: outages.len(),
+        longest_outage_secs: (longest_outage_ms / 1000) as u64,
+        mttr_secs,
+        restart_count,
+    }
+}
+
+fn uptime_file() -> PathBuf {
+    paths::state_dir().join("uptime.jsonl")
+}
+
+fn append_event(event: &UptimeEvent) {
+    let lock = UPTIME_FILE_LOCK.get_or_init(|| Mutex::new(()));
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+    let result = (|| -> Result<()> {
+        paths::ensure_dirs()?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(uptime_file())?;
+        let line = serde_json::to_string(event)?;
+        file.write_all(format!("{line}\n").as_bytes())?;
+        Ok(())
+    })();
+    if let Err(err) = result {
+        logger::warn(&format!("Failed to record uptime event: {err}"));
+    }
+}
+
+fn load_and_prune_events(now: i64) -> Result<Vec<UptimeEvent>> {
+    let lock = UPTIME_FILE_LOCK.get_or_init(|| Mutex::new(()));
+    let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+    let path = uptime_file();
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let raw = fs::read_to_string(&path)?;
+    let total_lines = raw.lines().count();
+    let mut events = raw
+        .lines()
+        .filter_map(|line| serde_json::from_str::<UptimeEvent>(line).ok())
+        .filter(|event| event.ts() >= now - RETENTION_MS)
+        .collect::<Vec<_>>();
+    events.sort_by_key(|event| event.ts());
+
+    if events.len() != total_lines {
+        let mut content = events
+            .iter()
+            .filter_map(|event| serde_json::to_string(event).ok())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        fs::write(&path, content)?;
+    }
+    Ok(events)
+}
+
+fn now_ms() -> i64 {
+    Local::now().timestamp_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_window, UptimeEvent};
+
+    fn sample(ts: i64, ok: bool) -> UptimeEvent {
+        UptimeEvent::Sample { ts, ok }
+    }
+
+    #[test]
+    fn compute_window_reports_outages_and_mttr() {
+        let events = vec![
+            UptimeEvent::Start { ts: 0 },
+            sample(60_000, true),
+            sample(120_000, false),
+            sample(180_000, false),
+            sample(240_000, true),
+            UptimeEvent::Start { ts: 240_000 },
+            sample(300_000, true),
+        ];
+        let window = compute_window("24h", &events, 0, 300_000);
+        assert_eq!(window.sample_count, 5);
+        assert_eq!(window.availability_percent, Some(60.0));
+        assert_eq!(window.outage_count, 1);
+        assert_eq!(window.longest_outage_secs, 120);
+        assert_eq!(window.mttr_secs, Some(120));
+        assert_eq!(window.restart_count, 2);
+    }
+
+    #[test]
+    fn compute_window_keeps_ongoing_outage_out_of_mttr() {
+        let events = vec![
+            sample(0, true),
+            sample(60_000, false),
+            sample(120_000, false),
+        ];
+        let window = compute_window("24h", &events, 0, 120_000);
+        assert_eq!(window.outage_count, 1);
+        assert_eq!(window.longest_outage_secs, 60);
+        assert_eq!(window.mttr_secs, None);
+    }
+
+    #[test]
+    fn compute_window_without_samples_has_no_availability() {
+        let window = compute_window("7d", &[], 0, 1);
+        assert_eq!(window.availability_percent, None);
+        assert_eq!(window.outage_count, 0);
+    }
+}
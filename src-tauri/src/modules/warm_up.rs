@@ -0,0 +1,154 @@
+use std::time::{Duration, Instant};
+
+use reqwest::StatusCode;
+use serde_json::json;
+
+use crate::models::{ModelChain, ProcessControlResult, WarmUpResult};
+
+use super::{browser, config, health, logger, model_catalog, process, state_store, tls, watchdog};
+
+// A cold Node gateway plus provider TLS handshake can take several seconds.
+const WARM_UP_TIMEOUT: Duration = Duration::from_secs(30);
+// Routes to the default agent's primary model.
+const DEFAULT_AGENT_MODEL: &str = "openclaw";
+
+/// Starts the gateway and, when enabled in run prefs, sends one tiny completion through
+/// it so module loading and provider handshakes happen before the user's first message.
+pub async fn start_with_warm_up() -> anyhow::Result<ProcessControlResult> {
+    // Spawning and waiting for readiness poll with blocking sleeps, so keep them off
+    // the async runtime's worker threads.
+    let (already_running, mut result) = tokio::task::spawn_blocking(|| {
+        let already_running = process::running_pid().is_some();
+        // A manual start acknowledges any crash loop and gets a clean backoff.
+        watchdog::reset();
+        process::start().map(|result| (already_running, result))
+    })
+    .await
+    .map_err(|err| anyhow::anyhow!("Gateway start worker failed: {err}"))??;
+    let enabled = state_store::load_run_prefs()
+        .map(|prefs| prefs.warm_up_after_start)
+        .unwrap_or(false);
+    if enabled && !already_running && result.running {
+        result.warm_up = Some(warm_up().await);
+    }
+    Ok(result)
+}
+
+pub async fn warm_up() -> WarmUpResult {
+    let cfg = match config::read_current_config() {
+        Ok(cfg) => cfg,
+        Err(err) => return failed(format!("Config unavailable: {err}")),
+    };
+    let health = health::health_check(&cfg.bind_address, cfg.port)
+        .await
+        .unwrap_or_default();
+    if !health.ok {
+        return failed("Gateway did not become reachable; warm-up skipped.".to_string());
+    }
+    let Ok(client) = tls::gateway_client(WARM_UP_TIMEOUT) else {
+        return failed("HTTP client unavailable.".to_string());
+    };
+    let host = match cfg.bind_address.trim() {
+        "" | "0.0.0.0" => "127.0.0.1",
+        other => other,
+    };
+    let url = format!(
+        "{}://{host}:{}/v1/chat/completions",
+        tls::gateway_scheme(),
+        cfg.port
+    );
+    // The cheapest model of the chain and one output token keep the cost negligible
+    // while still loading the gateway and opening a provider connection.
+    let mut request = client.post(&url).json(&json!({
+        "model": warm_up_model(&cfg.model_chain),
+        "max_tokens": 1,
+        "messages": [{ "role": "user", "content": "ping" }]
+    }));
+    if let Ok(Some(token)) = browser::read_gateway_token_from_config() {
+        request = request.bearer_auth(token);
+    }
+
+    let started = Instant::now();
+    let outcome = match request.send().await {
+        Ok(resp) if resp.status().is_success() => WarmUpResult {
+            ok: true,
+            latency_ms: Some(started.elapsed().as_millis() as u64),
+            message: "Warm-up completion succeeded.".to_string(),
+        },
+        Ok(resp) if resp.status() == StatusCode::NOT_FOUND => failed(
+            "Gateway chat completions endpoint is disabled; enable gateway.http.endpoints.chatCompletions to warm up."
+                .to_string(),
+        ),
+        Ok(resp) => failed(format!("Warm-up returned HTTP {}.", resp.status().as_u16())),
+        Err(err) => failed(format!("Warm-up request failed: {err}")),
+    };
+    if outcome.ok {
+        logger::info(&format!(
+            "Gateway warm-up finished in {} ms.",
+            outcome.latency_ms.unwrap_or_default()
+        ));
+    } else {
+        logger::warn(&outcome.message);
+    }
+    outcome
+}
+
+/// The chain model with the lowest known price; the default agent's primary when no
+/// price is known.
+fn warm_up_model(chain: &ModelChain) -> String {
+    std::iter::once(&chain.primary)
+        .chain(&chain.fallbacks)
+        .filter_map(|key| Some((model_catalog::known_output_price(key)?, key)))
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, key)| key.clone())
+        .unwrap_or_else(|| DEFAULT_AGENT_MODEL.to_string())
+}
+
+fn failed(message: String) -> WarmUpResult {
+    WarmUpResult {
+        ok: false,
+        latency_ms: None,
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::warm_up_model;
+    use crate::models::ModelChain;
+
+    fn chain(primary: &str, fallbacks: &[&str]) -> ModelChain {
+        ModelChain {
+            primary: primary.to_string(),
+            fallbacks: fallbacks.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn warm_up_picks_the_cheapest_priced_model() {
+        let mixed = chain(
+            "anthropic/claude-opus-4-6",
+            &[
+                "google/gemini-2.0-flash",
+                "openai/o4-mini",
+                "moonshot/kimi-k2.5",
+            ],
+        );
+        assert_eq!(warm_up_model(&mixed), "google/gemini-2.0-flash");
+
+        let trial = chain(
+            "openrouter/deepseek/deepseek-chat-v3-0324:free",
+            &["google/gemini-2.0-flash"],
+        );
+        assert_eq!(
+            warm_up_model(&trial),
+            "openrouter/deepseek/deepseek-chat-v3-0324:free"
+        );
+    }
+
+    #[test]
+    fn warm_up_uses_the_default_agent_without_prices() {
+        let unpriced = chain("moonshot/kimi-k2.5", &["custom/local-llama"]);
+        assert_eq!(warm_up_model(&unpriced), "openclaw");
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::Local;
+
+use crate::models::WatchdogState;
+
+use super::{logger, process, state_store};
+
+const BACKOFF_BASE_MS: i64 = 20_000;
+const BACKOFF_MAX_MS: i64 = 10 * 60 * 1000;
+
+// Whether the previous status probe saw the gateway up, to spot the moment it went down.
+static WAS_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Records a launch; the next crash measures its uptime from here. A gateway that dies
+/// at boot is then caught by the next `observe` like any other crash.
+pub fn note_started() {
+    WAS_RUNNING.store(true, Ordering::SeqCst);
+    update(|state, _, _| state.last_started_ms = now_ms());
+}
+
+/// Drops the last probe so the previous profile's gateway going away after a profile
+/// switch is not counted as a crash of the new one.
+pub fn forget_last_probe() {
+    WAS_RUNNING.store(false, Ordering::SeqCst);
+}
+
+/// Feeds one status probe. A gateway that was up and is now down while the user wants
+/// it running is counted as a crash; one that stayed up past the rapid-crash window
+/// clears the count.
+pub fn observe(running: bool, keep_running: bool) {
+    if running {
+        WAS_RUNNING.store(true, Ordering::SeqCst);
+        update(|state, rapid_ms, _| {
+            if state.consecutive_crashes > 0 && now_ms() - state.last_started_ms >= rapid_ms {
+                logger::info("Watchdog: gateway stable again, crash count reset.");
+                state.consecutive_crashes = 0;
+                state.next_attempt_ms = 0;
+            }
+        });
+        return;
+    }
+    if WAS_RUNNING.swap(false, Ordering::SeqCst) && keep_running {
+        note_crash();
+    }
+}
+
+fn note_crash() {
+    let tail = process::gateway_stderr_tail();
+    update(|state, rapid_ms, threshold| {
+        record_crash(state, now_ms(), rapid_ms, threshold);
+        state.last_crash_at = Some(Local::now().to_rfc3339());
+        state.last_stderr_tail = tail;
+        if state.crash_loop {
+            logger::error(&format!(
+                "Watchdog: crash loop detected after {} rapid crashes; autostart paused. {}",
+                state.consecutive_crashes,
+                state
+                    .last_stderr_tail
+                    .as_deref()
+                    .unwrap_or("no stderr output")
+            ));
+        } else {
+            logger::warn(&format!(
+                "Watchdog: gateway crashed (#{} in a row); next autostart in {} s.",
+                state.consecutive_crashes,
+                (state.next_attempt_ms - now_ms()).max(0) / 1000
+            ));
+        }
+    });
+}
+
+/// True when autostart may relaunch now: no crash loop and the backoff has passed.
+pub fn may_autostart() -> bool {
+    let state = current();
+    !state.crash_loop && now_ms() >= state.next_attempt_ms
+}
+
+pub fn current() -> WatchdogState {
+    state_store::load_run_prefs()
+        .map(|prefs| prefs.watchdog)
+        .unwrap_or_default()
+}
+
+/// Forgets the crash history. Called when the user starts or stops the gateway, which
+/// is also how a crash loop is acknowledged.
+pub fn reset() {
+    WAS_RUNNING.store(false, Ordering::SeqCst);
+    update(|state, _, _| {
+        if state.crash_loop {
+            logger::info("Watchdog: crash loop cleared by user action.");
+        }
+        *state = WatchdogState {
+            last_started_ms: state.last_started_ms,
+            ..WatchdogState::default()
+        };
+    });
+}
+
+fn record_crash(state: &mut WatchdogState, now_ms: i64, rapid_ms: i64, threshold: u32) {
+    let uptime_ms = (now_ms - state.last_started_ms).max(0);
+    state.last_uptime_secs = Some((uptime_ms / 1000) as u64);
+    state.consecutive_crashes = if uptime_ms < rapid_ms {
+        state.consecutive_crashes + 1
+    } else {
+        1
+    };
+    state.next_attempt_ms = now_ms + backoff_ms(state.consecutive_crashes);
+    state.crash_loop = state.consecutive_crashes >= threshold.max(1);
+}
+
+/// 20 s, 40 s, 80 s ... capped at 10 minutes.
+fn backoff_ms(crashes: u32) -> i64 {
+    let doublings = crashes.saturating_sub(1).min(16);
+    (BACKOFF_BASE_MS << doublings).min(BACKOFF_MAX_MS)
+}
+
+fn update(change: impl FnOnce(&mut WatchdogState, i64, u32)) {
+    let Ok(mut prefs) = state_store::load_run_prefs() else {
+        return;
+    };
+    let before = prefs.watchdog.clone();
+    let rapid_ms = (prefs.rapid_crash_secs * 1000) as i64;
+    change(&mut prefs.watchdog, rapid_ms, prefs.crash_loop_threshold);
+    // Status polls call this every few seconds; only write when something moved.
+    if prefs.watchdog == before {
+        return;
+    }
+    if let Err(err) = state_store::save_run_prefs(&prefs) {
+        logger::warn(&format!("Watchdog: failed to save state: {err}"));
+    }
+}
+
+fn now_ms() -> i64 {
+    Local::now().timestamp_millis()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backoff_ms, record_crash};
+    use crate::models::WatchdogState;
+
+    #[test]
+    fn backoff_doubles_and_is_capped() {
+        assert_eq!(backoff_ms(1), 20_000);
+        assert_eq!(backoff_ms(2), 40_000);
+        assert_eq!(backoff_ms(4), 160_000);
+        assert_eq!(backoff_ms(30), 600_000);
+    }
+
+    #[test]
+    fn rapid_crashes_accumulate_into_a_crash_loop() {
+        let mut state = WatchdogState::default();
+        for n in 1..=3 {
+            state.last_started_ms = 1_000 * n;
+            record_crash(&mut state, 1_000 * n + 2_000, 60_000, 3);
+        }
+        assert_eq!(state.consecutive_crashes, 3);
+        assert!(state.crash_loop);
+        assert_eq!(state.last_uptime_secs, Some(2));
+
+        // A crash after a long healthy run starts counting again.
+        let mut state = WatchdogState {
+            consecutive_crashes: 2,
+            last_started_ms: 0,
+            ..WatchdogState::default()
+        };
+        record_crash(&mut state, 3_600_000, 60_000, 3);
+        assert_eq!(state.consecutive_crashes, 1);
+        assert!(!state.crash_loop);
+        assert_eq!(state.next_attempt_ms, 3_620_000);
+    }
+}
@@ -0,0 +1,275 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+
+use anyhow::{anyhow, Result};
+use chrono::Local;
+
+use crate::models::{WorkspaceVersion, WorkspaceVersioningConfig};
+
+use super::{logger, paths, profiles, shell, state_store};
+
+const DEFAULT_VERSION_LIMIT: usize = 50;
+// Commits are authored locally; never depend on the user's global git identity.
+const GIT_IDENTITY_ARGS: &[&str] = &[
+    "-c",
+    "user.name=OpenClaw Installer",
+    "-c",
+    "user.email=installer@openclaw.local",
+    "-c",
+    "commit.gpgsign=false",
+];
+
+static LAST_AUTO_COMMIT_MS: OnceLock<Mutex<i64>> = OnceLock::new();
+static COMMITTING: AtomicBool = AtomicBool::new(false);
+
+pub fn workspace_dir() -> PathBuf {
+    paths::openclaw_home().join("workspace")
+}
+
+pub fn configure_workspace_versioning(
+    cfg: &WorkspaceVersioningConfig,
+) -> Result<WorkspaceVersioningConfig> {
+    let mut cfg = cfg.clone();
+    cfg.interval_minutes = cfg.interval_minutes.clamp(1, 24 * 60);
+    if cfg.enabled {
+        let workspace = workspace_dir();
+        fs::create_dir_all(&workspace)?;
+        if !workspace.join(".git").exists() {
+            let out = run_git(&["init"])?;
+            shell::ensure_success("git init", &out)?;
+            logger::info(&format!(
+                "Initialized workspace git repository: {}",
+                workspace.to_string_lossy()
+            ));
+        }
+        commit_pending("Initial workspace snapshot")?;
+    }
+    state_store::save_workspace_versioning(&cfg)?;
+    Ok(cfg)
+}
+
+/// Snapshots agent edits at most once per configured interval. Called from status
+/// polling, so git runs on its own thread and the status returns right away.
+pub fn auto_commit_if_due() {
+    let Ok(cfg) = state_store::load_workspace_versioning() else {
+        return;
+    };
+    if !cfg.enabled || !workspace_dir().join(".git").exists() {
+        return;
+    }
+    let now = Local::now().timestamp_millis();
+    let interval_ms = i64::from(cfg.interval_minutes.max(1)) * 60_000;
+    let lock = LAST_AUTO_COMMIT_MS.get_or_init(|| Mutex::new(0));
+    {
+        let mut last = lock.lock().unwrap_or_else(|e| e.into_inner());
+        if now - *last < interval_ms {
+            return;
+        }
+        *last = now;
+    }
+    if COMMITTING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    thread::spawn(|| {
+        let _tick = profiles::worker_tick();
+        let message = format!("Auto snapshot {}", Local::now().format("%Y-%m-%d %H:%M:%S"));
+        if let Err(err) = commit_pending(&message) {
+            logger::warn(&format!("Workspace auto-commit failed: {err}"));
+        }
+        COMMITTING.store(false, Ordering::SeqCst);
+    });
+}
+
+pub fn list_workspace_versions(
+    path: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<WorkspaceVersion>> {
+    ensure_repo()?;
+    let limit = limit
+        .unwrap_or(DEFAULT_VERSION_LIMIT)
+        .clamp(1, 500)
+        .to_string();
+    let mut args = vec![
+        "log",
+        "--format=%x1e%H%x1f%cI%x1f%s",
+        "--name-only",
+        "-n",
+        limit.as_str(),
+    ];
+    let rel = path.map(|p| validate_relative_path(&p)).transpose()?;
+    if let Some(rel) = &rel {
+        args.push("--");
+        args.push(rel.as_str());
+    }
+    let out = run_git(&args)?;
+    if out.code != 0 {
+        // A freshly initialized repo without commits has no history yet.
+        if out.stderr.contains("does not have any commits") {
+            return Ok(vec![]);
+        }
+        shell::ensure_success("git log", &out)?;
+    }
+    Ok(parse_git_log(&out.stdout))
+}
+
+pub fn restore_workspace_file(path: &str, rev: &str) -> Result<String> {
+    ensure_repo()?;
+    let rel = validate_relative_path(path)?;
+    if !is_valid_rev(rev) {
+        return Err(anyhow!("Invalid revision: {rev}"));
+    }
+    // Keep the current content recoverable before overwriting it.
+    commit_pending(&format!("Snapshot before restoring {rel}"))?;
+    let out = run_git(&["checkout", rev, "--", rel.as_str()])?;
+    shell::ensure_success("git checkout", &out)?;
+    let short = &rev[..rev.len().min(8)];
+    commit_pending(&format!("Restore {rel} to {short}"))?;
+    logger::warn(&format!("Workspace file {rel} restored to {short}."));
+    Ok(format!("Restored {rel} to revision {short}."))
+}
+
+fn ensure_repo() -> Result<()> {
+    if !workspace_dir().join(".git").exists() {
+        return Err(anyhow!(
+            "Workspace versioning is not enabled. Enable it first to start tracking changes."
+        ));
+    }
+    Ok(())
+}
+
+fn commit_pending(message: &str) -> Result<bool> {
+    let status = run_git(&["status", "--porcelain=v1", "-z"])?;
+    shell::ensure_success("git status", &status)?;
+    if changed_paths(&status.stdout).is_empty() {
+        return Ok(false);
+    }
+    let add = run_git(&["add", "-A"])?;
+    shell::ensure_success("git add", &add)?;
+    let commit = run_git(&["commit", "-q", "-m", message])?;
+    shell::ensure_success("git commit", &commit)?;
+    Ok(true)
+}
+
+fn run_git(args: &[&str]) -> Result<shell::CmdOutput> {
+    let git = shell::command_exists("git")
+        .ok_or_else(|| anyhow!("git was not found in PATH. Install Git for Windows first."))?;
+    let mut full = GIT_IDENTITY_ARGS
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+    full.extend(args.iter().map(|s| s.to_string()));
+    // Messages matched below (e.g. "does not have any commits") are the untranslated ones.
+    let env = [("LC_ALL".to_string(), "C".to_string())];
+    shell::run_command(git, &full, Some(&workspace_dir()), &env)
+}
+
+/// Paths from `git status --porcelain=v1 -z`: `XY path` records separated by NUL, where
+/// a rename or copy is followed by one more record holding the original path.
+fn changed_paths(raw: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut records = raw.split('\0').filter(|record| !record.is_empty());
+    while let Some(record) = records.next() {
+        let Some((status, path)) = record.split_at_checked(3) else {
+            continue;
+        };
+        out.push(path.to_string());
+        if status.contains(['R', 'C']) {
+            records.next();
+        }
+    }
+    out
+}
+
+fn validate_relative_path(raw: &str) -> Result<String> {
+    let normalized = raw.trim().replace('\\', "/");
+    let path = Path::new(&normalized);
+    if normalized.is_empty()
+        || path.is_absolute()
+        || path
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(anyhow!(
+            "Path must be relative to the workspace and must not leave it: {raw}"
+        ));
+    }
+    Ok(normalized)
+}
+
+fn is_valid_rev(rev: &str) -> bool {
+    (4..=40).contains(&rev.len()) && rev.chars().all(|ch| ch.is_ascii_hexdigit())
+}
+
+fn parse_git_log(raw: &str) -> Vec<WorkspaceVersion> {
+    raw.split('\u{1e}')
+        .filter_map(|record| {
+            let mut lines = record.lines();
+            let header = lines.next()?;
+            let mut fields = header.split('\u{1f}');
+            let rev = fields.next()?.trim().to_string();
+            if rev.is_empty() {
+                return None;
+            }
+            let committed_at = fields.next().unwrap_or_default().to_string();
+            let message = fields.next().unwrap_or_default().to_string();
+            let files = lines
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(|line| line.to_string())
+                .collect();
+            Some(WorkspaceVersion {
+                rev,
+                committed_at,
+                message,
+                files,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{changed_paths, is_valid_rev, parse_git_log, validate_relative_path};
+
+    #[test]
+    fn validate_relative_path_rejects_escapes() {
+        assert_eq!(
+            validate_relative_path("notes\\todo.md").expect("relative path"),
+            "notes/todo.md"
+        );
+        assert!(validate_relative_path("../openclaw.json").is_err());
+        assert!(validate_relative_path("/etc/passwd").is_err());
+        assert!(validate_relative_path("  ").is_err());
+    }
+
+    #[test]
+    fn porcelain_z_records_are_split_on_nul() {
+        let raw = " M MEMORY.md\0?? notes/a b.md\0R  new name.md\0old name.md\0A  日记.md\0";
+        assert_eq!(
+            changed_paths(raw),
+            vec!["MEMORY.md", "notes/a b.md", "new name.md", "日记.md"]
+        );
+        assert!(changed_paths("").is_empty());
+    }
+
+    #[test]
+    fn rev_must_be_hex_commit_id() {
+        assert!(is_valid_rev("a1b2c3d4"));
+        assert!(!is_valid_rev("HEAD~1"));
+        assert!(!is_valid_rev("abc"));
+    }
+
+    #[test]
+    fn parse_git_log_reads_records_and_files() {
+        let raw = "\u{1e}abc123\u{1f}2026-01-02T03:04:05+08:00\u{1f}Auto snapshot\n\nMEMORY.md\nnotes/a.md\n\u{1e}def456\u{1f}2026-01-01T00:00:00+08:00\u{1f}Initial workspace snapshot\n\nMEMORY.md\n";
+        let versions = parse_git_log(raw);
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].rev, "abc123");
+        assert_eq!(versions[0].message, "Auto snapshot");
+        assert_eq!(versions[0].files, vec!["MEMORY.md", "notes/a.md"]);
+        assert_eq!(versions[1].files, vec!["MEMORY.md"]);
+    }
+}
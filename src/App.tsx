@@ -1,8 +1,26 @@
 import { useEffect, useMemo, useState } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { AppLockScreen } from "./components/AppLockScreen";
 import { Layout } from "./components/Layout";
 import { t } from "./lib/i18n";
-import { checkEnv, getStatus, openManagementUrl as openManagementUrlApi } from "./lib/api";
-import type { AppPage, EnvCheckResult, InstallerStatus, Language, OpenClawConfigInput } from "./lib/types";
+import {
+  checkEnv,
+  getAppLockStatus,
+  getConsents,
+  getStatus,
+  openManagementUrl as openManagementUrlApi,
+  setConsent
+} from "./lib/api";
+import type {
+  AppLockStatus,
+  AppPage,
+  ConsentStatus,
+  EnvCheckResult,
+  InstallerStatus,
+  Language,
+  OpenClawConfigInput,
+  StatusChange
+} from "./lib/types";
 import { WelcomePage } from "./pages/WelcomePage";
 import { WizardPage } from "./pages/WizardPage";
 import { ExecutePage } from "./pages/ExecutePage";
@@ -67,6 +85,8 @@ function App() {
   const [envError, setEnvError] = useState<string | null>(null);
   const [payload, setPayload] = useState<OpenClawConfigInput>(defaultConfig);
   const [status, setStatus] = useState<InstallerStatus | null>(null);
+  const [lock, setLock] = useState<AppLockStatus | null>(null);
+  const [consents, setConsents] = useState<ConsentStatus | null>(null);
 
   const managementUrl = useMemo(() => {
     const activePort = status?.port ?? payload.port;
@@ -94,90 +114,121 @@ function App() {
     }
   };
 
+  const recordConsent = async (kind: "risk_statement" | "telemetry", accepted: boolean) => {
+    try {
+      setEnvError(null);
+      // Send the version that was shown so the backend rejects a stale statement.
+      setConsents(await setConsent(kind, accepted, kind === "risk_statement" ? consents?.risk_statement_version : undefined));
+    } catch (e) {
+      setEnvError(e instanceof Error ? e.message : String(e));
+    }
+  };
+
+  useEffect(() => {
+    getConsents()
+      .then(setConsents)
+      .catch(() => undefined);
+  }, []);
+
+  useEffect(() => {
+    getAppLockStatus()
+      .then(setLock)
+      .catch(() => undefined);
+    // Emitted when the window comes back from the tray after the lock timeout.
+    const unlisten = listen<AppLockStatus>("app-lock", (event) => setLock(event.payload));
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
   useEffect(() => {
     let active = true;
-    const refresh = async () => {
-      try {
-        const next = await getStatus();
+    getStatus()
+      .then((next) => {
         if (active) {
           setStatus(next);
         }
-      } catch {
-        // Keep last status when backend is temporarily unavailable.
-      }
-    };
-    refresh();
-    const timer = window.setInterval(refresh, 3000);
+      })
+      // Keep last status when backend is temporarily unavailable.
+      .catch(() => undefined);
+    // The backend probes in the background and pushes every transition.
+    const unlisten = listen<StatusChange>("status://changed", (event) => setStatus(event.payload.status));
     return () => {
       active = false;
-      window.clearInterval(timer);
+      unlisten.then((stop) => stop());
     };
   }, []);
 
   return (
-    <Layout
-      lang={lang}
-      setLang={setLang}
-      currentPage={page}
-      onNavigate={(next) => setPage(next as AppPage)}
-      statusText={statusText}
-      version={status?.version || "-"}
-      model={status?.current_model || "-"}
-    >
-      {page === "welcome" && (
-        <WelcomePage
-          lang={lang}
-          checking={checking}
-          env={env}
-          error={envError}
-          onCheck={runCheck}
-          onNext={() => setPage("wizard")}
-        />
-      )}
+    <>
+      <Layout
+        lang={lang}
+        setLang={setLang}
+        currentPage={page}
+        onNavigate={(next) => setPage(next as AppPage)}
+        statusText={statusText}
+        version={status?.version || "-"}
+        model={status?.current_model || "-"}
+      >
+        {page === "welcome" && (
+          <WelcomePage
+            lang={lang}
+            checking={checking}
+            env={env}
+            error={envError}
+            consents={consents}
+            onCheck={runCheck}
+            onConsent={recordConsent}
+            onNext={() => setPage("wizard")}
+          />
+        )}
 
-      {page === "wizard" && (
-        <WizardPage
-          lang={lang}
-          initial={payload}
-          onBack={() => setPage("welcome")}
-          onSubmit={(next) => {
-            setPayload(next);
-            setPage("execute");
-          }}
-        />
-      )}
+        {page === "wizard" && (
+          <WizardPage
+            lang={lang}
+            initial={payload}
+            onBack={() => setPage("welcome")}
+            onSubmit={(next) => {
+              setPayload(next);
+              setPage("execute");
+            }}
+          />
+        )}
 
-      {page === "execute" && (
-        <ExecutePage
-          lang={lang}
-          payload={payload}
-          onBack={() => setPage("wizard")}
-          onSuccess={(nextStatus) => {
-            setStatus(nextStatus);
-            setPage("success");
-          }}
-        />
-      )}
+        {page === "execute" && (
+          <ExecutePage
+            lang={lang}
+            payload={payload}
+            onBack={() => setPage("wizard")}
+            onSuccess={(nextStatus) => {
+              setStatus(nextStatus);
+              setPage("success");
+            }}
+          />
+        )}
 
-      {page === "success" && (
-        <SuccessPage
-          lang={lang}
-          status={status}
-          managementUrl={managementUrl}
-          onOpenManagementUrl={() => {
-            openManagementUrlApi(managementUrl).catch(() => undefined);
-          }}
-          onFinish={() => setPage("maintenance")}
-        />
-      )}
+        {page === "success" && (
+          <SuccessPage
+            lang={lang}
+            status={status}
+            managementUrl={managementUrl}
+            onOpenManagementUrl={() => {
+              openManagementUrlApi(managementUrl).catch(() => undefined);
+            }}
+            onFinish={() => setPage("maintenance")}
+          />
+        )}
 
-      {page === "maintenance" && (
-        <MaintenancePage
-          lang={lang}
-          onStatusUpdate={(nextStatus) => setStatus(nextStatus)}
-        />
-      )}
-    </Layout>
+        {page === "maintenance" && (
+          <MaintenancePage
+            lang={lang}
+            onStatusUpdate={(nextStatus) => setStatus(nextStatus)}
+          />
+        )}
+      </Layout>
+      {/* Drawn over the pages instead of replacing them, so wizard input survives a lock. */}
+      {lock?.locked && <AppLockScreen lang={lang} status={lock} onUnlocked={setLock} />}
+    </>
   );
 }
 
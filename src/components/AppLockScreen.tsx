@@ -0,0 +1,63 @@
+import { useState } from "react";
+import { t } from "../lib/i18n";
+import { unlockApp } from "../lib/api";
+import type { AppLockStatus, Language } from "../lib/types";
+
+interface AppLockScreenProps {
+  lang: Language;
+  status: AppLockStatus;
+  onUnlocked: (status: AppLockStatus) => void;
+}
+
+// Covers the whole window while the backend reports the installer as locked. The backend
+// refuses guarded commands on its own; this only collects the PIN.
+export function AppLockScreen({ lang, status, onUnlocked }: AppLockScreenProps) {
+  const [pin, setPin] = useState("");
+  const [busy, setBusy] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const submit = async () => {
+    try {
+      setBusy(true);
+      setError(null);
+      const next = await unlockApp(pin);
+      setPin("");
+      onUnlocked(next);
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    } finally {
+      setBusy(false);
+    }
+  };
+
+  return (
+    <div className="lock-screen" role="dialog" aria-modal="true">
+      <div className="card">
+        <h3>{t(lang, "lockTitle")}</h3>
+        <p>{t(lang, "lockDesc")}</p>
+        <input
+          type="password"
+          inputMode="numeric"
+          autoFocus
+          value={pin}
+          placeholder={t(lang, "lockPinPlaceholder")}
+          onChange={(event) => setPin(event.target.value)}
+          onKeyDown={(event) => {
+            if (event.key === "Enter" && pin && !busy) submit();
+          }}
+        />
+        <div className="action-row">
+          <button type="button" disabled={!pin || busy} onClick={submit}>
+            {t(lang, "lockUnlock")}
+          </button>
+        </div>
+        {status.retry_after_secs > 0 && (
+          <div className="alert">
+            {t(lang, "lockRetryAfter")} {status.retry_after_secs}
+          </div>
+        )}
+        {error && <div className="alert error">{error}</div>}
+      </div>
+    </div>
+  );
+}
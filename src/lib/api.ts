@@ -1,9 +1,12 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
+  AppLockStatus,
   BackupInfo,
   BackupResult,
   ConfigureResult,
+  ConsentStatus,
   EnvCheckResult,
+  ExistingConfigPrefill,
   HealthResult,
   InstallEnvResult,
   InstallLockInfo,
@@ -38,11 +41,17 @@ function withTimeout<T>(promise: Promise<T>, timeoutMs: number, timeoutMessage:
 
 export const checkEnv = (port: number) => invoke<EnvCheckResult>("check_env", { port });
 export const installEnv = (port: number) => invoke<InstallEnvResult>("install_env", { port });
-export const releasePort = (port: number) => invoke<string>("release_port", { port });
+export const releasePort = (port: number, expectedPid: number) =>
+  invoke<string>("release_port", { port, expectedPid });
 export const getInstallLockInfo = () => invoke<InstallLockInfo>("get_install_lock_info");
 export const installOpenClaw = (payload: OpenClawConfigInput) => invoke<InstallResult>("install_openclaw", { payload });
 export const uninstallOpenClaw = () => invoke<UninstallResult>("uninstall_openclaw");
 export const configure = (payload: OpenClawConfigInput) => invoke<ConfigureResult>("configure", { payload });
+export const onboardCore = (payload: OpenClawConfigInput) => invoke<ConfigureResult>("onboard_core", { payload });
+export const applyKeys = (payload: OpenClawConfigInput) => invoke<ConfigureResult>("apply_keys", { payload });
+export const applyModels = (payload: OpenClawConfigInput) => invoke<ConfigureResult>("apply_models", { payload });
+export const applyChannels = (payload: OpenClawConfigInput) => invoke<ConfigureResult>("apply_channels", { payload });
+export const applySkills = (payload: OpenClawConfigInput) => invoke<ConfigureResult>("apply_skills", { payload });
 export const getCurrentConfig = () => invoke<OpenClawFileConfig>("get_current_config");
 export const updateProviderApiKey = (provider: string, apiKey: string) =>
   invoke<string>("update_provider_api_key", { provider, apiKey });
@@ -56,6 +65,8 @@ export const backupNow = () => invoke<BackupResult>("backup");
 export const listBackups = () => invoke<BackupInfo[]>("list_backups");
 export const rollback = (backupId: string) => invoke<RollbackResult>("rollback", { backupId });
 export const upgrade = () => invoke<UpgradeResult>("upgrade");
+export const reconfigureFromExisting = (installDir?: string) =>
+  invoke<ExistingConfigPrefill>("reconfigure_from_existing", { installDir });
 export const switchModel = (primary: string, fallbacks: string[]) => invoke<ConfigureResult>("switch_model", { primary, fallbacks });
 export const securityCheck = () => invoke<SecurityResult>("security_check");
 export const listLogs = () => invoke<LogSummary[]>("list_logs");
@@ -71,7 +82,8 @@ export const donateWechatQr = () => invoke<string>("donate_wechat_qr");
 export const listSkillCatalog = () =>
   withTimeout(
     invoke<SkillCatalogItem[]>("list_skill_catalog"),
-    4_000,
+    // The first load (nothing cached on disk) waits up to 8s for the CLI, then ClawHub.
+    15_000,
     "list_skill_catalog timed out"
   );
 export const listModelCatalog = () =>
@@ -82,4 +94,10 @@ export const listModelCatalog = () =>
     35_000,
     "list_model_catalog timed out"
   );
+export const getAppLockStatus = () => invoke<AppLockStatus>("get_app_lock_status");
+export const unlockApp = (pin: string) => invoke<AppLockStatus>("unlock_app", { pin });
+export const lockApp = () => invoke<AppLockStatus>("lock_app");
+export const getConsents = () => invoke<ConsentStatus>("get_consents");
+export const setConsent = (kind: "risk_statement" | "telemetry", accepted: boolean, version?: string) =>
+  invoke<ConsentStatus>("set_consent", { kind, accepted, version });
 export const setupTelegramPair = (pairCode: string) => invoke<string>("setup_telegram_pair", { pairCode });
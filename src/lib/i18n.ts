@@ -10,6 +10,11 @@ const zh: Dict = {
   navExecute: "执行安装",
   navSuccess: "完成",
   navMaintenance: "维护中心",
+  lockTitle: "安装器已锁定",
+  lockDesc: "输入 PIN 以继续查看配置和执行操作。",
+  lockPinPlaceholder: "PIN",
+  lockUnlock: "解锁",
+  lockRetryAfter: "PIN 错误次数过多，请稍后重试（秒）：",
   statusRunning: "运行中",
   statusStopped: "已停止",
   statusUnknown: "未知",
@@ -20,6 +25,12 @@ const zh: Dict = {
   finish: "进入维护主页",
   welcomeTitle: "开始前检查",
   welcomeDesc: "检测系统版本、权限、网络和依赖。",
+  riskStatementTitle: "风险声明",
+  riskStatementText:
+    "OpenClaw 会以你的身份调用模型、执行工具并读写工作区文件。安装过程会代你确认 OpenClaw 的风险提示（--accept-risk）。请只连接你信任的渠道，并妥善保管 API Key。",
+  riskAccept: "我已阅读并接受以上风险声明",
+  riskRequired: "继续安装前需要接受风险声明。",
+  telemetryOptIn: "允许记录匿名诊断信息（仅保存在本机）",
   checkSummary: "环境检查摘要",
   adminYes: "管理员权限：是",
   adminNo: "管理员权限：否",
@@ -207,6 +218,11 @@ const en: Dict = {
   navExecute: "Execute",
   navSuccess: "Success",
   navMaintenance: "Maintenance",
+  lockTitle: "Installer locked",
+  lockDesc: "Enter the PIN to view the configuration and run actions.",
+  lockPinPlaceholder: "PIN",
+  lockUnlock: "Unlock",
+  lockRetryAfter: "Too many wrong PINs. Retry in (seconds):",
   statusRunning: "Running",
   statusStopped: "Stopped",
   statusUnknown: "Unknown",
@@ -217,6 +233,12 @@ const en: Dict = {
   finish: "Open Maintenance",
   welcomeTitle: "Pre-flight checks",
   welcomeDesc: "Validate OS, admin, network, and dependencies.",
+  riskStatementTitle: "Risk statement",
+  riskStatementText:
+    "OpenClaw calls models, runs tools and reads and writes workspace files on your behalf. Setup acknowledges OpenClaw's risk prompt (--accept-risk) for you. Only connect channels you trust and keep your API keys safe.",
+  riskAccept: "I have read and accept the risk statement above",
+  riskRequired: "Accept the risk statement before continuing with setup.",
+  telemetryOptIn: "Record anonymous diagnostics (kept on this computer)",
   checkSummary: "Environment summary",
   adminYes: "Admin: Yes",
   adminNo: "Admin: No",
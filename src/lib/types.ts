@@ -22,6 +22,7 @@ export interface OpenClawConfigInput {
   bind_address: string;
   source_method: SourceMethod;
   source_url?: string;
+  source_checksum?: string;
   launch_args: string;
   onboarding_mode: "local" | "remote";
   onboarding_flow: "quickstart" | "advanced" | "manual";
@@ -43,6 +44,7 @@ export interface OpenClawConfigInput {
   telegram_bot_token: string;
   telegram_pair_code: string;
   auto_open_dashboard: boolean;
+  trial_mode?: boolean;
 }
 
 export interface DependencyStatus {
@@ -68,10 +70,19 @@ export interface EnvCheckResult {
   port_status: PortStatus;
 }
 
+export interface ResultWarning {
+  code: string;
+  severity: "info" | "warning" | "error";
+  message: string;
+  remediation?: string;
+  provider?: string;
+  channel?: string;
+}
+
 export interface InstallEnvResult {
   installed: string[];
   skipped: string[];
-  warnings: string[];
+  warnings: ResultWarning[];
 }
 
 export interface InstallResult {
@@ -90,13 +101,29 @@ export interface InstallLockInfo {
 
 export interface ConfigureResult {
   config_path: string;
-  warnings: string[];
+  warnings: ResultWarning[];
+}
+
+export interface WarmUpResult {
+  ok: boolean;
+  latency_ms?: number | null;
+  message: string;
+}
+
+export type StartOutcome = "started" | "starting" | "failed-at-boot";
+
+export interface BootReport {
+  outcome: StartOutcome;
+  waited_ms: number;
+  stderr_tail?: string | null;
 }
 
 export interface ProcessControlResult {
   running: boolean;
   pid?: number;
   message: string;
+  boot?: BootReport | null;
+  warm_up?: WarmUpResult | null;
 }
 
 export interface HealthResult {
@@ -104,6 +131,10 @@ export interface HealthResult {
   status: number;
   url: string;
   body: string;
+  latency_ms?: number | null;
+  sluggish?: boolean;
+  foreign_service?: boolean;
+  unverified?: boolean;
 }
 
 export interface BackupInfo {
@@ -115,6 +146,7 @@ export interface BackupInfo {
 
 export interface BackupResult {
   backup: BackupInfo;
+  warnings?: string[];
 }
 
 export interface RollbackResult {
@@ -128,12 +160,14 @@ export interface UpgradeResult {
   rolled_back: boolean;
   backup_id: string;
   message: string;
+  warnings: ResultWarning[];
+  downtime_ms?: number | null;
 }
 
 export interface UninstallResult {
   stopped_process: boolean;
   removed_paths: string[];
-  warnings: string[];
+  warnings: ResultWarning[];
 }
 
 export interface SecurityIssue {
@@ -148,6 +182,34 @@ export interface SecurityResult {
   issues: SecurityIssue[];
 }
 
+export interface AppLockStatus {
+  enabled: boolean;
+  locked: boolean;
+  lock_after_minutes: number;
+  retry_after_secs: number;
+}
+
+export interface ConsentRecord {
+  version: string;
+  accepted: boolean;
+  decided_at: string;
+  migrated: boolean;
+}
+
+export interface ConsentStatus {
+  risk_statement_version: string;
+  risk_accepted: boolean;
+  records: {
+    risk_statement?: ConsentRecord | null;
+    telemetry?: ConsentRecord | null;
+  };
+}
+
+export interface StatusChange {
+  reason: string;
+  status: InstallerStatus;
+}
+
 export interface InstallerStatus {
   running: boolean;
   pid?: number;
@@ -171,6 +233,14 @@ export interface SkillCatalogItem {
   eligible: boolean;
   bundled: boolean;
   source: string;
+  required_bins: string[];
+  required_env: string[];
+  missing: string[];
+  footprint_bytes?: number | null;
+  permissions: string[];
+  version?: string | null;
+  stars?: number | null;
+  downloads?: number | null;
 }
 
 export interface ModelCatalogItem {
@@ -193,3 +263,12 @@ export interface OpenClawFileConfig {
   launch_args: string;
   updated_at: string;
 }
+
+export interface ExistingConfigPrefill {
+  config_path: string;
+  openclaw_home: string;
+  install_state_missing: boolean;
+  payload: OpenClawConfigInput;
+  masked_keys: string[];
+  configured_channels: string[];
+}
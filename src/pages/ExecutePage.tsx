@@ -151,10 +151,11 @@ export function ExecutePage({ lang, payload, onBack, onSuccess }: ExecutePagePro
           }
           if (!handled) {
             const processName = (env.port_status.process_name ?? "").toLowerCase();
+            const ownerPid = env.port_status.pid;
             const canAutoRelease = processName.includes("node") || processName.includes("openclaw");
-            if (canAutoRelease) {
+            if (canAutoRelease && ownerPid != null) {
               try {
-                const released = await releasePort(currentPayload.port);
+                const released = await releasePort(currentPayload.port, ownerPid);
                 appendUiLog(`release_port: ${released}`);
                 const recheck = await checkEnv(currentPayload.port);
                 handled = !recheck.port_status.in_use;
@@ -189,7 +190,7 @@ export function ExecutePage({ lang, payload, onBack, onSuccess }: ExecutePagePro
 
       if (index === 1) {
         const deps = await installEnv(payloadRef.current.port);
-        appendUiLog(`install_env: installed=${deps.installed.join(",") || "none"}, warnings=${deps.warnings.join(" | ") || "none"}`);
+        appendUiLog(`install_env: installed=${deps.installed.join(",") || "none"}, warnings=${deps.warnings.map((w) => w.message).join(" | ") || "none"}`);
         setStep(index, { state: "done", message: t(lang, "done") });
       }
 
@@ -203,7 +204,7 @@ export function ExecutePage({ lang, payload, onBack, onSuccess }: ExecutePagePro
         const conf = await configure(payloadRef.current);
         appendUiLog(`configure: ${conf.config_path}`);
         if (conf.warnings.length > 0) {
-          appendUiLog(`configure warnings: ${conf.warnings.join(" | ")}`);
+          appendUiLog(`configure warnings: ${conf.warnings.map((w) => w.message).join(" | ")}`);
         }
         setStep(index, { state: "done", message: t(lang, "done") });
       }
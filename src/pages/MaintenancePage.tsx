@@ -380,7 +380,7 @@ export function MaintenancePage({ lang, onStatusUpdate }: MaintenancePageProps)
       setUninstallProgress(100);
       setUninstallStage(t(lang, "uninstallCompleted"));
       const warningTail =
-        result.warnings.length > 0 ? ` | warnings: ${result.warnings.join(" | ")}` : "";
+        result.warnings.length > 0 ? ` | warnings: ${result.warnings.map((w) => w.message).join(" | ")}` : "";
       setMessage(`uninstall OK: removed=${result.removed_paths.length}${warningTail}`);
       window.setTimeout(() => {
         setUninstallRunning(false);
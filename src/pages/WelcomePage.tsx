@@ -1,4 +1,4 @@
-import type { EnvCheckResult, Language } from "../lib/types";
+import type { ConsentStatus, EnvCheckResult, Language } from "../lib/types";
 import { t } from "../lib/i18n";
 
 interface WelcomePageProps {
@@ -6,11 +6,14 @@ interface WelcomePageProps {
   checking: boolean;
   env: EnvCheckResult | null;
   error: string | null;
+  consents: ConsentStatus | null;
   onCheck: () => void;
+  onConsent: (kind: "risk_statement" | "telemetry", accepted: boolean) => void;
   onNext: () => void;
 }
 
-export function WelcomePage({ lang, checking, env, error, onCheck, onNext }: WelcomePageProps) {
+export function WelcomePage({ lang, checking, env, error, consents, onCheck, onConsent, onNext }: WelcomePageProps) {
+  const riskAccepted = consents?.risk_accepted ?? false;
   return (
     <div className="page">
       <h2>{t(lang, "welcomeTitle")}</h2>
@@ -19,13 +22,37 @@ export function WelcomePage({ lang, checking, env, error, onCheck, onNext }: Wel
         <button type="button" onClick={onCheck} disabled={checking}>
           {checking ? "..." : t(lang, "checkNow")}
         </button>
-        <button type="button" onClick={onNext} disabled={!env} className="secondary">
+        <button type="button" onClick={onNext} disabled={!env || !riskAccepted} className="secondary">
           {t(lang, "next")}
         </button>
       </div>
 
       {error && <div className="alert error">{error}</div>}
 
+      <div className="card">
+        <h3>{t(lang, "riskStatementTitle")}</h3>
+        <p>{t(lang, "riskStatementText")}</p>
+        <label className="check-item">
+          <input
+            type="checkbox"
+            checked={riskAccepted}
+            disabled={!consents}
+            onChange={(e) => onConsent("risk_statement", e.target.checked)}
+          />
+          {t(lang, "riskAccept")}
+        </label>
+        <label className="check-item">
+          <input
+            type="checkbox"
+            checked={consents?.records.telemetry?.accepted ?? false}
+            disabled={!consents}
+            onChange={(e) => onConsent("telemetry", e.target.checked)}
+          />
+          {t(lang, "telemetryOptIn")}
+        </label>
+        {consents && !riskAccepted && <p className="warn">{t(lang, "riskRequired")}</p>}
+      </div>
+
       {env && (
         <div className="card-grid">
           <div className="card">